@@ -78,6 +78,8 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		select_chain.clone(),
 		telemetry.as_ref().map(|x| x.handle()),
 	)?;
+	let grandpa_block_import = grandpa_block_import
+		.with_justification_archive_period(config.grandpa_justification_archive_period);
 
 	let slot_duration = sc_consensus_aura::slot_duration(&*client)?.slot_duration();
 
@@ -168,9 +170,11 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 
 	let role = config.role.clone();
 	let force_authoring = config.force_authoring;
+	let block_proof_size_limit = config.block_proof_size_limit;
 	let backoff_authoring_blocks: Option<()> = None;
 	let name = config.network.node_name.clone();
 	let enable_grandpa = !config.disable_grandpa;
+	let justification_archive_period = config.grandpa_justification_archive_period;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
 	let rpc_extensions_builder = {
@@ -206,13 +210,16 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	)?;
 
 	if role.is_authority() {
-		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+		let mut proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
 			client.clone(),
 			transaction_pool,
 			prometheus_registry.as_ref(),
 			telemetry.as_ref().map(|x| x.handle()),
 		);
+		if let Some(limit) = block_proof_size_limit {
+			proposer_factory.set_proof_size_limit(limit);
+		}
 
 		let can_author_with =
 			sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone());
@@ -267,6 +274,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		// FIXME #1578 make this available through chainspec
 		gossip_duration: Duration::from_millis(333),
 		justification_period: 512,
+		justification_archive_period,
 		name: Some(name),
 		observer_enabled: false,
 		keystore,
@@ -395,6 +403,7 @@ pub fn new_light(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		let config = sc_finality_grandpa::Config {
 			gossip_duration: std::time::Duration::from_millis(333),
 			justification_period: 512,
+			justification_archive_period: None,
 			name: Some(name),
 			observer_enabled: false,
 			keystore: None,
@@ -293,4 +293,8 @@ impl sc_transaction_pool_api::TransactionPool for Transactions {
 	fn ready_transaction(&self, _hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
 		unimplemented!()
 	}
+
+	fn inspect(&self) -> sc_transaction_pool_api::PoolInspection<TxHash<Self>> {
+		unimplemented!()
+	}
 }
@@ -18,7 +18,9 @@
 
 //! Substrate chain configurations.
 
-use sc_chain_spec::ChainSpecExtension;
+use std::sync::Arc;
+use sc_chain_spec::{ChainSpecExtension, GenesisConfigBuilderRuntimeCaller, RuntimeGenesisBuilder};
+use sc_executor::{NativeExecutor, WasmExecutionMethod};
 use sp_core::{Pair, Public, crypto::UncheckedInto, sr25519};
 use serde::{Serialize, Deserialize};
 use node_runtime::{
@@ -63,6 +65,17 @@ pub type ChainSpec = sc_service::GenericChainSpec<
 	GenesisConfig,
 	Extensions,
 >;
+/// Builds the genesis of chain specs given as a runtime code and a patch, see
+/// [`ChainSpec::from_runtime_patch`].
+///
+/// The native runtime is used when the version of the code matches it, the code is executed
+/// otherwise.
+pub fn genesis_builder() -> Arc<dyn RuntimeGenesisBuilder> {
+	Arc::new(GenesisConfigBuilderRuntimeCaller::new(
+		NativeExecutor::<node_executor::Executor>::new(WasmExecutionMethod::Interpreted, None, 1),
+	))
+}
+
 /// Flaming Fir testnet generator
 pub fn flaming_fir_config() -> Result<ChainSpec, String> {
 	ChainSpec::from_json_bytes(&include_bytes!("../res/flaming-fir.json")[..])
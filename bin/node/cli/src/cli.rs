@@ -36,6 +36,9 @@ pub enum Subcommand {
 	/// Key management cli utilities
 	Key(KeySubcommand),
 
+	/// Database maintenance utilities.
+	Db(sc_cli::DbSubcommand),
+
 	/// The custom inspect subcommmand for decoding blocks and extrinsics.
 	#[structopt(
 		name = "inspect",
@@ -79,6 +82,15 @@ pub enum Subcommand {
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
+	/// Export a finalized block with its full state.
+	ExportSnapshot(sc_cli::ExportSnapshotCmd),
+
+	/// Start an empty database from a snapshot.
+	///
+	/// The whole state of the snapshot is loaded in memory before it is written to the database,
+	/// so the import needs a few times the size of the state in memory.
+	ImportSnapshot(sc_cli::ImportSnapshotCmd),
+
 	/// Remove the whole chain.
 	PurgeChain(sc_cli::PurgeChainCmd),
 
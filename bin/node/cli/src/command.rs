@@ -58,7 +58,7 @@ impl SubstrateCli for Cli {
 				"staging" => Box::new(chain_spec::staging_testnet_config()),
 				path => Box::new(chain_spec::ChainSpec::from_json_file(
 					std::path::PathBuf::from(path),
-				)?),
+				)?.with_genesis_builder(chain_spec::genesis_builder())),
 			};
 		Ok(spec)
 	}
@@ -137,6 +137,44 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, import_queue), task_manager))
 			})
 		},
+		Some(Subcommand::ExportSnapshot(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				let aux_keys = |hash: &_| {
+					let mut keys = sc_consensus_babe::aux_schema::snapshot_keys(hash);
+					keys.extend(grandpa::AUX_SNAPSHOT_KEYS.iter().map(|key| key.to_vec()));
+					keys
+				};
+				Ok((cmd.run(client, config.database, aux_keys), task_manager))
+			})
+		},
+		Some(Subcommand::Db(sc_cli::DbSubcommand::Check(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let backend = sc_service::new_db_backend(sc_service::database_settings(&config))?;
+				cmd.run::<Block>(&backend)
+			})
+		},
+		Some(Subcommand::Db(sc_cli::DbSubcommand::Migrate(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run::<Block>(config.database))
+		},
+		Some(Subcommand::ImportSnapshot(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				// The snapshot is imported into an empty database, which must not be initialized
+				// with the genesis block by `new_partial`.
+				let backend = sc_service::new_db_backend(sc_service::database_settings(&config))?;
+				let executor = sc_executor::NativeExecutor::<Executor>::new(
+					config.wasm_method,
+					config.default_heap_pages,
+					config.max_runtime_instances,
+				);
+				cmd.run::<Block, _>(&backend, &executor)
+			})
+		},
 		Some(Subcommand::PurgeChain(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.database))
@@ -100,6 +100,8 @@ pub fn new_partial(
 		select_chain.clone(),
 		telemetry.as_ref().map(|x| x.handle()),
 	)?;
+	let grandpa_block_import = grandpa_block_import
+		.with_justification_archive_period(config.grandpa_justification_archive_period);
 	let justification_import = grandpa_block_import.clone();
 
 	let (block_import, babe_link) = sc_consensus_babe::block_import(
@@ -258,12 +260,26 @@ pub fn new_full_base(
 		);
 	}
 
+	if config.offchain_worker.indexing_enabled {
+		// Canonicalize the MMR nodes stored in the Off-chain DB on finality.
+		task_manager.spawn_handle().spawn(
+			"mmr-gadget",
+			mmr_gadget::MmrGadget::<_, _, _, <Block as BlockT>::Hash>::new(
+				client.clone(),
+				backend.clone(),
+				b"mmr".to_vec(),
+			).run(),
+		);
+	}
+
 	let role = config.role.clone();
 	let force_authoring = config.force_authoring;
+	let block_proof_size_limit = config.block_proof_size_limit;
 	let backoff_authoring_blocks =
 		Some(sc_consensus_slots::BackoffAuthoringOnFinalizedHeadLagging::default());
 	let name = config.network.node_name.clone();
 	let enable_grandpa = !config.disable_grandpa;
+	let justification_archive_period = config.grandpa_justification_archive_period;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
 	let _rpc_handlers = sc_service::spawn_tasks(
@@ -288,13 +304,16 @@ pub fn new_full_base(
 	(with_startup_data)(&block_import, &babe_link);
 
 	if let sc_service::config::Role::Authority { .. } = &role {
-		let proposer = sc_basic_authorship::ProposerFactory::new(
+		let mut proposer = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
 			client.clone(),
 			transaction_pool.clone(),
 			prometheus_registry.as_ref(),
 			telemetry.as_ref().map(|x| x.handle()),
 		);
+		if let Some(limit) = block_proof_size_limit {
+			proposer.set_proof_size_limit(limit);
+		}
 
 		let can_author_with =
 			sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone());
@@ -378,6 +397,7 @@ pub fn new_full_base(
 		// FIXME #1578 make this available through chainspec
 		gossip_duration: std::time::Duration::from_millis(333),
 		justification_period: 512,
+		justification_archive_period,
 		name: Some(name),
 		observer_enabled: false,
 		keystore,
@@ -536,6 +556,7 @@ pub fn new_light_base(
 		let config = grandpa::Config {
 			gossip_duration: std::time::Duration::from_millis(333),
 			justification_period: 512,
+			justification_archive_period: None,
 			name: Some(name),
 			observer_enabled: false,
 			keystore: None,
@@ -798,7 +819,7 @@ mod tests {
 				let check_era = frame_system::CheckEra::from(Era::Immortal);
 				let check_nonce = frame_system::CheckNonce::from(index);
 				let check_weight = frame_system::CheckWeight::new();
-				let payment = pallet_transaction_payment::ChargeTransactionPayment::from(0);
+				let payment = pallet_asset_tx_payment::ChargeAssetTxPayment::from(0, None);
 				let extra = (
 					check_spec_version,
 					check_tx_version,
@@ -23,7 +23,7 @@ use frame_support::{
 use sp_core::{NeverNativeValue, traits::Externalities, storage::well_known_keys};
 use sp_runtime::{
 	ApplyExtrinsicResult,
-	traits::Hash as HashT,
+	traits::{Hash as HashT, BlakeTwo256},
 	transaction_validity::InvalidTransaction,
 };
 use frame_system::{self, EventRecord, Phase, AccountInfo};
@@ -832,6 +832,32 @@ fn full_wasm_block_import_works_with_changes_trie() {
 	assert!(t.ext().storage_changes_root(&GENESIS_HASH).unwrap().is_some());
 }
 
+#[test]
+fn wasm_genesis_builder_matches_native_genesis() {
+	let code = compact_code_unwrap();
+	let config = serde_json::to_vec(&node_testing::genesis::config(false, Some(code))).unwrap();
+	let state_version = node_runtime::VERSION.state_version();
+
+	let mut t = TestExternalities::<BlakeTwo256>::new_with_code_and_state(
+		code,
+		Default::default(),
+		state_version,
+	);
+	let (r, native_used) = executor_call::<NeverNativeValue, fn() -> _>(
+		&mut t,
+		"GenesisBuilder_build_config",
+		&config.encode(),
+		false,
+		None,
+	);
+	assert!(!native_used);
+	let r = sp_genesis_builder::Result::decode(&mut &r.unwrap().into_encoded()[..]).unwrap();
+	assert_eq!(r, Ok(()));
+
+	let mut expected = new_test_ext(code, false);
+	assert_eq!(t.ext().storage_root(state_version), expected.ext().storage_root(state_version));
+}
+
 #[test]
 fn should_import_block_with_test_client() {
 	use node_testing::client::{
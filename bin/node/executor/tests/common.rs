@@ -131,9 +131,10 @@ pub fn executor_call<
 }
 
 pub fn new_test_ext(code: &[u8], support_changes_trie: bool) -> TestExternalities<BlakeTwo256> {
-	let mut ext = TestExternalities::new_with_code(
+	let mut ext = TestExternalities::new_with_code_and_state(
 		code,
 		node_testing::genesis::config(support_changes_trie, Some(code)).build_storage().unwrap(),
+		node_runtime::VERSION.state_version(),
 	);
 	ext.changes_trie_storage().insert(0, GENESIS_HASH.into(), Default::default());
 	ext
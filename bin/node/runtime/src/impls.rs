@@ -17,8 +17,9 @@
 
 //! Some configurable implementations as associated type for the substrate runtime.
 
-use frame_support::traits::{OnUnbalanced, Currency};
-use crate::{Balances, Authorship, NegativeImbalance};
+use frame_support::traits::{OnUnbalanced, Currency, fungibles::{Balanced, CreditOf}};
+use pallet_asset_tx_payment::HandleCredit;
+use crate::{AccountId, Assets, Balances, Authorship, NegativeImbalance};
 
 pub struct Author;
 impl OnUnbalanced<NegativeImbalance> for Author {
@@ -27,6 +28,15 @@ impl OnUnbalanced<NegativeImbalance> for Author {
 	}
 }
 
+/// A `HandleCredit` implementation that hands transaction fees paid in assets to the block author.
+pub struct CreditToBlockAuthor;
+impl HandleCredit<AccountId, Assets> for CreditToBlockAuthor {
+	fn handle_credit(credit: CreditOf<AccountId, Assets>) {
+		// If the author cannot take the credit, dropping it burns it.
+		let _ = <Assets as Balanced<AccountId>>::resolve(&Authorship::author(), credit);
+	}
+}
+
 #[cfg(test)]
 mod multiplier_tests {
 	use sp_runtime::{assert_eq_error_rate, FixedPointNumber, traits::{Convert, One, Zero}};
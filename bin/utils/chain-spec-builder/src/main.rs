@@ -23,6 +23,7 @@ use rand::{Rng, distributions::Alphanumeric, rngs::OsRng};
 use structopt::StructOpt;
 
 use sc_keystore::LocalKeystore;
+use sc_chain_spec::RuntimeGenesisBuilder;
 use node_cli::chain_spec::{self, AccountId};
 use sp_core::{
 	sr25519,
@@ -80,18 +81,40 @@ enum ChainSpecBuilder {
 		#[structopt(long, short)]
 		keystore_path: Option<PathBuf>,
 	},
-}
-
-impl ChainSpecBuilder {
-	/// Returns the path where the chain spec should be saved.
-	fn chain_spec_path(&self) -> &Path {
-		match self {
-			ChainSpecBuilder::New { chain_spec_path, .. } =>
-				chain_spec_path.as_path(),
-			ChainSpecBuilder::Generate { chain_spec_path, .. } =>
-				chain_spec_path.as_path(),
-		}
-	}
+	/// Create a new chain spec of which the genesis is built by the given runtime, from a patch
+	/// applied to its default genesis config.
+	///
+	/// The node doesn't need to be recompiled for such a chain spec, the genesis config only has
+	/// to be understood by the runtime.
+	CreateFromRuntime {
+		/// The name of the chain.
+		#[structopt(long, default_value = "Custom")]
+		name: String,
+		/// The id of the chain.
+		#[structopt(long, default_value = "custom")]
+		id: String,
+		/// Path to the runtime wasm code.
+		#[structopt(long, short)]
+		runtime_wasm_path: PathBuf,
+		/// Path to the JSON patch applied to the default genesis config of the runtime.
+		///
+		/// Objects are merged recursively and `null` removes a field. Without a patch, the default
+		/// genesis config is used.
+		#[structopt(long, short)]
+		patch_path: Option<PathBuf>,
+		/// Build the raw genesis storage instead of keeping the code and the patch.
+		#[structopt(long)]
+		raw: bool,
+		/// The path where the chain spec should be saved.
+		#[structopt(long, short, default_value = "./chain_spec.json")]
+		chain_spec_path: PathBuf,
+	},
+	/// Print the default genesis config of the given runtime, to be used as a base for patches.
+	DefaultConfig {
+		/// Path to the runtime wasm code.
+		#[structopt(long, short)]
+		runtime_wasm_path: PathBuf,
+	},
 }
 
 fn genesis_constructor(
@@ -148,6 +171,51 @@ fn generate_chain_spec(
 	chain_spec.as_json(false).map_err(|err| err)
 }
 
+fn create_chain_spec_from_runtime(
+	name: &str,
+	id: &str,
+	runtime_wasm_path: &Path,
+	patch_path: Option<&Path>,
+	raw: bool,
+) -> Result<String, String> {
+	let code = fs::read(runtime_wasm_path)
+		.map_err(|err| format!("Failed to read the runtime code: {}", err))?;
+	let patch = match patch_path {
+		Some(path) => {
+			let patch = fs::read(path).map_err(|err| format!("Failed to read the patch: {}", err))?;
+			serde_json::from_slice(&patch).map_err(|err| format!("Invalid patch: {}", err))?
+		},
+		None => serde_json::json!({}),
+	};
+
+	let chain_spec = chain_spec::ChainSpec::from_runtime_patch(
+		name,
+		id,
+		sc_chain_spec::ChainType::Live,
+		code,
+		patch,
+		vec![],
+		None,
+		None,
+		None,
+		Default::default(),
+	).with_genesis_builder(chain_spec::genesis_builder());
+
+	// Building the genesis storage ensures the patch is understood by the runtime, the raw
+	// chain spec does it anyway.
+	if !raw {
+		sp_runtime::BuildStorage::build_storage(&chain_spec)?;
+	}
+	chain_spec.as_json(raw)
+}
+
+fn default_genesis_config(runtime_wasm_path: &Path) -> Result<String, String> {
+	let code = fs::read(runtime_wasm_path)
+		.map_err(|err| format!("Failed to read the runtime code: {}", err))?;
+	let config = chain_spec::genesis_builder().default_config(&code)?;
+	serde_json::to_string_pretty(&config).map_err(|err| err.to_string())
+}
+
 fn generate_authority_keys_and_store(
 	seeds: &[String],
 	keystore_path: &Path,
@@ -245,54 +313,81 @@ fn main() -> Result<(), String> {
 	);
 
 	let builder = ChainSpecBuilder::from_args();
-	let chain_spec_path = builder.chain_spec_path().to_path_buf();
-
-	let (authority_seeds, nominator_accounts, endowed_accounts, sudo_account) = match builder {
-		ChainSpecBuilder::Generate { authorities, nominators, endowed, keystore_path, .. } => {
-			let authorities = authorities.max(1);
-			let rand_str = || -> String { OsRng.sample_iter(&Alphanumeric).take(32).collect() };
-
-			let authority_seeds = (0..authorities).map(|_| rand_str()).collect::<Vec<_>>();
-			let nominator_seeds = (0..nominators).map(|_| rand_str()).collect::<Vec<_>>();
-			let endowed_seeds = (0..endowed).map(|_| rand_str()).collect::<Vec<_>>();
-			let sudo_seed = rand_str();
 
-			print_seeds(&authority_seeds, &nominator_seeds, &endowed_seeds, &sudo_seed);
-
-			if let Some(keystore_path) = keystore_path {
-				generate_authority_keys_and_store(
-					&authority_seeds,
-					&keystore_path,
+	let (chain_spec_path, authority_seeds, nominator_accounts, endowed_accounts, sudo_account) =
+		match builder {
+			ChainSpecBuilder::Generate {
+				authorities,
+				nominators,
+				endowed,
+				keystore_path,
+				chain_spec_path,
+			} => {
+				let authorities = authorities.max(1);
+				let rand_str = || -> String { OsRng.sample_iter(&Alphanumeric).take(32).collect() };
+
+				let authority_seeds = (0..authorities).map(|_| rand_str()).collect::<Vec<_>>();
+				let nominator_seeds = (0..nominators).map(|_| rand_str()).collect::<Vec<_>>();
+				let endowed_seeds = (0..endowed).map(|_| rand_str()).collect::<Vec<_>>();
+				let sudo_seed = rand_str();
+
+				print_seeds(&authority_seeds, &nominator_seeds, &endowed_seeds, &sudo_seed);
+
+				if let Some(keystore_path) = keystore_path {
+					generate_authority_keys_and_store(
+						&authority_seeds,
+						&keystore_path,
+					)?;
+				}
+
+				let nominator_accounts = nominator_seeds
+					.into_iter()
+					.map(|seed| {
+						chain_spec::get_account_id_from_seed::<sr25519::Public>(&seed).to_ss58check()
+					})
+					.collect();
+
+				let endowed_accounts = endowed_seeds
+					.into_iter()
+					.map(|seed| {
+						chain_spec::get_account_id_from_seed::<sr25519::Public>(&seed).to_ss58check()
+					})
+					.collect();
+
+				let sudo_account =
+					chain_spec::get_account_id_from_seed::<sr25519::Public>(&sudo_seed).to_ss58check();
+
+				(chain_spec_path, authority_seeds, nominator_accounts, endowed_accounts, sudo_account)
+			}
+			ChainSpecBuilder::New {
+				authority_seeds,
+				nominator_accounts,
+				endowed_accounts,
+				sudo_account,
+				chain_spec_path,
+			} => (chain_spec_path, authority_seeds, nominator_accounts, endowed_accounts, sudo_account),
+			ChainSpecBuilder::CreateFromRuntime {
+				name,
+				id,
+				runtime_wasm_path,
+				patch_path,
+				raw,
+				chain_spec_path,
+			} => {
+				let json = create_chain_spec_from_runtime(
+					&name,
+					&id,
+					&runtime_wasm_path,
+					patch_path.as_deref(),
+					raw,
 				)?;
+				return fs::write(chain_spec_path, json).map_err(|err| err.to_string())
 			}
-
-			let nominator_accounts = nominator_seeds
-				.into_iter()
-				.map(|seed| {
-					chain_spec::get_account_id_from_seed::<sr25519::Public>(&seed).to_ss58check()
-				})
-				.collect();
-
-			let endowed_accounts = endowed_seeds
-				.into_iter()
-				.map(|seed| {
-					chain_spec::get_account_id_from_seed::<sr25519::Public>(&seed).to_ss58check()
-				})
-				.collect();
-
-			let sudo_account =
-				chain_spec::get_account_id_from_seed::<sr25519::Public>(&sudo_seed).to_ss58check();
-
-			(authority_seeds, nominator_accounts, endowed_accounts, sudo_account)
-		}
-		ChainSpecBuilder::New {
-			authority_seeds,
-			nominator_accounts,
-			endowed_accounts,
-			sudo_account,
-			..
-		} => (authority_seeds, nominator_accounts, endowed_accounts, sudo_account),
-	};
+			ChainSpecBuilder::DefaultConfig { runtime_wasm_path } => {
+				println!("{}", default_genesis_config(&runtime_wasm_path)?);
+				return Ok(())
+			}
+		};
 
 	let json = generate_chain_spec(
 		authority_seeds,
@@ -46,7 +46,10 @@ pub trait ExecutorProvider<Block: BlockT> {
 }
 
 /// Method call executor.
-pub trait CallExecutor<B: BlockT> {
+///
+/// Reading the runtime version of some wasm code is required to know with which state version
+/// a genesis or imported state has to be written.
+pub trait CallExecutor<B: BlockT>: sp_core::traits::ReadRuntimeVersion {
 	/// Externalities error type.
 	type Error: sp_state_machine::Error;
 
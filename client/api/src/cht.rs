@@ -29,7 +29,7 @@ use hash_db;
 use codec::Encode;
 use sp_trie;
 
-use sp_core::{H256, convert_hash};
+use sp_core::{H256, convert_hash, storage::StateVersion};
 use sp_runtime::traits::{Header as HeaderT, AtLeast32Bit, Zero, One};
 use sp_state_machine::{
 	MemoryDB, TrieBackend, Backend as StateBackend, StorageProof, InMemoryBackend,
@@ -116,7 +116,9 @@ pub fn build_proof<Header, Hasher, BlocksI, HashesI>(
 		.into_iter()
 		.map(|(k, v)| (k, Some(v)))
 		.collect::<Vec<_>>();
-	let mut storage = InMemoryBackend::<Hasher>::default().update(vec![(None, transaction)]);
+	// CHT values are block hashes, so they are always inlined and the layout makes no difference.
+	let mut storage = InMemoryBackend::<Hasher>::default()
+		.update(vec![(None, transaction)], StateVersion::V0);
 	let trie_storage = storage.as_trie_backend()
 		.expect("InMemoryState::as_trie_backend always returns Some; qed");
 	prove_read_on_trie_backend(
@@ -197,6 +197,18 @@ impl<H, N> LeafSet<H, N> where
 		}
 	}
 
+	/// Remove a block from the leaf set, returning whether it was a leaf.
+	///
+	/// Unlike [`revert`](Self::revert), this doesn't make its parent a leaf, the block must be
+	/// removed along with its fork.
+	pub fn remove(&mut self, hash: H, number: N) -> bool {
+		let removed = self.remove_leaf(&Reverse(number), &hash);
+		if removed {
+			self.pending_removed.push(hash);
+		}
+		removed
+	}
+
 	/// returns an iterator over all hashes in the leaf set
 	/// ordered by their block number descending.
 	pub fn hashes(&self) -> Vec<H> {
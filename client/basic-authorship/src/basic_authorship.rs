@@ -36,7 +36,10 @@ use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_INFO};
 use sc_block_builder::{BlockBuilderApi, BlockBuilderProvider};
 use sp_api::{ProvideRuntimeApi, ApiExt};
 use futures::{future, future::{Future, FutureExt}, channel::oneshot, select};
-use sp_blockchain::{HeaderBackend, ApplyExtrinsicFailed::Validity, Error::ApplyExtrinsicFailed};
+use sp_blockchain::{
+	HeaderBackend, ApplyExtrinsicFailed::{Validity, ProofSizeLimitExceeded},
+	Error::ApplyExtrinsicFailed,
+};
 use std::marker::PhantomData;
 
 use prometheus_endpoint::Registry as PrometheusRegistry;
@@ -68,6 +71,11 @@ pub struct ProposerFactory<A, B, C, PR> {
 	telemetry: Option<TelemetryHandle>,
 	/// When estimating the block size, should the proof be included?
 	include_proof_in_block_size_estimation: bool,
+	/// The maximum size of the storage proof of a block.
+	///
+	/// A proof is recorded to enforce it even when proof recording is disabled, in which case the
+	/// proof is dropped once the block is built.
+	proof_size_limit: Option<usize>,
 	/// phantom member to pin the `Backend`/`ProofRecording` type.
 	_phantom: PhantomData<(B, PR)>,
 }
@@ -91,6 +99,7 @@ impl<A, B, C> ProposerFactory<A, B, C, DisableProofRecording> {
 			telemetry,
 			client,
 			include_proof_in_block_size_estimation: false,
+			proof_size_limit: None,
 			_phantom: PhantomData,
 		}
 	}
@@ -118,6 +127,7 @@ impl<A, B, C> ProposerFactory<A, B, C, EnableProofRecording> {
 			default_block_size_limit: DEFAULT_BLOCK_SIZE_LIMIT,
 			telemetry,
 			include_proof_in_block_size_estimation: true,
+			proof_size_limit: None,
 			_phantom: PhantomData,
 		}
 	}
@@ -138,6 +148,14 @@ impl<A, B, C, PR> ProposerFactory<A, B, C, PR> {
 	pub fn set_default_block_size_limit(&mut self, limit: usize) {
 		self.default_block_size_limit = limit;
 	}
+
+	/// Set the maximum size in bytes of the storage proof of a block.
+	///
+	/// Transactions that would make the proof exceed this limit are left out of the block, the
+	/// same way as transactions that exhaust the block resources.
+	pub fn set_proof_size_limit(&mut self, limit: usize) {
+		self.proof_size_limit = Some(limit);
+	}
 }
 
 impl<B, Block, C, A, PR> ProposerFactory<A, B, C, PR>
@@ -174,6 +192,7 @@ impl<B, Block, C, A, PR> ProposerFactory<A, B, C, PR>
 			telemetry: self.telemetry.clone(),
 			_phantom: PhantomData,
 			include_proof_in_block_size_estimation: self.include_proof_in_block_size_estimation,
+			proof_size_limit: self.proof_size_limit,
 		};
 
 		proposer
@@ -216,6 +235,7 @@ pub struct Proposer<B, Block: BlockT, C, A: TransactionPool, PR> {
 	metrics: PrometheusMetrics,
 	default_block_size_limit: usize,
 	include_proof_in_block_size_estimation: bool,
+	proof_size_limit: Option<usize>,
 	telemetry: Option<TelemetryHandle>,
 	_phantom: PhantomData<(B, PR)>,
 }
@@ -293,10 +313,11 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 		/// It allows us to increase block utilization.
 		const MAX_SKIPPED_TRANSACTIONS: usize = 8;
 
+		// The proof size limit can only be enforced while recording the proof.
 		let mut block_builder = self.client.new_block_at(
 			&self.parent_id,
 			inherent_digests,
-			PR::ENABLED,
+			PR::ENABLED || self.proof_size_limit.is_some(),
 		)?;
 
 		for inherent in block_builder.create_inherents(inherent_data)? {
@@ -314,6 +335,9 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 			}
 		}
 
+		// The inherents are always included, only the transactions are subject to the limit.
+		block_builder.set_proof_size_limit(self.proof_size_limit);
+
 		// proceed with transactions
 		let block_timer = time::Instant::now();
 		let mut skipped = 0;
@@ -380,8 +404,7 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 					transaction_pushed = true;
 					debug!("[{:?}] Pushed to the block.", pending_tx_hash);
 				}
-				Err(ApplyExtrinsicFailed(Validity(e)))
-						if e.exhausted_resources() => {
+				Err(e) if is_block_full_error(&e) => {
 					if skipped < MAX_SKIPPED_TRANSACTIONS {
 						skipped += 1;
 						debug!(
@@ -422,6 +445,9 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 			|metrics| {
 				metrics.number_of_transactions.set(block.extrinsics().len() as u64);
 				metrics.block_constructed.observe(block_timer.elapsed().as_secs_f64());
+				if let Some(proof) = &proof {
+					metrics.proof_size.set(proof.encoded_size() as u64);
+				}
 			}
 		);
 
@@ -463,6 +489,15 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 	}
 }
 
+/// Returns `true` if the transaction couldn't be pushed because the block is full.
+fn is_block_full_error(error: &sp_blockchain::Error) -> bool {
+	match error {
+		ApplyExtrinsicFailed(Validity(e)) => e.exhausted_resources(),
+		ApplyExtrinsicFailed(ProofSizeLimitExceeded(_)) => true,
+		_ => false,
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -841,4 +876,70 @@ mod tests {
 		// block size and thus, one less transaction should fit into the limit.
 		assert_eq!(block.extrinsics().len(), extrinsics_num - 2);
 	}
+
+	#[test]
+	fn transactions_exceeding_proof_size_limit_are_left_out() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let txpool = BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner.clone(),
+			client.clone(),
+		);
+		let genesis_header = client.header(&BlockId::Number(0u64))
+			.expect("header get error")
+			.expect("there should be header");
+
+		block_on(
+			txpool.submit_at(&BlockId::number(0), SOURCE, vec![extrinsic(0), extrinsic(1)])
+		).unwrap();
+		block_on(txpool.maintain(chain_event(genesis_header.clone())));
+
+		let mut proposer_factory = ProposerFactory::with_proof_recording(
+			spawner.clone(),
+			client.clone(),
+			txpool.clone(),
+			None,
+			None,
+		);
+		let deadline = time::Duration::from_secs(300);
+
+		// The proof recorded while initializing the block already exceeds the limit.
+		proposer_factory.set_proof_size_limit(1);
+		let proposer = block_on(proposer_factory.init(&genesis_header)).unwrap();
+		let block = block_on(
+			proposer.propose(Default::default(), Default::default(), deadline, None)
+		).map(|r| r.block).unwrap();
+
+		assert!(block.extrinsics().is_empty());
+		// The transactions are not invalid, they are kept for the next block.
+		assert_eq!(txpool.ready().count(), 2);
+
+		proposer_factory.set_proof_size_limit(usize::max_value());
+		let proposer = block_on(proposer_factory.init(&genesis_header)).unwrap();
+		let proposal = block_on(
+			proposer.propose(Default::default(), Default::default(), deadline, None)
+		).unwrap();
+
+		assert_eq!(proposal.block.extrinsics().len(), 2);
+		assert!(proposal.proof.encoded_size() > 1);
+
+		// The limit is also enforced by proposers that don't return the proof.
+		let mut proposer_factory = ProposerFactory::new(
+			spawner.clone(),
+			client.clone(),
+			txpool.clone(),
+			None,
+			None,
+		);
+		proposer_factory.set_proof_size_limit(1);
+		let proposer = block_on(proposer_factory.init(&genesis_header)).unwrap();
+		let block = block_on(
+			proposer.propose(Default::default(), Default::default(), deadline, None)
+		).map(|r| r.block).unwrap();
+
+		assert!(block.extrinsics().is_empty());
+	}
 }
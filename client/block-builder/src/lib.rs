@@ -137,6 +137,8 @@ pub struct BlockBuilder<'a, Block: BlockT, A: ProvideRuntimeApi<Block>, B> {
 	backend: &'a B,
 	/// The estimated size of the block header.
 	estimated_header_size: usize,
+	/// The maximum size of the storage proof recorded while pushing extrinsics.
+	proof_size_limit: Option<usize>,
 }
 
 impl<'a, Block, A, B> BlockBuilder<'a, Block, A, B>
@@ -188,25 +190,52 @@ where
 			block_id,
 			backend,
 			estimated_header_size,
+			proof_size_limit: None,
 		})
 	}
 
+	/// Set the maximum size in bytes of the storage proof recorded while pushing extrinsics.
+	///
+	/// Extrinsics that would make the proof exceed the limit are rejected with
+	/// [`ApplyExtrinsicFailed::ProofSizeLimitExceeded`]. Has no effect if no proof is recorded.
+	pub fn set_proof_size_limit(&mut self, limit: Option<usize>) {
+		self.proof_size_limit = limit;
+	}
+
 	/// Push onto the block's list of extrinsics.
 	///
 	/// This will ensure the extrinsic can be validly executed (by executing it).
+	///
+	/// When a proof is recorded, the trie nodes accessed by an extrinsic that is rejected are
+	/// removed from the proof.
 	pub fn push(&mut self, xt: <Block as BlockT>::Extrinsic) -> Result<(), Error> {
 		let block_id = &self.block_id;
 		let extrinsics = &mut self.extrinsics;
+		let proof_recorder = self.api.proof_recorder();
+		let proof_size_limit = self.proof_size_limit;
 
-		self.api.execute_in_transaction(|api| {
+		if let Some(recorder) = &proof_recorder {
+			recorder.start_transaction();
+		}
+		let proof_size_before = proof_recorder.as_ref().map(|r| r.estimate_encoded_size());
+
+		let result = self.api.execute_in_transaction(|api| {
 			match api.apply_extrinsic_with_context(
 				block_id,
 				ExecutionContext::BlockConstruction,
 				xt.clone(),
 			) {
 				Ok(Ok(_)) => {
-					extrinsics.push(xt);
-					TransactionOutcome::Commit(Ok(()))
+					let proof_size = proof_recorder.as_ref().map(|r| r.estimate_encoded_size());
+					match (proof_size, proof_size_limit) {
+						(Some(size), Some(limit)) if size > limit => TransactionOutcome::Rollback(
+							Err(ApplyExtrinsicFailed::ProofSizeLimitExceeded(limit).into()),
+						),
+						_ => {
+							extrinsics.push(xt);
+							TransactionOutcome::Commit(Ok(()))
+						},
+					}
 				}
 				Ok(Err(tx_validity)) => {
 					TransactionOutcome::Rollback(
@@ -215,7 +244,29 @@ where
 				},
 				Err(e) => TransactionOutcome::Rollback(Err(Error::from(e))),
 			}
-		})
+		});
+
+		if let Some(recorder) = &proof_recorder {
+			if result.is_ok() {
+				recorder.commit_transaction();
+				log::trace!(
+					target: "block_builder",
+					"Extrinsic added {} bytes to the storage proof",
+					recorder.estimate_encoded_size() - proof_size_before.unwrap_or_default(),
+				);
+			} else {
+				recorder.rollback_transaction();
+			}
+		}
+
+		result
+	}
+
+	/// Returns the estimated size in bytes of the storage proof recorded so far.
+	///
+	/// Returns `None` if no proof is recorded.
+	pub fn proof_size(&self) -> Option<usize> {
+		self.api.proof_recorder().map(|recorder| recorder.estimate_encoded_size())
 	}
 
 	/// Consume the builder to build a valid `Block` containing all pushed extrinsics.
@@ -297,7 +348,9 @@ mod tests {
 	use sp_blockchain::HeaderBackend;
 	use sp_core::Blake2Hasher;
 	use sp_state_machine::Backend;
-	use substrate_test_runtime_client::{DefaultTestClientBuilderExt, TestClientBuilderExt};
+	use substrate_test_runtime_client::{
+		AccountKeyring, DefaultTestClientBuilderExt, TestClientBuilderExt,
+	};
 
 	#[test]
 	fn block_building_storage_proof_does_not_include_runtime_by_default() {
@@ -327,4 +380,42 @@ mod tests {
 				.contains("Database missing expected key"),
 		);
 	}
+
+	#[test]
+	fn extrinsics_exceeding_proof_size_limit_are_rejected() {
+		let builder = substrate_test_runtime_client::TestClientBuilder::new();
+		let backend = builder.backend();
+		let client = builder.build();
+		let transfer = |nonce| substrate_test_runtime_client::runtime::Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Bob.into(),
+			amount: 1,
+			nonce,
+		}.into_signed_tx();
+
+		let mut block_builder = BlockBuilder::new(
+			&client,
+			client.info().best_hash,
+			client.info().best_number,
+			RecordProof::Yes,
+			Default::default(),
+			&*backend,
+		).unwrap();
+
+		let initial_proof_size = block_builder.proof_size().unwrap();
+		block_builder.set_proof_size_limit(Some(initial_proof_size));
+		assert!(matches!(
+			block_builder.push(transfer(0)),
+			Err(Error::ApplyExtrinsicFailed(ApplyExtrinsicFailed::ProofSizeLimitExceeded(_))),
+		));
+		// The nodes read by the rejected extrinsic are not part of the proof.
+		assert_eq!(block_builder.proof_size(), Some(initial_proof_size));
+
+		block_builder.set_proof_size_limit(None);
+		block_builder.push(transfer(0)).unwrap();
+		assert!(block_builder.proof_size().unwrap() > initial_proof_size);
+
+		let block = block_builder.build().unwrap();
+		assert_eq!(block.block.extrinsics().len(), 1);
+	}
 }
@@ -24,7 +24,10 @@ use serde::{Serialize, Deserialize};
 use sp_core::{storage::{StorageKey, StorageData, ChildInfo, Storage, StorageChild}, Bytes};
 use sp_runtime::BuildStorage;
 use serde_json as json;
-use crate::{RuntimeGenesis, ChainType, extension::GetExtension, Properties};
+use crate::{
+	RuntimeGenesis, ChainType, extension::GetExtension, Properties,
+	genesis_config_builder::RuntimeGenesisBuilder,
+};
 use sc_network::config::MultiaddrWithPeerId;
 use sc_telemetry::TelemetryEndpoints;
 use sp_runtime::traits::{Block as BlockT, NumberFor};
@@ -34,6 +37,7 @@ enum GenesisSource<G> {
 	Binary(Cow<'static, [u8]>),
 	Factory(Arc<dyn Fn() -> G + Send + Sync>),
 	Storage(Storage),
+	Patch(Arc<RuntimeGenesisPatch>),
 }
 
 impl<G> Clone for GenesisSource<G> {
@@ -43,6 +47,7 @@ impl<G> Clone for GenesisSource<G> {
 			Self::Binary(ref d) => Self::Binary(d.clone()),
 			Self::Factory(ref f) => Self::Factory(f.clone()),
 			Self::Storage(ref s) => Self::Storage(s.clone()),
+			Self::Patch(ref p) => Self::Patch(p.clone()),
 		}
 	}
 }
@@ -89,6 +94,7 @@ impl<G: RuntimeGenesis> GenesisSource<G> {
 
 				Ok(Genesis::Raw(RawGenesis { top, children_default }))
 			},
+			Self::Patch(patch) => Ok(Genesis::RuntimeGenesis(RuntimeGenesisPatch::clone(patch))),
 		}
 	}
 }
@@ -97,6 +103,7 @@ impl<G: RuntimeGenesis, E> BuildStorage for ChainSpec<G, E> {
 	fn build_storage(&self) -> Result<Storage, String> {
 		match self.genesis.resolve()? {
 			Genesis::Runtime(gc) => gc.build_storage(),
+			Genesis::RuntimeGenesis(patch) => self.build_runtime_genesis(patch),
 			Genesis::Raw(RawGenesis { top: map, children_default: children_map }) => Ok(Storage {
 				top: map.into_iter().map(|(k, v)| (k.0, v.0)).collect(),
 				children_default: children_map.into_iter().map(|(storage_key, child_content)| {
@@ -132,12 +139,42 @@ pub struct RawGenesis {
 	pub children_default: HashMap<StorageKey, GenesisStorage>,
 }
 
+impl From<Storage> for RawGenesis {
+	fn from(storage: Storage) -> Self {
+		let top = storage.top.into_iter()
+			.map(|(k, v)| (StorageKey(k), StorageData(v)))
+			.collect();
+		let children_default = storage.children_default.into_iter()
+			.map(|(sk, child)| (
+				StorageKey(sk),
+				child.data.into_iter()
+					.map(|(k, v)| (StorageKey(k), StorageData(v)))
+					.collect(),
+			))
+			.collect();
+
+		RawGenesis { top, children_default }
+	}
+}
+
+/// Genesis made of a runtime code and a patch to its default `GenesisConfig`.
+///
+/// The genesis storage is built by the runtime itself, see [`RuntimeGenesisBuilder`].
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+struct RuntimeGenesisPatch {
+	code: Bytes,
+	patch: json::Value,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 enum Genesis<G> {
 	Runtime(G),
 	Raw(RawGenesis),
+	RuntimeGenesis(RuntimeGenesisPatch),
 }
 
 /// A configuration of a client. Does not include runtime storage initialization.
@@ -177,6 +214,7 @@ pub type NoExtension = Option<()>;
 pub struct ChainSpec<G, E = NoExtension> {
 	client_spec: ClientSpec<E>,
 	genesis: GenesisSource<G>,
+	genesis_builder: Option<Arc<dyn RuntimeGenesisBuilder>>,
 }
 
 impl<G, E: Clone> Clone for ChainSpec<G, E> {
@@ -184,6 +222,7 @@ impl<G, E: Clone> Clone for ChainSpec<G, E> {
 		ChainSpec {
 			client_spec: self.client_spec.clone(),
 			genesis: self.genesis.clone(),
+			genesis_builder: self.genesis_builder.clone(),
 		}
 	}
 }
@@ -243,38 +282,113 @@ impl<G, E> ChainSpec<G, E> {
 		properties: Option<Properties>,
 		extensions: E,
 	) -> Self {
-		let client_spec = ClientSpec {
-			name: name.to_owned(),
-			id: id.to_owned(),
+		let client_spec = ClientSpec::new(
+			name,
+			id,
 			chain_type,
 			boot_nodes,
 			telemetry_endpoints,
-			protocol_id: protocol_id.map(str::to_owned),
+			protocol_id,
 			properties,
 			extensions,
-			consensus_engine: (),
-			genesis: Default::default(),
-			light_sync_state: None,
-			code_substitutes: HashMap::new(),
-		};
+		);
 
 		ChainSpec {
 			client_spec,
 			genesis: GenesisSource::Factory(Arc::new(constructor)),
+			genesis_builder: None,
+		}
+	}
+
+	/// Create a spec of which the genesis is built by the runtime `code`, from `patch` applied
+	/// to its default `GenesisConfig`.
+	///
+	/// A [`RuntimeGenesisBuilder`] must be provided with [`Self::with_genesis_builder`] to build
+	/// the genesis storage of the spec.
+	pub fn from_runtime_patch(
+		name: &str,
+		id: &str,
+		chain_type: ChainType,
+		code: Vec<u8>,
+		patch: json::Value,
+		boot_nodes: Vec<MultiaddrWithPeerId>,
+		telemetry_endpoints: Option<TelemetryEndpoints>,
+		protocol_id: Option<&str>,
+		properties: Option<Properties>,
+		extensions: E,
+	) -> Self {
+		let client_spec = ClientSpec::new(
+			name,
+			id,
+			chain_type,
+			boot_nodes,
+			telemetry_endpoints,
+			protocol_id,
+			properties,
+			extensions,
+		);
+
+		ChainSpec {
+			client_spec,
+			genesis: GenesisSource::Patch(Arc::new(RuntimeGenesisPatch { code: Bytes(code), patch })),
+			genesis_builder: None,
 		}
 	}
 
+	/// Use `genesis_builder` to build the genesis storage of `runtimeGenesis` specs.
+	pub fn with_genesis_builder(mut self, genesis_builder: Arc<dyn RuntimeGenesisBuilder>) -> Self {
+		self.genesis_builder = Some(genesis_builder);
+		self
+	}
+
 	/// Type of the chain.
 	fn chain_type(&self) -> ChainType {
 		self.client_spec.chain_type.clone()
 	}
 
+	/// Build the genesis storage of a `runtimeGenesis` spec with the genesis builder.
+	fn build_runtime_genesis(&self, genesis: RuntimeGenesisPatch) -> Result<Storage, String> {
+		let genesis_builder = self.genesis_builder.as_ref().ok_or_else(|| {
+			"Building the genesis of a `runtimeGenesis` chain spec requires a runtime genesis \
+			 builder".to_string()
+		})?;
+		genesis_builder.build_storage_from_patch(&genesis.code.0, genesis.patch)
+	}
+
 	/// Hardcode infomation to allow light clients to sync quickly into the chain spec.
 	fn set_light_sync_state(&mut self, light_sync_state: SerializableLightSyncState) {
 		self.client_spec.light_sync_state = Some(light_sync_state);
 	}
 }
 
+impl<E> ClientSpec<E> {
+	fn new(
+		name: &str,
+		id: &str,
+		chain_type: ChainType,
+		boot_nodes: Vec<MultiaddrWithPeerId>,
+		telemetry_endpoints: Option<TelemetryEndpoints>,
+		protocol_id: Option<&str>,
+		properties: Option<Properties>,
+		extensions: E,
+	) -> Self {
+		ClientSpec {
+			name: name.to_owned(),
+			id: id.to_owned(),
+			chain_type,
+			boot_nodes,
+			telemetry_endpoints,
+			protocol_id: protocol_id.map(str::to_owned),
+			properties,
+			extensions,
+			consensus_engine: (),
+			genesis: Default::default(),
+			light_sync_state: None,
+			code_substitutes: HashMap::new(),
+		}
+	}
+}
+
 impl<G, E: serde::de::DeserializeOwned> ChainSpec<G, E> {
 	/// Parse json content into a `ChainSpec`
 	pub fn from_json_bytes(json: impl Into<Cow<'static, [u8]>>) -> Result<Self, String> {
@@ -284,6 +398,7 @@ impl<G, E: serde::de::DeserializeOwned> ChainSpec<G, E> {
 		Ok(ChainSpec {
 			client_spec,
 			genesis: GenesisSource::Binary(json),
+			genesis_builder: None,
 		})
 	}
 
@@ -296,6 +411,7 @@ impl<G, E: serde::de::DeserializeOwned> ChainSpec<G, E> {
 		Ok(ChainSpec {
 			client_spec,
 			genesis: GenesisSource::File(path),
+			genesis_builder: None,
 		})
 	}
 }
@@ -310,22 +426,9 @@ struct JsonContainer<G, E> {
 impl<G: RuntimeGenesis, E: serde::Serialize + Clone + 'static> ChainSpec<G, E> {
 	fn json_container(&self, raw: bool) -> Result<JsonContainer<G, E>, String> {
 		let genesis = match (raw, self.genesis.resolve()?) {
-			(true, Genesis::Runtime(g)) => {
-				let storage = g.build_storage()?;
-				let top = storage.top.into_iter()
-					.map(|(k, v)| (StorageKey(k), StorageData(v)))
-					.collect();
-				let children_default = storage.children_default.into_iter()
-					.map(|(sk, child)| (
-						StorageKey(sk),
-						child.data.into_iter()
-							.map(|(k, v)| (StorageKey(k), StorageData(v)))
-							.collect(),
-					))
-					.collect();
-
-				Genesis::Raw(RawGenesis { top, children_default })
-			},
+			(true, Genesis::Runtime(g)) => Genesis::Raw(g.build_storage()?.into()),
+			(true, Genesis::RuntimeGenesis(patch)) =>
+				Genesis::Raw(self.build_runtime_genesis(patch)?.into()),
 			(_, genesis) => genesis,
 		};
 		Ok(JsonContainer {
@@ -511,4 +614,53 @@ mod tests {
 
 		assert_eq!(spec.extensions().my_property, "Test Extension");
 	}
+
+	/// Builds the genesis storage by writing each field of the config under its name.
+	struct TestGenesisBuilder;
+
+	impl RuntimeGenesisBuilder for TestGenesisBuilder {
+		fn default_config(&self, _: &[u8]) -> Result<json::Value, String> {
+			Ok(json::json!({ "a": "1", "b": "2" }))
+		}
+
+		fn build_storage(&self, code: &[u8], config: json::Value) -> Result<Storage, String> {
+			let mut storage = Storage::default();
+			storage.top.insert(b":code".to_vec(), code.to_vec());
+			for (key, value) in config.as_object().ok_or("Expected an object")? {
+				storage.top.insert(key.clone().into_bytes(), value.to_string().into_bytes());
+			}
+			Ok(storage)
+		}
+	}
+
+	#[test]
+	fn runtime_genesis_is_built_from_patch() {
+		let spec = TestSpec::from_runtime_patch(
+			"Test",
+			"test",
+			ChainType::Local,
+			vec![1, 2, 3],
+			json::json!({ "b": "3", "c": "4" }),
+			Vec::new(),
+			None,
+			None,
+			None,
+			None,
+		);
+		assert!(spec.build_storage().is_err());
+
+		let reloaded = TestSpec::from_json_bytes(spec.as_json(false).unwrap().into_bytes()).unwrap()
+			.with_genesis_builder(Arc::new(TestGenesisBuilder));
+		let storage = reloaded.build_storage().unwrap();
+		let expected = vec![
+			(b":code".to_vec(), vec![1, 2, 3]),
+			(b"a".to_vec(), b"\"1\"".to_vec()),
+			(b"b".to_vec(), b"\"3\"".to_vec()),
+			(b"c".to_vec(), b"\"4\"".to_vec()),
+		];
+		assert_eq!(storage.top.into_iter().collect::<Vec<_>>(), expected);
+
+		let raw: json::Value = json::from_str(&reloaded.as_json(true).unwrap()).unwrap();
+		assert_eq!(raw["genesis"]["raw"]["top"]["0x61"], "0x223122");
+	}
 }
@@ -0,0 +1,163 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Building the genesis storage of a runtime through its `GenesisBuilder` api.
+
+use codec::{Decode, Encode};
+use serde_json::Value;
+use sp_core::{
+	NeverNativeValue, storage::{Storage, well_known_keys},
+	traits::{CodeExecutor, RuntimeCode, WrappedRuntimeCode},
+};
+use sp_state_machine::BasicExternalities;
+
+/// Builds the genesis storage of a runtime given as wasm code.
+///
+/// Required to build the genesis of chain specs that are made of a runtime code and a patch to
+/// its default `GenesisConfig`.
+pub trait RuntimeGenesisBuilder: Send + Sync {
+	/// Returns the default `GenesisConfig` of the runtime `code`.
+	fn default_config(&self, code: &[u8]) -> Result<Value, String>;
+
+	/// Builds the genesis storage of the runtime `code` from the full `GenesisConfig` `config`.
+	fn build_storage(&self, code: &[u8], config: Value) -> Result<Storage, String>;
+
+	/// Builds the genesis storage of the runtime `code` from `patch` applied to its default
+	/// `GenesisConfig`.
+	fn build_storage_from_patch(&self, code: &[u8], patch: Value) -> Result<Storage, String> {
+		let mut config = self.default_config(code)?;
+		json_merge(&mut config, patch);
+		self.build_storage(code, config)
+	}
+}
+
+/// [`RuntimeGenesisBuilder`] calling the `GenesisBuilder` api of the runtime with a
+/// [`CodeExecutor`].
+///
+/// The native runtime is used when the executor has one matching the version of the code,
+/// otherwise the code is executed.
+pub struct GenesisConfigBuilderRuntimeCaller<E> {
+	executor: E,
+}
+
+impl<E: CodeExecutor> GenesisConfigBuilderRuntimeCaller<E> {
+	/// Create a new instance calling the runtime with `executor`.
+	pub fn new(executor: E) -> Self {
+		GenesisConfigBuilderRuntimeCaller { executor }
+	}
+
+	fn call(
+		&self,
+		ext: &mut BasicExternalities,
+		code: &[u8],
+		method: &str,
+		data: &[u8],
+	) -> Result<Vec<u8>, String> {
+		let runtime_code = RuntimeCode {
+			code_fetcher: &WrappedRuntimeCode(code.into()),
+			heap_pages: None,
+			hash: sp_core::blake2_256(code).to_vec(),
+		};
+
+		self.executor
+			.call::<NeverNativeValue, fn() -> _>(ext, &runtime_code, method, data, true, None)
+			.0
+			.map(|result| result.into_encoded())
+			.map_err(|e| format!("Failed to call `{}`: {}", method, e))
+	}
+}
+
+impl<E: CodeExecutor> RuntimeGenesisBuilder for GenesisConfigBuilderRuntimeCaller<E> {
+	fn default_config(&self, code: &[u8]) -> Result<Value, String> {
+		let mut ext = BasicExternalities::new_empty();
+		let result = self.call(&mut ext, code, "GenesisBuilder_create_default_config", &[])?;
+		let json = Vec::<u8>::decode(&mut &result[..])
+			.map_err(|e| format!("Invalid default genesis config: {}", e))?;
+		serde_json::from_slice(&json).map_err(|e| format!("Invalid default genesis config: {}", e))
+	}
+
+	fn build_storage(&self, code: &[u8], config: Value) -> Result<Storage, String> {
+		let mut ext = BasicExternalities::new_empty();
+		let config = serde_json::to_vec(&config)
+			.expect("Serializing a JSON value can not fail; qed");
+		let result = self.call(&mut ext, code, "GenesisBuilder_build_config", &config.encode())?;
+		sp_genesis_builder::Result::decode(&mut &result[..])
+			.map_err(|e| format!("Invalid result of building the genesis config: {}", e))?
+			.map_err(|e| format!("Failed to build the genesis config: {}", String::from(e)))?;
+
+		let mut storage = ext.into_storages();
+		storage.top.insert(well_known_keys::CODE.to_vec(), code.to_vec());
+		Ok(storage)
+	}
+}
+
+/// Recursively merge `patch` into `base`, following RFC 7386.
+///
+/// Objects are merged key by key, a `null` in `patch` removes the key from `base` and any other
+/// value replaces the one of `base`.
+pub fn json_merge(base: &mut Value, patch: Value) {
+	match (base, patch) {
+		(Value::Object(base), Value::Object(patch)) => {
+			for (key, value) in patch {
+				if value.is_null() {
+					base.remove(&key);
+				} else {
+					json_merge(base.entry(key).or_insert(Value::Null), value);
+				}
+			}
+		},
+		(base, patch) => *base = patch,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn json_merge_patches_nested_objects() {
+		let mut base = json!({
+			"balances": { "balances": [["alice", 1]] },
+			"sudo": { "key": "alice" },
+			"system": { "code": "0x00", "changesTrieConfig": null },
+		});
+		json_merge(&mut base, json!({
+			"balances": { "balances": [["bob", 2]] },
+			"sudo": null,
+			"system": { "code": "0x01" },
+			"staking": { "validatorCount": 3 },
+		}));
+
+		assert_eq!(base, json!({
+			"balances": { "balances": [["bob", 2]] },
+			"system": { "code": "0x01", "changesTrieConfig": null },
+			"staking": { "validatorCount": 3 },
+		}));
+	}
+
+	#[test]
+	fn json_merge_replaces_non_objects() {
+		let mut base = json!({ "value": { "nested": 1 } });
+		json_merge(&mut base, json!({ "value": 2 }));
+		assert_eq!(base, json!({ "value": 2 }));
+
+		json_merge(&mut base, json!([1, 2]));
+		assert_eq!(base, json!([1, 2]));
+	}
+}
@@ -106,14 +106,32 @@
 //!
 //! pub type MyChainSpec<G> = GenericChainSpec<G, Extension>;
 //! ```
+//!
+//! Instead of a `GenesisConfig` compiled into the node, the genesis of a chain spec can be given
+//! as a runtime code together with a JSON patch to apply to the default `GenesisConfig` of that
+//! runtime (see [`GenericChainSpec::from_runtime_patch`]). The genesis storage is then built by
+//! the runtime itself, through its `GenesisBuilder` api, by a [`RuntimeGenesisBuilder`]:
+//!
+//! ```json
+//! "genesis": {
+//!   "runtimeGenesis": {
+//!     "code": "0x...",
+//!     "patch": { "balances": { "balances": [["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", 1000]] } }
+//!   }
+//! }
+//! ```
 
 mod chain_spec;
 mod extension;
+mod genesis_config_builder;
 
 pub use chain_spec::{
 	ChainSpec as GenericChainSpec, NoExtension, LightSyncState, SerializableLightSyncState,
 };
 pub use extension::{Group, Fork, Forks, Extension, GetExtension, get_extension};
+pub use genesis_config_builder::{
+	GenesisConfigBuilderRuntimeCaller, RuntimeGenesisBuilder, json_merge,
+};
 pub use sc_chain_spec_derive::{ChainSpecExtension, ChainSpecGroup};
 
 use serde::{Serialize, de::DeserializeOwned};
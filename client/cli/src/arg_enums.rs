@@ -92,6 +92,7 @@ arg_enum! {
 	#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 	pub enum TracingReceiver {
 		Log,
+		Otlp,
 	}
 }
 
@@ -99,6 +100,7 @@ impl Into<sc_tracing::TracingReceiver> for TracingReceiver {
 	fn into(self) -> sc_tracing::TracingReceiver {
 		match self {
 			TracingReceiver::Log => sc_tracing::TracingReceiver::Log,
+			TracingReceiver::Otlp => sc_tracing::TracingReceiver::Otlp(Default::default()),
 		}
 	}
 }
@@ -206,7 +208,9 @@ impl std::str::FromStr for Database {
 	fn from_str(s: &str) -> Result<Self, String> {
 		if s.eq_ignore_ascii_case("rocksdb") {
 			Ok(Self::RocksDb)
-		} else if s.eq_ignore_ascii_case("paritydb-experimental") {
+		} else if s.eq_ignore_ascii_case("paritydb") ||
+			s.eq_ignore_ascii_case("paritydb-experimental")
+		{
 			Ok(Self::ParityDb)
 		} else {
 			Err(format!("Unknown variant `{}`, known variants: {:?}", s, Self::variants()))
@@ -217,7 +221,7 @@ impl std::str::FromStr for Database {
 impl Database {
 	/// Returns all the variants of this enum to be shown in the cli.
 	pub fn variants() -> &'static [&'static str] {
-		&["rocksdb", "paritydb-experimental"]
+		&["rocksdb", "paritydb", "paritydb-experimental"]
 	}
 }
 
@@ -0,0 +1,37 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Database related CLI utilities
+
+use structopt::StructOpt;
+
+use super::{db_check_cmd::DbCheckCmd, db_migrate_cmd::DbMigrateCmd};
+
+/// Database utilities for the cli.
+#[derive(Debug, StructOpt)]
+pub enum DbSubcommand {
+	/// Check the consistency of the database, and optionally repair it.
+	///
+	/// This is an offline command: the node must be stopped, as the database is opened by the
+	/// command itself. ParityDb columns can't be iterated, so with ParityDb the scan of the
+	/// block columns for unknown and non-canonical blocks is skipped.
+	Check(DbCheckCmd),
+
+	/// Copy the database to another database backend.
+	Migrate(DbMigrateCmd),
+}
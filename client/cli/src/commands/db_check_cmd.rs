@@ -0,0 +1,102 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error, params::{DatabaseParams, PruningParams, SharedParams},
+};
+use log::{info, warn};
+use sc_service::{config::DatabaseCheckOptions, TFullBackend};
+use sp_runtime::traits::Block as BlockT;
+use structopt::StructOpt;
+
+/// The `db check` command used to check the consistency of the database.
+///
+/// The node must be stopped while it runs. With ParityDb, the block columns are not scanned.
+#[derive(Debug, StructOpt, Clone)]
+pub struct DbCheckCmd {
+	/// Repair the lookup indexes and remove the blocks that are not canonical below finality,
+	/// as well as the entries of unknown blocks.
+	#[structopt(long)]
+	pub repair: bool,
+
+	/// Don't read the full state of the best and finalized blocks.
+	///
+	/// Reading the state of a large chain can take a long time.
+	#[structopt(long)]
+	pub skip_state: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl DbCheckCmd {
+	/// Run the db check command
+	///
+	/// Fails if inconsistencies remain in the database.
+	pub fn run<B: BlockT>(&self, backend: &TFullBackend<B>) -> error::Result<()> {
+		let report = backend.check_database(DatabaseCheckOptions {
+			state: !self.skip_state,
+			repair: self.repair,
+		})?;
+
+		for inconsistency in &report.inconsistencies {
+			warn!("{}", inconsistency);
+		}
+		if !report.scanned_columns {
+			warn!("The database can't iterate over its columns, only the chain was checked");
+		}
+
+		let remaining = report.inconsistencies.iter()
+			.filter(|inconsistency| !report.repaired || !inconsistency.is_repairable())
+			.count();
+		info!(
+			"Checked {} canonical blocks, found {} inconsistencies, {} remaining",
+			report.canonical_blocks,
+			report.inconsistencies.len(),
+			remaining,
+		);
+		if remaining > 0 {
+			let hint = if report.repaired { "" } else { ", run with `--repair` to fix some" };
+			return Err(format!("The database has {} inconsistencies{}", remaining, hint).into())
+		}
+		Ok(())
+	}
+}
+
+impl CliConfiguration for DbCheckCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
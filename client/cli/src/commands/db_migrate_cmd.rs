@@ -0,0 +1,82 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{arg_enums::Database, error, params::SharedParams, CliConfiguration};
+use log::info;
+use sc_service::DatabaseConfig;
+use sp_runtime::traits::Block as BlockT;
+use structopt::StructOpt;
+
+/// The `db migrate` command used to copy the database to another backend.
+#[derive(Debug, StructOpt, Clone)]
+pub struct DbMigrateCmd {
+	/// Backend of the database to copy.
+	#[structopt(
+		long,
+		value_name = "DB",
+		case_insensitive = true,
+		possible_values = &Database::variants(),
+		default_value = "rocksdb",
+	)]
+	pub from: Database,
+
+	/// Backend of the new database.
+	///
+	/// The new database is created next to the existing one, which is left untouched.
+	#[structopt(
+		long,
+		value_name = "DB",
+		case_insensitive = true,
+		possible_values = &Database::variants(),
+		default_value = "paritydb",
+	)]
+	pub to: Database,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl DbMigrateCmd {
+	/// Run the db migrate command
+	pub fn run<B: BlockT>(&self, database_config: DatabaseConfig) -> error::Result<()> {
+		let config_dir = database_config.path()
+			.and_then(|path| path.parent())
+			.ok_or("Cannot migrate a custom database")?
+			.to_path_buf();
+		let target = self.database_config(&config_dir, 0, self.to)?;
+
+		info!("Migrating the {} database to {}...", database_config, target);
+		sc_service::migrate_database::<B>(&database_config, &target, &mut |column, copied| {
+			info!("Copied {} entries of the {} column", copied, column);
+		})?;
+		if let Some(path) = target.path() {
+			info!("Migration complete, the new database is at {}", path.display());
+		}
+		Ok(())
+	}
+}
+
+impl CliConfiguration for DbMigrateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database(&self) -> error::Result<Option<Database>> {
+		Ok(Some(self.from))
+	}
+}
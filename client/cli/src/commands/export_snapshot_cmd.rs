@@ -0,0 +1,110 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error,
+	params::{BlockNumberOrHash, DatabaseParams, PruningParams, SharedParams},
+};
+use log::info;
+use sc_client_api::{AuxStore, BlockBackend, StorageProvider, UsageProvider};
+use sc_service::{config::DatabaseConfig, chain_ops::export_snapshot};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{fmt::Debug, fs, io, path::PathBuf, str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+/// The `export-snapshot` command used to export a finalized block with its full state.
+#[derive(Debug, StructOpt, Clone)]
+pub struct ExportSnapshotCmd {
+	/// Output file name or stdout if unspecified.
+	#[structopt(parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	/// Block hash or number of the snapshot.
+	///
+	/// Default is the last finalized block.
+	#[structopt(long = "at", value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Number of headers of the blocks before the snapshot block to include.
+	#[structopt(long = "ancestors", value_name = "COUNT", default_value = "8192")]
+	pub ancestors: u32,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportSnapshotCmd {
+	/// Run the export-snapshot command
+	///
+	/// `aux_keys` returns the keys of the auxiliary data that the consensus engines of the node
+	/// need to resume from the block with the given hash.
+	pub async fn run<B, BA, C>(
+		&self,
+		client: Arc<C>,
+		database_config: DatabaseConfig,
+		aux_keys: impl Fn(&B::Hash) -> Vec<Vec<u8>>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		BA: sc_client_api::backend::Backend<B>,
+		C: UsageProvider<B> + StorageProvider<B, BA> + BlockBackend<B> + HeaderBackend<B>
+			+ AuxStore,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		if let DatabaseConfig::RocksDb { ref path, .. } = database_config {
+			info!("DB path: {}", path.display());
+		}
+
+		let block_id = self.at.as_ref().map(|b| b.parse()).transpose()?;
+		let file: Box<dyn io::Write> = match &self.output {
+			Some(filename) => Box::new(fs::File::create(filename)?),
+			None => Box::new(io::stdout()),
+		};
+
+		let output = io::BufWriter::new(file);
+		let (number, hash) = export_snapshot(client, block_id, self.ancestors, aux_keys, output)?;
+		info!("Exported snapshot of block #{} ({})", number, hash);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportSnapshotCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,85 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error, params::{DatabaseParams, PruningParams, SharedParams},
+};
+use log::info;
+use sc_service::{chain_ops::import_snapshot, TFullBackend};
+use sp_core::traits::ReadRuntimeVersion;
+use sp_runtime::traits::Block as BlockT;
+use std::{fs, io, path::PathBuf};
+use structopt::StructOpt;
+
+/// The `import-snapshot` command used to start a database from a snapshot.
+///
+/// The whole state of the snapshot is loaded in memory before it is written to the database,
+/// so the import needs a few times the size of the state in memory.
+#[derive(Debug, StructOpt, Clone)]
+pub struct ImportSnapshotCmd {
+	/// Snapshot file, as written by `export-snapshot`.
+	#[structopt(parse(from_os_str))]
+	pub input: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ImportSnapshotCmd {
+	/// Run the import-snapshot command
+	///
+	/// The database of `backend` must be empty, the state version of the snapshot is read from
+	/// its runtime with `executor`.
+	pub fn run<B, E>(
+		&self,
+		backend: &TFullBackend<B>,
+		executor: &E,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		E: ReadRuntimeVersion,
+	{
+		let file = io::BufReader::new(fs::File::open(&self.input)?);
+		let (number, hash) = import_snapshot(backend, executor, file)?;
+		info!("Imported snapshot of block #{} ({}), syncing will resume from it", number, hash);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ImportSnapshotCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -17,9 +17,14 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 mod build_spec_cmd;
 mod check_block_cmd;
+mod db;
+mod db_check_cmd;
+mod db_migrate_cmd;
 mod export_blocks_cmd;
+mod export_snapshot_cmd;
 mod export_state_cmd;
 mod import_blocks_cmd;
+mod import_snapshot_cmd;
 mod purge_chain_cmd;
 mod sign;
 mod verify;
@@ -37,9 +42,14 @@ pub mod utils;
 pub use self::{
 	build_spec_cmd::BuildSpecCmd,
 	check_block_cmd::CheckBlockCmd,
+	db::DbSubcommand,
+	db_check_cmd::DbCheckCmd,
+	db_migrate_cmd::DbMigrateCmd,
 	export_blocks_cmd::ExportBlocksCmd,
+	export_snapshot_cmd::ExportSnapshotCmd,
 	export_state_cmd::ExportStateCmd,
 	import_blocks_cmd::ImportBlocksCmd,
+	import_snapshot_cmd::ImportSnapshotCmd,
 	purge_chain_cmd::PurgeChainCmd,
 	sign::SignCmd,
 	generate::GenerateCmd,
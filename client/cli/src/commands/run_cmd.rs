@@ -27,11 +27,15 @@ use crate::params::TransactionPoolParams;
 use crate::CliConfiguration;
 use regex::Regex;
 use sc_service::{
-	config::{BasePath, PrometheusConfig, TransactionPoolOptions},
+	config::{
+		BasePath, HealthConfig, InformantJsonOutput, InformantOutputFormat, PrometheusConfig,
+		RpcLimits, TransactionPoolOptions,
+	},
 	ChainSpec, Role,
 };
 use sc_telemetry::TelemetryEndpoints;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::num::NonZeroU32;
 use structopt::StructOpt;
 
 /// The `run` command used to run a node.
@@ -50,6 +54,11 @@ pub struct RunCmd {
 	#[structopt(long)]
 	pub no_grandpa: bool,
 
+	/// Keep the GRANDPA justification of a finalized block every time finality crosses a
+	/// multiple of this many blocks, so that the finality of any block can be proven.
+	#[structopt(long = "grandpa-justification-archive-period", value_name = "BLOCKS")]
+	pub grandpa_justification_archive_period: Option<NonZeroU32>,
+
 	/// Experimental: Run in light client mode.
 	#[structopt(long = "light")]
 	pub light: bool,
@@ -105,6 +114,59 @@ pub struct RunCmd {
 	#[structopt(long = "rpc-max-payload")]
 	pub rpc_max_payload: Option<usize>,
 
+	/// Set the maximum size of RPC requests (both http and ws), in megabytes.
+	///
+	/// Defaults to `--rpc-max-payload`.
+	#[structopt(long = "rpc-max-request-size", value_name = "MEGABYTES")]
+	pub rpc_max_request_size: Option<usize>,
+
+	/// Set the maximum size of RPC responses (http, ws and ipc), in megabytes.
+	///
+	/// Larger responses are replaced with an error. Defaults to `--rpc-max-payload`.
+	#[structopt(long = "rpc-max-response-size", value_name = "MEGABYTES")]
+	pub rpc_max_response_size: Option<usize>,
+
+	/// Only allow calling the given RPC methods over http, ws and ipc.
+	///
+	/// Can be passed multiple times. Other methods are answered with a "Method not allowed"
+	/// error. By default all the methods are allowed.
+	#[structopt(long = "rpc-allow-method", value_name = "METHOD")]
+	pub rpc_allowed_methods: Vec<String>,
+
+	/// Never allow calling the given RPC method over http, ws and ipc.
+	///
+	/// Can be passed multiple times and takes precedence over `--rpc-allow-method`.
+	#[structopt(long = "rpc-deny-method", value_name = "METHOD")]
+	pub rpc_denied_methods: Vec<String>,
+
+	/// Maximum number of RPC calls per minute accepted from a single client.
+	///
+	/// Calls in excess of the limit are answered with a "Rate limit exceeded" error. Each ws
+	/// connection is limited on its own and all the ipc clients share the loopback address. The
+	/// http clients are identified by their address with `--rpc-trust-proxy-headers`, otherwise
+	/// they all share the same limit.
+	#[structopt(long = "rpc-rate-limit", value_name = "CALLS_PER_MINUTE")]
+	pub rpc_rate_limit: Option<NonZeroU32>,
+
+	/// Maximum number of calls per minute of a given RPC method accepted from a single client.
+	///
+	/// Expects the name of the method followed by the number of calls, e.g.
+	/// `state_getKeysPaged=10`. Can be passed multiple times.
+	#[structopt(
+		long = "rpc-method-rate-limit",
+		value_name = "METHOD=CALLS_PER_MINUTE",
+		parse(try_from_str = parse_method_rate_limit)
+	)]
+	pub rpc_method_rate_limits: Vec<(String, NonZeroU32)>,
+
+	/// Identify http RPC clients by the `X-Forwarded-For` or `X-Real-IP` header for the rate limits.
+	///
+	/// Only use this when the node is behind a reverse proxy that sets these headers, otherwise
+	/// clients can choose the address they are limited under. The http server doesn't know the
+	/// address of its clients by itself, so without this flag they all share the same limits.
+	#[structopt(long = "rpc-trust-proxy-headers")]
+	pub rpc_trust_proxy_headers: bool,
+
 	/// Listen to all Prometheus data source interfaces.
 	///
 	/// Default is local.
@@ -150,6 +212,38 @@ pub struct RunCmd {
 	#[structopt(long = "no-prometheus")]
 	pub no_prometheus: bool,
 
+	/// Serve the health endpoint on the given TCP port.
+	///
+	/// `/health` answers with the same data as the `system_health` RPC, and
+	/// `/health/readiness` answers `503` while the node is syncing, lagging behind or
+	/// without peers. The endpoint is disabled by default.
+	#[structopt(long = "health-port", value_name = "PORT")]
+	pub health_port: Option<u16>,
+
+	/// Listen to all health endpoint interfaces.
+	///
+	/// Default is local.
+	#[structopt(long = "health-external")]
+	pub health_external: bool,
+
+	/// Number of blocks the node can be behind the best block seen on the network while still
+	/// being reported as ready by the health endpoint.
+	#[structopt(long = "health-max-blocks-behind", value_name = "COUNT", default_value = "10")]
+	pub health_max_blocks_behind: u32,
+
+	/// Also write the informant status as newline-delimited JSON.
+	///
+	/// Each line carries the sync state, the best and finalized blocks, the number of peers, the
+	/// bandwidth, and the sizes of the import queue and of the transaction pool.
+	///
+	/// Pass `stdout` to write it to the standard output, or the path of a file to append it to.
+	#[structopt(
+		long = "informant-json",
+		value_name = "stdout|PATH",
+		parse(from_str = parse_informant_json),
+	)]
+	pub informant_json: Option<InformantJsonOutput>,
+
 	/// The human-readable name for this node.
 	///
 	/// The node name will be reported to the telemetry server, if enabled.
@@ -228,6 +322,13 @@ pub struct RunCmd {
 	#[structopt(long = "force-authoring")]
 	pub force_authoring: bool,
 
+	/// Maximum size in bytes of the storage proof of authored blocks.
+	///
+	/// Transactions that would make the proof exceed it are left for later blocks. Setting it
+	/// makes the node record the proof of the blocks it authors.
+	#[structopt(long = "block-proof-size-limit", value_name = "BYTES")]
+	pub block_proof_size_limit: Option<usize>,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub keystore_params: KeystoreParams,
@@ -359,6 +460,10 @@ impl CliConfiguration for RunCmd {
 		Ok(self.shared_params.dev || self.force_authoring)
 	}
 
+	fn block_proof_size_limit(&self) -> Result<Option<usize>> {
+		Ok(self.block_proof_size_limit)
+	}
+
 	fn prometheus_config(&self, default_listen_port: u16) -> Result<Option<PrometheusConfig>> {
 		Ok(if self.no_prometheus {
 			None
@@ -378,10 +483,36 @@ impl CliConfiguration for RunCmd {
 		})
 	}
 
+	fn health_config(&self) -> Result<Option<HealthConfig>> {
+		Ok(self.health_port.map(|port| {
+			let interface = if self.health_external {
+				Ipv4Addr::UNSPECIFIED
+			} else {
+				Ipv4Addr::LOCALHOST
+			};
+
+			HealthConfig {
+				address: SocketAddr::new(interface.into(), port),
+				max_blocks_behind: self.health_max_blocks_behind,
+			}
+		}))
+	}
+
+	fn informant_output_format(&self) -> Result<InformantOutputFormat> {
+		Ok(InformantOutputFormat {
+			json: self.informant_json.clone(),
+			..Default::default()
+		})
+	}
+
 	fn disable_grandpa(&self) -> Result<bool> {
 		Ok(self.no_grandpa)
 	}
 
+	fn grandpa_justification_archive_period(&self) -> Result<Option<NonZeroU32>> {
+		Ok(self.grandpa_justification_archive_period)
+	}
+
 	fn rpc_ws_max_connections(&self) -> Result<Option<usize>> {
 		Ok(self.ws_max_connections)
 	}
@@ -445,6 +576,28 @@ impl CliConfiguration for RunCmd {
 		Ok(self.rpc_max_payload)
 	}
 
+	fn rpc_max_request_size(&self) -> Result<Option<usize>> {
+		Ok(self.rpc_max_request_size)
+	}
+
+	fn rpc_max_response_size(&self) -> Result<Option<usize>> {
+		Ok(self.rpc_max_response_size)
+	}
+
+	fn rpc_limits(&self) -> Result<RpcLimits> {
+		Ok(RpcLimits {
+			allowed_methods: if self.rpc_allowed_methods.is_empty() {
+				None
+			} else {
+				Some(self.rpc_allowed_methods.iter().cloned().collect())
+			},
+			denied_methods: self.rpc_denied_methods.iter().cloned().collect(),
+			calls_per_minute: self.rpc_rate_limit,
+			method_calls_per_minute: self.rpc_method_rate_limits.iter().cloned().collect(),
+			trust_proxy_headers: self.rpc_trust_proxy_headers,
+		})
+	}
+
 	fn transaction_pool(&self) -> Result<TransactionPoolOptions> {
 		Ok(self.pool_config.transaction_pool())
 	}
@@ -531,6 +684,17 @@ impl std::fmt::Display for TelemetryParsingError {
 	}
 }
 
+fn parse_method_rate_limit(s: &str) -> std::result::Result<(String, NonZeroU32), String> {
+	let pos = s.rfind('=')
+		.ok_or_else(|| format!("Missing `=` in method rate limit `{}`", s))?;
+	let method = s[..pos].to_string();
+	if method.is_empty() {
+		return Err(format!("Missing method name in method rate limit `{}`", s))
+	}
+	let limit = s[pos + 1..].parse().map_err(|e| format!("{}", e))?;
+	Ok((method, limit))
+}
+
 fn parse_telemetry_endpoints(s: &str) -> std::result::Result<(String, u8), TelemetryParsingError> {
 	let pos = s.find(' ');
 	match pos {
@@ -587,6 +751,15 @@ fn parse_cors(s: &str) -> std::result::Result<Cors, Box<dyn std::error::Error>>
 	})
 }
 
+/// Parse the output of the JSON informant, `stdout` or the path of a file.
+fn parse_informant_json(s: &str) -> InformantJsonOutput {
+	if s == "stdout" {
+		InformantJsonOutput::Stdout
+	} else {
+		InformantJsonOutput::File(s.into())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -607,4 +780,15 @@ mod tests {
 		assert!(is_node_name_valid("www.visit.me").is_err());
 		assert!(is_node_name_valid("email@domain").is_err());
 	}
+
+	#[test]
+	fn method_rate_limit_parsing() {
+		assert_eq!(
+			parse_method_rate_limit("state_getKeysPaged=10").unwrap(),
+			("state_getKeysPaged".to_string(), NonZeroU32::new(10).unwrap()),
+		);
+		assert!(parse_method_rate_limit("state_getKeysPaged").is_err());
+		assert!(parse_method_rate_limit("=10").is_err());
+		assert!(parse_method_rate_limit("state_getKeysPaged=0").is_err());
+	}
 }
@@ -28,13 +28,15 @@ use log::warn;
 use names::{Generator, Name};
 use sc_client_api::execution_extensions::ExecutionStrategies;
 use sc_service::config::{
-	BasePath, Configuration, DatabaseConfig, ExtTransport, KeystoreConfig, NetworkConfiguration,
-	NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcMethods,
-	TaskExecutor, TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
+	BasePath, Configuration, DatabaseConfig, ExtTransport, HealthConfig, InformantOutputFormat,
+	KeystoreConfig, NetworkConfiguration, NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig,
+	PruningMode, Role, RpcLimits, RpcMethods, TaskExecutor, TelemetryEndpoints,
+	TransactionPoolOptions, WasmExecutionMethod,
 };
 use sc_service::{ChainSpec, TracingReceiver, KeepBlocks, TransactionStorageMode};
 use sc_tracing::logging::LoggerBuilder;
 use std::net::SocketAddr;
+use std::num::NonZeroU32;
 use std::path::PathBuf;
 
 /// The maximum number of characters for a node name.
@@ -377,6 +379,27 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(None)
 	}
 
+	/// Get maximum RPC request size in megabytes.
+	///
+	/// By default this is `None`, i.e. the maximum payload is used.
+	fn rpc_max_request_size(&self) -> Result<Option<usize>> {
+		Ok(None)
+	}
+
+	/// Get maximum RPC response size in megabytes.
+	///
+	/// By default this is `None`, i.e. the maximum payload is used.
+	fn rpc_max_response_size(&self) -> Result<Option<usize>> {
+		Ok(None)
+	}
+
+	/// Get the method filters and rate limits of the RPC servers.
+	///
+	/// By default nothing is filtered or limited.
+	fn rpc_limits(&self) -> Result<RpcLimits> {
+		Ok(Default::default())
+	}
+
 	/// Get the prometheus configuration (`None` if disabled)
 	///
 	/// By default this is `None`.
@@ -384,6 +407,20 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(None)
 	}
 
+	/// Get the health endpoint configuration (`None` if disabled)
+	///
+	/// By default this is `None`.
+	fn health_config(&self) -> Result<Option<HealthConfig>> {
+		Ok(None)
+	}
+
+	/// Get the output format of the informant.
+	///
+	/// By default the output is colored and no JSON is written.
+	fn informant_output_format(&self) -> Result<InformantOutputFormat> {
+		Ok(Default::default())
+	}
+
 	/// Get the telemetry endpoints (if any)
 	///
 	/// By default this is retrieved from the chain spec loaded by `load_spec`.
@@ -424,6 +461,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(Default::default())
 	}
 
+	/// Get the maximum size in bytes of the storage proof of authored blocks
+	///
+	/// By default this is `None`, i.e. unlimited.
+	fn block_proof_size_limit(&self) -> Result<Option<usize>> {
+		Ok(None)
+	}
+
 	/// Returns `Ok(true)` if grandpa should be disabled
 	///
 	/// By default this is `false`.
@@ -431,6 +475,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(Default::default())
 	}
 
+	/// Get the period, in blocks, at which GRANDPA justifications are kept (`None` if disabled)
+	///
+	/// By default this is `None`.
+	fn grandpa_justification_archive_period(&self) -> Result<Option<NonZeroU32>> {
+		Ok(None)
+	}
+
 	/// Get the development key seed from the current object
 	///
 	/// By default this is `None`.
@@ -541,13 +592,19 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			rpc_http_threads: self.rpc_http_threads()?,
 			rpc_cors: self.rpc_cors(is_dev)?,
 			rpc_max_payload: self.rpc_max_payload()?,
+			rpc_max_request_size: self.rpc_max_request_size()?,
+			rpc_max_response_size: self.rpc_max_response_size()?,
+			rpc_limits: self.rpc_limits()?,
 			prometheus_config: self.prometheus_config(DCV::prometheus_listen_port())?,
+			health_config: self.health_config()?,
 			telemetry_endpoints,
 			telemetry_external_transport: self.telemetry_external_transport()?,
 			default_heap_pages: self.default_heap_pages()?,
 			offchain_worker: self.offchain_worker(&role)?,
 			force_authoring: self.force_authoring()?,
+			block_proof_size_limit: self.block_proof_size_limit()?,
 			disable_grandpa: self.disable_grandpa()?,
+			grandpa_justification_archive_period: self.grandpa_justification_archive_period()?,
 			dev_key_seed: self.dev_key_seed(is_dev)?,
 			tracing_targets: self.tracing_targets()?,
 			tracing_receiver: self.tracing_receiver()?,
@@ -557,7 +614,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			announce_block: self.announce_block()?,
 			role,
 			base_path: Some(base_path),
-			informant_output_format: Default::default(),
+			informant_output_format: self.informant_output_format()?,
 		})
 	}
 
@@ -19,7 +19,10 @@
 use crate::params::node_key_params::NodeKeyParams;
 use crate::arg_enums::SyncMode;
 use sc_network::{
-	config::{NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, SetConfig, TransportConfig},
+	config::{
+		NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, RateLimit, SetConfig,
+		TransportConfig,
+	},
 	multiaddr::Protocol,
 };
 use sc_service::{ChainSpec, ChainType, config::{Multiaddr, MultiaddrWithPeerId}};
@@ -133,6 +136,18 @@ pub struct NetworkParams {
 	/// FastUnsafe - Same as Fast, but do skips downloading state proofs.
 	#[structopt(long, default_value = "Full")]
 	pub sync: SyncMode,
+
+	/// Limit the inbound bandwidth of a notifications or request-response protocol.
+	///
+	/// Expects the name of the protocol followed by the number of bytes per second and
+	/// optionally the maximum burst size, e.g. `/dot/sync/2=1048576/4194304`. Notifications
+	/// and requests received in excess of the limit are discarded. Can be passed multiple times.
+	#[structopt(
+		long = "protocol-rate-limit",
+		value_name = "PROTOCOL=BYTES_PER_SECOND[/BURST]",
+		parse(try_from_str = parse_protocol_rate_limit)
+	)]
+	pub protocol_rate_limits: Vec<(String, RateLimit)>,
 }
 
 impl NetworkParams {
@@ -227,6 +242,40 @@ impl NetworkParams {
 			yamux_window_size: None,
 			ipfs_server: self.ipfs_server,
 			sync_mode: self.sync.into(),
+			protocol_rate_limits: self.protocol_rate_limits.iter()
+				.map(|(protocol, limit)| (Cow::Owned(protocol.clone()), *limit))
+				.collect(),
 		}
 	}
 }
+
+fn parse_protocol_rate_limit(s: &str) -> Result<(String, RateLimit), String> {
+	let pos = s.rfind('=')
+		.ok_or_else(|| format!("Missing `=` in protocol rate limit `{}`", s))?;
+	let protocol = s[..pos].to_string();
+	if protocol.is_empty() {
+		return Err(format!("Missing protocol name in protocol rate limit `{}`", s))
+	}
+	let limit = s[pos + 1..].parse().map_err(|e| format!("{}", e))?;
+	Ok((protocol, limit))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn protocol_rate_limit_parsing() {
+		assert_eq!(
+			parse_protocol_rate_limit("/dot/sync/2=1024/4096").unwrap(),
+			("/dot/sync/2".to_string(), RateLimit { bytes_per_second: 1024, burst: 4096 }),
+		);
+		assert_eq!(
+			parse_protocol_rate_limit("/paritytech/grandpa/1=2048").unwrap(),
+			("/paritytech/grandpa/1".to_string(), RateLimit::per_second(2048)),
+		);
+		assert!(parse_protocol_rate_limit("/dot/sync/2").is_err());
+		assert!(parse_protocol_rate_limit("=1024").is_err());
+		assert!(parse_protocol_rate_limit("/dot/sync/2=fast").is_err());
+	}
+}
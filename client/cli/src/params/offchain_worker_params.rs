@@ -24,7 +24,7 @@
 //! a reasonable abstraction.
 
 use sc_network::config::Role;
-use sc_service::config::OffchainWorkerConfig;
+use sc_service::config::{OffchainIndexingMode, OffchainWorkerConfig};
 use structopt::StructOpt;
 
 use crate::error;
@@ -54,6 +54,14 @@ pub struct OffchainWorkerParams {
 		value_name = "ENABLE_OFFCHAIN_INDEXING"
 	)]
 	pub indexing_enabled: bool,
+
+	/// Only write the offchain indexing changes of a block once it is finalized.
+	///
+	/// The changes are recorded per block when it is imported, and the changes of blocks
+	/// on abandoned forks are discarded. By default, the changes are written as soon as the
+	/// block is imported.
+	#[structopt(long = "offchain-indexing-fork-aware")]
+	pub indexing_fork_aware: bool,
 }
 
 impl OffchainWorkerParams {
@@ -67,9 +75,15 @@ impl OffchainWorkerParams {
 		};
 
 		let indexing_enabled = self.indexing_enabled;
+		let indexing_mode = if self.indexing_fork_aware {
+			OffchainIndexingMode::ForkAware
+		} else {
+			OffchainIndexingMode::Immediate
+		};
 		Ok(OffchainWorkerConfig {
 			enabled,
 			indexing_enabled,
+			indexing_mode,
 		})
 	}
 }
@@ -71,6 +71,17 @@ pub struct SharedParams {
 		default_value = "Log"
 	)]
 	pub tracing_receiver: TracingReceiver,
+
+	/// Endpoint of the OpenTelemetry collector used by the `otlp` tracing receiver.
+	///
+	/// Spans are sent with the OTLP/HTTP protocol, only plain `http://` endpoints are supported.
+	/// Defaults to `http://localhost:4318/v1/traces`.
+	#[structopt(
+		long = "tracing-otlp-endpoint",
+		value_name = "URL",
+		parse(try_from_str = parse_otlp_endpoint)
+	)]
+	pub tracing_otlp_endpoint: Option<String>,
 }
 
 impl SharedParams {
@@ -115,7 +126,13 @@ impl SharedParams {
 
 	/// Receiver to process tracing messages.
 	pub fn tracing_receiver(&self) -> sc_service::TracingReceiver {
-		self.tracing_receiver.clone().into()
+		let mut receiver = self.tracing_receiver.clone().into();
+		if let (sc_service::TracingReceiver::Otlp(config), Some(endpoint)) =
+			(&mut receiver, &self.tracing_otlp_endpoint)
+		{
+			config.endpoint = endpoint.clone();
+		}
+		receiver
 	}
 
 	/// Comma separated list of targets for tracing.
@@ -123,3 +140,8 @@ impl SharedParams {
 		self.tracing_targets.clone()
 	}
 }
+
+fn parse_otlp_endpoint(s: &str) -> Result<String, String> {
+	sc_tracing::otlp::Endpoint::parse(s)?;
+	Ok(s.to_string())
+}
@@ -16,7 +16,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use sc_service::config::TransactionPoolOptions;
+use sc_service::config::{TransactionPoolJournalOptions, TransactionPoolOptions};
+use std::{path::PathBuf, time::Duration};
 use structopt::StructOpt;
 
 /// Parameters used to create the pool configuration.
@@ -29,6 +30,25 @@ pub struct TransactionPoolParams {
 	/// Maximum number of kilobytes of all transactions stored in the pool.
 	#[structopt(long = "pool-kbytes", value_name = "COUNT", default_value = "20480")]
 	pub pool_kbytes: usize,
+
+	/// Keep a journal of the transactions of the pool, so that they are imported again after a
+	/// restart of the node.
+	#[structopt(long = "pool-journal")]
+	pub pool_journal: bool,
+
+	/// File where the journal of the transaction pool is written.
+	///
+	/// By default, the journal is stored in the database of the node.
+	#[structopt(long = "pool-journal-path", value_name = "PATH", parse(from_os_str), requires = "pool-journal")]
+	pub pool_journal_path: Option<PathBuf>,
+
+	/// Maximum number of kilobytes of transactions kept in the journal.
+	#[structopt(long = "pool-journal-kbytes", value_name = "COUNT", default_value = "10240")]
+	pub pool_journal_kbytes: usize,
+
+	/// Maximum age, in seconds, of the transactions kept in the journal.
+	#[structopt(long = "pool-journal-max-age", value_name = "SECONDS", default_value = "10800")]
+	pub pool_journal_max_age: u64,
 }
 
 impl TransactionPoolParams {
@@ -45,6 +65,14 @@ impl TransactionPoolParams {
 		opts.future.count = self.pool_limit / factor;
 		opts.future.total_bytes = self.pool_kbytes * 1024 / factor;
 
+		if self.pool_journal {
+			opts.journal = Some(TransactionPoolJournalOptions {
+				max_bytes: self.pool_journal_kbytes * 1024,
+				max_age: Duration::from_secs(self.pool_journal_max_age),
+				path: self.pool_journal_path.clone(),
+			});
+		}
+
 		opts
 	}
 }
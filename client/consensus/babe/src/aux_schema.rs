@@ -37,6 +37,15 @@ pub fn block_weight_key<H: Encode>(block_hash: H) -> Vec<u8> {
 	(b"block_weight", block_hash).encode()
 }
 
+/// The aux storage keys needed to resume importing blocks from a snapshot of the given block.
+pub fn snapshot_keys<H: Encode>(block_hash: H) -> Vec<Vec<u8>> {
+	vec![
+		BABE_EPOCH_CHANGES_VERSION.to_vec(),
+		BABE_EPOCH_CHANGES_KEY.to_vec(),
+		block_weight_key(block_hash),
+	]
+}
+
 fn load_decode<B, T>(backend: &B, key: &[u8]) -> ClientResult<Option<T>>
 	where
 		B: AuxStore,
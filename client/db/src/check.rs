@@ -0,0 +1,556 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Consistency checks of the database of a full node.
+//!
+//! The canonical chain is walked back from the best block, checking the number and hash indexes,
+//! headers and bodies of its blocks. The columns are then scanned for entries of blocks that are
+//! unknown or were displaced by finality, the state database journal is compared to the chain and
+//! the state of the best and finalized blocks is read in full.
+
+use std::{collections::{hash_map::Entry, BTreeSet, HashMap, HashSet}, fmt};
+
+use codec::Decode;
+use log::{debug, info};
+use sp_blockchain::Result as ClientResult;
+use sp_core::storage::{well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX, ChildInfo};
+use sp_database::Transaction;
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::{
+	Block as BlockT, Header as HeaderT, NumberFor, SaturatedConversion, HashFor,
+};
+use sp_state_machine::Backend as StateBackend;
+
+use crate::{children, columns, utils, utils::meta_keys, Backend};
+
+/// Options of [`Backend::check_database`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CheckOptions {
+	/// Read the full state of the best and finalized blocks, which can take a long time.
+	pub state: bool,
+	/// Repair the inconsistencies that can be repaired.
+	pub repair: bool,
+}
+
+/// An inconsistency found in the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency<Hash, Number> {
+	/// The number index entry of the block of the canonical chain with the given number and hash
+	/// is missing or points to another block.
+	NumberIndex(Number, Hash),
+	/// The hash index entry of the block of the canonical chain with the given number and hash is
+	/// missing or points to another block.
+	HashIndex(Number, Hash),
+	/// The number index has an entry for the given number, which is above the best block.
+	NumberIndexAboveBest(Number),
+	/// The header of the block of the canonical chain with the given number and hash is missing or
+	/// invalid. The chain can't be checked below it.
+	MissingHeader(Number, Hash),
+	/// The body of the block of the canonical chain with the given number and hash is missing,
+	/// while the bodies of some of its ancestors are stored.
+	MissingBody(Number, Hash),
+	/// The block with the given number and hash is stored, but it is not on the canonical chain
+	/// and not above the last finalized block.
+	NonCanonicalBlock(Number, Hash),
+	/// The body of the block with the given number and hash is stored without its header.
+	OrphanedBody(Number, Hash),
+	/// The justifications of the block with the given number and hash are stored without its
+	/// header.
+	OrphanedJustifications(Number, Hash),
+	/// The hash index has an entry for the given hash, of which the header is not stored.
+	OrphanedHashIndex(Hash),
+	/// The state database journal refers to the block with the given number and hash, which is
+	/// unknown or not on the canonical chain.
+	StateJournal(Number, Hash),
+	/// The state of the block with the given number and hash can't be read in full.
+	MissingState(Number, Hash, String),
+}
+
+impl<Hash, Number> Inconsistency<Hash, Number> {
+	/// Returns `true` if the inconsistency is fixed when repairing the database.
+	pub fn is_repairable(&self) -> bool {
+		match self {
+			Inconsistency::NumberIndex(..) |
+			Inconsistency::HashIndex(..) |
+			Inconsistency::NumberIndexAboveBest(..) |
+			Inconsistency::NonCanonicalBlock(..) |
+			Inconsistency::OrphanedBody(..) |
+			Inconsistency::OrphanedJustifications(..) |
+			Inconsistency::OrphanedHashIndex(..) => true,
+			Inconsistency::MissingHeader(..) |
+			Inconsistency::MissingBody(..) |
+			Inconsistency::StateJournal(..) |
+			Inconsistency::MissingState(..) => false,
+		}
+	}
+}
+
+impl<Hash: fmt::Debug, Number: fmt::Display> fmt::Display for Inconsistency<Hash, Number> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Inconsistency::NumberIndex(number, hash) =>
+				write!(f, "Number index entry of block #{} ({:?}) is wrong", number, hash),
+			Inconsistency::HashIndex(number, hash) =>
+				write!(f, "Hash index entry of block #{} ({:?}) is wrong", number, hash),
+			Inconsistency::NumberIndexAboveBest(number) =>
+				write!(f, "Number index has an entry for #{}, above the best block", number),
+			Inconsistency::MissingHeader(number, hash) =>
+				write!(f, "Header of block #{} ({:?}) is missing or invalid", number, hash),
+			Inconsistency::MissingBody(number, hash) =>
+				write!(f, "Body of canonical block #{} ({:?}) is missing", number, hash),
+			Inconsistency::NonCanonicalBlock(number, hash) =>
+				write!(f, "Block #{} ({:?}) is not canonical and below finality", number, hash),
+			Inconsistency::OrphanedBody(number, hash) =>
+				write!(f, "Body of block #{} ({:?}) is stored without header", number, hash),
+			Inconsistency::OrphanedJustifications(number, hash) =>
+				write!(f, "Justifications of block #{} ({:?}) have no header", number, hash),
+			Inconsistency::OrphanedHashIndex(hash) =>
+				write!(f, "Hash index entry of {:?} points to a missing header", hash),
+			Inconsistency::StateJournal(number, hash) =>
+				write!(f, "State journal refers to unknown block #{} ({:?})", number, hash),
+			Inconsistency::MissingState(number, hash, error) =>
+				write!(f, "State of block #{} ({:?}) can't be read: {}", number, hash, error),
+		}
+	}
+}
+
+/// Outcome of [`Backend::check_database`].
+#[derive(Debug)]
+pub struct CheckReport<Hash, Number> {
+	/// Number of blocks of the canonical chain that were checked.
+	pub canonical_blocks: u64,
+	/// Whether the columns were scanned for entries of unknown and non-canonical blocks. Not all
+	/// databases can iterate over their columns.
+	pub scanned_columns: bool,
+	/// Inconsistencies found.
+	pub inconsistencies: Vec<Inconsistency<Hash, Number>>,
+	/// Whether the repairable inconsistencies were repaired.
+	pub repaired: bool,
+}
+
+impl<Block: BlockT> Backend<Block> {
+	/// Check that the indexes, blocks, state database journal and state stored in the database
+	/// agree with each other, and optionally repair the inconsistencies found.
+	///
+	/// Repairing rewrites the number and hash indexes of the canonical chain and removes the
+	/// blocks that are not canonical at or below the last finalized block, along with their
+	/// leaf set and children index entries, as well as the entries of unknown blocks. Missing
+	/// blocks and state can't be repaired.
+	pub fn check_database(
+		&self,
+		options: CheckOptions,
+	) -> ClientResult<CheckReport<Block::Hash, NumberFor<Block>>> {
+		let db = &*self.storage.db;
+		let (best_hash, best_number, finalized_hash, finalized_number, genesis_hash) = {
+			let meta = self.blockchain.meta.read();
+			(
+				meta.best_hash,
+				meta.best_number.saturated_into::<u64>(),
+				meta.finalized_hash,
+				meta.finalized_number.saturated_into::<u64>(),
+				meta.genesis_hash,
+			)
+		};
+		let mut report = CheckReport {
+			canonical_blocks: 0,
+			scanned_columns: false,
+			inconsistencies: Vec::new(),
+			repaired: false,
+		};
+		if genesis_hash == Default::default() {
+			return Ok(report)
+		}
+		let issues = &mut report.inconsistencies;
+
+		info!("Checking the canonical chain from block #{} ({:?})...", best_number, best_hash);
+		let mut canonical = Vec::new();
+		let mut hash = best_hash;
+		let mut number = best_number;
+		loop {
+			let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
+			let header = db.get(columns::HEADER, &lookup_key)
+				.and_then(|header| Block::Header::decode(&mut &header[..]).ok())
+				.filter(|header| header.hash() == hash);
+			let header = match header {
+				Some(header) => header,
+				None => {
+					issues.push(Inconsistency::MissingHeader(number.saturated_into(), hash));
+					break
+				},
+			};
+			canonical.push(hash);
+
+			let number_index = db.get(columns::KEY_LOOKUP, &utils::number_index_key(number)?);
+			if number_index.as_ref() != Some(&lookup_key) {
+				issues.push(Inconsistency::NumberIndex(number.saturated_into(), hash));
+			}
+			if db.get(columns::KEY_LOOKUP, hash.as_ref()) != Some(lookup_key) {
+				issues.push(Inconsistency::HashIndex(number.saturated_into(), hash));
+			}
+
+			if number == 0 {
+				break
+			}
+			// The chain of a node started from a snapshot ends at the oldest stored ancestor.
+			let parent_key =
+				utils::number_and_hash_to_lookup_key(number - 1, header.parent_hash())?;
+			if !db.contains(columns::HEADER, &parent_key) &&
+				!db.contains(columns::KEY_LOOKUP, &utils::number_index_key(number - 1)?)
+			{
+				break
+			}
+			hash = *header.parent_hash();
+			number -= 1;
+		}
+		canonical.reverse();
+		report.canonical_blocks = canonical.len() as u64;
+
+		// Number of the oldest block in `canonical`.
+		let first = (best_number + 1).saturating_sub(canonical.len() as u64);
+		let canonical_hash = |number: u64| {
+			number.checked_sub(first).and_then(|i| canonical.get(i as usize)).copied()
+		};
+
+		// Bodies are pruned from the oldest blocks, so they must be kept from the oldest block
+		// that has one.
+		let mut with_bodies = false;
+		for (number, hash) in (first..).zip(canonical.iter()) {
+			if db.contains(columns::BODY, &utils::number_and_hash_to_lookup_key(number, hash)?) {
+				with_bodies = true;
+			} else if with_bodies {
+				issues.push(Inconsistency::MissingBody(number.saturated_into(), *hash));
+			}
+		}
+
+		let mut number = best_number + 1;
+		while db.contains(columns::KEY_LOOKUP, &utils::number_index_key(number)?) {
+			issues.push(Inconsistency::NumberIndexAboveBest(number.saturated_into()));
+			number += 1;
+		}
+
+		info!("Scanning the block columns...");
+		let mut headers = BTreeSet::new();
+		report.scanned_columns = db.iter(columns::HEADER, &mut |key, _| {
+			headers.insert(key.to_vec());
+		});
+		if report.scanned_columns {
+			for key in &headers {
+				if let Some((number, hash)) = decode_lookup_key::<Block>(key) {
+					if number <= finalized_number &&
+						number >= first &&
+						canonical_hash(number) != Some(hash)
+					{
+						let number = number.saturated_into();
+						issues.push(Inconsistency::NonCanonicalBlock(number, hash));
+					}
+				}
+			}
+			db.iter(columns::BODY, &mut |key, _| {
+				if !headers.contains(key) {
+					if let Some((number, hash)) = decode_lookup_key::<Block>(key) {
+						issues.push(Inconsistency::OrphanedBody(number.saturated_into(), hash));
+					}
+				}
+			});
+			db.iter(columns::JUSTIFICATIONS, &mut |key, _| {
+				if !headers.contains(key) {
+					if let Some((number, hash)) = decode_lookup_key::<Block>(key) {
+						let number = number.saturated_into();
+						issues.push(Inconsistency::OrphanedJustifications(number, hash));
+					}
+				}
+			});
+			db.iter(columns::KEY_LOOKUP, &mut |key, value| {
+				// Number index entries are checked along the canonical chain.
+				if key.len() != 4 && !headers.contains(value) {
+					if let Ok(hash) = Block::Hash::decode(&mut &key[..]) {
+						issues.push(Inconsistency::OrphanedHashIndex(hash));
+					}
+				}
+			});
+		}
+
+		info!("Checking the state database journal...");
+		let best_canonical = self.storage.state_db.best_canonical();
+		for (hash, number) in self.storage.state_db.journal_blocks() {
+			let known = if best_canonical.map_or(false, |c| number <= c) && number >= first {
+				canonical_hash(number) == Some(hash)
+			} else {
+				db.contains(columns::HEADER, &utils::number_and_hash_to_lookup_key(number, hash)?)
+			};
+			if !known {
+				issues.push(Inconsistency::StateJournal(number.saturated_into(), hash));
+			}
+		}
+
+		if options.state {
+			let mut blocks = vec![(finalized_number, finalized_hash)];
+			if best_hash != finalized_hash {
+				blocks.push((best_number, best_hash));
+			}
+			for (number, hash) in blocks {
+				info!("Reading the state of block #{} ({:?})...", number, hash);
+				let result = sc_client_api::Backend::state_at(self, BlockId::Hash(hash))
+					.map_err(|e| e.to_string())
+					.and_then(|state| read_state::<HashFor<Block>, _>(&state));
+				match result {
+					Ok(keys) => debug!(target: "db", "Read {} keys of block #{}", keys, number),
+					Err(error) => {
+						let number = number.saturated_into();
+						issues.push(Inconsistency::MissingState(number, hash, error))
+					},
+				}
+			}
+		}
+
+		if options.repair {
+			self.repair(&report.inconsistencies)?;
+			report.repaired = true;
+		}
+
+		Ok(report)
+	}
+
+	fn repair(
+		&self,
+		inconsistencies: &[Inconsistency<Block::Hash, NumberFor<Block>>],
+	) -> ClientResult<()> {
+		let db = &*self.storage.db;
+		let mut transaction = Transaction::new();
+		let mut removed = Vec::new();
+		let mut leaves = self.blockchain.leaves.write();
+		// Children of the parents of the removed non-canonical blocks.
+		let mut children_of = HashMap::new();
+		let mut removed_blocks = HashSet::new();
+		for inconsistency in inconsistencies {
+			match inconsistency {
+				Inconsistency::NumberIndex(number, hash) => utils::insert_number_to_key_mapping(
+					&mut transaction,
+					columns::KEY_LOOKUP,
+					*number,
+					hash,
+				)?,
+				Inconsistency::HashIndex(number, hash) => utils::insert_hash_to_key_mapping(
+					&mut transaction,
+					columns::KEY_LOOKUP,
+					*number,
+					*hash,
+				)?,
+				Inconsistency::NumberIndexAboveBest(number) => utils::remove_number_to_key_mapping(
+					&mut transaction,
+					columns::KEY_LOOKUP,
+					*number,
+				)?,
+				Inconsistency::NonCanonicalBlock(number, hash) => {
+					let lookup_key = utils::number_and_hash_to_lookup_key(*number, hash)?;
+					let header = db.get(columns::HEADER, &lookup_key)
+						.and_then(|header| Block::Header::decode(&mut &header[..]).ok());
+					if let Some(header) = header {
+						let parent = *header.parent_hash();
+						let children = match children_of.entry(parent) {
+							Entry::Occupied(entry) => entry.into_mut(),
+							Entry::Vacant(entry) => entry.insert(children::read_children(
+								db,
+								columns::META,
+								meta_keys::CHILDREN_PREFIX,
+								parent,
+							)?),
+						};
+						children.retain(|child: &Block::Hash| child != hash);
+					}
+					for column in [columns::HEADER, columns::BODY, columns::JUSTIFICATIONS].iter() {
+						transaction.remove(*column, &lookup_key);
+					}
+					if db.get(columns::KEY_LOOKUP, hash.as_ref()) == Some(lookup_key) {
+						transaction.remove(columns::KEY_LOOKUP, hash.as_ref());
+					}
+					children::remove_children(
+						&mut transaction,
+						columns::META,
+						meta_keys::CHILDREN_PREFIX,
+						*hash,
+					);
+					leaves.remove(*hash, *number);
+					removed_blocks.insert(*hash);
+					removed.push(*hash);
+				},
+				Inconsistency::OrphanedBody(number, hash) => transaction.remove(
+					columns::BODY,
+					&utils::number_and_hash_to_lookup_key(*number, hash)?,
+				),
+				Inconsistency::OrphanedJustifications(number, hash) => transaction.remove(
+					columns::JUSTIFICATIONS,
+					&utils::number_and_hash_to_lookup_key(*number, hash)?,
+				),
+				Inconsistency::OrphanedHashIndex(hash) => {
+					transaction.remove(columns::KEY_LOOKUP, hash.as_ref());
+					removed.push(*hash);
+				},
+				Inconsistency::MissingHeader(..) |
+				Inconsistency::MissingBody(..) |
+				Inconsistency::StateJournal(..) |
+				Inconsistency::MissingState(..) => {},
+			}
+		}
+		for (parent, children) in children_of {
+			// The children of the removed blocks are removed with them.
+			if removed_blocks.contains(&parent) {
+				continue
+			}
+			if children.is_empty() {
+				children::remove_children(
+					&mut transaction,
+					columns::META,
+					meta_keys::CHILDREN_PREFIX,
+					parent,
+				);
+			} else {
+				children::write_children(
+					&mut transaction,
+					columns::META,
+					meta_keys::CHILDREN_PREFIX,
+					parent,
+					children,
+				);
+			}
+		}
+		leaves.prepare_transaction(&mut transaction, columns::META, meta_keys::LEAF_PREFIX);
+		db.commit(transaction)?;
+
+		for hash in removed {
+			self.blockchain.header_cache.lock().remove(&hash);
+			self.blockchain.header_metadata_cache.remove_header_metadata(hash);
+		}
+		Ok(())
+	}
+}
+
+/// Decode the number and hash of a block lookup key.
+fn decode_lookup_key<Block: BlockT>(key: &[u8]) -> Option<(u64, Block::Hash)> {
+	let number = utils::lookup_key_to_number::<u32>(key).ok()?;
+	let hash = Block::Hash::decode(&mut &key[4..]).ok()?;
+	Some((number.into(), hash))
+}
+
+/// Read every key and value of `state`, including its child tries, and return the number of keys.
+///
+/// Reaching every key visits every node of the tries, so this fails if any of them is missing.
+fn read_state<H, S>(state: &S) -> Result<u64, String>
+where
+	H: hash_db::Hasher,
+	S: StateBackend<H>,
+{
+	let mut keys = 0;
+	let mut key = Vec::new();
+	if state.storage(&key).map_err(|e| e.to_string())?.is_some() {
+		keys += 1;
+	}
+	while let Some(next) = state.next_storage_key(&key).map_err(|e| e.to_string())? {
+		state.storage(&next).map_err(|e| e.to_string())?;
+		keys += 1;
+
+		if let Some(storage_key) = next.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+			let child_info = ChildInfo::new_default(storage_key);
+			let mut child_key = Vec::new();
+			if state.child_storage(&child_info, &child_key).map_err(|e| e.to_string())?.is_some() {
+				keys += 1;
+			}
+			while let Some(next) = state.next_child_storage_key(&child_info, &child_key)
+				.map_err(|e| e.to_string())?
+			{
+				state.child_storage(&child_info, &next).map_err(|e| e.to_string())?;
+				keys += 1;
+				child_key = next;
+			}
+		}
+		key = next;
+	}
+	Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		DatabaseSettings, DatabaseSettingsSrc, KeepBlocks, OffchainIndexingMode,
+		TransactionStorageMode,
+	};
+	use crate::tests::{insert_header, Block};
+	use sc_client_api::backend::Backend as _;
+	use sc_state_db::PruningMode;
+	use sp_blockchain::{Backend as _, HeaderBackend};
+	use sp_core::H256;
+
+	#[test]
+	fn repairs_indexes_and_removes_non_canonical_blocks() {
+		let backend = Backend::<Block>::new_test(10, 10);
+		let genesis = insert_header(&backend, 0, Default::default(), None, Default::default());
+		let a1 = insert_header(&backend, 1, genesis, None, Default::default());
+		let b1 = insert_header(&backend, 1, genesis, None, H256::from([1; 32]));
+		let b2 = insert_header(&backend, 2, a1, None, H256::from([1; 32]));
+		let a2 = insert_header(&backend, 2, a1, None, Default::default());
+		backend.finalize_block(BlockId::Hash(a1), None).unwrap();
+		backend.finalize_block(BlockId::Hash(a2), None).unwrap();
+
+		let mut transaction = Transaction::new();
+		transaction.remove(columns::KEY_LOOKUP, &utils::number_index_key(1u64).unwrap());
+		transaction.remove(columns::KEY_LOOKUP, a2.as_ref());
+		transaction.set(columns::KEY_LOOKUP, &utils::number_index_key(3u64).unwrap(), &[]);
+		backend.storage.db.commit(transaction).unwrap();
+
+		let report = backend.check_database(CheckOptions { state: true, repair: false }).unwrap();
+		assert_eq!(report.canonical_blocks, 3);
+		assert!(report.scanned_columns);
+		assert_eq!(
+			report.inconsistencies,
+			vec![
+				Inconsistency::HashIndex(2, a2),
+				Inconsistency::NumberIndex(1, a1),
+				Inconsistency::NumberIndexAboveBest(3),
+				Inconsistency::NonCanonicalBlock(1, b1),
+				Inconsistency::NonCanonicalBlock(2, b2),
+			],
+		);
+		assert!(!report.repaired);
+		assert_eq!(backend.blockchain().leaves().unwrap(), vec![b2, a2]);
+
+		let report = backend.check_database(CheckOptions { state: false, repair: true }).unwrap();
+		assert_eq!(report.inconsistencies.len(), 5);
+		assert!(report.repaired);
+
+		// The leaf set and children index are read back from the database.
+		let backend = Backend::<Block>::new(DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			state_pruning: PruningMode::keep_blocks(10),
+			source: DatabaseSettingsSrc::Custom(backend.storage.db.clone()),
+			keep_blocks: KeepBlocks::Some(10),
+			transaction_storage: TransactionStorageMode::BlockBody,
+			offchain_indexing: OffchainIndexingMode::Immediate,
+		}, 10).unwrap();
+		assert_eq!(backend.blockchain().leaves().unwrap(), vec![a2]);
+		assert_eq!(backend.blockchain().children(a1).unwrap(), vec![a2]);
+		assert_eq!(backend.blockchain().children(genesis).unwrap(), vec![a1]);
+
+		let report = backend.check_database(CheckOptions::default()).unwrap();
+		assert!(report.inconsistencies.is_empty());
+		assert_eq!(backend.blockchain().hash(1).unwrap(), Some(a1));
+		assert_eq!(backend.blockchain().number(a2).unwrap(), Some(2));
+		assert!(backend.blockchain().header(BlockId::Hash(b1)).unwrap().is_none());
+	}
+}
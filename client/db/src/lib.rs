@@ -34,9 +34,11 @@ pub mod offchain;
 #[cfg(any(feature = "with-kvdb-rocksdb", test))]
 pub mod bench;
 
+mod check;
 mod children;
 mod cache;
 mod changes_tries_storage;
+mod migration;
 mod storage_cache;
 #[cfg(any(feature = "with-kvdb-rocksdb", test))]
 mod upgrade;
@@ -68,8 +70,7 @@ use hash_db::Prefix;
 use sp_trie::{MemoryDB, PrefixedMemoryDB, prefixed_key};
 use sp_database::Transaction;
 use sp_core::ChangesTrieConfiguration;
-use sp_core::offchain::OffchainOverlayedChange;
-use sp_core::storage::{well_known_keys, ChildInfo};
+use sp_core::storage::{well_known_keys, ChildInfo, StateVersion};
 use sp_arithmetic::traits::Saturating;
 use sp_runtime::{generic::{DigestItem, BlockId}, Justification, Justifications, Storage};
 use sp_runtime::traits::{
@@ -91,6 +92,8 @@ use crate::stats::StateUsageStats;
 // Re-export the Database trait so that one can pass an implementation of it.
 pub use sp_database::Database;
 pub use sc_state_db::PruningMode;
+pub use check::{CheckOptions, CheckReport, Inconsistency};
+pub use migration::migrate_database;
 
 #[cfg(any(feature = "with-kvdb-rocksdb", test))]
 pub use bench::BenchmarkingState;
@@ -238,16 +241,18 @@ impl<B: BlockT> StateBackend<HashFor<B>> for RefTrackingState<B> {
 	fn storage_root<'a>(
 		&self,
 		delta: impl Iterator<Item=(&'a [u8], Option<&'a [u8]>)>,
+		state_version: StateVersion,
 	) -> (B::Hash, Self::Transaction) where B::Hash: Ord {
-		self.state.storage_root(delta)
+		self.state.storage_root(delta, state_version)
 	}
 
 	fn child_storage_root<'a>(
 		&self,
 		child_info: &ChildInfo,
 		delta: impl Iterator<Item=(&'a [u8], Option<&'a [u8]>)>,
+		state_version: StateVersion,
 	) -> (B::Hash, bool, Self::Transaction) where B::Hash: Ord {
-		self.state.child_storage_root(child_info, delta)
+		self.state.child_storage_root(child_info, delta, state_version)
 	}
 
 	fn pairs(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
@@ -295,6 +300,8 @@ pub struct DatabaseSettings {
 	pub keep_blocks: KeepBlocks,
 	/// Block body/Transaction storage scheme.
 	pub transaction_storage: TransactionStorageMode,
+	/// Offchain indexing scheme.
+	pub offchain_indexing: OffchainIndexingMode,
 }
 
 /// Block pruning settings.
@@ -316,6 +323,24 @@ pub enum TransactionStorageMode {
 	StorageChain,
 }
 
+/// Offchain indexing scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffchainIndexingMode {
+	/// Write the offchain indexing changes of a block to the offchain storage when the block
+	/// is imported.
+	Immediate,
+	/// Record the offchain indexing changes of a block under its hash when the block is
+	/// imported, and write them to the offchain storage when it is finalized. The changes of
+	/// blocks that are never finalized are discarded.
+	ForkAware,
+}
+
+impl Default for OffchainIndexingMode {
+	fn default() -> Self {
+		OffchainIndexingMode::Immediate
+	}
+}
+
 /// Where to find the database..
 #[derive(Debug, Clone)]
 pub enum DatabaseSettingsSrc {
@@ -748,27 +773,16 @@ pub struct BlockImportOperation<Block: BlockT> {
 	set_head: Option<BlockId<Block>>,
 	commit_state: bool,
 	index_ops: Vec<IndexOperation>,
+	snapshot: Option<SnapshotAncestry<Block>>,
 }
 
-impl<Block: BlockT> BlockImportOperation<Block> {
-	fn apply_offchain(&mut self, transaction: &mut Transaction<DbHash>) {
-		let mut count = 0;
-		for ((prefix, key), value_operation) in self.offchain_storage_updates.drain(..) {
-			count += 1;
-			let key = crate::offchain::concatenate_prefix_and_key(&prefix, &key);
-			match value_operation {
-				OffchainOverlayedChange::SetValue(val) =>
-					transaction.set_from_vec(columns::OFFCHAIN, &key, val),
-				OffchainOverlayedChange::Remove =>
-					transaction.remove(columns::OFFCHAIN, &key),
-			}
-		}
-
-		if count > 0 {
-			log::debug!(target: "sc_offchain", "Applied {} offchain indexing changes.", count);
-		}
-	}
+/// Blocks before the block imported from a snapshot.
+struct SnapshotAncestry<Block: BlockT> {
+	genesis_hash: Block::Hash,
+	headers: Vec<Block::Header>,
+}
 
+impl<Block: BlockT> BlockImportOperation<Block> {
 	fn apply_aux(&mut self, transaction: &mut Transaction<DbHash>) {
 		for (key, maybe_val) in self.aux_ops.drain(..) {
 			match maybe_val {
@@ -781,6 +795,7 @@ impl<Block: BlockT> BlockImportOperation<Block> {
 	fn apply_new_state(
 		&mut self,
 		storage: Storage,
+		state_version: StateVersion,
 	) -> ClientResult<Block::Hash> {
 		if storage.top.keys().any(|k| well_known_keys::is_child_storage_key(&k)) {
 			return Err(sp_blockchain::Error::InvalidState.into());
@@ -799,7 +814,8 @@ impl<Block: BlockT> BlockImportOperation<Block> {
 				}
 				(&k[..], Some(&v[..]))
 			}),
-			child_delta
+			child_delta,
+			state_version,
 		);
 
 		let changes_trie_config = match changes_trie_config {
@@ -856,8 +872,9 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 	fn reset_storage(
 		&mut self,
 		storage: Storage,
+		state_version: StateVersion,
 	) -> ClientResult<Block::Hash> {
-		let root = self.apply_new_state(storage)?;
+		let root = self.apply_new_state(storage, state_version)?;
 		self.commit_state = true;
 		Ok(root)
 	}
@@ -866,8 +883,9 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 		&mut self,
 		storage: Storage,
 		commit: bool,
+		state_version: StateVersion,
 	) -> ClientResult<Block::Hash> {
-		let root = self.apply_new_state(storage)?;
+		let root = self.apply_new_state(storage, state_version)?;
 		self.commit_state = commit;
 		Ok(root)
 	}
@@ -1046,6 +1064,7 @@ pub struct Backend<Block: BlockT> {
 	is_archive: bool,
 	keep_blocks: KeepBlocks,
 	transaction_storage: TransactionStorageMode,
+	offchain_indexing: OffchainIndexingMode,
 	io_stats: FrozenForDuration<(kvdb::IoStats, StateUsageInfo)>,
 	state_usage: Arc<StateUsageStats>,
 	genesis_state: RwLock<Option<Arc<DbGenesisStorage<Block>>>>,
@@ -1086,6 +1105,7 @@ impl<Block: BlockT> Backend<Block> {
 			source: DatabaseSettingsSrc::Custom(db),
 			keep_blocks: KeepBlocks::Some(keep_blocks),
 			transaction_storage,
+			offchain_indexing: OffchainIndexingMode::Immediate,
 		};
 
 		Self::new(db_setting, canonicalization_delay).expect("failed to create test-db")
@@ -1143,6 +1163,7 @@ impl<Block: BlockT> Backend<Block> {
 			state_usage: Arc::new(StateUsageStats::new()),
 			keep_blocks: config.keep_blocks.clone(),
 			transaction_storage: config.transaction_storage.clone(),
+			offchain_indexing: config.offchain_indexing,
 			genesis_state: RwLock::new(None),
 		};
 
@@ -1163,6 +1184,63 @@ impl<Block: BlockT> Backend<Block> {
 		Ok(backend)
 	}
 
+	/// Seed an empty database with a block and its state, taken from a snapshot.
+	///
+	/// The block is imported as the best and finalized block, so that syncing continues from
+	/// it. Of the blocks before it, only the given ancestor headers are stored. They must be
+	/// ordered from the oldest to the parent of `header`.
+	///
+	/// The state is written in a single transaction, so `storage` and the trie nodes computed
+	/// from it are all held in memory until it is committed.
+	pub fn import_snapshot(
+		&self,
+		genesis_hash: Block::Hash,
+		ancestors: Vec<Block::Header>,
+		header: Block::Header,
+		justifications: Option<Justifications>,
+		storage: Storage,
+		state_version: StateVersion,
+		aux: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> ClientResult<()> {
+		if self.blockchain.meta.read().genesis_hash != Default::default() {
+			return Err(sp_blockchain::Error::Backend(
+				"Snapshots can only be imported into an empty database".into(),
+			))
+		}
+
+		let mut child = &header;
+		for ancestor in ancestors.iter().rev() {
+			if *child.parent_hash() != ancestor.hash() ||
+				*child.number() != *ancestor.number() + One::one()
+			{
+				return Err(sp_blockchain::Error::Backend(
+					format!("Snapshot ancestor {:?} is not the parent of {:?}", ancestor.hash(), child.hash()),
+				))
+			}
+			child = ancestor;
+		}
+
+		let mut operation = sc_client_api::Backend::begin_operation(self)?;
+		let state_root = operation.apply_new_state(storage, state_version)?;
+		if state_root != *header.state_root() {
+			return Err(sp_blockchain::Error::InvalidStateRoot)
+		}
+		operation.commit_state = true;
+		operation.pending_block = Some(PendingBlock {
+			header,
+			body: None,
+			indexed_body: None,
+			justifications,
+			leaf_state: NewBlockState::Final,
+		});
+		operation.aux_ops = aux.into_iter().map(|(key, value)| (key, Some(value))).collect();
+		operation.snapshot = Some(SnapshotAncestry { genesis_hash, headers: ancestors });
+
+		sc_client_api::Backend::commit_operation(self, operation)?;
+		self.blockchain.meta.write().genesis_hash = genesis_hash;
+		Ok(())
+	}
+
 	/// Handle setting head within a transaction. `route_to` should be the last
 	/// block that existed in the database. `best_to` should be the best block
 	/// to be set.
@@ -1256,11 +1334,13 @@ impl<Block: BlockT> Backend<Block> {
 		justification: Option<Justification>,
 		changes_trie_cache_ops: &mut Option<DbChangesTrieStorageTransaction<Block>>,
 		finalization_displaced: &mut Option<FinalizationDisplaced<Block::Hash, NumberFor<Block>>>,
+		offchain: &mut offchain::OffchainTransaction,
 	) -> ClientResult<MetaUpdate<Block>> {
 		// TODO: ensure best chain contains this block.
 		let number = *header.number();
 		self.ensure_sequential_finalization(header, last_finalized)?;
 		let with_state = sc_client_api::Backend::have_state_at(self, &hash, number);
+		offchain.finalize_block(transaction, number.saturated_into::<u64>(), hash.as_ref());
 
 		self.note_finalized(
 			transaction,
@@ -1337,12 +1417,16 @@ impl<Block: BlockT> Backend<Block> {
 		let mut finalization_displaced_leaves = None;
 
 		operation.apply_aux(&mut transaction);
-		operation.apply_offchain(&mut transaction);
 
 		let mut meta_updates = Vec::with_capacity(operation.finalized_blocks.len());
 		let mut last_finalized_hash = self.blockchain.meta.read().finalized_hash;
 		let mut last_finalized_num = self.blockchain.meta.read().finalized_number;
 		let best_num = self.blockchain.meta.read().best_number;
+		let mut offchain = offchain::OffchainTransaction::new(
+			&*self.storage.db,
+			self.offchain_indexing,
+			last_finalized_num.saturated_into::<u64>(),
+		);
 
 		let mut changes_trie_cache_ops = None;
 		for (block, justification) in operation.finalized_blocks {
@@ -1356,6 +1440,7 @@ impl<Block: BlockT> Backend<Block> {
 				justification,
 				&mut changes_trie_cache_ops,
 				&mut finalization_displaced_leaves,
+				&mut offchain,
 			)?);
 			last_finalized_hash = block_hash;
 			last_finalized_num = block_header.number().clone();
@@ -1411,6 +1496,31 @@ impl<Block: BlockT> Backend<Block> {
 				transaction.set_from_vec(columns::JUSTIFICATIONS, &lookup_key, justifications.encode());
 			}
 
+			if let Some(snapshot) = &operation.snapshot {
+				transaction.set(columns::META, meta_keys::GENESIS_HASH, snapshot.genesis_hash.as_ref());
+				for ancestor in &snapshot.headers {
+					let ancestor_number = *ancestor.number();
+					let ancestor_hash = ancestor.hash();
+					utils::insert_hash_to_key_mapping(
+						&mut transaction,
+						columns::KEY_LOOKUP,
+						ancestor_number,
+						ancestor_hash,
+					)?;
+					utils::insert_number_to_key_mapping(
+						&mut transaction,
+						columns::KEY_LOOKUP,
+						ancestor_number,
+						ancestor_hash,
+					)?;
+					transaction.set_from_vec(
+						columns::HEADER,
+						&utils::number_and_hash_to_lookup_key(ancestor_number, ancestor_hash)?,
+						ancestor.encode(),
+					);
+				}
+			}
+
 			if number.is_zero() {
 				transaction.set_from_vec(columns::META, meta_keys::FINALIZED_BLOCK, lookup_key.clone());
 				transaction.set(columns::META, meta_keys::GENESIS_HASH, hash.as_ref());
@@ -1519,14 +1629,28 @@ impl<Block: BlockT> Backend<Block> {
 			);
 
 			if !existing_header {
-				let changes_trie_config_update = operation.changes_trie_config_update;
-				changes_trie_cache_ops = Some(self.changes_tries_storage.commit(
+				offchain.import_block(
 					&mut transaction,
-					changes_trie_updates,
+					number.saturated_into::<u64>(),
+					hash.as_ref(),
+					finalized,
+					std::mem::take(&mut operation.offchain_storage_updates),
+				);
+
+				let changes_trie_config_update = operation.changes_trie_config_update;
+				// The block of a snapshot is the first one known to the cache.
+				let cache_parent = if operation.snapshot.is_some() {
+					cache::ComplexBlockId::new(Default::default(), Zero::zero())
+				} else {
 					cache::ComplexBlockId::new(
 						*header.parent_hash(),
 						if number.is_zero() { Zero::zero() } else { number - One::one() },
-					),
+					)
+				};
+				changes_trie_cache_ops = Some(self.changes_tries_storage.commit(
+					&mut transaction,
+					changes_trie_updates,
+					cache_parent,
 					cache::ComplexBlockId::new(hash, number),
 					header,
 					finalized,
@@ -1540,7 +1664,9 @@ impl<Block: BlockT> Backend<Block> {
 
 				if finalized {
 					// TODO: ensure best chain contains this block.
-					self.ensure_sequential_finalization(header, Some(last_finalized_hash))?;
+					if operation.snapshot.is_none() {
+						self.ensure_sequential_finalization(header, Some(last_finalized_hash))?;
+					}
 					self.note_finalized(
 						&mut transaction,
 						true,
@@ -1594,6 +1720,7 @@ impl<Block: BlockT> Backend<Block> {
 				None
 			}
 		} else {
+			offchain.apply(&mut transaction, std::mem::take(&mut operation.offchain_storage_updates));
 			None
 		};
 
@@ -1958,6 +2085,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			set_head: None,
 			commit_state: false,
 			index_ops: Default::default(),
+			snapshot: None,
 		})
 	}
 
@@ -2007,6 +2135,11 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 		let mut displaced = None;
 
 		let mut changes_trie_cache_ops = None;
+		let mut offchain = offchain::OffchainTransaction::new(
+			&*self.storage.db,
+			self.offchain_indexing,
+			self.blockchain.meta.read().finalized_number.saturated_into::<u64>(),
+		);
 		let m = self.finalize_block_with_transaction(
 			&mut transaction,
 			&hash,
@@ -2015,6 +2148,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			justification,
 			&mut changes_trie_cache_ops,
 			&mut displaced,
+			&mut offchain,
 		)?;
 		self.storage.db.commit(transaction)?;
 		self.blockchain.update_meta(m);
@@ -2527,6 +2661,7 @@ pub(crate) mod tests {
 			source: DatabaseSettingsSrc::Custom(backing),
 			keep_blocks: KeepBlocks::All,
 			transaction_storage: TransactionStorageMode::BlockBody,
+			offchain_indexing: OffchainIndexingMode::Immediate,
 		}, 0).unwrap();
 		assert_eq!(backend.blockchain().info().best_number, 9);
 		for i in 0..10 {
@@ -2554,14 +2689,15 @@ pub(crate) mod tests {
 
 			header.state_root = op.old_state.storage_root(storage
 				.iter()
-				.map(|(x, y)| (&x[..], Some(&y[..])))
+				.map(|(x, y)| (&x[..], Some(&y[..]))),
+				Default::default(),
 			).0.into();
 			let hash = header.hash();
 
 			op.reset_storage(Storage {
 				top: storage.into_iter().collect(),
 				children_default: Default::default(),
-			}).unwrap();
+			}, Default::default()).unwrap();
 			op.set_block_data(
 				header.clone(),
 				Some(vec![]),
@@ -2599,7 +2735,8 @@ pub(crate) mod tests {
 
 			let (root, overlay) = op.old_state.storage_root(
 				storage.iter()
-					.map(|(k, v)| (&k[..], v.as_ref().map(|v| &v[..])))
+					.map(|(k, v)| (&k[..], v.as_ref().map(|v| &v[..]))),
+				Default::default(),
 			);
 			op.update_db_storage(overlay).unwrap();
 			header.state_root = root.into();
@@ -2640,13 +2777,13 @@ pub(crate) mod tests {
 				extrinsics_root: Default::default(),
 			};
 
-			header.state_root = op.old_state.storage_root(std::iter::empty()).0.into();
+			header.state_root = op.old_state.storage_root(std::iter::empty(), Default::default()).0.into();
 			let hash = header.hash();
 
 			op.reset_storage(Storage {
 				top: Default::default(),
 				children_default: Default::default(),
-			}).unwrap();
+			}, Default::default()).unwrap();
 
 			key = op.db_updates.insert(EMPTY_PREFIX, b"hello");
 			op.set_block_data(
@@ -2681,7 +2818,8 @@ pub(crate) mod tests {
 			header.state_root = op.old_state.storage_root(storage
 				.iter()
 				.cloned()
-				.map(|(x, y)| (x, Some(y)))
+				.map(|(x, y)| (x, Some(y))),
+				Default::default(),
 			).0.into();
 			let hash = header.hash();
 
@@ -2719,7 +2857,8 @@ pub(crate) mod tests {
 			header.state_root = op.old_state.storage_root(storage
 				.iter()
 				.cloned()
-				.map(|(x, y)| (x, Some(y)))
+				.map(|(x, y)| (x, Some(y))),
+				Default::default(),
 			).0.into();
 			let hash = header.hash();
 
@@ -2757,7 +2896,8 @@ pub(crate) mod tests {
 			header.state_root = op.old_state.storage_root(storage
 				.iter()
 				.cloned()
-				.map(|(x, y)| (x, Some(y)))
+				.map(|(x, y)| (x, Some(y))),
+				Default::default(),
 			).0.into();
 
 			op.set_block_data(
@@ -3090,14 +3230,15 @@ pub(crate) mod tests {
 
 			header.state_root = op.old_state.storage_root(storage
 				.iter()
-				.map(|(x, y)| (&x[..], Some(&y[..])))
+				.map(|(x, y)| (&x[..], Some(&y[..]))),
+				Default::default(),
 			).0.into();
 			let hash = header.hash();
 
 			op.reset_storage(Storage {
 				top: storage.into_iter().collect(),
 				children_default: Default::default(),
-			}).unwrap();
+			}, Default::default()).unwrap();
 			op.set_block_data(
 				header.clone(),
 				Some(vec![]),
@@ -3131,7 +3272,8 @@ pub(crate) mod tests {
 
 			let (root, overlay) = op.old_state.storage_root(
 				storage.iter()
-					.map(|(k, v)| (&k[..], v.as_ref().map(|v| &v[..])))
+					.map(|(k, v)| (&k[..], v.as_ref().map(|v| &v[..]))),
+				Default::default(),
 			);
 			op.update_db_storage(overlay).unwrap();
 			header.state_root = root.into();
@@ -3366,4 +3508,96 @@ pub(crate) mod tests {
 		assert_eq!(None, backend.blockchain().header(BlockId::hash(prev_hash.clone())).unwrap());
 		assert!(!backend.have_state_at(&prev_hash, 1));
 	}
+
+	#[test]
+	fn import_snapshot_seeds_an_empty_database() {
+		let backend = Backend::<Block>::new_test(10, 0);
+		let storage = vec![(vec![1, 3, 5], vec![2, 4, 6])];
+		let state_root = backend.begin_operation().unwrap().old_state.storage_root(
+			storage.iter().map(|(x, y)| (&x[..], Some(&y[..]))),
+			Default::default(),
+		).0.into();
+		let snapshot_storage = || Storage {
+			top: storage.clone().into_iter().collect(),
+			children_default: Default::default(),
+		};
+
+		let genesis_hash = H256::repeat_byte(1);
+		let mut ancestors = Vec::new();
+		let mut parent_hash = genesis_hash;
+		for number in 1..3 {
+			let ancestor = Header {
+				number,
+				parent_hash,
+				state_root: Default::default(),
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+			parent_hash = ancestor.hash();
+			ancestors.push(ancestor);
+		}
+		let header = Header {
+			number: 3,
+			parent_hash,
+			state_root,
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let hash = header.hash();
+
+		let mut wrong_header = header.clone();
+		wrong_header.state_root = Default::default();
+		assert!(matches!(
+			backend.import_snapshot(
+				genesis_hash,
+				ancestors.clone(),
+				wrong_header,
+				None,
+				snapshot_storage(),
+				Default::default(),
+				Vec::new(),
+			),
+			Err(sp_blockchain::Error::InvalidStateRoot),
+		));
+
+		backend.import_snapshot(
+			genesis_hash,
+			ancestors,
+			header,
+			None,
+			snapshot_storage(),
+			Default::default(),
+			vec![(b"aux".to_vec(), b"value".to_vec())],
+		).unwrap();
+
+		let info = backend.blockchain().info();
+		assert_eq!(info.genesis_hash, genesis_hash);
+		assert_eq!((info.best_number, info.best_hash), (3, hash));
+		assert_eq!((info.finalized_number, info.finalized_hash), (3, hash));
+		assert_eq!(backend.blockchain().hash(2).unwrap(), Some(parent_hash));
+		assert_eq!(backend.get_aux(b"aux").unwrap(), Some(b"value".to_vec()));
+		let state = backend.state_at(BlockId::Hash(hash)).unwrap();
+		assert_eq!(state.storage(&[1, 3, 5]).unwrap(), Some(vec![2, 4, 6]));
+
+		// A second snapshot is refused.
+		assert!(backend.import_snapshot(
+			genesis_hash,
+			Vec::new(),
+			Header {
+				number: 5,
+				parent_hash: Default::default(),
+				state_root,
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			},
+			None,
+			snapshot_storage(),
+			Default::default(),
+			Vec::new(),
+		).is_err());
+
+		// Blocks are imported on top of the snapshot block.
+		let child = insert_header(&backend, 4, hash, None, Default::default());
+		assert_eq!(backend.blockchain().info().best_hash, child);
+	}
 }
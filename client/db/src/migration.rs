@@ -0,0 +1,343 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Migration of the database of a full node from RocksDb to ParityDb.
+//!
+//! RocksDb stores trie nodes under their hash prefixed by their position in the trie, and keeps
+//! a single copy of each. ParityDb stores them under their hash and counts references to them, so
+//! the state column and the state database journals are converted while copying. Indexed
+//! transactions are copied with their reference counts. All the other columns are copied as is.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sp_blockchain::{Error as ClientError, Result as ClientResult};
+use sp_database::{Database, Transaction};
+use sp_runtime::traits::Block as BlockT;
+
+use crate::utils::{self, DatabaseType, NUM_COLUMNS};
+use crate::{columns, DatabaseSettingsSrc, DbHash, StateMetaDb, DB_HASH_LEN};
+
+/// Maximum number of entries written in a single transaction.
+const BATCH_SIZE: usize = 10_000;
+
+/// Copy the database of a full node at `from` to a new database at `to`.
+///
+/// Only migrations from RocksDb to ParityDb are supported, and `to` must not contain any data.
+/// The database is copied to a temporary directory next to `to`, which is moved to `to` once the
+/// migration is complete and removed if it fails. `progress` is called while copying with the
+/// name of a column and the number of its entries copied so far.
+pub fn migrate_database<Block: BlockT>(
+	from: &DatabaseSettingsSrc,
+	to: &DatabaseSettingsSrc,
+	progress: &mut dyn FnMut(&'static str, u64),
+) -> ClientResult<()> {
+	let to_path = match (from, to) {
+		(
+			DatabaseSettingsSrc::RocksDb { path: from_path, .. },
+			DatabaseSettingsSrc::ParityDb { path: to_path },
+		) => {
+			if !from_path.exists() {
+				return Err(ClientError::Backend(
+					format!("No database found at {}", from_path.display()),
+				))
+			}
+			if to_path.read_dir().map_or(false, |mut entries| entries.next().is_some()) {
+				return Err(ClientError::Backend(
+					format!("Target directory {} is not empty", to_path.display()),
+				))
+			}
+			to_path
+		},
+		_ => return Err(ClientError::Backend(
+			format!("Migrating a {} database to {} is not supported", from, to),
+		)),
+	};
+
+	let mut tmp_path = to_path.clone().into_os_string();
+	tmp_path.push(".migration");
+	let tmp_path = PathBuf::from(tmp_path);
+	// Left behind if a previous migration was interrupted.
+	if tmp_path.exists() {
+		fs::remove_dir_all(&tmp_path).map_err(|e| io_error(&tmp_path, e))?;
+	}
+
+	let tmp = DatabaseSettingsSrc::ParityDb { path: tmp_path.clone() };
+	if let Err(e) = copy_database::<Block>(from, &tmp, progress) {
+		let _ = fs::remove_dir_all(&tmp_path);
+		return Err(e)
+	}
+
+	if to_path.exists() {
+		fs::remove_dir(to_path).map_err(|e| io_error(to_path, e))?;
+	}
+	fs::rename(&tmp_path, to_path).map_err(|e| io_error(to_path, e))
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> ClientError {
+	ClientError::Backend(format!("Error migrating the database to {}: {}", path.display(), e))
+}
+
+/// Copy the database at `from` to the new database at `to`, converting the entries that are
+/// stored differently.
+fn copy_database<Block: BlockT>(
+	from: &DatabaseSettingsSrc,
+	to: &DatabaseSettingsSrc,
+	progress: &mut dyn FnMut(&'static str, u64),
+) -> ClientResult<()> {
+	let source = utils::open_database_source::<Block>(from, DatabaseType::Full)?;
+	let target = utils::open_database_source::<Block>(to, DatabaseType::Full)?;
+
+	for col in 0..NUM_COLUMNS {
+		let name = column_name(col);
+		let mut batch = Batch::new(&*target);
+		let mut copied = 0u64;
+		let mut error = None;
+		let iterated = source.iter(col, &mut |key, value| {
+			if error.is_some() {
+				return
+			}
+			match copy_entry(&*source, &mut batch, col, key, value) {
+				Ok(()) => {
+					copied += 1;
+					if copied % BATCH_SIZE as u64 == 0 {
+						progress(name, copied);
+					}
+				},
+				Err(e) => error = Some(e),
+			}
+		});
+		if !iterated {
+			return Err(ClientError::Backend(format!("Can not iterate over the {} database", from)))
+		}
+		if let Some(e) = error {
+			return Err(e)
+		}
+		batch.flush()?;
+		progress(name, copied);
+	}
+
+	// The journals were copied along with the rest of the state metadata, and are overwritten
+	// with their converted records.
+	let journals = sc_state_db::ref_counted_journals::<Block::Hash, Vec<u8>, _>(
+		&StateMetaDb(&*source),
+		|key| state_key(key).to_vec(),
+	).map_err(|e| sp_blockchain::Error::from_state_db(e))?;
+	let mut transaction = Transaction::new();
+	for (key, record) in journals {
+		transaction.set_from_vec(columns::STATE_META, &key, record);
+	}
+	target.commit(transaction)?;
+
+	Ok(())
+}
+
+/// Copy a single entry of column `col` of `source`.
+fn copy_entry(
+	source: &dyn Database<DbHash>,
+	batch: &mut Batch,
+	col: u32,
+	key: &[u8],
+	value: &[u8],
+) -> ClientResult<()> {
+	match col {
+		// A node stored under several prefixes gets a reference for each of them.
+		columns::STATE => batch.set(col, state_key(key), value),
+		columns::TRANSACTION => {
+			// Reference counters are stored next to the values, under the key of the value
+			// followed by a zero byte.
+			if key.len() != DB_HASH_LEN {
+				return Ok(())
+			}
+			let mut counter_key = key.to_vec();
+			counter_key.push(0);
+			let references = match source.get(col, &counter_key) {
+				Some(counter) if counter.len() == 4 => {
+					let mut counter_data = [0; 4];
+					counter_data.copy_from_slice(&counter);
+					u32::from_le_bytes(counter_data)
+				},
+				Some(counter) => return Err(ClientError::Backend(
+					format!("Unexpected transaction counter len {}", counter.len()),
+				)),
+				None => 1,
+			};
+			for _ in 0..references {
+				batch.set(col, key, value)?;
+			}
+			Ok(())
+		},
+		_ => batch.set(col, key, value),
+	}
+}
+
+/// Key of a trie node in a database that does not prefix them.
+fn state_key(key: &[u8]) -> &[u8] {
+	&key[key.len().saturating_sub(DB_HASH_LEN)..]
+}
+
+fn column_name(col: u32) -> &'static str {
+	match col {
+		columns::META => "meta",
+		columns::STATE => "state",
+		columns::STATE_META => "state_meta",
+		columns::KEY_LOOKUP => "key_lookup",
+		columns::HEADER => "header",
+		columns::BODY => "body",
+		columns::JUSTIFICATIONS => "justifications",
+		columns::CHANGES_TRIE => "changes_trie",
+		columns::AUX => "aux",
+		columns::OFFCHAIN => "offchain",
+		columns::CACHE => "cache",
+		columns::TRANSACTION => "transaction",
+		_ => "unknown",
+	}
+}
+
+/// Entries written to the target database in transactions of at most `BATCH_SIZE` entries.
+///
+/// A key is written at most once per transaction, so that each write to a reference counted
+/// column adds a reference.
+struct Batch<'a> {
+	db: &'a dyn Database<DbHash>,
+	transaction: Transaction<DbHash>,
+	keys: HashSet<(u32, Vec<u8>)>,
+}
+
+impl<'a> Batch<'a> {
+	fn new(db: &'a dyn Database<DbHash>) -> Self {
+		Batch {
+			db,
+			transaction: Transaction::new(),
+			keys: HashSet::new(),
+		}
+	}
+
+	fn set(&mut self, col: u32, key: &[u8], value: &[u8]) -> ClientResult<()> {
+		let entry = (col, key.to_vec());
+		if self.keys.len() >= BATCH_SIZE || self.keys.contains(&entry) {
+			self.flush()?;
+		}
+		self.keys.insert(entry);
+		self.transaction.set(col, key, value);
+		Ok(())
+	}
+
+	fn flush(&mut self) -> ClientResult<()> {
+		self.keys.clear();
+		self.db.commit(std::mem::replace(&mut self.transaction, Transaction::new()))?;
+		Ok(())
+	}
+}
+
+#[cfg(all(test, feature = "with-kvdb-rocksdb", feature = "with-parity-db"))]
+mod tests {
+	use sc_client_api::backend::{Backend as _, BlockImportOperation as _, NewBlockState};
+	use sc_client_api::blockchain::Backend as _;
+	use sc_state_db::PruningMode;
+	use sp_blockchain::HeaderBackend;
+	use sp_core::storage::Storage;
+	use sp_runtime::{generic::BlockId, testing::Header, traits::Header as _};
+	use sp_state_machine::Backend as _;
+	use crate::{Backend, DatabaseSettings, KeepBlocks, OffchainIndexingMode, TransactionStorageMode};
+	use crate::tests::Block;
+	use super::*;
+
+	fn settings(source: DatabaseSettingsSrc) -> DatabaseSettings {
+		DatabaseSettings {
+			state_cache_size: 0,
+			state_cache_child_ratio: None,
+			state_pruning: PruningMode::ArchiveAll,
+			source,
+			keep_blocks: KeepBlocks::All,
+			transaction_storage: TransactionStorageMode::BlockBody,
+			offchain_indexing: OffchainIndexingMode::Immediate,
+		}
+	}
+
+	#[test]
+	fn migrated_blocks_and_state_can_be_read() {
+		let dir = tempfile::TempDir::new().unwrap();
+		let rocksdb = DatabaseSettingsSrc::RocksDb {
+			path: dir.path().join("rocksdb"),
+			cache_size: 128,
+		};
+		let paritydb = DatabaseSettingsSrc::ParityDb { path: dir.path().join("paritydb") };
+
+		let (genesis_hash, hash) = {
+			let backend = Backend::<Block>::new(settings(rocksdb.clone()), 0).unwrap();
+
+			let mut op = backend.begin_operation().unwrap();
+			let storage = Storage {
+				top: vec![(vec![1, 3, 5], vec![2, 4, 6]), (vec![1, 2, 3], vec![9, 9, 9])]
+					.into_iter().collect(),
+				children_default: Default::default(),
+			};
+			let genesis = Header {
+				number: 0,
+				parent_hash: Default::default(),
+				state_root: op.reset_storage(storage, Default::default()).unwrap(),
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+			op.set_block_data(genesis.clone(), Some(vec![]), None, None, NewBlockState::Final)
+				.unwrap();
+			backend.commit_operation(op).unwrap();
+
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(genesis.hash())).unwrap();
+			let changes = vec![(vec![1, 3, 5], None), (vec![5, 5, 5], Some(vec![4, 5, 6]))];
+			let (state_root, overlay) = op.old_state.storage_root(
+				changes.iter().map(|(k, v)| (&k[..], v.as_ref().map(|v| &v[..]))),
+				Default::default(),
+			);
+			op.update_db_storage(overlay).unwrap();
+			op.update_storage(changes, Vec::new()).unwrap();
+			let header = Header {
+				number: 1,
+				parent_hash: genesis.hash(),
+				state_root: state_root.into(),
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+			op.set_block_data(header.clone(), Some(vec![]), None, None, NewBlockState::Final)
+				.unwrap();
+			backend.commit_operation(op).unwrap();
+
+			(genesis.hash(), header.hash())
+		};
+
+		migrate_database::<Block>(&rocksdb, &paritydb, &mut |_, _| ()).unwrap();
+		assert!(!dir.path().join("paritydb.migration").exists());
+
+		let backend = Backend::<Block>::new(settings(paritydb), 0).unwrap();
+		let info = backend.blockchain().info();
+		assert_eq!(info.genesis_hash, genesis_hash);
+		assert_eq!((info.best_number, info.best_hash), (1, hash));
+		assert_eq!((info.finalized_number, info.finalized_hash), (1, hash));
+		assert_eq!(backend.blockchain().hash(0).unwrap(), Some(genesis_hash));
+		assert_eq!(backend.blockchain().body(BlockId::Hash(hash)).unwrap(), Some(Vec::new()));
+
+		let genesis_state = backend.state_at(BlockId::Hash(genesis_hash)).unwrap();
+		assert_eq!(genesis_state.storage(&[1, 3, 5]).unwrap(), Some(vec![2, 4, 6]));
+		let state = backend.state_at(BlockId::Hash(hash)).unwrap();
+		assert_eq!(state.storage(&[1, 3, 5]).unwrap(), None);
+		assert_eq!(state.storage(&[1, 2, 3]).unwrap(), Some(vec![9, 9, 9]));
+		assert_eq!(state.storage(&[5, 5, 5]).unwrap(), Some(vec![4, 5, 6]));
+	}
+}
@@ -20,9 +20,18 @@
 
 use std::{collections::HashMap, sync::Arc};
 
-use crate::{columns, Database, DbHash, Transaction};
+use crate::{columns, Database, DbHash, OffchainIndexingMode, Transaction};
+use codec::{Decode, Encode};
 use parking_lot::Mutex;
-use log::error;
+use log::{debug, error, warn};
+use sp_state_machine::OffchainChangesCollection;
+
+/// Prefix of the keys under which the offchain indexing changes of non-finalized blocks are
+/// recorded, followed by the block number.
+const JOURNAL_PREFIX: &[u8] = b"offchain_indexing_journal";
+
+/// Offchain indexing changes of the non-finalized blocks at a given height, by block hash.
+type Journal = Vec<(Vec<u8>, OffchainChangesCollection)>;
 
 /// Offchain local storage
 #[derive(Clone)]
@@ -116,6 +125,142 @@ impl sp_core::offchain::OffchainStorage for LocalStorage {
 	}
 }
 
+/// Offchain indexing changes written by a database transaction.
+///
+/// Changes that depend on the current value of a key see the values written earlier in the
+/// same transaction.
+pub(crate) struct OffchainTransaction<'a> {
+	db: &'a dyn Database<DbHash>,
+	mode: OffchainIndexingMode,
+	finalized_number: u64,
+	written: HashMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> OffchainTransaction<'a> {
+	/// Create a new offchain transaction on top of the given database, of which the last
+	/// finalized block is `finalized_number`.
+	pub fn new(
+		db: &'a dyn Database<DbHash>,
+		mode: OffchainIndexingMode,
+		finalized_number: u64,
+	) -> Self {
+		Self {
+			db,
+			mode,
+			finalized_number,
+			written: HashMap::new(),
+		}
+	}
+
+	/// Write the offchain indexing changes to the offchain storage.
+	pub fn apply(
+		&mut self,
+		transaction: &mut Transaction<DbHash>,
+		changes: OffchainChangesCollection,
+	) {
+		let count = changes.len();
+		for ((prefix, key), change) in changes {
+			let key = concatenate_prefix_and_key(&prefix, &key);
+			let current = match self.written.get(&key) {
+				Some(value) => value.clone(),
+				None => self.db.get(columns::OFFCHAIN, &key),
+			};
+			let value = change.apply(current);
+			match &value {
+				Some(value) => transaction.set(columns::OFFCHAIN, &key, value),
+				None => transaction.remove(columns::OFFCHAIN, &key),
+			}
+			self.written.insert(key, value);
+		}
+
+		if count > 0 {
+			debug!(target: "sc_offchain", "Applied {} offchain indexing changes.", count);
+		}
+	}
+
+	/// Handle the offchain indexing changes of an imported block.
+	///
+	/// In fork-aware mode, the changes of a block that is not finalized are recorded until it
+	/// is, and the changes of a block on a fork that is already abandoned are discarded.
+	pub fn import_block(
+		&mut self,
+		transaction: &mut Transaction<DbHash>,
+		number: u64,
+		hash: &[u8],
+		finalized: bool,
+		changes: OffchainChangesCollection,
+	) {
+		if changes.is_empty() {
+			return
+		}
+		if finalized || self.mode == OffchainIndexingMode::Immediate {
+			return self.apply(transaction, changes)
+		}
+		if number <= self.finalized_number {
+			debug!(
+				target: "sc_offchain",
+				"Discarded {} offchain indexing changes of abandoned block #{}.",
+				changes.len(),
+				number,
+			);
+			return
+		}
+
+		let key = journal_key(number);
+		let mut journal = self.read_journal(&key);
+		journal.retain(|(h, _)| h.as_slice() != hash);
+		journal.push((hash.to_vec(), changes));
+		transaction.set_from_vec(columns::OFFCHAIN, &key, journal.encode());
+	}
+
+	/// Write the recorded offchain indexing changes of a finalized block, and discard the ones
+	/// of the other blocks at the same height.
+	pub fn finalize_block(
+		&mut self,
+		transaction: &mut Transaction<DbHash>,
+		number: u64,
+		hash: &[u8],
+	) {
+		self.finalized_number = number;
+		if self.mode == OffchainIndexingMode::Immediate {
+			return
+		}
+
+		let key = journal_key(number);
+		let journal = self.read_journal(&key);
+		if journal.is_empty() {
+			return
+		}
+		transaction.remove(columns::OFFCHAIN, &key);
+		for (block_hash, changes) in journal {
+			if block_hash.as_slice() == hash {
+				self.apply(transaction, changes);
+			} else {
+				debug!(
+					target: "sc_offchain",
+					"Discarded {} offchain indexing changes of abandoned block #{}.",
+					changes.len(),
+					number,
+				);
+			}
+		}
+	}
+
+	fn read_journal(&self, key: &[u8]) -> Journal {
+		match self.db.get(columns::OFFCHAIN, key) {
+			Some(journal) => Journal::decode(&mut &journal[..]).unwrap_or_else(|e| {
+				warn!(target: "sc_offchain", "Discarding corrupted offchain indexing journal: {:?}", e);
+				Vec::new()
+			}),
+			None => Vec::new(),
+		}
+	}
+}
+
+fn journal_key(number: u64) -> Vec<u8> {
+	concatenate_prefix_and_key(JOURNAL_PREFIX, &number.to_be_bytes())
+}
+
 /// Concatenate the prefix and key to create an offchain key in the db.
 pub(crate) fn concatenate_prefix_and_key(prefix: &[u8], key: &[u8]) -> Vec<u8> {
 	prefix
@@ -128,7 +273,7 @@ pub(crate) fn concatenate_prefix_and_key(prefix: &[u8], key: &[u8]) -> Vec<u8> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use sp_core::offchain::OffchainStorage;
+	use sp_core::offchain::{OffchainOverlayedChange, OffchainStorage, STORAGE_PREFIX};
 
 	#[test]
 	fn should_compare_and_set_and_clear_the_locks_map() {
@@ -156,4 +301,78 @@ mod tests {
 		assert!(storage.locks.lock().is_empty(), "Locks map should be empty!");
 	}
 
+	fn indexing_changes(changes: Vec<(&[u8], OffchainOverlayedChange)>) -> OffchainChangesCollection {
+		changes.into_iter()
+			.map(|(key, change)| ((STORAGE_PREFIX.to_vec(), key.to_vec()), change))
+			.collect()
+	}
+
+	#[test]
+	fn indexing_changes_depend_on_earlier_ones() {
+		let db = sp_database::as_database(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
+		let storage = LocalStorage::new(db.clone());
+
+		let mut tx = Transaction::new();
+		let mut offchain = OffchainTransaction::new(&*db, OffchainIndexingMode::Immediate, 0);
+		offchain.apply(&mut tx, indexing_changes(vec![
+			(&b"key"[..], OffchainOverlayedChange::SetValue(b"a".to_vec())),
+		]));
+		offchain.apply(&mut tx, indexing_changes(vec![
+			(&b"key"[..], OffchainOverlayedChange::Append(b"b".to_vec())),
+			(&b"other"[..], OffchainOverlayedChange::CompareAndSet {
+				old_value: Some(b"a".to_vec()),
+				new_value: b"b".to_vec(),
+			}),
+		]));
+		db.commit(tx).unwrap();
+
+		assert_eq!(storage.get(STORAGE_PREFIX, b"key"), Some(b"ab".to_vec()));
+		assert_eq!(storage.get(STORAGE_PREFIX, b"other"), None);
+	}
+
+	#[test]
+	fn fork_aware_indexing_only_writes_finalized_changes() {
+		let db = sp_database::as_database(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
+		let storage = LocalStorage::new(db.clone());
+		fn set(value: &[u8]) -> OffchainOverlayedChange {
+			OffchainOverlayedChange::SetValue(value.to_vec())
+		}
+
+		let mut tx = Transaction::new();
+		let mut offchain = OffchainTransaction::new(&*db, OffchainIndexingMode::ForkAware, 0);
+		offchain.import_block(&mut tx, 1, b"a1", false, indexing_changes(vec![
+			(&b"key"[..], set(b"a1")),
+		]));
+		offchain.import_block(&mut tx, 2, b"a2", false, indexing_changes(vec![
+			(&b"key"[..], set(b"a2")),
+		]));
+		db.commit(tx).unwrap();
+
+		let mut tx = Transaction::new();
+		let mut offchain = OffchainTransaction::new(&*db, OffchainIndexingMode::ForkAware, 0);
+		offchain.import_block(&mut tx, 1, b"b1", false, indexing_changes(vec![
+			(&b"fork"[..], set(b"b1")),
+		]));
+		db.commit(tx).unwrap();
+		assert_eq!(storage.get(STORAGE_PREFIX, b"key"), None);
+
+		let mut tx = Transaction::new();
+		let mut offchain = OffchainTransaction::new(&*db, OffchainIndexingMode::ForkAware, 0);
+		offchain.finalize_block(&mut tx, 1, b"a1");
+		db.commit(tx).unwrap();
+		assert_eq!(storage.get(STORAGE_PREFIX, b"key"), Some(b"a1".to_vec()));
+		assert_eq!(storage.get(STORAGE_PREFIX, b"fork"), None);
+		assert_eq!(db.get(columns::OFFCHAIN, &journal_key(1)), None);
+
+		// Blocks on forks that are already abandoned are ignored.
+		let mut tx = Transaction::new();
+		let mut offchain = OffchainTransaction::new(&*db, OffchainIndexingMode::ForkAware, 1);
+		offchain.import_block(&mut tx, 1, b"c1", false, indexing_changes(vec![
+			(&b"fork"[..], set(b"c1")),
+		]));
+		offchain.finalize_block(&mut tx, 2, b"a2");
+		db.commit(tx).unwrap();
+		assert_eq!(storage.get(STORAGE_PREFIX, b"key"), Some(b"a2".to_vec()));
+		assert_eq!(storage.get(STORAGE_PREFIX, b"fork"), None);
+	}
 }
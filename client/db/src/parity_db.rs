@@ -17,11 +17,12 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 /// A `Database` adapter for parity-db.
 
+use std::collections::HashMap;
 use sp_database::{Database, Change, ColumnId, Transaction, error::DatabaseError};
 use crate::utils::{DatabaseType, NUM_COLUMNS};
 use crate::columns;
 
-struct DbAdapter(parity_db::Db);
+struct DbAdapter(parity_db::Db, Vec<ColumnId>);
 
 fn handle_err<T>(result: parity_db::Result<T>) -> T {
 	match result {
@@ -32,31 +33,108 @@ fn handle_err<T>(result: parity_db::Result<T>) -> T {
 	}
 }
 
+/// Columns of which the values are reference counted in the database at `path`.
+///
+/// Trie nodes and indexed transactions are stored under the hash of their value, and the same
+/// value may be inserted by several blocks. Setting such a value again adds a reference to it
+/// and removing it releases one, so that pruning only deletes values that are no longer used.
+///
+/// parity-db refuses to open a database with a different column configuration than the one it
+/// was created with, and the `TRANSACTION` column used not to be reference counted. Existing
+/// databases keep their configuration, so they can't store indexed transactions until they are
+/// migrated to a new database.
+fn ref_counted_columns(
+	path: &std::path::Path,
+	db_type: DatabaseType,
+) -> parity_db::Result<Vec<ColumnId>> {
+	let columns = match db_type {
+		DatabaseType::Full => vec![columns::STATE, columns::TRANSACTION],
+		DatabaseType::Light => Vec::new(),
+	};
+	let metadata = match std::fs::read_to_string(path.join("metadata")) {
+		Ok(metadata) => metadata,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(columns),
+		Err(e) => return Err(parity_db::Error::Io(e)),
+	};
+	Ok(columns.into_iter().filter(|col| {
+		let prefix = format!("col{}=", col);
+		metadata.lines()
+			.find_map(|line| line.strip_prefix(&prefix))
+			.map_or(true, |options| options.contains("refc: true"))
+	}).collect())
+}
+
 /// Wrap parity-db database into a trait object that implements `sp_database::Database`
 pub fn open<H: Clone + AsRef<[u8]>>(path: &std::path::Path, db_type: DatabaseType)
 	-> parity_db::Result<std::sync::Arc<dyn Database<H>>>
 {
 	let mut config = parity_db::Options::with_columns(path, NUM_COLUMNS as u8);
 	config.sync = true; // Flush each commit
-	if db_type == DatabaseType::Full {
-		let mut state_col = &mut config.columns[columns::STATE as usize];
-		state_col.ref_counted = true;
-		state_col.preimage = true;
-		state_col.uniform = true;
+	let ref_counted = ref_counted_columns(path, db_type)?;
+	for col in &ref_counted {
+		let column = &mut config.columns[*col as usize];
+		column.ref_counted = true;
+		// Keys are hashes of the values, so a value is never overwritten by a different one.
+		column.preimage = true;
+		column.uniform = true;
 	}
 	let db = parity_db::Db::open(&config)?;
-	Ok(std::sync::Arc::new(DbAdapter(db)))
+	Ok(std::sync::Arc::new(DbAdapter(db, ref_counted)))
+}
+
+impl DbAdapter {
+	fn ensure_ref_counted(&self, col: ColumnId) -> Result<(), DatabaseError> {
+		if self.1.contains(&col) {
+			Ok(())
+		} else {
+			Err(DatabaseError(Box::new(std::io::Error::new(
+				std::io::ErrorKind::Other,
+				format!(
+					"Column {} is not reference counted, the database must be migrated to a new one",
+					col,
+				),
+			))))
+		}
+	}
 }
 
 impl<H: Clone + AsRef<[u8]>> Database<H> for DbAdapter {
 	fn commit(&self, transaction: Transaction<H>) -> Result<(), DatabaseError> {
-		handle_err(self.0.commit(transaction.0.into_iter().map(|change|
-			match change {
+		let mut changes: Vec<(u8, Vec<u8>, Option<Vec<u8>>)> =
+			Vec::with_capacity(transaction.0.len());
+		// Index in `changes` of the values stored by this transaction, which may be referenced
+		// before they are committed.
+		let mut stored = HashMap::new();
+		for change in transaction.0 {
+			changes.push(match change {
 				Change::Set(col, key, value) => (col as u8, key, Some(value)),
 				Change::Remove(col, key) => (col as u8, key, None),
-				_ => unimplemented!(),
-			}))
-		);
+				Change::Store(col, key, value) => {
+					self.ensure_ref_counted(col)?;
+					stored.insert((col, key.as_ref().to_vec()), changes.len());
+					(col as u8, key.as_ref().to_vec(), Some(value))
+				},
+				Change::Reference(col, key) => {
+					self.ensure_ref_counted(col)?;
+					// A reference is added by inserting the value again. Like with the
+					// `kvdb` adapter, referencing a missing value does nothing.
+					let key = key.as_ref().to_vec();
+					let value = match stored.get(&(col, key.clone())) {
+						Some(index) => changes[*index].2.clone(),
+						None => handle_err(self.0.get(col as u8, &key)),
+					};
+					match value {
+						Some(value) => (col as u8, key, Some(value)),
+						None => continue,
+					}
+				},
+				Change::Release(col, key) => {
+					self.ensure_ref_counted(col)?;
+					(col as u8, key.as_ref().to_vec(), None)
+				},
+			});
+		}
+		handle_err(self.0.commit(changes));
 
 		Ok(())
 	}
@@ -73,3 +151,72 @@ impl<H: Clone + AsRef<[u8]>> Database<H> for DbAdapter {
 		handle_err(self.0.get_size(col as u8, key)).map(|s| s as usize)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::DbHash;
+
+	/// Wait until the previous commits are written. parity-db writes them in the background, and
+	/// released values stay readable until then.
+	fn wait_for_commits(db: &dyn Database<DbHash>) {
+		let marker = DbHash::repeat_byte(0xff);
+		let mut transaction = Transaction::new();
+		transaction.store(columns::STATE, marker, b"marker".to_vec());
+		db.commit(transaction).unwrap();
+		let mut transaction = Transaction::new();
+		transaction.release(columns::STATE, marker);
+		db.commit(transaction).unwrap();
+		while db.get(columns::STATE, marker.as_ref()).is_some() {
+			std::thread::sleep(std::time::Duration::from_millis(10));
+		}
+	}
+
+	#[test]
+	fn values_stored_in_a_transaction_can_be_referenced_by_it() {
+		let dir = tempfile::TempDir::new().unwrap();
+		let db = open::<DbHash>(dir.path(), DatabaseType::Full).unwrap();
+		let hash = DbHash::repeat_byte(1);
+
+		let mut transaction = Transaction::new();
+		transaction.store(columns::STATE, hash, b"value".to_vec());
+		transaction.reference(columns::STATE, hash);
+		db.commit(transaction).unwrap();
+
+		let mut transaction = Transaction::new();
+		transaction.release(columns::STATE, hash);
+		db.commit(transaction).unwrap();
+		wait_for_commits(&*db);
+		assert_eq!(db.get(columns::STATE, hash.as_ref()), Some(b"value".to_vec()));
+
+		let mut transaction = Transaction::new();
+		transaction.release(columns::STATE, hash);
+		db.commit(transaction).unwrap();
+		wait_for_commits(&*db);
+		assert_eq!(db.get(columns::STATE, hash.as_ref()), None);
+	}
+
+	#[test]
+	fn databases_without_ref_counted_transactions_can_be_opened() {
+		let dir = tempfile::TempDir::new().unwrap();
+		{
+			let mut config = parity_db::Options::with_columns(dir.path(), NUM_COLUMNS as u8);
+			let state = &mut config.columns[columns::STATE as usize];
+			state.ref_counted = true;
+			state.preimage = true;
+			state.uniform = true;
+			parity_db::Db::open(&config).unwrap();
+		}
+
+		let db = open::<DbHash>(dir.path(), DatabaseType::Full).unwrap();
+		let hash = DbHash::repeat_byte(1);
+		let mut transaction = Transaction::new();
+		transaction.store(columns::STATE, hash, b"value".to_vec());
+		db.commit(transaction).unwrap();
+		assert_eq!(db.get(columns::STATE, hash.as_ref()), Some(b"value".to_vec()));
+
+		let mut transaction = Transaction::new();
+		transaction.store(columns::TRANSACTION, hash, b"value".to_vec());
+		assert!(db.commit(transaction).is_err());
+	}
+}
@@ -138,7 +138,9 @@ fn version_file_path(path: &Path) -> PathBuf {
 #[cfg(test)]
 mod tests {
 	use sc_state_db::PruningMode;
-	use crate::{DatabaseSettings, DatabaseSettingsSrc, KeepBlocks, TransactionStorageMode};
+	use crate::{
+		DatabaseSettings, DatabaseSettingsSrc, KeepBlocks, OffchainIndexingMode, TransactionStorageMode,
+	};
 	use crate::tests::Block;
 	use super::*;
 
@@ -158,6 +160,7 @@ mod tests {
 			source: DatabaseSettingsSrc::RocksDb { path: db_path.to_owned(), cache_size: 128 },
 			keep_blocks: KeepBlocks::All,
 			transaction_storage: TransactionStorageMode::BlockBody,
+			offchain_indexing: OffchainIndexingMode::Immediate,
 		}, DatabaseType::Full).map(|_| ())
 	}
 
@@ -215,6 +215,14 @@ pub fn block_id_to_lookup_key<Block>(
 pub fn open_database<Block: BlockT>(
 	config: &DatabaseSettings,
 	db_type: DatabaseType,
+) -> sp_blockchain::Result<Arc<dyn Database<DbHash>>> {
+	open_database_source::<Block>(&config.source, db_type)
+}
+
+/// Opens the database at the given source.
+pub fn open_database_source<Block: BlockT>(
+	source: &DatabaseSettingsSrc,
+	db_type: DatabaseType,
 ) -> sp_blockchain::Result<Arc<dyn Database<DbHash>>> {
 	#[allow(unused)]
 	fn db_open_error(feat: &'static str) -> sp_blockchain::Error {
@@ -223,7 +231,7 @@ pub fn open_database<Block: BlockT>(
 		)
 	}
 
-	let db: Arc<dyn Database<DbHash>> = match &config.source {
+	let db: Arc<dyn Database<DbHash>> = match source {
 		#[cfg(any(feature = "with-kvdb-rocksdb", test))]
 		DatabaseSettingsSrc::RocksDb { path, cache_size } => {
 			// first upgrade database to required version
@@ -382,13 +382,20 @@ fn decode_runtime_apis(apis: &[u8]) -> Result<Vec<([u8; 8], u32)>, WasmError> {
 pub fn read_embedded_version(
 	blob: &RuntimeBlob,
 ) -> Result<Option<RuntimeVersion>, WasmError> {
-	if let Some(mut version_section) = blob.custom_section_contents("runtime_version") {
+	if let Some(version_section) = blob.custom_section_contents("runtime_version") {
 		// We do not use `decode_version` here because the runtime_version section is not supposed
 		// to ever contain a legacy version. Apart from that `decode_version` relies on presence
 		// of a special API in the `apis` field to treat the input as a non-legacy version. However
 		// the structure found in the `runtime_version` always contain an empty `apis` field. Therefore
 		// the version read will be mistakenly treated as an legacy one.
-		let mut decoded_version = sp_api::RuntimeVersion::decode(&mut version_section)
+		//
+		// Runtimes built before the `state_version` field was introduced embed a section without
+		// it, so fall back to decoding the section as a `Core` version 3 `RuntimeVersion`.
+		let mut decoded_version = sp_api::RuntimeVersion::decode(&mut &version_section[..])
+			.or_else(|_| sp_api::RuntimeVersion::decode_with_version_hint(
+				&mut &version_section[..],
+				Some(3),
+			))
 			.map_err(|_|
 				WasmError::Instantiation("failed to decode version section".into())
 			)?;
@@ -522,10 +529,30 @@ mod tests {
 			impl_version: 1,
 			apis: sp_api::create_apis_vec!([(<dyn Core::<Block>>::ID, 3)]),
 			transaction_version: 3,
+			state_version: 4,
 		};
 
 		let version = decode_version(&old_runtime_version.encode()).unwrap();
 		assert_eq!(3, version.transaction_version);
+		assert_eq!(0, version.state_version);
+	}
+
+	#[test]
+	fn new_runtime_version_decodes_state_version_with_version_4() {
+		let runtime_version = sp_api::RuntimeVersion {
+			spec_name: "test".into(),
+			impl_name: "test".into(),
+			authoring_version: 1,
+			spec_version: 1,
+			impl_version: 1,
+			apis: sp_api::create_apis_vec!([(<dyn Core::<Block>>::ID, 4)]),
+			transaction_version: 3,
+			state_version: 1,
+		};
+
+		let version = decode_version(&runtime_version.encode()).unwrap();
+		assert_eq!(3, version.transaction_version);
+		assert_eq!(sp_core::storage::StateVersion::V1, version.state_version());
 	}
 
 	#[test]
@@ -541,8 +568,9 @@ mod tests {
 			authoring_version: 100,
 			spec_version: 100,
 			impl_version: 100,
-			apis: sp_api::create_apis_vec!([(<dyn Core::<Block>>::ID, 3)]),
+			apis: sp_api::create_apis_vec!([(<dyn Core::<Block>>::ID, 4)]),
 			transaction_version: 100,
+			state_version: 1,
 		};
 
 		let embedded = sp_version::embed::embed_runtime_version(
@@ -16,6 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use parity_scale_codec::Encode;
 use serde::{Serialize, Deserialize};
 
 use sc_finality_grandpa::FinalityProofProvider;
@@ -32,6 +33,13 @@ pub trait RpcFinalityProofProvider<Block: BlockT> {
 		&self,
 		block: NumberFor<Block>,
 	) -> Result<Option<EncodedFinalityProof>, sc_finality_grandpa::FinalityProofError>;
+
+	/// Prove finality of the given block number on its own, by returning the closest stored
+	/// justification and the headers linking it to the block.
+	fn rpc_prove_block_finality(
+		&self,
+		block: NumberFor<Block>,
+	) -> Result<Option<EncodedFinalityProof>, sc_finality_grandpa::FinalityProofError>;
 }
 
 impl<B, Block> RpcFinalityProofProvider<Block> for FinalityProofProvider<B, Block>
@@ -47,4 +55,12 @@ where
 		self.prove_finality(block)
 			.map(|x| x.map(|y| EncodedFinalityProof(y.into())))
 	}
+
+	fn rpc_prove_block_finality(
+		&self,
+		block: NumberFor<Block>,
+	) -> Result<Option<EncodedFinalityProof>, sc_finality_grandpa::FinalityProofError> {
+		self.prove_block_finality(block)
+			.map(|x| x.map(|y| EncodedFinalityProof(y.encode().into())))
+	}
 }
@@ -89,6 +89,15 @@ pub trait GrandpaApi<Notification, Hash, Number> {
 		&self,
 		block: Number,
 	) -> FutureResult<Option<EncodedFinalityProof>>;
+
+	/// Prove finality of any finalized block by returning the closest stored justification of a
+	/// descendant and the headers linking them together. The proof is checked knowing only the
+	/// authorities of the set whose id it contains.
+	#[rpc(name = "grandpa_proveBlockFinality")]
+	fn prove_block_finality(
+		&self,
+		block: Number,
+	) -> FutureResult<Option<EncodedFinalityProof>>;
 }
 
 /// Implements the GrandpaApi RPC trait for interacting with GRANDPA.
@@ -184,6 +193,23 @@ where
 				.compat()
 		)
 	}
+
+	fn prove_block_finality(
+		&self,
+		block: NumberFor<Block>,
+	) -> FutureResult<Option<EncodedFinalityProof>> {
+		let result = self.finality_proof_provider.rpc_prove_block_finality(block);
+		let future = async move { result }.boxed();
+		Box::new(
+			future
+				.map_err(|e| {
+					warn!("Error proving block finality: {}", e);
+					error::Error::ProveFinalityFailed(e)
+				})
+				.map_err(jsonrpc_core::Error::from)
+				.compat()
+		)
+	}
 }
 
 #[cfg(test)]
@@ -196,7 +222,7 @@ mod tests {
 	use sc_block_builder::{BlockBuilder, RecordProof};
 	use sc_finality_grandpa::{
 		report, AuthorityId, GrandpaJustificationSender, GrandpaJustification,
-		FinalityProof,
+		FinalityProof, BlockFinalityProof,
 	};
 	use sp_blockchain::HeaderBackend;
 	use sp_core::crypto::Public;
@@ -263,6 +289,25 @@ mod tests {
 					.into()
 			)))
 		}
+
+		fn rpc_prove_block_finality(
+			&self,
+			_block: NumberFor<Block>
+		) -> Result<Option<EncodedFinalityProof>, sc_finality_grandpa::FinalityProofError> {
+			let finality_proof = self.finality_proof
+				.as_ref()
+				.expect("Don't call rpc_prove_block_finality without setting the FinalityProof");
+			Ok(Some(EncodedFinalityProof(
+				BlockFinalityProof {
+					set_id: 1,
+					block: finality_proof.block,
+					justification: finality_proof.justification.clone(),
+					headers: finality_proof.unknown_headers.clone(),
+				}
+					.encode()
+					.into()
+			)))
+		}
 	}
 
 	impl ReportVoterState for TestVoterState {
@@ -535,4 +580,29 @@ mod tests {
 		let finality_proof_rpc: FinalityProof<Header> = Decode::decode(&mut &result[..]).unwrap();
 		assert_eq!(finality_proof_rpc, finality_proof);
 	}
+
+	#[test]
+	fn prove_block_finality_with_test_finality_proof_provider() {
+		let finality_proof = FinalityProof {
+			block: header(42).hash(),
+			justification: create_justification().encode(),
+			unknown_headers: vec![header(42)],
+		};
+		let (io,  _) = setup_io_handler_with_finality_proofs(
+			TestVoterState,
+			Some(finality_proof.clone()),
+		);
+
+		let request =
+			"{\"jsonrpc\":\"2.0\",\"method\":\"grandpa_proveBlockFinality\",\"params\":[41],\"id\":1}";
+
+		let meta = sc_rpc::Metadata::default();
+		let resp = io.handle_request_sync(request, meta);
+		let mut resp: serde_json::Value = serde_json::from_str(&resp.unwrap()).unwrap();
+		let result: sp_core::Bytes = serde_json::from_value(resp["result"].take()).unwrap();
+		let proof: BlockFinalityProof<Header> = Decode::decode(&mut &result[..]).unwrap();
+		assert_eq!(proof.set_id, 1);
+		assert_eq!(proof.block, finality_proof.block);
+		assert_eq!(proof.headers, finality_proof.unknown_headers);
+	}
 }
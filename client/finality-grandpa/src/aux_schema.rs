@@ -46,6 +46,9 @@ const BEST_JUSTIFICATION: &[u8] = b"grandpa_best_justification";
 
 const CURRENT_VERSION: u32 = 3;
 
+/// Keys of the auxiliary data needed to resume finalizing blocks from a node snapshot.
+pub const SNAPSHOT_KEYS: &[&[u8]] = &[VERSION_KEY, SET_STATE_KEY, AUTHORITY_SET_KEY];
+
 /// The voter set state.
 #[derive(Debug, Clone, Encode, Decode)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -1081,6 +1081,7 @@ where
 			self.client.clone(),
 			&self.authority_set,
 			Some(self.config.justification_period.into()),
+			self.config.justification_archive_period.map(|period| period.get().into()),
 			hash,
 			number,
 			(round, commit).into(),
@@ -1252,6 +1253,7 @@ pub(crate) fn finalize_block<BE, Block, Client>(
 	client: Arc<Client>,
 	authority_set: &SharedAuthoritySet<Block::Hash, NumberFor<Block>>,
 	justification_period: Option<NumberFor<Block>>,
+	justification_archive_period: Option<NumberFor<Block>>,
 	hash: Block::Hash,
 	number: NumberFor<Block>,
 	justification_or_commit: JustificationOrCommit<Block>,
@@ -1333,6 +1335,15 @@ where
 					}
 				}
 
+				// justifications are also kept periodically on request of the node operator
+				if !justification_required {
+					if let Some(archive_period) = justification_archive_period {
+						let last_finalized_number = client.info().finalized_number;
+						justification_required =
+							last_finalized_number / archive_period != number / archive_period;
+					}
+				}
+
 				let justification = GrandpaJustification::from_commit(
 					&client,
 					round_number,
@@ -35,6 +35,12 @@
 //! Finality proof provider can choose how to provide finality proof on its own. The incomplete
 //! finality proof (that finalizes some block C that is ancestor of the B and descendant
 //! of the U) could be returned.
+//!
+//! A [`BlockFinalityProof`] instead proves the finality of any finalized block B on its own, using
+//! the closest justification stored at or after B. Such a proof is only as compact as the
+//! distance to the next stored justification, which can be bounded by the
+//! [`justification_archive_period`](crate::Config::justification_archive_period) option. It is
+//! checked with [`check_block_finality_proof`], knowing only the authorities of each set.
 
 use log::{trace, warn};
 use std::sync::Arc;
@@ -49,7 +55,7 @@ use sp_runtime::{
 use sc_client_api::backend::Backend;
 
 use crate::{
-	SharedAuthoritySet, best_justification,
+	SharedAuthoritySet, best_justification, BlockNumberOps, SetId,
 	authorities::{AuthoritySetChangeId, AuthoritySetChanges},
 	justification::GrandpaJustification,
 };
@@ -122,6 +128,25 @@ where
 			block,
 		)
 	}
+
+	/// Prove finality of the given block number on its own, by returning the closest stored
+	/// justification of a descendant block and the headers linking them together.
+	pub fn prove_block_finality(
+		&self,
+		block: NumberFor<Block>,
+	) -> Result<Option<BlockFinalityProof<Block::Header>>, FinalityProofError> {
+		let (authority_set_changes, current_set_id) = match self.shared_authority_set.as_ref() {
+			Some(set) => (set.authority_set_changes(), set.set_id()),
+			None => return Ok(None),
+		};
+
+		prove_block_finality(
+			&*self.backend,
+			authority_set_changes,
+			current_set_id,
+			block,
+		)
+	}
 }
 
 /// Finality for block B is proved by providing:
@@ -137,6 +162,20 @@ pub struct FinalityProof<Header: HeaderT> {
 	pub unknown_headers: Vec<Header>,
 }
 
+/// Proof of finality of a single block B, that doesn't depend on the last block finalized by the
+/// caller.
+#[derive(Debug, PartialEq, Encode, Decode, Clone)]
+pub struct BlockFinalityProof<Header: HeaderT> {
+	/// Id of the authority set that signed the justification.
+	pub set_id: SetId,
+	/// The hash of block F for which justification is provided.
+	pub block: Header::Hash,
+	/// Justification of the block F, which is either B or a descendant of B.
+	pub justification: Vec<u8>,
+	/// The headers in the range (B; F], linking B to F. Ordered.
+	pub headers: Vec<Header>,
+}
+
 /// Errors occurring when trying to prove finality
 #[derive(Debug, derive_more::Display, derive_more::From)]
 pub enum FinalityProofError {
@@ -242,6 +281,129 @@ where
 	))
 }
 
+fn prove_block_finality<Block, B>(
+	backend: &B,
+	authority_set_changes: AuthoritySetChanges<NumberFor<Block>>,
+	current_set_id: SetId,
+	block: NumberFor<Block>,
+) -> Result<Option<BlockFinalityProof<Block::Header>>, FinalityProofError>
+where
+	Block: BlockT,
+	B: Backend<Block>,
+{
+	let info = backend.blockchain().info();
+	if info.finalized_number < block {
+		trace!(
+			target: "afg",
+			"Requested finality proof for #{} while we only have finalized #{}.",
+			block,
+			info.finalized_number,
+		);
+		return Err(FinalityProofError::BlockNotYetFinalized);
+	}
+
+	// The justification must have been signed by the set that finalized the block, hence we only
+	// look for it up to the last block of that set.
+	let (set_id, last_block) = match authority_set_changes.get_set_id(block) {
+		AuthoritySetChangeId::Latest => (current_set_id, info.finalized_number),
+		AuthoritySetChangeId::Set(set_id, last_block_for_set) => (set_id, last_block_for_set),
+		AuthoritySetChangeId::Unknown => {
+			warn!(
+				target: "afg",
+				"AuthoritySetChanges does not cover the requested block #{} due to missing data. \
+				 You need to resync to populate AuthoritySetChanges properly.",
+				block,
+			);
+			return Err(FinalityProofError::BlockNotInAuthoritySetChanges);
+		}
+	};
+
+	let mut headers = Vec::new();
+	let mut current = block;
+	let justification = loop {
+		if current > block {
+			headers.push(backend.blockchain().expect_header(BlockId::Number(current))?);
+		}
+
+		let stored = backend
+			.blockchain()
+			.justifications(BlockId::Number(current))?
+			.and_then(|justifications| justifications.into_justification(GRANDPA_ENGINE_ID));
+		if let Some(justification) = stored {
+			break justification;
+		}
+
+		if current >= last_block || headers.len() >= MAX_UNKNOWN_HEADERS {
+			// Justifications of blocks of the latest set are only stored periodically, but the
+			// one of the last finalized block is always kept aside.
+			let best = if current == info.finalized_number {
+				best_justification(backend)?
+					.filter(|j: &GrandpaJustification<Block>| j.target().0 == current)
+			} else {
+				None
+			};
+
+			match best {
+				Some(justification) => break justification.encode(),
+				None => {
+					trace!(
+						target: "afg",
+						"No justification found when making finality proof for #{}. \
+						Returning empty proof.",
+						block,
+					);
+					return Ok(None);
+				}
+			}
+		}
+
+		current += One::one();
+	};
+
+	Ok(Some(BlockFinalityProof {
+		set_id,
+		block: backend.blockchain().expect_block_hash_from_id(&BlockId::Number(current))?,
+		justification,
+		headers,
+	}))
+}
+
+/// Check a [`BlockFinalityProof`], given the authorities of the set that signed it.
+///
+/// Returns the hash and number of the block whose finality is proven.
+pub fn check_block_finality_proof<Block>(
+	proof: &BlockFinalityProof<Block::Header>,
+	authorities: &sp_finality_grandpa::AuthorityList,
+) -> sp_blockchain::Result<(Block::Hash, NumberFor<Block>)>
+where
+	Block: BlockT,
+	NumberFor<Block>: BlockNumberOps,
+{
+	use sp_blockchain::Error as ClientError;
+
+	let justification = GrandpaJustification::<Block>::decode(&mut &proof.justification[..])
+		.map_err(|_| ClientError::JustificationDecode)?;
+	justification.verify(proof.set_id, authorities)?;
+	if justification.target().1 != proof.block {
+		return Err(ClientError::BadJustification(
+			"justification doesn't target the proven block".into(),
+		));
+	}
+
+	let mut child = proof.block;
+	for header in proof.headers.iter().rev() {
+		if header.hash() != child {
+			return Err(ClientError::BadJustification("headers don't form a chain".into()));
+		}
+		child = *header.parent_hash();
+	}
+
+	match proof.headers.first() {
+		Some(first) => Ok((child, *first.number() - One::one())),
+		None => Ok((proof.block, justification.target().0)),
+	}
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
 	use super::*;
@@ -581,4 +743,86 @@ pub(crate) mod tests {
 			}
 		);
 	}
+
+	#[test]
+	fn block_finality_proof_uses_closest_stored_justification() {
+		let (client, backend, blocks) = test_blockchain(8, &[4]);
+		let block4 = &blocks[3];
+		let block5 = &blocks[4];
+		let block6 = &blocks[5];
+
+		let alice = Ed25519Keyring::Alice;
+		let round = 6;
+		let commit = create_commit(block6.clone(), round, 1, &[alice]);
+		let grandpa_just6 = GrandpaJustification::from_commit(&client, round, commit).unwrap();
+		client.finalize_block(BlockId::Number(6), Some((ID, grandpa_just6.encode()))).unwrap();
+		client.finalize_block(BlockId::Number(8), None).unwrap();
+
+		let mut authority_set_changes = AuthoritySetChanges::empty();
+		authority_set_changes.append(0, 2);
+
+		let proof_of_4 = prove_block_finality(&*backend, authority_set_changes, 1, 4)
+			.unwrap()
+			.unwrap();
+		assert_eq!(
+			proof_of_4,
+			BlockFinalityProof {
+				set_id: 1,
+				block: block6.hash(),
+				justification: grandpa_just6.encode(),
+				headers: vec![block5.header().clone(), block6.header().clone()],
+			},
+		);
+
+		let authorities = vec![(alice.public().into(), 1u64)];
+		assert_eq!(
+			check_block_finality_proof::<Block>(&proof_of_4, &authorities).unwrap(),
+			(block4.hash(), 4),
+		);
+
+		// The proof can't be verified with the authorities of another set.
+		let other_authorities = vec![(Ed25519Keyring::Bob.public().into(), 1u64)];
+		check_block_finality_proof::<Block>(&proof_of_4, &other_authorities).unwrap_err();
+
+		// Headers that don't link to the justified block are rejected.
+		let mut broken = proof_of_4.clone();
+		broken.headers.remove(1);
+		check_block_finality_proof::<Block>(&broken, &authorities).unwrap_err();
+	}
+
+	#[test]
+	fn block_finality_proof_in_last_set_falls_back_to_latest_justification() {
+		let (client, backend, blocks) = test_blockchain(8, &[4, 5, 8]);
+		let block8 = &blocks[7];
+
+		let round = 8;
+		let commit = create_commit(block8.clone(), round, 1, &[Ed25519Keyring::Alice]);
+		let grandpa_just8 = GrandpaJustification::from_commit(&client, round, commit).unwrap();
+
+		let mut authority_set_changes = AuthoritySetChanges::empty();
+		authority_set_changes.append(0, 5);
+
+		// Nothing stored yet.
+		assert_eq!(
+			prove_block_finality(&*backend, authority_set_changes.clone(), 1, 7).unwrap(),
+			None,
+		);
+
+		store_best_justification(&client, &grandpa_just8);
+		let proof_of_7 = prove_block_finality(&*backend, authority_set_changes, 1, 7)
+			.unwrap()
+			.unwrap();
+		assert_eq!(proof_of_7.block, block8.hash());
+		assert_eq!(proof_of_7.headers, vec![block8.header().clone()]);
+		assert_eq!(proof_of_7.justification, grandpa_just8.encode());
+	}
+
+	#[test]
+	fn block_finality_proof_fails_for_unfinalized_block() {
+		let (_, backend, _) = test_blockchain(6, &[4]);
+		assert!(matches!(
+			prove_block_finality(&*backend, AuthoritySetChanges::empty(), 0, 5),
+			Err(FinalityProofError::BlockNotYetFinalized),
+		));
+	}
 }
@@ -16,7 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+use std::{collections::HashMap, marker::PhantomData, num::NonZeroU32, sync::Arc};
 
 use log::debug;
 use parity_scale_codec::Encode;
@@ -60,6 +60,7 @@ pub struct GrandpaBlockImport<Backend, Block: BlockT, Client, SC> {
 	send_voter_commands: TracingUnboundedSender<VoterCommand<Block::Hash, NumberFor<Block>>>,
 	authority_set_hard_forks: HashMap<Block::Hash, PendingChange<Block::Hash, NumberFor<Block>>>,
 	justification_sender: GrandpaJustificationSender<Block>,
+	justification_archive_period: Option<NumberFor<Block>>,
 	telemetry: Option<TelemetryHandle>,
 	_phantom: PhantomData<Backend>,
 }
@@ -75,6 +76,7 @@ impl<Backend, Block: BlockT, Client, SC: Clone> Clone
 			send_voter_commands: self.send_voter_commands.clone(),
 			authority_set_hard_forks: self.authority_set_hard_forks.clone(),
 			justification_sender: self.justification_sender.clone(),
+			justification_archive_period: self.justification_archive_period,
 			telemetry: self.telemetry.clone(),
 			_phantom: PhantomData,
 		}
@@ -634,10 +636,21 @@ impl<Backend, Block: BlockT, Client, SC> GrandpaBlockImport<Backend, Block, Clie
 			send_voter_commands,
 			authority_set_hard_forks,
 			justification_sender,
+			justification_archive_period: None,
 			telemetry,
 			_phantom: PhantomData,
 		}
 	}
+
+	/// Keep the justifications imported for finalized blocks every time finality crosses a
+	/// multiple of `period` blocks, like [`Config::justification_archive_period`] does for the
+	/// voter.
+	///
+	/// [`Config::justification_archive_period`]: crate::Config::justification_archive_period
+	pub fn with_justification_archive_period(mut self, period: Option<NonZeroU32>) -> Self {
+		self.justification_archive_period = period.map(|period| period.get().into());
+		self
+	}
 }
 
 impl<BE, Block: BlockT, Client, SC> GrandpaBlockImport<BE, Block, Client, SC>
@@ -683,6 +696,7 @@ where
 			self.inner.clone(),
 			&self.authority_set,
 			None,
+			self.justification_archive_period,
 			hash,
 			number,
 			justification.into(),
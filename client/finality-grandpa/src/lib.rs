@@ -87,6 +87,7 @@ use finality_grandpa::{voter, voter_set::VoterSet};
 pub use finality_grandpa::BlockNumberOps;
 
 use std::{fmt, io};
+use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::time::Duration;
 use std::pin::Pin;
@@ -122,8 +123,11 @@ mod until_imported;
 mod voting_rule;
 
 pub use authorities::{AuthoritySet, AuthoritySetChanges, SharedAuthoritySet};
-pub use aux_schema::best_justification;
-pub use finality_proof::{FinalityProof, FinalityProofError, FinalityProofProvider};
+pub use aux_schema::{best_justification, SNAPSHOT_KEYS as AUX_SNAPSHOT_KEYS};
+pub use finality_proof::{
+	BlockFinalityProof, FinalityProof, FinalityProofError, FinalityProofProvider,
+	check_block_finality_proof,
+};
 pub use import::{find_forced_change, find_scheduled_change, GrandpaBlockImport};
 pub use justification::GrandpaJustification;
 pub use notification::{GrandpaJustificationSender, GrandpaJustificationStream};
@@ -261,6 +265,10 @@ pub struct Config {
 	/// at least every justification_period blocks. There are some other events which might cause
 	/// justification generation.
 	pub justification_period: u32,
+	/// If set, the justification of a finalized block is kept every time finality crosses a
+	/// multiple of this many blocks, including when following the network as an observer. This
+	/// allows proving the finality of any block with a bounded number of headers.
+	pub justification_archive_period: Option<NonZeroU32>,
 	/// Whether the GRANDPA observer protocol is live on the network and thereby
 	/// a full-node not running as a validator is running the GRANDPA observer
 	/// protocol (we will only issue catch-up requests to authorities when the
@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use std::marker::{PhantomData, Unpin};
+use std::num::NonZeroU32;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -70,6 +71,7 @@ fn grandpa_observer<BE, Block: BlockT, Client, S, F>(
 	authority_set: &SharedAuthoritySet<Block::Hash, NumberFor<Block>>,
 	voters: &Arc<VoterSet<AuthorityId>>,
 	justification_sender: &Option<GrandpaJustificationSender<Block>>,
+	justification_archive_period: Option<NumberFor<Block>>,
 	last_finalized_number: NumberFor<Block>,
 	commits: S,
 	note_round: F,
@@ -123,6 +125,7 @@ where
 				client.clone(),
 				&authority_set,
 				None,
+				justification_archive_period,
 				finalized_hash,
 				finalized_number,
 				(round, commit).into(),
@@ -198,6 +201,7 @@ where
 		config.keystore,
 		voter_commands_rx,
 		Some(justification_sender),
+		config.justification_archive_period,
 		telemetry.clone(),
 	);
 
@@ -220,6 +224,7 @@ struct ObserverWork<B: BlockT, BE, Client, N: NetworkT<B>> {
 	keystore: Option<SyncCryptoStorePtr>,
 	voter_commands_rx: TracingUnboundedReceiver<VoterCommand<B::Hash, NumberFor<B>>>,
 	justification_sender: Option<GrandpaJustificationSender<B>>,
+	justification_archive_period: Option<NonZeroU32>,
 	telemetry: Option<TelemetryHandle>,
 	_phantom: PhantomData<BE>,
 }
@@ -239,6 +244,7 @@ where
 		keystore: Option<SyncCryptoStorePtr>,
 		voter_commands_rx: TracingUnboundedReceiver<VoterCommand<B::Hash, NumberFor<B>>>,
 		justification_sender: Option<GrandpaJustificationSender<B>>,
+		justification_archive_period: Option<NonZeroU32>,
 		telemetry: Option<TelemetryHandle>,
 	) -> Self {
 		let mut work = ObserverWork {
@@ -251,6 +257,7 @@ where
 			keystore: keystore.clone(),
 			voter_commands_rx,
 			justification_sender,
+			justification_archive_period,
 			telemetry,
 			_phantom: PhantomData,
 		};
@@ -298,6 +305,7 @@ where
 			&self.persistent_data.authority_set,
 			&voters,
 			&self.justification_sender,
+			self.justification_archive_period.map(|period| period.get().into()),
 			last_finalized_number,
 			global_in,
 			note_round,
@@ -443,6 +451,7 @@ mod tests {
 			voter_command_rx,
 			None,
 			None,
+			None,
 		);
 
 		// Trigger a reputation change through the gossip validator.
@@ -275,6 +275,7 @@ fn initialize_grandpa(
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
 				justification_period: 32,
+				justification_archive_period: None,
 				keystore: Some(keystore),
 				name: Some(format!("peer#{}", peer_id)),
 				local_role: Role::Authority,
@@ -419,6 +420,7 @@ fn finalize_3_voters_1_full_observer() {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
 				justification_period: 32,
+				justification_archive_period: None,
 				keystore: None,
 				name: Some(format!("peer#{}", peer_id)),
 				local_role: Role::Authority,
@@ -522,6 +524,7 @@ fn transition_3_voters_twice_1_full_observer() {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
 				justification_period: 32,
+				justification_archive_period: None,
 				keystore: Some(keystore),
 				name: Some(format!("peer#{}", peer_id)),
 				local_role: Role::Authority,
@@ -950,6 +953,7 @@ fn voter_persists_its_votes() {
 		let config = Config {
 			gossip_duration: TEST_GOSSIP_DURATION,
 			justification_period: 32,
+			justification_archive_period: None,
 			keystore: Some(bob_keystore.clone()),
 			name: Some(format!("peer#{}", 1)),
 			local_role: Role::Authority,
@@ -993,6 +997,7 @@ fn voter_persists_its_votes() {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
 				justification_period: 32,
+				justification_archive_period: None,
 				keystore: Some(keystore),
 				name: Some(format!("peer#{}", 0)),
 				local_role: Role::Authority,
@@ -1034,6 +1039,7 @@ fn voter_persists_its_votes() {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
 				justification_period: 32,
+				justification_archive_period: None,
 				keystore: Some(keystore),
 				name: Some(format!("peer#{}", 0)),
 				local_role: Role::Authority,
@@ -1194,6 +1200,7 @@ fn finalize_3_voters_1_light_observer() {
 		Config {
 			gossip_duration: TEST_GOSSIP_DURATION,
 			justification_period: 32,
+			justification_archive_period: None,
 			keystore: None,
 			name: Some("observer".to_string()),
 			local_role: Role::Full,
@@ -1236,6 +1243,7 @@ fn voter_catches_up_to_latest_round_when_behind() {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
 				justification_period: 32,
+				justification_archive_period: None,
 				keystore,
 				name: Some(format!("peer#{}", peer_id)),
 				local_role: Role::Authority,
@@ -1359,6 +1367,7 @@ where
 	let config = Config {
 		gossip_duration: TEST_GOSSIP_DURATION,
 		justification_period: 32,
+		justification_archive_period: None,
 		keystore,
 		name: None,
 		local_role: Role::Authority,
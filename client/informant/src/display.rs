@@ -18,15 +18,20 @@
 
 use crate::OutputFormat;
 use ansi_term::Colour;
-use log::info;
+use log::{info, warn};
 use sc_client_api::ClientInfo;
 use sc_network::{NetworkStatus, SyncState};
-use sp_runtime::traits::{Block as BlockT, CheckedDiv, NumberFor, Saturating, Zero};
+use sc_transaction_pool_api::PoolStatus;
+use serde_json::json;
+use sp_runtime::traits::{
+	Block as BlockT, CheckedDiv, NumberFor, SaturatedConversion, Saturating, Zero,
+};
 use std::{
 	convert::{TryFrom, TryInto},
 	fmt,
+	io::Write,
 };
-use wasm_timer::Instant;
+use wasm_timer::{Instant, SystemTime, UNIX_EPOCH};
 
 /// State of the informant display system.
 ///
@@ -41,6 +46,7 @@ use wasm_timer::Instant;
 /// Call `InformantDisplay::new` to initialize the state, then regularly call `display` with the
 /// information to display.
 ///
+/// If enabled by the output format, the same information is also written as a line of JSON.
 pub struct InformantDisplay<B: BlockT> {
 	/// Head of chain block number from the last time `display` has been called.
 	/// `None` if `display` has never been called.
@@ -53,22 +59,37 @@ pub struct InformantDisplay<B: BlockT> {
 	last_total_bytes_outbound: u64,
 	/// The format to print output in.
 	format: OutputFormat,
+	/// Destination of the JSON output, if enabled.
+	json: Option<Box<dyn Write + Send>>,
 }
 
 impl<B: BlockT> InformantDisplay<B> {
 	/// Builds a new informant display system.
 	pub fn new(format: OutputFormat) -> InformantDisplay<B> {
+		let json = format.json.as_ref().and_then(|output| match output.open() {
+			Ok(json) => Some(json),
+			Err(e) => {
+				warn!("Failed to open the informant JSON output {:?}: {}", output, e);
+				None
+			},
+		});
 		InformantDisplay {
 			last_number: None,
 			last_update: Instant::now(),
 			last_total_bytes_inbound: 0,
 			last_total_bytes_outbound: 0,
 			format,
+			json,
 		}
 	}
 
-	/// Displays the informant by calling `info!`.
-	pub fn display(&mut self, info: &ClientInfo<B>, net_status: NetworkStatus<B>) {
+	/// Displays the informant by calling `info!`, and writes the JSON output if enabled.
+	pub fn display(
+		&mut self,
+		info: &ClientInfo<B>,
+		net_status: NetworkStatus<B>,
+		pool_status: PoolStatus,
+	) {
 		let best_number = info.chain.best_number;
 		let best_hash = info.chain.best_hash;
 		let finalized_number = info.chain.finalized_number;
@@ -93,6 +114,16 @@ impl<B: BlockT> InformantDisplay<B> {
 				(diff_bytes_inbound, diff_bytes_outbound)
 			};
 
+		let best_seen_block = net_status.best_seen_block;
+		let num_queued_blocks = net_status.num_queued_blocks;
+		let state_download = net_status.state_sync.as_ref()
+			.map(|state| json!({ "percentage": state.percentage, "size": state.size }));
+		let sync_state = match (&net_status.sync_state, best_seen_block, &state_download) {
+			(_, _, Some(_)) => "downloading_state",
+			(SyncState::Idle, _, _) => "idle",
+			(SyncState::Downloading, None, _) => "preparing",
+			(SyncState::Downloading, Some(_), None) => "syncing",
+		};
 		let (level, status, target) = match (
 			net_status.sync_state,
 			net_status.best_seen_block,
@@ -143,6 +174,39 @@ impl<B: BlockT> InformantDisplay<B> {
 				TransferRateFormat(avg_bytes_per_sec_outbound),
 			)
 		}
+
+		if let Some(json) = &mut self.json {
+			let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)
+				.map_or(0, |duration| duration.as_millis() as u64);
+			let status = json!({
+				"timestamp": timestamp,
+				"sync_state": sync_state,
+				"target": best_seen_block.map(|n| n.saturated_into::<u64>()),
+				"state_download": state_download,
+				"best": {
+					"number": best_number.saturated_into::<u64>(),
+					"hash": format!("{:?}", best_hash),
+				},
+				"finalized": {
+					"number": finalized_number.saturated_into::<u64>(),
+					"hash": format!("{:?}", info.chain.finalized_hash),
+				},
+				"peers": num_connected_peers,
+				"bandwidth": {
+					"inbound": avg_bytes_per_sec_inbound,
+					"outbound": avg_bytes_per_sec_outbound,
+				},
+				"import_queue": num_queued_blocks,
+				"txpool": {
+					"ready": pool_status.ready,
+					"future": pool_status.future,
+				},
+			});
+			if let Err(e) = writeln!(json, "{}", status).and_then(|()| json.flush()) {
+				warn!("Failed to write the informant JSON output: {}", e);
+				self.json = None;
+			}
+		}
 	}
 }
 
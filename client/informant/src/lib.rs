@@ -28,7 +28,9 @@ use sc_network::NetworkService;
 use sp_blockchain::HeaderMetadata;
 use sp_runtime::traits::{Block as BlockT, Header};
 use sc_transaction_pool_api::TransactionPool;
-use std::{fmt::Display, sync::Arc, time::Duration, collections::VecDeque};
+use std::{
+	collections::VecDeque, fmt::Display, io::Write, path::PathBuf, sync::Arc, time::Duration,
+};
 
 mod display;
 
@@ -44,16 +46,54 @@ pub struct OutputFormat {
 	///
 	/// Is enabled by default.
 	pub enable_color: bool,
+	/// Also write the status as newline-delimited JSON, for orchestration tools.
+	///
+	/// Each line is an object with the fields:
+	///
+	/// - `timestamp`: milliseconds since the UNIX epoch.
+	/// - `sync_state`: one of `idle`, `preparing`, `syncing` and `downloading_state`.
+	/// - `target`: number of the best block seen on the network, or `null`.
+	/// - `state_download`: `percentage` and `size` in bytes of the state being downloaded, or
+	///   `null`.
+	/// - `best` and `finalized`: `number` and `hash` of the best and last finalized blocks.
+	/// - `peers`: number of connected peers.
+	/// - `bandwidth`: `inbound` and `outbound` bytes per second.
+	/// - `import_queue`: number of blocks waiting to be imported.
+	/// - `txpool`: number of `ready` and `future` transactions in the pool.
+	///
+	/// Is disabled by default.
+	pub json: Option<JsonOutput>,
 }
 
 impl Default for OutputFormat {
 	fn default() -> Self {
 		Self {
 			enable_color: true,
+			json: None,
 		}
 	}
 }
 
+/// Destination of the JSON status output.
+#[derive(Clone, Debug)]
+pub enum JsonOutput {
+	/// Write to the standard output.
+	Stdout,
+	/// Append to the file at the given path.
+	File(PathBuf),
+}
+
+impl JsonOutput {
+	fn open(&self) -> std::io::Result<Box<dyn Write + Send>> {
+		Ok(match self {
+			JsonOutput::Stdout => Box::new(std::io::stdout()),
+			JsonOutput::File(path) => Box::new(
+				std::fs::OpenOptions::new().create(true).append(true).open(path)?,
+			),
+		})
+	}
+}
+
 /// Marker trait for a type that implements `TransactionPool` and `MallocSizeOf` on `not(target_os = "unknown")`.
 #[cfg(target_os = "unknown")]
 pub trait TransactionPoolAndMaybeMallogSizeOf: TransactionPool {}
@@ -104,7 +144,7 @@ where
 				"Subsystems memory [txpool: {} kB]",
 				parity_util_mem::malloc_size(&*pool) / 1024,
 			);
-			display.display(&info, net_status);
+			display.display(&info, net_status, pool.status());
 			future::ready(())
 		});
 
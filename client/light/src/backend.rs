@@ -26,7 +26,7 @@ use parking_lot::RwLock;
 use codec::{Decode, Encode};
 
 use sp_core::ChangesTrieConfiguration;
-use sp_core::storage::{well_known_keys, ChildInfo};
+use sp_core::storage::{well_known_keys, ChildInfo, StateVersion};
 use sp_core::offchain::storage::InMemOffchainStorage;
 use sp_state_machine::{
 	Backend as StateBackend, TrieBackend, InMemoryBackend, ChangesTrieTransaction,
@@ -322,7 +322,12 @@ impl<S, Block> BlockImportOperation<Block> for ImportOperation<Block, S>
 		Ok(())
 	}
 
-	fn set_genesis_state(&mut self, input: Storage, commit: bool) -> ClientResult<Block::Hash> {
+	fn set_genesis_state(
+		&mut self,
+		input: Storage,
+		commit: bool,
+		state_version: StateVersion,
+	) -> ClientResult<Block::Hash> {
 		check_genesis_storage(&input)?;
 
 		// changes trie configuration
@@ -346,8 +351,12 @@ impl<S, Block> BlockImportOperation<Block> for ImportOperation<Block, S>
 			storage.insert(Some(storage_child.child_info), storage_child.data);
 		}
 
-		let storage_update = InMemoryBackend::from(storage);
-		let (storage_root, _) = storage_update.full_storage_root(std::iter::empty(), child_delta);
+		let storage_update = InMemoryBackend::from((storage, state_version));
+		let (storage_root, _) = storage_update.full_storage_root(
+			std::iter::empty(),
+			child_delta,
+			state_version,
+		);
 		if commit {
 			self.storage_update = Some(storage_update);
 		}
@@ -355,7 +364,11 @@ impl<S, Block> BlockImportOperation<Block> for ImportOperation<Block, S>
 		Ok(storage_root)
 	}
 
-	fn reset_storage(&mut self, _input: Storage) -> ClientResult<Block::Hash> {
+	fn reset_storage(
+		&mut self,
+		_input: Storage,
+		_state_version: StateVersion,
+	) -> ClientResult<Block::Hash> {
 		Err(ClientError::NotAvailableOnLightClient)
 	}
 
@@ -513,10 +526,11 @@ impl<H: Hasher> StateBackend<H> for GenesisOrUnavailableState<H>
 	fn storage_root<'a>(
 		&self,
 		delta: impl Iterator<Item=(&'a [u8], Option<&'a [u8]>)>,
+		state_version: StateVersion,
 	) -> (H::Out, Self::Transaction) where H::Out: Ord {
 		match *self {
 			GenesisOrUnavailableState::Genesis(ref state) =>
-				state.storage_root(delta),
+				state.storage_root(delta, state_version),
 			GenesisOrUnavailableState::Unavailable => Default::default(),
 		}
 	}
@@ -525,10 +539,11 @@ impl<H: Hasher> StateBackend<H> for GenesisOrUnavailableState<H>
 		&self,
 		child_info: &ChildInfo,
 		delta: impl Iterator<Item=(&'a [u8], Option<&'a [u8]>)>,
+		state_version: StateVersion,
 	) -> (H::Out, bool, Self::Transaction) where H::Out: Ord {
 		match *self {
 			GenesisOrUnavailableState::Genesis(ref state) => {
-				let (root, is_equal, _) = state.child_storage_root(child_info, delta);
+				let (root, is_equal, _) = state.child_storage_root(child_info, delta, state_version);
 				(root, is_equal, Default::default())
 			},
 			GenesisOrUnavailableState::Unavailable =>
@@ -71,6 +71,20 @@ impl<B, L: Clone> Clone for GenesisCallExecutor<B, L> {
 	}
 }
 
+impl<B, L> sp_core::traits::ReadRuntimeVersion for GenesisCallExecutor<B, L>
+where
+	B: Send + Sync,
+	L: sp_core::traits::ReadRuntimeVersion,
+{
+	fn read_runtime_version(
+		&self,
+		wasm_code: &[u8],
+		ext: &mut dyn sp_externalities::Externalities,
+	) -> Result<Vec<u8>, String> {
+		self.local.read_runtime_version(wasm_code, ext)
+	}
+}
+
 impl<Block, B, Local> CallExecutor<Block> for
 	GenesisCallExecutor<B, Local>
 	where
@@ -0,0 +1,305 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! MMR offchain gadget.
+//!
+//! The MMR pallet adds the MMR nodes to the Off-chain DB (using the offchain indexing API)
+//! under fork-specific keys, derived from the parent hash of the block that added them.
+//! This gadget listens to finality notifications and:
+//! 1. moves the nodes added by newly finalized blocks under their canonical keys,
+//! 2. removes the nodes added by blocks of stale forks.
+//!
+//! The gadget requires offchain indexing to be enabled, it is a no-op otherwise.
+
+#![warn(missing_docs)]
+
+use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+
+use codec::{Decode, Encode};
+use futures::StreamExt;
+use log::{debug, error, info, trace, warn};
+
+use pallet_mmr_primitives::{utils, MmrApi};
+use sc_client_api::{Backend, BlockchainEvents, FinalityNotification};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::{Backend as _, HeaderBackend, HeaderMetadata};
+use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{
+		Block as BlockT, Header as HeaderT, NumberFor, One, Saturating, UniqueSaturatedInto, Zero,
+	},
+};
+
+/// Logging target for the MMR gadget.
+pub const LOG_TARGET: &str = "mmr";
+
+/// The MMR offchain gadget.
+///
+/// See the [crate-level documentation](crate) for details.
+pub struct MmrGadget<B, BE, C, MmrHash> {
+	client: Arc<C>,
+	backend: Arc<BE>,
+	indexing_prefix: Vec<u8>,
+	_phantom: PhantomData<(B, MmrHash)>,
+}
+
+impl<B, BE, C, MmrHash> MmrGadget<B, BE, C, MmrHash> where
+	B: BlockT,
+	BE: Backend<B>,
+	C: BlockchainEvents<B>
+		+ HeaderBackend<B>
+		+ HeaderMetadata<B, Error = sp_blockchain::Error>
+		+ ProvideRuntimeApi<B>,
+	C::Api: MmrApi<B, MmrHash>,
+	MmrHash: codec::Codec,
+{
+	/// Create a new gadget.
+	///
+	/// The `indexing_prefix` must match the `INDEXING_PREFIX` of the MMR pallet in the runtime.
+	pub fn new(client: Arc<C>, backend: Arc<BE>, indexing_prefix: Vec<u8>) -> Self {
+		Self { client, backend, indexing_prefix, _phantom: Default::default() }
+	}
+
+	/// Run the gadget until the finality notification stream ends.
+	pub async fn run(self) {
+		let offchain_db = match self.backend.offchain_storage() {
+			Some(offchain_db) => offchain_db,
+			None => {
+				warn!(target: LOG_TARGET, "Offchain storage is not available, MMR gadget is disabled.");
+				return
+			}
+		};
+
+		let mut offchain_mmr = OffchainMmr::<B, BE, C, MmrHash> {
+			client: self.client.clone(),
+			backend: self.backend.clone(),
+			offchain_db,
+			indexing_prefix: self.indexing_prefix,
+			pruned_blocks: HashSet::new(),
+			_phantom: Default::default(),
+		};
+
+		let mut finality_notifications = self.client.finality_notification_stream();
+		while let Some(notification) = finality_notifications.next().await {
+			offchain_mmr.canonicalize_and_prune(&notification);
+		}
+	}
+}
+
+/// Offchain MMR storage manipulated by the gadget.
+struct OffchainMmr<B: BlockT, BE: Backend<B>, C, MmrHash> {
+	client: Arc<C>,
+	backend: Arc<BE>,
+	offchain_db: BE::OffchainStorage,
+	indexing_prefix: Vec<u8>,
+	/// Blocks of the stale forks whose nodes have already been removed.
+	pruned_blocks: HashSet<B::Hash>,
+	_phantom: PhantomData<MmrHash>,
+}
+
+impl<B, BE, C, MmrHash> OffchainMmr<B, BE, C, MmrHash> where
+	B: BlockT,
+	BE: Backend<B>,
+	C: HeaderBackend<B> + HeaderMetadata<B, Error = sp_blockchain::Error> + ProvideRuntimeApi<B>,
+	C::Api: MmrApi<B, MmrHash>,
+	MmrHash: codec::Codec,
+{
+	/// Key under which the hash of the last canonicalized block is kept.
+	fn canon_head_key(&self) -> Vec<u8> {
+		(&self.indexing_prefix, b"canon-head").encode()
+	}
+
+	fn canon_head(&self) -> Option<B::Hash> {
+		self.offchain_db
+			.get(STORAGE_PREFIX, &self.canon_head_key())
+			.and_then(|encoded| Decode::decode(&mut &*encoded).ok())
+			.or_else(|| self.client.hash(Zero::zero()).ok().flatten())
+	}
+
+	/// Return the number of the block which added the first MMR leaf, as seen from the
+	/// finalized block `at`.
+	///
+	/// The MMR pallet adds exactly one leaf per block, so the leaf index of any block
+	/// can be derived from its number.
+	fn first_mmr_block_number(&self, at: &B::Hash, number: NumberFor<B>) -> Option<NumberFor<B>> {
+		let at = BlockId::hash(*at);
+		let api = self.client.runtime_api();
+		match api.has_api_with::<dyn MmrApi<B, MmrHash>, _>(&at, |version| version >= 2) {
+			Ok(true) => (),
+			Ok(false) => {
+				trace!(target: LOG_TARGET, "MMR runtime API not available at {:?}.", at);
+				return None
+			},
+			Err(e) => {
+				error!(target: LOG_TARGET, "Error checking MMR runtime API at {:?}: {:?}", at, e);
+				return None
+			},
+		}
+
+		match api.mmr_leaf_count(&at) {
+			Ok(Ok(leaf_count)) if leaf_count > 0 => {
+				let leaf_count: NumberFor<B> = leaf_count.unique_saturated_into();
+				Some(number.saturating_sub(leaf_count).saturating_add(One::one()))
+			},
+			Ok(Ok(_)) => None,
+			e => {
+				error!(target: LOG_TARGET, "Error getting MMR leaf count at {:?}: {:?}", at, e);
+				None
+			},
+		}
+	}
+
+	/// Return the index of the MMR leaf added by the block with given number.
+	fn leaf_index(number: NumberFor<B>, first_mmr_block: NumberFor<B>) -> Option<u64> {
+		if number < first_mmr_block {
+			return None
+		}
+		Some(number.saturating_sub(first_mmr_block).unique_saturated_into())
+	}
+
+	/// Move the nodes added by the block with given number and parent to the canonical keys.
+	fn canonicalize_block(
+		&mut self,
+		number: NumberFor<B>,
+		parent_hash: B::Hash,
+		first_mmr_block: NumberFor<B>,
+	) {
+		let leaf_index = match Self::leaf_index(number, first_mmr_block) {
+			Some(leaf_index) => leaf_index,
+			None => return,
+		};
+
+		for pos in utils::NodesUtils::nodes_added_by_leaf(leaf_index) {
+			let temp_key = utils::node_temp_offchain_key(&self.indexing_prefix, pos, parent_hash);
+			let canon_key = utils::node_canon_offchain_key(&self.indexing_prefix, pos);
+			match self.offchain_db.get(STORAGE_PREFIX, &temp_key) {
+				Some(elem) => {
+					self.offchain_db.set(STORAGE_PREFIX, &canon_key, &elem);
+					self.offchain_db.remove(STORAGE_PREFIX, &temp_key);
+					trace!(
+						target: LOG_TARGET,
+						"Moved MMR node at pos {} of block {:?} to canonical key.",
+						pos, number,
+					);
+				},
+				None => debug!(
+					target: LOG_TARGET,
+					"MMR node at pos {} of block {:?} not found in the offchain db.",
+					pos, number,
+				),
+			}
+		}
+	}
+
+	/// Remove the nodes added by the block with given number and parent.
+	fn prune_block(
+		&mut self,
+		number: NumberFor<B>,
+		parent_hash: B::Hash,
+		first_mmr_block: NumberFor<B>,
+	) {
+		let leaf_index = match Self::leaf_index(number, first_mmr_block) {
+			Some(leaf_index) => leaf_index,
+			None => return,
+		};
+
+		for pos in utils::NodesUtils::nodes_added_by_leaf(leaf_index) {
+			let temp_key = utils::node_temp_offchain_key(&self.indexing_prefix, pos, parent_hash);
+			self.offchain_db.remove(STORAGE_PREFIX, &temp_key);
+		}
+	}
+
+	/// Canonicalize the nodes of all blocks finalized since the last notification
+	/// and prune the nodes of the forks that became stale.
+	fn canonicalize_and_prune(&mut self, notification: &FinalityNotification<B>) {
+		let finalized = notification.hash;
+		let finalized_number = *notification.header.number();
+		let first_mmr_block = match self.first_mmr_block_number(&finalized, finalized_number) {
+			Some(first_mmr_block) => first_mmr_block,
+			None => return,
+		};
+
+		// Canonicalize.
+		let canon_head = match self.canon_head() {
+			Some(canon_head) => canon_head,
+			None => return,
+		};
+		match sp_blockchain::tree_route(&*self.client, canon_head, finalized) {
+			Ok(route) => {
+				let mut parent_hash = route.common_block().hash;
+				for block in route.enacted() {
+					self.canonicalize_block(block.number, parent_hash, first_mmr_block);
+					parent_hash = block.hash;
+				}
+				self.offchain_db.set(STORAGE_PREFIX, &self.canon_head_key(), &finalized.encode());
+				info!(
+					target: LOG_TARGET,
+					"Canonicalized MMR nodes of {} finalized blocks, up to #{:?} ({:?}).",
+					route.enacted().len(), finalized_number, finalized,
+				);
+			},
+			Err(e) => {
+				error!(
+					target: LOG_TARGET,
+					"Failed to canonicalize MMR nodes from {:?} to {:?}: {:?}",
+					canon_head, finalized, e,
+				);
+				return
+			},
+		}
+
+		// Prune stale forks.
+		//
+		// A stale fork always starts below the finalized block, so its first block shares
+		// the temporary keys with an already canonicalized block, whose nodes are safe under
+		// the canonical keys by now.
+		let leaves = match self.backend.blockchain().leaves() {
+			Ok(leaves) => leaves,
+			Err(e) => {
+				error!(target: LOG_TARGET, "Failed to get the block tree leaves: {:?}", e);
+				return
+			},
+		};
+		let mut stale_blocks = HashSet::new();
+		for leaf in leaves {
+			let route = match sp_blockchain::tree_route(&*self.client, finalized, leaf) {
+				Ok(route) => route,
+				Err(e) => {
+					debug!(target: LOG_TARGET, "Failed to get tree route to {:?}: {:?}", leaf, e);
+					continue
+				},
+			};
+			// Descendants of the finalized block are not stale.
+			if route.retracted().is_empty() {
+				continue
+			}
+
+			let mut parent_hash = route.common_block().hash;
+			for block in route.enacted() {
+				if !self.pruned_blocks.contains(&block.hash) {
+					self.prune_block(block.number, parent_hash, first_mmr_block);
+				}
+				stale_blocks.insert(block.hash);
+				parent_hash = block.hash;
+			}
+		}
+		// Only remember the blocks that are still part of the tree.
+		self.pruned_blocks = stale_blocks;
+	}
+}
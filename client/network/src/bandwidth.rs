@@ -0,0 +1,324 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Per-protocol bandwidth accounting and rate limiting.
+//!
+//! The transport layer only knows about the total number of bytes going through the sockets.
+//! The [`ProtocolBandwidth`] struct defined in this module is fed by the notifications handler
+//! and by the request-response behaviour with the size of each notification, request and
+//! response, and keeps a counter per protocol and per direction.
+//!
+//! Additionally, a [`RateLimit`] can be configured for the inbound traffic of any protocol. Once
+//! the limit of a protocol is exceeded, inbound notifications are discarded and inbound requests
+//! are left unanswered, so that a flood on one protocol can't starve the others.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, collections::HashMap, convert::TryFrom, fmt, str::FromStr};
+use wasm_timer::Instant;
+
+/// Direction of some traffic, from the point of view of the local node.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+	/// Data received from a remote.
+	Inbound,
+	/// Data sent to a remote.
+	Outbound,
+}
+
+impl Direction {
+	/// Returns the label used for this direction in the Prometheus metrics.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Direction::Inbound => "in",
+			Direction::Outbound => "out",
+		}
+	}
+}
+
+/// Limit of the inbound bandwidth of a protocol.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+	/// Average number of bytes per second that are accepted.
+	pub bytes_per_second: u64,
+	/// Maximum number of bytes that can be accepted in a burst. Values lower than
+	/// `bytes_per_second` are treated as `bytes_per_second`.
+	pub burst: u64,
+}
+
+impl RateLimit {
+	/// Builds a new [`RateLimit`] whose burst is equal to one second worth of traffic.
+	pub fn per_second(bytes_per_second: u64) -> Self {
+		RateLimit { bytes_per_second, burst: bytes_per_second }
+	}
+
+	fn capacity(&self) -> u64 {
+		self.burst.max(self.bytes_per_second)
+	}
+}
+
+impl fmt::Display for RateLimit {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.burst > self.bytes_per_second {
+			write!(f, "{}/{}", self.bytes_per_second, self.burst)
+		} else {
+			write!(f, "{}", self.bytes_per_second)
+		}
+	}
+}
+
+/// Error when parsing a [`RateLimit`].
+#[derive(Debug, derive_more::Display, derive_more::Error)]
+#[display(fmt = "Invalid rate limit, expected `BYTES_PER_SECOND[/BURST]`")]
+pub struct ParseRateLimitError;
+
+impl FromStr for RateLimit {
+	type Err = ParseRateLimitError;
+
+	/// Parses a string of the form `BYTES_PER_SECOND` or `BYTES_PER_SECOND/BURST`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = s.splitn(2, '/');
+		let bytes_per_second = parts.next()
+			.ok_or(ParseRateLimitError)?
+			.trim()
+			.parse::<u64>()
+			.map_err(|_| ParseRateLimitError)?;
+		let burst = match parts.next() {
+			Some(burst) => burst.trim().parse::<u64>().map_err(|_| ParseRateLimitError)?,
+			None => bytes_per_second,
+		};
+
+		if bytes_per_second == 0 {
+			return Err(ParseRateLimitError)
+		}
+
+		Ok(RateLimit { bytes_per_second, burst })
+	}
+}
+
+/// Bandwidth statistics of a single protocol.
+///
+/// **Warning**: This API is not stable.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolBandwidthInfo {
+	/// Total number of bytes received on this protocol.
+	pub bytes_inbound: u64,
+	/// Total number of bytes sent on this protocol.
+	pub bytes_outbound: u64,
+	/// Total number of inbound bytes that have been discarded because of the rate limit.
+	pub bytes_rate_limited: u64,
+	/// Configured inbound rate limit, if any, formatted as `BYTES_PER_SECOND[/BURST]`.
+	pub rate_limit: Option<String>,
+}
+
+/// Token bucket used to enforce a [`RateLimit`].
+#[derive(Debug)]
+struct TokenBucket {
+	limit: RateLimit,
+	/// Number of bytes that can currently be accepted.
+	available: u64,
+	/// Last time `available` has been refilled.
+	last_refill: Instant,
+}
+
+impl TokenBucket {
+	fn new(limit: RateLimit, now: Instant) -> Self {
+		TokenBucket { limit, available: limit.capacity(), last_refill: now }
+	}
+
+	/// Refills the bucket according to the time elapsed since the last refill, then tries to
+	/// take `bytes` out of it. Returns `false` if there isn't enough available.
+	fn try_consume(&mut self, bytes: u64, now: Instant) -> bool {
+		let elapsed = if now > self.last_refill {
+			now.duration_since(self.last_refill)
+		} else {
+			Default::default()
+		};
+		let refill = (elapsed.as_micros() * u128::from(self.limit.bytes_per_second)) / 1_000_000;
+		if refill > 0 {
+			let refill = u64::try_from(refill).unwrap_or(u64::MAX);
+			self.available = self.available.saturating_add(refill).min(self.limit.capacity());
+			self.last_refill = now;
+		}
+
+		if bytes <= self.available {
+			self.available -= bytes;
+			true
+		} else {
+			false
+		}
+	}
+}
+
+#[derive(Debug, Default)]
+struct ProtocolEntry {
+	info: ProtocolBandwidthInfo,
+	limiter: Option<TokenBucket>,
+}
+
+/// Per-protocol bandwidth counters, shared between the various components of the networking.
+#[derive(Debug)]
+pub struct ProtocolBandwidth {
+	protocols: Mutex<HashMap<Cow<'static, str>, ProtocolEntry>>,
+}
+
+impl ProtocolBandwidth {
+	/// Builds a new [`ProtocolBandwidth`] with the given inbound rate limits.
+	pub fn new(limits: impl IntoIterator<Item = (Cow<'static, str>, RateLimit)>) -> Self {
+		let now = Instant::now();
+		let protocols = limits.into_iter()
+			.map(|(protocol, limit)| {
+				let entry = ProtocolEntry {
+					info: ProtocolBandwidthInfo {
+						rate_limit: Some(limit.to_string()),
+						.. Default::default()
+					},
+					limiter: Some(TokenBucket::new(limit, now)),
+				};
+				(protocol, entry)
+			})
+			.collect();
+
+		ProtocolBandwidth { protocols: Mutex::new(protocols) }
+	}
+
+	/// Records `bytes` of traffic on `protocol` without applying any rate limit.
+	///
+	/// Used for outbound traffic, and for responses to requests that the local node has emitted.
+	pub fn report(&self, protocol: &str, direction: Direction, bytes: usize) {
+		let bytes = u64::try_from(bytes).unwrap_or(u64::MAX);
+		let mut protocols = self.protocols.lock();
+		let info = &mut Self::entry(&mut protocols, protocol).info;
+		match direction {
+			Direction::Inbound => info.bytes_inbound = info.bytes_inbound.saturating_add(bytes),
+			Direction::Outbound => info.bytes_outbound = info.bytes_outbound.saturating_add(bytes),
+		}
+	}
+
+	/// Records `bytes` of inbound traffic on `protocol` and checks them against the rate limit
+	/// of this protocol.
+	///
+	/// Returns `false` if the limit has been exceeded, in which case the caller is expected to
+	/// discard the data.
+	pub fn check_inbound(&self, protocol: &str, bytes: usize) -> bool {
+		self.check_inbound_at(protocol, bytes, Instant::now())
+	}
+
+	fn check_inbound_at(&self, protocol: &str, bytes: usize, now: Instant) -> bool {
+		let bytes = u64::try_from(bytes).unwrap_or(u64::MAX);
+		let mut protocols = self.protocols.lock();
+		let entry = Self::entry(&mut protocols, protocol);
+		entry.info.bytes_inbound = entry.info.bytes_inbound.saturating_add(bytes);
+
+		let accepted = entry.limiter.as_mut().map_or(true, |l| l.try_consume(bytes, now));
+		if !accepted {
+			entry.info.bytes_rate_limited = entry.info.bytes_rate_limited.saturating_add(bytes);
+		}
+		accepted
+	}
+
+	/// Returns the statistics of all the protocols that have seen some traffic or that have a
+	/// rate limit configured.
+	pub fn info(&self) -> HashMap<String, ProtocolBandwidthInfo> {
+		self.protocols.lock()
+			.iter()
+			.map(|(protocol, entry)| (protocol.to_string(), entry.info.clone()))
+			.collect()
+	}
+
+	fn entry<'a>(
+		protocols: &'a mut HashMap<Cow<'static, str>, ProtocolEntry>,
+		protocol: &str,
+	) -> &'a mut ProtocolEntry {
+		if !protocols.contains_key(protocol) {
+			protocols.insert(Cow::Owned(protocol.to_owned()), ProtocolEntry::default());
+		}
+		protocols.get_mut(protocol).expect("Entry inserted above if missing; qed")
+	}
+}
+
+impl Default for ProtocolBandwidth {
+	fn default() -> Self {
+		ProtocolBandwidth::new(None)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	const PROTOCOL: &str = "/test/proto/1";
+
+	#[test]
+	fn rate_limit_parsing() {
+		assert_eq!("1024".parse::<RateLimit>().unwrap(), RateLimit::per_second(1024));
+		assert_eq!(
+			"1024/4096".parse::<RateLimit>().unwrap(),
+			RateLimit { bytes_per_second: 1024, burst: 4096 },
+		);
+		assert!("".parse::<RateLimit>().is_err());
+		assert!("0".parse::<RateLimit>().is_err());
+		assert!("12/abc".parse::<RateLimit>().is_err());
+	}
+
+	#[test]
+	fn counts_per_protocol_and_direction() {
+		let bandwidth = ProtocolBandwidth::default();
+		bandwidth.report(PROTOCOL, Direction::Outbound, 10);
+		bandwidth.report(PROTOCOL, Direction::Inbound, 5);
+		assert!(bandwidth.check_inbound(PROTOCOL, 7));
+		bandwidth.report("/other/1", Direction::Outbound, 1);
+
+		let info = bandwidth.info();
+		assert_eq!(info.len(), 2);
+		assert_eq!(info[PROTOCOL], ProtocolBandwidthInfo {
+			bytes_inbound: 12,
+			bytes_outbound: 10,
+			bytes_rate_limited: 0,
+			rate_limit: None,
+		});
+		assert_eq!(info["/other/1"].bytes_outbound, 1);
+	}
+
+	#[test]
+	fn inbound_limit_is_enforced_and_refilled() {
+		let bandwidth = ProtocolBandwidth::new(
+			vec![(Cow::Borrowed(PROTOCOL), RateLimit { bytes_per_second: 100, burst: 200 })]
+		);
+		let start = Instant::now();
+
+		assert!(bandwidth.check_inbound_at(PROTOCOL, 150, start));
+		assert!(!bandwidth.check_inbound_at(PROTOCOL, 100, start));
+		assert!(bandwidth.check_inbound_at(PROTOCOL, 50, start));
+
+		// Half a second later, 50 bytes are available again.
+		let later = start + Duration::from_millis(500);
+		assert!(!bandwidth.check_inbound_at(PROTOCOL, 60, later));
+		assert!(bandwidth.check_inbound_at(PROTOCOL, 50, later));
+
+		// Other protocols aren't affected.
+		assert!(bandwidth.check_inbound_at("/other/1", 1_000_000, later));
+
+		let info = &bandwidth.info()[PROTOCOL];
+		assert_eq!(info.bytes_inbound, 410);
+		assert_eq!(info.bytes_rate_limited, 160);
+		assert_eq!(info.rate_limit.as_deref(), Some("100/200"));
+	}
+}
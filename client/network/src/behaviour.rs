@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+	bandwidth::ProtocolBandwidth,
 	config::ProtocolId,
 	bitswap::Bitswap,
 	discovery::{DiscoveryBehaviour, DiscoveryConfig, DiscoveryOut},
@@ -28,7 +29,7 @@ use crate::{
 use bytes::Bytes;
 use futures::{channel::oneshot, stream::StreamExt};
 use libp2p::NetworkBehaviour;
-use libp2p::core::{Multiaddr, PeerId, PublicKey};
+use libp2p::core::{ConnectedPoint, Multiaddr, PeerId, PublicKey};
 use libp2p::identify::IdentifyInfo;
 use libp2p::kad::record;
 use libp2p::swarm::{
@@ -42,6 +43,7 @@ use std::{
 	borrow::Cow,
 	collections::{HashSet, VecDeque},
 	iter,
+	sync::Arc,
 	task::{Context, Poll},
 	time::Duration,
 };
@@ -196,6 +198,7 @@ impl<B: BlockT> Behaviour<B> {
 		light_client_request_protocol_config: request_responses::ProtocolConfig,
 		// All remaining request protocol configs.
 		mut request_response_protocols: Vec<request_responses::ProtocolConfig>,
+		protocol_bandwidth: Arc<ProtocolBandwidth>,
 	) -> Result<Self, request_responses::RegisterError> {
 		// Extract protocol name and add to `request_response_protocols`.
 		let block_request_protocol_name = block_request_protocol_config.name.to_string();
@@ -211,7 +214,10 @@ impl<B: BlockT> Behaviour<B> {
 			discovery: disco_config.finish(),
 			bitswap: bitswap.into(),
 			request_responses:
-				request_responses::RequestResponsesBehaviour::new(request_response_protocols.into_iter())?,
+				request_responses::RequestResponsesBehaviour::new(
+					request_response_protocols.into_iter(),
+					protocol_bandwidth,
+				)?,
 			light_client_request_sender,
 			events: VecDeque::new(),
 			block_request_protocol_name,
@@ -256,6 +262,11 @@ impl<B: BlockT> Behaviour<B> {
 		self.peer_info.node(peer_id)
 	}
 
+	/// Returns the endpoints of all the connections that are currently open.
+	pub fn connected_endpoints(&self) -> impl Iterator<Item = (&PeerId, &ConnectedPoint)> {
+		self.peer_info.connected_endpoints()
+	}
+
 	/// Initiates sending a request.
 	pub fn send_request(
 		&mut self,
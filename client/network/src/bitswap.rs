@@ -18,7 +18,12 @@
 //!
 //! Allows querying transactions by hash over standard bitswap protocol
 //! Only supports bitswap 1.2.0.
-//! CID is expected to reference 256-bit Blake2b transaction hash.
+//!
+//! CIDv1 are expected to reference blocks of the UnixFS DAG of indexed data by their sha2-256
+//! hash, as laid out by `sp_transaction_storage_proof::unixfs`. Indexed data is stored under the
+//! digest of its root block. When the root is requested, the DAG is rebuilt and its other blocks
+//! are remembered, along with the data itself, so that an IPFS node walking the DAG from the root
+//! can then fetch them.
 
 use std::collections::VecDeque;
 use std::io;
@@ -37,8 +42,11 @@ use libp2p::swarm::{
 	ProtocolsHandler, IntoProtocolsHandler, OneShotHandler,
 };
 use log::{error, debug, trace};
+use lru::LruCache;
 use prost::Message;
+use sp_core::hashing::sha2_256;
 use sp_runtime::traits::{Block as BlockT};
+use sp_transaction_storage_proof::unixfs;
 use unsigned_varint::{encode as varint_encode};
 use crate::chain::Client;
 use crate::schema::bitswap::{
@@ -58,6 +66,10 @@ const MAX_PACKET_SIZE: usize = 16 * 1024 * 1024;
 const MAX_RESPONSE_QUEUE: usize = 20;
 // Max number of blocks per wantlist
 const MAX_WANTED_BLOCKS: usize = 16;
+// Max number of remembered blocks of UnixFS DAGs.
+const MAX_DAG_BLOCKS: usize = 4096;
+// Max number of remembered indexed data that leaves of UnixFS DAGs are read from.
+const MAX_DAG_DATA: usize = 8;
 
 const PROTOCOL_NAME: &'static [u8] = b"/ipfs/bitswap/1.2.0";
 
@@ -173,10 +185,22 @@ impl Prefix {
 	}
 }
 
+/// A block of the UnixFS DAG of some indexed data.
+struct DagBlock<B: BlockT> {
+	/// Hash under which the data is indexed.
+	root: B::Hash,
+	/// Multicodec of the block.
+	codec: u64,
+	/// Content of the block.
+	content: unixfs::BlockContent,
+}
+
 /// Network behaviour that handles sending and receiving IPFS blocks.
-pub struct Bitswap<B> {
+pub struct Bitswap<B: BlockT> {
 	client: Arc<dyn Client<B>>,
 	ready_blocks: VecDeque<(PeerId, BitswapMessage)>,
+	dag_blocks: LruCache<B::Hash, DagBlock<B>>,
+	dag_data: LruCache<B::Hash, Arc<Vec<u8>>>,
 }
 
 impl<B: BlockT> Bitswap<B> {
@@ -185,7 +209,59 @@ impl<B: BlockT> Bitswap<B> {
 		Bitswap {
 			client,
 			ready_blocks: Default::default(),
+			dag_blocks: LruCache::new(MAX_DAG_BLOCKS),
+			dag_data: LruCache::new(MAX_DAG_DATA),
+		}
+	}
+
+	/// Get the non-empty data indexed under `root`, reading it from the client if it is not
+	/// remembered.
+	fn indexed_data(
+		&mut self,
+		root: &B::Hash,
+	) -> Result<Option<Arc<Vec<u8>>>, sp_blockchain::Error> {
+		if let Some(data) = self.dag_data.get(root) {
+			return Ok(Some(data.clone()))
+		}
+		let data = match self.client.indexed_transaction(root)? {
+			Some(data) if !data.is_empty() => Arc::new(data),
+			_ => return Ok(None),
+		};
+		self.dag_data.put(*root, data.clone());
+		Ok(Some(data))
+	}
+
+	/// Get the content of the block referenced by `cid`, which must be a supported CID.
+	fn block_data(&mut self, cid: &cid::Cid) -> Result<Option<Vec<u8>>, sp_blockchain::Error> {
+		let mut hash = B::Hash::default();
+		hash.as_mut().copy_from_slice(&cid.hash().digest()[0..32]);
+
+		if !self.dag_blocks.contains(&hash) {
+			// This may be the root of some indexed data, in which case its DAG is rebuilt.
+			let data = match self.indexed_data(&hash)? {
+				Some(data) => data,
+				None => return Ok(None),
+			};
+			for block in unixfs::build_dag(&data, sha2_256) {
+				let mut block_hash = B::Hash::default();
+				block_hash.as_mut().copy_from_slice(&block.digest);
+				self.dag_blocks.put(
+					block_hash,
+					DagBlock { root: hash, codec: block.codec, content: block.content },
+				);
+			}
 		}
+
+		let (root, range) = match self.dag_blocks.get(&hash) {
+			Some(block) if block.codec != cid.codec() => return Ok(None),
+			Some(DagBlock { content: unixfs::BlockContent::Node(node), .. }) =>
+				return Ok(Some(node.clone())),
+			Some(DagBlock { root, content: unixfs::BlockContent::Leaf { offset, len }, .. }) =>
+				(*root, *offset as usize .. (*offset + *len) as usize),
+			None => return Ok(None),
+		};
+		let data = self.indexed_data(&root)?;
+		Ok(data.and_then(|data| data.get(range).map(|leaf| leaf.to_vec())))
 	}
 }
 
@@ -248,24 +324,22 @@ impl<B: BlockT> NetworkBehaviour for Bitswap<B> {
 				}
 			};
 			if cid.version() != cid::Version::V1
-				|| cid.hash().code() != u64::from(cid::multihash::Code::Blake2b256)
+				|| cid.hash().code() != u64::from(cid::multihash::Code::Sha2_256)
 				|| cid.hash().size() != 32
 			{
 				debug!(target: LOG_TARGET, "Ignoring unsupported CID {}: {}", peer, cid);
 				continue
 			}
-			let mut hash = B::Hash::default();
-			hash.as_mut().copy_from_slice(&cid.hash().digest()[0..32]);
-			let transaction = match self.client.indexed_transaction(&hash) {
+			let transaction = match self.block_data(&cid) {
 				Ok(ex) => ex,
 				Err(e) => {
-					error!(target: LOG_TARGET, "Error retrieving transaction {}: {}", hash, e);
+					error!(target: LOG_TARGET, "Error retrieving block {}: {}", cid, e);
 					None
 				}
 			};
 			match transaction {
 				Some(transaction) => {
-					trace!(target: LOG_TARGET, "Found CID {:?}, hash {:?}", cid, hash);
+					trace!(target: LOG_TARGET, "Found CID {:?}", cid);
 					if entry.want_type == WantType::Block as i32 {
 						let prefix = Prefix {
 							version: cid.version(),
@@ -285,7 +359,7 @@ impl<B: BlockT> NetworkBehaviour for Bitswap<B> {
 					}
 				},
 				None => {
-					trace!(target: LOG_TARGET, "Missing CID {:?}, hash {:?}", cid, hash);
+					trace!(target: LOG_TARGET, "Missing CID {:?}", cid);
 					if entry.send_dont_have {
 						response.block_presences.push(BlockPresence {
 							r#type: BlockPresenceType::DontHave as i32,
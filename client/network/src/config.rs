@@ -21,8 +21,10 @@
 //! The [`Params`] struct is the struct that must be passed in order to initialize the networking.
 //! See the documentation of [`Params`].
 
+pub use crate::bandwidth::RateLimit;
 pub use crate::chain::Client;
 pub use crate::on_demand_layer::{AlwaysBadChecker, OnDemand};
+pub use crate::peer_store::PeerStore;
 pub use crate::request_responses::{
 	IncomingRequest,
 	OutgoingResponse,
@@ -132,6 +134,10 @@ pub struct Params<B: BlockT, H: ExHashT> {
 	/// [`crate::state_request_handler::StateRequestHandler::new`] allowing
 	/// both outgoing and incoming requests.
 	pub state_request_protocol_config: RequestResponseConfig,
+
+	/// Storage where the reputations of the nodes and the bans are saved, so that they survive a
+	/// restart of the node. If `None`, they are lost when the node stops.
+	pub peer_store: Option<Arc<dyn PeerStore>>,
 }
 
 /// Role of the local node.
@@ -466,6 +472,13 @@ pub struct NetworkConfiguration {
 	/// a modification of the way the implementation works. Different nodes with different
 	/// configured values remain compatible with each other.
 	pub yamux_window_size: Option<u32>,
+
+	/// Limits of the inbound bandwidth, indexed by the name of the notifications or
+	/// request-response protocol they apply to.
+	///
+	/// Inbound notifications and requests that exceed the limit of their protocol are discarded.
+	/// Protocols that don't appear in this list are not limited.
+	pub protocol_rate_limits: HashMap<Cow<'static, str>, RateLimit>,
 }
 
 impl NetworkConfiguration {
@@ -499,6 +512,7 @@ impl NetworkConfiguration {
 			kademlia_disjoint_query_paths: false,
 			yamux_window_size: None,
 			ipfs_server: false,
+			protocol_rate_limits: HashMap::new(),
 		}
 	}
 
@@ -143,6 +143,7 @@ fn build_test_full_node(network_config: config::NetworkConfiguration)
 		metrics_registry: None,
 		block_request_protocol_config,
 		state_request_protocol_config,
+		peer_store: None,
 		light_client_request_protocol_config,
 	})
 	.unwrap();
@@ -257,6 +257,7 @@ mod service;
 mod transport;
 mod utils;
 
+pub mod bandwidth;
 pub mod block_request_handler;
 pub mod bitswap;
 pub mod light_client_requests;
@@ -264,6 +265,7 @@ pub mod state_request_handler;
 pub mod config;
 pub mod error;
 pub mod network_state;
+pub mod peer_store;
 pub mod transactions;
 
 #[doc(inline)]
@@ -324,4 +326,6 @@ pub struct NetworkStatus<B: BlockT> {
 	pub total_bytes_outbound: u64,
 	/// State sync in progress.
 	pub state_sync: Option<protocol::sync::StateDownloadProgress>,
+	/// Number of blocks in the import queue.
+	pub num_queued_blocks: u32,
 }
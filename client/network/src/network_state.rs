@@ -20,6 +20,7 @@
 //!
 //! **Warning**: These APIs are not stable.
 
+use crate::bandwidth::ProtocolBandwidthInfo;
 use libp2p::{core::ConnectedPoint, Multiaddr};
 use serde::{Deserialize, Serialize};
 use std::{collections::{HashMap, HashSet}, time::Duration};
@@ -44,6 +45,9 @@ pub struct NetworkState {
 	pub not_connected_peers: HashMap<String, NotConnectedPeer>,
 	/// State of the peerset manager.
 	pub peerset: serde_json::Value,
+	/// Bandwidth usage, by notifications or request-response protocol name.
+	#[serde(default)]
+	pub protocol_bandwidth: HashMap<String, ProtocolBandwidthInfo>,
 }
 
 /// Part of the `NetworkState` struct. Unstable.
@@ -108,6 +108,12 @@ impl PeerInfoBehaviour {
 		self.nodes_info.get(peer_id).map(Node)
 	}
 
+	/// Returns the endpoints of all the connections that are currently open.
+	pub fn connected_endpoints(&self) -> impl Iterator<Item = (&PeerId, &ConnectedPoint)> {
+		self.nodes_info.iter()
+			.flat_map(|(peer_id, info)| info.endpoints.iter().map(move |endpoint| (peer_id, endpoint)))
+	}
+
 	/// Inserts a ping time in the cache. Has no effect if we don't have any entry for that node,
 	/// which shouldn't happen.
 	fn handle_ping_report(&mut self, peer_id: &PeerId, ping_time: Duration) {
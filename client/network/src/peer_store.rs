@@ -0,0 +1,199 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Bans and persistence of the reputations of the nodes across restarts.
+//!
+//! The node operator can ban either a single node, identified by its [`PeerId`], or a whole range
+//! of IP addresses. Bans of single nodes are handled by the peerset, while IP ranges are checked
+//! by the [`NetworkWorker`](crate::NetworkWorker) every time a connection is established.
+//!
+//! Both the bans and the reputations of the nodes are periodically written to a [`PeerStore`], and
+//! loaded back when the network starts.
+
+use ip_network::IpNetwork;
+use libp2p::{multiaddr::Protocol, Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+use std::{fmt, net::IpAddr, str::FromStr};
+use wasm_timer::{SystemTime, UNIX_EPOCH};
+
+/// Key under which the state of the networking is saved in the auxiliary storage.
+const PEER_STORE_KEY: &[u8] = b"sc_network_peer_store";
+
+/// Storage for the state of the networking that must survive a restart of the node.
+pub trait PeerStore: Send + Sync {
+	/// Loads the data previously passed to [`PeerStore::store`], if any.
+	fn load(&self) -> Option<Vec<u8>>;
+
+	/// Overwrites the stored data.
+	fn store(&self, data: &[u8]);
+}
+
+impl<T> PeerStore for T where T: sc_client_api::AuxStore + Send + Sync {
+	fn load(&self) -> Option<Vec<u8>> {
+		match self.get_aux(PEER_STORE_KEY) {
+			Ok(data) => data,
+			Err(err) => {
+				log::warn!(target: "sub-libp2p", "Failed to load the peer store: {}", err);
+				None
+			}
+		}
+	}
+
+	fn store(&self, data: &[u8]) {
+		if let Err(err) = self.insert_aux(&[(PEER_STORE_KEY, data)], &[]) {
+			log::warn!(target: "sub-libp2p", "Failed to save the peer store: {}", err);
+		}
+	}
+}
+
+/// State of the networking written to the [`PeerStore`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SavedState {
+	/// Reputations and bans of individual nodes.
+	pub peerset: sc_peerset::PeersetSnapshot,
+	/// Banned IP ranges, with the UNIX timestamp at which their ban expires.
+	pub banned_ranges: Vec<(String, Option<u64>)>,
+}
+
+impl SavedState {
+	/// Decodes the content of a [`PeerStore`]. Returns the default state if the data is
+	/// malformed, as losing reputations isn't a reason to refuse to start.
+	pub fn decode(data: &[u8]) -> Self {
+		serde_json::from_slice(data).unwrap_or_else(|err| {
+			log::warn!(target: "sub-libp2p", "Discarding malformed peer store: {}", err);
+			Default::default()
+		})
+	}
+
+	/// Encodes the state in order to write it to a [`PeerStore`].
+	pub fn encode(&self) -> Vec<u8> {
+		serde_json::to_vec(self).expect("Serializing a struct of strings and integers can't fail; qed")
+	}
+}
+
+/// Target of a ban.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BanTarget {
+	/// A single node.
+	Peer(PeerId),
+	/// All the nodes whose IP address belongs to this range.
+	IpRange(IpNetwork),
+}
+
+impl fmt::Display for BanTarget {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			BanTarget::Peer(peer_id) => write!(f, "{}", peer_id.to_base58()),
+			BanTarget::IpRange(range) => write!(f, "{}", range),
+		}
+	}
+}
+
+/// Error when parsing a [`BanTarget`].
+#[derive(Debug, derive_more::Display, derive_more::Error)]
+#[display(fmt = "Invalid ban target, expected a peer ID, an IP address or a CIDR range")]
+pub struct ParseBanTargetError;
+
+impl FromStr for BanTarget {
+	type Err = ParseBanTargetError;
+
+	/// Parses a base58-encoded [`PeerId`], an IP address such as `192.0.2.1`, or a range of IP
+	/// addresses such as `192.0.2.0/24`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		if let Ok(peer_id) = s.parse::<PeerId>() {
+			return Ok(BanTarget::Peer(peer_id))
+		}
+		if let Ok(ip) = s.parse::<IpAddr>() {
+			return Ok(BanTarget::IpRange(IpNetwork::from(ip)))
+		}
+		s.parse::<IpNetwork>()
+			.map(BanTarget::IpRange)
+			.map_err(|_| ParseBanTargetError)
+	}
+}
+
+/// Description of an active ban.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BanInfo {
+	/// Banned peer ID or IP range.
+	pub target: String,
+	/// UNIX timestamp, in seconds, at which the ban expires. `None` if the ban is permanent.
+	pub expires_at: Option<u64>,
+}
+
+/// Returns the current UNIX timestamp, in seconds.
+pub(crate) fn unix_time() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Extracts the IP address of a multiaddress, if any.
+pub(crate) fn multiaddr_ip(address: &Multiaddr) -> Option<IpAddr> {
+	address.iter().find_map(|protocol| match protocol {
+		Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+		Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+		_ => None,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ban_target_parsing() {
+		let peer_id = PeerId::random();
+		assert_eq!(peer_id.to_base58().parse::<BanTarget>().unwrap(), BanTarget::Peer(peer_id));
+
+		let single = "192.0.2.1".parse::<BanTarget>().unwrap();
+		assert_eq!(single.to_string(), "192.0.2.1/32");
+
+		let range = "192.0.2.0/24".parse::<BanTarget>().unwrap();
+		match range {
+			BanTarget::IpRange(range) => {
+				assert!(range.contains("192.0.2.200".parse::<IpAddr>().unwrap()));
+				assert!(!range.contains("192.0.3.1".parse::<IpAddr>().unwrap()));
+			},
+			_ => panic!("expected an IP range"),
+		}
+
+		assert!("2001:db8::/32".parse::<BanTarget>().is_ok());
+		assert!("not a target".parse::<BanTarget>().is_err());
+	}
+
+	#[test]
+	fn extracts_ip_of_multiaddr() {
+		let addr: Multiaddr = "/ip4/192.0.2.1/tcp/30333".parse().unwrap();
+		assert_eq!(multiaddr_ip(&addr), Some("192.0.2.1".parse().unwrap()));
+		let addr: Multiaddr = "/dns/example.com/tcp/30333".parse().unwrap();
+		assert_eq!(multiaddr_ip(&addr), None);
+	}
+
+	#[test]
+	fn saved_state_roundtrip() {
+		let state = SavedState {
+			peerset: Default::default(),
+			banned_ranges: vec![("192.0.2.0/24".into(), Some(10))],
+		};
+		let decoded = SavedState::decode(&state.encode());
+		assert_eq!(decoded.banned_ranges, state.banned_ranges);
+		assert!(SavedState::decode(b"garbage").banned_ranges.is_empty());
+	}
+}
@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+	bandwidth::ProtocolBandwidth,
 	chain::Client,
 	config::{self, ProtocolId},
 	error,
@@ -289,6 +290,7 @@ impl<B: BlockT> Protocol<B> {
 		notifications_protocols_handshakes: Vec<Vec<u8>>,
 		block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 		metrics_registry: Option<&Registry>,
+		bandwidth: Arc<ProtocolBandwidth>,
 	) -> error::Result<(Protocol<B>, sc_peerset::PeersetHandle, Vec<(PeerId, Multiaddr)>)> {
 		let info = chain.info();
 		let sync = ChainSync::new(
@@ -409,6 +411,7 @@ impl<B: BlockT> Protocol<B> {
 							max_notification_size: s.max_notification_size,
 						})
 					),
+				bandwidth,
 			)
 		};
 
@@ -473,6 +476,27 @@ impl<B: BlockT> Protocol<B> {
 		self.behaviour.peerset_debug_info()
 	}
 
+	/// Returns the reputations and bans of the peerset, in order to persist them.
+	pub fn peerset_snapshot(&mut self) -> sc_peerset::PeersetSnapshot {
+		self.behaviour.peerset_snapshot()
+	}
+
+	/// Restores the reputations and bans previously returned by [`Protocol::peerset_snapshot`].
+	pub fn restore_peerset(&mut self, snapshot: sc_peerset::PeersetSnapshot) {
+		self.behaviour.restore_peerset(snapshot)
+	}
+
+	/// Returns true if the given peer has been explicitly banned.
+	pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+		self.behaviour.is_banned(peer_id)
+	}
+
+	/// Returns the list of explicitly banned peers, with the UNIX timestamp at which their ban
+	/// expires.
+	pub fn banned_peers(&self) -> Vec<(PeerId, Option<u64>)> {
+		self.behaviour.banned_peers()
+	}
+
 	/// Returns the number of peers we're connected to.
 	pub fn num_connected_peers(&self) -> usize {
 		self.peers.values().count()
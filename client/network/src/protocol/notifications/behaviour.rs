@@ -16,6 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::bandwidth::ProtocolBandwidth;
 use crate::protocol::notifications::{
 	handler::{self, NotificationsSink, NotifsHandlerProto, NotifsHandlerOut, NotifsHandlerIn}
 };
@@ -98,6 +99,9 @@ pub struct Notifications {
 	/// Notification protocols. Entries never change after initialization.
 	notif_protocols: Vec<handler::ProtocolConfig>,
 
+	/// Per-protocol bandwidth counters and inbound rate limits, passed to each handler.
+	bandwidth: Arc<ProtocolBandwidth>,
+
 	/// Receiver for instructions about who to connect to or disconnect from.
 	peerset: sc_peerset::Peerset,
 
@@ -373,6 +377,7 @@ impl Notifications {
 	pub fn new(
 		peerset: sc_peerset::Peerset,
 		notif_protocols: impl Iterator<Item = ProtocolConfig>,
+		bandwidth: Arc<ProtocolBandwidth>,
 	) -> Self {
 		let notif_protocols = notif_protocols
 			.map(|cfg| handler::ProtocolConfig {
@@ -387,6 +392,7 @@ impl Notifications {
 
 		Notifications {
 			notif_protocols,
+			bandwidth,
 			peerset,
 			peers: FnvHashMap::default(),
 			delays: Default::default(),
@@ -624,6 +630,28 @@ impl Notifications {
 		self.peerset.debug_info()
 	}
 
+	/// Returns the reputations and bans of the peerset, in order to persist them.
+	pub fn peerset_snapshot(&mut self) -> sc_peerset::PeersetSnapshot {
+		self.peerset.snapshot()
+	}
+
+	/// Restores the reputations and bans previously returned by
+	/// [`Notifications::peerset_snapshot`].
+	pub fn restore_peerset(&mut self, snapshot: sc_peerset::PeersetSnapshot) {
+		self.peerset.restore(snapshot)
+	}
+
+	/// Returns true if the given peer has been explicitly banned.
+	pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+		self.peerset.is_banned(peer_id)
+	}
+
+	/// Returns the list of explicitly banned peers, with the UNIX timestamp at which their ban
+	/// expires.
+	pub fn banned_peers(&self) -> Vec<(PeerId, Option<u64>)> {
+		self.peerset.banned_peers().map(|(peer_id, until)| (peer_id.clone(), until)).collect()
+	}
+
 	/// Function that is called when the peerset wants us to connect to a peer.
 	fn peerset_report_connect(&mut self, peer_id: PeerId, set_id: sc_peerset::SetId) {
 		// If `PeerId` is unknown to us, insert an entry, start dialing, and return early.
@@ -1042,7 +1070,7 @@ impl NetworkBehaviour for Notifications {
 	type OutEvent = NotificationsOut;
 
 	fn new_handler(&mut self) -> Self::ProtocolsHandler {
-		NotifsHandlerProto::new(self.notif_protocols.clone())
+		NotifsHandlerProto::new(self.notif_protocols.clone(), self.bandwidth.clone())
 	}
 
 	fn addresses_of_peer(&mut self, _: &PeerId) -> Vec<Multiaddr> {
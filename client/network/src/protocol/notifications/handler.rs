@@ -57,6 +57,7 @@
 //! It is illegal to send a [`NotifsHandlerIn::Open`] before a previously-emitted
 //! [`NotifsHandlerIn::Open`] has gotten an answer.
 
+use crate::bandwidth::{Direction, ProtocolBandwidth};
 use crate::protocol::notifications::{
 	upgrade::{
 		NotificationsIn, NotificationsOut, NotificationsInSubstream, NotificationsOutSubstream,
@@ -111,6 +112,9 @@ pub struct NotifsHandlerProto {
 	/// Name of protocols, prototypes for upgrades for inbound substreams, and the message we
 	/// send or respond with in the handshake.
 	protocols: Vec<ProtocolConfig>,
+
+	/// Per-protocol bandwidth counters and inbound rate limits, shared between all connections.
+	bandwidth: Arc<ProtocolBandwidth>,
 }
 
 /// The actual handler once the connection has been established.
@@ -129,6 +133,9 @@ pub struct NotifsHandler {
 	/// Remote we are connected to.
 	peer_id: PeerId,
 
+	/// Per-protocol bandwidth counters and inbound rate limits, shared between all connections.
+	bandwidth: Arc<ProtocolBandwidth>,
+
 	/// Events to return in priority from `poll`.
 	events_queue: VecDeque<
 		ProtocolsHandlerEvent<NotificationsOut, usize, NotifsHandlerOut, NotifsHandlerError>
@@ -243,6 +250,7 @@ impl IntoProtocolsHandler for NotifsHandlerProto {
 				}
 			}).collect(),
 			peer_id: peer_id.clone(),
+			bandwidth: self.bandwidth,
 			endpoint: connected_point.clone(),
 			when_connection_open: Instant::now(),
 			events_queue: VecDeque::with_capacity(16),
@@ -457,11 +465,15 @@ impl NotifsHandlerProto {
 	/// handshake, and the maximum allowed size of a notification. At the moment, the message
 	/// is always the same whether we open a substream ourselves or respond to handshake from
 	/// the remote.
+	///
+	/// The size of every notification sent or received is reported to `bandwidth`.
 	pub fn new(
 		list: impl Into<Vec<ProtocolConfig>>,
+		bandwidth: Arc<ProtocolBandwidth>,
 	) -> Self {
 		NotifsHandlerProto {
 			protocols: list.into(),
+			bandwidth,
 		}
 	}
 }
@@ -727,8 +739,9 @@ impl ProtocolsHandler for NotifsHandler {
 		// For each open substream, try send messages from `notifications_sink_rx` to the
 		// substream.
 		for protocol_index in 0..self.protocols.len() {
+			let Protocol { config, state, .. } = &mut self.protocols[protocol_index];
 			if let State::Open { notifications_sink_rx, out_substream: Some(out_substream), .. }
-				= &mut self.protocols[protocol_index].state
+				= state
 			{
 				loop {
 					// Only proceed with `out_substream.poll_ready_unpin` if there is an element
@@ -763,6 +776,7 @@ impl ProtocolsHandler for NotifsHandler {
 						}
 					};
 
+					self.bandwidth.report(&config.name, Direction::Outbound, message.len());
 					let _ = out_substream.start_send_unpin(message);
 					// Note that flushing is performed later down this function.
 				}
@@ -800,7 +814,8 @@ impl ProtocolsHandler for NotifsHandler {
 		for protocol_index in 0..self.protocols.len() {
 			// Inbound substreams being closed is always tolerated, except for the
 			// `OpenDesiredByRemote` state which might need to be switched back to `Closed`.
-			match &mut self.protocols[protocol_index].state {
+			let Protocol { config, state, .. } = &mut self.protocols[protocol_index];
+			match state {
 				State::Closed { .. } |
 				State::Open { in_substream: None, .. } |
 				State::Opening { in_substream: None } => {}
@@ -809,6 +824,17 @@ impl ProtocolsHandler for NotifsHandler {
 					match Stream::poll_next(Pin::new(in_substream.as_mut().unwrap()), cx) {
 						Poll::Pending => {},
 						Poll::Ready(Some(Ok(message))) => {
+							if !self.bandwidth.check_inbound(&config.name, message.len()) {
+								log::debug!(
+									target: "sub-libp2p",
+									"Inbound rate limit of protocol {:?} exceeded. Discarding \
+									 notification from {:?}.",
+									config.name, self.peer_id,
+								);
+								cx.waker().wake_by_ref();
+								continue;
+							}
+
 							let event = NotifsHandlerOut::Notification {
 								protocol_index,
 								message,
@@ -85,7 +85,7 @@ fn build_nodes() -> (Swarm<CustomProtoWithAddr>, Swarm<CustomProtoWithAddr>) {
 				fallback_names: Vec::new(),
 				handshake: Vec::new(),
 				max_notification_size: 1024 * 1024
-			})),
+			}), Default::default()),
 			addrs: addrs
 				.iter()
 				.enumerate()
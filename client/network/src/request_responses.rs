@@ -34,6 +34,10 @@
 //! - If provided, a ["requests processing"](ProtocolConfig::inbound_queue) channel
 //! is used to handle incoming requests.
 //!
+//! - The size of all requests and responses is reported to a shared [`ProtocolBandwidth`].
+//! Incoming requests that exceed the inbound rate limit of their protocol are not passed to the
+//! "requests processing" channel, which is then reported as an [`InboundFailure::Omission`].
+//!
 
 use futures::{channel::{mpsc, oneshot}, prelude::*};
 use libp2p::{
@@ -52,10 +56,10 @@ use libp2p::{
 };
 use std::{
 	borrow::Cow, collections::{hash_map::Entry, HashMap}, convert::TryFrom as _, io, iter,
-	pin::Pin, task::{Context, Poll}, time::Duration,
+	pin::Pin, sync::Arc, task::{Context, Poll}, time::Duration,
 };
 use wasm_timer::Instant;
-use crate::ReputationChange;
+use crate::{bandwidth::{Direction, ProtocolBandwidth}, ReputationChange};
 
 pub use libp2p::request_response::{InboundFailure, OutboundFailure, RequestId};
 
@@ -255,6 +259,9 @@ pub struct RequestResponsesBehaviour {
 	/// Whenever a response is received on `pending_responses`, insert a channel to be notified
 	/// when the request has been sent out.
 	send_feedback: HashMap<ProtocolRequestId, oneshot::Sender<()>>,
+
+	/// Per-protocol bandwidth counters and inbound rate limits.
+	bandwidth: Arc<ProtocolBandwidth>,
 }
 
 /// Generated by the response builder and waiting to be processed.
@@ -269,7 +276,10 @@ struct RequestProcessingOutcome {
 impl RequestResponsesBehaviour {
 	/// Creates a new behaviour. Must be passed a list of supported protocols. Returns an error if
 	/// the same protocol is passed twice.
-	pub fn new(list: impl Iterator<Item = ProtocolConfig>) -> Result<Self, RegisterError> {
+	pub fn new(
+		list: impl Iterator<Item = ProtocolConfig>,
+		bandwidth: Arc<ProtocolBandwidth>,
+	) -> Result<Self, RegisterError> {
 		let mut protocols = HashMap::new();
 		for protocol in list {
 			let mut cfg = RequestResponseConfig::default();
@@ -300,6 +310,7 @@ impl RequestResponsesBehaviour {
 			pending_responses: Default::default(),
 			pending_responses_arrival_time: Default::default(),
 			send_feedback: Default::default(),
+			bandwidth,
 		})
 	}
 
@@ -318,6 +329,7 @@ impl RequestResponsesBehaviour {
 	) {
 		if let Some((protocol, _)) = self.protocols.get_mut(protocol_name) {
 			if protocol.is_connected(target) || connect.should_connect() {
+				self.bandwidth.report(protocol_name, Direction::Outbound, request.len());
 				let request_id = protocol.send_request(target, request);
 				let prev_req_id = self.pending_requests.insert(
 					(protocol_name.to_string().into(), request_id).into(),
@@ -502,6 +514,7 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 
 				if let Ok(payload) = result {
 					if let Some((protocol, _)) = self.protocols.get_mut(&*protocol_name) {
+						self.bandwidth.report(&protocol_name, Direction::Outbound, payload.len());
 						if let Err(_) = protocol.send_response(inner_channel, Ok(payload)) {
 							// Note: Failure is handled further below when receiving
 							// `InboundFailure` event from `RequestResponse` behaviour.
@@ -585,7 +598,16 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 
 							// Submit the request to the "response builder" passed by the user at
 							// initialization.
-							if let Some(resp_builder) = resp_builder {
+							if !self.bandwidth.check_inbound(protocol, request.len()) {
+								// Silently drop `tx`, which is reported the same way as a busy
+								// response builder.
+								log::debug!(
+									target: "sub-libp2p",
+									"Inbound rate limit of protocol {:?} exceeded. Ignoring request \
+									 from {:?}.",
+									protocol, peer,
+								);
+							} else if let Some(resp_builder) = resp_builder {
 								// If the response builder is too busy, silently drop `tx`. This
 								// will be reported by the corresponding `RequestResponse` through
 								// an `InboundFailure::Omission` event.
@@ -626,6 +648,10 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 							},
 							..
 						} => {
+							if let Ok(response) = &response {
+								self.bandwidth.report(protocol, Direction::Inbound, response.len());
+							}
+
 							let (started, delivered) = match self.pending_requests.remove(
 								&(protocol.clone(), request_id).into(),
 							) {
@@ -937,7 +963,7 @@ mod tests {
 			.multiplex(libp2p::yamux::YamuxConfig::default())
 			.boxed();
 
-		let behaviour = RequestResponsesBehaviour::new(list).unwrap();
+		let behaviour = RequestResponsesBehaviour::new(list, Default::default()).unwrap();
 
 		let mut swarm = Swarm::new(transport, behaviour, keypair.public().into_peer_id());
 		let listen_addr: Multiaddr = format!("/memory/{}", rand::random::<u64>()).parse().unwrap();
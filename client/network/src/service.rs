@@ -29,6 +29,7 @@
 
 use crate::{
 	ExHashT, NetworkStateInfo, NetworkStatus,
+	bandwidth::{ProtocolBandwidth, ProtocolBandwidthInfo},
 	behaviour::{self, Behaviour, BehaviourOut},
 	config::{parse_str_addr, Params, TransportConfig},
 	DhtEvent,
@@ -39,6 +40,7 @@ use crate::{
 	},
 	on_demand_layer::AlwaysBadChecker,
 	light_client_requests,
+	peer_store::{self, BanInfo, BanTarget, PeerStore, SavedState},
 	protocol::{
 		self,
 		message::generic::Roles,
@@ -52,12 +54,14 @@ use crate::{
 	},
 	transactions,
 	transport, ReputationChange,
+	utils::interval,
 
 	bitswap::Bitswap,
 };
 
 use codec::Encode as _;
 use futures::{channel::oneshot, prelude::*};
+use ip_network::IpNetwork;
 use libp2p::{PeerId, multiaddr, Multiaddr};
 use libp2p::core::{
 	ConnectedPoint,
@@ -102,12 +106,16 @@ use std::{
 		Arc,
 	},
 	task::Poll,
+	time::Duration,
 };
 
 pub use behaviour::{ResponseFailure, InboundFailure, RequestFailure, OutboundFailure, IfDisconnected};
 
 mod metrics;
 mod out_events;
+
+/// Interval at which the reputations of the nodes and the bans are written to the [`PeerStore`].
+const PEER_STORE_SAVE_INTERVAL: Duration = Duration::from_secs(5 * 60);
 #[cfg(test)]
 mod tests;
 
@@ -123,6 +131,8 @@ pub struct NetworkService<B: BlockT + 'static, H: ExHashT> {
 	local_peer_id: PeerId,
 	/// Bandwidth logging system. Can be queried to know the average bandwidth consumed.
 	bandwidth: Arc<transport::BandwidthSinks>,
+	/// Per-protocol bandwidth counters and inbound rate limits.
+	protocol_bandwidth: Arc<ProtocolBandwidth>,
 	/// Peerset manager (PSM); manages the reputation of nodes and indicates the network which
 	/// nodes it should be connected to or not.
 	peerset: PeersetHandle,
@@ -191,8 +201,12 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			local_peer_id.to_base58(),
 		);
 
+		let protocol_bandwidth = Arc::new(ProtocolBandwidth::new(
+			params.network_config.protocol_rate_limits.clone()
+		));
+
 		let default_notif_handshake_message = Roles::from(&params.role).encode();
-		let (protocol, peerset_handle, mut known_addresses) = Protocol::new(
+		let (mut protocol, peerset_handle, mut known_addresses) = Protocol::new(
 			protocol::ProtocolConfig {
 				roles: From::from(&params.role),
 				max_parallel_downloads: params.network_config.max_parallel_downloads,
@@ -205,8 +219,27 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				.map(|_| default_notif_handshake_message.clone())).collect(),
 			params.block_announce_validator,
 			params.metrics_registry.as_ref(),
+			protocol_bandwidth.clone(),
 		)?;
 
+		// Restore the reputations and the bans saved before the last shutdown.
+		let mut banned_ranges = HashMap::new();
+		if let Some(data) = params.peer_store.as_ref().and_then(|store| store.load()) {
+			let saved = SavedState::decode(&data);
+			protocol.restore_peerset(saved.peerset);
+
+			let now = peer_store::unix_time();
+			for (range, until) in saved.banned_ranges {
+				if until.map_or(false, |until| until <= now) {
+					continue
+				}
+				match range.parse::<IpNetwork>() {
+					Ok(range) => { banned_ranges.insert(range, until); },
+					Err(_) => warn!(target: "sub-libp2p", "Ignoring invalid banned IP range {:?}", range),
+				}
+			}
+		}
+
 		// List of multiaddresses that we know in the network.
 		let mut bootnodes = Vec::new();
 		let mut boot_node_ids = HashSet::new();
@@ -344,6 +377,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 					bitswap,
 					params.light_client_request_protocol_config,
 					params.network_config.request_response_protocols,
+					protocol_bandwidth.clone(),
 				);
 
 				match result {
@@ -381,6 +415,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			Some(registry) => {
 				Some(metrics::register(registry, MetricSources {
 					bandwidth: bandwidth.clone(),
+					protocol_bandwidth: protocol_bandwidth.clone(),
 					major_syncing: is_major_syncing.clone(),
 					connected_peers: num_connected.clone(),
 				})?)
@@ -405,6 +440,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 
 		let service = Arc::new(NetworkService {
 			bandwidth,
+			protocol_bandwidth,
 			external_addresses: external_addresses.clone(),
 			num_connected: num_connected.clone(),
 			is_major_syncing: is_major_syncing.clone(),
@@ -439,6 +475,9 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			tx_handler_controller,
 			metrics,
 			boot_node_ids,
+			peer_store: params.peer_store,
+			peer_store_save_interval: Box::pin(interval(PEER_STORE_SAVE_INTERVAL)),
+			banned_ranges,
 		})
 	}
 
@@ -454,6 +493,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			total_bytes_inbound: self.total_bytes_inbound(),
 			total_bytes_outbound: self.total_bytes_outbound(),
 			state_sync: status.state_sync,
+			num_queued_blocks: self.num_queued_blocks(),
 		}
 	}
 
@@ -467,6 +507,11 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 		self.service.bandwidth.total_outbound()
 	}
 
+	/// Returns the number of bytes sent and received so far, by protocol.
+	pub fn protocol_bandwidth(&self) -> HashMap<String, ProtocolBandwidthInfo> {
+		self.service.protocol_bandwidth()
+	}
+
 	/// Returns the number of peers we're connected to.
 	pub fn num_connected_peers(&self) -> usize {
 		self.network_service.behaviour().user_protocol().num_connected_peers()
@@ -602,6 +647,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			connected_peers,
 			not_connected_peers,
 			peerset: swarm.behaviour_mut().user_protocol_mut().peerset_debug_info(),
+			protocol_bandwidth: self.service.protocol_bandwidth(),
 		}
 	}
 
@@ -628,6 +674,98 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 	pub fn reserved_peers(&self) -> impl Iterator<Item = &PeerId> {
 		self.network_service.behaviour().user_protocol().reserved_peers()
 	}
+
+	/// Returns the list of active bans, both of individual nodes and of IP ranges.
+	pub fn bans(&self) -> Vec<BanInfo> {
+		let now = peer_store::unix_time();
+		let peers = self.network_service.behaviour().user_protocol().banned_peers()
+			.into_iter()
+			.map(|(peer_id, until)| (BanTarget::Peer(peer_id), until));
+		let ranges = self.banned_ranges.iter()
+			.map(|(range, until)| (BanTarget::IpRange(*range), *until));
+
+		peers.chain(ranges)
+			.filter(|(_, until)| until.map_or(true, |until| until > now))
+			.map(|(target, expires_at)| BanInfo { target: target.to_string(), expires_at })
+			.collect()
+	}
+
+	/// Bans a node or a range of IP addresses, and closes the connections that are affected.
+	///
+	/// See [`NetworkService::ban`].
+	pub fn ban(&mut self, target: BanTarget, duration: Option<Duration>) {
+		info!(target: "sub-libp2p", "Banning {} for {:?}", target, duration);
+		match target {
+			BanTarget::Peer(peer_id) => {
+				self.service.peerset.ban_peer(peer_id.clone(), duration);
+				self.close_connections(&peer_id);
+			},
+			BanTarget::IpRange(range) => {
+				let until = duration.map(|d| peer_store::unix_time().saturating_add(d.as_secs()));
+				self.banned_ranges.insert(range, until);
+
+				let affected = self.network_service.behaviour().connected_endpoints()
+					.filter(|(_, endpoint)| peer_store::multiaddr_ip(endpoint.get_remote_address())
+						.map_or(false, |ip| range.contains(ip)))
+					.map(|(peer_id, _)| peer_id.clone())
+					.collect::<HashSet<_>>();
+				for peer_id in affected {
+					self.close_connections(&peer_id);
+				}
+			},
+		}
+	}
+
+	/// Lifts a ban previously set with [`NetworkWorker::ban`].
+	pub fn unban(&mut self, target: BanTarget) {
+		info!(target: "sub-libp2p", "Unbanning {}", target);
+		match target {
+			BanTarget::Peer(peer_id) => self.service.peerset.unban_peer(peer_id),
+			BanTarget::IpRange(range) => { self.banned_ranges.remove(&range); },
+		}
+	}
+
+	/// Returns true if a connection with the given node through the given address must be
+	/// refused.
+	fn is_banned(&mut self, peer_id: &PeerId, address: &Multiaddr) -> bool {
+		if self.network_service.behaviour().user_protocol().is_banned(peer_id) {
+			return true
+		}
+
+		let ip = match peer_store::multiaddr_ip(address) {
+			Some(ip) => ip,
+			None => return false,
+		};
+		let now = peer_store::unix_time();
+		self.banned_ranges.retain(|_, until| until.map_or(true, |until| until > now));
+		self.banned_ranges.keys().any(|range| range.contains(ip))
+	}
+
+	/// Closes all the connections with the given node.
+	fn close_connections(&mut self, peer_id: &PeerId) {
+		// libp2p closes the connections when banning a node. The ban is immediately lifted, as
+		// the bans of the peerset and of IP ranges are enforced by the `NetworkWorker` itself.
+		Swarm::<B>::ban_peer_id(&mut self.network_service, peer_id.clone());
+		Swarm::<B>::unban_peer_id(&mut self.network_service, peer_id.clone());
+	}
+
+	/// Writes the reputations of the nodes and the bans to the [`PeerStore`], if any.
+	fn save_peer_store(&mut self) {
+		let store = match &self.peer_store {
+			Some(store) => store.clone(),
+			None => return,
+		};
+
+		let now = peer_store::unix_time();
+		let state = SavedState {
+			peerset: self.network_service.behaviour_mut().user_protocol_mut().peerset_snapshot(),
+			banned_ranges: self.banned_ranges.iter()
+				.filter(|(_, until)| until.map_or(true, |until| until > now))
+				.map(|(range, until)| (range.to_string(), *until))
+				.collect(),
+		};
+		store.store(&state.encode());
+	}
 }
 
 impl<B: BlockT + 'static, H: ExHashT> NetworkService<B, H> {
@@ -636,6 +774,12 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkService<B, H> {
 		&self.local_peer_id
 	}
 
+	/// Returns the number of bytes sent and received so far, by notifications or request-response
+	/// protocol.
+	pub fn protocol_bandwidth(&self) -> HashMap<String, ProtocolBandwidthInfo> {
+		self.protocol_bandwidth.info()
+	}
+
 	/// Set authorized peers.
 	///
 	/// Need a better solution to manage authorized peers, but now just use reserved peers for
@@ -959,6 +1103,33 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkService<B, H> {
 		self.peerset.report_peer(who, cost_benefit);
 	}
 
+	/// Bans a node or a range of IP addresses, either for the given duration or permanently.
+	///
+	/// The existing connections with the banned nodes are closed, and new connections are closed
+	/// as soon as they are established. Bans are saved in the [`PeerStore`] passed at
+	/// initialization, if any, and survive a restart.
+	pub fn ban(&self, target: BanTarget, duration: Option<Duration>) {
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::Ban(target, duration));
+	}
+
+	/// Lifts a ban set with [`NetworkService::ban`]. Has no effect if the target isn't banned.
+	pub fn unban(&self, target: BanTarget) {
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::Unban(target));
+	}
+
+	/// Returns the list of active bans.
+	///
+	/// Returns an error if the `NetworkWorker` is no longer running.
+	pub async fn bans(&self) -> Result<Vec<BanInfo>, ()> {
+		let (tx, rx) = oneshot::channel();
+
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::Bans {
+			pending_response: tx,
+		});
+
+		rx.await.map_err(|_| ())
+	}
+
 	/// Disconnect from a node as soon as possible.
 	///
 	/// This triggers the same effects as if the connection had closed itself spontaneously.
@@ -1374,6 +1545,11 @@ enum ServiceToWorkerMsg<B: BlockT, H: ExHashT> {
 	},
 	DisconnectPeer(PeerId, Cow<'static, str>),
 	NewBestBlockImported(B::Hash, NumberFor<B>),
+	Ban(BanTarget, Option<Duration>),
+	Unban(BanTarget),
+	Bans {
+		pending_response: oneshot::Sender<Vec<BanInfo>>,
+	},
 }
 
 /// Main network worker. Must be polled in order for the network to advance.
@@ -1408,6 +1584,12 @@ pub struct NetworkWorker<B: BlockT + 'static, H: ExHashT> {
 	peers_notifications_sinks: Arc<Mutex<HashMap<(PeerId, Cow<'static, str>), NotificationsSink>>>,
 	/// Controller for the handler of incoming and outgoing transactions.
 	tx_handler_controller: transactions::TransactionsHandlerController<H>,
+	/// Storage where the reputations of the nodes and the bans are saved.
+	peer_store: Option<Arc<dyn PeerStore>>,
+	/// Interval at which the state is written to `peer_store`.
+	peer_store_save_interval: Pin<Box<dyn Stream<Item = ()> + Send>>,
+	/// Banned IP ranges, with the UNIX timestamp at which the ban expires.
+	banned_ranges: HashMap<IpNetwork, Option<u64>>,
 }
 
 impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
@@ -1438,6 +1620,11 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 			}
 		}
 
+		// Periodically save the reputations and the bans.
+		while let Poll::Ready(Some(())) = this.peer_store_save_interval.poll_next_unpin(cx) {
+			this.save_peer_store();
+		}
+
 		// At the time of writing of this comment, due to a high volume of messages, the network
 		// worker sometimes takes a long time to process the loop below. When that happens, the
 		// rest of the polling is frozen. In order to avoid negative side-effects caused by this
@@ -1511,6 +1698,13 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 					this.network_service.behaviour_mut().user_protocol_mut().disconnect_peer(&who, &protocol_name),
 				ServiceToWorkerMsg::NewBestBlockImported(hash, number) =>
 					this.network_service.behaviour_mut().user_protocol_mut().new_best_block_imported(hash, number),
+				ServiceToWorkerMsg::Ban(target, duration) =>
+					this.ban(target, duration),
+				ServiceToWorkerMsg::Unban(target) =>
+					this.unban(target),
+				ServiceToWorkerMsg::Bans { pending_response } => {
+					let _ = pending_response.send(this.bans());
+				},
 			}
 		}
 
@@ -1524,10 +1718,13 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 				break;
 			}
 
-			// Process the next action coming from the network.
-			let next_event = this.network_service.next_event();
-			futures::pin_mut!(next_event);
-			let poll_value = next_event.poll_unpin(cx);
+			// Process the next action coming from the network. The future is dropped before
+			// handling the event, as some events need to act on the swarm.
+			let poll_value = {
+				let next_event = this.network_service.next_event();
+				futures::pin_mut!(next_event);
+				next_event.poll_unpin(cx)
+			};
 
 			match poll_value {
 				Poll::Pending => break,
@@ -1720,6 +1917,16 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 				Poll::Ready(SwarmEvent::ConnectionEstablished { peer_id, endpoint, num_established }) => {
 					debug!(target: "sub-libp2p", "Libp2p => Connected({:?})", peer_id);
 
+					if this.is_banned(&peer_id, endpoint.get_remote_address()) {
+						debug!(
+							target: "sub-libp2p",
+							"Closing connection with banned node {:?} at {}",
+							peer_id,
+							endpoint.get_remote_address(),
+						);
+						this.close_connections(&peer_id);
+					}
+
 					if let Some(metrics) = this.metrics.as_ref() {
 						let direction = match endpoint {
 							ConnectedPoint::Dialer { .. } => "out",
@@ -1914,6 +2121,12 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 impl<B: BlockT + 'static, H: ExHashT> Unpin for NetworkWorker<B, H> {
 }
 
+impl<B: BlockT + 'static, H: ExHashT> Drop for NetworkWorker<B, H> {
+	fn drop(&mut self) {
+		self.save_peer_store();
+	}
+}
+
 /// The libp2p swarm, customized for our needs.
 type Swarm<B> = libp2p::swarm::Swarm<Behaviour<B>>;
 
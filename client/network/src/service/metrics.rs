@@ -16,6 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::bandwidth::{Direction, ProtocolBandwidth};
 use crate::transport::BandwidthSinks;
 use prometheus_endpoint::{
 	self as prometheus,
@@ -36,6 +37,7 @@ pub use prometheus_endpoint::{Histogram, HistogramVec};
 /// Registers all networking metrics with the given registry.
 pub fn register(registry: &Registry, sources: MetricSources) -> Result<Metrics, PrometheusError> {
 	BandwidthCounters::register(registry, sources.bandwidth)?;
+	ProtocolBandwidthCounters::register(registry, sources.protocol_bandwidth)?;
 	MajorSyncingGauge::register(registry, sources.major_syncing)?;
 	NumConnectedGauge::register(registry, sources.connected_peers)?;
 	Metrics::register(registry)
@@ -44,6 +46,7 @@ pub fn register(registry: &Registry, sources: MetricSources) -> Result<Metrics,
 /// Predefined metric sources that are fed directly into prometheus.
 pub struct MetricSources {
 	pub bandwidth: Arc<BandwidthSinks>,
+	pub protocol_bandwidth: Arc<ProtocolBandwidth>,
 	pub major_syncing: Arc<AtomicBool>,
 	pub connected_peers: Arc<AtomicUsize>,
 }
@@ -288,6 +291,59 @@ impl MetricSource for BandwidthCounters {
 	}
 }
 
+/// The per-protocol bandwidth counter metrics.
+#[derive(Clone)]
+pub struct ProtocolBandwidthCounters(Arc<ProtocolBandwidth>);
+
+impl ProtocolBandwidthCounters {
+	/// Registers the `ProtocolBandwidthCounters` metrics whose values are
+	/// obtained from the given `ProtocolBandwidth`.
+	fn register(registry: &Registry, source: Arc<ProtocolBandwidth>) -> Result<(), PrometheusError> {
+		prometheus::register(SourcedCounter::new(
+			&Opts::new(
+				"sub_libp2p_protocol_bytes_total",
+				"Bandwidth usage of notifications and request-response protocols"
+			).variable_label("protocol").variable_label("direction"),
+			ProtocolBandwidthCounters(source.clone()),
+		)?, registry)?;
+
+		prometheus::register(SourcedCounter::new(
+			&Opts::new(
+				"sub_libp2p_protocol_rate_limited_bytes_total",
+				"Number of inbound bytes discarded because of the rate limit of their protocol"
+			).variable_label("protocol"),
+			ProtocolRateLimitedCounters(source),
+		)?, registry)?;
+
+		Ok(())
+	}
+}
+
+impl MetricSource for ProtocolBandwidthCounters {
+	type N = u64;
+
+	fn collect(&self, mut set: impl FnMut(&[&str], Self::N)) {
+		for (protocol, info) in self.0.info() {
+			set(&[protocol.as_str(), Direction::Inbound.as_str()], info.bytes_inbound);
+			set(&[protocol.as_str(), Direction::Outbound.as_str()], info.bytes_outbound);
+		}
+	}
+}
+
+/// The per-protocol rate-limited bytes metric.
+#[derive(Clone)]
+pub struct ProtocolRateLimitedCounters(Arc<ProtocolBandwidth>);
+
+impl MetricSource for ProtocolRateLimitedCounters {
+	type N = u64;
+
+	fn collect(&self, mut set: impl FnMut(&[&str], Self::N)) {
+		for (protocol, info) in self.0.info() {
+			set(&[protocol.as_str()], info.bytes_rate_limited);
+		}
+	}
+}
+
 /// The "major syncing" metric.
 #[derive(Clone)]
 pub struct MajorSyncingGauge(Arc<AtomicBool>);
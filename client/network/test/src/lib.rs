@@ -824,6 +824,7 @@ pub trait TestNetFactory: Sized where <Self::BlockImport as BlockImport<Block>>:
 			metrics_registry: None,
 			block_request_protocol_config,
 			state_request_protocol_config,
+			peer_store: None,
 			light_client_request_protocol_config,
 		}).unwrap();
 
@@ -918,6 +919,7 @@ pub trait TestNetFactory: Sized where <Self::BlockImport as BlockImport<Block>>:
 			metrics_registry: None,
 			block_request_protocol_config,
 			state_request_protocol_config,
+			peer_store: None,
 			light_client_request_protocol_config,
 		}).unwrap();
 
@@ -31,15 +31,21 @@
 //!
 //! In addition, for each, set, the peerset also holds a list of reserved nodes towards which it
 //! will at all time try to maintain a connection with.
+//!
+//! Nodes can also be banned, either permanently or for a certain duration, in which case their
+//! reputation is kept at the minimum possible value until the ban expires. The reputations and
+//! the bans can be extracted with [`Peerset::snapshot`] and restored after a restart with
+//! [`Peerset::restore`].
 
 mod peersstate;
 
 use std::{collections::HashSet, collections::VecDeque};
 use futures::prelude::*;
 use log::{debug, error, trace};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{collections::HashMap, pin::Pin, task::{Context, Poll}, time::Duration};
-use wasm_timer::{Delay, Instant};
+use std::{collections::HashMap, pin::Pin, str::FromStr, task::{Context, Poll}, time::Duration};
+use wasm_timer::{Delay, Instant, SystemTime, UNIX_EPOCH};
 use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedSender, TracingUnboundedReceiver};
 
 pub use libp2p::PeerId;
@@ -52,6 +58,41 @@ const DISCONNECT_REPUTATION_CHANGE: i32 = -256;
 /// the list.
 const FORGET_AFTER: Duration = Duration::from_secs(3600);
 
+/// Moves a reputation one second closer to zero.
+///
+/// If we multiply each second the reputation by `k` (where `k` is between 0 and 1), it takes
+/// `ln(0.5) / ln(k)` seconds to reduce the reputation by half. Use this formula to empirically
+/// determine a value of `k` that looks correct.
+fn reput_tick(reput: i32) -> i32 {
+	// We use `k = 0.98`, so we divide by `50`. With that value, it takes 34.3 seconds to reduce
+	// the reputation by half.
+	let mut diff = reput / 50;
+	if diff == 0 && reput < 0 {
+		diff = -1;
+	} else if diff == 0 && reput > 0 {
+		diff = 1;
+	}
+	reput.saturating_sub(diff)
+}
+
+/// Applies `secs` seconds worth of [`reput_tick`] to a reputation.
+fn decay_reputation(mut reput: i32, secs: u64) -> i32 {
+	// Even the lowest reputation reaches zero after a couple thousand ticks, so this loop is
+	// bounded no matter the value of `secs`.
+	for _ in 0..secs {
+		if reput == 0 {
+			break;
+		}
+		reput = reput_tick(reput);
+	}
+	reput
+}
+
+/// Returns the current UNIX timestamp, in seconds.
+fn unix_time() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
 #[derive(Debug)]
 enum Action {
 	AddReservedPeer(SetId, PeerId),
@@ -61,6 +102,8 @@ enum Action {
 	ReportPeer(PeerId, ReputationChange),
 	AddToPeersSet(SetId, PeerId),
 	RemoveFromPeersSet(SetId, PeerId),
+	BanPeer(PeerId, Option<Duration>),
+	UnbanPeer(PeerId),
 }
 
 /// Identifier of a set in the peerset.
@@ -161,6 +204,19 @@ impl PeersetHandle {
 	pub fn remove_from_peers_set(&self, set_id: SetId, peer_id: PeerId) {
 		let _ = self.tx.unbounded_send(Action::RemoveFromPeersSet(set_id, peer_id));
 	}
+
+	/// Bans a peer, for the given duration or permanently if `None`. The peer is disconnected
+	/// from all the sets and no connection with it is accepted or attempted until the ban
+	/// expires or [`PeersetHandle::unban_peer`] is called.
+	pub fn ban_peer(&self, peer_id: PeerId, duration: Option<Duration>) {
+		let _ = self.tx.unbounded_send(Action::BanPeer(peer_id, duration));
+	}
+
+	/// Lifts a ban previously set with [`PeersetHandle::ban_peer`]. The reputation of the peer
+	/// then decays back to normal over time.
+	pub fn unban_peer(&self, peer_id: PeerId) {
+		let _ = self.tx.unbounded_send(Action::UnbanPeer(peer_id));
+	}
 }
 
 /// Message that can be sent by the peer set manager (PSM).
@@ -198,6 +254,21 @@ impl From<u64> for IncomingIndex {
 	}
 }
 
+/// State of the peerset that can be persisted across restarts of the node.
+///
+/// Obtained with [`Peerset::snapshot`] and restored with [`Peerset::restore`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeersetSnapshot {
+	/// UNIX timestamp, in seconds, at which the snapshot has been taken.
+	pub timestamp: u64,
+	/// Non-zero reputations, by base58-encoded `PeerId`.
+	pub reputations: Vec<(String, i32)>,
+	/// Banned peers, by base58-encoded `PeerId`, with the UNIX timestamp at which their ban
+	/// expires. `None` if the ban is permanent.
+	pub banned_peers: Vec<(String, Option<u64>)>,
+}
+
 /// Configuration to pass when creating the peer set manager.
 #[derive(Debug)]
 pub struct PeersetConfig {
@@ -255,6 +326,9 @@ pub struct Peerset {
 	/// Next time to do a periodic call to `alloc_slots` with all sets. This is done once per
 	/// second, to match the period of the reputation updates.
 	next_periodic_alloc_slots: Delay,
+	/// Peers that have been banned, with the UNIX timestamp at which their ban expires. `None`
+	/// if the ban is permanent.
+	banned_peers: HashMap<PeerId, Option<u64>>,
 }
 
 impl Peerset {
@@ -283,6 +357,7 @@ impl Peerset {
 				created: now,
 				latest_time_update: now,
 				next_periodic_alloc_slots: Delay::new(Duration::new(0, 0)),
+				banned_peers: HashMap::new(),
 			}
 		};
 
@@ -437,8 +512,13 @@ impl Peerset {
 
 		drop(reputation);
 
+		self.disconnect_from_all_sets(&peer_id);
+	}
+
+	/// Disconnects the given peer from all the sets it is connected to.
+	fn disconnect_from_all_sets(&mut self, peer_id: &PeerId) {
 		for set_index in 0..self.data.num_sets() {
-			if let peersstate::Peer::Connected(peer) = self.data.peer(set_index, &peer_id) {
+			if let peersstate::Peer::Connected(peer) = self.data.peer(set_index, peer_id) {
 				let peer = peer.disconnect();
 				self.message_queue.push_back(Message::Drop {
 					set_id: SetId(set_index),
@@ -450,6 +530,116 @@ impl Peerset {
 		}
 	}
 
+	fn on_ban_peer(&mut self, peer_id: PeerId, duration: Option<Duration>) {
+		self.update_time();
+
+		let until = duration.map(|d| unix_time().saturating_add(d.as_secs()));
+		debug!(target: "peerset", "Banning {} until {:?}", peer_id, until);
+		self.banned_peers.insert(peer_id.clone(), until);
+		self.data.peer_reputation(peer_id.clone()).set_reputation(i32::MIN);
+		self.disconnect_from_all_sets(&peer_id);
+	}
+
+	fn on_unban_peer(&mut self, peer_id: PeerId) {
+		if self.lift_ban(peer_id) {
+			for set_index in 0..self.data.num_sets() {
+				self.alloc_slots(SetId(set_index));
+			}
+		}
+	}
+
+	/// Removes the ban of a peer, if any. Returns `true` if the peer was banned.
+	fn lift_ban(&mut self, peer_id: PeerId) -> bool {
+		if self.banned_peers.remove(&peer_id).is_none() {
+			return false;
+		}
+
+		debug!(target: "peerset", "Unbanning {}", peer_id);
+		// Bring the reputation back to the ban threshold, from where it decays normally.
+		let mut reputation = self.data.peer_reputation(peer_id);
+		if reputation.reputation() < BANNED_THRESHOLD {
+			reputation.set_reputation(BANNED_THRESHOLD);
+		}
+		true
+	}
+
+	/// Returns the list of banned peers, with the UNIX timestamp at which their ban expires.
+	/// `None` if the ban is permanent.
+	pub fn banned_peers(&self) -> impl Iterator<Item = (&PeerId, Option<u64>)> {
+		self.banned_peers.iter().map(|(peer_id, until)| (peer_id, *until))
+	}
+
+	/// Returns true if the given peer has been explicitly banned and its ban hasn't expired yet.
+	pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+		match self.banned_peers.get(peer_id) {
+			Some(Some(until)) => *until > unix_time(),
+			Some(None) => true,
+			None => false,
+		}
+	}
+
+	/// Returns the reputations and bans of all the peers that are worth remembering across a
+	/// restart of the node.
+	pub fn snapshot(&mut self) -> PeersetSnapshot {
+		self.update_time();
+
+		let mut reputations = Vec::new();
+		for peer_id in self.data.peers().cloned().collect::<Vec<_>>() {
+			let reputation = self.data.peer_reputation(peer_id.clone()).reputation();
+			if reputation != 0 {
+				reputations.push((peer_id.to_base58(), reputation));
+			}
+		}
+
+		PeersetSnapshot {
+			timestamp: unix_time(),
+			reputations,
+			banned_peers: self.banned_peers.iter()
+				.map(|(peer_id, until)| (peer_id.to_base58(), *until))
+				.collect(),
+		}
+	}
+
+	/// Restores reputations and bans from a [`PeersetSnapshot`].
+	///
+	/// Reputations decay for the time elapsed since the snapshot has been taken, as if the node
+	/// had kept running. Expired bans and malformed entries are ignored.
+	pub fn restore(&mut self, snapshot: PeersetSnapshot) {
+		let now = unix_time();
+		let elapsed = now.saturating_sub(snapshot.timestamp);
+
+		for (peer_id, reputation) in snapshot.reputations {
+			let peer_id = match PeerId::from_str(&peer_id) {
+				Ok(peer_id) => peer_id,
+				Err(_) => {
+					debug!(target: "peerset", "Ignoring malformed peer id {:?} in snapshot", peer_id);
+					continue
+				}
+			};
+
+			let reputation = decay_reputation(reputation, elapsed);
+			if reputation != 0 {
+				self.data.peer_reputation(peer_id).set_reputation(reputation);
+			}
+		}
+
+		for (peer_id, until) in snapshot.banned_peers {
+			if until.map_or(false, |until| until <= now) {
+				continue
+			}
+
+			match PeerId::from_str(&peer_id) {
+				Ok(peer_id) => {
+					self.banned_peers.insert(peer_id.clone(), until);
+					self.data.peer_reputation(peer_id.clone()).set_reputation(i32::MIN);
+					self.disconnect_from_all_sets(&peer_id);
+				},
+				Err(_) =>
+					debug!(target: "peerset", "Ignoring malformed peer id {:?} in snapshot", peer_id),
+			}
+		}
+	}
+
 	/// Updates the value of `self.latest_time_update` and performs all the updates that happen
 	/// over time, such as reputation increases for staying connected.
 	fn update_time(&mut self) {
@@ -464,22 +654,24 @@ impl Peerset {
 			elapsed_now.as_secs() - elapsed_latest.as_secs()
 		};
 
+		// Lift the bans that have expired.
+		if !self.banned_peers.is_empty() {
+			let now_unix = unix_time();
+			let expired = self.banned_peers.iter()
+				.filter(|(_, until)| until.map_or(false, |until| until <= now_unix))
+				.map(|(peer_id, _)| peer_id.clone())
+				.collect::<Vec<_>>();
+			for peer_id in expired {
+				self.lift_ban(peer_id);
+			}
+		}
+
 		// For each elapsed second, move the node reputation towards zero.
-		// If we multiply each second the reputation by `k` (where `k` is between 0 and 1), it
-		// takes `ln(0.5) / ln(k)` seconds to reduce the reputation by half. Use this formula to
-		// empirically determine a value of `k` that looks correct.
 		for _ in 0..secs_diff {
 			for peer_id in self.data.peers().cloned().collect::<Vec<_>>() {
-				// We use `k = 0.98`, so we divide by `50`. With that value, it takes 34.3 seconds
-				// to reduce the reputation by half.
-				fn reput_tick(reput: i32) -> i32 {
-					let mut diff = reput / 50;
-					if diff == 0 && reput < 0 {
-						diff = -1;
-					} else if diff == 0 && reput > 0 {
-						diff = 1;
-					}
-					reput.saturating_sub(diff)
+				// The reputation of banned peers stays at the minimum until the ban is lifted.
+				if self.banned_peers.contains_key(&peer_id) {
+					continue;
 				}
 
 				let mut peer_reputation = self.data.peer_reputation(peer_id.clone());
@@ -742,6 +934,10 @@ impl Stream for Peerset {
 					self.add_to_peers_set(sets_name, peer_id),
 				Action::RemoveFromPeersSet(sets_name, peer_id) =>
 					self.on_remove_from_peers_set(sets_name, peer_id),
+				Action::BanPeer(peer_id, duration) =>
+					self.on_ban_peer(peer_id, duration),
+				Action::UnbanPeer(peer_id) =>
+					self.on_unban_peer(peer_id),
 			}
 		}
 	}
@@ -762,7 +958,10 @@ pub enum DropReason {
 mod tests {
 	use libp2p::PeerId;
 	use futures::prelude::*;
-	use super::{PeersetConfig, Peerset, Message, IncomingIndex, ReputationChange, SetConfig, SetId, BANNED_THRESHOLD};
+	use super::{
+		PeersetConfig, Peerset, PeersetSnapshot, Message, IncomingIndex, ReputationChange,
+		SetConfig, SetId, BANNED_THRESHOLD, decay_reputation, unix_time,
+	};
 	use std::{pin::Pin, task::Poll, thread, time::Duration};
 
 	fn assert_messages(mut peerset: Peerset, messages: Vec<Message>) -> Peerset {
@@ -970,4 +1169,98 @@ mod tests {
 
 		futures::executor::block_on(fut);
 	}
+
+	#[test]
+	fn test_explicit_ban_outlives_decay() {
+		let (mut peerset, handle) = Peerset::from_config(PeersetConfig {
+			sets: vec![SetConfig {
+				in_peers: 25,
+				out_peers: 25,
+				bootnodes: vec![],
+				reserved_nodes: Default::default(),
+				reserved_only: false,
+			}],
+		});
+
+		let peer_id = PeerId::random();
+		handle.ban_peer(peer_id.clone(), None);
+
+		let fut = futures::future::poll_fn(move |cx| {
+			// We need one polling for the message to be processed.
+			assert_eq!(Stream::poll_next(Pin::new(&mut peerset), cx), Poll::Pending);
+			assert_eq!(peerset.banned_peers().count(), 1);
+
+			// Contrary to a bad reputation, the ban doesn't go away over time.
+			thread::sleep(Duration::from_millis(1500));
+			peerset.incoming(SetId::from(0), peer_id.clone(), IncomingIndex(1));
+			if let Poll::Ready(msg) = Stream::poll_next(Pin::new(&mut peerset), cx) {
+				assert_eq!(msg.unwrap(), Message::Reject(IncomingIndex(1)));
+			} else {
+				panic!()
+			}
+
+			// Once unbanned, the peerset is willing to connect to the peer again.
+			handle.unban_peer(peer_id.clone());
+			if let Poll::Ready(msg) = Stream::poll_next(Pin::new(&mut peerset), cx) {
+				assert_eq!(msg.unwrap(), Message::Connect { set_id: SetId::from(0), peer_id: peer_id.clone() });
+			} else {
+				panic!()
+			}
+			assert_eq!(peerset.banned_peers().count(), 0);
+
+			Poll::Ready(())
+		});
+
+		futures::executor::block_on(fut);
+	}
+
+	#[test]
+	fn test_snapshot_restore() {
+		let config = || PeersetConfig {
+			sets: vec![SetConfig {
+				in_peers: 25,
+				out_peers: 25,
+				bootnodes: vec![],
+				reserved_nodes: Default::default(),
+				reserved_only: false,
+			}],
+		};
+
+		let good = PeerId::random();
+		let banned = PeerId::random();
+		let expired = PeerId::random();
+		let now = unix_time();
+
+		let (mut peerset, _) = Peerset::from_config(config());
+		peerset.restore(PeersetSnapshot {
+			// Taken 100 seconds ago.
+			timestamp: now - 100,
+			reputations: vec![
+				(good.to_base58(), 10_000),
+				("not a peer id".into(), 10_000),
+			],
+			banned_peers: vec![
+				(banned.to_base58(), None),
+				(expired.to_base58(), Some(now - 1)),
+			],
+		});
+
+		let snapshot = peerset.snapshot();
+		let reputation_of = |peer_id: &PeerId| snapshot.reputations.iter()
+			.find(|(p, _)| *p == peer_id.to_base58())
+			.map(|(_, r)| *r);
+		assert_eq!(reputation_of(&good), Some(decay_reputation(10_000, 100)));
+		assert!(reputation_of(&good).unwrap() < 10_000);
+		assert_eq!(reputation_of(&banned), Some(i32::MIN));
+		assert_eq!(reputation_of(&expired), None);
+		assert_eq!(snapshot.banned_peers, vec![(banned.to_base58(), None)]);
+	}
+
+	#[test]
+	fn test_decay_reaches_zero() {
+		assert_eq!(decay_reputation(0, 10), 0);
+		assert_eq!(decay_reputation(i32::MIN, u64::MAX), 0);
+		assert_eq!(decay_reputation(i32::MAX, u64::MAX), 0);
+		assert_eq!(decay_reputation(-100, 1), -98);
+	}
 }
@@ -45,6 +45,7 @@ impl MetricsLink {
 pub struct Metrics {
 	pub block_constructed: Histogram,
 	pub number_of_transactions: Gauge<U64>,
+	pub proof_size: Gauge<U64>,
 }
 
 impl Metrics {
@@ -64,6 +65,13 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			proof_size: register(
+				Gauge::new(
+					"proposer_block_proof_size",
+					"Size in bytes of the storage proof of the last proposed block",
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
@@ -21,6 +21,7 @@ use std::sync::Arc;
 
 use jsonrpc_core::futures::sync::mpsc;
 use jsonrpc_pubsub::{Session, PubSubMetadata};
+use sc_rpc_server::RpcPeer;
 
 /// RPC Metadata.
 ///
@@ -30,6 +31,7 @@ use jsonrpc_pubsub::{Session, PubSubMetadata};
 #[derive(Default, Clone)]
 pub struct Metadata {
 	session: Option<Arc<Session>>,
+	peer: Option<RpcPeer>,
 }
 
 impl jsonrpc_core::Metadata for Metadata {}
@@ -44,6 +46,7 @@ impl Metadata {
 	pub fn new(transport: mpsc::Sender<String>) -> Self {
 		Metadata {
 			session: Some(Arc::new(Session::new(transport))),
+			peer: None,
 		}
 	}
 
@@ -55,6 +58,16 @@ impl Metadata {
 	}
 }
 
+impl sc_rpc_server::PeerMetadata for Metadata {
+	fn peer(&self) -> Option<RpcPeer> {
+		self.peer
+	}
+
+	fn set_peer(&mut self, peer: RpcPeer) {
+		self.peer = Some(peer);
+	}
+}
+
 impl From<mpsc::Sender<String>> for Metadata {
 	fn from(sender: mpsc::Sender<String>) -> Self {
 		Self::new(sender)
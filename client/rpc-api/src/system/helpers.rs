@@ -73,6 +73,16 @@ pub struct PeerInfo<Hash, Number> {
 	pub best_number: Number,
 }
 
+/// Ban of a node or of a range of IP addresses
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BanInfo {
+	/// Banned peer ID or IP range
+	pub target: String,
+	/// UNIX timestamp, in seconds, at which the ban expires. `None` if the ban is permanent.
+	pub expires_at: Option<u64>,
+}
+
 /// The role the node is running as
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum NodeRole {
@@ -125,6 +135,17 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn should_serialize_ban_info() {
+		assert_eq!(
+			::serde_json::to_string(&BanInfo {
+				target: "192.0.2.0/24".into(),
+				expires_at: Some(1_600_000_000),
+			}).unwrap(),
+			r#"{"target":"192.0.2.0/24","expiresAt":1600000000}"#,
+		);
+	}
+
 	#[test]
 	fn should_serialize_sync_state() {
 		assert_eq!(
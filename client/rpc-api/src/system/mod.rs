@@ -27,7 +27,7 @@ use futures::{future::BoxFuture, compat::Compat};
 
 use self::error::Result as SystemResult;
 
-pub use self::helpers::{SystemInfo, Health, PeerInfo, NodeRole, SyncState};
+pub use self::helpers::{SystemInfo, Health, PeerInfo, NodeRole, SyncState, BanInfo};
 pub use self::gen_client::Client as SystemClient;
 
 /// Substrate system RPC API
@@ -106,6 +106,26 @@ pub trait SystemApi<Hash, Number> {
 	#[rpc(name = "system_reservedPeers", returns = "Vec<String>")]
 	fn system_reserved_peers(&self) -> Receiver<Vec<String>>;
 
+	/// Bans a node or a range of IP addresses, either for the given number of seconds or
+	/// permanently. Returns the empty string or an error.
+	///
+	/// The target is either a PeerId such as `QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV`,
+	/// an IP address such as `198.51.100.19`, or a range of IP addresses such as
+	/// `198.51.100.0/24`.
+	#[rpc(name = "system_banPeer", returns = "()")]
+	fn system_ban_peer(&self, target: String, duration_secs: Option<u64>)
+		-> Compat<BoxFuture<'static, Result<(), jsonrpc_core::Error>>>;
+
+	/// Lifts a ban set with `system_banPeer`. Returns the empty string or an error.
+	#[rpc(name = "system_unbanPeer", returns = "()")]
+	fn system_unban_peer(&self, target: String)
+		-> Compat<BoxFuture<'static, Result<(), jsonrpc_core::Error>>>;
+
+	/// Returns the list of active bans.
+	#[rpc(name = "system_listBans", returns = "Vec<BanInfo>")]
+	fn system_list_bans(&self)
+		-> Compat<BoxFuture<'static, jsonrpc_core::Result<Vec<BanInfo>>>>;
+
 	/// Returns the roles the node is running as.
 	#[rpc(name = "system_nodeRoles", returns = "Vec<NodeRole>")]
 	fn system_node_roles(&self) -> Receiver<Vec<NodeRole>>;
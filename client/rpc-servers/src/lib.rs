@@ -20,6 +20,7 @@
 
 #![warn(missing_docs)]
 
+mod limits;
 mod middleware;
 
 use std::io;
@@ -43,9 +44,13 @@ pub type RpcHandler<T> = pubsub::PubSubHandler<T, RpcMiddleware>;
 
 pub use self::inner::*;
 pub use middleware::{RpcMiddleware, RpcMetrics};
+pub use limits::{
+	PeerMetadata, Rejection, RpcLimiter, RpcLimits, RpcPeer, RATE_LIMITED_ERROR,
+	RESPONSE_TOO_LARGE_ERROR,
+};
 
 /// Construct rpc `IoHandler`
-pub fn rpc_handler<M: PubSubMetadata>(
+pub fn rpc_handler<M: PubSubMetadata + PeerMetadata>(
 	extension: impl IoHandlerExtension<M>,
 	rpc_middleware: RpcMiddleware,
 ) -> RpcHandler<M> {
@@ -81,17 +86,31 @@ mod inner {
 
 	/// Start HTTP server listening on given address.
 	///
+	/// The server doesn't expose the address of the client. When `trust_proxy_headers` is set, it
+	/// is taken from the `X-Forwarded-For` or `X-Real-IP` header of the request, otherwise it is
+	/// unknown and all the clients share the same rate limits.
+	///
 	/// **Note**: Only available if `not(target_os = "unknown")`.
-	pub fn start_http<M: pubsub::PubSubMetadata + Default>(
+	pub fn start_http<M: pubsub::PubSubMetadata + PeerMetadata + Default>(
 		addr: &std::net::SocketAddr,
 		thread_pool_size: Option<usize>,
 		cors: Option<&Vec<String>>,
 		io: RpcHandler<M>,
 		maybe_max_payload_mb: Option<usize>,
+		trust_proxy_headers: bool,
 	) -> io::Result<http::Server> {
 		let max_request_body_size = maybe_max_payload_mb.map(|mb| mb.saturating_mul(MEGABYTE))
 			.unwrap_or(RPC_MAX_PAYLOAD_DEFAULT);
-		http::ServerBuilder::new(io)
+		let meta_extractor = move |request: &http::hyper::Request<http::hyper::Body>| {
+			let mut meta = M::default();
+			if trust_proxy_headers {
+				if let Some(peer) = forwarded_peer(request.headers()) {
+					meta.set_peer(peer.into());
+				}
+			}
+			meta
+		};
+		http::ServerBuilder::with_meta_extractor(io, meta_extractor)
 			.threads(thread_pool_size.unwrap_or(HTTP_THREADS))
 			.health_api(("/health", "system_health"))
 			.allowed_hosts(hosts_filtering(cors.is_some()))
@@ -107,12 +126,18 @@ mod inner {
 
 	/// Start IPC server listening on given path.
 	///
+	/// IPC clients are local, so they are all rate limited as the loopback address.
+	///
 	/// **Note**: Only available if `not(target_os = "unknown")`.
-	pub fn start_ipc<M: pubsub::PubSubMetadata + Default>(
+	pub fn start_ipc<M: pubsub::PubSubMetadata + PeerMetadata + Default>(
 		addr: &str,
 		io: RpcHandler<M>,
 	) -> io::Result<ipc::Server> {
-		let builder = ipc::ServerBuilder::new(io);
+		let builder = ipc::ServerBuilder::with_meta_extractor(io, |_: &ipc::RequestContext| {
+			let mut meta = M::default();
+			meta.set_peer(RpcPeer::Address(std::net::Ipv4Addr::LOCALHOST.into()));
+			meta
+		});
 		#[cfg(target_os = "unix")]
 		builder.set_security_attributes({
 			let security_attributes = ipc::SecurityAttributes::empty();
@@ -124,9 +149,13 @@ mod inner {
 
 	/// Start WS server listening on given address.
 	///
+	/// The server doesn't expose the address of the client, so each connection is rate limited on
+	/// its own.
+	///
 	/// **Note**: Only available if `not(target_os = "unknown")`.
 	pub fn start_ws<
-		M: pubsub::PubSubMetadata + From<jsonrpc_core::futures::sync::mpsc::Sender<String>>,
+		M: pubsub::PubSubMetadata + PeerMetadata +
+			From<jsonrpc_core::futures::sync::mpsc::Sender<String>>,
 	>(
 		addr: &std::net::SocketAddr,
 		max_connections: Option<usize>,
@@ -136,7 +165,12 @@ mod inner {
 	) -> io::Result<ws::Server> {
 		let rpc_max_payload = maybe_max_payload_mb.map(|mb| mb.saturating_mul(MEGABYTE))
 			.unwrap_or(RPC_MAX_PAYLOAD_DEFAULT);
-		ws::ServerBuilder::with_meta_extractor(io, |context: &ws::RequestContext| context.sender().into())
+		let meta_extractor = |context: &ws::RequestContext| {
+			let mut meta = M::from(context.sender());
+			meta.set_peer(RpcPeer::Connection(context.session_id));
+			meta
+		};
+		ws::ServerBuilder::with_meta_extractor(io, meta_extractor)
 			.max_payload(rpc_max_payload)
 			.max_connections(max_connections.unwrap_or(WS_MAX_CONNECTIONS))
 			.allowed_origins(map_cors(cors))
@@ -152,6 +186,17 @@ mod inner {
 			})
 	}
 
+	/// The client address reported by a reverse proxy, if any.
+	fn forwarded_peer(headers: &http::hyper::HeaderMap) -> Option<std::net::IpAddr> {
+		// `X-Forwarded-For` is a list of the addresses the request went through, the first one
+		// being the client.
+		let forwarded_for = headers.get("x-forwarded-for")
+			.and_then(|value| value.to_str().ok())
+			.and_then(|value| value.split(',').next());
+		let real_ip = headers.get("x-real-ip").and_then(|value| value.to_str().ok());
+		forwarded_for.or(real_ip).and_then(|peer| peer.trim().parse().ok())
+	}
+
 	fn map_cors<T: for<'a> From<&'a str>>(
 		cors: Option<&Vec<String>>
 	) -> http::DomainsValidation<T> {
@@ -173,3 +218,91 @@ mod inner {
 #[cfg(target_os = "unknown")]
 mod inner {
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{cell::Cell, num::NonZeroU32, sync::{Arc, mpsc}};
+	use jsonrpc_core::futures::sync::mpsc::Sender;
+	use ws::ws as client;
+
+	#[derive(Default, Clone)]
+	struct Metadata {
+		session: Option<Arc<pubsub::Session>>,
+		peer: Option<RpcPeer>,
+	}
+
+	impl jsonrpc_core::Metadata for Metadata {}
+
+	impl PubSubMetadata for Metadata {
+		fn session(&self) -> Option<Arc<pubsub::Session>> {
+			self.session.clone()
+		}
+	}
+
+	impl PeerMetadata for Metadata {
+		fn peer(&self) -> Option<RpcPeer> {
+			self.peer
+		}
+
+		fn set_peer(&mut self, peer: RpcPeer) {
+			self.peer = Some(peer);
+		}
+	}
+
+	impl From<Sender<String>> for Metadata {
+		fn from(sender: Sender<String>) -> Self {
+			Metadata { session: Some(Arc::new(pubsub::Session::new(sender))), peer: None }
+		}
+	}
+
+	/// Calls `rpc_methods` `calls` times over a new WS connection and returns the responses.
+	fn call_over_ws(addr: &std::net::SocketAddr, calls: u64) -> Vec<serde_json::Value> {
+		let (tx, rx) = mpsc::channel();
+		client::connect(format!("ws://{}", addr), |out: client::Sender| {
+			for id in 0..calls {
+				let call = format!(r#"{{"jsonrpc":"2.0","id":{},"method":"rpc_methods"}}"#, id);
+				out.send(call).expect("connection is open; qed");
+			}
+			let tx = tx.clone();
+			let received = Cell::new(0);
+			move |message: client::Message| {
+				let response = serde_json::from_str(message.as_text()?)
+					.expect("the server sends JSON; qed");
+				tx.send(response).expect("receiver is alive; qed");
+				received.set(received.get() + 1);
+				if received.get() == calls {
+					out.close(client::CloseCode::Normal)?;
+				}
+				Ok(())
+			}
+		}).expect("the server is running; qed");
+		rx.try_iter().collect()
+	}
+
+	fn is_rate_limited(response: &serde_json::Value) -> bool {
+		response["error"]["code"] == RATE_LIMITED_ERROR
+	}
+
+	#[test]
+	fn ws_connections_are_rate_limited_independently() {
+		let limiter = Arc::new(RpcLimiter::new(RpcLimits {
+			calls_per_minute: NonZeroU32::new(2),
+			..Default::default()
+		}));
+		let middleware = RpcMiddleware::new(RpcMetrics::new(None).unwrap(), "ws")
+			.with_limiter(limiter);
+		let io = rpc_handler(jsonrpc_core::IoHandler::<Metadata>::default(), middleware);
+		let server = start_ws(&"127.0.0.1:0".parse().unwrap(), None, None, io, None).unwrap();
+
+		let responses = call_over_ws(server.addr(), 3);
+		assert_eq!(responses.len(), 3);
+		assert_eq!(responses.iter().filter(|response| is_rate_limited(response)).count(), 1);
+
+		let responses = call_over_ws(server.addr(), 2);
+		assert_eq!(responses.len(), 2);
+		assert!(responses.iter().all(|response| response["result"].is_object()));
+
+		server.close();
+	}
+}
@@ -0,0 +1,329 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Method filtering and rate limiting of RPC calls.
+
+use std::{
+	collections::{HashMap, HashSet},
+	net::IpAddr,
+	num::NonZeroU32,
+	time::{Duration, Instant},
+};
+use jsonrpc_core::{Error, ErrorCode};
+use parking_lot::Mutex;
+
+/// Error code returned to clients that exceeded one of their rate limits.
+///
+/// This is the "limit exceeded" code used by other JSON-RPC node implementations.
+pub const RATE_LIMITED_ERROR: i64 = -32005;
+
+/// Error code returned instead of a response that is larger than the configured maximum.
+pub const RESPONSE_TOO_LARGE_ERROR: i64 = -32006;
+
+/// Length of the window over which calls are counted.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Number of tracked windows above which expired ones are dropped.
+const MAX_TRACKED_WINDOWS: usize = 10_000;
+
+/// Identity of an RPC client, under which its calls are counted by the rate limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RpcPeer {
+	/// A client of which the address is known.
+	Address(IpAddr),
+	/// A WS connection, identified by its session id.
+	///
+	/// The WS server doesn't expose the address of its clients, so each connection is limited on
+	/// its own.
+	Connection(u64),
+}
+
+impl From<IpAddr> for RpcPeer {
+	fn from(address: IpAddr) -> Self {
+		RpcPeer::Address(address)
+	}
+}
+
+/// Metadata that can carry the identity of the client that sent a request.
+///
+/// Rate limits are enforced per client. All the clients of which the identity is unknown share
+/// a single limit, as they can't be told apart.
+pub trait PeerMetadata {
+	/// Returns the identity of the client, if known.
+	fn peer(&self) -> Option<RpcPeer>;
+
+	/// Records the identity of the client.
+	fn set_peer(&mut self, peer: RpcPeer);
+}
+
+/// Method filters and rate limits applied to RPC calls.
+#[derive(Debug, Clone, Default)]
+pub struct RpcLimits {
+	/// If set, only the methods in this list can be called.
+	pub allowed_methods: Option<HashSet<String>>,
+	/// Methods that can never be called, even if present in `allowed_methods`.
+	pub denied_methods: HashSet<String>,
+	/// Maximum number of calls per minute accepted from a single client.
+	pub calls_per_minute: Option<NonZeroU32>,
+	/// Maximum number of calls per minute of a specific method accepted from a single client.
+	pub method_calls_per_minute: HashMap<String, NonZeroU32>,
+	/// Take the client address of HTTP requests from the `X-Forwarded-For` and `X-Real-IP`
+	/// headers.
+	///
+	/// Only enable this when the node is behind a reverse proxy that sets those headers,
+	/// otherwise clients can pick the address they are rate limited under.
+	pub trust_proxy_headers: bool,
+}
+
+impl RpcLimits {
+	/// Returns `true` if no call is ever rejected.
+	pub fn is_unrestricted(&self) -> bool {
+		self.allowed_methods.is_none() &&
+			self.denied_methods.is_empty() &&
+			!self.has_rate_limits()
+	}
+
+	/// Returns `true` if some methods are rate limited.
+	pub fn has_rate_limits(&self) -> bool {
+		self.calls_per_minute.is_some() || !self.method_calls_per_minute.is_empty()
+	}
+}
+
+/// Reason for rejecting an RPC call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+	/// The method is not in the allow list or is in the deny list.
+	MethodNotAllowed,
+	/// The client exceeded one of its rate limits.
+	RateLimited,
+}
+
+impl Rejection {
+	/// Label used when reporting the rejection in the metrics.
+	pub fn label(&self) -> &'static str {
+		match self {
+			Rejection::MethodNotAllowed => "method_not_allowed",
+			Rejection::RateLimited => "rate_limited",
+		}
+	}
+}
+
+impl From<Rejection> for Error {
+	fn from(rejection: Rejection) -> Error {
+		match rejection {
+			Rejection::MethodNotAllowed => Error {
+				code: ErrorCode::MethodNotFound,
+				message: "Method not allowed".into(),
+				data: None,
+			},
+			Rejection::RateLimited => Error {
+				code: ErrorCode::ServerError(RATE_LIMITED_ERROR),
+				message: "Rate limit exceeded, retry later".into(),
+				data: None,
+			},
+		}
+	}
+}
+
+/// Error returned instead of a response that exceeds the maximum response size.
+pub fn response_too_large_error(max_response_size: usize) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RESPONSE_TOO_LARGE_ERROR),
+		message: format!("Response is larger than the maximum of {} bytes", max_response_size),
+		data: None,
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct WindowKey {
+	/// The client the calls are counted for, `None` for the clients of unknown identity.
+	peer: Option<RpcPeer>,
+	method: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+	start: Instant,
+	calls: u32,
+}
+
+/// Checks RPC calls against [`RpcLimits`].
+///
+/// Calls are counted in fixed windows of one minute. A single limiter is shared by all the
+/// transports so that clients can't escape their limits by switching between them.
+#[derive(Debug)]
+pub struct RpcLimiter {
+	limits: RpcLimits,
+	windows: Mutex<HashMap<WindowKey, Window>>,
+}
+
+impl RpcLimiter {
+	/// Create a new limiter enforcing the given limits.
+	pub fn new(limits: RpcLimits) -> Self {
+		RpcLimiter {
+			limits,
+			windows: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// The limits enforced by this limiter.
+	pub fn limits(&self) -> &RpcLimits {
+		&self.limits
+	}
+
+	/// Check whether a call to `method` from `peer` is accepted, and count it if it is.
+	///
+	/// The calls of all the clients of unknown identity are counted together.
+	pub fn check(&self, peer: Option<RpcPeer>, method: &str) -> Result<(), Rejection> {
+		if self.limits.denied_methods.contains(method) {
+			return Err(Rejection::MethodNotAllowed)
+		}
+		if let Some(allowed) = &self.limits.allowed_methods {
+			if !allowed.contains(method) {
+				return Err(Rejection::MethodNotAllowed)
+			}
+		}
+
+		if self.limits.calls_per_minute.is_none() &&
+			!self.limits.method_calls_per_minute.contains_key(method)
+		{
+			return Ok(())
+		}
+		self.check_rate(peer, method, Instant::now())
+	}
+
+	fn check_rate(
+		&self,
+		peer: Option<RpcPeer>,
+		method: &str,
+		now: Instant,
+	) -> Result<(), Rejection> {
+		let method_limit = self.limits.method_calls_per_minute.get(method);
+		let mut windows = self.windows.lock();
+		if windows.len() > MAX_TRACKED_WINDOWS {
+			windows.retain(|_, window| now.duration_since(window.start) < RATE_LIMIT_WINDOW);
+		}
+
+		// Both limits are checked before counting the call, so that a rejected call doesn't
+		// consume any of the budget.
+		let peer_key = WindowKey { peer, method: None };
+		let method_key = WindowKey { peer, method: Some(method.to_owned()) };
+		let limited = [
+			(&peer_key, self.limits.calls_per_minute.as_ref()),
+			(&method_key, method_limit),
+		];
+		for (key, limit) in limited.iter() {
+			if let Some(limit) = limit {
+				let calls = windows.get(*key)
+					.filter(|window| now.duration_since(window.start) < RATE_LIMIT_WINDOW)
+					.map_or(0, |window| window.calls);
+				if calls >= limit.get() {
+					return Err(Rejection::RateLimited)
+				}
+			}
+		}
+		for (key, limit) in limited.iter() {
+			if limit.is_some() {
+				let window = windows.entry((*key).clone())
+					.or_insert(Window { start: now, calls: 0 });
+				if now.duration_since(window.start) >= RATE_LIMIT_WINDOW {
+					*window = Window { start: now, calls: 0 };
+				}
+				window.calls += 1;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn limiter(limits: RpcLimits) -> RpcLimiter {
+		RpcLimiter::new(limits)
+	}
+
+	#[test]
+	fn allow_and_deny_lists_are_applied() {
+		let limiter = limiter(RpcLimits {
+			allowed_methods: Some(["system_health", "state_getKeysPaged"]
+				.iter().map(|m| m.to_string()).collect()),
+			denied_methods: ["state_getKeysPaged".to_string()].iter().cloned().collect(),
+			..Default::default()
+		});
+
+		assert_eq!(limiter.check(None, "system_health"), Ok(()));
+		assert_eq!(limiter.check(None, "state_getKeysPaged"), Err(Rejection::MethodNotAllowed));
+		assert_eq!(limiter.check(None, "author_submitExtrinsic"), Err(Rejection::MethodNotAllowed));
+	}
+
+	#[test]
+	fn peers_are_limited_independently() {
+		let limiter = limiter(RpcLimits {
+			calls_per_minute: NonZeroU32::new(2),
+			..Default::default()
+		});
+		let alice = Some(RpcPeer::Address(IpAddr::from([10, 0, 0, 1])));
+		let bob = Some(RpcPeer::Connection(1));
+		let now = Instant::now();
+
+		assert_eq!(limiter.check_rate(alice, "system_health", now), Ok(()));
+		assert_eq!(limiter.check_rate(alice, "chain_getHeader", now), Ok(()));
+		assert_eq!(limiter.check_rate(alice, "system_health", now), Err(Rejection::RateLimited));
+		assert_eq!(limiter.check_rate(bob, "system_health", now), Ok(()));
+
+		let later = now + RATE_LIMIT_WINDOW;
+		assert_eq!(limiter.check_rate(alice, "system_health", later), Ok(()));
+	}
+
+	#[test]
+	fn method_limits_only_apply_to_their_method() {
+		let limiter = limiter(RpcLimits {
+			calls_per_minute: NonZeroU32::new(3),
+			method_calls_per_minute: [("state_getKeysPaged".to_string(), NonZeroU32::new(1).unwrap())]
+				.iter().cloned().collect(),
+			..Default::default()
+		});
+		let peer = Some(RpcPeer::Address(IpAddr::from([10, 0, 0, 1])));
+		let now = Instant::now();
+
+		assert_eq!(limiter.check_rate(peer, "state_getKeysPaged", now), Ok(()));
+		assert_eq!(limiter.check_rate(peer, "state_getKeysPaged", now), Err(Rejection::RateLimited));
+		// The rejected call did not count towards the per peer limit.
+		assert_eq!(limiter.check_rate(peer, "system_health", now), Ok(()));
+		assert_eq!(limiter.check_rate(peer, "system_health", now), Ok(()));
+		assert_eq!(limiter.check_rate(peer, "system_health", now), Err(Rejection::RateLimited));
+	}
+
+	#[test]
+	fn unknown_peers_share_a_limit() {
+		let limiter = limiter(RpcLimits {
+			method_calls_per_minute: [("state_getKeysPaged".to_string(), NonZeroU32::new(1).unwrap())]
+				.iter().cloned().collect(),
+			..Default::default()
+		});
+		let peer = Some(RpcPeer::Address(IpAddr::from([10, 0, 0, 1])));
+
+		assert_eq!(limiter.check(None, "system_health"), Ok(()));
+		assert_eq!(limiter.check(None, "state_getKeysPaged"), Ok(()));
+		assert_eq!(limiter.check(None, "state_getKeysPaged"), Err(Rejection::RateLimited));
+		assert_eq!(limiter.check(peer, "state_getKeysPaged"), Ok(()));
+	}
+}
@@ -18,9 +18,11 @@
 
 //! Middleware for RPC requests.
 
+use std::{io, sync::Arc};
 use jsonrpc_core::{
 	Middleware as RequestMiddleware, Metadata,
-	Request, Response, FutureResponse, FutureOutput
+	Request, Response, FutureResponse, FutureOutput,
+	Call, Output, Failure,
 };
 use prometheus_endpoint::{
 	Registry, CounterVec, PrometheusError,
@@ -29,10 +31,13 @@ use prometheus_endpoint::{
 
 use futures::{future::Either, Future};
 
+use crate::{MEGABYTE, limits::{PeerMetadata, RpcLimiter, response_too_large_error}};
+
 /// Metrics for RPC middleware
 #[derive(Debug, Clone)]
 pub struct RpcMetrics {
 	rpc_calls: Option<CounterVec<U64>>,
+	rpc_calls_rejected: Option<CounterVec<U64>>,
 }
 
 impl RpcMetrics {
@@ -51,14 +56,63 @@ impl RpcMetrics {
 					r,
 				)
 			).transpose()?,
+			rpc_calls_rejected: metrics_registry.map(|r|
+				register(
+					CounterVec::new(
+						Opts::new(
+							"rpc_calls_rejected_total",
+							"Number of rpc calls rejected because of the configured limits",
+						),
+						&["protocol", "reason"]
+					)?,
+					r,
+				)
+			).transpose()?,
 		})
 	}
+
+	fn report_rejected(&self, transport_label: &str, reason: &str) {
+		if let Some(ref rpc_calls_rejected) = self.rpc_calls_rejected {
+			rpc_calls_rejected.with_label_values(&[transport_label, reason]).inc();
+		}
+	}
+}
+
+/// Writer that discards its input and fails once more than `max` bytes were written to it.
+struct SizeLimit {
+	written: usize,
+	max: usize,
+}
+
+impl io::Write for SizeLimit {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.written = self.written.saturating_add(buf.len());
+		if self.written > self.max {
+			return Err(io::ErrorKind::Other.into())
+		}
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Returns `true` if the JSON encoding of `response` is larger than `max` bytes.
+///
+/// The server encodes the response again to send it, so the encoding is only measured here,
+/// without being stored, and measuring stops as soon as `max` bytes are exceeded.
+fn exceeds_size(response: &Response, max: usize) -> bool {
+	serde_json::to_writer(SizeLimit { written: 0, max }, response)
+		.map_or_else(|e| e.is_io(), |()| false)
 }
 
 /// Middleware for RPC calls
 pub struct RpcMiddleware {
 	metrics: RpcMetrics,
 	transport_label: String,
+	limiter: Option<Arc<RpcLimiter>>,
+	max_response_size: Option<usize>,
 }
 
 impl RpcMiddleware {
@@ -70,11 +124,25 @@ impl RpcMiddleware {
 		RpcMiddleware {
 			metrics,
 			transport_label: String::from(transport_label),
+			limiter: None,
+			max_response_size: None,
 		}
 	}
+
+	/// Reject the calls that are not accepted by the given limiter.
+	pub fn with_limiter(mut self, limiter: Arc<RpcLimiter>) -> Self {
+		self.limiter = Some(limiter);
+		self
+	}
+
+	/// Replace responses larger than `max_response_size_mb` megabytes with an error.
+	pub fn with_max_response_size(mut self, max_response_size_mb: Option<usize>) -> Self {
+		self.max_response_size = max_response_size_mb.map(|mb| mb.saturating_mul(MEGABYTE));
+		self
+	}
 }
 
-impl<M: Metadata> RequestMiddleware<M> for RpcMiddleware {
+impl<M: Metadata + PeerMetadata> RequestMiddleware<M> for RpcMiddleware {
 	type Future = FutureResponse;
 	type CallFuture = FutureOutput;
 
@@ -87,6 +155,79 @@ impl<M: Metadata> RequestMiddleware<M> for RpcMiddleware {
 			rpc_calls.with_label_values(&[self.transport_label.as_str()]).inc();
 		}
 
-		Either::B(next(request, meta))
+		match self.max_response_size {
+			Some(max_response_size) => {
+				let metrics = self.metrics.clone();
+				let transport_label = self.transport_label.clone();
+				Either::A(Box::new(next(request, meta).map(move |response| {
+					response.map(|response| {
+						if !exceeds_size(&response, max_response_size) {
+							return response
+						}
+
+						metrics.report_rejected(&transport_label, "response_too_large");
+						let too_large = |output: Output| Output::Failure(Failure {
+							jsonrpc: output.version(),
+							error: response_too_large_error(max_response_size),
+							id: output.id().clone(),
+						});
+						match response {
+							Response::Single(output) => Response::Single(too_large(output)),
+							Response::Batch(outputs) =>
+								Response::Batch(outputs.into_iter().map(too_large).collect()),
+						}
+					})
+				})))
+			}
+			None => Either::B(next(request, meta)),
+		}
+	}
+
+	fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<FutureOutput, X>
+	where
+		F: Fn(Call, M) -> X + Send + Sync,
+		X: Future<Item = Option<Output>, Error = ()> + Send + 'static,
+	{
+		let limiter = match self.limiter {
+			Some(ref limiter) => limiter,
+			None => return Either::B(next(call, meta)),
+		};
+
+		let (method, id_and_version) = match call {
+			Call::MethodCall(ref method_call) =>
+				(&method_call.method, Some((method_call.id.clone(), method_call.jsonrpc))),
+			Call::Notification(ref notification) => (&notification.method, None),
+			Call::Invalid { .. } => return Either::B(next(call, meta)),
+		};
+
+		match limiter.check(meta.peer(), method) {
+			Ok(()) => Either::B(next(call, meta)),
+			Err(rejection) => {
+				self.metrics.report_rejected(&self.transport_label, rejection.label());
+				// Notifications don't get any response, not even an error.
+				let output = id_and_version
+					.map(|(id, jsonrpc)| Output::from(Err(rejection.into()), id, jsonrpc));
+				Either::A(Box::new(futures::future::ok(output)))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpc_core::{Id, Success, Value, Version};
+
+	#[test]
+	fn response_size_is_measured_up_to_the_limit() {
+		let response = Response::Single(Output::Success(Success {
+			jsonrpc: Some(Version::V2),
+			result: Value::String("a".repeat(100)),
+			id: Id::Num(1),
+		}));
+		let size = serde_json::to_vec(&response).unwrap().len();
+
+		assert!(!exceeds_size(&response, size));
+		assert!(exceeds_size(&response, size - 1));
 	}
 }
@@ -87,6 +87,7 @@ fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
 						connected_peers: Default::default(),
 						not_connected_peers: Default::default(),
 						peerset: serde_json::Value::Null,
+						protocol_bandwidth: Default::default(),
 					}).unwrap());
 				},
 				Request::NetworkAddReservedPeer(peer, sender) => {
@@ -104,6 +105,18 @@ fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
 				Request::NetworkReservedPeers(sender) => {
 					let _ = sender.send(vec!["QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".to_string()]);
 				}
+				Request::NetworkBanPeer(target, _, sender) | Request::NetworkUnbanPeer(target, sender) => {
+					let _ = match target.parse::<sc_network::peer_store::BanTarget>() {
+						Ok(_) => sender.send(Ok(())),
+						Err(s) => sender.send(Err(error::Error::MalformattedPeerArg(s.to_string()))),
+					};
+				}
+				Request::NetworkBans(sender) => {
+					let _ = sender.send(vec![BanInfo {
+						target: "198.51.100.0/24".to_string(),
+						expires_at: None,
+					}]);
+				}
 				Request::NodeRoles(sender) => {
 					let _ = sender.send(vec![NodeRole::Authority]);
 				}
@@ -289,6 +302,7 @@ fn system_network_state() {
 			connected_peers: Default::default(),
 			not_connected_peers: Default::default(),
 			peerset: serde_json::Value::Null,
+			protocol_bandwidth: Default::default(),
 		}
 	);
 }
@@ -345,6 +359,30 @@ fn system_network_reserved_peers() {
 	);
 }
 
+#[test]
+fn system_network_ban_and_unban() {
+	let mut runtime = tokio::runtime::current_thread::Runtime::new().unwrap();
+
+	let peer_fut = api(None).system_ban_peer("QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".into(), None);
+	let range_fut = api(None).system_ban_peer("198.51.100.0/24".into(), Some(60));
+	let bad_fut = api(None).system_ban_peer("/ip4/198.51.100.19/tcp/30333".into(), None);
+	assert_eq!(runtime.block_on(peer_fut), Ok(()));
+	assert_eq!(runtime.block_on(range_fut), Ok(()));
+	assert!(runtime.block_on(bad_fut).is_err());
+
+	let unban_fut = api(None).system_unban_peer("198.51.100.19".into());
+	assert_eq!(runtime.block_on(unban_fut), Ok(()));
+}
+
+#[test]
+fn system_network_list_bans() {
+	let mut runtime = tokio::runtime::current_thread::Runtime::new().unwrap();
+	assert_eq!(
+		runtime.block_on(api(None).system_list_bans()).unwrap(),
+		vec![BanInfo { target: "198.51.100.0/24".to_string(), expires_at: None }],
+	);
+}
+
 #[test]
 fn test_add_reset_log_filter() {
 	const EXPECTED_BEFORE_ADD: &'static str = "EXPECTED_BEFORE_ADD";
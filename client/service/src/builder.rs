@@ -70,7 +70,7 @@ use sp_core::traits::{
 use sp_keystore::{CryptoStore, SyncCryptoStore, SyncCryptoStorePtr};
 use sp_runtime::BuildStorage;
 use sc_client_api::{
-	BlockBackend, BlockchainEvents,
+	AuxStore, BlockBackend, BlockchainEvents,
 	StorageProvider,
 	proof_provider::ProofProvider,
 	execution_extensions::ExecutionExtensions
@@ -303,6 +303,19 @@ pub fn new_full_client<TBl, TRtApi, TExecDisp>(
 	new_full_parts(config, telemetry).map(|parts| parts.0)
 }
 
+/// Settings of the database of a node with the given configuration.
+pub fn database_settings(config: &Configuration) -> DatabaseSettings {
+	DatabaseSettings {
+		state_cache_size: config.state_cache_size,
+		state_cache_child_ratio: config.state_cache_child_ratio.map(|v| (v, 100)),
+		state_pruning: config.state_pruning.clone(),
+		source: config.database.clone(),
+		keep_blocks: config.keep_blocks.clone(),
+		transaction_storage: config.transaction_storage.clone(),
+		offchain_indexing: config.offchain_worker.indexing_mode,
+	}
+}
+
 /// Create the initial parts of a full node.
 pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 	config: &Configuration,
@@ -335,18 +348,7 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 		.unwrap_or_default();
 
 	let (client, backend) = {
-		let db_config = sc_client_db::DatabaseSettings {
-			state_cache_size: config.state_cache_size,
-			state_cache_child_ratio:
-			config.state_cache_child_ratio.map(|v| (v, 100)),
-			state_pruning: config.state_pruning.clone(),
-			source: config.database.clone(),
-			keep_blocks: config.keep_blocks.clone(),
-			transaction_storage: config.transaction_storage.clone(),
-		};
-
-
-		let backend = new_db_backend(db_config)?;
+		let backend = new_db_backend(database_settings(config))?;
 
 		let extensions = sc_client_api::execution_extensions::ExecutionExtensions::new(
 			config.execution_strategies.clone(),
@@ -415,16 +417,7 @@ pub fn new_light_parts<TBl, TRtApi, TExecDisp>(
 	);
 
 	let db_storage = {
-		let db_settings = sc_client_db::DatabaseSettings {
-			state_cache_size: config.state_cache_size,
-			state_cache_child_ratio:
-				config.state_cache_child_ratio.map(|v| (v, 100)),
-			state_pruning: config.state_pruning.clone(),
-			source: config.database.clone(),
-			keep_blocks: config.keep_blocks.clone(),
-			transaction_storage: config.transaction_storage.clone(),
-		};
-		sc_client_db::light::LightStorage::new(db_settings)?
+		sc_client_db::light::LightStorage::new(database_settings(config))?
 	};
 	let light_blockchain = sc_light::new_light_blockchain(db_storage);
 	let fetch_checker = Arc::new(
@@ -648,6 +641,17 @@ pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 		MetricsService::new(telemetry.clone())
 	};
 
+	// Health endpoint.
+	#[cfg(not(target_os = "unknown"))]
+	{
+		if let Some(health_config) = config.health_config.clone() {
+			spawn_handle.spawn(
+				"health-endpoint",
+				crate::health::run(health_config, system_rpc_tx.clone()),
+			);
+		}
+	}
+
 	// Periodically updated metrics and telemetry updates.
 	spawn_handle.spawn("telemetry-periodic-send",
 		metrics_service.run(
@@ -874,7 +878,7 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 		TBl: BlockT,
 		TCl: ProvideRuntimeApi<TBl> + HeaderMetadata<TBl, Error=sp_blockchain::Error> + Chain<TBl> +
 		BlockBackend<TBl> + BlockIdTo<TBl, Error=sp_blockchain::Error> + ProofProvider<TBl> +
-		HeaderBackend<TBl> + BlockchainEvents<TBl> + 'static,
+		HeaderBackend<TBl> + BlockchainEvents<TBl> + AuxStore + 'static,
 		TExPool: MaintainedTransactionPool<Block=TBl, Hash = <TBl as BlockT>::Hash> + 'static,
 		TImpQu: ImportQueue<TBl> + 'static,
 {
@@ -971,6 +975,7 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 		block_request_protocol_config,
 		state_request_protocol_config,
 		light_client_request_protocol_config,
+		peer_store: Some(client.clone() as Arc<dyn sc_network::config::PeerStore>),
 	};
 
 	// Storage chains don't keep full block history and can't be synced in full mode.
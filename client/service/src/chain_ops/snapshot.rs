@@ -0,0 +1,353 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use crate::client::genesis::resolve_state_version_from_wasm;
+use blake2_rfc::blake2b::Blake2b;
+use codec::{Decode, Encode, IoReader};
+use log::info;
+use sc_client_api::{AuxStore, BlockBackend, StorageProvider, UsageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::traits::ReadRuntimeVersion;
+use sp_core::storage::{ChildInfo, Storage, StorageChild, well_known_keys};
+use sp_runtime::{Justifications, generic::BlockId};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor, Zero};
+use std::{io::{self, Read, Write}, sync::Arc};
+
+/// Bytes at the start of a snapshot.
+const SNAPSHOT_MAGIC: &[u8] = b"substrate-snapshot";
+
+/// Version of the snapshot format.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Length of the checksum at the end of a snapshot.
+const CHECKSUM_LEN: usize = 32;
+
+/// A finalized block from which a node can resume syncing.
+///
+/// It is written as `SNAPSHOT_MAGIC`, the format version, the SCALE encoded snapshot, the state
+/// of the block as a sequence of `Some(StateEntry)` terminated by `None`, and the blake2-256
+/// hash of everything before it. The state entries are written one at a time, so exporting
+/// never holds the state in memory. Reading a snapshot does, see [`Snapshot::read`].
+#[derive(Encode, Decode)]
+pub struct Snapshot<B: BlockT> {
+	/// Hash of the genesis block of the chain.
+	pub genesis_hash: B::Hash,
+	/// Headers of the blocks before the snapshot block, from the oldest to its parent.
+	pub ancestors: Vec<B::Header>,
+	/// Header of the snapshot block.
+	pub header: B::Header,
+	/// Justifications of the snapshot block.
+	pub justifications: Option<Justifications>,
+	/// Auxiliary data of the consensus engines at the snapshot block, such as the authority set.
+	pub aux: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// A key-value pair of the state of a snapshot block.
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+pub struct StateEntry {
+	/// Unprefixed storage key of the default child storage of the pair, `None` for the top
+	/// storage.
+	pub child: Option<Vec<u8>>,
+	/// Key of the pair.
+	pub key: Vec<u8>,
+	/// Value of the pair.
+	pub value: Vec<u8>,
+}
+
+/// Writes a snapshot to an output, computing its checksum along the way.
+pub struct SnapshotWriter<W> {
+	output: W,
+	hasher: Blake2b,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+	/// Start writing `snapshot` to `output`. The state of the snapshot block must then be
+	/// written with [`push`](Self::push) before calling [`finish`](Self::finish).
+	pub fn new<B: BlockT>(output: W, snapshot: &Snapshot<B>) -> Result<Self, Error> {
+		let mut writer = SnapshotWriter { output, hasher: Blake2b::new(CHECKSUM_LEN) };
+		writer.write(SNAPSHOT_MAGIC)?;
+		writer.write(&SNAPSHOT_VERSION.encode())?;
+		writer.write(&snapshot.encode())?;
+		Ok(writer)
+	}
+
+	/// Write an entry of the state of the snapshot block.
+	pub fn push(&mut self, entry: &StateEntry) -> Result<(), Error> {
+		self.write(&Some(entry).encode())
+	}
+
+	/// Write the end of the state and the checksum, returning the output.
+	pub fn finish(mut self) -> Result<W, Error> {
+		self.write(&None::<StateEntry>.encode())?;
+		let SnapshotWriter { mut output, hasher } = self;
+		output.write_all(hasher.finalize().as_bytes())?;
+		output.flush()?;
+		Ok(output)
+	}
+
+	fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+		self.hasher.update(data);
+		self.output.write_all(data)?;
+		Ok(())
+	}
+}
+
+/// Hashes everything read from the inner reader.
+struct HashingReader<R> {
+	input: R,
+	hasher: Blake2b,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let read = self.input.read(buf)?;
+		self.hasher.update(&buf[..read]);
+		Ok(read)
+	}
+}
+
+impl<B: BlockT> Snapshot<B> {
+	/// Read a snapshot and the state of its block from `input`, verifying its checksum.
+	///
+	/// The whole state is collected in memory, as the database imports it in a single
+	/// transaction. Reading a snapshot thus needs more memory than the size of its state.
+	pub fn read(input: impl Read) -> Result<(Self, Storage), Error> {
+		let mut reader = HashingReader { input, hasher: Blake2b::new(CHECKSUM_LEN) };
+		let mut magic = vec![0; SNAPSHOT_MAGIC.len()];
+		if reader.read_exact(&mut magic).is_err() || magic != SNAPSHOT_MAGIC {
+			return Err("Not a snapshot".into())
+		}
+
+		let mut input = IoReader(&mut reader);
+		let version = u32::decode(&mut input)
+			.map_err(|e| Error::Other(format!("Invalid snapshot version: {}", e)))?;
+		if version != SNAPSHOT_VERSION {
+			return Err(Error::Other(format!("Unsupported snapshot version {}", version)))
+		}
+		let snapshot = Self::decode(&mut input)
+			.map_err(|e| Error::Other(format!("Invalid snapshot: {}", e)))?;
+
+		let mut storage = Storage::default();
+		loop {
+			let entry = Option::<StateEntry>::decode(&mut input)
+				.map_err(|e| Error::Other(format!("Invalid snapshot state: {}", e)))?;
+			match entry {
+				Some(StateEntry { child: None, key, value }) => {
+					storage.top.insert(key, value);
+				},
+				Some(StateEntry { child: Some(child), key, value }) => {
+					storage.children_default.entry(child)
+						.or_insert_with_key(|child| StorageChild {
+							data: Default::default(),
+							child_info: ChildInfo::new_default(child),
+						})
+						.data
+						.insert(key, value);
+				},
+				None => break,
+			}
+		}
+
+		let HashingReader { mut input, hasher } = reader;
+		let mut checksum = [0; CHECKSUM_LEN];
+		input.read_exact(&mut checksum).map_err(|_| Error::from("Snapshot is truncated"))?;
+		if hasher.finalize().as_bytes() != checksum {
+			return Err("Snapshot checksum mismatch, the file is corrupted".into())
+		}
+		if input.read(&mut [0])? != 0 {
+			return Err("Unexpected data after the snapshot checksum".into())
+		}
+
+		Ok((snapshot, storage))
+	}
+}
+
+/// Export a snapshot of the given finalized `block` to `output`. If `block` is `None`, the last
+/// finalized block will be used.
+///
+/// Up to `ancestors` headers of the blocks before it are included, as consensus engines may look
+/// them up when importing the next blocks. `aux_keys` returns the keys of the auxiliary data
+/// that the consensus engines need to resume from the block with the given hash.
+pub fn export_snapshot<B, BA, C>(
+	client: Arc<C>,
+	block: Option<BlockId<B>>,
+	ancestors: u32,
+	aux_keys: impl Fn(&B::Hash) -> Vec<Vec<u8>>,
+	output: impl Write,
+) -> Result<(NumberFor<B>, B::Hash), Error>
+where
+	B: BlockT,
+	BA: sc_client_api::backend::Backend<B>,
+	C: UsageProvider<B> + StorageProvider<B, BA> + BlockBackend<B> + HeaderBackend<B> + AuxStore,
+{
+	let info = client.usage_info().chain;
+	let block = block.unwrap_or_else(|| BlockId::Hash(info.finalized_hash));
+	let header = client.header(block.clone())?
+		.ok_or_else(|| Error::Other(format!("Unknown block {}", block)))?;
+	let number = *header.number();
+	let hash = header.hash();
+	if number > info.finalized_number || client.hash(number)? != Some(hash) {
+		return Err(Error::Other(format!("Block {} is not finalized", block)))
+	}
+
+	let mut ancestor_headers = Vec::new();
+	let mut parent = header.clone();
+	while ancestor_headers.len() < ancestors as usize && !parent.number().is_zero() {
+		match client.header(BlockId::Hash(*parent.parent_hash()))? {
+			Some(ancestor) => {
+				ancestor_headers.push(ancestor.clone());
+				parent = ancestor;
+			},
+			None => break,
+		}
+	}
+	ancestor_headers.reverse();
+
+	let mut aux = Vec::new();
+	for key in aux_keys(&hash) {
+		if let Some(value) = client.get_aux(&key)? {
+			aux.push((key, value));
+		}
+	}
+
+	let block = BlockId::Hash(hash);
+	let snapshot = Snapshot::<B> {
+		genesis_hash: info.genesis_hash,
+		ancestors: ancestor_headers,
+		justifications: client.justifications(&block)?,
+		header,
+		aux,
+	};
+	let mut writer = SnapshotWriter::new(output, &snapshot)?;
+
+	// The key iterators stop at the first error. An incomplete state is still caught when the
+	// snapshot is imported, as it doesn't match the state root of the block.
+	info!("Exporting state of block #{} ({})...", number, hash);
+	let mut children = Vec::new();
+	for key in client.storage_keys_iter(&block, None, None)? {
+		// Child storage roots are recomputed from the child storages when importing.
+		if let Some(child) = key.0.strip_prefix(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+			children.push(child.to_vec());
+			continue
+		}
+		if let Some(value) = client.storage(&block, &key)? {
+			writer.push(&StateEntry { child: None, key: key.0, value: value.0 })?;
+		}
+	}
+	for child in children {
+		let child_info = ChildInfo::new_default(&child);
+		for key in client.child_storage_keys_iter(&block, child_info.clone(), None, None)? {
+			if let Some(value) = client.child_storage(&block, &child_info, &key)? {
+				writer.push(&StateEntry { child: Some(child.clone()), key: key.0, value: value.0 })?;
+			}
+		}
+	}
+	writer.finish()?;
+
+	Ok((number, hash))
+}
+
+/// Import a snapshot from `input` into an empty database.
+///
+/// The state of the snapshot must match the state root of its block. The state version is
+/// read from the runtime of the snapshot with the given `executor`.
+///
+/// The whole state is held in memory until it is committed, together with the trie nodes
+/// computed from it, so the import needs a few times the size of the state in memory.
+pub fn import_snapshot<B, E>(
+	backend: &sc_client_db::Backend<B>,
+	executor: &E,
+	input: impl Read,
+) -> Result<(NumberFor<B>, B::Hash), Error>
+where
+	B: BlockT,
+	E: ReadRuntimeVersion,
+{
+	let (snapshot, storage) = Snapshot::<B>::read(input)?;
+	let number = *snapshot.header.number();
+	let hash = snapshot.header.hash();
+	info!("Importing state of block #{} ({})...", number, hash);
+
+	let state_version = resolve_state_version_from_wasm(&storage, executor)?;
+	backend.import_snapshot(
+		snapshot.genesis_hash,
+		snapshot.ancestors,
+		snapshot.header,
+		snapshot.justifications,
+		storage,
+		state_version,
+		snapshot.aux,
+	)?;
+
+	Ok((number, hash))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use substrate_test_runtime_client::runtime::{Block, Header};
+
+	fn snapshot() -> Vec<u8> {
+		let snapshot = Snapshot::<Block> {
+			genesis_hash: Default::default(),
+			ancestors: Vec::new(),
+			header: Header::new(
+				1,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			),
+			justifications: None,
+			aux: vec![(b"key".to_vec(), b"value".to_vec())],
+		};
+		let mut writer = SnapshotWriter::new(Vec::new(), &snapshot).unwrap();
+		writer.push(&StateEntry { child: None, key: b"top".to_vec(), value: b"value".to_vec() })
+			.unwrap();
+		writer.push(&StateEntry {
+			child: Some(b"child".to_vec()),
+			key: b"key".to_vec(),
+			value: b"value".to_vec(),
+		}).unwrap();
+		writer.finish().unwrap()
+	}
+
+	#[test]
+	fn snapshot_is_read_back() {
+		let (read, storage) = Snapshot::<Block>::read(&snapshot()[..]).unwrap();
+		assert_eq!(*read.header.number(), 1);
+		assert_eq!(read.aux, vec![(b"key".to_vec(), b"value".to_vec())]);
+		assert_eq!(storage.top.get(&b"top"[..]), Some(&b"value".to_vec()));
+		let child = &storage.children_default[&b"child"[..]];
+		assert_eq!(child.child_info, ChildInfo::new_default(b"child"));
+		assert_eq!(child.data.get(&b"key"[..]), Some(&b"value".to_vec()));
+	}
+
+	#[test]
+	fn corrupted_snapshot_is_rejected() {
+		let mut data = snapshot();
+
+		// Last byte of the last state value.
+		let value = data.len() - CHECKSUM_LEN - 2;
+		data[value] ^= 1;
+		assert!(Snapshot::<Block>::read(&data[..]).is_err());
+		assert!(Snapshot::<Block>::read(&data[..10]).is_err());
+		assert!(Snapshot::<Block>::read(&data[..data.len() - 1]).is_err());
+	}
+}
@@ -126,6 +126,21 @@ impl<Block: BlockT, B, E> Clone for LocalCallExecutor<Block, B, E> where E: Clon
 	}
 }
 
+impl<Block, B, E> sp_core::traits::ReadRuntimeVersion for LocalCallExecutor<Block, B, E>
+where
+	B: backend::Backend<Block>,
+	E: CodeExecutor + RuntimeInfo + Clone + 'static,
+	Block: BlockT,
+{
+	fn read_runtime_version(
+		&self,
+		wasm_code: &[u8],
+		ext: &mut dyn sp_externalities::Externalities,
+	) -> Result<Vec<u8>, String> {
+		self.executor.read_runtime_version(wasm_code, ext)
+	}
+}
+
 impl<B, E, Block> CallExecutor<Block> for LocalCallExecutor<Block, B, E>
 where
 	B: backend::Backend<Block>,
@@ -337,8 +337,16 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		if info.finalized_state.is_none() {
 			let genesis_storage = build_genesis_storage.build_storage()
 				.map_err(sp_blockchain::Error::Storage)?;
+			let genesis_state_version = genesis::resolve_state_version_from_wasm(
+				&genesis_storage,
+				&executor,
+			)?;
 			let mut op = backend.begin_operation()?;
-			let state_root = op.set_genesis_state(genesis_storage, !config.no_genesis)?;
+			let state_root = op.set_genesis_state(
+				genesis_storage,
+				!config.no_genesis,
+				genesis_state_version,
+			)?;
 			let genesis_block = genesis::construct_genesis_block::<Block>(state_root.into());
 			info!("🔨 Initializing Genesis block/state (state: {}, header-hash: {})",
 				genesis_block.header().state_root(),
@@ -810,7 +818,11 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 							children_default: Default::default(),
 						};
 
-						let state_root = operation.op.reset_storage(storage)?;
+						let state_version = genesis::resolve_state_version_from_wasm(
+							&storage,
+							&self.executor,
+						)?;
+						let state_root = operation.op.reset_storage(storage, state_version)?;
 						if state_root != *import_headers.post().state_root() {
 							// State root mismatch when importing state. This should not happen in safe fast sync mode,
 							// but may happen in unsafe mode.
@@ -1849,7 +1861,12 @@ impl<B, E, Block, RA> sp_consensus::BlockImport<Block> for &Client<B, E, Block,
 		mut import_block: BlockImportParams<Block, backend::TransactionFor<B, Block>>,
 		new_cache: HashMap<CacheKeyId, Vec<u8>>,
 	) -> Result<ImportResult, Self::Error> {
-		let span = tracing::span!(tracing::Level::DEBUG, "import_block");
+		let span = tracing::span!(
+			tracing::Level::DEBUG,
+			"import_block",
+			block_number = ?import_block.header.number(),
+			block_hash = ?import_block.post_hash(),
+		);
 		let _enter = span.enter();
 
 		let storage_changes = match self.prepare_block_storage_changes(&mut import_block).map_err(|e| {
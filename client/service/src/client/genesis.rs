@@ -18,7 +18,38 @@
 
 //! Tool for creating the genesis block.
 
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Hash as HashT, Zero};
+use codec::Decode;
+use sp_core::{storage::well_known_keys, traits::ReadRuntimeVersion};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, Hash as HashT, Zero},
+	StateVersion, Storage,
+};
+use sp_state_machine::BasicExternalities;
+
+/// Return the state version given the genesis storage and executor.
+///
+/// The version is read from the runtime found at `:code`. Genesis storage without any runtime
+/// code uses the default state version.
+pub fn resolve_state_version_from_wasm<E>(
+	storage: &Storage,
+	executor: &E,
+) -> sp_blockchain::Result<StateVersion>
+where
+	E: ReadRuntimeVersion,
+{
+	if let Some(wasm) = storage.top.get(well_known_keys::CODE) {
+		// Only used to read the runtime version.
+		let mut ext = BasicExternalities::new_empty();
+		let version = executor
+			.read_runtime_version(wasm, &mut ext)
+			.map_err(sp_blockchain::Error::VersionInvalid)?;
+		let version = sp_version::RuntimeVersion::decode(&mut &version[..])
+			.map_err(|e| sp_blockchain::Error::VersionInvalid(e.to_string()))?;
+		Ok(version.state_version())
+	} else {
+		Ok(StateVersion::default())
+	}
+}
 
 /// Create a genesis block, given the initial storage.
 pub fn construct_genesis_block<
@@ -20,7 +20,8 @@
 
 pub use sc_client_db::{
 	Database, PruningMode, DatabaseSettingsSrc as DatabaseConfig,
-	KeepBlocks, TransactionStorageMode
+	KeepBlocks, TransactionStorageMode, OffchainIndexingMode,
+	CheckOptions as DatabaseCheckOptions,
 };
 pub use sc_network::Multiaddr;
 pub use sc_network::config::{
@@ -31,11 +32,16 @@ pub use sc_network::config::{
 pub use sc_executor::WasmExecutionMethod;
 pub use sc_client_api::execution_extensions::{ExecutionStrategies, ExecutionStrategy};
 
-use std::{io, future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, sync::Arc};
-pub use sc_transaction_pool::Options as TransactionPoolOptions;
+use std::{
+	io, future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, num::NonZeroU32,
+	sync::Arc,
+};
+pub use sc_transaction_pool::{Options as TransactionPoolOptions, JournalOptions as TransactionPoolJournalOptions};
 use sc_chain_spec::ChainSpec;
 use sp_core::crypto::SecretString;
 pub use sc_telemetry::TelemetryEndpoints;
+pub use sc_rpc_server::RpcLimits;
+pub use sc_informant::{OutputFormat as InformantOutputFormat, JsonOutput as InformantJsonOutput};
 use prometheus_endpoint::Registry;
 #[cfg(not(target_os = "unknown"))]
 use tempfile::TempDir;
@@ -97,8 +103,16 @@ pub struct Configuration {
 	pub rpc_methods: RpcMethods,
 	/// Maximum payload of rpc request/responses.
 	pub rpc_max_payload: Option<usize>,
+	/// Maximum size of rpc requests in megabytes. Defaults to `rpc_max_payload`.
+	pub rpc_max_request_size: Option<usize>,
+	/// Maximum size of rpc responses in megabytes. Defaults to `rpc_max_payload`.
+	pub rpc_max_response_size: Option<usize>,
+	/// Method filters and rate limits of the HTTP and WebSockets RPC servers.
+	pub rpc_limits: RpcLimits,
 	/// Prometheus endpoint configuration. `None` if disabled.
 	pub prometheus_config: Option<PrometheusConfig>,
+	/// Health endpoint configuration. `None` if disabled.
+	pub health_config: Option<HealthConfig>,
 	/// Telemetry service URL. `None` if disabled.
 	pub telemetry_endpoints: Option<TelemetryEndpoints>,
 	/// External WASM transport for the telemetry. If `Some`, when connection to a telemetry
@@ -110,8 +124,13 @@ pub struct Configuration {
 	pub offchain_worker: OffchainWorkerConfig,
 	/// Enable authoring even when offline.
 	pub force_authoring: bool,
+	/// Maximum size in bytes of the storage proof of authored blocks. Unlimited if `None`.
+	pub block_proof_size_limit: Option<usize>,
 	/// Disable GRANDPA when running in validator mode
 	pub disable_grandpa: bool,
+	/// Keep the GRANDPA justification of a finalized block every time finality crosses a multiple
+	/// of this many blocks. Disabled if `None`.
+	pub grandpa_justification_archive_period: Option<NonZeroU32>,
 	/// Development key seed.
 	///
 	/// When running in development mode, the seed will be used to generate authority keys by the keystore.
@@ -175,6 +194,8 @@ pub struct OffchainWorkerConfig {
 	pub enabled: bool,
 	/// allow writes from the runtime to the offchain worker database.
 	pub indexing_enabled: bool,
+	/// When the writes from the runtime are made to the offchain worker database.
+	pub indexing_mode: OffchainIndexingMode,
 }
 
 /// Configuration of the Prometheus endpoint.
@@ -199,6 +220,16 @@ impl PrometheusConfig {
 	}
 }
 
+/// Configuration of the health endpoint.
+#[derive(Debug, Clone)]
+pub struct HealthConfig {
+	/// Address to listen on.
+	pub address: SocketAddr,
+	/// Number of blocks the node can be behind the best block seen on the network while still
+	/// being reported as ready.
+	pub max_blocks_behind: u32,
+}
+
 impl Configuration {
 	/// Returns a string displaying the node role.
 	pub fn display_role(&self) -> String {
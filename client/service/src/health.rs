@@ -0,0 +1,177 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! HTTP endpoint reporting the health of the node.
+//!
+//! `/health` always answers `200 OK` with the same body as the `system_health` RPC, so that it
+//! can be used as a liveness probe. `/health/readiness` answers `503 Service Unavailable` instead
+//! when the node is syncing, is more than `max_blocks_behind` blocks behind the best block seen
+//! on the network, or has no peers while it should have some.
+
+use crate::config::HealthConfig;
+use futures::channel::oneshot;
+use hyper::{Body, Method, Response, Server, StatusCode, service::{make_service_fn, service_fn}};
+use log::{debug, error, info};
+use prometheus_endpoint::{Executor, Incoming};
+use sc_rpc::system::{Health, Request, SyncState};
+use sp_runtime::traits::{AtLeast32BitUnsigned, Block as BlockT};
+use sp_utils::mpsc::TracingUnboundedSender;
+use std::fmt::Display;
+
+/// Returns why a node with the given health and sync state is not ready, if it isn't.
+fn not_ready_reason<N: AtLeast32BitUnsigned + Display + Copy>(
+	health: &Health,
+	sync_state: &SyncState<N>,
+	max_blocks_behind: u32,
+) -> Option<String> {
+	if health.is_syncing {
+		return Some("major syncing".into())
+	}
+	if health.should_have_peers && health.peers == 0 {
+		return Some("no peers".into())
+	}
+	let behind = sync_state.highest_block
+		.map_or_else(N::zero, |highest| highest.saturating_sub(sync_state.current_block));
+	if behind > N::from(max_blocks_behind) {
+		return Some(format!("{} blocks behind", behind))
+	}
+	None
+}
+
+/// Serve the health endpoint, answering with the state reported through `system_rpc_tx`.
+pub(crate) async fn run<B: BlockT>(
+	config: HealthConfig,
+	system_rpc_tx: TracingUnboundedSender<Request<B>>,
+) {
+	let listener = match async_std::net::TcpListener::bind(config.address).await {
+		Ok(listener) => listener,
+		Err(e) => {
+			error!("Failed to start the health endpoint at {}: {}", config.address, e);
+			return
+		},
+	};
+	info!("💓 Health endpoint started at {}", config.address);
+
+	let max_blocks_behind = config.max_blocks_behind;
+	let service = make_service_fn(move |_| {
+		let system_rpc_tx = system_rpc_tx.clone();
+
+		async move {
+			Ok::<_, hyper::Error>(service_fn(move |request: hyper::Request<Body>| {
+				respond(request, max_blocks_behind, system_rpc_tx.clone())
+			}))
+		}
+	});
+
+	let server = Server::builder(Incoming(listener.incoming()))
+		.executor(Executor)
+		.serve(service);
+
+	if let Err(e) = server.await {
+		error!("Health endpoint stopped: {}", e);
+	}
+}
+
+async fn respond<B: BlockT>(
+	request: hyper::Request<Body>,
+	max_blocks_behind: u32,
+	system_rpc_tx: TracingUnboundedSender<Request<B>>,
+) -> Result<Response<Body>, hyper::http::Error> {
+	if request.method() != Method::GET {
+		return Response::builder().status(StatusCode::METHOD_NOT_ALLOWED).body(Body::empty())
+	}
+
+	let (status, health) = match request.uri().path() {
+		"/health" => match query(&system_rpc_tx, Request::Health).await {
+			Some(health) => (StatusCode::OK, Some(health)),
+			None => (StatusCode::SERVICE_UNAVAILABLE, None),
+		},
+		"/health/readiness" => {
+			let health = query(&system_rpc_tx, Request::Health).await;
+			let sync_state = query(&system_rpc_tx, Request::SyncState).await;
+			match (health, sync_state) {
+				(Some(health), Some(sync_state)) => {
+					match not_ready_reason(&health, &sync_state, max_blocks_behind) {
+						Some(reason) => {
+							debug!(target: "health", "Node is not ready: {}", reason);
+							(StatusCode::SERVICE_UNAVAILABLE, Some(health))
+						},
+						None => (StatusCode::OK, Some(health)),
+					}
+				},
+				_ => (StatusCode::SERVICE_UNAVAILABLE, None),
+			}
+		},
+		_ => return Response::builder().status(StatusCode::NOT_FOUND).body(Body::from("Not found.")),
+	};
+
+	match health {
+		Some(health) => Response::builder().status(status)
+			.header("Content-Type", "application/json")
+			.body(Body::from(to_json(&health))),
+		None => Response::builder().status(status).body(Body::empty()),
+	}
+}
+
+/// Send a request to the network worker and wait for its answer.
+async fn query<B: BlockT, T>(
+	system_rpc_tx: &TracingUnboundedSender<Request<B>>,
+	request: impl FnOnce(oneshot::Sender<T>) -> Request<B>,
+) -> Option<T> {
+	let (sender, receiver) = oneshot::channel();
+	system_rpc_tx.unbounded_send(request(sender)).ok()?;
+	receiver.await.ok()
+}
+
+fn to_json(health: &Health) -> String {
+	serde_json::to_string(health).expect("Health can always be serialized; qed")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn health(peers: usize, is_syncing: bool) -> Health {
+		Health { peers, is_syncing, should_have_peers: true }
+	}
+
+	fn sync_state(current_block: u64, highest_block: Option<u64>) -> SyncState<u64> {
+		SyncState { starting_block: 0, current_block, highest_block }
+	}
+
+	#[test]
+	fn readiness_follows_health_and_sync_state() {
+		assert_eq!(not_ready_reason(&health(3, false), &sync_state(10, Some(15)), 5), None);
+		assert_eq!(not_ready_reason(&health(3, false), &sync_state(10, None), 5), None);
+		assert_eq!(
+			not_ready_reason(&health(3, false), &sync_state(10, Some(16)), 5),
+			Some("6 blocks behind".into()),
+		);
+		assert_eq!(
+			not_ready_reason(&health(3, true), &sync_state(10, Some(10)), 5),
+			Some("major syncing".into()),
+		);
+		assert_eq!(
+			not_ready_reason(&health(0, false), &sync_state(10, Some(10)), 5),
+			Some("no peers".into()),
+		);
+
+		let alone = Health { should_have_peers: false, ..health(0, false) };
+		assert_eq!(not_ready_reason(&alone, &sync_state(10, None), 5), None);
+	}
+}
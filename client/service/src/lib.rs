@@ -28,6 +28,8 @@ pub mod error;
 
 mod metrics;
 mod builder;
+#[cfg(not(target_os = "unknown"))]
+mod health;
 #[cfg(feature = "test-helpers")]
 pub mod client;
 #[cfg(not(feature = "test-helpers"))]
@@ -38,9 +40,10 @@ use std::{io, pin::Pin};
 use std::net::SocketAddr;
 use std::collections::HashMap;
 use std::task::Poll;
+use std::time::Duration;
 
 use futures::{Future, FutureExt, Stream, StreamExt, stream, compat::*};
-use sc_network::PeerId;
+use sc_network::{PeerId, peer_store::BanTarget};
 use log::{warn, debug, error};
 use codec::{Encode, Decode};
 use sp_runtime::generic::BlockId;
@@ -50,7 +53,7 @@ use sp_utils::mpsc::TracingUnboundedReceiver;
 
 pub use self::error::Error;
 pub use self::builder::{
-	new_full_client, new_db_backend, new_client, new_full_parts, new_light_parts,
+	new_full_client, new_db_backend, database_settings, new_client, new_full_parts, new_light_parts,
 	spawn_tasks, build_network, build_offchain_workers,
 	BuildNetworkParams, KeystoreContainer, NetworkStarter, SpawnTasksParams, TFullClient, TLightClient,
 	TFullBackend, TLightBackend, TLightBackendWithHash, TLightClientWithBackend,
@@ -58,7 +61,7 @@ pub use self::builder::{
 };
 pub use config::{
 	BasePath, Configuration, DatabaseConfig, PruningMode, Role, RpcMethods, TaskExecutor, TaskType,
-	KeepBlocks, TransactionStorageMode,
+	KeepBlocks, TransactionStorageMode, OffchainIndexingMode, HealthConfig,
 };
 pub use sc_chain_spec::{
 	ChainSpec, GenericChainSpec, Properties, RuntimeGenesis, Extension as ChainSpecExtension,
@@ -68,6 +71,7 @@ pub use sc_transaction_pool_api::{TransactionPool, InPoolTransaction, error::Int
 pub use sc_transaction_pool::Options as TransactionPoolOptions;
 pub use sc_rpc::Metadata as RpcMetadata;
 pub use sc_executor::NativeExecutionDispatch;
+pub use sc_client_db::migrate_database;
 #[doc(hidden)]
 pub use std::{ops::Deref, result::Result, sync::Arc};
 #[doc(hidden)]
@@ -262,6 +266,36 @@ async fn build_network_future<
 							))),
 						};
 					}
+					sc_rpc::system::Request::NetworkBanPeer(target, duration_secs, sender) => {
+						let _ = match target.parse::<BanTarget>() {
+							Ok(target) => {
+								network.ban(target, duration_secs.map(Duration::from_secs));
+								sender.send(Ok(()))
+							}
+							Err(e) => sender.send(Err(sc_rpc::system::error::Error::MalformattedPeerArg(
+								e.to_string(),
+							))),
+						};
+					}
+					sc_rpc::system::Request::NetworkUnbanPeer(target, sender) => {
+						let _ = match target.parse::<BanTarget>() {
+							Ok(target) => {
+								network.unban(target);
+								sender.send(Ok(()))
+							}
+							Err(e) => sender.send(Err(sc_rpc::system::error::Error::MalformattedPeerArg(
+								e.to_string(),
+							))),
+						};
+					}
+					sc_rpc::system::Request::NetworkBans(sender) => {
+						let _ = sender.send(network.bans().into_iter().map(|ban|
+							sc_rpc::system::BanInfo {
+								target: ban.target,
+								expires_at: ban.expires_at,
+							}
+						).collect());
+					}
 					sc_rpc::system::Request::NetworkReservedPeers(sender) => {
 						let reserved_peers = network.reserved_peers();
 						let reserved_peers = reserved_peers
@@ -370,11 +404,26 @@ fn start_rpc_servers<
 		}
 	}
 
+	// A single limiter is shared by all the servers, so that a client is limited the same way
+	// whatever the transport it uses.
+	let limiter = (!config.rpc_limits.is_unrestricted())
+		.then(|| Arc::new(sc_rpc_server::RpcLimiter::new(config.rpc_limits.clone())));
+	let max_request_size = config.rpc_max_request_size.or(config.rpc_max_payload);
+	let max_response_size = config.rpc_max_response_size.or(config.rpc_max_payload);
+	let limited_middleware = |transport_label: &str| {
+		let middleware = sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), transport_label)
+			.with_max_response_size(max_response_size);
+		match limiter {
+			Some(ref limiter) => middleware.with_limiter(limiter.clone()),
+			None => middleware,
+		}
+	};
+
 	Ok(Box::new((
 		config.rpc_ipc.as_ref().map(|path| sc_rpc_server::start_ipc(
 			&*path, gen_handler(
 				sc_rpc::DenyUnsafe::No,
-				sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "ipc")
+				limited_middleware("ipc"),
 			)
 		)),
 		maybe_start_server(
@@ -385,9 +434,10 @@ fn start_rpc_servers<
 				config.rpc_cors.as_ref(),
 				gen_handler(
 					deny_unsafe(&address, &config.rpc_methods),
-					sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "http")
+					limited_middleware("http"),
 				),
-				config.rpc_max_payload
+				max_request_size,
+				config.rpc_limits.trust_proxy_headers,
 			),
 		)?.map(|s| waiting::HttpServer(Some(s))),
 		maybe_start_server(
@@ -398,9 +448,9 @@ fn start_rpc_servers<
 				config.rpc_cors.as_ref(),
 				gen_handler(
 					deny_unsafe(&address, &config.rpc_methods),
-					sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "ws")
+					limited_middleware("ws"),
 				),
-				config.rpc_max_payload
+				max_request_size,
 			),
 		)?.map(|s| waiting::WsServer(Some(s))),
 	)))
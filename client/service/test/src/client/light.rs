@@ -191,6 +191,16 @@ impl ProvideChtRoots<Block> for DummyStorage {
 
 struct DummyCallExecutor;
 
+impl sp_core::traits::ReadRuntimeVersion for DummyCallExecutor {
+	fn read_runtime_version(
+		&self,
+		_wasm_code: &[u8],
+		_ext: &mut dyn sp_externalities::Externalities,
+	) -> Result<Vec<u8>, String> {
+		unreachable!()
+	}
+}
+
 impl CallExecutor<Block> for DummyCallExecutor {
 	type Error = ClientError;
 
@@ -267,7 +277,7 @@ fn local_state_is_created_when_genesis_state_is_available() {
 	);
 	let mut op = backend.begin_operation().unwrap();
 	op.set_block_data(header0, None, None, None, NewBlockState::Final).unwrap();
-	op.set_genesis_state(Default::default(), true).unwrap();
+	op.set_genesis_state(Default::default(), true, Default::default()).unwrap();
 	backend.commit_operation(op).unwrap();
 
 	match backend.state_at(BlockId::Number(0)).unwrap() {
@@ -449,7 +459,7 @@ fn prepare_for_read_proof_check() -> (TestChecker, Header, StorageProof, u32) {
 	let remote_block_hash = remote_client.block_hash(0).unwrap().unwrap();
 	let mut remote_block_header = remote_client.header(&remote_block_id).unwrap().unwrap();
 	remote_block_header.state_root = remote_client.state_at(&remote_block_id).unwrap()
-		.storage_root(::std::iter::empty()).0.into();
+		.storage_root(::std::iter::empty(), Default::default()).0.into();
 
 	// 'fetch' read proof from remote node
 	let heap_pages = remote_client.storage(&remote_block_id, &StorageKey(well_known_keys::HEAP_PAGES.to_vec()))
@@ -493,7 +503,7 @@ fn prepare_for_read_child_proof_check() -> (TestChecker, Header, StorageProof, V
 	let remote_block_hash = remote_client.block_hash(0).unwrap().unwrap();
 	let mut remote_block_header = remote_client.header(&remote_block_id).unwrap().unwrap();
 	remote_block_header.state_root = remote_client.state_at(&remote_block_id).unwrap()
-		.storage_root(::std::iter::empty()).0.into();
+		.storage_root(::std::iter::empty(), Default::default()).0.into();
 
 	// 'fetch' child read proof from remote node
 	let child_value = remote_client.child_storage(
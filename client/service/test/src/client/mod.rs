@@ -32,7 +32,8 @@ use sc_client_api::{
 	StorageProvider, BlockBackend, in_mem, BlockchainEvents,
 };
 use sc_client_db::{
-	Backend, DatabaseSettings, DatabaseSettingsSrc, PruningMode, KeepBlocks, TransactionStorageMode
+	Backend, DatabaseSettings, DatabaseSettingsSrc, PruningMode, KeepBlocks, TransactionStorageMode,
+	OffchainIndexingMode,
 };
 use sc_block_builder::BlockBuilderProvider;
 use sc_service::client::{self, Client, LocalCallExecutor, new_in_mem};
@@ -1542,6 +1543,7 @@ fn doesnt_import_blocks_that_revert_finality() {
 			state_pruning: PruningMode::ArchiveAll,
 			keep_blocks: KeepBlocks::All,
 			transaction_storage: TransactionStorageMode::BlockBody,
+			offchain_indexing: OffchainIndexingMode::Immediate,
 			source: DatabaseSettingsSrc::RocksDb {
 				path: tmp.path().into(),
 				cache_size: 1024,
@@ -1746,6 +1748,7 @@ fn returns_status_for_pruned_blocks() {
 			state_pruning: PruningMode::keep_blocks(1),
 			keep_blocks: KeepBlocks::All,
 			transaction_storage: TransactionStorageMode::BlockBody,
+			offchain_indexing: OffchainIndexingMode::Immediate,
 			source: DatabaseSettingsSrc::RocksDb {
 				path: tmp.path().into(),
 				cache_size: 1024,
@@ -2217,3 +2220,53 @@ fn reorg_triggers_a_notification_even_for_sources_that_should_not_trigger_notifi
 	let tree_route = notification.tree_route.unwrap();
 	assert_eq!(tree_route.enacted()[0].hash, b1.hash());
 }
+
+#[test]
+fn imports_blocks_on_top_of_a_snapshot() {
+	let mut client = substrate_test_runtime_client::new();
+	let transfer = |nonce| Transfer {
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Ferdie.into(),
+		amount: 1,
+		nonce,
+	};
+
+	for nonce in 0..2 {
+		let mut builder = client.new_block(Default::default()).unwrap();
+		builder.push_transfer(transfer(nonce)).unwrap();
+		let block = builder.build().unwrap().block;
+		block_on(client.import(BlockOrigin::Own, block)).unwrap();
+	}
+	let snapshot_hash = client.chain_info().best_hash;
+	ClientExt::finalize_block(&client, BlockId::Hash(snapshot_hash), None).unwrap();
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_transfer(transfer(2)).unwrap();
+	let next = builder.build().unwrap().block;
+
+	let mut snapshot = Vec::new();
+	sc_service::chain_ops::export_snapshot(
+		Arc::new(client),
+		None,
+		1,
+		|_| Vec::new(),
+		&mut snapshot,
+	).unwrap();
+
+	let backend = Arc::new(Backend::<Block>::new_test(1000, 0));
+	let imported = sc_service::chain_ops::import_snapshot(&*backend, &executor(), &snapshot[..])
+		.unwrap();
+	assert_eq!(imported, (2, snapshot_hash));
+
+	let mut client = TestClientBuilder::with_backend(backend).build();
+	assert_eq!(client.chain_info().best_hash, snapshot_hash);
+	assert_eq!(client.chain_info().finalized_hash, snapshot_hash);
+
+	block_on(client.import(BlockOrigin::NetworkInitialSync, next.clone())).unwrap();
+	assert_eq!(client.chain_info().best_hash, next.hash());
+	assert_eq!(
+		client.runtime_api().balance_of(&BlockId::Hash(next.hash()), AccountKeyring::Ferdie.into())
+			.unwrap(),
+		3,
+	);
+}
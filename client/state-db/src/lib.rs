@@ -447,6 +447,22 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		self.non_canonical.revert_pending();
 	}
 
+	fn journal_blocks(&self) -> Vec<(BlockHash, u64)> {
+		// The pruning window ends with the last canonicalized block. The window numbers its
+		// blocks from zero, which is not the number of the first block it was given.
+		let last_canonicalized = self.non_canonical.last_canonicalized_block_number();
+		let mut blocks = match (&self.pruning, last_canonicalized) {
+			(Some(pruning), Some(last)) => {
+				let hashes = pruning.blocks();
+				let first = (last + 1).saturating_sub(hashes.len() as u64);
+				hashes.into_iter().zip(first..).collect()
+			},
+			_ => Vec::new(),
+		};
+		blocks.extend(self.non_canonical.blocks());
+		blocks
+	}
+
 	fn memory_info(&self) -> StateDbMemoryInfo {
 		StateDbMemoryInfo {
 			non_canonical: MemorySize::from_bytes(malloc_size(&self.non_canonical)),
@@ -546,17 +562,38 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 		self.db.write().revert_pending();
 	}
 
+	/// Returns the hashes and numbers of the blocks of which the state changes are journaled,
+	/// either to be canonicalized or to be pruned.
+	pub fn journal_blocks(&self) -> Vec<(BlockHash, u64)> {
+		self.db.read().journal_blocks()
+	}
+
 	/// Returns the current memory statistics of this instance.
 	pub fn memory_info(&self) -> StateDbMemoryInfo {
 		self.db.read().memory_info()
 	}
 }
 
+/// Convert the state journals of `db` for a backend that counts references to the nodes and
+/// that stores them under the keys returned by `map_key`.
+///
+/// Pruning journals are read as if `db` did not count references, so nodes that were inserted
+/// again after being deleted are no longer scheduled for deletion. Returns the records to write
+/// in place of the existing ones, under the same keys.
+pub fn ref_counted_journals<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	map_key: impl Fn(&Key) -> Key,
+) -> Result<Vec<(Vec<u8>, DBValue)>, Error<D::Error>> {
+	let mut journals = NonCanonicalOverlay::<BlockHash, Key>::new(db)?.mapped_journal(&map_key);
+	journals.extend(RefWindow::<BlockHash, Key>::new(db, true)?.ref_counted_journal(&map_key));
+	Ok(journals)
+}
+
 #[cfg(test)]
 mod tests {
 	use std::io;
 	use sp_core::H256;
-	use crate::{StateDb, PruningMode, Constraints};
+	use crate::{StateDb, PruningMode, Constraints, ref_counted_journals};
 	use crate::test::{make_db, make_changeset, TestDb};
 
 	fn make_test_db(settings: PruningMode) -> (TestDb, StateDb<H256, H256>) {
@@ -673,6 +710,37 @@ mod tests {
 		assert!(db.data_eq(&make_db(&[1, 21, 3, 921, 922, 93, 94])));
 	}
 
+	#[test]
+	fn journal_blocks_cover_window_and_overlay() {
+		let (_, sdb) = make_test_db(PruningMode::Constrained(Constraints {
+			max_blocks: Some(2),
+			max_mem: None,
+		}));
+		assert_eq!(
+			sdb.journal_blocks(),
+			vec![
+				(H256::from_low_u64_be(21), 2),
+				(H256::from_low_u64_be(3), 3),
+				(H256::from_low_u64_be(4), 4),
+			],
+		);
+	}
+
+	#[test]
+	fn ref_counted_journals_map_keys() {
+		let mode = PruningMode::Constrained(Constraints { max_blocks: Some(2), max_mem: None });
+		let (mut db, sdb) = make_test_db(mode.clone());
+		let map_key = |k: &H256| H256::from_low_u64_be(k.to_low_u64_be() + 1000);
+		let journals = ref_counted_journals::<H256, H256, _>(&db, map_key).unwrap();
+		assert_eq!(journals.len(), 3);
+		db.meta.extend(journals);
+
+		let state_db: StateDb<H256, H256> = StateDb::new(mode, true, &db).unwrap();
+		assert_eq!(state_db.journal_blocks(), sdb.journal_blocks());
+		let commit = state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(4)).unwrap();
+		assert!(commit.data.inserted.iter().any(|(k, _)| *k == H256::from_low_u64_be(1004)));
+	}
+
 	#[test]
 	fn detects_incompatible_mode() {
 		let mut db = make_db(&[]);
@@ -343,6 +343,44 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 			.unwrap_or_default()
 	}
 
+	/// Hashes and numbers of the blocks in the overlay, except the levels of which the
+	/// canonicalization is pending. Their canonical blocks are already in the pruning window.
+	pub fn blocks(&self) -> Vec<(BlockHash, u64)> {
+		let front = self.front_block_number();
+		self.levels
+			.iter()
+			.enumerate()
+			.skip(self.pending_canonicalizations.len())
+			.flat_map(|(i, level)| level.blocks.iter().map(move |r| (r.hash.clone(), front + i as u64)))
+			.collect()
+	}
+
+	/// Journal records of all the blocks in the overlay, with the keys of the nodes converted by
+	/// `map_key`.
+	pub fn mapped_journal(&self, map_key: &dyn Fn(&Key) -> Key) -> Vec<(Vec<u8>, DBValue)> {
+		self.levels
+			.iter()
+			.flat_map(|level| level.blocks.iter())
+			.map(|overlay| {
+				let record = JournalRecord {
+					hash: overlay.hash.clone(),
+					parent_hash: self.parents.get(&overlay.hash)
+						.expect("there is a parent entry for each entry in levels; qed")
+						.clone(),
+					inserted: overlay.inserted.iter()
+						.map(|k| {
+							let (_, value) = self.values.get(k)
+								.expect("values are kept until their block is discarded; qed");
+							(map_key(k), value.clone())
+						})
+						.collect(),
+					deleted: overlay.deleted.iter().map(|k| map_key(k)).collect(),
+				};
+				(overlay.journal_key.clone(), record.encode())
+			})
+			.collect()
+	}
+
 	/// Select a top-level root and canonicalized it. Discards all sibling subtrees and the root.
 	/// Returns a set of changes that need to be added to the DB.
 	pub fn canonicalize<E: fmt::Debug>(
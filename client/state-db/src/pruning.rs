@@ -26,7 +26,7 @@
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use codec::{Encode, Decode};
-use crate::{CommitSet, Error, MetaDb, to_meta_key, Hash};
+use crate::{CommitSet, DBValue, Error, MetaDb, to_meta_key, Hash};
 use log::{trace, warn};
 
 const LAST_PRUNED: &[u8] = b"last_pruned";
@@ -145,6 +145,31 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 		self.pending_number + self.pending_prunings as u64
 	}
 
+	/// Hashes of the blocks in the window, from the oldest to the last canonicalized block.
+	pub fn blocks(&self) -> Vec<BlockHash> {
+		self.death_rows.iter().skip(self.pending_prunings).map(|r| r.hash.clone()).collect()
+	}
+
+	/// Journal records of all the blocks in the window for a backend that counts references to
+	/// the nodes, with the keys of the nodes converted by `map_key`.
+	///
+	/// Such a backend keeps inserted nodes alive by itself, so the records only list the nodes
+	/// that are still to be deleted.
+	pub fn ref_counted_journal(&self, map_key: &dyn Fn(&Key) -> Key) -> Vec<(Vec<u8>, DBValue)> {
+		self.death_rows
+			.iter()
+			.skip(self.pending_prunings)
+			.map(|row| {
+				let record = JournalRecord::<BlockHash, Key> {
+					hash: row.hash.clone(),
+					inserted: Vec::new(),
+					deleted: row.deleted.iter().map(|k| map_key(k)).collect(),
+				};
+				(row.journal_key.clone(), record.encode())
+			})
+			.collect()
+	}
+
 	pub fn have_block(&self, hash: &BlockHash) -> bool {
 		self.death_rows.iter().skip(self.pending_prunings).any(|r| r.hash == *hash)
 	}
@@ -24,12 +24,13 @@
 //!
 //! See `sp-tracing` for examples on how to use tracing.
 //!
-//! Currently we only provide `Log` (default).
+//! We provide `Log` (default), and `Otlp` which exports spans to an OpenTelemetry collector.
 
 #![warn(missing_docs)]
 
 pub mod block;
 pub mod logging;
+pub mod otlp;
 
 use rustc_hash::FxHashMap;
 use serde::ser::{Serialize, SerializeMap, Serializer};
@@ -62,6 +63,8 @@ pub struct ProfilingLayer {
 pub enum TracingReceiver {
 	/// Output to logger
 	Log,
+	/// Export to an OpenTelemetry collector
+	Otlp(otlp::OtlpConfig),
 }
 
 impl Default for TracingReceiver {
@@ -76,6 +79,10 @@ pub trait TraceHandler: Send + Sync {
 	fn handle_span(&self, span: SpanDatum);
 	/// Process a `TraceEvent`
 	fn handle_event(&self, event: TraceEvent);
+	/// Process a `SpanDatum` that is filtered out, and so is not passed to `handle_span`
+	///
+	/// Its children may already have been passed to `handle_span`.
+	fn handle_filtered_span(&self, _span: SpanDatum) {}
 }
 
 /// Represents a tracing event, complete with values
@@ -207,6 +214,9 @@ impl ProfilingLayer {
 	pub fn new(receiver: TracingReceiver, targets: &str) -> Self {
 		match receiver {
 			TracingReceiver::Log => Self::new_with_handler(Box::new(LogTraceHandler), targets),
+			TracingReceiver::Otlp(config) => {
+				Self::new_with_handler(Box::new(otlp::OtlpTraceHandler::new(config)), targets)
+			},
 		}
 	}
 
@@ -341,6 +351,8 @@ where
 					}
 					if self.check_target(&span_datum.target, &span_datum.level) {
 						self.trace_handler.handle_span(span_datum);
+					} else {
+						self.trace_handler.handle_filtered_span(span_datum);
 					}
 				} else {
 					self.trace_handler.handle_span(span_datum);
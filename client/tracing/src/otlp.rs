@@ -0,0 +1,548 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Export of tracing spans to an OpenTelemetry collector.
+//!
+//! Spans are sent with the OTLP/HTTP protocol, JSON encoded, to a collector that is usually
+//! running next to the node. Only plain `http://` endpoints are supported.
+//!
+//! Spans are reported when they exit, children before their parents, so they are kept until
+//! their root span exits and then exported as a single trace. The children of a span that is
+//! filtered out are attached to its parent instead. The `block_number` and
+//! `block_hash` values of a span are added to all its descendants, which includes the spans of
+//! the runtime relayed through `sp-tracing`.
+
+use crate::{SpanDatum, TraceEvent, TraceHandler};
+use parking_lot::{Mutex, MutexGuard};
+use serde_json::{json, Value};
+use std::{
+	collections::HashMap,
+	io::{self, BufRead, BufReader, Write},
+	net::{TcpStream, ToSocketAddrs},
+	sync::mpsc,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Default endpoint of the collector.
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:4318/v1/traces";
+
+/// Values of a span that are added to all its descendants.
+const INHERITED_VALUES: &[&str] = &["block_number", "block_hash"];
+
+/// Maximum number of spans kept while waiting for their root span to exit. Once reached, the
+/// pending spans are exported as traces of their own.
+const MAX_PENDING_SPANS: usize = 100_000;
+
+/// Maximum number of traces waiting to be exported. Traces are dropped once reached.
+const MAX_QUEUED_TRACES: usize = 1_024;
+
+/// Maximum number of spans sent in a single request.
+const MAX_BATCH_SPANS: usize = 10_000;
+
+/// Maximum time a trace waits before being exported.
+const EXPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Timeout of the requests to the collector.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configuration of the OTLP exporter.
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+	/// Endpoint of the collector, such as [`DEFAULT_ENDPOINT`].
+	pub endpoint: String,
+	/// Name of the service reported with the spans.
+	pub service_name: String,
+}
+
+impl Default for OtlpConfig {
+	fn default() -> Self {
+		OtlpConfig {
+			endpoint: DEFAULT_ENDPOINT.into(),
+			service_name: "substrate".into(),
+		}
+	}
+}
+
+/// Address and path of a collector endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+	/// Host and port of the collector.
+	pub address: String,
+	/// Path the spans are sent to.
+	pub path: String,
+}
+
+impl Endpoint {
+	/// Parse an `http://host[:port][/path]` endpoint.
+	pub fn parse(endpoint: &str) -> Result<Self, String> {
+		let rest = endpoint.strip_prefix("http://")
+			.ok_or_else(|| format!("Invalid OTLP endpoint `{}`, expected an http URL", endpoint))?;
+		let (authority, path) = match rest.find('/') {
+			Some(i) => (&rest[..i], &rest[i..]),
+			None => (rest, "/v1/traces"),
+		};
+		if authority.is_empty() {
+			return Err(format!("Invalid OTLP endpoint `{}`, missing host", endpoint))
+		}
+		let has_port = authority.rsplit(':').next()
+			.map_or(false, |port| port.parse::<u16>().is_ok());
+		let address = if has_port {
+			authority.to_owned()
+		} else {
+			format!("{}:80", authority)
+		};
+		Ok(Endpoint { address, path: path.to_owned() })
+	}
+}
+
+/// `TraceHandler` exporting spans to an OpenTelemetry collector.
+pub struct OtlpTraceHandler {
+	pending: Mutex<Pending>,
+	traces: Mutex<Option<mpsc::SyncSender<Vec<Value>>>>,
+	trace_id_seed: u64,
+}
+
+#[derive(Default)]
+struct Pending {
+	/// Subtrees of spans that exited, by id of their parent.
+	children: HashMap<u64, Vec<PendingSpan>>,
+	/// Number of spans in `children`.
+	len: usize,
+	/// Number of traces exported so far.
+	traces: u64,
+}
+
+impl Pending {
+	/// Remove the subtrees of the children of the span with the given id.
+	fn take_children(&mut self, id: u64) -> Vec<PendingSpan> {
+		let children = self.children.remove(&id).unwrap_or_default();
+		self.len -= children.iter().map(|child| child.len).sum::<usize>();
+		children
+	}
+}
+
+struct PendingSpan {
+	span: SpanDatum,
+	start: u128,
+	end: u128,
+	children: Vec<PendingSpan>,
+	len: usize,
+}
+
+impl OtlpTraceHandler {
+	/// Create a handler exporting spans as configured by `config`.
+	///
+	/// The spans are exported from a background thread. If the endpoint is invalid, an error is
+	/// logged and spans are dropped.
+	pub fn new(config: OtlpConfig) -> Self {
+		let traces = match Endpoint::parse(&config.endpoint) {
+			Ok(endpoint) => {
+				let (sender, receiver) = mpsc::sync_channel(MAX_QUEUED_TRACES);
+				let spawned = std::thread::Builder::new()
+					.name("otlp-exporter".into())
+					.spawn(move || export(endpoint, config.service_name, receiver));
+				match spawned {
+					Ok(_) => Some(sender),
+					Err(e) => {
+						log::error!(target: "otlp", "Failed to start the OTLP exporter: {}", e);
+						None
+					},
+				}
+			},
+			Err(e) => {
+				log::error!(target: "otlp", "{}", e);
+				None
+			},
+		};
+		OtlpTraceHandler {
+			pending: Mutex::new(Default::default()),
+			traces: Mutex::new(traces),
+			trace_id_seed: unix_nanos(SystemTime::now()) as u64,
+		}
+	}
+
+	/// Add `nodes` to the pending children of the span with id `parent_id`, or export them as
+	/// traces if they have no parent.
+	fn attach(
+		&self,
+		mut pending: MutexGuard<'_, Pending>,
+		parent_id: Option<u64>,
+		nodes: Vec<PendingSpan>,
+	) {
+		let mut complete = Vec::new();
+		match parent_id {
+			Some(parent_id) => {
+				pending.len += nodes.iter().map(|node| node.len).sum::<usize>();
+				pending.children.entry(parent_id).or_default().extend(nodes);
+				if pending.len > MAX_PENDING_SPANS {
+					complete.extend(pending.children.drain().flat_map(|(_, spans)| spans));
+					pending.len = 0;
+				}
+			},
+			None => complete = nodes,
+		}
+		let first_trace = pending.traces;
+		pending.traces += complete.len() as u64;
+		drop(pending);
+
+		for (trace, root) in (first_trace..).zip(complete) {
+			self.export_trace(root, trace);
+		}
+	}
+
+	fn export_trace(&self, root: PendingSpan, trace: u64) {
+		let mut trace_id = [0u8; 16];
+		trace_id[..8].copy_from_slice(&self.trace_id_seed.to_be_bytes());
+		trace_id[8..].copy_from_slice(&trace.to_be_bytes());
+		let mut spans = Vec::with_capacity(root.len);
+		encode_span(root, &to_hex(&trace_id), &HashMap::new(), &mut spans);
+
+		let mut traces = self.traces.lock();
+		if let Some(sender) = traces.as_ref() {
+			match sender.try_send(spans) {
+				Ok(()) => (),
+				Err(mpsc::TrySendError::Full(_)) => {
+					log::debug!(target: "otlp", "Too many traces waiting for export, dropping one");
+				},
+				Err(mpsc::TrySendError::Disconnected(_)) => *traces = None,
+			}
+		}
+	}
+}
+
+impl TraceHandler for OtlpTraceHandler {
+	fn handle_span(&self, span: SpanDatum) {
+		let end = unix_nanos(SystemTime::now());
+		let start = end.saturating_sub(span.overall_time.as_nanos());
+		let mut pending = self.pending.lock();
+		let children = pending.take_children(span.id.into_u64());
+		let len = children.iter().map(|child| child.len).sum::<usize>() + 1;
+		let parent_id = span.parent_id.as_ref().map(|id| id.into_u64());
+		let node = PendingSpan { span, start, end, children, len };
+		self.attach(pending, parent_id, vec![node]);
+	}
+
+	fn handle_event(&self, _event: TraceEvent) {}
+
+	fn handle_filtered_span(&self, span: SpanDatum) {
+		let mut pending = self.pending.lock();
+		let mut children = pending.take_children(span.id.into_u64());
+		if children.is_empty() {
+			return
+		}
+		for child in &mut children {
+			child.span.parent_id = span.parent_id.clone();
+		}
+		self.attach(pending, span.parent_id.map(|id| id.into_u64()), children);
+	}
+}
+
+/// Append the OTLP encoding of `node` and its descendants to `spans`.
+fn encode_span(
+	node: PendingSpan,
+	trace_id: &str,
+	inherited: &HashMap<&'static str, Value>,
+	spans: &mut Vec<Value>,
+) {
+	let PendingSpan { span, start, end, children, .. } = node;
+	let mut inherited = inherited.clone();
+	for key in INHERITED_VALUES {
+		if let Some(value) = attribute_value(&span, key) {
+			inherited.insert(*key, value);
+		}
+	}
+
+	let mut attributes = vec![
+		attribute("code.namespace", json!({ "stringValue": span.target })),
+		attribute("level", json!({ "stringValue": span.level.to_string() })),
+	];
+	if span.line != 0 {
+		attributes.push(attribute("code.lineno", json!({ "intValue": span.line.to_string() })));
+	}
+	for (key, value) in &span.values.bool_values {
+		attributes.push(attribute(key, json!({ "boolValue": value })));
+	}
+	for (key, value) in &span.values.i64_values {
+		attributes.push(attribute(key, json!({ "intValue": value.to_string() })));
+	}
+	for (key, value) in &span.values.u64_values {
+		attributes.push(attribute(key, int_value(*value)));
+	}
+	for (key, value) in &span.values.string_values {
+		attributes.push(attribute(key, json!({ "stringValue": value })));
+	}
+	for (key, value) in &inherited {
+		if attribute_value(&span, key).is_none() {
+			attributes.push(attribute(key, value.clone()));
+		}
+	}
+
+	let mut encoded = json!({
+		"traceId": trace_id,
+		"spanId": to_hex(&span.id.into_u64().to_be_bytes()),
+		"name": span.name,
+		// SPAN_KIND_INTERNAL
+		"kind": 1,
+		"startTimeUnixNano": start.to_string(),
+		"endTimeUnixNano": end.to_string(),
+		"attributes": attributes,
+	});
+	if let Some(parent_id) = &span.parent_id {
+		encoded["parentSpanId"] = to_hex(&parent_id.into_u64().to_be_bytes()).into();
+	}
+	spans.push(encoded);
+
+	for child in children {
+		encode_span(child, trace_id, &inherited, spans);
+	}
+}
+
+fn attribute_value(span: &SpanDatum, key: &str) -> Option<Value> {
+	let values = &span.values;
+	values.string_values.get(key).map(|value| json!({ "stringValue": value }))
+		.or_else(|| values.u64_values.get(key).map(|value| int_value(*value)))
+		.or_else(|| values.i64_values.get(key).map(|value| json!({ "intValue": value.to_string() })))
+}
+
+fn attribute(key: &str, value: Value) -> Value {
+	json!({ "key": key, "value": value })
+}
+
+fn int_value(value: u64) -> Value {
+	// OTLP integers are signed.
+	if value <= i64::MAX as u64 {
+		json!({ "intValue": value.to_string() })
+	} else {
+		json!({ "stringValue": value.to_string() })
+	}
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+	time.duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_nanos())
+}
+
+/// Export the traces received from `traces` until all the senders are dropped.
+fn export(endpoint: Endpoint, service_name: String, traces: mpsc::Receiver<Vec<Value>>) {
+	let service_name = attribute("service.name", json!({ "stringValue": service_name }));
+	let resource = json!({ "attributes": [service_name] });
+	while let Ok(mut spans) = traces.recv() {
+		let deadline = Instant::now() + EXPORT_INTERVAL;
+		let mut disconnected = false;
+		while spans.len() < MAX_BATCH_SPANS {
+			let timeout = deadline.saturating_duration_since(Instant::now());
+			match traces.recv_timeout(timeout) {
+				Ok(trace) => spans.extend(trace),
+				Err(mpsc::RecvTimeoutError::Timeout) => break,
+				Err(mpsc::RecvTimeoutError::Disconnected) => {
+					disconnected = true;
+					break
+				},
+			}
+		}
+
+		let request = json!({
+			"resourceSpans": [{
+				"resource": resource,
+				"instrumentationLibrarySpans": [{
+					"instrumentationLibrary": { "name": "sc-tracing" },
+					"spans": spans,
+				}],
+			}],
+		});
+		if let Err(e) = post(&endpoint, request.to_string().as_bytes()) {
+			log::debug!(target: "otlp", "Failed to export spans to {}: {}", endpoint.address, e);
+		}
+		if disconnected {
+			break
+		}
+	}
+}
+
+/// Send `body` to the collector and check that it was accepted.
+fn post(endpoint: &Endpoint, body: &[u8]) -> io::Result<()> {
+	let address = endpoint.address.to_socket_addrs()?.next()
+		.ok_or_else(|| io::Error::new(io::ErrorKind::Other, "Failed to resolve the address"))?;
+	let mut stream = TcpStream::connect_timeout(&address, REQUEST_TIMEOUT)?;
+	stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+	stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+	write!(
+		stream,
+		"POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+			Content-Length: {}\r\nConnection: close\r\n\r\n",
+		endpoint.path,
+		endpoint.address,
+		body.len(),
+	)?;
+	stream.write_all(body)?;
+
+	let mut status = String::new();
+	BufReader::new(stream).read_line(&mut status)?;
+	match status.split(' ').nth(1) {
+		Some(code) if code.starts_with('2') => Ok(()),
+		_ => Err(io::Error::new(
+			io::ErrorKind::Other,
+			format!("Collector responded with `{}`", status.trim()),
+		)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ProfilingLayer;
+	use std::{io::Read, net::TcpListener};
+	use tracing_subscriber::layer::SubscriberExt;
+
+	/// Accept a single export request and return its body.
+	fn receive_export(listener: &TcpListener) -> Value {
+		let (stream, _) = listener.accept().unwrap();
+		let mut reader = BufReader::new(stream);
+		let mut content_length = 0;
+		loop {
+			let mut line = String::new();
+			reader.read_line(&mut line).unwrap();
+			let line = line.trim();
+			if line.is_empty() {
+				break
+			}
+			if let Some(length) = line.strip_prefix("Content-Length: ") {
+				content_length = length.parse().unwrap();
+			}
+		}
+		let mut body = vec![0; content_length];
+		reader.read_exact(&mut body).unwrap();
+		reader.get_mut().write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+		serde_json::from_slice(&body).unwrap()
+	}
+
+	fn attribute<'a>(span: &'a Value, key: &str) -> Option<&'a Value> {
+		span["attributes"].as_array().unwrap().iter()
+			.find(|attribute| attribute["key"] == key)
+			.map(|attribute| &attribute["value"])
+	}
+
+	#[test]
+	fn parses_endpoints() {
+		assert_eq!(
+			Endpoint::parse(DEFAULT_ENDPOINT),
+			Ok(Endpoint { address: "localhost:4318".into(), path: "/v1/traces".into() }),
+		);
+		assert_eq!(
+			Endpoint::parse("http://collector"),
+			Ok(Endpoint { address: "collector:80".into(), path: "/v1/traces".into() }),
+		);
+		assert!(Endpoint::parse("https://collector:4318").is_err());
+		assert!(Endpoint::parse("http:///v1/traces").is_err());
+	}
+
+	#[test]
+	fn exports_spans_to_collector() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let handler = OtlpTraceHandler::new(OtlpConfig {
+			endpoint: format!("http://{}/v1/traces", listener.local_addr().unwrap()),
+			service_name: "test-node".into(),
+		});
+		let layer = ProfilingLayer::new_with_handler(Box::new(handler), "test_target,runtime");
+		let subscriber = tracing_subscriber::fmt().with_writer(std::io::sink).finish().with(layer);
+		let _sub_guard = tracing::subscriber::set_default(subscriber);
+
+		{
+			let import = tracing::info_span!(
+				target: "test_target",
+				"import_block",
+				block_number = 7u64,
+				block_hash = "0x01",
+			);
+			let _import = import.enter();
+			// A runtime span, as relayed by `sp-tracing`.
+			let wasm = tracing::info_span!(
+				target: "test_target",
+				"wasm_tracing",
+				name = "apply_extrinsic",
+				target = "runtime",
+			);
+			let _wasm = wasm.enter();
+		}
+
+		let request = receive_export(&listener);
+		let resource_spans = &request["resourceSpans"][0];
+		assert_eq!(
+			resource_spans["resource"]["attributes"][0]["value"]["stringValue"],
+			"test-node",
+		);
+		let spans = resource_spans["instrumentationLibrarySpans"][0]["spans"]
+			.as_array()
+			.unwrap();
+		assert_eq!(spans.len(), 2);
+		let (import, wasm) = (&spans[0], &spans[1]);
+		assert_eq!(import["name"], "import_block");
+		assert_eq!(wasm["name"], "apply_extrinsic");
+		assert_eq!(wasm["traceId"], import["traceId"]);
+		assert_eq!(wasm["parentSpanId"], import["spanId"]);
+		assert!(import.get("parentSpanId").is_none());
+		assert_eq!(attribute(wasm, "code.namespace").unwrap()["stringValue"], "runtime");
+		assert_eq!(attribute(wasm, "wasm").unwrap()["boolValue"], true);
+		assert_eq!(attribute(wasm, "block_number").unwrap()["intValue"], "7");
+		assert_eq!(attribute(wasm, "block_hash").unwrap()["stringValue"], "0x01");
+	}
+
+	#[test]
+	fn children_of_filtered_spans_are_attached_to_their_grandparent() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let handler = OtlpTraceHandler::new(OtlpConfig {
+			endpoint: format!("http://{}/v1/traces", listener.local_addr().unwrap()),
+			service_name: "test-node".into(),
+		});
+		let layer = ProfilingLayer::new_with_handler(Box::new(handler), "test_target,runtime");
+		let subscriber = tracing_subscriber::fmt().with_writer(std::io::sink).finish().with(layer);
+		let _sub_guard = tracing::subscriber::set_default(subscriber);
+
+		{
+			let import = tracing::info_span!(target: "test_target", "import_block");
+			let _import = import.enter();
+			let filtered = tracing::info_span!(
+				target: "test_target",
+				"wasm_tracing",
+				name = "execute_block",
+				target = "frame_executive",
+			);
+			let _filtered = filtered.enter();
+			let wasm = tracing::info_span!(
+				target: "test_target",
+				"wasm_tracing",
+				name = "apply_extrinsic",
+				target = "runtime",
+			);
+			let _wasm = wasm.enter();
+		}
+
+		let request = receive_export(&listener);
+		let spans = request["resourceSpans"][0]["instrumentationLibrarySpans"][0]["spans"]
+			.as_array()
+			.unwrap();
+		assert_eq!(spans.len(), 2);
+		let (import, wasm) = (&spans[0], &spans[1]);
+		assert_eq!(import["name"], "import_block");
+		assert_eq!(wasm["name"], "apply_extrinsic");
+		assert_eq!(wasm["traceId"], import["traceId"]);
+		assert_eq!(wasm["parentSpanId"], import["spanId"]);
+	}
+}
@@ -25,6 +25,7 @@ use std::{
 	hash::Hash,
 	sync::Arc,
 	pin::Pin,
+	time::Duration,
 };
 use futures::{Future, Stream};
 use serde::{Deserialize, Serialize};
@@ -56,6 +57,48 @@ impl PoolStatus {
 	}
 }
 
+/// Queue of the pool in which a transaction is waiting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PoolQueue {
+	/// All the requirements of the transaction are satisfied, it can be included in a block.
+	Ready,
+	/// The transaction is waiting for some of its requirements to be provided.
+	Future,
+}
+
+/// Detailed information about a transaction in the pool, as part of [`PoolInspection`].
+#[derive(Debug, Clone)]
+pub struct InspectedTransaction<Hash> {
+	/// Hash of the transaction.
+	pub hash: Hash,
+	/// Queue in which the transaction is waiting.
+	pub queue: PoolQueue,
+	/// Priority of the transaction.
+	pub priority: TransactionPriority,
+	/// Number of the block after which the transaction is no longer valid.
+	pub valid_till: TransactionLongevity,
+	/// Tags required by the transaction.
+	pub requires: Vec<TransactionTag>,
+	/// Tags provided by the transaction.
+	pub provides: Vec<TransactionTag>,
+	/// Whether the transaction is propagated to other peers.
+	pub propagate: bool,
+	/// Source of the transaction.
+	pub source: TransactionSource,
+	/// How long the transaction has been in the pool.
+	pub in_pool_for: Duration,
+}
+
+/// Content of the pool, as returned by [`TransactionPool::inspect`].
+#[derive(Debug, Clone)]
+pub struct PoolInspection<Hash> {
+	/// Transactions of the pool, both ready and future.
+	pub transactions: Vec<InspectedTransaction<Hash>>,
+	/// Hashes of the transactions that are temporarily banned from entering the pool.
+	pub banned: Vec<Hash>,
+}
+
 /// Possible transaction status events.
 ///
 /// This events are being emitted by `TransactionPool` watchers,
@@ -247,6 +290,11 @@ pub trait TransactionPool: Send + Sync {
 
 	/// Return specific ready transaction by hash, if there is one.
 	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>>;
+
+	// *** RPC
+	/// Returns detailed information about all the transactions in the pool, both ready and future,
+	/// and the transactions that are banned from it.
+	fn inspect(&self) -> PoolInspection<TxHash<Self>>;
 }
 
 /// Events that the transaction pool listens for.
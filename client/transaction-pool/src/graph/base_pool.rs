@@ -37,7 +37,8 @@ use sp_runtime::transaction_validity::{
 	TransactionPriority as Priority,
 	TransactionSource as Source,
 };
-use sc_transaction_pool_api::{error, PoolStatus, InPoolTransaction};
+use sc_transaction_pool_api::{error, PoolQueue, PoolStatus, InPoolTransaction};
+use wasm_timer::Instant;
 
 use super::{
 	future::{FutureTransactions, WaitingTransaction},
@@ -370,6 +371,20 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		self.future.all()
 	}
 
+	/// Returns all the transactions in the pool, along with the queue they are waiting in and the
+	/// time at which they were imported.
+	pub fn all_with_import_time(&self) -> Vec<(Arc<Transaction<Hash, Ex>>, PoolQueue, Instant)> {
+		self.ready.all_with_import_time()
+			.into_iter()
+			.map(|(tx, imported_at)| (tx, PoolQueue::Ready, imported_at))
+			.chain(
+				self.future.all_with_import_time()
+					.into_iter()
+					.map(|(tx, imported_at)| (tx, PoolQueue::Future, imported_at))
+			)
+			.collect()
+	}
+
 	/// Returns pool transactions given list of hashes.
 	///
 	/// Includes both ready and future pool. For every hash in the `hashes`
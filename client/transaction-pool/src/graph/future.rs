@@ -229,6 +229,11 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 		self.waiting.values().map(|waiting| &*waiting.transaction)
 	}
 
+	/// Returns all the transactions of the queue along with the time at which they were imported.
+	pub fn all_with_import_time(&self) -> Vec<(Arc<Transaction<Hash, Ex>>, Instant)> {
+		self.waiting.values().map(|waiting| (waiting.transaction.clone(), waiting.imported_at)).collect()
+	}
+
 	/// Removes and returns all future transactions.
 	pub fn clear(&mut self) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		self.wanted_tags.clear();
@@ -113,6 +113,8 @@ pub struct Options {
 	pub future: base::Limit,
 	/// Reject future transactions.
 	pub reject_future_transactions: bool,
+	/// Journal keeping the transactions across restarts. Disabled if `None`.
+	pub journal: Option<crate::journal::JournalOptions>,
 }
 
 impl Default for Options {
@@ -127,6 +129,7 @@ impl Default for Options {
 				total_bytes: 1 * 1024 * 1024,
 			},
 			reject_future_transactions: false,
+			journal: None,
 		}
 	}
 }
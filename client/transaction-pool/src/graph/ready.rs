@@ -25,6 +25,7 @@ use std::{
 
 use serde::Serialize;
 use log::trace;
+use wasm_timer::Instant;
 use sp_runtime::traits::Member;
 use sp_runtime::transaction_validity::{
 	TransactionTag as Tag,
@@ -89,6 +90,8 @@ pub struct ReadyTx<Hash, Ex> {
 	/// Some transactions might be already pruned from the queue,
 	/// so when we compute ready set we may consider this transactions ready earlier.
 	pub requires_offset: usize,
+	/// Time of import to the pool, including the time spent in the future queue.
+	pub imported_at: Instant,
 }
 
 impl<Hash: Clone, Ex> Clone for ReadyTx<Hash, Ex> {
@@ -97,6 +100,7 @@ impl<Hash: Clone, Ex> Clone for ReadyTx<Hash, Ex> {
 			transaction: self.transaction.clone(),
 			unlocks: self.unlocks.clone(),
 			requires_offset: self.requires_offset,
+			imported_at: self.imported_at,
 		}
 	}
 }
@@ -183,6 +187,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 		self.insertion_id += 1;
 		let insertion_id = self.insertion_id;
 		let hash = tx.transaction.hash.clone();
+		let imported_at = tx.imported_at;
 		let transaction = tx.transaction;
 
 		let (replaced, unlocks) = self.replace_previous(&transaction)?;
@@ -225,6 +230,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 			transaction,
 			unlocks,
 			requires_offset,
+			imported_at,
 		});
 
 		Ok(replaced)
@@ -238,6 +244,16 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 			.fold(None, f)
 	}
 
+	/// Returns all the transactions of the queue, in no particular order, along with the time at
+	/// which they were imported to the pool.
+	pub fn all_with_import_time(&self) -> Vec<(Arc<Transaction<Hash, Ex>>, Instant)> {
+		self.ready
+			.read()
+			.values()
+			.map(|tx| (tx.transaction.transaction.clone(), tx.imported_at))
+			.collect()
+	}
+
 	/// Returns true if given transaction is part of the queue.
 	pub fn contains(&self, hash: &Hash) -> bool {
 		self.ready.read().contains_key(hash)
@@ -61,6 +61,11 @@ impl<Hash: hash::Hash + Eq + Clone> PoolRotator<Hash> {
 		self.banned_until.read().contains_key(hash)
 	}
 
+	/// Returns the hashes of the currently banned extrinsics.
+	pub fn banned(&self) -> Vec<Hash> {
+		self.banned_until.read().keys().cloned().collect()
+	}
+
 	/// Bans given set of hashes.
 	pub fn ban(&self, now: &Instant, hashes: impl IntoIterator<Item=Hash>) {
 		let mut banned = self.banned_until.write();
@@ -158,6 +163,7 @@ mod tests {
 
 		// then
 		assert!(rotator.is_banned(&hash));
+		assert_eq!(rotator.banned(), vec![hash]);
 	}
 
 
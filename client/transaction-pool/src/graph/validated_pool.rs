@@ -29,7 +29,7 @@ use sp_runtime::{
 	traits::{self, SaturatedConversion},
 	transaction_validity::{TransactionTag as Tag, ValidTransaction, TransactionSource},
 };
-use sc_transaction_pool_api::{error, PoolStatus};
+use sc_transaction_pool_api::{error, InspectedTransaction, PoolInspection, PoolStatus};
 use wasm_timer::Instant;
 use futures::channel::mpsc::{channel, Sender};
 use retain_mut::RetainMut;
@@ -614,6 +614,38 @@ impl<B: ChainApi> ValidatedPool<B> {
 		self.pool.read().ready()
 	}
 
+	/// Returns the hash, the source and the extrinsic of all the transactions in the pool, both
+	/// ready and future.
+	pub fn all_transactions(&self) -> Vec<(ExtrinsicHash<B>, TransactionSource, ExtrinsicFor<B>)> {
+		let pool = self.pool.read();
+		pool.ready()
+			.map(|tx| (tx.hash.clone(), tx.source, tx.data.clone()))
+			.chain(pool.futures().map(|tx| (tx.hash.clone(), tx.source, tx.data.clone())))
+			.collect()
+	}
+
+	/// Returns detailed information about all the transactions in the pool, ready and future, and
+	/// the hashes of the banned transactions.
+	pub fn inspect(&self) -> PoolInspection<ExtrinsicHash<B>> {
+		let now = Instant::now();
+		let transactions = self.pool.read().all_with_import_time()
+			.into_iter()
+			.map(|(tx, queue, imported_at)| InspectedTransaction {
+				hash: tx.hash.clone(),
+				queue,
+				priority: tx.priority,
+				valid_till: tx.valid_till,
+				requires: tx.requires.clone(),
+				provides: tx.provides.clone(),
+				propagate: tx.propagate,
+				source: tx.source,
+				in_pool_for: now.saturating_duration_since(imported_at),
+			})
+			.collect();
+
+		PoolInspection { transactions, banned: self.rotator.banned() }
+	}
+
 	/// Returns a Vec of hashes and extrinsics in the future pool.
 	pub fn futures(&self) -> Vec<(ExtrinsicHash<B>, ExtrinsicFor<B>)> {
 		self.pool.read().futures()
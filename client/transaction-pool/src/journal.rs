@@ -0,0 +1,432 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Journal of the transactions of the pool.
+//!
+//! The journal keeps a copy of the ready and future transactions of the pool, so that they are
+//! not lost when the node restarts. It is brought in sync with the content of the pool after
+//! every new best block, which records the newly accepted transactions and forgets about those
+//! that have been included or dropped. The journal is written from a background thread, so that
+//! the maintenance of the pool doesn't wait for the disk. On startup, the journaled transactions
+//! are re-validated against the best block and imported again.
+
+use std::{collections::HashMap, fs, hash, io, path::PathBuf, sync::Arc, thread, time::Duration};
+
+use codec::{Decode, Encode};
+use parking_lot::{Condvar, Mutex};
+use sp_runtime::transaction_validity::TransactionSource;
+use wasm_timer::{SystemTime, UNIX_EPOCH};
+
+/// Key under which the journal is saved in the auxiliary storage of the client.
+const JOURNAL_AUX_KEY: &[u8] = b"sc_transaction_pool_journal";
+
+/// Configuration of the transactions journal.
+#[derive(Debug, Clone)]
+pub struct JournalOptions {
+	/// Maximum total size, in bytes, of the journaled transactions. The oldest transactions are
+	/// left out of the journal when the limit is reached.
+	pub max_bytes: usize,
+	/// Transactions that have been in the pool for longer than this aren't journaled, and aren't
+	/// imported again on startup.
+	pub max_age: Duration,
+	/// File where the journal is written. If `None`, the journal is kept in the database of the
+	/// client.
+	pub path: Option<PathBuf>,
+}
+
+impl Default for JournalOptions {
+	fn default() -> Self {
+		Self {
+			max_bytes: 10 * 1024 * 1024,
+			max_age: Duration::from_secs(3 * 60 * 60),
+			path: None,
+		}
+	}
+}
+
+/// Storage backing a [`Journal`].
+pub trait JournalStore: Send + Sync {
+	/// Loads the data previously passed to [`JournalStore::store`], if any.
+	fn load(&self) -> Option<Vec<u8>>;
+
+	/// Overwrites the stored data.
+	fn store(&self, data: &[u8]);
+}
+
+/// Journal store writing to a file.
+pub struct FileJournalStore(PathBuf);
+
+impl FileJournalStore {
+	/// Creates a store writing to the given file.
+	pub fn new(path: PathBuf) -> Self {
+		FileJournalStore(path)
+	}
+}
+
+impl JournalStore for FileJournalStore {
+	fn load(&self) -> Option<Vec<u8>> {
+		match fs::read(&self.0) {
+			Ok(data) => Some(data),
+			Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+			Err(e) => {
+				log::warn!(target: "txpool", "Failed to read the journal {:?}: {}", self.0, e);
+				None
+			}
+		}
+	}
+
+	fn store(&self, data: &[u8]) {
+		// Write to a temporary file first, so that a crash can't leave a truncated journal.
+		let tmp = self.0.with_extension("tmp");
+		let result = fs::write(&tmp, data).and_then(|_| fs::rename(&tmp, &self.0));
+		if let Err(e) = result {
+			log::warn!(target: "txpool", "Failed to write the journal {:?}: {}", self.0, e);
+		}
+	}
+}
+
+/// Journal store writing to the auxiliary storage of the client.
+pub struct AuxJournalStore<Client>(Arc<Client>);
+
+impl<Client> AuxJournalStore<Client> {
+	/// Creates a store writing to the auxiliary storage of the given client.
+	pub fn new(client: Arc<Client>) -> Self {
+		AuxJournalStore(client)
+	}
+}
+
+impl<Client> JournalStore for AuxJournalStore<Client>
+	where Client: sc_client_api::AuxStore + Send + Sync,
+{
+	fn load(&self) -> Option<Vec<u8>> {
+		self.0.get_aux(JOURNAL_AUX_KEY).unwrap_or_else(|e| {
+			log::warn!(target: "txpool", "Failed to read the journal: {:?}", e);
+			None
+		})
+	}
+
+	fn store(&self, data: &[u8]) {
+		if let Err(e) = self.0.insert_aux(&[(JOURNAL_AUX_KEY, data)], &[]) {
+			log::warn!(target: "txpool", "Failed to write the journal: {:?}", e);
+		}
+	}
+}
+
+/// A transaction as written in the journal.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+struct Entry {
+	/// Source of the transaction when it was first accepted.
+	source: TransactionSource,
+	/// UNIX timestamp, in seconds, at which the transaction has been first seen.
+	inserted_at: u64,
+	/// SCALE-encoded extrinsic.
+	extrinsic: Vec<u8>,
+}
+
+/// Returns the current UNIX timestamp, in seconds.
+pub(crate) fn unix_time() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Content of the journal waiting to be written by a [`Writer`].
+#[derive(Default)]
+struct WriterState {
+	/// Latest content of the journal, if it hasn't been written yet.
+	queued: Option<Vec<u8>>,
+	/// Set when the journal is dropped. The thread exits after writing the queued content.
+	closed: bool,
+}
+
+/// Writes the content of the journal to its store from a background thread.
+///
+/// If the journal changes again before the previous content has been written, only the latest
+/// content is written.
+struct Writer {
+	state: Arc<(Mutex<WriterState>, Condvar)>,
+	thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Writer {
+	fn spawn(store: Box<dyn JournalStore>) -> Self {
+		let state = Arc::new((Mutex::new(WriterState::default()), Condvar::new()));
+		let thread_state = state.clone();
+		let thread = thread::Builder::new()
+			.name("txpool-journal".into())
+			.spawn(move || {
+				let (state, condvar) = &*thread_state;
+				loop {
+					let data = {
+						let mut state = state.lock();
+						while state.queued.is_none() && !state.closed {
+							condvar.wait(&mut state);
+						}
+						match state.queued.take() {
+							Some(data) => data,
+							None => return,
+						}
+					};
+					store.store(&data);
+				}
+			})
+			.expect("Failed to spawn the transaction pool journal thread");
+
+		Writer { state, thread: Some(thread) }
+	}
+
+	/// Queues `data` to be written, replacing the content that hasn't been written yet.
+	fn queue(&self, data: Vec<u8>) {
+		let (state, condvar) = &*self.state;
+		state.lock().queued = Some(data);
+		condvar.notify_one();
+	}
+}
+
+impl Drop for Writer {
+	fn drop(&mut self) {
+		let (state, condvar) = &*self.state;
+		state.lock().closed = true;
+		condvar.notify_one();
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
+
+/// Journal of the transactions of the pool.
+pub(crate) struct Journal<Hash, Ex> {
+	writer: Writer,
+	options: JournalOptions,
+	entries: HashMap<Hash, Entry>,
+	/// Transactions loaded on startup and not yet imported back into the pool. While this isn't
+	/// `None`, the journal isn't updated, so that the loaded transactions aren't lost if the node
+	/// stops again before importing them.
+	pending: Option<Vec<(TransactionSource, Ex)>>,
+}
+
+impl<Hash: hash::Hash + Eq + Clone, Ex: Encode + Decode> Journal<Hash, Ex> {
+	/// Loads the journal from the given store.
+	///
+	/// Transactions that are too old or that can't be decoded are discarded. `hash_of` returns
+	/// the hash of an extrinsic.
+	pub fn load(
+		store: Box<dyn JournalStore>,
+		options: JournalOptions,
+		hash_of: impl Fn(&Ex) -> Hash,
+		now: u64,
+	) -> Self {
+		let stored = store.load()
+			.map(|data| Vec::<Entry>::decode(&mut &data[..]).unwrap_or_else(|e| {
+				log::warn!(target: "txpool", "Discarding malformed journal: {:?}", e);
+				Vec::new()
+			}))
+			.unwrap_or_default();
+
+		let mut entries = HashMap::new();
+		let mut pending = Vec::new();
+		for entry in stored {
+			if entry.inserted_at.saturating_add(options.max_age.as_secs()) < now {
+				continue
+			}
+			let xt = match Ex::decode(&mut &entry.extrinsic[..]) {
+				Ok(xt) => xt,
+				Err(_) => continue,
+			};
+			entries.insert(hash_of(&xt), entry.clone());
+			pending.push((entry.source, xt));
+		}
+
+		log::debug!(target: "txpool", "Loaded {} transactions from the journal", pending.len());
+
+		Journal { writer: Writer::spawn(store), options, entries, pending: Some(pending) }
+	}
+
+	/// Returns the transactions loaded on startup, that must be imported into the pool.
+	///
+	/// Returns an empty list on all the calls but the first.
+	pub fn take_pending(&mut self) -> Vec<(TransactionSource, Ex)> {
+		self.pending.take().unwrap_or_default()
+	}
+
+	/// Brings the journal in sync with the content of the pool, then writes it to the store if
+	/// it has changed.
+	///
+	/// Transactions that aren't in the journal yet are recorded, and those that are no longer in
+	/// the pool are forgotten. Has no effect until [`Journal::take_pending`] has been called.
+	pub fn update<'a>(
+		&mut self,
+		transactions: impl IntoIterator<Item = (Hash, TransactionSource, &'a Ex)>,
+		now: u64,
+	) where Ex: 'a {
+		if self.pending.is_some() {
+			return
+		}
+
+		let mut changed = false;
+		let mut entries = HashMap::new();
+		for (hash, source, xt) in transactions {
+			// Transactions resubmitted from retracted blocks are known to the rest of the network.
+			if source == TransactionSource::InBlock {
+				continue
+			}
+			let entry = match self.entries.remove(&hash) {
+				Some(entry) => entry,
+				None => {
+					changed = true;
+					Entry { source, inserted_at: now, extrinsic: xt.encode() }
+				},
+			};
+			if entry.inserted_at.saturating_add(self.options.max_age.as_secs()) >= now {
+				entries.insert(hash, entry);
+			} else {
+				changed = true;
+			}
+		}
+		// The remaining entries have been included in a block or dropped from the pool.
+		changed |= !self.entries.is_empty();
+
+		// Leave the oldest transactions out of the journal if it exceeds the size limit.
+		let mut by_age = entries.iter()
+			.map(|(hash, entry)| (entry.inserted_at, entry.extrinsic.len(), hash))
+			.collect::<Vec<_>>();
+		by_age.sort_by(|a, b| b.0.cmp(&a.0));
+		let mut total_bytes = 0usize;
+		let over_limit = by_age.into_iter()
+			.filter(|(_, len, _)| {
+				total_bytes = total_bytes.saturating_add(*len);
+				total_bytes > self.options.max_bytes
+			})
+			.map(|(_, _, hash)| hash.clone())
+			.collect::<Vec<_>>();
+		for hash in over_limit {
+			changed = true;
+			entries.remove(&hash);
+		}
+
+		self.entries = entries;
+		if changed {
+			self.write();
+		}
+	}
+
+	fn write(&self) {
+		let mut entries = self.entries.values().collect::<Vec<_>>();
+		entries.sort_by_key(|entry| entry.inserted_at);
+		self.writer.queue(entries.encode());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Default, Clone)]
+	struct MemoryStore(Arc<Mutex<Option<Vec<u8>>>>);
+
+	impl JournalStore for MemoryStore {
+		fn load(&self) -> Option<Vec<u8>> {
+			self.0.lock().clone()
+		}
+
+		fn store(&self, data: &[u8]) {
+			*self.0.lock() = Some(data.to_vec());
+		}
+	}
+
+	type TestJournal = Journal<u64, Vec<u8>>;
+
+	fn hash_of(xt: &Vec<u8>) -> u64 {
+		xt[0] as u64
+	}
+
+	fn options(max_bytes: usize) -> JournalOptions {
+		JournalOptions { max_bytes, max_age: Duration::from_secs(100), path: None }
+	}
+
+	fn load(store: &MemoryStore, max_bytes: usize, now: u64) -> TestJournal {
+		Journal::load(Box::new(store.clone()), options(max_bytes), hash_of, now)
+	}
+
+	#[test]
+	fn records_and_restores_transactions() {
+		let store = MemoryStore::default();
+		let mut journal = load(&store, 1024, 0);
+		assert!(journal.take_pending().is_empty());
+
+		let (a, b, c) = (vec![1u8, 1], vec![2u8, 2], vec![3u8, 3]);
+		journal.update(vec![
+			(1, TransactionSource::External, &a),
+			(2, TransactionSource::Local, &b),
+			(3, TransactionSource::InBlock, &c),
+		], 10);
+
+		// `1` has been included in a block.
+		journal.update(vec![(2, TransactionSource::Local, &b)], 20);
+		drop(journal);
+
+		let mut journal = load(&store, 1024, 30);
+		assert_eq!(journal.take_pending(), vec![(TransactionSource::Local, b.clone())]);
+		assert!(journal.take_pending().is_empty());
+	}
+
+	#[test]
+	fn pending_transactions_are_kept_until_taken() {
+		let store = MemoryStore::default();
+		let mut journal = load(&store, 1024, 0);
+		journal.take_pending();
+		let a = vec![1u8];
+		journal.update(vec![(1, TransactionSource::External, &a)], 0);
+		drop(journal);
+
+		// The node restarts, then stops before importing the transactions.
+		let mut journal = load(&store, 1024, 0);
+		journal.update(vec![], 0);
+		drop(journal);
+
+		let mut journal = load(&store, 1024, 0);
+		assert_eq!(journal.take_pending(), vec![(TransactionSource::External, a)]);
+	}
+
+	#[test]
+	fn enforces_age_and_size_limits() {
+		let store = MemoryStore::default();
+		let mut journal = load(&store, 10, 0);
+		journal.take_pending();
+
+		let old = vec![1u8; 4];
+		journal.update(vec![(1, TransactionSource::External, &old)], 0);
+
+		let (a, b) = (vec![2u8; 4], vec![3u8; 4]);
+		journal.update(vec![
+			(1, TransactionSource::External, &old),
+			(2, TransactionSource::External, &a),
+			(3, TransactionSource::External, &b),
+		], 50);
+		drop(journal);
+
+		// Only two transactions fit in 10 bytes, and the oldest one is left out.
+		let mut restored = load(&store, 10, 60).take_pending();
+		restored.sort_by_key(|(_, xt)| xt.clone());
+		assert_eq!(restored, vec![
+			(TransactionSource::External, a.clone()),
+			(TransactionSource::External, b.clone()),
+		]);
+
+		// After `max_age`, nothing is restored.
+		assert!(load(&store, 10, 200).take_pending().is_empty());
+	}
+}
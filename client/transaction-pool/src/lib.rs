@@ -28,6 +28,7 @@ mod revalidation;
 mod metrics;
 
 pub mod error;
+pub mod journal;
 
 /// Common types for testing the transaction pool
 #[cfg(feature = "test-helpers")]
@@ -39,6 +40,7 @@ pub mod test_helpers {
 }
 
 pub use graph::{Options, Transaction};
+pub use journal::{JournalOptions, JournalStore};
 pub use crate::api::{FullChainApi, LightChainApi};
 use std::{collections::{HashMap, HashSet}, sync::Arc, pin::Pin, convert::TryInto};
 use futures::{prelude::*, future::{self, ready}, channel::oneshot};
@@ -52,7 +54,7 @@ use sp_core::traits::SpawnEssentialNamed;
 use sc_transaction_pool_api::{
 	TransactionPool, PoolStatus, ImportNotificationStream, TxHash, TransactionFor,
 	TransactionStatusStreamFor, MaintainedTransactionPool, PoolFuture, ChainEvent,
-	TransactionSource,
+	TransactionSource, PoolInspection,
 };
 use graph::{IsValidator, ExtrinsicHash};
 use wasm_timer::Instant;
@@ -87,8 +89,11 @@ pub struct BasicPool<PoolApi, Block>
 	revalidation_queue: Arc<revalidation::RevalidationQueue<PoolApi>>,
 	ready_poll: Arc<Mutex<ReadyPoll<ReadyIteratorFor<PoolApi>, Block>>>,
 	metrics: PrometheusMetrics,
+	journal: Option<Arc<Mutex<JournalFor<PoolApi>>>>,
 }
 
+type JournalFor<PoolApi> = journal::Journal<ExtrinsicHash<PoolApi>, graph::ExtrinsicFor<PoolApi>>;
+
 struct ReadyPoll<T, Block: BlockT> {
 	updated_at: NumberFor<Block>,
 	pollers: Vec<(NumberFor<Block>, oneshot::Sender<T>)>,
@@ -187,6 +192,7 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 				revalidation_strategy: Arc::new(Mutex::new(RevalidationStrategy::Always)),
 				ready_poll: Default::default(),
 				metrics: Default::default(),
+				journal: None,
 			},
 			background_task,
 			notifier,
@@ -235,9 +241,20 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 			)),
 			ready_poll: Arc::new(Mutex::new(ReadyPoll::new(best_block_number))),
 			metrics: PrometheusMetrics::new(prometheus),
+			journal: None,
 		}
 	}
 
+	/// Enables the journal of the transactions, stored in the given `store`.
+	///
+	/// The transactions previously written in the journal are re-validated and imported at the
+	/// next new best block.
+	pub fn enable_journal(&mut self, store: Box<dyn JournalStore>, options: JournalOptions) {
+		let pool = self.pool.clone();
+		let journal = journal::Journal::load(store, options, |xt| pool.hash_of(xt), journal::unix_time());
+		self.journal = Some(Arc::new(Mutex::new(journal)));
+	}
+
 	/// Gets shared reference to the underlying pool.
 	pub fn pool(&self) -> &Arc<graph::Pool<PoolApi>> {
 		&self.pool
@@ -363,6 +380,10 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 	fn ready(&self) -> ReadyIteratorFor<PoolApi> {
 		Box::new(self.pool.validated_pool().ready())
 	}
+
+	fn inspect(&self) -> PoolInspection<TxHash<Self>> {
+		self.pool.validated_pool().inspect()
+	}
 }
 
 impl<Block, Client, Fetcher> LightPool<Block, Client, Fetcher>
@@ -401,6 +422,7 @@ where
 		+ sp_runtime::traits::BlockIdTo<Block>
 		+ sc_client_api::ExecutorProvider<Block>
 		+ sc_client_api::UsageProvider<Block>
+		+ sc_client_api::AuxStore
 		+ Send
 		+ Sync
 		+ 'static,
@@ -415,7 +437,8 @@ where
 		client: Arc<Client>,
 	) -> Arc<Self> {
 		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus, &spawner));
-		let pool = Arc::new(Self::with_revalidation_type(
+		let journal_options = options.journal.clone();
+		let mut pool = Self::with_revalidation_type(
 			options,
 			is_validator,
 			pool_api,
@@ -423,7 +446,17 @@ where
 			RevalidationType::Full,
 			spawner,
 			client.usage_info().chain.best_number,
-		));
+		);
+
+		if let Some(journal_options) = journal_options {
+			let store: Box<dyn JournalStore> = match &journal_options.path {
+				Some(path) => Box::new(journal::FileJournalStore::new(path.clone())),
+				None => Box::new(journal::AuxJournalStore::new(client.clone())),
+			};
+			pool.enable_journal(store, journal_options);
+		}
+
+		let pool = Arc::new(pool);
 
 		// make transaction pool available for off-chain runtime calls.
 		client.execution_extensions().register_transaction_pool(&pool);
@@ -607,6 +640,59 @@ async fn prune_known_txs_for_block<Block: BlockT, Api: graph::ChainApi<Block = B
 	hashes
 }
 
+/// Imports the transactions loaded from the journal on startup.
+async fn restore_journaled_transactions<Api: graph::ChainApi>(
+	at: &BlockId<Api::Block>,
+	pool: &graph::Pool<Api>,
+	transactions: Vec<(TransactionSource, graph::ExtrinsicFor<Api>)>,
+) {
+	if transactions.is_empty() {
+		return
+	}
+
+	for source in [TransactionSource::Local, TransactionSource::External].iter() {
+		let xts = transactions.iter()
+			.filter(|(s, _)| s == source)
+			.map(|(_, xt)| xt.clone())
+			.collect::<Vec<_>>();
+		if xts.is_empty() {
+			continue
+		}
+
+		let count = xts.len();
+		match pool.submit_at(at, *source, xts).await {
+			Ok(results) => log::info!(
+				target: "txpool",
+				"Restored {} of {} {:?} transactions from the journal",
+				results.iter().filter(|r| r.is_ok()).count(),
+				count,
+				source,
+			),
+			Err(e) => log::warn!(
+				target: "txpool",
+				"Failed to restore transactions from the journal: {:?}",
+				e,
+			),
+		}
+	}
+}
+
+impl<PoolApi, Block> Drop for BasicPool<PoolApi, Block>
+	where
+		Block: BlockT,
+		PoolApi: graph::ChainApi<Block=Block>,
+{
+	fn drop(&mut self) {
+		if let Some(journal) = &self.journal {
+			let transactions = self.pool.validated_pool().all_transactions();
+			journal.lock().update(
+				transactions.iter().map(|(hash, source, xt)| (hash.clone(), *source, xt)),
+				journal::unix_time(),
+			);
+		}
+	}
+}
+
 impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 	where
 		Block: BlockT,
@@ -640,6 +726,7 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 				let revalidation_queue = self.revalidation_queue.clone();
 				let ready_poll = self.ready_poll.clone();
 				let metrics = self.metrics.clone();
+				let journal = self.journal.clone();
 
 				async move {
 					// We keep track of everything we prune so that later we won't add
@@ -755,6 +842,17 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 
 						revalidation_strategy.lock().clear();
 					}
+
+					if let Some(journal) = journal {
+						let pending = journal.lock().take_pending();
+						restore_journaled_transactions(&id, &*pool, pending).await;
+
+						let transactions = pool.validated_pool().all_transactions();
+						journal.lock().update(
+							transactions.iter().map(|(hash, source, xt)| (hash.clone(), *source, xt)),
+							journal::unix_time(),
+						);
+					}
 				}.boxed()
 			}
 			ChainEvent::Finalized { hash } => {
@@ -97,6 +97,42 @@ fn late_nonce_should_be_queued() {
 	assert_eq!(pending, vec![209, 210]);
 }
 
+#[test]
+fn inspect_should_report_queues_and_tags() {
+	let (pool, _guard, _notifier) = maintained_pool();
+
+	let future = block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 210))).unwrap();
+	let inspected = pool.inspect().transactions;
+	assert_eq!(inspected.len(), 1);
+	assert_eq!(inspected[0].hash, future);
+	assert_eq!(inspected[0].queue, sc_transaction_pool_api::PoolQueue::Future);
+	assert_eq!(inspected[0].provides, vec![vec![210]]);
+	assert_eq!(inspected[0].requires, vec![vec![209]]);
+
+	std::thread::sleep(std::time::Duration::from_millis(10));
+	let ready = block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 209))).unwrap();
+	let inspected = pool.inspect().transactions;
+	assert_eq!(inspected.len(), 2);
+	assert!(inspected.iter().all(|tx| tx.queue == sc_transaction_pool_api::PoolQueue::Ready));
+
+	// The promoted transaction keeps the time at which it entered the pool.
+	let in_pool_for = |hash| inspected.iter().find(|tx| tx.hash == hash).unwrap().in_pool_for;
+	assert!(in_pool_for(future) > in_pool_for(ready));
+	assert!(pool.inspect().banned.is_empty());
+}
+
+#[test]
+fn inspect_should_report_banned_transactions() {
+	let (pool, _guard, _notifier) = maintained_pool();
+	let hash = block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 209))).unwrap();
+
+	pool.remove_invalid(&[hash]);
+
+	let inspection = pool.inspect();
+	assert!(inspection.transactions.is_empty());
+	assert_eq!(inspection.banned, vec![hash]);
+}
+
 #[test]
 fn prune_tags_should_work() {
 	let pool = pool();
@@ -227,6 +263,42 @@ fn should_prune_old_during_maintenance() {
 	assert_eq!(pool.status().ready, 0);
 }
 
+#[derive(Default, Clone)]
+struct MemoryJournalStore(Arc<std::sync::Mutex<Option<Vec<u8>>>>);
+
+impl JournalStore for MemoryJournalStore {
+	fn load(&self) -> Option<Vec<u8>> {
+		self.0.lock().unwrap().clone()
+	}
+
+	fn store(&self, data: &[u8]) {
+		*self.0.lock().unwrap() = Some(data.to_vec());
+	}
+}
+
+#[test]
+fn should_restore_journaled_transactions_after_restart() {
+	let store = MemoryJournalStore::default();
+	let api = Arc::new(TestApi::with_alice_nonce(209));
+	let xt = uxt(Alice, 209);
+
+	let (mut pool, _background_task, _notifier) = BasicPool::new_test(api.clone());
+	pool.enable_journal(Box::new(store.clone()), Default::default());
+	let header = api.push_block(1, vec![], true);
+	block_on(pool.maintain(block_event(header)));
+	block_on(pool.submit_one(&BlockId::number(1), SOURCE, xt.clone())).expect("Imported");
+	drop(pool);
+
+	// The node restarts.
+	let (mut pool, _background_task, _notifier) = BasicPool::new_test(api.clone());
+	pool.enable_journal(Box::new(store), Default::default());
+	assert_eq!(pool.status().ready, 0);
+
+	let header = api.push_block(2, vec![], true);
+	block_on(pool.maintain(block_event(header)));
+	assert_eq!(pool.ready().map(|tx| tx.data.clone()).collect::<Vec<_>>(), vec![xt]);
+}
+
 #[test]
 fn should_revalidate_during_maintenance() {
 	let xt1 = uxt(Alice, 209);
@@ -0,0 +1,56 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition of the asset conversion pallet, for quoting swaps.
+//!
+//! This API should be imported and implemented by the runtime of a node that wants to offer
+//! price quotes and pool reserves of the asset conversion pallet to its clients.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+sp_api::decl_runtime_apis! {
+	/// The API to quote swaps and inspect the pools of the asset conversion pallet.
+	pub trait AssetConversionApi<Balance, AssetId> where
+		Balance: codec::Codec,
+		AssetId: codec::Codec,
+	{
+		/// Get the amount of `asset2` received in exchange for `amount` of `asset1`.
+		///
+		/// If `include_fee` is `false`, the amount is the one at the current price of the pool,
+		/// without fees nor price impact.
+		fn quote_price_exact_tokens_for_tokens(
+			asset1: AssetId,
+			asset2: AssetId,
+			amount: Balance,
+			include_fee: bool,
+		) -> Option<Balance>;
+
+		/// Get the amount of `asset1` needed in exchange for `amount` of `asset2`.
+		///
+		/// If `include_fee` is `false`, the amount is the one at the current price of the pool,
+		/// without fees nor price impact.
+		fn quote_price_tokens_for_exact_tokens(
+			asset1: AssetId,
+			asset2: AssetId,
+			amount: Balance,
+			include_fee: bool,
+		) -> Option<Balance>;
+
+		/// Get the reserves of `asset1` and `asset2` in their pool, if the pool exists.
+		fn get_reserves(asset1: AssetId, asset2: AssetId) -> Option<(Balance, Balance)>;
+	}
+}
@@ -0,0 +1,179 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Asset conversion pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::{*, Pallet as AssetConversion};
+
+use frame_benchmarking::{benchmarks, account, whitelisted_caller, impl_benchmark_test_suite};
+use frame_support::traits::tokens::{
+	fungible::{Inspect as _, Mutate as _},
+	fungibles::{Create as _, Mutate as _},
+};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+
+// The amount every account is endowed with, of every asset.
+fn endowment<T: Config>() -> T::Balance {
+	T::Currency::minimum_balance().max(One::one()).saturating_mul(1_000_000u32.into())
+}
+
+// The amount of each asset provided as liquidity to every pool.
+fn liquidity<T: Config>() -> T::Balance {
+	endowment::<T>() / 1_000u32.into()
+}
+
+// Create the asset `id`, and endow `who` with it and with the native currency.
+fn create_asset<T: Config>(id: u32, who: &T::AccountId) -> MultiAssetIdOf<T> where
+	T::Assets: fungibles::Create<T::AccountId> + fungibles::Mutate<T::AccountId>,
+	T::Currency: fungible::Mutate<T::AccountId>,
+	T::AssetId: From<u32>,
+{
+	let id: T::AssetId = id.into();
+	assert!(T::Assets::create(id, who.clone(), true, One::one()).is_ok());
+	assert!(T::Assets::mint_into(id, who, endowment::<T>()).is_ok());
+	if T::Currency::balance(who).is_zero() {
+		assert!(T::Currency::mint_into(who, endowment::<T>()).is_ok());
+	}
+	NativeOrAssetId::Asset(id)
+}
+
+// Create the pool of `asset1` and `asset2`, and provide it with liquidity from `who`.
+fn create_pool<T: Config>(
+	who: &T::AccountId,
+	asset1: MultiAssetIdOf<T>,
+	asset2: MultiAssetIdOf<T>,
+) -> Result<(), &'static str> {
+	AssetConversion::<T>::create_pool(RawOrigin::Signed(who.clone()).into(), asset1, asset2)?;
+	AssetConversion::<T>::add_liquidity(
+		RawOrigin::Signed(who.clone()).into(),
+		asset1,
+		asset2,
+		liquidity::<T>(),
+		liquidity::<T>(),
+		One::one(),
+		One::one(),
+		who.clone(),
+	)?;
+	Ok(())
+}
+
+// Create a path of `n` assets, starting from the native currency, with a pool between every two
+// consecutive assets.
+fn create_path<T: Config>(who: &T::AccountId, n: u32) -> Result<Vec<MultiAssetIdOf<T>>, &'static str> where
+	T::Assets: fungibles::Create<T::AccountId> + fungibles::Mutate<T::AccountId>,
+	T::Currency: fungible::Mutate<T::AccountId>,
+	T::AssetId: From<u32>,
+{
+	let mut path = vec![NativeOrAssetId::Native];
+	for i in 1 .. n {
+		let asset = create_asset::<T>(i, who);
+		create_pool::<T>(who, path[path.len() - 1], asset)?;
+		path.push(asset);
+	}
+	Ok(path)
+}
+
+benchmarks! {
+	where_clause { where
+		T::Assets: fungibles::Create<T::AccountId> + fungibles::Mutate<T::AccountId>,
+		T::Currency: fungible::Mutate<T::AccountId>,
+		T::AssetId: From<u32>,
+	}
+
+	create_pool {
+		let caller: T::AccountId = whitelisted_caller();
+		let asset = create_asset::<T>(1, &caller);
+	}: _(RawOrigin::Signed(caller.clone()), NativeOrAssetId::Native, asset)
+	verify {
+		let pool_id = AssetConversion::<T>::get_pool_id(NativeOrAssetId::Native, asset);
+		assert!(Pools::<T>::contains_key(&pool_id));
+	}
+
+	add_liquidity {
+		let caller: T::AccountId = whitelisted_caller();
+		let asset = create_asset::<T>(1, &caller);
+		create_pool::<T>(&caller, NativeOrAssetId::Native, asset)?;
+		let amount = liquidity::<T>();
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		NativeOrAssetId::Native,
+		asset,
+		amount,
+		amount,
+		One::one(),
+		One::one(),
+		caller.clone()
+	)
+	verify {
+		let reserves = AssetConversion::<T>::get_reserves(NativeOrAssetId::Native, asset)?;
+		assert_eq!(reserves, (amount.saturating_mul(2u32.into()), amount.saturating_mul(2u32.into())));
+	}
+
+	remove_liquidity {
+		let caller: T::AccountId = whitelisted_caller();
+		let asset = create_asset::<T>(1, &caller);
+		create_pool::<T>(&caller, NativeOrAssetId::Native, asset)?;
+		let withdraw_to: T::AccountId = account("withdraw_to", 0, SEED);
+		let lp_token_burn = liquidity::<T>() / 2u32.into();
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		NativeOrAssetId::Native,
+		asset,
+		lp_token_burn,
+		One::one(),
+		One::one(),
+		withdraw_to.clone()
+	)
+	verify {
+		assert!(!T::Currency::balance(&withdraw_to).is_zero());
+	}
+
+	swap_exact_tokens_for_tokens {
+		let n in 2 .. T::MaxSwapPathLength::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let path = create_path::<T>(&caller, n)?;
+		let amount_in = liquidity::<T>() / 100u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), path.clone(), amount_in, One::one(), caller.clone(), false)
+	verify {
+		assert_eq!(
+			T::Currency::balance(&caller),
+			endowment::<T>() - liquidity::<T>() - amount_in,
+		);
+	}
+
+	swap_tokens_for_exact_tokens {
+		let n in 2 .. T::MaxSwapPathLength::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let path = create_path::<T>(&caller, n)?;
+		let amount_out = liquidity::<T>() / 100u32.into();
+		let amount_in_max = liquidity::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), path.clone(), amount_out, amount_in_max, caller.clone(), false)
+	verify {
+		assert!(T::Currency::balance(&caller) < endowment::<T>() - liquidity::<T>());
+	}
+}
+
+impl_benchmark_test_suite!(
+	AssetConversion,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
@@ -0,0 +1,759 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! # Asset Conversion Pallet
+//!
+//! A pallet to swap fungible assets through constant product pools.
+//!
+//! - [`Config`]
+//! - [`Call`]
+//!
+//! ## Overview
+//!
+//! A pool holds reserves of two assets, either of which may be the native currency, and keeps
+//! the product of the reserves constant across swaps, up to the fee taken on their input. Anyone
+//! can create a pool, for a setup fee, and provide it with liquidity, in exchange for the
+//! liquidity token of the pool, which is minted through `PoolAssets`. Burning liquidity tokens
+//! withdraws the matching share of both reserves. The fees stay in the pool, so that they accrue
+//! to the liquidity providers.
+//!
+//! Swaps may go through several pools, following a path of assets in which every two
+//! consecutive assets have a pool. Both directions of swap take a slippage limit: the minimum
+//! amount to receive for an exact amount in, or the maximum amount to pay for an exact amount
+//! out.
+//!
+//! The pallet only relies on the [`fungible`] and [`fungibles`] traits, so that it can work with
+//! any currency and assets pallet.
+//!
+//! ### Terminology
+//!
+//! - **Pool:** The reserves of two distinct assets, held by an account derived from the pool id.
+//! - **Pool id:** The pair of assets of a pool, in ascending order.
+//! - **Liquidity token:** An asset representing a share of the reserves of a pool.
+//! - **Path:** The assets a swap goes through, from the asset paid to the asset received.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `create_pool` - Create a pool between two assets, along with its liquidity token.
+//! - `add_liquidity` - Provide liquidity to a pool and receive liquidity tokens.
+//! - `remove_liquidity` - Burn liquidity tokens and withdraw a share of a pool.
+//! - `swap_exact_tokens_for_tokens` - Swap an exact amount in for at least a minimum amount out.
+//! - `swap_tokens_for_exact_tokens` - Swap at most a maximum amount in for an exact amount out.
+//!
+//! ### Public Functions
+//!
+//! - `quote_price_exact_tokens_for_tokens` - Quote the amount received for an exact amount in.
+//! - `quote_price_tokens_for_exact_tokens` - Quote the amount to pay for an exact amount out.
+//! - `get_reserves` - Get the reserves of a pool.
+//!
+//! These back the `AssetConversionApi` runtime API.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod types;
+mod benchmarking;
+pub mod weights;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use codec::Encode;
+use sp_core::U256;
+use sp_std::{convert::TryFrom, prelude::*};
+use sp_runtime::{
+	PerThing, Permill,
+	traits::{AccountIdConversion, AtLeast32BitUnsigned, One, Saturating, Zero},
+};
+use frame_support::{
+	ensure, transactional, PalletId,
+	traits::{
+		Get,
+		tokens::{
+			fungible::{self, Inspect as _, Transfer as _},
+			fungibles::{self, Create as _, Inspect as _, Mutate as _, Transfer as _},
+		},
+	},
+};
+
+pub use types::*;
+pub use weights::WeightInfo;
+pub use pallet::*;
+
+/// An asset which may be exchanged by the pallet.
+pub type MultiAssetIdOf<T> = NativeOrAssetId<<T as Config>::AssetId>;
+
+/// The id of a pool: its two assets, in ascending order.
+pub type PoolIdOf<T> = (MultiAssetIdOf<T>, MultiAssetIdOf<T>);
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The native currency.
+		type Currency: fungible::Inspect<Self::AccountId, Balance = Self::Balance>
+			+ fungible::Transfer<Self::AccountId>;
+
+		/// The balance type of the native currency and of the assets.
+		///
+		/// Swaps are computed on 256 bits, so balances must fit in a `u128`.
+		type Balance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy
+			+ Into<u128> + TryFrom<u128>;
+
+		/// Identifier of an asset which may be exchanged.
+		type AssetId: Member + Parameter + Copy + Ord;
+
+		/// The assets which may be exchanged, besides the native currency.
+		type Assets: fungibles::Inspect<Self::AccountId, AssetId = Self::AssetId, Balance = Self::Balance>
+			+ fungibles::Transfer<Self::AccountId>;
+
+		/// Identifier of a liquidity token.
+		type PoolAssetId: Member + Parameter + AtLeast32BitUnsigned + Default + Copy;
+
+		/// The registry of liquidity tokens, in which a new token is created for each pool.
+		type PoolAssets: fungibles::Inspect<Self::AccountId, AssetId = Self::PoolAssetId, Balance = Self::Balance>
+			+ fungibles::Create<Self::AccountId>
+			+ fungibles::Mutate<Self::AccountId>;
+
+		/// The fee taken on the input of a swap, which stays in the pool.
+		#[pallet::constant]
+		type LPFee: Get<Permill>;
+
+		/// The amount of liquidity tokens locked in a pool when its first liquidity is added.
+		///
+		/// They are minted to the pool itself and can never be burnt, so that a pool can never be
+		/// emptied.
+		#[pallet::constant]
+		type MintMinLiquidity: Get<Self::Balance>;
+
+		/// The maximum number of assets in the path of a swap.
+		#[pallet::constant]
+		type MaxSwapPathLength: Get<u32>;
+
+		/// The fee in the native currency paid by the creator of a pool.
+		///
+		/// It prevents the creation of useless pools, which each take a liquidity token.
+		#[pallet::constant]
+		type PoolSetupFee: Get<Self::Balance>;
+
+		/// The account to which the pool setup fee is paid.
+		type PoolSetupFeeReceiver: Get<Self::AccountId>;
+
+		/// The pallet's id, used for deriving the accounts of the pools.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The pools, by their id.
+	#[pallet::storage]
+	pub type Pools<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		PoolIdOf<T>,
+		PoolInfo<T::PoolAssetId>,
+		OptionQuery,
+	>;
+
+	/// The liquidity token of the next pool to be created.
+	#[pallet::storage]
+	pub type NextPoolAssetId<T: Config> = StorageValue<_, T::PoolAssetId, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		T::Balance = "Balance",
+		T::PoolAssetId = "PoolAssetId",
+		PoolIdOf<T> = "PoolId"
+	)]
+	pub enum Event<T: Config> {
+		/// A pool was created. \[creator, pool_id, lp_token\]
+		PoolCreated(T::AccountId, PoolIdOf<T>, T::PoolAssetId),
+		/// Liquidity was added to a pool.
+		/// \[who, mint_to, pool_id, amount1, amount2, lp_token, lp_token_minted\]
+		LiquidityAdded(
+			T::AccountId,
+			T::AccountId,
+			PoolIdOf<T>,
+			T::Balance,
+			T::Balance,
+			T::PoolAssetId,
+			T::Balance,
+		),
+		/// Liquidity was removed from a pool.
+		/// \[who, withdraw_to, pool_id, amount1, amount2, lp_token, lp_token_burned\]
+		LiquidityRemoved(
+			T::AccountId,
+			T::AccountId,
+			PoolIdOf<T>,
+			T::Balance,
+			T::Balance,
+			T::PoolAssetId,
+			T::Balance,
+		),
+		/// Assets were swapped along a path. \[who, send_to, path, amount_in, amount_out\]
+		SwapExecuted(T::AccountId, T::AccountId, Vec<MultiAssetIdOf<T>>, T::Balance, T::Balance),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The two assets of a pool must be distinct.
+		EqualAssets,
+		/// A pool of these assets already exists.
+		PoolExists,
+		/// There is no pool of these assets.
+		PoolNotFound,
+		/// The desired amounts of liquidity to add must not be zero.
+		WrongDesiredAmount,
+		/// The amount of the first asset is less than the given minimum.
+		AmountOneLessThanMinimal,
+		/// The amount of the second asset is less than the given minimum.
+		AmountTwoLessThanMinimal,
+		/// The liquidity added is too small to mint any liquidity token.
+		InsufficientLiquidityMinted,
+		/// The amount of liquidity tokens to burn must not be zero.
+		ZeroLiquidity,
+		/// The amounts of a swap must not be zero.
+		ZeroAmount,
+		/// The reserves of a pool are too small for the operation.
+		InsufficientLiquidity,
+		/// The swap would yield less than the given minimum.
+		ProvidedMinimumNotSufficientForSwap,
+		/// The swap would cost more than the given maximum.
+		ProvidedMaximumNotSufficientForSwap,
+		/// The path must hold between two and `MaxSwapPathLength` assets, and go through each
+		/// pool at most once.
+		InvalidPath,
+		/// An arithmetic overflow occurred.
+		Overflow,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Create a pool between `asset1` and `asset2`, along with its liquidity token.
+		///
+		/// The creator pays `PoolSetupFee` to `PoolSetupFeeReceiver`. The pool is empty until
+		/// liquidity is added with `add_liquidity`.
+		///
+		/// Emits `PoolCreated`.
+		#[pallet::weight(T::WeightInfo::create_pool())]
+		#[transactional]
+		pub fn create_pool(
+			origin: OriginFor<T>,
+			asset1: MultiAssetIdOf<T>,
+			asset2: MultiAssetIdOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(asset1 != asset2, Error::<T>::EqualAssets);
+
+			let pool_id = Self::get_pool_id(asset1, asset2);
+			ensure!(!Pools::<T>::contains_key(&pool_id), Error::<T>::PoolExists);
+
+			let setup_fee = T::PoolSetupFee::get();
+			if !setup_fee.is_zero() {
+				T::Currency::transfer(&sender, &T::PoolSetupFeeReceiver::get(), setup_fee, false)?;
+			}
+
+			let pool_account = Self::get_pool_account(&pool_id);
+			frame_system::Pallet::<T>::inc_providers(&pool_account);
+
+			let lp_token = NextPoolAssetId::<T>::get();
+			T::PoolAssets::create(lp_token, pool_account, false, One::one())?;
+			NextPoolAssetId::<T>::put(lp_token.saturating_add(One::one()));
+
+			Pools::<T>::insert(&pool_id, PoolInfo { lp_token });
+			Self::deposit_event(Event::PoolCreated(sender, pool_id, lp_token));
+			Ok(())
+		}
+
+		/// Add liquidity to the pool of `asset1` and `asset2`, and mint the matching liquidity
+		/// tokens to `mint_to`.
+		///
+		/// The first liquidity of a pool sets its price, and is taken as desired. Later
+		/// liquidity is taken at the price of the pool: as much of the desired amounts as the
+		/// price allows, but no less than the given minimums.
+		///
+		/// Emits `LiquidityAdded`.
+		#[pallet::weight(T::WeightInfo::add_liquidity())]
+		#[transactional]
+		pub fn add_liquidity(
+			origin: OriginFor<T>,
+			asset1: MultiAssetIdOf<T>,
+			asset2: MultiAssetIdOf<T>,
+			amount1_desired: T::Balance,
+			amount2_desired: T::Balance,
+			amount1_min: T::Balance,
+			amount2_min: T::Balance,
+			mint_to: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let pool_id = Self::get_pool_id(asset1, asset2);
+			// Work in the order of the assets in the pool.
+			let (amount1_desired, amount2_desired, amount1_min, amount2_min) = if pool_id.0 == asset1 {
+				(amount1_desired, amount2_desired, amount1_min, amount2_min)
+			} else {
+				(amount2_desired, amount1_desired, amount2_min, amount1_min)
+			};
+			let (asset1, asset2) = pool_id;
+
+			let pool = Pools::<T>::get(&pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				!amount1_desired.is_zero() && !amount2_desired.is_zero(),
+				Error::<T>::WrongDesiredAmount
+			);
+
+			let pool_account = Self::get_pool_account(&pool_id);
+			let reserve1 = Self::get_balance(&pool_account, asset1);
+			let reserve2 = Self::get_balance(&pool_account, asset2);
+
+			let (amount1, amount2) = if reserve1.is_zero() || reserve2.is_zero() {
+				(amount1_desired, amount2_desired)
+			} else {
+				let amount2_optimal = Self::quote(amount1_desired, reserve1, reserve2)?;
+				if amount2_optimal <= amount2_desired {
+					(amount1_desired, amount2_optimal)
+				} else {
+					let amount1_optimal = Self::quote(amount2_desired, reserve2, reserve1)?;
+					(amount1_optimal.min(amount1_desired), amount2_desired)
+				}
+			};
+			ensure!(amount1 >= amount1_min, Error::<T>::AmountOneLessThanMinimal);
+			ensure!(amount2 >= amount2_min, Error::<T>::AmountTwoLessThanMinimal);
+
+			Self::transfer(asset1, &sender, &pool_account, amount1, true)?;
+			Self::transfer(asset2, &sender, &pool_account, amount2, true)?;
+
+			let total_supply = T::PoolAssets::total_issuance(pool.lp_token);
+			let lp_token_amount = if total_supply.is_zero() {
+				let liquidity = Self::calc_initial_liquidity(amount1, amount2)?;
+				let locked = T::MintMinLiquidity::get();
+				ensure!(liquidity > locked, Error::<T>::InsufficientLiquidityMinted);
+				T::PoolAssets::mint_into(pool.lp_token, &pool_account, locked)?;
+				liquidity - locked
+			} else {
+				let side1 = Self::mul_div(amount1, total_supply, reserve1)?;
+				let side2 = Self::mul_div(amount2, total_supply, reserve2)?;
+				side1.min(side2)
+			};
+			ensure!(!lp_token_amount.is_zero(), Error::<T>::InsufficientLiquidityMinted);
+			T::PoolAssets::mint_into(pool.lp_token, &mint_to, lp_token_amount)?;
+
+			Self::deposit_event(Event::LiquidityAdded(
+				sender,
+				mint_to,
+				pool_id,
+				amount1,
+				amount2,
+				pool.lp_token,
+				lp_token_amount,
+			));
+			Ok(())
+		}
+
+		/// Burn `lp_token_burn` liquidity tokens of the pool of `asset1` and `asset2`, and
+		/// withdraw the matching share of its reserves to `withdraw_to`.
+		///
+		/// Emits `LiquidityRemoved`.
+		#[pallet::weight(T::WeightInfo::remove_liquidity())]
+		#[transactional]
+		pub fn remove_liquidity(
+			origin: OriginFor<T>,
+			asset1: MultiAssetIdOf<T>,
+			asset2: MultiAssetIdOf<T>,
+			lp_token_burn: T::Balance,
+			amount1_min_receive: T::Balance,
+			amount2_min_receive: T::Balance,
+			withdraw_to: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let pool_id = Self::get_pool_id(asset1, asset2);
+			// Work in the order of the assets in the pool.
+			let (amount1_min_receive, amount2_min_receive) = if pool_id.0 == asset1 {
+				(amount1_min_receive, amount2_min_receive)
+			} else {
+				(amount2_min_receive, amount1_min_receive)
+			};
+			let (asset1, asset2) = pool_id;
+
+			ensure!(!lp_token_burn.is_zero(), Error::<T>::ZeroLiquidity);
+			let pool = Pools::<T>::get(&pool_id).ok_or(Error::<T>::PoolNotFound)?;
+
+			let pool_account = Self::get_pool_account(&pool_id);
+			let reserve1 = Self::get_balance(&pool_account, asset1);
+			let reserve2 = Self::get_balance(&pool_account, asset2);
+			let total_supply = T::PoolAssets::total_issuance(pool.lp_token);
+
+			let amount1 = Self::mul_div(lp_token_burn, reserve1, total_supply)?;
+			let amount2 = Self::mul_div(lp_token_burn, reserve2, total_supply)?;
+			ensure!(
+				!amount1.is_zero() && amount1 >= amount1_min_receive,
+				Error::<T>::AmountOneLessThanMinimal
+			);
+			ensure!(
+				!amount2.is_zero() && amount2 >= amount2_min_receive,
+				Error::<T>::AmountTwoLessThanMinimal
+			);
+
+			T::PoolAssets::burn_from(pool.lp_token, &sender, lp_token_burn)?;
+
+			Self::transfer(asset1, &pool_account, &withdraw_to, amount1, true)?;
+			Self::transfer(asset2, &pool_account, &withdraw_to, amount2, true)?;
+
+			Self::deposit_event(Event::LiquidityRemoved(
+				sender,
+				withdraw_to,
+				pool_id,
+				amount1,
+				amount2,
+				pool.lp_token,
+				lp_token_burn,
+			));
+			Ok(())
+		}
+
+		/// Swap exactly `amount_in` of the first asset of `path` for at least `amount_out_min`
+		/// of its last asset, sent to `send_to`.
+		///
+		/// If `keep_alive` is set, the swap fails rather than reap the sender's account.
+		///
+		/// Emits `SwapExecuted`.
+		#[pallet::weight(T::WeightInfo::swap_exact_tokens_for_tokens(path.len() as u32))]
+		#[transactional]
+		pub fn swap_exact_tokens_for_tokens(
+			origin: OriginFor<T>,
+			path: Vec<MultiAssetIdOf<T>>,
+			amount_in: T::Balance,
+			amount_out_min: T::Balance,
+			send_to: T::AccountId,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!amount_in.is_zero() && !amount_out_min.is_zero(), Error::<T>::ZeroAmount);
+			Self::validate_swap_path(&path)?;
+
+			let amounts = Self::get_amounts_out(amount_in, &path)?;
+			let amount_out = *amounts.last().expect("path holds at least two assets; qed");
+			ensure!(amount_out >= amount_out_min, Error::<T>::ProvidedMinimumNotSufficientForSwap);
+
+			Self::do_swap(&sender, &amounts, &path, &send_to, keep_alive)?;
+
+			Self::deposit_event(Event::SwapExecuted(sender, send_to, path, amount_in, amount_out));
+			Ok(())
+		}
+
+		/// Swap at most `amount_in_max` of the first asset of `path` for exactly `amount_out`
+		/// of its last asset, sent to `send_to`.
+		///
+		/// If `keep_alive` is set, the swap fails rather than reap the sender's account.
+		///
+		/// Emits `SwapExecuted`.
+		#[pallet::weight(T::WeightInfo::swap_tokens_for_exact_tokens(path.len() as u32))]
+		#[transactional]
+		pub fn swap_tokens_for_exact_tokens(
+			origin: OriginFor<T>,
+			path: Vec<MultiAssetIdOf<T>>,
+			amount_out: T::Balance,
+			amount_in_max: T::Balance,
+			send_to: T::AccountId,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!amount_out.is_zero() && !amount_in_max.is_zero(), Error::<T>::ZeroAmount);
+			Self::validate_swap_path(&path)?;
+
+			let amounts = Self::get_amounts_in(amount_out, &path)?;
+			let amount_in = amounts[0];
+			ensure!(amount_in <= amount_in_max, Error::<T>::ProvidedMaximumNotSufficientForSwap);
+
+			Self::do_swap(&sender, &amounts, &path, &send_to, keep_alive)?;
+
+			Self::deposit_event(Event::SwapExecuted(sender, send_to, path, amount_in, amount_out));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The id of the pool of `asset1` and `asset2`, whichever order they are given in.
+	pub fn get_pool_id(asset1: MultiAssetIdOf<T>, asset2: MultiAssetIdOf<T>) -> PoolIdOf<T> {
+		if asset1 <= asset2 {
+			(asset1, asset2)
+		} else {
+			(asset2, asset1)
+		}
+	}
+
+	/// The account holding the reserves of the pool `pool_id`.
+	pub fn get_pool_account(pool_id: &PoolIdOf<T>) -> T::AccountId {
+		T::PalletId::get().into_sub_account(sp_io::hashing::blake2_256(&pool_id.encode()))
+	}
+
+	/// The reserves of `asset1` and `asset2` in their pool, in the given order.
+	pub fn get_reserves(
+		asset1: MultiAssetIdOf<T>,
+		asset2: MultiAssetIdOf<T>,
+	) -> Result<(T::Balance, T::Balance), Error<T>> {
+		let pool_id = Self::get_pool_id(asset1, asset2);
+		ensure!(Pools::<T>::contains_key(&pool_id), Error::<T>::PoolNotFound);
+		let pool_account = Self::get_pool_account(&pool_id);
+		Ok((Self::get_balance(&pool_account, asset1), Self::get_balance(&pool_account, asset2)))
+	}
+
+	/// The amount of `asset2` received for exactly `amount` of `asset1`.
+	///
+	/// Without `include_fee`, this is the amount at the current price of the pool, ignoring
+	/// the fee and the price impact of the swap.
+	pub fn quote_price_exact_tokens_for_tokens(
+		asset1: MultiAssetIdOf<T>,
+		asset2: MultiAssetIdOf<T>,
+		amount: T::Balance,
+		include_fee: bool,
+	) -> Option<T::Balance> {
+		let (reserve1, reserve2) = Self::get_reserves(asset1, asset2).ok()?;
+		if include_fee {
+			Self::get_amount_out(amount, reserve1, reserve2).ok()
+		} else {
+			Self::quote(amount, reserve1, reserve2).ok()
+		}
+	}
+
+	/// The amount of `asset1` needed for exactly `amount` of `asset2`.
+	///
+	/// Without `include_fee`, this is the amount at the current price of the pool, ignoring
+	/// the fee and the price impact of the swap.
+	pub fn quote_price_tokens_for_exact_tokens(
+		asset1: MultiAssetIdOf<T>,
+		asset2: MultiAssetIdOf<T>,
+		amount: T::Balance,
+		include_fee: bool,
+	) -> Option<T::Balance> {
+		let (reserve1, reserve2) = Self::get_reserves(asset1, asset2).ok()?;
+		if include_fee {
+			Self::get_amount_in(amount, reserve1, reserve2).ok()
+		} else {
+			Self::quote(amount, reserve2, reserve1).ok()
+		}
+	}
+
+	fn get_balance(who: &T::AccountId, asset: MultiAssetIdOf<T>) -> T::Balance {
+		match asset {
+			NativeOrAssetId::Native => T::Currency::balance(who),
+			NativeOrAssetId::Asset(id) => T::Assets::balance(id, who),
+		}
+	}
+
+	fn transfer(
+		asset: MultiAssetIdOf<T>,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<T::Balance, sp_runtime::DispatchError> {
+		match asset {
+			NativeOrAssetId::Native => T::Currency::transfer(from, to, amount, keep_alive),
+			NativeOrAssetId::Asset(id) => T::Assets::transfer(id, from, to, amount, keep_alive),
+		}
+	}
+
+	/// Check that `path` has a valid length and goes through existing pools, each at most once.
+	fn validate_swap_path(path: &[MultiAssetIdOf<T>]) -> Result<(), Error<T>> {
+		ensure!(
+			path.len() >= 2 && path.len() as u32 <= T::MaxSwapPathLength::get(),
+			Error::<T>::InvalidPath
+		);
+		let mut pools = Vec::<PoolIdOf<T>>::with_capacity(path.len() - 1);
+		for pair in path.windows(2) {
+			ensure!(pair[0] != pair[1], Error::<T>::InvalidPath);
+			let pool_id = Self::get_pool_id(pair[0], pair[1]);
+			ensure!(!pools.contains(&pool_id), Error::<T>::InvalidPath);
+			ensure!(Pools::<T>::contains_key(&pool_id), Error::<T>::PoolNotFound);
+			pools.push(pool_id);
+		}
+		Ok(())
+	}
+
+	/// The amounts at each step of `path` when swapping exactly `amount_in`.
+	fn get_amounts_out(
+		amount_in: T::Balance,
+		path: &[MultiAssetIdOf<T>],
+	) -> Result<Vec<T::Balance>, Error<T>> {
+		let mut amounts = Vec::with_capacity(path.len());
+		amounts.push(amount_in);
+		for pair in path.windows(2) {
+			let (reserve_in, reserve_out) = Self::get_reserves(pair[0], pair[1])?;
+			let amount = *amounts.last().expect("amounts is never empty; qed");
+			amounts.push(Self::get_amount_out(amount, reserve_in, reserve_out)?);
+		}
+		Ok(amounts)
+	}
+
+	/// The amounts at each step of `path` when swapping for exactly `amount_out`.
+	fn get_amounts_in(
+		amount_out: T::Balance,
+		path: &[MultiAssetIdOf<T>],
+	) -> Result<Vec<T::Balance>, Error<T>> {
+		let mut amounts = Vec::with_capacity(path.len());
+		amounts.push(amount_out);
+		for pair in path.windows(2).rev() {
+			let (reserve_in, reserve_out) = Self::get_reserves(pair[0], pair[1])?;
+			let amount = *amounts.last().expect("amounts is never empty; qed");
+			amounts.push(Self::get_amount_in(amount, reserve_in, reserve_out)?);
+		}
+		amounts.reverse();
+		Ok(amounts)
+	}
+
+	/// Move `amounts` along `path`: from `sender` into the first pool, from each pool into the
+	/// next one, and from the last pool to `send_to`.
+	fn do_swap(
+		sender: &T::AccountId,
+		amounts: &[T::Balance],
+		path: &[MultiAssetIdOf<T>],
+		send_to: &T::AccountId,
+		keep_alive: bool,
+	) -> Result<(), sp_runtime::DispatchError> {
+		let pools = path.windows(2)
+			.map(|pair| Self::get_pool_account(&Self::get_pool_id(pair[0], pair[1])))
+			.collect::<Vec<_>>();
+		Self::transfer(path[0], sender, &pools[0], amounts[0], keep_alive)?;
+		for (i, pool_account) in pools.iter().enumerate() {
+			let to = pools.get(i + 1).unwrap_or(send_to);
+			Self::transfer(path[i + 1], pool_account, to, amounts[i + 1], true)?;
+		}
+		Ok(())
+	}
+
+	/// The amount out of a pool with the given reserves, for exactly `amount_in`, after fee.
+	fn get_amount_out(
+		amount_in: T::Balance,
+		reserve_in: T::Balance,
+		reserve_out: T::Balance,
+	) -> Result<T::Balance, Error<T>> {
+		ensure!(!reserve_in.is_zero() && !reserve_out.is_zero(), Error::<T>::InsufficientLiquidity);
+		let accuracy = U256::from(Permill::ACCURACY);
+		let fee = U256::from(T::LPFee::get().deconstruct());
+
+		let amount_in_with_fee = Self::to_u256(amount_in).checked_mul(accuracy - fee)
+			.ok_or(Error::<T>::Overflow)?;
+		let numerator = amount_in_with_fee.checked_mul(Self::to_u256(reserve_out))
+			.ok_or(Error::<T>::Overflow)?;
+		let denominator = Self::to_u256(reserve_in).checked_mul(accuracy)
+			.and_then(|r| r.checked_add(amount_in_with_fee))
+			.ok_or(Error::<T>::Overflow)?;
+
+		Self::from_u256(numerator / denominator)
+	}
+
+	/// The amount into a pool with the given reserves, for exactly `amount_out`, after fee.
+	fn get_amount_in(
+		amount_out: T::Balance,
+		reserve_in: T::Balance,
+		reserve_out: T::Balance,
+	) -> Result<T::Balance, Error<T>> {
+		ensure!(!reserve_in.is_zero() && !reserve_out.is_zero(), Error::<T>::InsufficientLiquidity);
+		ensure!(amount_out < reserve_out, Error::<T>::InsufficientLiquidity);
+		let accuracy = U256::from(Permill::ACCURACY);
+		let fee = U256::from(T::LPFee::get().deconstruct());
+
+		let numerator = Self::to_u256(reserve_in).checked_mul(Self::to_u256(amount_out))
+			.and_then(|n| n.checked_mul(accuracy))
+			.ok_or(Error::<T>::Overflow)?;
+		let denominator = Self::to_u256(reserve_out - amount_out).checked_mul(accuracy - fee)
+			.ok_or(Error::<T>::Overflow)?;
+		ensure!(!denominator.is_zero(), Error::<T>::InsufficientLiquidity);
+
+		// Round up, so that the pool is never short-changed.
+		Self::from_u256(numerator / denominator + U256::one())
+	}
+
+	/// The amount of the other asset worth `amount` at the current price of a pool with the
+	/// given reserves.
+	fn quote(
+		amount: T::Balance,
+		reserve1: T::Balance,
+		reserve2: T::Balance,
+	) -> Result<T::Balance, Error<T>> {
+		ensure!(!reserve1.is_zero() && !reserve2.is_zero(), Error::<T>::InsufficientLiquidity);
+		Self::mul_div(amount, reserve2, reserve1)
+	}
+
+	/// The liquidity tokens minted for the first liquidity of a pool: the geometric mean of
+	/// the amounts.
+	fn calc_initial_liquidity(
+		amount1: T::Balance,
+		amount2: T::Balance,
+	) -> Result<T::Balance, Error<T>> {
+		let product = Self::to_u256(amount1).checked_mul(Self::to_u256(amount2))
+			.ok_or(Error::<T>::Overflow)?;
+		Self::from_u256(Self::integer_sqrt(product))
+	}
+
+	/// The square root of `n`, rounded down.
+	fn integer_sqrt(n: U256) -> U256 {
+		if n.is_zero() {
+			return n
+		}
+		// Newton's method, starting above the root.
+		let mut x = n;
+		let mut y = (x >> 1) + (x & U256::one());
+		while y < x {
+			x = y;
+			y = (x + n / x) >> 1;
+		}
+		x
+	}
+
+	/// `a * b / c`, computed on 256 bits.
+	fn mul_div(a: T::Balance, b: T::Balance, c: T::Balance) -> Result<T::Balance, Error<T>> {
+		let result = Self::to_u256(a).checked_mul(Self::to_u256(b))
+			.and_then(|n| n.checked_div(Self::to_u256(c)))
+			.ok_or(Error::<T>::Overflow)?;
+		Self::from_u256(result)
+	}
+
+	fn to_u256(value: T::Balance) -> U256 {
+		let value: u128 = value.into();
+		U256::from(value)
+	}
+
+	fn from_u256(value: U256) -> Result<T::Balance, Error<T>> {
+		ensure!(value <= U256::from(u128::max_value()), Error::<T>::Overflow);
+		T::Balance::try_from(value.low_u128()).map_err(|_| Error::<T>::Overflow)
+	}
+}
@@ -0,0 +1,361 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Tests for the asset conversion pallet.
+
+use super::*;
+use crate::{Error, mock::{*, Event}};
+use frame_support::{assert_noop, assert_ok};
+
+const NATIVE: NativeOrAssetId<u32> = NativeOrAssetId::Native;
+const ASSET: NativeOrAssetId<u32> = NativeOrAssetId::Asset(2);
+const OTHER_ASSET: NativeOrAssetId<u32> = NativeOrAssetId::Asset(3);
+
+fn create_asset(id: u32, owner: u128, amount: u128) {
+	assert_ok!(Assets::force_create(Origin::root(), id, owner, false, 1));
+	assert_ok!(Assets::mint(Origin::signed(owner), id, owner, amount));
+}
+
+fn native_balance(who: u128) -> u128 {
+	<Balances as fungible::Inspect<u128>>::balance(&who)
+}
+
+fn asset_balance(id: u32, who: u128) -> u128 {
+	<Assets as fungibles::Inspect<u128>>::balance(id, &who)
+}
+
+fn lp_balance(lp_token: u32, who: u128) -> u128 {
+	<PoolAssets as fungibles::Inspect<u128>>::balance(lp_token, &who)
+}
+
+/// Create the pool of the native currency and asset 2, with 100_000 native and 10_000 of
+/// the asset provided by account 1.
+fn setup_pool() {
+	create_asset(2, 1, 1_000_000);
+	assert_ok!(AssetConversion::create_pool(Origin::signed(1), NATIVE, ASSET));
+	assert_ok!(AssetConversion::add_liquidity(
+		Origin::signed(1), NATIVE, ASSET, 100_000, 10_000, 100_000, 10_000, 1,
+	));
+}
+
+#[test]
+fn create_pool_works() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, 1, 1_000_000);
+		assert_ok!(AssetConversion::create_pool(Origin::signed(1), ASSET, NATIVE));
+
+		let pool_id = (NATIVE, ASSET);
+		assert_eq!(Pools::<Test>::get(pool_id), Some(PoolInfo { lp_token: 0 }));
+		assert_eq!(NextPoolAssetId::<Test>::get(), 1);
+		System::assert_last_event(Event::AssetConversion(crate::Event::PoolCreated(1, pool_id, 0)));
+
+		// The liquidity token was created.
+		assert_eq!(<PoolAssets as fungibles::Inspect<u128>>::minimum_balance(0), 1);
+
+		assert_noop!(
+			AssetConversion::create_pool(Origin::signed(2), NATIVE, ASSET),
+			Error::<Test>::PoolExists,
+		);
+		assert_noop!(
+			AssetConversion::create_pool(Origin::signed(1), ASSET, ASSET),
+			Error::<Test>::EqualAssets,
+		);
+	});
+}
+
+#[test]
+fn create_pool_charges_setup_fee() {
+	new_test_ext().execute_with(|| {
+		PoolSetupFee::set(100);
+		create_asset(2, 1, 1_000_000);
+		assert_ok!(AssetConversion::create_pool(Origin::signed(1), NATIVE, ASSET));
+		assert_eq!(native_balance(1), 999_900);
+		assert_eq!(native_balance(PoolSetupFeeReceiver::get()), 100);
+
+		// The fee must be affordable.
+		create_asset(3, 4, 1_000_000);
+		assert!(AssetConversion::create_pool(Origin::signed(4), NATIVE, NativeOrAssetId::Asset(3))
+			.is_err());
+		assert_eq!(NextPoolAssetId::<Test>::get(), 1);
+	});
+}
+
+#[test]
+fn add_liquidity_works() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, 1, 1_000_000);
+		assert_ok!(AssetConversion::create_pool(Origin::signed(1), NATIVE, ASSET));
+		let pool_id = (NATIVE, ASSET);
+		let pool_account = AssetConversion::get_pool_account(&pool_id);
+
+		// The first liquidity is taken as desired, and mints the geometric mean of the amounts,
+		// less the part locked in the pool.
+		assert_ok!(AssetConversion::add_liquidity(
+			Origin::signed(1), NATIVE, ASSET, 10_000, 1_000, 10_000, 1_000, 1,
+		));
+		System::assert_last_event(Event::AssetConversion(
+			crate::Event::LiquidityAdded(1, 1, pool_id, 10_000, 1_000, 0, 3_062),
+		));
+		assert_eq!(lp_balance(0, 1), 3_062);
+		assert_eq!(lp_balance(0, pool_account), 100);
+		assert_eq!(native_balance(pool_account), 10_000);
+		assert_eq!(asset_balance(2, pool_account), 1_000);
+		assert_eq!(native_balance(1), 990_000);
+
+		// Later liquidity is taken at the price of the pool, whichever order the assets are
+		// given in.
+		assert_ok!(AssetConversion::add_liquidity(
+			Origin::signed(1), ASSET, NATIVE, 3_000, 20_000, 0, 0, 2,
+		));
+		System::assert_last_event(Event::AssetConversion(
+			crate::Event::LiquidityAdded(1, 2, pool_id, 20_000, 2_000, 0, 6_324),
+		));
+		assert_eq!(lp_balance(0, 2), 6_324);
+		assert_eq!(native_balance(pool_account), 30_000);
+		assert_eq!(asset_balance(2, pool_account), 3_000);
+	});
+}
+
+#[test]
+fn add_liquidity_checks_amounts() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, 1, 1_000_000);
+		assert_noop!(
+			AssetConversion::add_liquidity(Origin::signed(1), NATIVE, ASSET, 10_000, 1_000, 0, 0, 1),
+			Error::<Test>::PoolNotFound,
+		);
+		assert_ok!(AssetConversion::create_pool(Origin::signed(1), NATIVE, ASSET));
+		assert_noop!(
+			AssetConversion::add_liquidity(Origin::signed(1), NATIVE, ASSET, 0, 1_000, 0, 0, 1),
+			Error::<Test>::WrongDesiredAmount,
+		);
+		// The first liquidity must mint more than the locked amount.
+		assert_noop!(
+			AssetConversion::add_liquidity(Origin::signed(1), NATIVE, ASSET, 100, 100, 0, 0, 1),
+			Error::<Test>::InsufficientLiquidityMinted,
+		);
+
+		assert_ok!(AssetConversion::add_liquidity(
+			Origin::signed(1), NATIVE, ASSET, 10_000, 1_000, 10_000, 1_000, 1,
+		));
+		// At the price of the pool, 20_000 native are worth 2_000 of the asset.
+		assert_noop!(
+			AssetConversion::add_liquidity(Origin::signed(1), NATIVE, ASSET, 20_000, 3_000, 0, 2_500, 1),
+			Error::<Test>::AmountTwoLessThanMinimal,
+		);
+		assert_noop!(
+			AssetConversion::add_liquidity(Origin::signed(1), NATIVE, ASSET, 30_000, 2_000, 25_000, 0, 1),
+			Error::<Test>::AmountOneLessThanMinimal,
+		);
+	});
+}
+
+#[test]
+fn remove_liquidity_works() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, 1, 1_000_000);
+		assert_ok!(AssetConversion::create_pool(Origin::signed(1), NATIVE, ASSET));
+		assert_ok!(AssetConversion::add_liquidity(
+			Origin::signed(1), NATIVE, ASSET, 10_000, 1_000, 10_000, 1_000, 1,
+		));
+		let pool_id = (NATIVE, ASSET);
+		let pool_account = AssetConversion::get_pool_account(&pool_id);
+
+		assert_noop!(
+			AssetConversion::remove_liquidity(Origin::signed(1), NATIVE, ASSET, 0, 0, 0, 1),
+			Error::<Test>::ZeroLiquidity,
+		);
+		assert_noop!(
+			AssetConversion::remove_liquidity(Origin::signed(1), NATIVE, ASSET, 3_062, 9_684, 0, 1),
+			Error::<Test>::AmountOneLessThanMinimal,
+		);
+
+		assert_ok!(AssetConversion::remove_liquidity(
+			Origin::signed(1), NATIVE, ASSET, 3_062, 9_683, 968, 1,
+		));
+		System::assert_last_event(Event::AssetConversion(
+			crate::Event::LiquidityRemoved(1, 1, pool_id, 9_683, 968, 0, 3_062),
+		));
+		assert_eq!(lp_balance(0, 1), 0);
+		assert_eq!(native_balance(1), 999_683);
+		assert_eq!(asset_balance(2, 1), 999_968);
+		// The locked liquidity keeps the pool from being emptied.
+		assert_eq!(native_balance(pool_account), 317);
+		assert_eq!(asset_balance(2, pool_account), 32);
+
+		// There are no more liquidity tokens to burn.
+		assert!(AssetConversion::remove_liquidity(
+			Origin::signed(1), NATIVE, ASSET, 1, 0, 0, 1,
+		).is_err());
+	});
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens_works() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		let pool_account = AssetConversion::get_pool_account(&(NATIVE, ASSET));
+
+		assert_noop!(
+			AssetConversion::swap_exact_tokens_for_tokens(
+				Origin::signed(2), vec![NATIVE, ASSET], 1_000, 99, 2, true,
+			),
+			Error::<Test>::ProvidedMinimumNotSufficientForSwap,
+		);
+		assert_noop!(
+			AssetConversion::swap_exact_tokens_for_tokens(
+				Origin::signed(2), vec![NATIVE, ASSET], 0, 1, 2, true,
+			),
+			Error::<Test>::ZeroAmount,
+		);
+
+		assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+			Origin::signed(2), vec![NATIVE, ASSET], 1_000, 98, 2, true,
+		));
+		System::assert_last_event(Event::AssetConversion(
+			crate::Event::SwapExecuted(2, 2, vec![NATIVE, ASSET], 1_000, 98),
+		));
+		assert_eq!(native_balance(2), 999_000);
+		assert_eq!(asset_balance(2, 2), 98);
+		assert_eq!(native_balance(pool_account), 101_000);
+		assert_eq!(asset_balance(2, pool_account), 9_902);
+		// The fee stays in the pool.
+		assert!(101_000 * 9_902 > 100_000 * 10_000);
+	});
+}
+
+#[test]
+fn swap_tokens_for_exact_tokens_works() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		let pool_account = AssetConversion::get_pool_account(&(NATIVE, ASSET));
+
+		assert_noop!(
+			AssetConversion::swap_tokens_for_exact_tokens(
+				Origin::signed(2), vec![NATIVE, ASSET], 98, 992, 2, true,
+			),
+			Error::<Test>::ProvidedMaximumNotSufficientForSwap,
+		);
+		// The pool cannot give away its whole reserve.
+		assert_noop!(
+			AssetConversion::swap_tokens_for_exact_tokens(
+				Origin::signed(2), vec![NATIVE, ASSET], 10_000, 1_000_000, 2, true,
+			),
+			Error::<Test>::InsufficientLiquidity,
+		);
+
+		assert_ok!(AssetConversion::swap_tokens_for_exact_tokens(
+			Origin::signed(2), vec![NATIVE, ASSET], 98, 993, 3, true,
+		));
+		System::assert_last_event(Event::AssetConversion(
+			crate::Event::SwapExecuted(2, 3, vec![NATIVE, ASSET], 993, 98),
+		));
+		assert_eq!(native_balance(2), 999_007);
+		assert_eq!(asset_balance(2, 3), 98);
+		assert_eq!(native_balance(pool_account), 100_993);
+		assert_eq!(asset_balance(2, pool_account), 9_902);
+	});
+}
+
+#[test]
+fn swap_along_path_works() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		create_asset(3, 1, 1_000_000);
+		assert_ok!(AssetConversion::create_pool(Origin::signed(1), NATIVE, OTHER_ASSET));
+		assert_ok!(AssetConversion::add_liquidity(
+			Origin::signed(1), NATIVE, OTHER_ASSET, 200_000, 50_000, 200_000, 50_000, 1,
+		));
+
+		let native = AssetConversion::quote_price_exact_tokens_for_tokens(ASSET, NATIVE, 500, true)
+			.unwrap();
+		let amount_out = AssetConversion::quote_price_exact_tokens_for_tokens(
+			NATIVE, OTHER_ASSET, native, true,
+		).unwrap();
+		assert!(amount_out > 0);
+
+		assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+			Origin::signed(1), vec![ASSET, NATIVE, OTHER_ASSET], 500, amount_out, 2, true,
+		));
+		assert_eq!(asset_balance(3, 2), amount_out);
+		// The intermediate asset only goes from pool to pool.
+		assert_eq!(native_balance(2), 1_000_000);
+	});
+}
+
+#[test]
+fn invalid_paths_are_rejected() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		assert_noop!(
+			AssetConversion::swap_exact_tokens_for_tokens(
+				Origin::signed(1), vec![NATIVE], 1_000, 1, 1, true,
+			),
+			Error::<Test>::InvalidPath,
+		);
+		assert_noop!(
+			AssetConversion::swap_exact_tokens_for_tokens(
+				Origin::signed(1), vec![NATIVE, ASSET, NATIVE], 1_000, 1, 1, true,
+			),
+			Error::<Test>::InvalidPath,
+		);
+		assert_noop!(
+			AssetConversion::swap_exact_tokens_for_tokens(
+				Origin::signed(1), vec![NATIVE, ASSET, NATIVE, ASSET, NATIVE], 1_000, 1, 1, true,
+			),
+			Error::<Test>::InvalidPath,
+		);
+		assert_noop!(
+			AssetConversion::swap_tokens_for_exact_tokens(
+				Origin::signed(1), vec![NATIVE, OTHER_ASSET], 10, 1_000, 1, true,
+			),
+			Error::<Test>::PoolNotFound,
+		);
+	});
+}
+
+#[test]
+fn quotes_work() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		assert_eq!(AssetConversion::get_reserves(NATIVE, ASSET).ok(), Some((100_000, 10_000)));
+		assert_eq!(AssetConversion::get_reserves(ASSET, NATIVE).ok(), Some((10_000, 100_000)));
+		assert!(AssetConversion::get_reserves(NATIVE, OTHER_ASSET).is_err());
+
+		assert_eq!(
+			AssetConversion::quote_price_exact_tokens_for_tokens(NATIVE, ASSET, 1_000, false),
+			Some(100),
+		);
+		assert_eq!(
+			AssetConversion::quote_price_exact_tokens_for_tokens(NATIVE, ASSET, 1_000, true),
+			Some(98),
+		);
+		assert_eq!(
+			AssetConversion::quote_price_tokens_for_exact_tokens(NATIVE, ASSET, 98, false),
+			Some(980),
+		);
+		assert_eq!(
+			AssetConversion::quote_price_tokens_for_exact_tokens(NATIVE, ASSET, 98, true),
+			Some(993),
+		);
+		assert_eq!(
+			AssetConversion::quote_price_exact_tokens_for_tokens(NATIVE, OTHER_ASSET, 1_000, true),
+			None,
+		);
+	});
+}
@@ -0,0 +1,38 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Types used by the asset conversion pallet.
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use sp_runtime::RuntimeDebug;
+
+/// An asset which may be exchanged in a pool: either the native currency or an asset.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug, MaxEncodedLen)]
+pub enum NativeOrAssetId<AssetId> {
+	/// The native currency.
+	Native,
+	/// An asset of the assets registry.
+	Asset(AssetId),
+}
+
+/// Information about a pool.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen)]
+pub struct PoolInfo<PoolAssetId> {
+	/// The liquidity token of the pool.
+	pub lp_token: PoolAssetId,
+}
@@ -19,7 +19,7 @@
 
 use super::*;
 use crate::{Error, mock::*};
-use sp_runtime::TokenError;
+use sp_runtime::{TokenError, traits::ConvertInto};
 use frame_support::{assert_ok, assert_noop, traits::Currency};
 use pallet_balances::Error as BalancesError;
 
@@ -621,6 +621,21 @@ fn imbalances_should_work() {
 	});
 }
 
+#[test]
+fn fungibles_create_should_work() {
+	use frame_support::traits::tokens::fungibles::{Create, Mutate};
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(<Assets as Create<u64>>::create(0, 1, true, 1));
+		assert_eq!(Asset::<Test>::get(0).unwrap().owner, 1);
+		assert_noop!(<Assets as Create<u64>>::create(0, 2, true, 1), Error::<Test>::InUse);
+		assert_noop!(<Assets as Create<u64>>::create(1, 2, true, 0), Error::<Test>::MinBalanceZero);
+
+		assert_ok!(Assets::mint_into(0, &2, 100));
+		assert_eq!(Assets::balance(0, 2), 100);
+	});
+}
+
 #[test]
 fn force_metadata_should_work() {
 	new_test_ext().execute_with(|| {
@@ -699,3 +714,29 @@ fn force_asset_status_should_work(){
 		assert_eq!(Assets::total_supply(0), 200);
 	});
 }
+
+#[test]
+fn balance_conversion_should_work() {
+	new_test_ext().execute_with(|| {
+		use frame_support::traits::tokens::BalanceConversion;
+
+		let id = 42;
+		assert_ok!(Assets::force_create(Origin::root(), id, 1, true, 10));
+		let not_sufficient = 23;
+		assert_ok!(Assets::force_create(Origin::root(), not_sufficient, 1, false, 10));
+
+		assert_eq!(
+			BalanceToAssetBalance::<Balances, Test, ConvertInto>::to_asset_balance(100, 1234),
+			Err(ConversionError::AssetMissing)
+		);
+		assert_eq!(
+			BalanceToAssetBalance::<Balances, Test, ConvertInto>::to_asset_balance(100, not_sufficient),
+			Err(ConversionError::AssetNotSufficient)
+		);
+		// 10 / 1 == 10 -> the conversion should 10x the value
+		assert_eq!(
+			BalanceToAssetBalance::<Balances, Test, ConvertInto>::to_asset_balance(100, id),
+			Ok(100 * 10)
+		);
+	});
+}
@@ -14,33 +14,23 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-pub use pallet::*;
 
-#[frame_support::pallet]
-pub mod pallet {
-	#[allow(unused_imports)]
-	use frame_support::pallet_prelude::*;
-	#[allow(unused_imports)]
-	use frame_system::pallet_prelude::*;
+//! Runtime API definition of the atomic swap pallet.
+//!
+//! This API should be imported and implemented by the runtime of a node that wants its clients,
+//! such as relayers, to look up the pending swaps of an account without scanning the storage.
 
-	#[pallet::pallet]
-	pub struct Pallet<T>(_);
+#![cfg_attr(not(feature = "std"), no_std)]
 
-	#[pallet::config]
-	pub trait Config: frame_system::Config {}
+use sp_std::prelude::*;
 
-	#[pallet::genesis_config]
-	pub struct GenesisConfig {}
-
-	#[cfg(feature = "std")]
-	impl Default for GenesisConfig {
-		fn default() -> Self {
-			Self {}
-		}
-	}
-
-	#[pallet::genesis_build]
-	impl<T: Config> GenesisBuild<T> for GenesisConfig {
-		fn build(&self) {}
+sp_api::decl_runtime_apis! {
+	/// The API to query the pending swaps of the atomic swap pallet.
+	pub trait AtomicSwapApi<AccountId, PendingSwap> where
+		AccountId: codec::Codec,
+		PendingSwap: codec::Codec,
+	{
+		/// Get the pending swaps which can be claimed by `target`, with their hashed proof.
+		fn pending_swaps(target: AccountId) -> Vec<([u8; 32], PendingSwap)>;
 	}
 }
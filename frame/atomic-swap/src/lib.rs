@@ -29,29 +29,44 @@
 //! is used to allow the target to approve (claim) the swap. If the swap is not
 //! claimed within a specified duration of time, the sender may cancel it.
 //!
+//! The proof may be hashed with any of the [`HashAlgorithm`]s, so that swaps can be
+//! made with the hashed time-locked contracts of other chains (e.g. SHA-256 for Bitcoin,
+//! Keccak-256 for Ethereum). The duration may be given in blocks or, for chains with
+//! different block times, as a timestamp duration.
+//!
+//! Besides [`BalanceSwapAction`], the pallet provides [`FungiblesSwapAction`] and
+//! [`NonfungiblesSwapAction`] to swap assets and items. They keep the swapped assets
+//! in the pallet account until the swap is claimed or cancelled.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
 //!
 //! * [`create_swap`](Call::create_swap) - called by a sender to register a new atomic swap
+//! * [`create_swap_with`](Call::create_swap_with) - called by a sender to register a new atomic
+//!   swap with a given hash algorithm and timelock
 //! * [`claim_swap`](Call::claim_swap) - called by the target to approve a swap
 //! * [`cancel_swap`](Call::cancel_swap) - may be called by a sender after a specified duration
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod migrations;
 mod tests;
 
 use sp_std::{prelude::*, marker::PhantomData, ops::{Deref, DerefMut}};
-use sp_io::hashing::blake2_256;
+use sp_io::hashing::{blake2_256, keccak_256, sha2_256};
 use frame_support::{
-	RuntimeDebugNoBound,
-	traits::{Get, Currency, ReservableCurrency, BalanceStatus},
+	Parameter, PalletId, RuntimeDebugNoBound,
+	traits::{
+		Get, Currency, ReservableCurrency, BalanceStatus, Time,
+		tokens::{fungibles, nonfungibles},
+	},
 	weights::Weight,
 	dispatch::DispatchResult,
 };
 use codec::{Encode, Decode};
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{RuntimeDebug, traits::{AccountIdConversion, Saturating}};
 
 /// Pending atomic swap operation.
 #[derive(Clone, Eq, PartialEq, RuntimeDebugNoBound, Encode, Decode)]
@@ -60,13 +75,62 @@ pub struct PendingSwap<T: Config> {
 	pub source: T::AccountId,
 	/// Action of this swap.
 	pub action: T::SwapAction,
-	/// End block of the lock.
-	pub end_block: T::BlockNumber,
+	/// End of the lock, after which the source may cancel the swap.
+	pub expiry: Timelock<T::BlockNumber, MomentOf<T>>,
+	/// Algorithm used to hash the proof.
+	pub hash_algorithm: HashAlgorithm,
 }
 
 /// Hashed proof type.
 pub type HashedProof = [u8; 32];
 
+/// Moment type of the time provider.
+pub type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
+
+/// Algorithm used to hash the secret proof of a swap.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub enum HashAlgorithm {
+	/// BLAKE2b-256, the default for swaps between Substrate chains.
+	Blake2_256,
+	/// SHA-256, as used by Bitcoin HTLCs.
+	Sha2_256,
+	/// Keccak-256, as used by Ethereum HTLCs.
+	Keccak256,
+}
+
+impl Default for HashAlgorithm {
+	fn default() -> Self {
+		HashAlgorithm::Blake2_256
+	}
+}
+
+impl HashAlgorithm {
+	/// All the supported hash algorithms.
+	pub const ALL: [HashAlgorithm; 3] =
+		[HashAlgorithm::Blake2_256, HashAlgorithm::Sha2_256, HashAlgorithm::Keccak256];
+
+	/// Hash the given `proof` with this algorithm.
+	pub fn hash(&self, proof: &[u8]) -> HashedProof {
+		match self {
+			HashAlgorithm::Blake2_256 => blake2_256(proof),
+			HashAlgorithm::Sha2_256 => sha2_256(proof),
+			HashAlgorithm::Keccak256 => keccak_256(proof),
+		}
+	}
+}
+
+/// Lock of a swap, either in blocks or in time.
+///
+/// When creating a swap, this is the duration of the lock. In a [`PendingSwap`] it is the end
+/// of the lock.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub enum Timelock<BlockNumber, Moment> {
+	/// Lock measured in block numbers.
+	BlockNumber(BlockNumber),
+	/// Lock measured with the timestamp of the blocks, as reported by `Config::Time`.
+	Timestamp(Moment),
+}
+
 /// Definition of a pending atomic swap action. It contains the following three phrases:
 ///
 /// - **Reserve**: reserve the resources needed for a swap. This is to make sure that **Claim**
@@ -134,6 +198,96 @@ impl<T: Config, AccountId, C> SwapAction<AccountId, T> for BalanceSwapAction<Acc
 	}
 }
 
+/// A swap action that transfers an `amount` of a `fungibles` asset.
+///
+/// The asset is kept in the pallet account until the swap is claimed or cancelled. Note that the
+/// pallet account must be able to hold the asset, i.e. for assets which are not sufficient it must
+/// be endowed with the native currency beforehand.
+#[derive(Clone, RuntimeDebug, Eq, PartialEq, Encode, Decode)]
+pub struct FungiblesSwapAction<AccountId, F: fungibles::Transfer<AccountId>> {
+	asset: F::AssetId,
+	amount: F::Balance,
+	_marker: PhantomData<(AccountId, F)>,
+}
+
+impl<AccountId, F> FungiblesSwapAction<AccountId, F> where F: fungibles::Transfer<AccountId> {
+	/// Create a new swap action of `amount` of `asset`.
+	pub fn new(asset: F::AssetId, amount: F::Balance) -> Self {
+		Self { asset, amount, _marker: PhantomData }
+	}
+}
+
+impl<T: Config, F> SwapAction<T::AccountId, T> for FungiblesSwapAction<T::AccountId, F>
+	where F: fungibles::Transfer<T::AccountId>
+{
+	fn reserve(&self, source: &T::AccountId) -> DispatchResult {
+		F::transfer(self.asset, source, &Pallet::<T>::account_id(), self.amount, true).map(|_| ())
+	}
+
+	fn claim(&self, _source: &T::AccountId, target: &T::AccountId) -> bool {
+		F::transfer(self.asset, &Pallet::<T>::account_id(), target, self.amount, false).is_ok()
+	}
+
+	fn weight(&self) -> Weight {
+		T::DbWeight::get().reads_writes(3, 3)
+	}
+
+	fn cancel(&self, source: &T::AccountId) {
+		let _ = F::transfer(self.asset, &Pallet::<T>::account_id(), source, self.amount, false);
+	}
+}
+
+/// A swap action that transfers the item `instance` of `class` of a `nonfungibles` collection.
+///
+/// The item is kept in the pallet account until the swap is claimed or cancelled.
+#[derive(Clone, RuntimeDebug, Eq, PartialEq, Encode, Decode)]
+pub struct NonfungiblesSwapAction<AccountId, N> where
+	N: nonfungibles::Transfer<AccountId>,
+	N::ClassId: Parameter,
+	N::InstanceId: Parameter,
+{
+	class: N::ClassId,
+	instance: N::InstanceId,
+	_marker: PhantomData<(AccountId, N)>,
+}
+
+impl<AccountId, N> NonfungiblesSwapAction<AccountId, N> where
+	N: nonfungibles::Transfer<AccountId>,
+	N::ClassId: Parameter,
+	N::InstanceId: Parameter,
+{
+	/// Create a new swap action of the item `instance` of `class`.
+	pub fn new(class: N::ClassId, instance: N::InstanceId) -> Self {
+		Self { class, instance, _marker: PhantomData }
+	}
+}
+
+impl<T: Config, N> SwapAction<T::AccountId, T> for NonfungiblesSwapAction<T::AccountId, N> where
+	N: nonfungibles::Transfer<T::AccountId>,
+	N::ClassId: Parameter,
+	N::InstanceId: Parameter,
+{
+	fn reserve(&self, source: &T::AccountId) -> DispatchResult {
+		frame_support::ensure!(
+			N::owner(&self.class, &self.instance).as_ref() == Some(source),
+			Error::<T>::NotOwner,
+		);
+		N::transfer(&self.class, &self.instance, &Pallet::<T>::account_id())
+	}
+
+	fn claim(&self, _source: &T::AccountId, target: &T::AccountId) -> bool {
+		N::transfer(&self.class, &self.instance, target).is_ok()
+	}
+
+	fn weight(&self) -> Weight {
+		T::DbWeight::get().reads_writes(3, 3)
+	}
+
+	fn cancel(&self, source: &T::AccountId) {
+		let _ = N::transfer(&self.class, &self.instance, source);
+	}
+}
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -161,12 +315,39 @@ pub mod pallet {
 		/// proof instead.
 		#[pallet::constant]
 		type ProofLimit: Get<u32>;
+		/// Time provider, used for swaps locked with a timestamp.
+		type Time: Time;
+		/// The pallet id, used to derive the account holding the assets of pending swaps.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	pub struct Pallet<T>(PhantomData<T>);
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::<T>::get() == Releases::V0 {
+				StorageVersion::<T>::put(Releases::V1);
+				migrations::v1::migrate::<T>().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			migrations::v1::pre_migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			migrations::v1::post_migrate::<T>()
+		}
+	}
+
 	#[pallet::storage]
 	pub type PendingSwaps<T: Config> = StorageDoubleMap<_,
 		Twox64Concat, T::AccountId,
@@ -174,6 +355,27 @@ pub mod pallet {
 		PendingSwap<T>,
 	>;
 
+	/// Storage version of the pallet.
+	#[pallet::storage]
+	pub(crate) type StorageVersion<T> = StorageValue<_, Releases, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig;
+
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			Self
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			// Genesis uses the latest storage version.
+			StorageVersion::<T>::put(Releases::V1);
+		}
+	}
+
 	#[pallet::error]
 	pub enum Error<T> {
 		/// Swap already exists.
@@ -192,6 +394,8 @@ pub mod pallet {
 		ClaimActionMismatch,
 		/// Duration has not yet passed for the swap to be cancelled.
 		DurationNotPassed,
+		/// Source does not own the item to swap.
+		NotOwner,
 	}
 
 	/// Event of atomic swap pallet.
@@ -235,25 +439,40 @@ pub mod pallet {
 			duration: T::BlockNumber,
 		) -> DispatchResult {
 			let source = ensure_signed(origin)?;
-			ensure!(
-				!PendingSwaps::<T>::contains_key(&target, hashed_proof),
-				Error::<T>::AlreadyExist
-			);
-
-			action.reserve(&source)?;
-
-			let swap = PendingSwap {
+			Self::do_create_swap(
 				source,
+				target,
+				hashed_proof,
+				HashAlgorithm::Blake2_256,
 				action,
-				end_block: frame_system::Pallet::<T>::block_number() + duration,
-			};
-			PendingSwaps::<T>::insert(target.clone(), hashed_proof.clone(), swap.clone());
-
-			Self::deposit_event(
-				Event::NewSwap(target, hashed_proof, swap)
-			);
+				Timelock::BlockNumber(duration),
+			)
+		}
 
-			Ok(())
+		/// Register a new atomic swap, with the given hash algorithm and timelock.
+		///
+		/// Same as [`create_swap`](Call::create_swap), but allows to match the hashed time-locked
+		/// contracts of other chains.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `target`: Receiver of the atomic swap.
+		/// - `hashed_proof`: The hash of the secret proof.
+		/// - `hash_algorithm`: The algorithm used to compute `hashed_proof`.
+		/// - `action`: Action to execute once the swap is claimed.
+		/// - `duration`: Locked duration of the atomic swap, either in blocks or as a timestamp
+		///   duration. The same safety recommendations as for `create_swap` apply.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 1).saturating_add(40_000_000))]
+		pub fn create_swap_with(
+			origin: OriginFor<T>,
+			target: T::AccountId,
+			hashed_proof: HashedProof,
+			hash_algorithm: HashAlgorithm,
+			action: T::SwapAction,
+			duration: Timelock<T::BlockNumber, MomentOf<T>>,
+		) -> DispatchResult {
+			let source = ensure_signed(origin)?;
+			Self::do_create_swap(source, target, hashed_proof, hash_algorithm, action, duration)
 		}
 
 		/// Claim an atomic swap.
@@ -263,10 +482,15 @@ pub mod pallet {
 		/// - `proof`: Revealed proof of the claim.
 		/// - `action`: Action defined in the swap, it must match the entry in blockchain. Otherwise
 		///   the operation fails. This is used for weight calculation.
+		/// The proof is hashed with every supported algorithm, to find the swap it unlocks.
 		#[pallet::weight(
-			T::DbWeight::get().reads_writes(1, 1)
+			T::DbWeight::get().reads_writes(HashAlgorithm::ALL.len() as Weight, 1)
 				.saturating_add(40_000_000)
-				.saturating_add((proof.len() as Weight).saturating_mul(100))
+				.saturating_add(
+					(proof.len() as Weight)
+						.saturating_mul(100)
+						.saturating_mul(HashAlgorithm::ALL.len() as Weight)
+				)
 				.saturating_add(action.weight())
 		)]
 		pub fn claim_swap(
@@ -280,9 +504,14 @@ pub mod pallet {
 			);
 
 			let target = ensure_signed(origin)?;
-			let hashed_proof = blake2_256(&proof);
-
-			let swap = PendingSwaps::<T>::get(&target, hashed_proof)
+			let (hashed_proof, swap) = HashAlgorithm::ALL
+				.iter()
+				.find_map(|hash_algorithm| {
+					let hashed_proof = hash_algorithm.hash(&proof);
+					PendingSwaps::<T>::get(&target, hashed_proof)
+						.filter(|swap| swap.hash_algorithm == *hash_algorithm)
+						.map(|swap| (hashed_proof, swap))
+				})
 				.ok_or(Error::<T>::InvalidProof)?;
 			ensure!(swap.action == action, Error::<T>::ClaimActionMismatch);
 
@@ -303,7 +532,7 @@ pub mod pallet {
 		///
 		/// - `target`: Target of the original atomic swap.
 		/// - `hashed_proof`: Hashed proof of the original atomic swap.
-		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1).saturating_add(40_000_000))]
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 1).saturating_add(40_000_000))]
 		pub fn cancel_swap(
 			origin: OriginFor<T>,
 			target: T::AccountId,
@@ -317,10 +546,7 @@ pub mod pallet {
 				swap.source == source,
 				Error::<T>::SourceMismatch,
 			);
-			ensure!(
-				frame_system::Pallet::<T>::block_number() >= swap.end_block,
-				Error::<T>::DurationNotPassed,
-			);
+			ensure!(Self::is_expired(&swap.expiry), Error::<T>::DurationNotPassed);
 
 			swap.action.cancel(&swap.source);
 			PendingSwaps::<T>::remove(&target, hashed_proof.clone());
@@ -333,3 +559,72 @@ pub mod pallet {
 		}
 	}
 }
+
+/// A value placed in storage that represents the current version of the pallet storage.
+/// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+/// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	/// Swaps hashed with blake2_256 and locked in blocks.
+	V0,
+	/// Swaps with a hash algorithm and a timelock.
+	V1,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V0
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account holding the assets of pending swaps, for the swap actions which need one.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// The pending swaps which can be claimed by `target`, with their hashed proof.
+	pub fn pending_swaps(target: &T::AccountId) -> Vec<(HashedProof, PendingSwap<T>)> {
+		PendingSwaps::<T>::iter_prefix(target).collect()
+	}
+
+	fn do_create_swap(
+		source: T::AccountId,
+		target: T::AccountId,
+		hashed_proof: HashedProof,
+		hash_algorithm: HashAlgorithm,
+		action: T::SwapAction,
+		duration: Timelock<T::BlockNumber, MomentOf<T>>,
+	) -> DispatchResult {
+		frame_support::ensure!(
+			!PendingSwaps::<T>::contains_key(&target, hashed_proof),
+			Error::<T>::AlreadyExist
+		);
+
+		action.reserve(&source)?;
+
+		let expiry = match duration {
+			Timelock::BlockNumber(duration) => Timelock::BlockNumber(
+				frame_system::Pallet::<T>::block_number().saturating_add(duration)
+			),
+			Timelock::Timestamp(duration) =>
+				Timelock::Timestamp(T::Time::now().saturating_add(duration)),
+		};
+		let swap = PendingSwap { source, action, expiry, hash_algorithm };
+		PendingSwaps::<T>::insert(target.clone(), hashed_proof.clone(), swap.clone());
+
+		Self::deposit_event(
+			Event::NewSwap(target, hashed_proof, swap)
+		);
+
+		Ok(())
+	}
+
+	fn is_expired(expiry: &Timelock<T::BlockNumber, MomentOf<T>>) -> bool {
+		match expiry {
+			Timelock::BlockNumber(end_block) =>
+				frame_system::Pallet::<T>::block_number() >= *end_block,
+			Timelock::Timestamp(end_time) => T::Time::now() >= *end_time,
+		}
+	}
+}
@@ -0,0 +1,73 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Storage migrations for the atomic swap pallet.
+
+use super::*;
+
+/// Migrate the pending swaps to carry a hash algorithm and a timelock.
+pub(crate) mod v1 {
+	use super::*;
+
+	/// Pending swap as stored before the migration.
+	#[derive(Encode, Decode)]
+	struct OldPendingSwap<T: Config> {
+		source: T::AccountId,
+		action: T::SwapAction,
+		end_block: T::BlockNumber,
+	}
+
+	#[cfg(feature = "try-runtime")]
+	pub(crate) fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			StorageVersion::<T>::get() == Releases::V0,
+			"Storage version too high."
+		);
+
+		Ok(())
+	}
+
+	/// Existing swaps were hashed with blake2_256 and locked in blocks.
+	///
+	/// Returns the weight consumed.
+	pub(crate) fn migrate<T: Config>() -> Weight {
+		let mut reads_writes = 0;
+
+		PendingSwaps::<T>::translate::<OldPendingSwap<T>, _>(|_target, _hashed_proof, old| {
+			reads_writes += 1;
+			Some(PendingSwap {
+				source: old.source,
+				action: old.action,
+				expiry: Timelock::BlockNumber(old.end_block),
+				hash_algorithm: HashAlgorithm::Blake2_256,
+			})
+		});
+
+		T::DbWeight::get().reads_writes(reads_writes, reads_writes)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	pub(crate) fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			StorageVersion::<T>::get() == Releases::V1,
+			"Storage version not updated."
+		);
+
+		Ok(())
+	}
+}
@@ -3,7 +3,9 @@
 use super::*;
 use crate as pallet_atomic_swap;
 
-use frame_support::parameter_types;
+use frame_support::{
+	assert_noop, assert_ok, parameter_types, traits::{GenesisBuild, OnRuntimeUpgrade},
+};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
@@ -21,7 +23,10 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		AtomicSwap: pallet_atomic_swap::{Pallet, Call, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		Uniques: pallet_uniques::{Pallet, Call, Storage, Event<T>},
+		AtomicSwap: pallet_atomic_swap::{Pallet, Call, Storage, Config, Event<T>},
 	}
 );
 
@@ -69,14 +74,118 @@ impl pallet_balances::Config for Test {
 	type AccountStore = System;
 	type WeightInfo = ();
 }
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const AssetDeposit: u64 = 1;
+	pub const ApprovalDeposit: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+}
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type WeightInfo = ();
+	type Extra = ();
+}
+parameter_types! {
+	pub const ClassDeposit: u64 = 2;
+	pub const InstanceDeposit: u64 = 1;
+	pub const KeyLimit: u32 = 50;
+	pub const ValueLimit: u32 = 50;
+	pub const AttributeDepositBase: u64 = 1;
+}
+impl pallet_uniques::Config for Test {
+	type Event = Event;
+	type ClassId = u32;
+	type InstanceId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type ClassDeposit = ClassDeposit;
+	type InstanceDeposit = InstanceDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type AttributeDepositBase = AttributeDepositBase;
+	type DepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type WeightInfo = ();
+}
+
+/// Swap action of the tests, swapping either balances, assets or items.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode)]
+pub enum TestSwapAction {
+	Balance(BalanceSwapAction<u64, Balances>),
+	Asset(FungiblesSwapAction<u64, Assets>),
+	Item(NonfungiblesSwapAction<u64, Uniques>),
+}
+
+impl SwapAction<u64, Test> for TestSwapAction {
+	fn reserve(&self, source: &u64) -> DispatchResult {
+		match self {
+			TestSwapAction::Balance(action) => SwapAction::<u64, Test>::reserve(action, source),
+			TestSwapAction::Asset(action) => SwapAction::<u64, Test>::reserve(action, source),
+			TestSwapAction::Item(action) => SwapAction::<u64, Test>::reserve(action, source),
+		}
+	}
+
+	fn claim(&self, source: &u64, target: &u64) -> bool {
+		match self {
+			TestSwapAction::Balance(action) => SwapAction::<u64, Test>::claim(action, source, target),
+			TestSwapAction::Asset(action) => SwapAction::<u64, Test>::claim(action, source, target),
+			TestSwapAction::Item(action) => SwapAction::<u64, Test>::claim(action, source, target),
+		}
+	}
+
+	fn weight(&self) -> Weight {
+		match self {
+			TestSwapAction::Balance(action) => SwapAction::<u64, Test>::weight(action),
+			TestSwapAction::Asset(action) => SwapAction::<u64, Test>::weight(action),
+			TestSwapAction::Item(action) => SwapAction::<u64, Test>::weight(action),
+		}
+	}
+
+	fn cancel(&self, source: &u64) {
+		match self {
+			TestSwapAction::Balance(action) => SwapAction::<u64, Test>::cancel(action, source),
+			TestSwapAction::Asset(action) => SwapAction::<u64, Test>::cancel(action, source),
+			TestSwapAction::Item(action) => SwapAction::<u64, Test>::cancel(action, source),
+		}
+	}
+}
+
+fn balance(value: u64) -> TestSwapAction {
+	TestSwapAction::Balance(BalanceSwapAction::new(value))
+}
+
 parameter_types! {
 	pub const ProofLimit: u32 = 1024;
 	pub const ExpireDuration: u64 = 100;
+	pub const AtomicSwapPalletId: PalletId = PalletId(*b"py/atswp");
 }
 impl Config for Test {
 	type Event = Event;
-	type SwapAction = BalanceSwapAction<u64, Balances>;
+	type SwapAction = TestSwapAction;
 	type ProofLimit = ProofLimit;
+	type Time = Timestamp;
+	type PalletId = AtomicSwapPalletId;
 }
 
 const A: u64 = 1;
@@ -91,6 +200,7 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		],
 	};
 	genesis.assimilate_storage(&mut t).unwrap();
+	GenesisBuild::<Test>::assimilate_storage(&pallet_atomic_swap::GenesisConfig, &mut t).unwrap();
 	t.into()
 }
 
@@ -110,7 +220,7 @@ fn two_party_successful_swap() {
 			Origin::signed(A),
 			B,
 			hashed_proof.clone(),
-			BalanceSwapAction::new(50),
+			balance(50),
 			1000,
 		).unwrap();
 
@@ -124,7 +234,7 @@ fn two_party_successful_swap() {
 			Origin::signed(B),
 			A,
 			hashed_proof.clone(),
-			BalanceSwapAction::new(75),
+			balance(75),
 			1000,
 		).unwrap();
 
@@ -137,7 +247,7 @@ fn two_party_successful_swap() {
 		AtomicSwap::claim_swap(
 			Origin::signed(A),
 			proof.to_vec(),
-			BalanceSwapAction::new(75),
+			balance(75),
 		).unwrap();
 
 		assert_eq!(Balances::free_balance(A), 100 + 75);
@@ -149,10 +259,203 @@ fn two_party_successful_swap() {
 		AtomicSwap::claim_swap(
 			Origin::signed(B),
 			proof.to_vec(),
-			BalanceSwapAction::new(50),
+			balance(50),
 		).unwrap();
 
 		assert_eq!(Balances::free_balance(A), 100 - 50);
 		assert_eq!(Balances::free_balance(B), 200 + 50);
 	});
 }
+
+#[test]
+fn swaps_can_use_other_hash_algorithms() {
+	new_test_ext().execute_with(|| {
+		let proof: [u8; 2] = [4, 2];
+
+		for (hash_algorithm, value) in vec![
+			(HashAlgorithm::Sha2_256, 10),
+			(HashAlgorithm::Keccak256, 20),
+		] {
+			assert_ok!(AtomicSwap::create_swap_with(
+				Origin::signed(A),
+				B,
+				hash_algorithm.hash(&proof),
+				hash_algorithm,
+				balance(value),
+				Timelock::BlockNumber(1000),
+			));
+		}
+		assert_eq!(Balances::free_balance(A), 100 - 30);
+		assert_eq!(AtomicSwap::pending_swaps(&B).len(), 2);
+
+		// The same proof unlocks both swaps, one at a time.
+		assert_ok!(AtomicSwap::claim_swap(Origin::signed(B), proof.to_vec(), balance(10)));
+		assert_eq!(Balances::free_balance(B), 200 + 10);
+		assert_ok!(AtomicSwap::claim_swap(Origin::signed(B), proof.to_vec(), balance(20)));
+		assert_eq!(Balances::free_balance(B), 200 + 30);
+		assert!(AtomicSwap::pending_swaps(&B).is_empty());
+	});
+}
+
+#[test]
+fn hash_algorithm_must_match_the_hashed_proof() {
+	new_test_ext().execute_with(|| {
+		let proof: [u8; 2] = [4, 2];
+
+		// The hashed proof is a blake2_256 hash, but the swap expects a keccak_256 one.
+		assert_ok!(AtomicSwap::create_swap_with(
+			Origin::signed(A),
+			B,
+			blake2_256(&proof),
+			HashAlgorithm::Keccak256,
+			balance(50),
+			Timelock::BlockNumber(1000),
+		));
+
+		assert_noop!(
+			AtomicSwap::claim_swap(Origin::signed(B), proof.to_vec(), balance(50)),
+			Error::<Test>::InvalidProof,
+		);
+	});
+}
+
+#[test]
+fn swaps_can_be_locked_with_a_timestamp() {
+	new_test_ext().execute_with(|| {
+		let hashed_proof = sha2_256(&[4, 2]);
+		Timestamp::set_timestamp(500);
+
+		assert_ok!(AtomicSwap::create_swap_with(
+			Origin::signed(A),
+			B,
+			hashed_proof,
+			HashAlgorithm::Sha2_256,
+			balance(50),
+			Timelock::Timestamp(1000),
+		));
+		assert_eq!(
+			PendingSwaps::<Test>::get(B, hashed_proof).unwrap().expiry,
+			Timelock::Timestamp(1500),
+		);
+
+		// Blocks do not matter for timestamp locks.
+		System::set_block_number(10_000);
+		Timestamp::set_timestamp(1499);
+		assert_noop!(
+			AtomicSwap::cancel_swap(Origin::signed(A), B, hashed_proof),
+			Error::<Test>::DurationNotPassed,
+		);
+
+		Timestamp::set_timestamp(1500);
+		assert_ok!(AtomicSwap::cancel_swap(Origin::signed(A), B, hashed_proof));
+		assert_eq!(Balances::free_balance(A), 100);
+	});
+}
+
+#[test]
+fn assets_can_be_swapped() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, A, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(A), 0, A, 100));
+
+		let proof: [u8; 2] = [4, 2];
+		let action = TestSwapAction::Asset(FungiblesSwapAction::new(0, 40));
+		assert_ok!(AtomicSwap::create_swap(
+			Origin::signed(A),
+			B,
+			blake2_256(&proof),
+			action.clone(),
+			1000,
+		));
+		assert_eq!(Assets::balance(0, A), 60);
+		assert_eq!(Assets::balance(0, AtomicSwap::account_id()), 40);
+
+		assert_ok!(AtomicSwap::claim_swap(Origin::signed(B), proof.to_vec(), action));
+		assert_eq!(Assets::balance(0, AtomicSwap::account_id()), 0);
+		assert_eq!(Assets::balance(0, B), 40);
+	});
+}
+
+#[test]
+fn items_can_be_swapped() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Uniques::force_create(Origin::root(), 0, A, true));
+		assert_ok!(Uniques::mint(Origin::signed(A), 0, 42, A));
+
+		let hashed_proof = blake2_256(&[4, 2]);
+		let action = TestSwapAction::Item(NonfungiblesSwapAction::new(0, 42));
+
+		// Only the owner of the item can swap it.
+		assert_noop!(
+			AtomicSwap::create_swap(Origin::signed(B), A, hashed_proof, action.clone(), 1000),
+			Error::<Test>::NotOwner,
+		);
+
+		assert_ok!(AtomicSwap::create_swap(Origin::signed(A), B, hashed_proof, action.clone(), 1000));
+		assert_eq!(Uniques::owner(0, 42), Some(AtomicSwap::account_id()));
+
+		// The item goes back to its owner when the swap is cancelled.
+		System::set_block_number(1000);
+		assert_ok!(AtomicSwap::cancel_swap(Origin::signed(A), B, hashed_proof));
+		assert_eq!(Uniques::owner(0, 42), Some(A));
+
+		assert_ok!(AtomicSwap::create_swap(Origin::signed(A), B, hashed_proof, action.clone(), 1000));
+		assert_ok!(AtomicSwap::claim_swap(Origin::signed(B), vec![4, 2], action));
+		assert_eq!(Uniques::owner(0, 42), Some(B));
+	});
+}
+
+#[test]
+fn expiry_saturates() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(10);
+		let hashed_proof = blake2_256(&[4, 2]);
+		assert_ok!(AtomicSwap::create_swap(
+			Origin::signed(A),
+			B,
+			hashed_proof,
+			balance(50),
+			u64::MAX,
+		));
+		assert_eq!(
+			PendingSwaps::<Test>::get(B, hashed_proof).unwrap().expiry,
+			Timelock::BlockNumber(u64::MAX),
+		);
+	});
+}
+
+#[test]
+fn genesis_uses_latest_storage_version() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V1);
+
+		// Swaps created since genesis are left alone by the migration.
+		let hashed_proof = blake2_256(&[4, 2]);
+		assert_ok!(AtomicSwap::create_swap(Origin::signed(A), B, hashed_proof, balance(50), 10));
+		AtomicSwap::on_runtime_upgrade();
+		assert!(PendingSwaps::<Test>::get(B, hashed_proof).is_some());
+	});
+}
+
+#[test]
+fn migration_to_v1_works() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::<Test>::put(Releases::V0);
+		let hashed_proof = blake2_256(&[4, 2]);
+		// A swap as stored by the previous version: source, action and end block.
+		frame_support::storage::unhashed::put(
+			&PendingSwaps::<Test>::hashed_key_for(B, hashed_proof),
+			&(A, balance(50), 10u64),
+		);
+
+		AtomicSwap::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V1);
+		assert_eq!(PendingSwaps::<Test>::get(B, hashed_proof), Some(PendingSwap {
+			source: A,
+			action: balance(50),
+			expiry: Timelock::BlockNumber(10),
+			hash_algorithm: HashAlgorithm::Blake2_256,
+		}));
+	});
+}
@@ -65,7 +65,6 @@ pub mod pallet {
 		pub keys: Vec<AuthorityId>,
 	}
 
-	#[cfg(feature = "std")]
 	impl Default for GenesisConfig {
 		fn default() -> Self {
 			Self {
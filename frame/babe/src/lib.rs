@@ -300,7 +300,6 @@ pub mod pallet {
 		pub epoch_config: Option<BabeEpochConfiguration>,
 	}
 
-	#[cfg(feature = "std")]
 	impl Default for GenesisConfig {
 		fn default() -> Self {
 			GenesisConfig {
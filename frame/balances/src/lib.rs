@@ -514,7 +514,6 @@ pub mod pallet {
 		pub balances: Vec<(T::AccountId, T::Balance)>,
 	}
 
-	#[cfg(feature = "std")]
 	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
 		fn default() -> Self {
 			Self {
@@ -541,7 +540,10 @@ pub mod pallet {
 			}
 
 			// ensure no duplicates exist.
-			let endowed_accounts = self.balances.iter().map(|(x, _)| x).cloned().collect::<std::collections::BTreeSet<_>>();
+			let endowed_accounts = self.balances.iter()
+				.map(|(x, _)| x)
+				.cloned()
+				.collect::<sp_std::collections::btree_set::BTreeSet<_>>();
 
 			assert!(endowed_accounts.len() == self.balances.len(), "duplicate balances in genesis.");
 
@@ -29,6 +29,7 @@ pub use utils::*;
 pub use analysis::{Analysis, BenchmarkSelector, RegressionModel, AnalysisChoice};
 #[doc(hidden)]
 pub use sp_io::storage::root as storage_root;
+pub use sp_runtime::StateVersion;
 #[doc(hidden)]
 pub use sp_runtime::traits::Zero;
 #[doc(hidden)]
@@ -820,7 +821,7 @@ macro_rules! impl_benchmark {
 
 							// Time the storage root recalculation.
 							let start_storage_root = $crate::benchmarking::current_time();
-							$crate::storage_root();
+							$crate::storage_root($crate::StateVersion::V1);
 							let finish_storage_root = $crate::benchmarking::current_time();
 							let elapsed_storage_root = finish_storage_root - start_storage_root;
 
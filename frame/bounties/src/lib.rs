@@ -35,6 +35,10 @@
 //! curator or once the bounty is active or payout is pending, resulting in the slash of the
 //! curator's deposit.
 //!
+//! An active bounty can be split into child bounties, each with its own curator, through a
+//! `ChildBountyManager` such as `pallet-child-bounties`. A bounty with active child bounties can
+//! neither be awarded nor closed.
+//!
 //!
 //! ### Terminology
 //!
@@ -131,6 +135,35 @@ pub trait Config: frame_system::Config + pallet_treasury::Config {
 
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
+
+	/// The child bounty manager.
+	type ChildBountyManager: ChildBountyManager<BalanceOf<Self>>;
+}
+
+/// Interface of a pallet managing the child bounties of a bounty.
+pub trait ChildBountyManager<Balance> {
+	/// Number of active child bounties of the bounty `bounty_id`.
+	fn child_bounties_count(bounty_id: BountyIndex) -> BountyIndex;
+
+	/// Takes the total curator fees paid out by the child bounties of the bounty `bounty_id`.
+	///
+	/// Those fees are deducted from the fee of the parent bounty curator when it is claimed.
+	fn children_curator_fees(bounty_id: BountyIndex) -> Balance;
+
+	/// Hook called when the bounty `bounty_id` is removed without being claimed.
+	fn bounty_removed(bounty_id: BountyIndex);
+}
+
+impl<Balance: Zero> ChildBountyManager<Balance> for () {
+	fn child_bounties_count(_bounty_id: BountyIndex) -> BountyIndex {
+		Default::default()
+	}
+
+	fn children_curator_fees(_bounty_id: BountyIndex) -> Balance {
+		Zero::zero()
+	}
+
+	fn bounty_removed(_bounty_id: BountyIndex) {}
 }
 
 /// An index of a bounty. Just a `u32`.
@@ -153,6 +186,13 @@ pub struct Bounty<AccountId, Balance, BlockNumber> {
 	status: BountyStatus<AccountId, BlockNumber>,
 }
 
+impl<AccountId: Clone, Balance, BlockNumber: Clone> Bounty<AccountId, Balance, BlockNumber> {
+	/// Getter for bounty status, to be used for child bounties.
+	pub fn get_status(&self) -> BountyStatus<AccountId, BlockNumber> {
+		self.status.clone()
+	}
+}
+
 /// The status of a bounty proposal.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
 pub enum BountyStatus<AccountId, BlockNumber> {
@@ -253,6 +293,8 @@ decl_error! {
 		PendingPayout,
 		/// The bounties cannot be claimed/closed because it's still in the countdown period.
 		Premature,
+		/// The bounty cannot be closed because it has active child bounties.
+		HasActiveChildBounty,
 	}
 }
 
@@ -299,7 +341,7 @@ decl_module! {
 		/// - `value`: The total payment amount of this bounty, curator fee included.
 		/// - `description`: The description of this bounty.
 		#[weight = <T as Config>::WeightInfo::propose_bounty(description.len() as u32)]
-		fn propose_bounty(
+		pub fn propose_bounty(
 			origin,
 			#[compact] value: BalanceOf<T>,
 			description: Vec<u8>,
@@ -317,7 +359,7 @@ decl_module! {
 		/// - O(1).
 		/// # </weight>
 		#[weight = <T as Config>::WeightInfo::approve_bounty()]
-		fn approve_bounty(origin, #[compact] bounty_id: BountyIndex) {
+		pub fn approve_bounty(origin, #[compact] bounty_id: BountyIndex) {
 			T::ApproveOrigin::ensure_origin(origin)?;
 
 			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
@@ -340,7 +382,7 @@ decl_module! {
 		/// - O(1).
 		/// # </weight>
 		#[weight = <T as Config>::WeightInfo::propose_curator()]
-		fn propose_curator(
+		pub fn propose_curator(
 			origin,
 			#[compact] bounty_id: BountyIndex,
 			curator: <T::Lookup as StaticLookup>::Source,
@@ -385,7 +427,7 @@ decl_module! {
 		/// - O(1).
 		/// # </weight>
 		#[weight = <T as Config>::WeightInfo::unassign_curator()]
-		fn unassign_curator(
+		pub fn unassign_curator(
 			origin,
 			#[compact] bounty_id: BountyIndex,
 		) {
@@ -466,7 +508,7 @@ decl_module! {
 		/// - O(1).
 		/// # </weight>
 		#[weight = <T as Config>::WeightInfo::accept_curator()]
-		fn accept_curator(origin, #[compact] bounty_id: BountyIndex) {
+		pub fn accept_curator(origin, #[compact] bounty_id: BountyIndex) {
 			let signer = ensure_signed(origin)?;
 
 			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
@@ -501,7 +543,7 @@ decl_module! {
 		/// - O(1).
 		/// # </weight>
 		#[weight = <T as Config>::WeightInfo::award_bounty()]
-		fn award_bounty(origin, #[compact] bounty_id: BountyIndex, beneficiary: <T::Lookup as StaticLookup>::Source) {
+		pub fn award_bounty(origin, #[compact] bounty_id: BountyIndex, beneficiary: <T::Lookup as StaticLookup>::Source) {
 			let signer = ensure_signed(origin)?;
 			let beneficiary = T::Lookup::lookup(beneficiary)?;
 
@@ -516,6 +558,11 @@ decl_module! {
 					},
 					_ => return Err(Error::<T>::UnexpectedStatus.into()),
 				}
+				// The bounty cannot be awarded while its child bounties still hold funds.
+				ensure!(
+					T::ChildBountyManager::child_bounties_count(bounty_id) == 0,
+					Error::<T>::HasActiveChildBounty,
+				);
 				bounty.status = BountyStatus::PendingPayout {
 					curator: signer,
 					beneficiary: beneficiary.clone(),
@@ -538,7 +585,7 @@ decl_module! {
 		/// - O(1).
 		/// # </weight>
 		#[weight = <T as Config>::WeightInfo::claim_bounty()]
-		fn claim_bounty(origin, #[compact] bounty_id: BountyIndex) {
+		pub fn claim_bounty(origin, #[compact] bounty_id: BountyIndex) {
 			let _ = ensure_signed(origin)?; // anyone can trigger claim
 
 			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
@@ -547,7 +594,9 @@ decl_module! {
 					ensure!(system::Pallet::<T>::block_number() >= unlock_at, Error::<T>::Premature);
 					let bounty_account = Self::bounty_account_id(bounty_id);
 					let balance = T::Currency::free_balance(&bounty_account);
-					let fee = bounty.fee.min(balance); // just to be safe
+					// The curators of the child bounties were paid out of the parent curator fee.
+					let children_fee = T::ChildBountyManager::children_curator_fees(bounty_id);
+					let fee = bounty.fee.saturating_sub(children_fee).min(balance); // just to be safe
 					let payout = balance.saturating_sub(fee);
 					let err_amount = T::Currency::unreserve(&curator, bounty.curator_deposit);
 					debug_assert!(err_amount.is_zero());
@@ -579,12 +628,18 @@ decl_module! {
 		/// - O(1).
 		/// # </weight>
 		#[weight = <T as Config>::WeightInfo::close_bounty_proposed().max(<T as Config>::WeightInfo::close_bounty_active())]
-		fn close_bounty(origin, #[compact] bounty_id: BountyIndex) -> DispatchResultWithPostInfo {
+		pub fn close_bounty(origin, #[compact] bounty_id: BountyIndex) -> DispatchResultWithPostInfo {
 			T::RejectOrigin::ensure_origin(origin)?;
 
 			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResultWithPostInfo {
 				let bounty = maybe_bounty.as_ref().ok_or(Error::<T>::InvalidIndex)?;
 
+				// Child bounties must be closed first, so that their funds are returned.
+				ensure!(
+					T::ChildBountyManager::child_bounties_count(bounty_id) == 0,
+					Error::<T>::HasActiveChildBounty,
+				);
+
 				match &bounty.status {
 					BountyStatus::Proposed => {
 						// The reject origin would like to cancel a proposed bounty.
@@ -631,6 +686,8 @@ decl_module! {
 				debug_assert!(res.is_ok());
 				*maybe_bounty = None;
 
+				T::ChildBountyManager::bounty_removed(bounty_id);
+
 				Self::deposit_event(Event::<T>::BountyCanceled(bounty_id));
 				Ok(Some(<T as Config>::WeightInfo::close_bounty_active()).into())
 			})
@@ -647,7 +704,7 @@ decl_module! {
 		/// - O(1).
 		/// # </weight>
 		#[weight = <T as Config>::WeightInfo::extend_bounty_expiry()]
-		fn extend_bounty_expiry(origin, #[compact] bounty_id: BountyIndex, _remark: Vec<u8>) {
+		pub fn extend_bounty_expiry(origin, #[compact] bounty_id: BountyIndex, _remark: Vec<u8>) {
 			let signer = ensure_signed(origin)?;
 
 			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
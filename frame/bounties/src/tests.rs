@@ -125,6 +125,10 @@ impl pallet_treasury::Config for Test {
 	type WeightInfo = ();
 	type SpendFunds = Bounties;
 	type MaxApprovals = MaxApprovals;
+	type SpendAssetOrigin = frame_system::EnsureRoot<u128>;
+	type AssetId = u32;
+	type AssetBalance = u64;
+	type Assets = pallet_treasury::NoAssets<u32, u64>;
 }
 parameter_types! {
 	pub const BountyDepositBase: u64 = 80;
@@ -144,6 +148,7 @@ impl Config for Test {
 	type DataDepositPerByte = DataDepositPerByte;
 	type MaximumReasonLength = MaximumReasonLength;
 	type WeightInfo = ();
+	type ChildBountyManager = ();
 }
 
 type TreasuryError = pallet_treasury::Error::<Test>;
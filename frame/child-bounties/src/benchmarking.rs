@@ -0,0 +1,194 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Child bounties pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller, impl_benchmark_test_suite};
+use frame_support::traits::OnInitialize;
+
+use crate::Pallet as ChildBounties;
+use pallet_bounties::Module as Bounties;
+use pallet_treasury::Pallet as Treasury;
+
+const SEED: u32 = 0;
+
+fn setup_pot_account<T: Config>() {
+	let pot_account = Bounties::<T>::account_id();
+	let value = T::Currency::minimum_balance().saturating_mul(1_000_000_000u32.into());
+	let _ = T::Currency::make_free_balance_be(&pot_account, value);
+}
+
+// Create an active parent bounty, returning its curator and index.
+fn create_parent_bounty<T: Config>() -> Result<(T::AccountId, BountyIndex), &'static str> {
+	setup_pot_account::<T>();
+
+	let caller: T::AccountId = account("caller", 0, SEED);
+	let value = T::BountyValueMinimum::get()
+		.max(T::ChildBountyValueMinimum::get())
+		.saturating_mul(100u32.into());
+	let fee = value / 2u32.into();
+	let reason = Vec::new();
+	let deposit = T::BountyDepositBase::get();
+	let _ = T::Currency::make_free_balance_be(&caller, deposit + T::Currency::minimum_balance());
+	let curator: T::AccountId = account("curator", 0, SEED);
+	let _ = T::Currency::make_free_balance_be(&curator, fee + T::Currency::minimum_balance());
+	let curator_lookup = T::Lookup::unlookup(curator.clone());
+
+	Bounties::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, reason)?;
+	let bounty_id = Bounties::<T>::bounty_count() - 1;
+	Bounties::<T>::approve_bounty(RawOrigin::Root.into(), bounty_id)?;
+	Treasury::<T>::on_initialize(T::BlockNumber::zero());
+	Bounties::<T>::propose_curator(RawOrigin::Root.into(), bounty_id, curator_lookup, fee)?;
+	Bounties::<T>::accept_curator(RawOrigin::Signed(curator.clone()).into(), bounty_id)?;
+	Ok((curator, bounty_id))
+}
+
+// Value and fee of the child bounties added by the benchmarks.
+fn child_bounty_value_and_fee<T: Config>() -> (BalanceOf<T>, BalanceOf<T>) {
+	let value = T::ChildBountyValueMinimum::get().saturating_mul(10u32.into());
+	(value, value / 2u32.into())
+}
+
+// Add a child bounty to an active parent bounty, returning the parent curator, the parent index
+// and the child index.
+fn create_child_bounty<T: Config>() -> Result<(T::AccountId, BountyIndex, BountyIndex), &'static str> {
+	let (curator, bounty_id) = create_parent_bounty::<T>()?;
+	let (value, _) = child_bounty_value_and_fee::<T>();
+	ChildBounties::<T>::add_child_bounty(
+		RawOrigin::Signed(curator.clone()).into(),
+		bounty_id,
+		value,
+		Vec::new(),
+	)?;
+	let child_bounty_id = ChildBountyCount::<T>::get() - 1;
+	Ok((curator, bounty_id, child_bounty_id))
+}
+
+// Add a child bounty with an active curator, returning the parent index, the child index and the
+// child curator.
+fn create_active_child_bounty<T: Config>() -> Result<(BountyIndex, BountyIndex, T::AccountId), &'static str> {
+	let (curator, bounty_id, child_bounty_id) = create_child_bounty::<T>()?;
+	let (_, fee) = child_bounty_value_and_fee::<T>();
+	let child_curator: T::AccountId = account("child-curator", 0, SEED);
+	let _ = T::Currency::make_free_balance_be(&child_curator, fee + T::Currency::minimum_balance());
+	ChildBounties::<T>::propose_curator(
+		RawOrigin::Signed(curator).into(),
+		bounty_id,
+		child_bounty_id,
+		T::Lookup::unlookup(child_curator.clone()),
+		fee,
+	)?;
+	ChildBounties::<T>::accept_curator(
+		RawOrigin::Signed(child_curator.clone()).into(),
+		bounty_id,
+		child_bounty_id,
+	)?;
+	Ok((bounty_id, child_bounty_id, child_curator))
+}
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+benchmarks! {
+	add_child_bounty {
+		let d in 0 .. T::MaximumReasonLength::get();
+
+		let (curator, bounty_id) = create_parent_bounty::<T>()?;
+		let (value, _) = child_bounty_value_and_fee::<T>();
+		let description = vec![0; d as usize];
+	}: _(RawOrigin::Signed(curator), bounty_id, value, description)
+	verify {
+		assert_last_event::<T>(Event::<T>::Added(bounty_id, 0).into())
+	}
+
+	propose_curator {
+		let (curator, bounty_id, child_bounty_id) = create_child_bounty::<T>()?;
+		let (_, fee) = child_bounty_value_and_fee::<T>();
+		let child_curator: T::AccountId = account("child-curator", 0, SEED);
+		let child_curator_lookup = T::Lookup::unlookup(child_curator);
+	}: _(RawOrigin::Signed(curator), bounty_id, child_bounty_id, child_curator_lookup, fee)
+
+	accept_curator {
+		let (curator, bounty_id, child_bounty_id) = create_child_bounty::<T>()?;
+		let (_, fee) = child_bounty_value_and_fee::<T>();
+		let child_curator: T::AccountId = account("child-curator", 0, SEED);
+		let _ = T::Currency::make_free_balance_be(&child_curator, fee + T::Currency::minimum_balance());
+		ChildBounties::<T>::propose_curator(
+			RawOrigin::Signed(curator).into(),
+			bounty_id,
+			child_bounty_id,
+			T::Lookup::unlookup(child_curator.clone()),
+			fee,
+		)?;
+	}: _(RawOrigin::Signed(child_curator), bounty_id, child_bounty_id)
+
+	// Worst case when the parent bounty update is due and any sender unassigns the child curator.
+	unassign_curator {
+		let (bounty_id, child_bounty_id, _) = create_active_child_bounty::<T>()?;
+		frame_system::Pallet::<T>::set_block_number(T::BountyUpdatePeriod::get() + 1u32.into());
+		let caller = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), bounty_id, child_bounty_id)
+
+	award_child_bounty {
+		let (bounty_id, child_bounty_id, child_curator) = create_active_child_bounty::<T>()?;
+		let beneficiary = T::Lookup::unlookup(account("beneficiary", 0, SEED));
+	}: _(RawOrigin::Signed(child_curator), bounty_id, child_bounty_id, beneficiary)
+
+	claim_child_bounty {
+		let (bounty_id, child_bounty_id, child_curator) = create_active_child_bounty::<T>()?;
+		let beneficiary_account: T::AccountId = account("beneficiary", 0, SEED);
+		let beneficiary = T::Lookup::unlookup(beneficiary_account.clone());
+		ChildBounties::<T>::award_child_bounty(
+			RawOrigin::Signed(child_curator.clone()).into(),
+			bounty_id,
+			child_bounty_id,
+			beneficiary,
+		)?;
+
+		frame_system::Pallet::<T>::set_block_number(T::BountyDepositPayoutDelay::get());
+		ensure!(T::Currency::free_balance(&beneficiary_account).is_zero(), "Beneficiary already has balance");
+	}: _(RawOrigin::Signed(child_curator), bounty_id, child_bounty_id)
+	verify {
+		ensure!(!T::Currency::free_balance(&beneficiary_account).is_zero(), "Beneficiary didn't get paid");
+	}
+
+	close_child_bounty_added {
+		let (_, bounty_id, child_bounty_id) = create_child_bounty::<T>()?;
+	}: close_child_bounty(RawOrigin::Root, bounty_id, child_bounty_id)
+	verify {
+		assert_last_event::<T>(Event::<T>::Canceled(bounty_id, child_bounty_id).into())
+	}
+
+	close_child_bounty_active {
+		let (bounty_id, child_bounty_id, _) = create_active_child_bounty::<T>()?;
+	}: close_child_bounty(RawOrigin::Root, bounty_id, child_bounty_id)
+	verify {
+		assert_last_event::<T>(Event::<T>::Canceled(bounty_id, child_bounty_id).into())
+	}
+}
+
+impl_benchmark_test_suite!(
+	ChildBounties,
+	crate::tests::new_test_ext(),
+	crate::tests::Test,
+);
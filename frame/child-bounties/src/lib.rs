@@ -0,0 +1,738 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Child Bounties Pallet ( pallet-child-bounties )
+//!
+//! ## Child Bounty
+//!
+//! > NOTE: This pallet is tightly coupled with pallet-treasury and pallet-bounties.
+//!
+//! With child bounties, a large bounty proposal can be divided into smaller chunks, for parallel
+//! execution, and for efficient governance and tracking of spent funds.
+//!
+//! A child bounty is a smaller piece of work, extracted from a parent bounty. The curator of the
+//! parent bounty adds child bounties, funded from the parent bounty account, and proposes a
+//! curator for each of them. Each child bounty then follows its own lifecycle: the child curator
+//! accepts the role against a deposit, awards the child bounty to a beneficiary, who can claim the
+//! payout after a delay.
+//!
+//! The curator fees of the child bounties are paid out of the curator fee of the parent bounty.
+//! The parent bounty can neither be awarded nor closed while it has active child bounties.
+//!
+//! ### Terminology
+//!
+//! - **Parent bounty:** An active bounty of pallet-bounties funding the child bounties.
+//! - **Child bounty:** A portion of the parent bounty value, with its own curator and payout.
+//! - **Child bounty curator:** An account managing the child bounty and assigning its payout
+//!   address.
+//! - **Child bounty curator fee:** The payment of the child bounty curator, included in the child
+//!   bounty value.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! Child Bounty protocol:
+//! - `add_child_bounty` - Add a child bounty for a parent bounty, funded from the parent bounty.
+//! - `propose_curator` - Assign an account to a child bounty as candidate curator.
+//! - `accept_curator` - Accept a child bounty assignment, setting a curator deposit.
+//! - `award_child_bounty` - Close and pay out the specified amount for the completed work.
+//! - `claim_child_bounty` - Claim a specific child bounty amount from the payout address.
+//! - `unassign_curator` - Unassign an accepted curator from a specific child bounty.
+//! - `close_child_bounty` - Cancel the child bounty and return its funds to the parent bounty.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use sp_std::prelude::*;
+
+use frame_support::traits::{
+	Currency, ExistenceRequirement::{AllowDeath, KeepAlive}, Get, OnUnbalanced,
+	ReservableCurrency, WithdrawReasons,
+};
+
+use sp_runtime::{
+	traits::{AccountIdConversion, BadOrigin, CheckedSub, Saturating, StaticLookup, Zero},
+	DispatchResult, RuntimeDebug,
+};
+
+use codec::{Decode, Encode};
+use frame_support::pallet_prelude::*;
+use frame_system::pallet_prelude::*;
+use pallet_bounties::BountyStatus;
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub use pallet_bounties::BountyIndex;
+
+type BalanceOf<T> = pallet_treasury::BalanceOf<T>;
+type BountiesError<T> = pallet_bounties::Error<T>;
+
+/// A child bounty proposal.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ChildBounty<AccountId, Balance, BlockNumber> {
+	/// The parent of this child bounty.
+	parent_bounty: BountyIndex,
+	/// The (total) amount that should be paid if this child bounty is rewarded.
+	value: Balance,
+	/// The child bounty curator fee. Included in value.
+	fee: Balance,
+	/// The deposit of the child bounty curator.
+	curator_deposit: Balance,
+	/// The status of this child bounty.
+	status: ChildBountyStatus<AccountId, BlockNumber>,
+}
+
+/// The status of a child bounty.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ChildBountyStatus<AccountId, BlockNumber> {
+	/// The child bounty is added and waiting for curator assignment.
+	Added,
+	/// A curator has been proposed by the parent bounty curator. Waiting for acceptance from the
+	/// child bounty curator.
+	CuratorProposed {
+		/// The assigned child bounty curator of this child bounty.
+		curator: AccountId,
+	},
+	/// The child bounty is active and waiting to be awarded. Its update deadline is the one of the
+	/// parent bounty.
+	Active {
+		/// The curator of this child bounty.
+		curator: AccountId,
+	},
+	/// The child bounty is awarded and waiting to released after a delay.
+	PendingPayout {
+		/// The child bounty curator of this child bounty.
+		curator: AccountId,
+		/// The beneficiary of the child bounty.
+		beneficiary: AccountId,
+		/// When the child bounty can be claimed.
+		unlock_at: BlockNumber,
+	},
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config + pallet_treasury::Config + pallet_bounties::Config
+	{
+		/// Maximum number of child bounties that can be added to a parent bounty.
+		#[pallet::constant]
+		type MaxActiveChildBountyCount: Get<u32>;
+
+		/// Minimum value for a child bounty.
+		#[pallet::constant]
+		type ChildBountyValueMinimum: Get<BalanceOf<Self>>;
+
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The parent bounty is not in active state.
+		ParentBountyNotActive,
+		/// The bounty balance is not enough to add new child bounty.
+		InsufficientBountyBalance,
+		/// Number of child bounties exceeds limit `MaxActiveChildBountyCount`.
+		TooManyChildBounties,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	pub enum Event<T: Config> {
+		/// A child bounty is added. \[index, child_index\]
+		Added(BountyIndex, BountyIndex),
+		/// A child bounty is awarded to a beneficiary. \[index, child_index, beneficiary\]
+		Awarded(BountyIndex, BountyIndex, T::AccountId),
+		/// A child bounty is claimed by beneficiary. \[index, child_index, payout, beneficiary\]
+		Claimed(BountyIndex, BountyIndex, BalanceOf<T>, T::AccountId),
+		/// A child bounty is cancelled. \[index, child_index\]
+		Canceled(BountyIndex, BountyIndex),
+	}
+
+	/// Number of total child bounties.
+	#[pallet::storage]
+	#[pallet::getter(fn child_bounty_count)]
+	pub type ChildBountyCount<T: Config> = StorageValue<_, BountyIndex, ValueQuery>;
+
+	/// Number of active child bounties per parent bounty.
+	#[pallet::storage]
+	#[pallet::getter(fn parent_child_bounties)]
+	pub type ParentChildBounties<T: Config> =
+		StorageMap<_, Twox64Concat, BountyIndex, u32, ValueQuery>;
+
+	/// Child bounties that have been added, keyed by parent bounty and child bounty index.
+	#[pallet::storage]
+	#[pallet::getter(fn child_bounties)]
+	pub type ChildBounties<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		BountyIndex,
+		Twox64Concat,
+		BountyIndex,
+		ChildBounty<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	/// The description of each child bounty.
+	#[pallet::storage]
+	#[pallet::getter(fn child_bounty_descriptions)]
+	pub type ChildBountyDescriptions<T: Config> =
+		StorageMap<_, Twox64Concat, BountyIndex, Vec<u8>>;
+
+	/// The cumulative curator fees paid out by the child bounties of each parent bounty.
+	#[pallet::storage]
+	#[pallet::getter(fn children_curator_fees)]
+	pub type ChildrenCuratorFees<T: Config> =
+		StorageMap<_, Twox64Concat, BountyIndex, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Add a new child bounty.
+		///
+		/// The dispatch origin for this call must be the curator of the parent bounty, and the
+		/// parent bounty must be in "active" state.
+		///
+		/// The child bounty is funded from the parent bounty account, which must keep at least
+		/// the existential deposit.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty for which the child bounty is added.
+		/// - `value`: Value for executing the child bounty.
+		/// - `description`: Text description for the child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::add_child_bounty(description.len() as u32))]
+		pub fn add_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] value: BalanceOf<T>,
+			description: Vec<u8>,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+
+			ensure!(
+				description.len() <= T::MaximumReasonLength::get() as usize,
+				BountiesError::<T>::ReasonTooBig,
+			);
+			ensure!(value >= T::ChildBountyValueMinimum::get(), BountiesError::<T>::InvalidValue);
+			ensure!(
+				Self::parent_child_bounties(parent_bounty_id) < T::MaxActiveChildBountyCount::get(),
+				Error::<T>::TooManyChildBounties,
+			);
+
+			let (curator, _) = Self::ensure_bounty_active(parent_bounty_id)?;
+			ensure!(signer == curator, BountiesError::<T>::RequireCurator);
+
+			// Read parent bounty account info.
+			let parent_bounty_account =
+				pallet_bounties::Module::<T>::bounty_account_id(parent_bounty_id);
+
+			// Ensure parent bounty has enough balance after adding child bounty.
+			let bounty_balance = T::Currency::free_balance(&parent_bounty_account);
+			let new_bounty_balance = bounty_balance
+				.checked_sub(&value)
+				.ok_or(Error::<T>::InsufficientBountyBalance)?;
+			T::Currency::ensure_can_withdraw(
+				&parent_bounty_account,
+				value,
+				WithdrawReasons::TRANSFER,
+				new_bounty_balance,
+			)?;
+
+			let child_bounty_id = Self::child_bounty_count();
+			let child_bounty_account = Self::child_bounty_account_id(child_bounty_id);
+
+			// Transfer funds from parent bounty to child bounty.
+			T::Currency::transfer(&parent_bounty_account, &child_bounty_account, value, KeepAlive)?;
+
+			ChildBountyCount::<T>::put(child_bounty_id.saturating_add(1));
+			ParentChildBounties::<T>::mutate(parent_bounty_id, |count| {
+				*count = count.saturating_add(1)
+			});
+			ChildBounties::<T>::insert(parent_bounty_id, child_bounty_id, ChildBounty {
+				parent_bounty: parent_bounty_id,
+				value,
+				fee: Zero::zero(),
+				curator_deposit: Zero::zero(),
+				status: ChildBountyStatus::Added,
+			});
+			ChildBountyDescriptions::<T>::insert(child_bounty_id, description);
+
+			Self::deposit_event(Event::<T>::Added(parent_bounty_id, child_bounty_id));
+			Ok(())
+		}
+
+		/// Propose curator for funded child bounty.
+		///
+		/// The dispatch origin for this call must be the curator of the parent bounty, and the
+		/// parent bounty must be in "active" state.
+		///
+		/// The child bounty must be in "Added" state. The fee is paid out of the child bounty
+		/// value, and deducted from the fee of the parent bounty curator once paid out.
+		///
+		/// - `parent_bounty_id`: Index of parent bounty.
+		/// - `child_bounty_id`: Index of child bounty.
+		/// - `curator`: Address of child bounty curator.
+		/// - `fee`: Payment fee to child bounty curator for execution.
+		#[pallet::weight(<T as Config>::WeightInfo::propose_curator())]
+		pub fn propose_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+			curator: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] fee: BalanceOf<T>,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+			let child_bounty_curator = T::Lookup::lookup(curator)?;
+
+			let (curator, _) = Self::ensure_bounty_active(parent_bounty_id)?;
+			ensure!(signer == curator, BountiesError::<T>::RequireCurator);
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty =
+						maybe_child_bounty.as_mut().ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					ensure!(
+						child_bounty.status == ChildBountyStatus::Added,
+						BountiesError::<T>::UnexpectedStatus,
+					);
+					ensure!(fee < child_bounty.value, BountiesError::<T>::InvalidFee);
+
+					child_bounty.status =
+						ChildBountyStatus::CuratorProposed { curator: child_bounty_curator };
+					child_bounty.fee = fee;
+
+					Ok(())
+				},
+			)
+		}
+
+		/// Accept the curator role for the child bounty.
+		///
+		/// The dispatch origin for this call must be the curator of this child bounty.
+		///
+		/// A deposit will be reserved from the child bounty curator and refunded upon successful
+		/// payout or cancellation. Its ratio to the fee is `BountyCuratorDeposit`, like for parent
+		/// bounties.
+		///
+		/// - `parent_bounty_id`: Index of parent bounty.
+		/// - `child_bounty_id`: Index of child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::accept_curator())]
+		pub fn accept_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+
+			let _ = Self::ensure_bounty_active(parent_bounty_id)?;
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty =
+						maybe_child_bounty.as_mut().ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					if let ChildBountyStatus::CuratorProposed { ref curator } = child_bounty.status {
+						ensure!(signer == *curator, BountiesError::<T>::RequireCurator);
+
+						let deposit = T::BountyCuratorDeposit::get() * child_bounty.fee;
+						T::Currency::reserve(curator, deposit)?;
+						child_bounty.curator_deposit = deposit;
+
+						child_bounty.status = ChildBountyStatus::Active { curator: curator.clone() };
+						Ok(())
+					} else {
+						Err(BountiesError::<T>::UnexpectedStatus.into())
+					}
+				},
+			)
+		}
+
+		/// Unassign curator from a child bounty.
+		///
+		/// The dispatch origin for this call can be either `RejectOrigin`, or the curator of the
+		/// parent bounty, or any signed origin.
+		///
+		/// For the origin other than `RejectOrigin` and the child bounty curator, the parent bounty
+		/// must be in "active" state, for this child bounty curator to be unassigned.
+		///
+		/// If this function is called by the `RejectOrigin` or the parent bounty curator, we assume
+		/// that the child bounty curator is malicious or inactive. As a result, the child bounty
+		/// curator deposit is slashed.
+		///
+		/// If the origin is the child bounty curator, we take this as a sign that they are unable
+		/// to do their job, and are willingly giving up. We could slash the deposit, but for now
+		/// we allow them to unreserve their deposit and exit without issue. (We may want to change
+		/// this if it is abused.)
+		///
+		/// Finally, the origin can be anyone iff the child bounty curator is "inactive", that is
+		/// if the update due of the parent bounty has passed. In this case the child bounty
+		/// curator is slashed too.
+		///
+		/// - `parent_bounty_id`: Index of parent bounty.
+		/// - `child_bounty_id`: Index of child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::unassign_curator())]
+		pub fn unassign_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResult {
+			let maybe_sender = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| T::RejectOrigin::ensure_origin(origin).map(|_| None))?;
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty =
+						maybe_child_bounty.as_mut().ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					let slash_curator = |curator: &T::AccountId, curator_deposit: &mut BalanceOf<T>| {
+						let imbalance = T::Currency::slash_reserved(curator, *curator_deposit).0;
+						T::OnSlash::on_unbalanced(imbalance);
+						*curator_deposit = Zero::zero();
+					};
+
+					match child_bounty.status {
+						ChildBountyStatus::Added => {
+							// No curator to unassign at this point.
+							return Err(BountiesError::<T>::UnexpectedStatus.into())
+						},
+						ChildBountyStatus::CuratorProposed { ref curator } => {
+							// A child bounty curator has been proposed, but not accepted yet.
+							// Either `RejectOrigin`, the parent bounty curator or the proposed
+							// child bounty curator can unassign the child bounty curator.
+							if let Some(sender) = maybe_sender {
+								if sender != *curator {
+									let (parent_curator, _) =
+										Self::ensure_bounty_active(parent_bounty_id)?;
+									ensure!(sender == parent_curator, BadOrigin);
+								}
+							}
+						},
+						ChildBountyStatus::Active { ref curator } => {
+							match maybe_sender {
+								// If the `RejectOrigin` is calling this function, slash the curator.
+								None => {
+									slash_curator(curator, &mut child_bounty.curator_deposit);
+									// Continue to change child bounty status below.
+								},
+								Some(sender) if sender == *curator => {
+									// This is the child bounty curator, willingly giving up their
+									// role. Give back their deposit.
+									let err_amount =
+										T::Currency::unreserve(curator, child_bounty.curator_deposit);
+									debug_assert!(err_amount.is_zero());
+									child_bounty.curator_deposit = Zero::zero();
+									// Continue to change child bounty status below.
+								},
+								Some(sender) => {
+									let (parent_curator, update_due) =
+										Self::ensure_bounty_active(parent_bounty_id)?;
+									if sender == parent_curator ||
+										update_due < frame_system::Pallet::<T>::block_number()
+									{
+										// Slash the child bounty curator if the parent curator
+										// calls this function, or the curator is inactive.
+										slash_curator(curator, &mut child_bounty.curator_deposit);
+										// Continue to change child bounty status below.
+									} else {
+										// Curator has more time to give an update.
+										return Err(BountiesError::<T>::Premature.into())
+									}
+								},
+							}
+						},
+						ChildBountyStatus::PendingPayout { ref curator, .. } => {
+							// The child bounty is pending payout, so only the `RejectOrigin` or
+							// the parent bounty curator can unassign the child bounty curator.
+							// By doing so, they are claiming the curator is acting maliciously,
+							// so we slash the curator.
+							if let Some(sender) = maybe_sender {
+								let (parent_curator, _) =
+									Self::ensure_bounty_active(parent_bounty_id)?;
+								ensure!(sender == parent_curator, BadOrigin);
+							}
+							slash_curator(curator, &mut child_bounty.curator_deposit);
+							// Continue to change child bounty status below.
+						},
+					};
+
+					// Move the child bounty state to Added.
+					child_bounty.status = ChildBountyStatus::Added;
+					Ok(())
+				},
+			)
+		}
+
+		/// Award child bounty to a beneficiary.
+		///
+		/// The beneficiary will be able to claim the funds after a delay of
+		/// `BountyDepositPayoutDelay`, like for parent bounties.
+		///
+		/// The dispatch origin for this call must be the curator of this child bounty, and the
+		/// parent bounty must be in "active" state.
+		///
+		/// - `parent_bounty_id`: Index of parent bounty.
+		/// - `child_bounty_id`: Index of child bounty.
+		/// - `beneficiary`: Beneficiary account.
+		#[pallet::weight(<T as Config>::WeightInfo::award_child_bounty())]
+		pub fn award_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			let _ = Self::ensure_bounty_active(parent_bounty_id)?;
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty =
+						maybe_child_bounty.as_mut().ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					if let ChildBountyStatus::Active { ref curator } = child_bounty.status {
+						ensure!(signer == *curator, BountiesError::<T>::RequireCurator);
+						child_bounty.status = ChildBountyStatus::PendingPayout {
+							curator: signer,
+							beneficiary: beneficiary.clone(),
+							unlock_at: frame_system::Pallet::<T>::block_number() +
+								T::BountyDepositPayoutDelay::get(),
+						};
+						Ok(())
+					} else {
+						Err(BountiesError::<T>::UnexpectedStatus.into())
+					}
+				},
+			)?;
+
+			Self::deposit_event(Event::<T>::Awarded(parent_bounty_id, child_bounty_id, beneficiary));
+			Ok(())
+		}
+
+		/// Claim the payout from an awarded child bounty after payout delay.
+		///
+		/// The dispatch origin for this call may be any signed origin.
+		///
+		/// The child bounty curator gets their fee and deposit back, and the beneficiary gets the
+		/// rest of the child bounty funds. The child bounty curator fee is then deducted from the
+		/// fee of the parent bounty curator.
+		///
+		/// - `parent_bounty_id`: Index of parent bounty.
+		/// - `child_bounty_id`: Index of child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::claim_child_bounty())]
+		pub fn claim_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResult {
+			let _ = ensure_signed(origin)?; // anyone can trigger claim
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty =
+						maybe_child_bounty.as_ref().ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					if let ChildBountyStatus::PendingPayout { ref curator, ref beneficiary, ref unlock_at } =
+						child_bounty.status
+					{
+						ensure!(
+							frame_system::Pallet::<T>::block_number() >= *unlock_at,
+							BountiesError::<T>::Premature,
+						);
+
+						let child_bounty_account = Self::child_bounty_account_id(child_bounty_id);
+						let balance = T::Currency::free_balance(&child_bounty_account);
+						let fee = child_bounty.fee.min(balance); // just to be safe
+						let payout = balance.saturating_sub(fee);
+						let err_amount = T::Currency::unreserve(curator, child_bounty.curator_deposit);
+						debug_assert!(err_amount.is_zero());
+						let res = T::Currency::transfer(&child_bounty_account, curator, fee, AllowDeath); // should not fail
+						debug_assert!(res.is_ok());
+						let res = T::Currency::transfer(&child_bounty_account, beneficiary, payout, AllowDeath); // should not fail
+						debug_assert!(res.is_ok());
+
+						// The parent bounty curator fee is reduced by the fee paid here.
+						ChildrenCuratorFees::<T>::mutate(parent_bounty_id, |fees| {
+							*fees = fees.saturating_add(fee)
+						});
+						ParentChildBounties::<T>::mutate(parent_bounty_id, |count| {
+							*count = count.saturating_sub(1)
+						});
+						ChildBountyDescriptions::<T>::remove(child_bounty_id);
+
+						Self::deposit_event(Event::<T>::Claimed(
+							parent_bounty_id,
+							child_bounty_id,
+							payout,
+							beneficiary.clone(),
+						));
+
+						*maybe_child_bounty = None;
+						Ok(())
+					} else {
+						Err(BountiesError::<T>::UnexpectedStatus.into())
+					}
+				},
+			)
+		}
+
+		/// Cancel a proposed or active child bounty. The child bounty funds are transferred back
+		/// to the parent bounty account, and the child bounty curator deposit is unreserved if
+		/// possible.
+		///
+		/// The dispatch origin for this call must be either the parent bounty curator, with the
+		/// parent bounty in "active" state, or `RejectOrigin`.
+		///
+		/// A child bounty pending payout cannot be closed: its curator must be unassigned first.
+		///
+		/// - `parent_bounty_id`: Index of parent bounty.
+		/// - `child_bounty_id`: Index of child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::close_child_bounty_added()
+			.max(<T as Config>::WeightInfo::close_child_bounty_active()))]
+		pub fn close_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResultWithPostInfo {
+			let maybe_sender = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| T::RejectOrigin::ensure_origin(origin).map(|_| None))?;
+
+			if let Some(sender) = maybe_sender {
+				let (parent_curator, _) = Self::ensure_bounty_active(parent_bounty_id)?;
+				ensure!(sender == parent_curator, BadOrigin);
+			}
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResultWithPostInfo {
+					let child_bounty =
+						maybe_child_bounty.as_ref().ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					let weight = match &child_bounty.status {
+						ChildBountyStatus::Added |
+						ChildBountyStatus::CuratorProposed { .. } => {
+							// Nothing extra to do besides the removal of the child bounty below.
+							<T as Config>::WeightInfo::close_child_bounty_added()
+						},
+						ChildBountyStatus::Active { curator } => {
+							// Cancelled, refund deposit of the working child bounty curator.
+							let err_amount =
+								T::Currency::unreserve(curator, child_bounty.curator_deposit);
+							debug_assert!(err_amount.is_zero());
+							// Then execute removal of the child bounty below.
+							<T as Config>::WeightInfo::close_child_bounty_active()
+						},
+						ChildBountyStatus::PendingPayout { .. } => {
+							// Child bounty is already pending payout. If the parent curator or
+							// `RejectOrigin` wants to close it, the child curator was acting
+							// maliciously, so they should first unassign the child curator,
+							// slashing their deposit.
+							return Err(BountiesError::<T>::PendingPayout.into())
+						},
+					};
+
+					// Transfer the funds back to the parent bounty.
+					let child_bounty_account = Self::child_bounty_account_id(child_bounty_id);
+					let parent_bounty_account =
+						pallet_bounties::Module::<T>::bounty_account_id(parent_bounty_id);
+					let balance = T::Currency::free_balance(&child_bounty_account);
+					let res = T::Currency::transfer(
+						&child_bounty_account,
+						&parent_bounty_account,
+						balance,
+						AllowDeath,
+					); // should not fail
+					debug_assert!(res.is_ok());
+
+					ParentChildBounties::<T>::mutate(parent_bounty_id, |count| {
+						*count = count.saturating_sub(1)
+					});
+					ChildBountyDescriptions::<T>::remove(child_bounty_id);
+					*maybe_child_bounty = None;
+
+					Self::deposit_event(Event::<T>::Canceled(parent_bounty_id, child_bounty_id));
+					Ok(Some(weight).into())
+				},
+			)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account ID of a child bounty account.
+	pub fn child_bounty_account_id(id: BountyIndex) -> T::AccountId {
+		// This uses the same scheme as the bounty accounts of pallet-bounties, with a different
+		// prefix so that a child bounty and a bounty with the same index get different accounts.
+		T::PalletId::get().into_sub_account(("cb", id))
+	}
+
+	/// Returns the curator and update due of the parent bounty, if it is active.
+	fn ensure_bounty_active(
+		bounty_id: BountyIndex,
+	) -> Result<(T::AccountId, T::BlockNumber), DispatchError> {
+		let parent_bounty = pallet_bounties::Module::<T>::bounties(bounty_id)
+			.ok_or(BountiesError::<T>::InvalidIndex)?;
+		if let BountyStatus::Active { curator, update_due } = parent_bounty.get_status() {
+			Ok((curator, update_due))
+		} else {
+			Err(Error::<T>::ParentBountyNotActive.into())
+		}
+	}
+}
+
+impl<T: Config> pallet_bounties::ChildBountyManager<BalanceOf<T>> for Pallet<T> {
+	fn child_bounties_count(bounty_id: BountyIndex) -> BountyIndex {
+		Self::parent_child_bounties(bounty_id)
+	}
+
+	fn children_curator_fees(bounty_id: BountyIndex) -> BalanceOf<T> {
+		ChildrenCuratorFees::<T>::take(bounty_id)
+	}
+
+	fn bounty_removed(bounty_id: BountyIndex) {
+		ChildrenCuratorFees::<T>::remove(bounty_id);
+	}
+}
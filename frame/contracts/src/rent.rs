@@ -32,7 +32,7 @@ use frame_support::{
 };
 use pallet_contracts_primitives::{ContractAccessError, RentProjection, RentProjectionResult};
 use sp_runtime::{
-	DispatchError,
+	DispatchError, StateVersion,
 	traits::{Bounded, CheckedDiv, CheckedMul, SaturatedConversion, Saturating, Zero},
 };
 
@@ -279,7 +279,9 @@ where
 		let tombstone = <TombstoneContractInfo<T>>::new(
 			// This operation is cheap enough because last_write (delta not included)
 			// is not this block as it has been checked earlier.
-			&child::root(&child_trie_info)[..],
+			// Tombstones are always computed with the V0 layout so that they stay comparable
+			// with tombstones created before the runtime switched state versions.
+			&child::root(&child_trie_info, StateVersion::V0)[..],
 			code_hash,
 		);
 
@@ -513,6 +515,7 @@ where
 				// Note: this operation is heavy.
 				let child_storage_root = child::root(
 					&alive_contract_info.child_trie_info(),
+					StateVersion::V0,
 				);
 
 				let tombstone = <TombstoneContractInfo<T>>::new(
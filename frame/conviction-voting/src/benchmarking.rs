@@ -0,0 +1,186 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Conviction voting pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account, whitelist_account, impl_benchmark_test_suite};
+use frame_support::traits::{Currency, Get};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+use crate::Pallet as ConvictionVoting;
+
+const SEED: u32 = 0;
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let caller: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	caller
+}
+
+fn account_vote<T: Config>(b: BalanceOf<T>) -> AccountVote<BalanceOf<T>> {
+	let v = Vote { aye: true, conviction: Conviction::Locked1x };
+	AccountVote::Standard { vote: v, balance: b }
+}
+
+// Create `n` ongoing polls of the first class, returning the class and the poll indices.
+fn create_polls<T: Config>(n: u32) -> Result<(ClassOf<T>, Vec<PollIndexOf<T>>), &'static str> {
+	let class = T::Polls::classes().into_iter().next().ok_or("No poll class")?;
+	let polls = (0 .. n)
+		.map(|_| T::Polls::create_ongoing(class.clone()).map_err(|_| "Cannot create poll"))
+		.collect::<Result<Vec<_>, _>>()?;
+	Ok((class, polls))
+}
+
+// Vote on all the given polls with `who`.
+fn vote_on<T: Config>(who: &T::AccountId, polls: &[PollIndexOf<T>]) -> Result<(), &'static str> {
+	let account_vote = account_vote::<T>(100u32.into());
+	for i in polls {
+		ConvictionVoting::<T>::vote(RawOrigin::Signed(who.clone()).into(), *i, account_vote)?;
+	}
+	Ok(())
+}
+
+fn votes_of<T: Config>(who: &T::AccountId, class: &ClassOf<T>) -> Result<usize, &'static str> {
+	match VotingFor::<T>::get(who, class) {
+		Voting::Casting(Casting { votes, .. }) => Ok(votes.len()),
+		_ => Err("Votes are not direct"),
+	}
+}
+
+benchmarks! {
+	vote_new {
+		let r in 0 .. T::MaxVotes::get() - 1;
+
+		let caller = funded_account::<T>("caller", 0);
+		let (class, polls) = create_polls::<T>(r + 1)?;
+		vote_on::<T>(&caller, &polls[.. r as usize])?;
+		let index = polls[r as usize];
+		whitelist_account!(caller);
+	}: vote(RawOrigin::Signed(caller.clone()), index, account_vote::<T>(100u32.into()))
+	verify {
+		assert_eq!(votes_of::<T>(&caller, &class)?, (r + 1) as usize, "Vote was not recorded.");
+	}
+
+	vote_existing {
+		let r in 1 .. T::MaxVotes::get();
+
+		let caller = funded_account::<T>("caller", 0);
+		let (class, polls) = create_polls::<T>(r)?;
+		vote_on::<T>(&caller, &polls)?;
+		let index = polls[(r - 1) as usize];
+		let nay = AccountVote::Standard {
+			vote: Vote { aye: false, conviction: Conviction::Locked1x },
+			balance: 100u32.into(),
+		};
+		whitelist_account!(caller);
+	}: vote(RawOrigin::Signed(caller.clone()), index, nay)
+	verify {
+		assert_eq!(votes_of::<T>(&caller, &class)?, r as usize, "Vote was not changed.");
+	}
+
+	remove_vote {
+		let r in 1 .. T::MaxVotes::get();
+
+		let caller = funded_account::<T>("caller", 0);
+		let (class, polls) = create_polls::<T>(r)?;
+		vote_on::<T>(&caller, &polls)?;
+		let index = polls[(r - 1) as usize];
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller.clone()), Some(class.clone()), index)
+	verify {
+		assert_eq!(votes_of::<T>(&caller, &class)?, (r - 1) as usize, "Vote was not removed.");
+	}
+
+	remove_other_vote {
+		let r in 1 .. T::MaxVotes::get();
+
+		let caller = funded_account::<T>("caller", 0);
+		let voter = funded_account::<T>("caller", 1);
+		let (class, polls) = create_polls::<T>(r)?;
+		vote_on::<T>(&voter, &polls)?;
+		let index = polls[(r - 1) as usize];
+		// The aye vote lost, so it can be removed by anyone.
+		T::Polls::end_ongoing(index, false).map_err(|_| "Cannot end poll")?;
+		let voter_lookup = T::Lookup::unlookup(voter.clone());
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller), voter_lookup, class.clone(), index)
+	verify {
+		assert_eq!(votes_of::<T>(&voter, &class)?, (r - 1) as usize, "Vote was not removed.");
+	}
+
+	delegate {
+		let r in 0 .. T::MaxVotes::get();
+
+		let caller = funded_account::<T>("caller", 0);
+		let voter = funded_account::<T>("caller", 1);
+		let (class, polls) = create_polls::<T>(r)?;
+		vote_on::<T>(&voter, &polls)?;
+		let voter_lookup = T::Lookup::unlookup(voter.clone());
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller.clone()), class.clone(), voter_lookup, Conviction::Locked1x, 100u32.into())
+	verify {
+		assert!(matches!(VotingFor::<T>::get(&caller, &class), Voting::Delegating(_)));
+	}
+
+	undelegate {
+		let r in 0 .. T::MaxVotes::get();
+
+		let caller = funded_account::<T>("caller", 0);
+		let voter = funded_account::<T>("caller", 1);
+		let (class, polls) = create_polls::<T>(r)?;
+		vote_on::<T>(&voter, &polls)?;
+		ConvictionVoting::<T>::delegate(
+			RawOrigin::Signed(caller.clone()).into(),
+			class.clone(),
+			T::Lookup::unlookup(voter),
+			Conviction::Locked1x,
+			100u32.into(),
+		)?;
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller.clone()), class.clone())
+	verify {
+		assert!(matches!(VotingFor::<T>::get(&caller, &class), Voting::Casting(_)));
+	}
+
+	unlock {
+		let caller = funded_account::<T>("caller", 0);
+		let (class, polls) = create_polls::<T>(1)?;
+		vote_on::<T>(&caller, &polls)?;
+		ConvictionVoting::<T>::remove_vote(
+			RawOrigin::Signed(caller.clone()).into(),
+			Some(class.clone()),
+			polls[0],
+		)?;
+		let caller_lookup = T::Lookup::unlookup(caller.clone());
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller.clone()), class, caller_lookup)
+	verify {
+		assert!(ClassLocksFor::<T>::get(&caller).is_empty());
+	}
+}
+
+impl_benchmark_test_suite!(
+	ConvictionVoting,
+	crate::tests::new_test_ext(),
+	crate::tests::Test,
+);
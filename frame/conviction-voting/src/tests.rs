@@ -0,0 +1,498 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! The crate's tests.
+
+use std::{cell::RefCell, collections::BTreeMap, convert::TryInto};
+
+use super::*;
+use crate as pallet_conviction_voting;
+use frame_support::{assert_noop, assert_ok, parameter_types, traits::VoteTally};
+use sp_core::H256;
+use sp_runtime::{
+	Perbill,
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Voting: pallet_conviction_voting::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::AllowAll;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxLocks: u32 = 10;
+}
+impl pallet_balances::Config for Test {
+	type MaxLocks = MaxLocks;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TestPollState {
+	Ongoing(TallyOf<Test>, u8),
+	Completed(u64, bool),
+}
+use TestPollState::*;
+
+thread_local! {
+	static POLLS: RefCell<BTreeMap<u8, TestPollState>> = RefCell::new(vec![
+		(1, Ongoing(Tally::default(), 0)),
+		(2, Ongoing(Tally::default(), 0)),
+		(3, Ongoing(Tally::default(), 1)),
+		(4, Completed(1, true)),
+		(5, Completed(1, false)),
+	].into_iter().collect());
+}
+
+/// A set of polls which can be altered directly by the tests.
+pub struct TestPolls;
+impl TestPolls {
+	fn get() -> BTreeMap<u8, TestPollState> {
+		POLLS.with(|p| p.borrow().clone())
+	}
+	fn set(polls: BTreeMap<u8, TestPollState>) {
+		POLLS.with(|p| *p.borrow_mut() = polls)
+	}
+}
+
+impl Polling<TallyOf<Test>> for TestPolls {
+	type Index = u8;
+	type Votes = u64;
+	type Moment = u64;
+	type Class = u8;
+
+	fn classes() -> Vec<u8> {
+		vec![0, 1, 2]
+	}
+
+	fn access_poll<R>(
+		index: u8,
+		f: impl FnOnce(PollStatus<&mut TallyOf<Test>, u64, u8>) -> R,
+	) -> R {
+		let mut polls = Self::get();
+		let r = match polls.get_mut(&index) {
+			Some(Ongoing(tally, class)) => f(PollStatus::Ongoing(tally, *class)),
+			Some(Completed(when, approved)) => f(PollStatus::Completed(*when, *approved)),
+			None => f(PollStatus::None),
+		};
+		Self::set(polls);
+		r
+	}
+
+	fn try_access_poll<R>(
+		index: u8,
+		f: impl FnOnce(PollStatus<&mut TallyOf<Test>, u64, u8>) -> Result<R, DispatchError>,
+	) -> Result<R, DispatchError> {
+		let mut polls = Self::get();
+		let r = match polls.get_mut(&index) {
+			Some(Ongoing(tally, class)) => f(PollStatus::Ongoing(tally, *class)),
+			Some(Completed(when, approved)) => f(PollStatus::Completed(*when, *approved)),
+			None => f(PollStatus::None),
+		}?;
+		Self::set(polls);
+		Ok(r)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_ongoing(class: u8) -> Result<u8, ()> {
+		let mut polls = Self::get();
+		let index = polls.keys().rev().next().map_or(0, |x| x + 1);
+		polls.insert(index, Ongoing(Tally::default(), class));
+		Self::set(polls);
+		Ok(index)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn end_ongoing(index: u8, approved: bool) -> Result<(), ()> {
+		let mut polls = Self::get();
+		match polls.get(&index) {
+			Some(Ongoing(..)) => {},
+			_ => return Err(()),
+		}
+		let now = frame_system::Pallet::<Test>::block_number();
+		polls.insert(index, Completed(now, approved));
+		Self::set(polls);
+		Ok(())
+	}
+}
+
+/// The total issuance of the test currency.
+pub struct TotalIssuance;
+impl Get<u64> for TotalIssuance {
+	fn get() -> u64 {
+		Balances::total_issuance()
+	}
+}
+
+parameter_types! {
+	pub const MaxVotes: u32 = 3;
+	pub const VoteLockingPeriod: u64 = 3;
+}
+impl Config for Test {
+	type Event = Event;
+	type Currency = pallet_balances::Pallet<Self>;
+	type Polls = TestPolls;
+	type MaxTurnout = TotalIssuance;
+	type MaxVotes = MaxVotes;
+	type VoteLockingPeriod = VoteLockingPeriod;
+	type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 10), (2, 20), (3, 30), (4, 40), (5, 50), (6, 60)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn aye(amount: u64, conviction: u8) -> AccountVote<u64> {
+	let vote = Vote { aye: true, conviction: conviction.try_into().unwrap() };
+	AccountVote::Standard { vote, balance: amount }
+}
+
+fn nay(amount: u64, conviction: u8) -> AccountVote<u64> {
+	let vote = Vote { aye: false, conviction: conviction.try_into().unwrap() };
+	AccountVote::Standard { vote, balance: amount }
+}
+
+fn split(aye: u64, nay: u64) -> AccountVote<u64> {
+	AccountVote::Split { aye, nay }
+}
+
+fn tally(index: u8) -> TallyOf<Test> {
+	<TestPolls as Polling<TallyOf<Test>>>::access_poll(index, |s| s.ensure_ongoing().unwrap().0.clone())
+}
+
+fn complete(index: u8, approved: bool) {
+	let mut polls = TestPolls::get();
+	polls.insert(index, Completed(System::block_number(), approved));
+	TestPolls::set(polls);
+}
+
+fn class_locks_for(who: u64) -> Vec<(u8, u64)> {
+	ClassLocksFor::<Test>::get(who)
+}
+
+#[test]
+fn params_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Balances::free_balance(42), 0);
+		assert_eq!(Balances::total_issuance(), 210);
+	});
+}
+
+#[test]
+fn basic_voting_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Voting::vote(Origin::signed(1), 3, aye(2, 5)));
+		assert_eq!(tally(3), Tally::from_parts(10, 0, 2));
+		assert_ok!(Voting::vote(Origin::signed(1), 3, nay(2, 5)));
+		assert_eq!(tally(3), Tally::from_parts(0, 10, 0));
+		assert_eq!(Balances::usable_balance(1), 8);
+
+		assert_ok!(Voting::vote(Origin::signed(1), 3, aye(5, 1)));
+		assert_eq!(tally(3), Tally::from_parts(5, 0, 5));
+		assert_ok!(Voting::vote(Origin::signed(1), 3, nay(5, 1)));
+		assert_eq!(tally(3), Tally::from_parts(0, 5, 0));
+		assert_eq!(Balances::usable_balance(1), 5);
+
+		assert_ok!(Voting::vote(Origin::signed(1), 3, aye(10, 0)));
+		assert_eq!(tally(3), Tally::from_parts(1, 0, 10));
+		assert_ok!(Voting::vote(Origin::signed(1), 3, nay(10, 0)));
+		assert_eq!(tally(3), Tally::from_parts(0, 1, 0));
+		assert_eq!(Balances::usable_balance(1), 0);
+
+		assert_ok!(Voting::remove_vote(Origin::signed(1), None, 3));
+		assert_eq!(tally(3), Tally::from_parts(0, 0, 0));
+
+		assert_ok!(Voting::unlock(Origin::signed(1), 1, 1));
+		assert_eq!(Balances::usable_balance(1), 10);
+	});
+}
+
+#[test]
+fn split_voting_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Voting::vote(Origin::signed(1), 3, split(10, 0)));
+		assert_eq!(tally(3), Tally::from_parts(1, 0, 10));
+		assert_ok!(Voting::vote(Origin::signed(1), 3, split(5, 5)));
+		assert_eq!(tally(3), Tally::from_parts(0, 0, 5));
+		assert_eq!(Balances::usable_balance(1), 0);
+
+		assert_ok!(Voting::remove_vote(Origin::signed(1), None, 3));
+		assert_eq!(tally(3), Tally::from_parts(0, 0, 0));
+
+		assert_ok!(Voting::unlock(Origin::signed(1), 1, 1));
+		assert_eq!(Balances::usable_balance(1), 10);
+	});
+}
+
+#[test]
+fn tally_reports_support_and_approval() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Voting::vote(Origin::signed(6), 1, aye(42, 1)));
+		assert_ok!(Voting::vote(Origin::signed(5), 1, nay(14, 1)));
+		let t = tally(1);
+		assert_eq!(t.ayes(), 42);
+		assert_eq!(t.support(), Perbill::from_percent(20));
+		assert_eq!(t.approval(), Perbill::from_percent(75));
+		assert_eq!(Tally::<u64, TotalIssuance>::default().approval(), Perbill::zero());
+	});
+}
+
+#[test]
+fn classes_are_independent() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Voting::delegate(Origin::signed(2), 0, 1, Conviction::Locked1x, 20));
+		assert_noop!(
+			Voting::vote(Origin::signed(2), 1, aye(10, 0)),
+			Error::<Test>::AlreadyDelegating,
+		);
+		assert_ok!(Voting::vote(Origin::signed(2), 3, aye(10, 0)));
+		assert_eq!(tally(3), Tally::from_parts(1, 0, 10));
+		assert_eq!(class_locks_for(2), vec![(0, 20), (1, 10)]);
+		assert_eq!(Balances::usable_balance(2), 0);
+
+		assert_ok!(Voting::remove_vote(Origin::signed(2), None, 3));
+		assert_ok!(Voting::unlock(Origin::signed(2), 1, 2));
+		assert_eq!(class_locks_for(2), vec![(0, 20)]);
+		assert_eq!(Balances::usable_balance(2), 0);
+	});
+}
+
+#[test]
+fn delegation_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Voting::vote(Origin::signed(1), 1, aye(10, 1)));
+		assert_eq!(tally(1), Tally::from_parts(10, 0, 10));
+
+		assert_ok!(Voting::delegate(Origin::signed(2), 0, 1, Conviction::Locked2x, 20));
+		assert_eq!(tally(1), Tally::from_parts(50, 0, 30));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::Voting(crate::Event::Delegated(2, 1)),
+		);
+
+		// Votes cast after the delegation also count the delegated votes.
+		assert_ok!(Voting::vote(Origin::signed(1), 2, nay(10, 1)));
+		assert_eq!(tally(2), Tally::from_parts(0, 50, 0));
+
+		// Delegations are per class.
+		assert_ok!(Voting::vote(Origin::signed(1), 3, aye(10, 1)));
+		assert_eq!(tally(3), Tally::from_parts(10, 0, 10));
+
+		assert_ok!(Voting::undelegate(Origin::signed(2), 0));
+		assert_eq!(tally(1), Tally::from_parts(10, 0, 10));
+		assert_eq!(tally(2), Tally::from_parts(0, 10, 0));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::Voting(crate::Event::Undelegated(2)),
+		);
+	});
+}
+
+#[test]
+fn redelegation_needs_undelegation() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Voting::delegate(Origin::signed(2), 0, 1, Conviction::Locked1x, 10));
+		assert_noop!(
+			Voting::delegate(Origin::signed(2), 0, 3, Conviction::Locked1x, 10),
+			Error::<Test>::AlreadyDelegating,
+		);
+		assert_ok!(Voting::undelegate(Origin::signed(2), 0));
+		assert_ok!(Voting::delegate(Origin::signed(2), 0, 3, Conviction::Locked1x, 10));
+		assert_noop!(Voting::undelegate(Origin::signed(3), 0), Error::<Test>::NotDelegating);
+	});
+}
+
+#[test]
+fn undelegation_keeps_lock_for_conviction_period() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Voting::delegate(Origin::signed(2), 0, 1, Conviction::Locked2x, 20));
+		assert_ok!(Voting::undelegate(Origin::signed(2), 0));
+
+		// Two lock periods from block 1.
+		System::set_block_number(6);
+		assert_ok!(Voting::unlock(Origin::signed(2), 0, 2));
+		assert_eq!(Balances::usable_balance(2), 0);
+
+		System::set_block_number(7);
+		assert_ok!(Voting::unlock(Origin::signed(2), 0, 2));
+		assert_eq!(Balances::usable_balance(2), 20);
+		assert!(class_locks_for(2).is_empty());
+	});
+}
+
+#[test]
+fn winning_vote_keeps_lock_for_conviction_period() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Voting::vote(Origin::signed(1), 1, aye(10, 1)));
+		assert_ok!(Voting::vote(Origin::signed(2), 1, nay(20, 1)));
+		complete(1, true);
+
+		// The losing vote can be removed straight away, even by someone else.
+		assert_ok!(Voting::remove_other_vote(Origin::signed(3), 2, 0, 1));
+		assert_ok!(Voting::unlock(Origin::signed(3), 0, 2));
+		assert_eq!(Balances::usable_balance(2), 20);
+
+		// The winning vote only by its owner, keeping the lock.
+		assert_noop!(
+			Voting::remove_other_vote(Origin::signed(3), 1, 0, 1),
+			Error::<Test>::NoPermissionYet,
+		);
+		assert_noop!(Voting::remove_vote(Origin::signed(1), None, 1), Error::<Test>::ClassNeeded);
+		assert_ok!(Voting::remove_vote(Origin::signed(1), Some(0), 1));
+		assert_ok!(Voting::unlock(Origin::signed(1), 0, 1));
+		assert_eq!(Balances::usable_balance(1), 0);
+
+		System::set_block_number(4);
+		assert_ok!(Voting::unlock(Origin::signed(1), 0, 1));
+		assert_eq!(Balances::usable_balance(1), 10);
+	});
+}
+
+#[test]
+fn expired_vote_can_be_removed_by_anyone() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Voting::vote(Origin::signed(1), 1, aye(10, 1)));
+		assert_noop!(
+			Voting::remove_other_vote(Origin::signed(2), 1, 0, 1),
+			Error::<Test>::NoPermission,
+		);
+		complete(1, true);
+		System::set_block_number(4);
+		assert_ok!(Voting::remove_other_vote(Origin::signed(2), 1, 0, 1));
+		assert_noop!(
+			Voting::remove_other_vote(Origin::signed(2), 1, 0, 1),
+			Error::<Test>::NotVoter,
+		);
+	});
+}
+
+#[test]
+fn errors_with_vote_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Voting::vote(Origin::signed(1), 0, aye(10, 0)), Error::<Test>::NotOngoing);
+		assert_noop!(Voting::vote(Origin::signed(1), 4, aye(10, 0)), Error::<Test>::NotOngoing);
+		assert_noop!(
+			Voting::vote(Origin::signed(1), 3, aye(11, 0)),
+			Error::<Test>::InsufficientFunds,
+		);
+
+		assert_ok!(Voting::delegate(Origin::signed(1), 0, 2, Conviction::None, 10));
+		assert_noop!(
+			Voting::vote(Origin::signed(1), 1, aye(10, 0)),
+			Error::<Test>::AlreadyDelegating,
+		);
+	});
+}
+
+#[test]
+fn errors_with_delegating_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Voting::delegate(Origin::signed(1), 0, 1, Conviction::None, 10),
+			Error::<Test>::Nonsense,
+		);
+		assert_noop!(
+			Voting::delegate(Origin::signed(1), 3, 2, Conviction::None, 10),
+			Error::<Test>::BadClass,
+		);
+		assert_noop!(
+			Voting::delegate(Origin::signed(1), 0, 2, Conviction::None, 11),
+			Error::<Test>::InsufficientFunds,
+		);
+
+		assert_ok!(Voting::vote(Origin::signed(1), 1, aye(10, 0)));
+		assert_noop!(
+			Voting::delegate(Origin::signed(1), 0, 2, Conviction::None, 10),
+			Error::<Test>::AlreadyVoting,
+		);
+	});
+}
+
+#[test]
+fn max_votes_are_enforced() {
+	new_test_ext().execute_with(|| {
+		let mut polls = TestPolls::get();
+		polls.insert(6, Ongoing(Tally::default(), 0));
+		polls.insert(7, Ongoing(Tally::default(), 0));
+		TestPolls::set(polls);
+
+		assert_ok!(Voting::vote(Origin::signed(1), 1, aye(10, 0)));
+		assert_ok!(Voting::vote(Origin::signed(1), 2, aye(10, 0)));
+		assert_ok!(Voting::vote(Origin::signed(1), 6, aye(10, 0)));
+		assert_noop!(
+			Voting::vote(Origin::signed(1), 7, aye(10, 0)),
+			Error::<Test>::MaxVotesReached,
+		);
+		// Changing an existing vote is still possible, as is voting in another class.
+		assert_ok!(Voting::vote(Origin::signed(1), 6, nay(10, 0)));
+		assert_ok!(Voting::vote(Origin::signed(1), 3, aye(10, 0)));
+	});
+}
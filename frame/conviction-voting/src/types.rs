@@ -0,0 +1,248 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Miscellaneous additional datatypes.
+
+use sp_std::{prelude::*, fmt::Debug, marker::PhantomData};
+use codec::{Codec, Encode, Decode};
+use frame_support::{
+	traits::{Get, VoteTally},
+	CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
+use sp_runtime::{
+	Perbill, RuntimeDebug,
+	traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedSub, One, Saturating, Zero},
+};
+use pallet_democracy::{AccountVote, Conviction, Delegations, PriorLock};
+
+/// Info regarding an ongoing poll.
+///
+/// `Total` provides the total amount of voting capital against which the support is measured.
+#[derive(CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, Encode, Decode)]
+pub struct Tally<Votes: Clone + PartialEq + Eq + Debug + Codec, Total> {
+	/// The number of aye votes, expressed in terms of post-conviction lock-vote.
+	pub ayes: Votes,
+	/// The number of nay votes, expressed in terms of post-conviction lock-vote.
+	pub nays: Votes,
+	/// The amount of funds currently supporting the poll. Pre-conviction.
+	pub support: Votes,
+	dummy: PhantomData<Total>,
+}
+
+impl<Votes: Clone + PartialEq + Eq + Debug + Codec + Zero, Total> Default for Tally<Votes, Total> {
+	fn default() -> Self {
+		Self { ayes: Zero::zero(), nays: Zero::zero(), support: Zero::zero(), dummy: PhantomData }
+	}
+}
+
+impl<
+	Votes: Clone + Default + PartialEq + Eq + Debug + Codec + Copy + AtLeast32BitUnsigned,
+	Total: Get<Votes>,
+> VoteTally<Votes> for Tally<Votes, Total> {
+	fn ayes(&self) -> Votes {
+		self.ayes
+	}
+
+	fn support(&self) -> Perbill {
+		Perbill::from_rational(self.support, Total::get().max(One::one()))
+	}
+
+	fn approval(&self) -> Perbill {
+		let total = self.ayes.saturating_add(self.nays);
+		if total.is_zero() {
+			return Perbill::zero()
+		}
+		Perbill::from_rational(self.ayes, total)
+	}
+}
+
+impl<
+	Votes: Clone + Default + PartialEq + Eq + Debug + Codec + Copy + AtLeast32BitUnsigned,
+	Total,
+> Tally<Votes, Total> {
+	/// Create a new tally with the given vote.
+	pub fn new(vote: AccountVote<Votes>) -> Self {
+		let mut tally = Self::default();
+		let _ = tally.add(vote);
+		tally
+	}
+
+	/// Create a tally from its parts.
+	pub fn from_parts(ayes: Votes, nays: Votes, support: Votes) -> Self {
+		Self { ayes, nays, support, dummy: PhantomData }
+	}
+
+	/// Add an account's vote into the tally.
+	pub fn add(&mut self, vote: AccountVote<Votes>) -> Option<()> {
+		match vote {
+			AccountVote::Standard { vote, balance } => {
+				let Delegations { votes, capital } = vote.conviction.votes(balance);
+				match vote.aye {
+					true => {
+						self.support = self.support.checked_add(&capital)?;
+						self.ayes = self.ayes.checked_add(&votes)?;
+					},
+					false => self.nays = self.nays.checked_add(&votes)?,
+				}
+			},
+			AccountVote::Split { aye, nay } => {
+				let aye = Conviction::None.votes(aye);
+				let nay = Conviction::None.votes(nay);
+				self.support = self.support.checked_add(&aye.capital)?;
+				self.ayes = self.ayes.checked_add(&aye.votes)?;
+				self.nays = self.nays.checked_add(&nay.votes)?;
+			},
+		}
+		Some(())
+	}
+
+	/// Remove an account's vote from the tally.
+	pub fn remove(&mut self, vote: AccountVote<Votes>) -> Option<()> {
+		match vote {
+			AccountVote::Standard { vote, balance } => {
+				let Delegations { votes, capital } = vote.conviction.votes(balance);
+				match vote.aye {
+					true => {
+						self.support = self.support.checked_sub(&capital)?;
+						self.ayes = self.ayes.checked_sub(&votes)?;
+					},
+					false => self.nays = self.nays.checked_sub(&votes)?,
+				}
+			},
+			AccountVote::Split { aye, nay } => {
+				let aye = Conviction::None.votes(aye);
+				let nay = Conviction::None.votes(nay);
+				self.support = self.support.checked_sub(&aye.capital)?;
+				self.ayes = self.ayes.checked_sub(&aye.votes)?;
+				self.nays = self.nays.checked_sub(&nay.votes)?;
+			},
+		}
+		Some(())
+	}
+
+	/// Increment some amount of votes.
+	pub fn increase(&mut self, approve: bool, delegations: Delegations<Votes>) {
+		match approve {
+			true => {
+				self.support = self.support.saturating_add(delegations.capital);
+				self.ayes = self.ayes.saturating_add(delegations.votes);
+			},
+			false => self.nays = self.nays.saturating_add(delegations.votes),
+		}
+	}
+
+	/// Decrement some amount of votes.
+	pub fn reduce(&mut self, approve: bool, delegations: Delegations<Votes>) {
+		match approve {
+			true => {
+				self.support = self.support.saturating_sub(delegations.capital);
+				self.ayes = self.ayes.saturating_sub(delegations.votes);
+			},
+			false => self.nays = self.nays.saturating_sub(delegations.votes),
+		}
+	}
+}
+
+/// The votes of an account which is voting directly on one class of polls.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct Casting<Balance, BlockNumber, PollIndex> {
+	/// The current votes of the account, ordered by poll index.
+	pub votes: Vec<(PollIndex, AccountVote<Balance>)>,
+	/// The total amount of delegations that this account has received.
+	pub delegations: Delegations<Balance>,
+	/// Any pre-existing locks from past voting/delegating activity.
+	pub prior: PriorLock<BlockNumber, Balance>,
+}
+
+/// The delegation of an account's votes on one class of polls.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct Delegating<Balance, AccountId, BlockNumber> {
+	/// The amount of balance delegated.
+	pub balance: Balance,
+	/// The account to which the votes are delegated.
+	pub target: AccountId,
+	/// The conviction with which the votes are delegated.
+	pub conviction: Conviction,
+	/// The total amount of delegations that this account has received.
+	pub delegations: Delegations<Balance>,
+	/// Any pre-existing locks from past voting/delegating activity.
+	pub prior: PriorLock<BlockNumber, Balance>,
+}
+
+/// An indicator for what an account is doing on one class of polls; it can either be delegating
+/// or voting.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum Voting<Balance, AccountId, BlockNumber, PollIndex> {
+	/// The account is voting directly.
+	Casting(Casting<Balance, BlockNumber, PollIndex>),
+	/// The account is delegating `balance` of its balance to a `target` account with `conviction`.
+	Delegating(Delegating<Balance, AccountId, BlockNumber>),
+}
+
+impl<Balance: Default, AccountId, BlockNumber: Default, PollIndex> Default
+	for Voting<Balance, AccountId, BlockNumber, PollIndex>
+{
+	fn default() -> Self {
+		Voting::Casting(Casting {
+			votes: Vec::new(),
+			delegations: Default::default(),
+			prior: Default::default(),
+		})
+	}
+}
+
+impl<
+	Balance: Saturating + Ord + Zero + Copy,
+	BlockNumber: Ord + Copy + Zero,
+	AccountId,
+	PollIndex,
+> Voting<Balance, AccountId, BlockNumber, PollIndex> {
+	/// Forget any prior lock which has expired by `now`.
+	pub fn rejig(&mut self, now: BlockNumber) {
+		match self {
+			Voting::Casting(Casting { prior, .. }) => prior,
+			Voting::Delegating(Delegating { prior, .. }) => prior,
+		}.rejig(now);
+	}
+
+	/// The amount of this account's balance that must currently be locked due to voting.
+	pub fn locked_balance(&self) -> Balance {
+		match self {
+			Voting::Casting(Casting { votes, prior, .. }) => votes.iter()
+				.map(|i| i.1.balance())
+				.fold(prior.locked(), |a, i| a.max(i)),
+			Voting::Delegating(Delegating { balance, prior, .. }) => (*balance).max(prior.locked()),
+		}
+	}
+
+	/// Set the delegations received and the prior lock of the account.
+	pub fn set_common(
+		&mut self,
+		delegations: Delegations<Balance>,
+		prior: PriorLock<BlockNumber, Balance>,
+	) {
+		let (d, p) = match self {
+			Voting::Casting(Casting { ref mut delegations, ref mut prior, .. }) =>
+				(delegations, prior),
+			Voting::Delegating(Delegating { ref mut delegations, ref mut prior, .. }) =>
+				(delegations, prior),
+		};
+		*d = delegations;
+		*p = prior;
+	}
+}
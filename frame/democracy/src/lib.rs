@@ -173,7 +173,7 @@ mod types;
 pub mod weights;
 pub use weights::WeightInfo;
 pub use vote_threshold::{Approved, VoteThreshold};
-pub use vote::{Vote, AccountVote, Voting};
+pub use vote::{Vote, AccountVote, Voting, PriorLock};
 pub use conviction::Conviction;
 pub use types::{ReferendumInfo, ReferendumStatus, Tally, UnvoteScope, Delegations};
 pub use pallet::*;
@@ -469,7 +469,6 @@ pub mod pallet {
 		_phantom: sp_std::marker::PhantomData<T>,
 	}
 
-	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
 			GenesisConfig {
@@ -99,6 +99,7 @@ impl<BlockNumber: Ord + Copy + Zero, Balance: Ord + Copy + Zero> PriorLock<Block
 		self.1 = self.1.max(amount);
 	}
 
+	/// The amount of balance which remains locked.
 	pub fn locked(&self) -> Balance {
 		self.1
 	}
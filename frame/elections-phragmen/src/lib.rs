@@ -650,7 +650,6 @@ pub mod pallet {
 		pub members: Vec<(T::AccountId, BalanceOf<T>)>,
 	}
 
-	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
 			Self { members: Default::default() }
@@ -676,7 +675,10 @@ pub mod pallet {
 				// Nonetheless, stakes will be updated for term 1 onwards according to the election.
 				Members::<T>::mutate(|members| {
 					match members.binary_search_by(|m| m.who.cmp(member)) {
-						Ok(_) => panic!("Duplicate member in elections-phragmen genesis: {}", member),
+						Ok(_) => panic!(
+							"Duplicate member in elections-phragmen genesis: {:?}",
+							member,
+						),
 						Err(pos) => members.insert(
 							pos,
 							SeatHolder { who: member.clone(), stake: *stake, deposit: Zero::zero() },
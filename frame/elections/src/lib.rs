@@ -373,7 +373,6 @@ pub mod pallet {
 		pub members: Vec<(T::AccountId, T::BlockNumber)>,
 	}
 
-	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
 			GenesisConfig {
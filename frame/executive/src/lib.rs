@@ -621,7 +621,7 @@ mod tests {
 
 				#[weight = 0]
 				fn calculate_storage_root(_origin) {
-					let root = sp_io::storage::root();
+					let root = sp_io::storage::root(sp_runtime::StateVersion::V1);
 					sp_io::storage::set("storage_root".as_bytes(), &root);
 				}
 			}
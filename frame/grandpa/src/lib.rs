@@ -325,7 +325,6 @@ pub mod pallet {
 		pub authorities: AuthorityList,
 	}
 
-	#[cfg(feature = "std")]
 	impl Default for GenesisConfig {
 		fn default() -> Self {
 			Self {
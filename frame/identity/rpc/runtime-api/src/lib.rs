@@ -0,0 +1,38 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition of the identity pallet, for username lookups.
+//!
+//! This API should be imported and implemented by the runtime of a node that wants to resolve
+//! usernames to accounts, and accounts to their primary username, for its clients.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	/// The API to look up the usernames of the identity pallet.
+	pub trait IdentityApi<AccountId> where
+		AccountId: codec::Codec,
+	{
+		/// Get the account owning `username`, if any. `username` includes its suffix.
+		fn account_of_username(username: Vec<u8>) -> Option<AccountId>;
+
+		/// Get the primary username of `who`, if any.
+		fn username_of(who: AccountId) -> Option<Vec<u8>>;
+	}
+}
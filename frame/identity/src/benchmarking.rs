@@ -22,6 +22,7 @@
 use super::*;
 
 use frame_system::RawOrigin;
+use sp_runtime::traits::IdentifyAccount;
 use frame_benchmarking::{benchmarks, account, whitelisted_caller, impl_benchmark_test_suite};
 use sp_runtime::traits::Bounded;
 use frame_support::{ensure, traits::Get};
@@ -51,6 +52,28 @@ fn add_registrars<T: Config>(r: u32) -> Result<(), &'static str> {
 	Ok(())
 }
 
+// Adds `authority` as a username authority with the longest suffix possible, and returns the
+// longest name that can be granted under it, without its suffix.
+fn add_username_authority<T: Config>(authority: &T::AccountId) -> Result<Vec<u8>, &'static str> {
+	let suffix = vec![b'a'; T::MaxSuffixLength::get() as usize];
+	let name = vec![b'b'; (T::MaxUsernameLength::get() - T::MaxSuffixLength::get() - 1) as usize];
+	Identity::<T>::add_username_authority(
+		RawOrigin::Root.into(),
+		T::Lookup::unlookup(authority.clone()),
+		suffix,
+		u32::max_value(),
+	)?;
+	Ok(name)
+}
+
+// The full username granted for `name` by an authority added by `add_username_authority`.
+fn full_username<T: Config>(name: &[u8]) -> Username<T> {
+	let mut username = name.to_vec();
+	username.push(b'.');
+	username.extend(vec![b'a'; T::MaxSuffixLength::get() as usize]);
+	username.try_into().expect("name is built to fit with the suffix; qed")
+}
+
 // Create `s` sub-accounts for the identity of `who` and return them.
 // Each will have 32 bytes of raw data added to it.
 fn create_sub_accounts<T: Config>(who: &T::AccountId, s: u32) -> Result<Vec<(T::AccountId, Data)>, &'static str> {
@@ -330,6 +353,9 @@ benchmarks! {
 		let info = create_identity_info::<T>(x);
 		Identity::<T>::set_identity(target_origin.clone(), info)?;
 		let _ = add_sub_accounts::<T>(&target, s)?;
+		let username = full_username::<T>(b"target");
+		AccountOfUsername::<T>::insert(&username, &target);
+		UsernameOf::<T>::insert(&target, username);
 
 		// User requests judgement from all the registrars, and they approve
 		for i in 0..r {
@@ -345,6 +371,7 @@ benchmarks! {
 	}: _(RawOrigin::Root, target_lookup)
 	verify {
 		ensure!(!IdentityOf::<T>::contains_key(&target), "Identity not removed");
+		ensure!(!UsernameOf::<T>::contains_key(&target), "Username not removed");
 	}
 
 	add_sub {
@@ -397,6 +424,90 @@ benchmarks! {
 		ensure!(!SuperOf::<T>::contains_key(&caller), "Sub not removed");
 	}
 
+	add_username_authority {
+		let authority: T::AccountId = account("authority", 0, SEED);
+		let suffix = vec![b'a'; T::MaxSuffixLength::get() as usize];
+	}: _(RawOrigin::Root, T::Lookup::unlookup(authority.clone()), suffix, 10)
+	verify {
+		assert_last_event::<T>(Event::<T>::AuthorityAdded(authority).into());
+	}
+
+	remove_username_authority {
+		let authority: T::AccountId = account("authority", 0, SEED);
+		add_username_authority::<T>(&authority)?;
+	}: _(RawOrigin::Root, T::Lookup::unlookup(authority.clone()))
+	verify {
+		assert_last_event::<T>(Event::<T>::AuthorityRemoved(authority).into());
+	}
+
+	set_username_for {
+		let authority: T::AccountId = account("authority", 0, SEED);
+		let name = add_username_authority::<T>(&authority)?;
+		let username = full_username::<T>(&name);
+		let (public, signature) = T::BenchmarkHelper::sign_message(&username[..]);
+		let who = public.into_account();
+	}: _(RawOrigin::Signed(authority), T::Lookup::unlookup(who.clone()), name, Some(signature))
+	verify {
+		ensure!(AccountOfUsername::<T>::get(&username) == Some(who.clone()), "Username not set");
+		assert_last_event::<T>(Event::<T>::PrimaryUsernameSet(who, username).into());
+	}
+
+	accept_username {
+		let caller: T::AccountId = whitelisted_caller();
+		let authority: T::AccountId = account("authority", 0, SEED);
+		let name = add_username_authority::<T>(&authority)?;
+		Identity::<T>::set_username_for(
+			RawOrigin::Signed(authority).into(),
+			T::Lookup::unlookup(caller.clone()),
+			name.clone(),
+			None,
+		)?;
+		let username = full_username::<T>(&name);
+	}: _(RawOrigin::Signed(caller.clone()), username.clone())
+	verify {
+		assert_last_event::<T>(Event::<T>::PrimaryUsernameSet(caller, username).into());
+	}
+
+	remove_expired_approval {
+		let caller: T::AccountId = whitelisted_caller();
+		let authority: T::AccountId = account("authority", 0, SEED);
+		let name = add_username_authority::<T>(&authority)?;
+		Identity::<T>::set_username_for(
+			RawOrigin::Signed(authority).into(),
+			T::Lookup::unlookup(caller.clone()),
+			name.clone(),
+			None,
+		)?;
+		let username = full_username::<T>(&name);
+		let expired = frame_system::Pallet::<T>::block_number()
+			+ T::PendingUsernameExpiration::get() + 1u32.into();
+		frame_system::Pallet::<T>::set_block_number(expired);
+	}: _(RawOrigin::Signed(caller.clone()), username.clone())
+	verify {
+		assert_last_event::<T>(Event::<T>::PreapprovalExpired(caller, username).into());
+	}
+
+	set_primary_username {
+		let caller: T::AccountId = whitelisted_caller();
+		let first = full_username::<T>(b"first");
+		let second = full_username::<T>(b"second");
+		AccountOfUsername::<T>::insert(&first, &caller);
+		AccountOfUsername::<T>::insert(&second, &caller);
+		UsernameOf::<T>::insert(&caller, first);
+	}: _(RawOrigin::Signed(caller.clone()), second.clone())
+	verify {
+		assert_last_event::<T>(Event::<T>::PrimaryUsernameSet(caller, second).into());
+	}
+
+	remove_dangling_username {
+		let caller: T::AccountId = whitelisted_caller();
+		let target: T::AccountId = account("target", 0, SEED);
+		let username = full_username::<T>(b"target");
+		AccountOfUsername::<T>::insert(&username, &target);
+	}: _(RawOrigin::Signed(caller), username.clone())
+	verify {
+		assert_last_event::<T>(Event::<T>::DanglingUsernameRemoved(target, username).into());
+	}
 }
 
 impl_benchmark_test_suite!(
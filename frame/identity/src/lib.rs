@@ -63,9 +63,36 @@
 //! * `set_fields` - Set the fields that a registrar cares about in their judgements.
 //! * `provide_judgement` - Provide a judgement to an identity.
 //!
+//! #### For general users with usernames
+//! * `accept_username` - Accept a username granted by an authority.
+//! * `set_primary_username` - Choose which of an account's usernames is its primary one.
+//!
+//! #### For username authorities
+//! * `set_username_for` - Grant a username under the authority's suffix to an account.
+//!
+//! #### For anyone
+//! * `remove_expired_approval` - Remove a granted username that was not accepted in time.
+//! * `remove_dangling_username` - Remove a username whose account lost its primary username.
+//!
 //! #### For super-users
 //! * `add_registrar` - Add a new registrar to the system.
 //! * `kill_identity` - Forcibly remove the associated identity; the deposit is lost.
+//! * `add_username_authority` - Add an authority that may grant usernames under a suffix.
+//! * `remove_username_authority` - Remove a username authority.
+//!
+//! ### Usernames
+//!
+//! Usernames are unique, human-readable handles of the form `name.suffix` that resolve to an
+//! account. They are granted by username authorities, each of which owns one suffix (e.g. `acme`
+//! for `*.acme`) and may only grant a limited number of usernames under it.
+//!
+//! An authority may either provide a signature of the full username by the receiving account, in
+//! which case the username is set immediately, or grant it without one, in which case the account
+//! has `PendingUsernameExpiration` blocks to accept it. The first username of an account becomes
+//! its primary username, which is the one returned by reverse lookups.
+//!
+//! When an identity is killed through `kill_identity`, its primary username is removed. The
+//! account's remaining usernames are then dangling and may be removed by anyone.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Config`]: ./trait.Config.html
@@ -80,18 +107,42 @@ pub mod weights;
 
 use sp_std::prelude::*;
 use sp_std::convert::TryInto;
-use sp_runtime::traits::{StaticLookup, Zero, AppendZerosInput, Saturating};
-use frame_support::traits::{BalanceStatus, Currency, OnUnbalanced, ReservableCurrency};
+use sp_runtime::traits::{StaticLookup, Zero, AppendZerosInput, Saturating, Verify, IdentifyAccount};
+use frame_support::{BoundedVec, traits::{BalanceStatus, Currency, OnUnbalanced, ReservableCurrency}};
 pub use weights::WeightInfo;
 
 pub use pallet::*;
 pub use types::{
-	Data, IdentityField, IdentityFields, IdentityInfo, Judgement, RegistrarIndex,
-	RegistrarInfo, Registration,
+	AuthorityProperties, Data, IdentityField, IdentityFields, IdentityInfo, Judgement,
+	RegistrarIndex, RegistrarInfo, Registration,
 };
 
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+type AuthorityPropertiesOf<T> = AuthorityProperties<Suffix<T>>;
+
+/// A full username, i.e. a name and the suffix of the authority that granted it, joined by `.`.
+pub type Username<T> = BoundedVec<u8, <T as Config>::MaxUsernameLength>;
+/// The suffix of the usernames granted by a username authority.
+pub type Suffix<T> = BoundedVec<u8, <T as Config>::MaxSuffixLength>;
+
+/// Provides signatures of usernames to benchmark `set_username_for`.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<Signer, Signature> {
+	/// Sign `message` with a new key, returning the key and the signature.
+	fn sign_message(message: &[u8]) -> (Signer, Signature);
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl BenchmarkHelper<sp_runtime::MultiSigner, sp_runtime::MultiSignature> for () {
+	fn sign_message(message: &[u8]) -> (sp_runtime::MultiSigner, sp_runtime::MultiSignature) {
+		let key_type = sp_runtime::KeyTypeId(*b"ustm");
+		let public = sp_io::crypto::sr25519_generate(key_type, None);
+		let signature = sp_io::crypto::sr25519_sign(key_type, &public, message)
+			.expect("the key was generated in the keystore above; qed");
+		(public.into(), signature.into())
+	}
+}
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -145,8 +196,34 @@ pub mod pallet {
 		/// The origin which may add or remove registrars. Root can always do this.
 		type RegistrarOrigin: EnsureOrigin<Self::Origin>;
 
+		/// The signature type with which accounts accept the usernames granted to them.
+		type OffchainSignature: Verify<Signer = Self::SigningPublicKey> + Parameter;
+
+		/// The public key type corresponding to `OffchainSignature`.
+		type SigningPublicKey: IdentifyAccount<AccountId = Self::AccountId>;
+
+		/// The origin which may add or remove username authorities. Root can always do this.
+		type UsernameAuthorityOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The number of blocks within which a username granted without a signature must be
+		/// accepted.
+		#[pallet::constant]
+		type PendingUsernameExpiration: Get<Self::BlockNumber>;
+
+		/// The maximum length of the suffix of a username authority.
+		#[pallet::constant]
+		type MaxSuffixLength: Get<u32>;
+
+		/// The maximum length of a username, including its suffix and the separating `.`.
+		#[pallet::constant]
+		type MaxUsernameLength: Get<u32>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
+
+		/// Helper to sign usernames in benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::SigningPublicKey, Self::OffchainSignature>;
 	}
 
 	#[pallet::pallet]
@@ -206,6 +283,54 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The authorities allowed to grant usernames, with their suffix and remaining allocation.
+	///
+	/// TWOX-NOTE: OK ― `AccountId` is a secure hash.
+	#[pallet::storage]
+	#[pallet::getter(fn username_authority)]
+	pub(super) type UsernameAuthorities<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		AuthorityPropertiesOf<T>,
+		OptionQuery,
+	>;
+
+	/// The account owning each username.
+	///
+	/// An account may own several usernames, one of which is its primary username.
+	#[pallet::storage]
+	pub(super) type AccountOfUsername<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		Username<T>,
+		T::AccountId,
+		OptionQuery,
+	>;
+
+	/// The primary username of an account, used for reverse lookups.
+	///
+	/// TWOX-NOTE: OK ― `AccountId` is a secure hash.
+	#[pallet::storage]
+	pub(super) type UsernameOf<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Username<T>,
+		OptionQuery,
+	>;
+
+	/// Usernames granted without a signature, with the account they were granted to and the block
+	/// number after which they can no longer be accepted.
+	#[pallet::storage]
+	pub(super) type PendingUsernames<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		Username<T>,
+		(T::AccountId, T::BlockNumber),
+		OptionQuery,
+	>;
+
 	#[pallet::error]
 	pub enum Error<T> {
 		/// Too many subs-accounts.
@@ -239,14 +364,36 @@ pub mod pallet {
 		/// Sender is not a sub-account.
 		NotSub,
 		/// Sub-account isn't owned by sender.
-		NotOwned
+		NotOwned,
+		/// The suffix is empty, too long or contains invalid characters.
+		InvalidSuffix,
+		/// The sender is not a username authority.
+		NotUsernameAuthority,
+		/// The authority has no allocation of usernames left.
+		NoAllocation,
+		/// The signature of the username is invalid.
+		InvalidSignature,
+		/// The username is empty, too long or contains invalid characters.
+		InvalidUsername,
+		/// The username is already taken or pending acceptance.
+		UsernameTaken,
+		/// The username does not exist.
+		NoUsername,
+		/// The pending username has not expired yet.
+		NotExpired,
+		/// The pending username has expired and can no longer be accepted.
+		Expired,
+		/// The username is the primary username of its account.
+		NotDangling,
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	#[pallet::metadata(
 		T::AccountId = "AccountId",
-		BalanceOf<T> = "Balance"
+		BalanceOf<T> = "Balance",
+		Username<T> = "Username",
+		T::BlockNumber = "BlockNumber"
 	)]
 	pub enum Event<T: Config> {
 		/// A name was set or reset (which will remove all judgements). \[who\]
@@ -271,6 +418,21 @@ pub mod pallet {
 		/// A sub-identity was cleared, and the given deposit repatriated from the
 		/// main identity account to the sub-identity account. \[sub, main, deposit\]
 		SubIdentityRevoked(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// A username authority was added. \[authority\]
+		AuthorityAdded(T::AccountId),
+		/// A username authority was removed. \[authority\]
+		AuthorityRemoved(T::AccountId),
+		/// A username was set for an account. \[who, username\]
+		UsernameSet(T::AccountId, Username<T>),
+		/// A username was granted without a signature and awaits acceptance.
+		/// \[who, username, expiration\]
+		UsernameQueued(T::AccountId, Username<T>, T::BlockNumber),
+		/// A pending username expired and was removed. \[who, username\]
+		PreapprovalExpired(T::AccountId, Username<T>),
+		/// The primary username of an account was set. \[who, username\]
+		PrimaryUsernameSet(T::AccountId, Username<T>),
+		/// A dangling username was removed. \[who, username\]
+		DanglingUsernameRemoved(T::AccountId, Username<T>),
 	}
 
 	#[pallet::call]
@@ -788,6 +950,10 @@ pub mod pallet {
 			for sub in sub_ids.iter() {
 				<SuperOf<T>>::remove(sub);
 			}
+			// Remove their primary username; any other username of theirs is now dangling.
+			if let Some(username) = <UsernameOf<T>>::take(&target) {
+				<AccountOfUsername<T>>::remove(&username);
+			}
 			// Slash their deposit from them.
 			T::Slashed::on_unbalanced(T::Currency::slash_reserved(&target, deposit).0);
 
@@ -896,6 +1062,186 @@ pub mod pallet {
 			});
 			Ok(())
 		}
+
+		/// Add an authority that may grant up to `allocation` usernames ending in `.suffix`.
+		///
+		/// If `authority` is already a username authority, its suffix and allocation are replaced.
+		///
+		/// The dispatch origin for this call must be `T::UsernameAuthorityOrigin`.
+		///
+		/// - `suffix`: lowercase alphanumeric, without the separating `.`.
+		///
+		/// Emits `AuthorityAdded` if successful.
+		#[pallet::weight(T::WeightInfo::add_username_authority())]
+		pub fn add_username_authority(
+			origin: OriginFor<T>,
+			authority: <T::Lookup as StaticLookup>::Source,
+			suffix: Vec<u8>,
+			allocation: u32,
+		) -> DispatchResult {
+			T::UsernameAuthorityOrigin::ensure_origin(origin)?;
+			let authority = T::Lookup::lookup(authority)?;
+			ensure!(Self::validate_name(&suffix), Error::<T>::InvalidSuffix);
+			let suffix: Suffix<T> = suffix.try_into().map_err(|_| Error::<T>::InvalidSuffix)?;
+
+			UsernameAuthorities::<T>::insert(&authority, AuthorityProperties { suffix, allocation });
+
+			Self::deposit_event(Event::AuthorityAdded(authority));
+			Ok(())
+		}
+
+		/// Remove a username authority. Usernames it has already granted are left untouched.
+		///
+		/// The dispatch origin for this call must be `T::UsernameAuthorityOrigin`.
+		///
+		/// Emits `AuthorityRemoved` if successful.
+		#[pallet::weight(T::WeightInfo::remove_username_authority())]
+		pub fn remove_username_authority(
+			origin: OriginFor<T>,
+			authority: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::UsernameAuthorityOrigin::ensure_origin(origin)?;
+			let authority = T::Lookup::lookup(authority)?;
+			UsernameAuthorities::<T>::take(&authority).ok_or(Error::<T>::NotUsernameAuthority)?;
+
+			Self::deposit_event(Event::AuthorityRemoved(authority));
+			Ok(())
+		}
+
+		/// Grant the username `username.suffix` to `who`, where `suffix` is the suffix of the
+		/// sender. This uses one unit of the sender's allocation.
+		///
+		/// If `signature` is a valid signature of the full username by `who`, the username is set
+		/// immediately. Otherwise `who` must accept it with `accept_username` within
+		/// `T::PendingUsernameExpiration` blocks.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must be a username
+		/// authority.
+		///
+		/// - `username`: lowercase alphanumeric, without the suffix.
+		///
+		/// Emits `UsernameSet` or `UsernameQueued` if successful.
+		#[pallet::weight(T::WeightInfo::set_username_for())]
+		pub fn set_username_for(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			username: Vec<u8>,
+			signature: Option<T::OffchainSignature>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			let mut authority = UsernameAuthorities::<T>::get(&sender)
+				.ok_or(Error::<T>::NotUsernameAuthority)?;
+			ensure!(authority.allocation > 0, Error::<T>::NoAllocation);
+			ensure!(Self::validate_name(&username), Error::<T>::InvalidUsername);
+
+			let mut full_username = username;
+			full_username.push(b'.');
+			full_username.extend_from_slice(&authority.suffix);
+			let full_username: Username<T> = full_username.try_into()
+				.map_err(|_| Error::<T>::InvalidUsername)?;
+			ensure!(
+				!AccountOfUsername::<T>::contains_key(&full_username)
+					&& !PendingUsernames::<T>::contains_key(&full_username),
+				Error::<T>::UsernameTaken,
+			);
+			if let Some(signature) = &signature {
+				ensure!(signature.verify(&full_username[..], &who), Error::<T>::InvalidSignature);
+			}
+
+			authority.allocation -= 1;
+			UsernameAuthorities::<T>::insert(&sender, authority);
+
+			if signature.is_some() {
+				Self::insert_username(&who, full_username);
+			} else {
+				let expiration = frame_system::Pallet::<T>::block_number()
+					.saturating_add(T::PendingUsernameExpiration::get());
+				PendingUsernames::<T>::insert(&full_username, (who.clone(), expiration));
+				Self::deposit_event(Event::UsernameQueued(who, full_username, expiration));
+			}
+			Ok(())
+		}
+
+		/// Accept a username granted to the sender without a signature.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must be the account
+		/// the username was granted to.
+		///
+		/// Emits `UsernameSet` if successful.
+		#[pallet::weight(T::WeightInfo::accept_username())]
+		pub fn accept_username(origin: OriginFor<T>, username: Username<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let (who, expiration) = PendingUsernames::<T>::get(&username)
+				.ok_or(Error::<T>::NoUsername)?;
+			ensure!(who == sender, Error::<T>::InvalidUsername);
+			ensure!(frame_system::Pallet::<T>::block_number() <= expiration, Error::<T>::Expired);
+
+			PendingUsernames::<T>::remove(&username);
+			Self::insert_username(&who, username);
+			Ok(())
+		}
+
+		/// Remove a username that was granted without a signature and not accepted in time.
+		///
+		/// The dispatch origin for this call must be _Signed_. The transaction fee is refunded if
+		/// the username is removed.
+		///
+		/// Emits `PreapprovalExpired` if successful.
+		#[pallet::weight(T::WeightInfo::remove_expired_approval())]
+		pub fn remove_expired_approval(
+			origin: OriginFor<T>,
+			username: Username<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let (who, expiration) = PendingUsernames::<T>::get(&username)
+				.ok_or(Error::<T>::NoUsername)?;
+			ensure!(frame_system::Pallet::<T>::block_number() > expiration, Error::<T>::NotExpired);
+
+			PendingUsernames::<T>::remove(&username);
+
+			Self::deposit_event(Event::PreapprovalExpired(who, username));
+			Ok(Pays::No.into())
+		}
+
+		/// Make one of the sender's usernames its primary username.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must own `username`.
+		///
+		/// Emits `PrimaryUsernameSet` if successful.
+		#[pallet::weight(T::WeightInfo::set_primary_username())]
+		pub fn set_primary_username(origin: OriginFor<T>, username: Username<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let who = AccountOfUsername::<T>::get(&username).ok_or(Error::<T>::NoUsername)?;
+			ensure!(who == sender, Error::<T>::InvalidUsername);
+
+			UsernameOf::<T>::insert(&who, username.clone());
+
+			Self::deposit_event(Event::PrimaryUsernameSet(who, username));
+			Ok(())
+		}
+
+		/// Remove a username of an account that has no primary username, which happens once its
+		/// identity has been killed.
+		///
+		/// The dispatch origin for this call must be _Signed_. The transaction fee is refunded if
+		/// the username is removed.
+		///
+		/// Emits `DanglingUsernameRemoved` if successful.
+		#[pallet::weight(T::WeightInfo::remove_dangling_username())]
+		pub fn remove_dangling_username(
+			origin: OriginFor<T>,
+			username: Username<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let who = AccountOfUsername::<T>::get(&username).ok_or(Error::<T>::NoUsername)?;
+			ensure!(!UsernameOf::<T>::contains_key(&who), Error::<T>::NotDangling);
+
+			AccountOfUsername::<T>::remove(&username);
+
+			Self::deposit_event(Event::DanglingUsernameRemoved(who, username));
+			Ok(Pays::No.into())
+		}
 	}
 
 }
@@ -908,4 +1254,31 @@ impl<T: Config> Pallet<T> {
 			.filter_map(|a| SuperOf::<T>::get(&a).map(|x| (a, x.1)))
 			.collect()
 	}
+
+	/// Get the account owning `username`, if any.
+	pub fn lookup_username(username: Vec<u8>) -> Option<T::AccountId> {
+		let username: Username<T> = username.try_into().ok()?;
+		AccountOfUsername::<T>::get(&username)
+	}
+
+	/// Get the primary username of `who`, if any.
+	pub fn primary_username(who: &T::AccountId) -> Option<Vec<u8>> {
+		UsernameOf::<T>::get(who).map(Into::into)
+	}
+
+	/// Whether `name` is non-empty and only made of lowercase ASCII letters and digits.
+	fn validate_name(name: &[u8]) -> bool {
+		!name.is_empty() && name.iter().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+	}
+
+	/// Give `username` to `who`, making it its primary username if it has none.
+	fn insert_username(who: &T::AccountId, username: Username<T>) {
+		AccountOfUsername::<T>::insert(&username, who);
+		Self::deposit_event(Event::UsernameSet(who.clone(), username.clone()));
+
+		if !UsernameOf::<T>::contains_key(who) {
+			UsernameOf::<T>::insert(who, username.clone());
+			Self::deposit_event(Event::PrimaryUsernameSet(who.clone(), username));
+		}
+	}
 }
@@ -22,11 +22,11 @@ use crate as pallet_identity;
 
 use codec::{Encode, Decode};
 use sp_runtime::traits::BadOrigin;
-use frame_support::{assert_ok, assert_noop, parameter_types, ord_parameter_types, BoundedVec};
+use frame_support::{assert_ok, assert_noop, parameter_types, ord_parameter_types, BoundedVec, weights::Pays};
 use sp_core::H256;
 use frame_system::{EnsureSignedBy, EnsureOneOf, EnsureRoot};
 use sp_runtime::{
-	testing::Header, traits::{BlakeTwo256, IdentityLookup},
+	testing::{Header, TestSignature, UintAuthorityId}, traits::{BlakeTwo256, IdentityLookup},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -95,6 +95,9 @@ parameter_types! {
 	pub const MaxSubAccounts: u32 = 2;
 	pub const MaxAdditionalFields: u32 = 2;
 	pub const MaxRegistrars: u32 = 20;
+	pub const PendingUsernameExpiration: u64 = 100;
+	pub const MaxSuffixLength: u32 = 7;
+	pub const MaxUsernameLength: u32 = 16;
 }
 ord_parameter_types! {
 	pub const One: u64 = 1;
@@ -122,7 +125,24 @@ impl pallet_identity::Config for Test {
 	type MaxRegistrars = MaxRegistrars;
 	type RegistrarOrigin = EnsureOneOrRoot;
 	type ForceOrigin = EnsureTwoOrRoot;
+	type OffchainSignature = TestSignature;
+	type SigningPublicKey = UintAuthorityId;
+	type UsernameAuthorityOrigin = EnsureOneOrRoot;
+	type PendingUsernameExpiration = PendingUsernameExpiration;
+	type MaxSuffixLength = MaxSuffixLength;
+	type MaxUsernameLength = MaxUsernameLength;
 	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = TestBenchmarkHelper;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct TestBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl BenchmarkHelper<UintAuthorityId, TestSignature> for TestBenchmarkHelper {
+	fn sign_message(message: &[u8]) -> (UintAuthorityId, TestSignature) {
+		(UintAuthorityId(40), TestSignature(40, message.to_vec()))
+	}
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -472,3 +492,151 @@ fn setting_account_id_should_work() {
 		assert_ok!(Identity::set_account_id(Origin::signed(4), 0, 3));
 	});
 }
+
+fn username(name: &[u8]) -> Username<Test> {
+	name.to_vec().try_into().unwrap()
+}
+
+#[test]
+fn adding_username_authorities_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Identity::add_username_authority(Origin::signed(3), 3, b"acme".to_vec(), 2), BadOrigin);
+		assert_noop!(
+			Identity::add_username_authority(Origin::signed(1), 3, b"Acme".to_vec(), 2),
+			Error::<Test>::InvalidSuffix,
+		);
+		assert_noop!(
+			Identity::add_username_authority(Origin::signed(1), 3, b"acmeacme".to_vec(), 2),
+			Error::<Test>::InvalidSuffix,
+		);
+		assert_ok!(Identity::add_username_authority(Origin::signed(1), 3, b"acme".to_vec(), 2));
+		assert_eq!(Identity::username_authority(3).unwrap().allocation, 2);
+
+		assert_ok!(Identity::remove_username_authority(Origin::signed(1), 3));
+		assert_eq!(Identity::username_authority(3), None);
+		assert_noop!(
+			Identity::remove_username_authority(Origin::signed(1), 3),
+			Error::<Test>::NotUsernameAuthority,
+		);
+	});
+}
+
+#[test]
+fn setting_username_with_signature_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Identity::add_username_authority(Origin::signed(1), 3, b"acme".to_vec(), 2));
+		let signature = |who, name: &[u8]| Some(TestSignature(who, name.to_vec()));
+
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(4), 10, b"ten".to_vec(), signature(10, b"ten.acme")),
+			Error::<Test>::NotUsernameAuthority,
+		);
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(3), 10, b"ten".to_vec(), signature(20, b"ten.acme")),
+			Error::<Test>::InvalidSignature,
+		);
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(3), 10, b"ten".to_vec(), signature(10, b"ten")),
+			Error::<Test>::InvalidSignature,
+		);
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(3), 10, b"ten.x".to_vec(), signature(10, b"ten.x.acme")),
+			Error::<Test>::InvalidUsername,
+		);
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(3), 10, b"tententententen".to_vec(), None),
+			Error::<Test>::InvalidUsername,
+		);
+
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"ten".to_vec(), signature(10, b"ten.acme")));
+		assert_eq!(Identity::lookup_username(b"ten.acme".to_vec()), Some(10));
+		assert_eq!(Identity::primary_username(&10), Some(b"ten.acme".to_vec()));
+		assert_eq!(Identity::username_authority(3).unwrap().allocation, 1);
+
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(3), 20, b"ten".to_vec(), signature(20, b"ten.acme")),
+			Error::<Test>::UsernameTaken,
+		);
+
+		// A second username does not replace the primary one until asked to.
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"tenth".to_vec(), signature(10, b"tenth.acme")));
+		assert_eq!(Identity::primary_username(&10), Some(b"ten.acme".to_vec()));
+		assert_noop!(
+			Identity::set_primary_username(Origin::signed(20), username(b"tenth.acme")),
+			Error::<Test>::InvalidUsername,
+		);
+		assert_ok!(Identity::set_primary_username(Origin::signed(10), username(b"tenth.acme")));
+		assert_eq!(Identity::primary_username(&10), Some(b"tenth.acme".to_vec()));
+
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(3), 20, b"twenty".to_vec(), signature(20, b"twenty.acme")),
+			Error::<Test>::NoAllocation,
+		);
+	});
+}
+
+#[test]
+fn accepting_username_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Identity::add_username_authority(Origin::signed(1), 3, b"acme".to_vec(), 2));
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"ten".to_vec(), None));
+		assert_eq!(Identity::lookup_username(b"ten.acme".to_vec()), None);
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(3), 20, b"ten".to_vec(), None),
+			Error::<Test>::UsernameTaken,
+		);
+
+		assert_noop!(Identity::accept_username(Origin::signed(20), username(b"ten.acme")), Error::<Test>::InvalidUsername);
+		assert_noop!(Identity::accept_username(Origin::signed(10), username(b"one.acme")), Error::<Test>::NoUsername);
+		assert_ok!(Identity::accept_username(Origin::signed(10), username(b"ten.acme")));
+		assert_eq!(Identity::lookup_username(b"ten.acme".to_vec()), Some(10));
+		assert_eq!(Identity::primary_username(&10), Some(b"ten.acme".to_vec()));
+	});
+}
+
+#[test]
+fn pending_usernames_should_expire() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Identity::add_username_authority(Origin::signed(1), 3, b"acme".to_vec(), 2));
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"ten".to_vec(), None));
+
+		System::set_block_number(101);
+		assert_noop!(
+			Identity::remove_expired_approval(Origin::signed(20), username(b"ten.acme")),
+			Error::<Test>::NotExpired,
+		);
+
+		System::set_block_number(102);
+		assert_noop!(Identity::accept_username(Origin::signed(10), username(b"ten.acme")), Error::<Test>::Expired);
+		let info = Identity::remove_expired_approval(Origin::signed(20), username(b"ten.acme")).unwrap();
+		assert_eq!(info.pays_fee, Pays::No);
+		assert_noop!(Identity::accept_username(Origin::signed(10), username(b"ten.acme")), Error::<Test>::NoUsername);
+
+		// The username is free again.
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 20, b"ten".to_vec(), None));
+	});
+}
+
+#[test]
+fn killing_identity_should_remove_usernames() {
+	new_test_ext().execute_with(|| {
+		let signature = |who, name: &[u8]| Some(TestSignature(who, name.to_vec()));
+		assert_ok!(Identity::add_username_authority(Origin::signed(1), 3, b"acme".to_vec(), 2));
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"ten".to_vec(), signature(10, b"ten.acme")));
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"tenth".to_vec(), signature(10, b"tenth.acme")));
+		assert_noop!(
+			Identity::remove_dangling_username(Origin::signed(20), username(b"tenth.acme")),
+			Error::<Test>::NotDangling,
+		);
+
+		assert_ok!(Identity::kill_identity(Origin::signed(2), 10));
+		assert_eq!(Identity::primary_username(&10), None);
+		assert_eq!(Identity::lookup_username(b"ten.acme".to_vec()), None);
+
+		assert_eq!(Identity::lookup_username(b"tenth.acme".to_vec()), Some(10));
+		assert_ok!(Identity::remove_dangling_username(Origin::signed(20), username(b"tenth.acme")));
+		assert_eq!(Identity::lookup_username(b"tenth.acme".to_vec()), None);
+	});
+}
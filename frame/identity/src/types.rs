@@ -316,3 +316,14 @@ pub struct RegistrarInfo<
 	/// these fields.
 	pub fields: IdentityFields,
 }
+
+/// Information concerning a username authority.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen)]
+pub struct AuthorityProperties<Suffix> {
+	/// The suffix appended to every username granted by this authority, without the separating
+	/// `.`.
+	pub suffix: Suffix,
+
+	/// The number of usernames that this authority may still grant.
+	pub allocation: u32,
+}
@@ -26,6 +26,8 @@ use sp_std::fmt;
 #[cfg(not(feature = "std"))]
 use sp_std::prelude::Vec;
 
+pub mod utils;
+
 /// A provider of the MMR's leaf data.
 pub trait LeafDataProvider {
 	/// A type that should end up in the leaf of MMR.
@@ -286,6 +288,17 @@ pub struct Proof<Hash> {
 	pub items: Vec<Hash>,
 }
 
+/// A MMR proof data for a group of leaves.
+#[derive(codec::Encode, codec::Decode, RuntimeDebug, Clone, PartialEq, Eq)]
+pub struct BatchProof<Hash> {
+	/// The indices of the leaves the proof is for.
+	pub leaf_indices: Vec<u64>,
+	/// Number of leaves in MMR, when the proof was generated.
+	pub leaf_count: u64,
+	/// Proof elements (hashes of siblings of inner nodes on the path to the leaves).
+	pub items: Vec<Hash>,
+}
+
 /// Merkle Mountain Range operation error.
 #[derive(RuntimeDebug, codec::Encode, codec::Decode, PartialEq, Eq)]
 pub enum Error {
@@ -404,6 +417,7 @@ impl EncodableOpaqueLeaf {
 
 sp_api::decl_runtime_apis! {
 	/// API to interact with MMR pallet.
+	#[api_version(2)]
 	pub trait MmrApi<Hash: codec::Codec> {
 		/// Generate MMR proof for a leaf under given index.
 		fn generate_proof(leaf_index: u64) -> Result<(EncodableOpaqueLeaf, Proof<Hash>), Error>;
@@ -423,6 +437,38 @@ sp_api::decl_runtime_apis! {
 		/// The leaf data is expected to be encoded in it's compact form.
 		fn verify_proof_stateless(root: Hash, leaf: EncodableOpaqueLeaf, proof: Proof<Hash>)
 			-> Result<(), Error>;
+
+		/// Return the number of leaves in the MMR.
+		fn mmr_leaf_count() -> Result<u64, Error>;
+
+		/// Generate MMR proof for a leaf under given index, against the MMR as it was when it
+		/// contained `leaf_count` leaves.
+		///
+		/// This allows generating proofs for past blocks using the Off-chain DB of a recent block.
+		fn generate_historical_proof(leaf_index: u64, leaf_count: u64)
+			-> Result<(EncodableOpaqueLeaf, Proof<Hash>), Error>;
+
+		/// Generate MMR proof for the leaves under given indices.
+		fn generate_batch_proof(leaf_indices: Vec<u64>)
+			-> Result<(Vec<EncodableOpaqueLeaf>, BatchProof<Hash>), Error>;
+
+		/// Generate MMR proof for the leaves under given indices, against the MMR as it was when
+		/// it contained `leaf_count` leaves.
+		fn generate_historical_batch_proof(leaf_indices: Vec<u64>, leaf_count: u64)
+			-> Result<(Vec<EncodableOpaqueLeaf>, BatchProof<Hash>), Error>;
+
+		/// Verify MMR batch proof against on-chain MMR.
+		fn verify_batch_proof(leaves: Vec<EncodableOpaqueLeaf>, proof: BatchProof<Hash>)
+			-> Result<(), Error>;
+
+		/// Verify MMR batch proof against given root hash.
+		///
+		/// The leaf data is expected to be encoded in it's compact form.
+		fn verify_batch_proof_stateless(
+			root: Hash,
+			leaves: Vec<EncodableOpaqueLeaf>,
+			proof: BatchProof<Hash>,
+		) -> Result<(), Error>;
 	}
 }
 
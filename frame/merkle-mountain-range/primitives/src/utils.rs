@@ -0,0 +1,144 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Merkle Mountain Range utilities.
+
+use codec::Encode;
+#[cfg(not(feature = "std"))]
+use sp_std::prelude::Vec;
+
+/// MMR nodes & size -related utilities.
+pub struct NodesUtils {
+	no_of_leaves: u64,
+}
+
+impl NodesUtils {
+	/// Create new instance of MMR nodes utilities for given number of leaves.
+	pub fn new(no_of_leaves: u64) -> Self {
+		Self { no_of_leaves }
+	}
+
+	/// Calculate number of peaks in the MMR.
+	pub fn number_of_peaks(&self) -> u64 {
+		self.number_of_leaves().count_ones() as u64
+	}
+
+	/// Return the number of leaves in the MMR.
+	pub fn number_of_leaves(&self) -> u64 {
+		self.no_of_leaves
+	}
+
+	/// Calculate the total size of MMR (number of nodes).
+	pub fn size(&self) -> u64 {
+		2 * self.no_of_leaves - self.number_of_peaks()
+	}
+
+	/// Calculate maximal depth of the MMR.
+	pub fn depth(&self) -> u32 {
+		if self.no_of_leaves == 0 {
+			return 0
+		}
+
+		64 - self.no_of_leaves
+				.next_power_of_two()
+				.leading_zeros()
+	}
+
+	/// Return the index of the leaf whose insertion added the node at position `pos` to the MMR.
+	///
+	/// Appending a leaf adds the leaf itself, followed by the inner nodes it completes.
+	pub fn leaf_index_that_added_node(pos: u64) -> u64 {
+		// The leaf `i` is added at position `size(i)`, so look for the last leaf at or before `pos`.
+		let (mut low, mut high) = (0, pos + 1);
+		while high - low > 1 {
+			let mid = low + (high - low) / 2;
+			if NodesUtils::new(mid).size() <= pos {
+				low = mid;
+			} else {
+				high = mid;
+			}
+		}
+		low
+	}
+
+	/// Return the positions of the nodes added to the MMR when appending the leaf `leaf_index`.
+	pub fn nodes_added_by_leaf(leaf_index: u64) -> sp_std::ops::Range<u64> {
+		NodesUtils::new(leaf_index).size() .. NodesUtils::new(leaf_index + 1).size()
+	}
+}
+
+/// Key under which the node at `pos` is stored in the Off-chain DB once canonicalized.
+pub fn node_canon_offchain_key(prefix: &[u8], pos: u64) -> Vec<u8> {
+	(prefix, pos).encode()
+}
+
+/// Key under which the node at `pos` is stored in the Off-chain DB by a block with the given
+/// `parent_hash`, before being canonicalized.
+///
+/// Nodes are first stored under fork-specific keys, since blocks of different forks may add
+/// different nodes at the same position.
+pub fn node_temp_offchain_key<Hash: Encode>(prefix: &[u8], pos: u64, parent_hash: Hash) -> Vec<u8> {
+	(prefix, pos, parent_hash).encode()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_calculate_number_of_leaves_correctly() {
+		assert_eq!(
+			vec![0, 1, 2, 3, 4, 9, 15, 21]
+				.into_iter()
+				.map(|n| NodesUtils::new(n).depth())
+				.collect::<Vec<_>>(),
+			vec![0, 1, 2, 3, 3, 5, 5, 6]
+		);
+	}
+
+	#[test]
+	fn should_calculate_depth_correclty() {
+		assert_eq!(
+			vec![0, 1, 2, 3, 4, 9, 15, 21]
+				.into_iter()
+				.map(|n| NodesUtils::new(n).number_of_leaves())
+				.collect::<Vec<_>>(),
+			vec![0, 1, 2, 3, 4, 9, 15, 21]
+		);
+	}
+
+	#[test]
+	fn should_calculate_number_of_peaks_correctly() {
+		assert_eq!(
+			vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 21]
+				.into_iter()
+				.map(|n| NodesUtils::new(n).number_of_peaks())
+				.collect::<Vec<_>>(),
+			vec![0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, 3]
+		);
+	}
+
+	#[test]
+	fn should_find_the_leaf_that_added_a_node() {
+		// Leaves 0..4 add positions [0], [1, 2], [3], [4, 5, 6] and [7].
+		assert_eq!(
+			(0..8).map(NodesUtils::leaf_index_that_added_node).collect::<Vec<_>>(),
+			vec![0, 1, 1, 2, 3, 3, 3, 4]
+		);
+		assert_eq!(NodesUtils::nodes_added_by_leaf(3), 4..7);
+	}
+}
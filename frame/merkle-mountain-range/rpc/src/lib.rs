@@ -26,14 +26,14 @@ use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 use serde::{Deserialize, Serialize};
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::Bytes;
 use sp_runtime::{
 	generic::BlockId,
-	traits::{Block as BlockT},
+	traits::{Block as BlockT, Saturating, UniqueSaturatedInto},
 };
-use pallet_mmr_primitives::{Error as MmrError, Proof};
+use pallet_mmr_primitives::{BatchProof, Error as MmrError, Proof};
 
 pub use pallet_mmr_primitives::MmrApi as MmrRuntimeApi;
 
@@ -67,6 +67,36 @@ impl<BlockHash> LeafProof<BlockHash> {
 	}
 }
 
+/// Retrieved MMR leaves and their proof.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LeafBatchProof<BlockHash> {
+	/// Block hash the proof was generated for.
+	pub block_hash: BlockHash,
+	/// SCALE-encoded vector of leaf data.
+	pub leaves: Bytes,
+	/// SCALE-encoded proof data. See [pallet_mmr_primitives::BatchProof].
+	pub proof: Bytes,
+}
+
+impl<BlockHash> LeafBatchProof<BlockHash> {
+	/// Create new `LeafBatchProof` from given concrete `leaves` and `proof`.
+	pub fn new<Leaf, MmrHash>(
+		block_hash: BlockHash,
+		leaves: Vec<Leaf>,
+		proof: BatchProof<MmrHash>,
+	) -> Self where
+		Leaf: Encode,
+		MmrHash: Encode,
+	{
+		Self {
+			block_hash,
+			leaves: Bytes(leaves.encode()),
+			proof: Bytes(proof.encode()),
+		}
+	}
+}
+
 /// MMR RPC methods.
 #[rpc]
 pub trait MmrApi<BlockHash> {
@@ -76,6 +106,10 @@ pub trait MmrApi<BlockHash> {
 	/// MMR proof for leaf at given `leaf_index`.
 	/// Optionally, a block hash at which the runtime should be queried can be specified.
 	///
+	/// The proof is generated against the MMR as it was at block `at`. If `at` is part of the
+	/// best chain, the nodes are read from the Off-chain DB of the best block, which allows
+	/// generating proofs for blocks whose state has already been pruned.
+	///
 	/// Returns the (full) leaf itself and a proof for this leaf (compact encoding, i.e. hash of
 	/// the leaf). Both parameters are SCALE-encoded.
 	#[rpc(name = "mmr_generateProof")]
@@ -84,6 +118,21 @@ pub trait MmrApi<BlockHash> {
 		leaf_index: u64,
 		at: Option<BlockHash>,
 	) -> Result<LeafProof<BlockHash>>;
+
+	/// Generate MMR proof for the given leaf indices.
+	///
+	/// Same as `mmr_generateProof`, but for many leaves at once. The proof is generated
+	/// against the MMR as it was at block `at`.
+	///
+	/// Returns the leaves and a proof for these leaves (compact encoding, i.e. hash of
+	/// the leaves). Both parameters are SCALE-encoded.
+	/// The order of the leaves follows the (sorted) `leaf_indices` of the proof.
+	#[rpc(name = "mmr_generateBatchProof")]
+	fn generate_batch_proof(
+		&self,
+		leaf_indices: Vec<u64>,
+		at: Option<BlockHash>,
+	) -> Result<LeafBatchProof<BlockHash>>;
 }
 
 /// An implementation of MMR specific RPC methods.
@@ -123,17 +172,112 @@ where
 			self.client.info().best_hash
 		);
 
-		let (leaf, proof) = api
-			.generate_proof_with_context(
-				&BlockId::hash(block_hash),
-				sp_core::ExecutionContext::OffchainCall(None),
-				leaf_index,
-			)
+		let (leaf, proof) = match self.historical_query(block_hash)? {
+			HistoricalQuery::Current(at) => api
+				.generate_proof_with_context(
+					&at,
+					sp_core::ExecutionContext::OffchainCall(None),
+					leaf_index,
+				),
+			HistoricalQuery::Historical(at, leaves_count) => api
+				.generate_historical_proof_with_context(
+					&at,
+					sp_core::ExecutionContext::OffchainCall(None),
+					leaf_index,
+					leaves_count,
+				),
+		}
 			.map_err(runtime_error_into_rpc_error)?
 			.map_err(mmr_error_into_rpc_error)?;
 
 		Ok(LeafProof::new(block_hash, leaf, proof))
 	}
+
+	fn generate_batch_proof(
+		&self,
+		leaf_indices: Vec<u64>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<LeafBatchProof<<Block as BlockT>::Hash>> {
+		let api = self.client.runtime_api();
+		let block_hash = at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		);
+
+		let (leaves, proof) = match self.historical_query(block_hash)? {
+			HistoricalQuery::Current(at) => api
+				.generate_batch_proof_with_context(
+					&at,
+					sp_core::ExecutionContext::OffchainCall(None),
+					leaf_indices,
+				),
+			HistoricalQuery::Historical(at, leaves_count) => api
+				.generate_historical_batch_proof_with_context(
+					&at,
+					sp_core::ExecutionContext::OffchainCall(None),
+					leaf_indices,
+					leaves_count,
+				),
+		}
+			.map_err(runtime_error_into_rpc_error)?
+			.map_err(mmr_error_into_rpc_error)?;
+
+		Ok(LeafBatchProof::new(block_hash, leaves, proof))
+	}
+}
+
+/// Describes how the runtime should be queried to generate a proof for a given block.
+enum HistoricalQuery<Block: BlockT> {
+	/// Generate the proof using the state of the block itself.
+	Current(BlockId<Block>),
+	/// Generate the proof at the given (best) block, against the MMR with given number of leaves.
+	Historical(BlockId<Block>, u64),
+}
+
+impl<C, Block, MmrHash> Mmr<C, (Block, MmrHash)>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: MmrRuntimeApi<
+		Block,
+		MmrHash,
+	>,
+	MmrHash: Codec + Send + Sync + 'static,
+{
+	/// Figure out how to generate a proof for the MMR as it was at `block_hash`.
+	///
+	/// Blocks of the best chain are served from the best block, using the number of leaves the
+	/// MMR had at `block_hash`. Other blocks (and runtimes without historical proofs support)
+	/// are queried directly.
+	fn historical_query(&self, block_hash: Block::Hash) -> Result<HistoricalQuery<Block>> {
+		let info = self.client.info();
+		let number = self.client.number(block_hash)
+			.map_err(runtime_error_into_rpc_error)?
+			.ok_or_else(|| Error::invalid_params(format!("Unknown block: {:?}", block_hash)))?;
+		let on_best_chain = self.client.hash(number).ok().flatten() == Some(block_hash);
+		if !on_best_chain || block_hash == info.best_hash {
+			return Ok(HistoricalQuery::Current(BlockId::hash(block_hash)));
+		}
+
+		let best = BlockId::hash(info.best_hash);
+		let api = self.client.runtime_api();
+		let supports_historical = api
+			.has_api_with::<dyn MmrRuntimeApi<Block, MmrHash>, _>(&best, |version| version >= 2)
+			.map_err(runtime_error_into_rpc_error)?;
+		if !supports_historical {
+			return Ok(HistoricalQuery::Current(BlockId::hash(block_hash)));
+		}
+
+		let best_leaves_count = api
+			.mmr_leaf_count(&best)
+			.map_err(runtime_error_into_rpc_error)?
+			.map_err(mmr_error_into_rpc_error)?;
+		let distance: u64 = UniqueSaturatedInto::<u64>::unique_saturated_into(
+			info.best_number.saturating_sub(number)
+		);
+		let leaves_count = best_leaves_count.saturating_sub(distance);
+		Ok(HistoricalQuery::Historical(best, leaves_count))
+	}
 }
 
 const RUNTIME_ERROR: i64 = 8000;
@@ -220,4 +364,26 @@ mod tests {
 		assert_eq!(actual, expected);
 
 	}
+
+	#[test]
+	fn should_serialize_leaf_batch_proof() {
+		// given
+		let leaves = vec![vec![1_u8, 2, 3, 4]];
+		let proof = BatchProof {
+			leaf_indices: vec![1],
+			leaf_count: 9,
+			items: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+		};
+
+		let leaf_batch_proof = LeafBatchProof::new(H256::repeat_byte(0), leaves, proof);
+
+		// when
+		let actual = serde_json::to_string(&leaf_batch_proof).unwrap();
+
+		// then
+		assert_eq!(
+			actual,
+			r#"{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","leaves":"0x041001020304","proof":"0x04010000000000000009000000000000000801010101010101010101010101010101010101010101010101010101010101010202020202020202020202020202020202020202020202020202020202020202"}"#
+		);
+	}
 }
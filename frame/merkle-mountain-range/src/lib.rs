@@ -57,8 +57,8 @@
 //!
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::Encode;
 use frame_support::weights::Weight;
+use sp_std::prelude::*;
 use sp_runtime::traits;
 
 mod default_weights;
@@ -223,9 +223,42 @@ pub fn verify_leaf_proof<H, L>(
 	}
 }
 
+/// Stateless MMR proof verification for batch of leaves.
+///
+/// This function can be used to verify received MMR batch proof (`proof`)
+/// for given leaves set (`leaves`) against a known MMR root hash (`root`).
+/// Note, the leaves should be sorted such that corresponding leaves and leaf indices have the
+/// same position in both the `leaves` vector and the `leaf_indices` vector contained in the
+/// [primitives::BatchProof].
+pub fn verify_leaves_proof<H, L>(
+	root: H::Output,
+	leaves: Vec<mmr::Node<H, L>>,
+	proof: primitives::BatchProof<H::Output>,
+) -> Result<(), primitives::Error> where
+	H: traits::Hash,
+	L: primitives::FullLeaf,
+{
+	let is_valid = mmr::verify_leaves_proof::<H, L>(root, leaves, proof)?;
+	if is_valid {
+		Ok(())
+	} else {
+		Err(primitives::Error::Verify.log_debug(("The proof is incorrect.", root)))
+	}
+}
+
 impl<T: Config<I>, I: 'static> Pallet<T, I> {
-	fn offchain_key(pos: u64) -> sp_std::prelude::Vec<u8> {
-		(T::INDEXING_PREFIX, pos).encode()
+	/// Build canonical offchain key for the node at given position.
+	pub fn node_canon_offchain_key(pos: u64) -> sp_std::prelude::Vec<u8> {
+		primitives::utils::node_canon_offchain_key(T::INDEXING_PREFIX, pos)
+	}
+
+	/// Build the fork-specific offchain key for the node at given position, added by
+	/// a block with given `parent_hash`.
+	pub fn node_temp_offchain_key(
+		pos: u64,
+		parent_hash: <T as frame_system::Config>::Hash,
+	) -> sp_std::prelude::Vec<u8> {
+		primitives::utils::node_temp_offchain_key(T::INDEXING_PREFIX, pos, parent_hash)
 	}
 
 	/// Generate a MMR proof for the given `leaf_index`.
@@ -242,6 +275,56 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		mmr.generate_proof(leaf_index)
 	}
 
+	/// Generate a MMR proof for the given `leaf_index`, against the MMR as it was
+	/// when it contained `leaves_count` leaves.
+	///
+	/// This allows proving leaves against historic MMR roots, using the Off-chain DB
+	/// of a recent block. Same restrictions as for [Self::generate_proof] apply.
+	pub fn generate_historical_proof(leaf_index: u64, leaves_count: u64) -> Result<
+		(LeafOf<T, I>, primitives::Proof<<T as Config<I>>::Hash>),
+		primitives::Error,
+	> {
+		let mmr = Self::historical_mmr(leaves_count)?;
+		mmr.generate_proof(leaf_index)
+	}
+
+	/// Generate a MMR proof for the given `leaf_indices`.
+	///
+	/// Same restrictions as for [Self::generate_proof] apply.
+	/// The returned leaves are sorted by their index, the same way as the `leaf_indices`
+	/// of the proof.
+	pub fn generate_batch_proof(leaf_indices: Vec<u64>) -> Result<
+		(Vec<LeafOf<T, I>>, primitives::BatchProof<<T as Config<I>>::Hash>),
+		primitives::Error,
+	> {
+		let mmr: ModuleMmr<mmr::storage::OffchainStorage, T, I> = mmr::Mmr::new(Self::mmr_leaves());
+		mmr.generate_batch_proof(leaf_indices)
+	}
+
+	/// Generate a MMR proof for the given `leaf_indices`, against the MMR as it was
+	/// when it contained `leaves_count` leaves.
+	///
+	/// Same restrictions as for [Self::generate_proof] apply.
+	pub fn generate_historical_batch_proof(leaf_indices: Vec<u64>, leaves_count: u64) -> Result<
+		(Vec<LeafOf<T, I>>, primitives::BatchProof<<T as Config<I>>::Hash>),
+		primitives::Error,
+	> {
+		let mmr = Self::historical_mmr(leaves_count)?;
+		mmr.generate_batch_proof(leaf_indices)
+	}
+
+	fn historical_mmr(leaves_count: u64) -> Result<
+		ModuleMmr<mmr::storage::OffchainStorage, T, I>,
+		primitives::Error,
+	> {
+		if leaves_count == 0 || leaves_count > Self::mmr_leaves() {
+			return Err(primitives::Error::GenerateProof.log_debug(
+				("Invalid number of leaves.", leaves_count)
+			));
+		}
+		Ok(mmr::Mmr::new(leaves_count))
+	}
+
 	/// Verify MMR proof for given `leaf`.
 	///
 	/// This method is safe to use within the runtime code.
@@ -269,4 +352,32 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			Err(primitives::Error::Verify.log_debug("The proof is incorrect."))
 		}
 	}
+
+	/// Verify MMR proof for given `leaves`.
+	///
+	/// This method is safe to use within the runtime code.
+	/// It will return `Ok(())` if the proof is valid
+	/// and an `Err(..)` if MMR is inconsistent (some leaves are missing)
+	/// or the proof is invalid.
+	pub fn verify_leaves(
+		leaves: Vec<LeafOf<T, I>>,
+		proof: primitives::BatchProof<<T as Config<I>>::Hash>,
+	) -> Result<(), primitives::Error> {
+		if proof.leaf_count > Self::mmr_leaves()
+			|| proof.leaf_count == 0
+			|| proof.items.len() as u64 > mmr::utils::NodesUtils::new(proof.leaf_count).size()
+		{
+			return Err(primitives::Error::Verify.log_debug(
+				"The proof has incorrect number of leaves or proof items."
+			));
+		}
+
+		let mmr: ModuleMmr<mmr::storage::RuntimeStorage, T, I> = mmr::Mmr::new(proof.leaf_count);
+		let is_valid = mmr.verify_leaves_proof(leaves, proof)?;
+		if is_valid {
+			Ok(())
+		} else {
+			Err(primitives::Error::Verify.log_debug("The proof is incorrect."))
+		}
+	}
 }
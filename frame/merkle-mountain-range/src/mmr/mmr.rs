@@ -25,7 +25,7 @@ use crate::{
 	primitives::{self, Error},
 };
 #[cfg(not(feature = "std"))]
-use sp_std::vec;
+use sp_std::{vec, prelude::Vec};
 
 /// Stateless verification of the leaf proof.
 pub fn verify_leaf_proof<H, L>(
@@ -52,6 +52,41 @@ pub fn verify_leaf_proof<H, L>(
 	).map_err(|e| Error::Verify.log_debug(e))
 }
 
+/// Stateless verification of the proof for a batch of leaves.
+///
+/// Note that the leaves must be given in the same order as the `leaf_indices` of the proof.
+pub fn verify_leaves_proof<H, L>(
+	root: H::Output,
+	leaves: Vec<Node<H, L>>,
+	proof: primitives::BatchProof<H::Output>,
+) -> Result<bool, Error> where
+	H: sp_runtime::traits::Hash,
+	L: primitives::FullLeaf,
+{
+	let size = NodesUtils::new(proof.leaf_count).size();
+	if leaves.len() != proof.leaf_indices.len() {
+		return Err(Error::Verify.log_debug("Proof leaf_indices not same length with leaves"));
+	}
+
+	let leaves_and_position_data = proof.leaf_indices
+		.into_iter()
+		.map(mmr_lib::leaf_index_to_pos)
+		.zip(leaves.into_iter())
+		.collect();
+
+	let p = mmr_lib::MerkleProof::<
+		Node<H, L>,
+		Hasher<H, L>,
+	>::new(
+		size,
+		proof.items.into_iter().map(Node::Hash).collect(),
+	);
+	p.verify(
+		Node::Hash(root),
+		leaves_and_position_data,
+	).map_err(|e| Error::Verify.log_debug(e))
+}
+
 /// A wrapper around a MMR library to expose limited functionality.
 ///
 /// Available functions depend on the storage kind ([Runtime](crate::mmr::storage::RuntimeStorage)
@@ -106,6 +141,32 @@ impl<StorageType, T, I, L> Mmr<StorageType, T, I, L> where
 		).map_err(|e| Error::Verify.log_debug(e))
 	}
 
+	/// Verify proof of a batch of leaves.
+	pub fn verify_leaves_proof(
+		&self,
+		leaves: Vec<L>,
+		proof: primitives::BatchProof<<T as Config<I>>::Hash>,
+	) -> Result<bool, Error> {
+		let p = mmr_lib::MerkleProof::<
+			NodeOf<T, I, L>,
+			Hasher<HashingOf<T, I>, L>,
+		>::new(
+			self.mmr.mmr_size(),
+			proof.items.into_iter().map(Node::Hash).collect(),
+		);
+		if leaves.len() != proof.leaf_indices.len() {
+			return Err(Error::Verify.log_debug("Proof leaf_indices not same length with leaves"));
+		}
+		let leaves_positions_and_data = proof.leaf_indices
+			.into_iter()
+			.map(mmr_lib::leaf_index_to_pos)
+			.zip(leaves.into_iter().map(Node::Data))
+			.collect();
+		let root = self.mmr.get_root().map_err(|e| Error::GetRoot.log_error(e))?;
+		p.verify(root, leaves_positions_and_data)
+			.map_err(|e| Error::Verify.log_debug(e))
+	}
+
 	/// Return the internal size of the MMR (number of nodes).
 	#[cfg(test)]
 	pub fn size(&self) -> u64 {
@@ -156,21 +217,54 @@ impl<T, I, L> Mmr<OffchainStorage, T, I, L> where
 		(L, primitives::Proof<<T as Config<I>>::Hash>),
 		Error
 	> {
-		let position = mmr_lib::leaf_index_to_pos(leaf_index);
+		let (mut leaves, proof) = self.generate_batch_proof(vec![leaf_index])?;
+		let leaf = leaves.pop().ok_or(Error::LeafNotFound)?;
+		Ok((leaf, primitives::Proof {
+			leaf_index,
+			leaf_count: proof.leaf_count,
+			items: proof.items,
+		}))
+	}
+
+	/// Generate a proof for given leaf indices.
+	///
+	/// The indices are sorted and deduplicated, the returned leaves
+	/// follow the order of `leaf_indices` in the proof.
+	pub fn generate_batch_proof(&self, mut leaf_indices: Vec<u64>) -> Result<
+		(Vec<L>, primitives::BatchProof<<T as Config<I>>::Hash>),
+		Error
+	> {
+		leaf_indices.sort_unstable();
+		leaf_indices.dedup();
+		if leaf_indices.is_empty() {
+			return Err(Error::GenerateProof.log_debug("No leaves given."));
+		}
+		if leaf_indices.iter().any(|index| *index >= self.leaves) {
+			return Err(Error::LeafNotFound.log_debug(("Leaf index out of range.", self.leaves)));
+		}
+
 		let store = <Storage<OffchainStorage, T, I, L>>::default();
-		let leaf = match mmr_lib::MMRStore::get_elem(&store, position) {
-			Ok(Some(Node::Data(leaf))) => leaf,
-			e => return Err(Error::LeafNotFound.log_debug(e)),
-		};
+		let positions = leaf_indices
+			.iter()
+			.map(|index| mmr_lib::leaf_index_to_pos(*index))
+			.collect::<Vec<_>>();
+		let leaves = positions
+			.iter()
+			.map(|position| match mmr_lib::MMRStore::get_elem(&store, *position) {
+				Ok(Some(Node::Data(leaf))) => Ok(leaf),
+				e => Err(Error::LeafNotFound.log_debug(e)),
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+
 		let leaf_count = self.leaves;
-		self.mmr.gen_proof(vec![position])
+		self.mmr.gen_proof(positions)
 			.map_err(|e| Error::GenerateProof.log_error(e))
-			.map(|p| primitives::Proof {
-				leaf_index,
+			.map(|p| primitives::BatchProof {
+				leaf_indices,
 				leaf_count,
 				items: p.proof_items().iter().map(|x| x.hash()).collect(),
 			})
-			.map(|p| (leaf, p))
+			.map(|p| (leaves, p))
 	}
 }
 
@@ -18,10 +18,11 @@
 //! A MMR storage implementations.
 
 use codec::Encode;
+use sp_runtime::traits::{One, Saturating, UniqueSaturatedInto};
 #[cfg(not(feature = "std"))]
 use sp_std::prelude::Vec;
 
-use crate::mmr::{NodeOf, Node};
+use crate::mmr::{NodeOf, Node, utils::NodesUtils};
 use crate::{NumberOfLeaves, Nodes, Pallet, Config, primitives};
 
 /// A marker type for runtime-specific storage implementation.
@@ -31,6 +32,9 @@ use crate::{NumberOfLeaves, Nodes, Pallet, Config, primitives};
 /// 1. We add nodes (leaves) hashes to the on-chain storge (see [crate::Nodes]).
 /// 2. We add full leaves (and all inner nodes as well) into the `IndexingAPI` during block
 ///    processing, so the values end up in the Offchain DB if indexing is enabled.
+///    The nodes are stored under a fork-specific key (see
+///    [primitives::utils::node_temp_offchain_key]), they are moved to the canonical key once
+///    the block is finalized.
 pub struct RuntimeStorage;
 
 /// A marker type for offchain-specific storage implementation.
@@ -60,11 +64,26 @@ impl<T, I, L> mmr_lib::MMRStore<NodeOf<T, I, L>> for Storage<OffchainStorage, T,
 	L: primitives::FullLeaf + codec::Decode,
 {
 	fn get_elem(&self, pos: u64) -> mmr_lib::Result<Option<NodeOf<T, I, L>>> {
-		let key = Pallet::<T, I>::offchain_key(pos);
-		// Retrieve the element from Off-chain DB.
-		Ok(sp_io::offchain
-			::local_storage_get(sp_core::offchain::StorageKind::PERSISTENT, &key)
-			.and_then(|v| codec::Decode::decode(&mut &*v).ok()))
+		// Try the canonical key first, the node is there if its block has been finalized.
+		let key = Pallet::<T, I>::node_canon_offchain_key(pos);
+		if let Some(elem) = Self::get_offchain(&key) {
+			return Ok(Some(elem));
+		}
+
+		// Otherwise the node has been added by a non-finalized block of the current fork,
+		// find out the parent of that block and look under the fork-specific key.
+		let leaves = NumberOfLeaves::<T, I>::get();
+		let leaf_index = NodesUtils::leaf_index_that_added_node(pos);
+		if leaf_index >= leaves {
+			return Ok(None);
+		}
+		let blocks_ago: T::BlockNumber = (leaves - 1 - leaf_index).unique_saturated_into();
+		let parent_number = <frame_system::Pallet<T>>::block_number()
+			.saturating_sub(blocks_ago)
+			.saturating_sub(One::one());
+		let parent_hash = <frame_system::Pallet<T>>::block_hash(parent_number);
+		let key = Pallet::<T, I>::node_temp_offchain_key(pos, parent_hash);
+		Ok(Self::get_offchain(&key))
 	}
 
 	fn append(&mut self, _: u64, _: Vec<NodeOf<T, I, L>>) -> mmr_lib::Result<()> {
@@ -72,6 +91,18 @@ impl<T, I, L> mmr_lib::MMRStore<NodeOf<T, I, L>> for Storage<OffchainStorage, T,
  	}
 }
 
+impl<T, I, L> Storage<OffchainStorage, T, I, L> where
+	T: Config<I>,
+	I: 'static,
+	L: primitives::FullLeaf + codec::Decode,
+{
+	fn get_offchain(key: &[u8]) -> Option<NodeOf<T, I, L>> {
+		sp_io::offchain
+			::local_storage_get(sp_core::offchain::StorageKind::PERSISTENT, key)
+			.and_then(|v| codec::Decode::decode(&mut &*v).ok())
+	}
+}
+
 impl<T, I, L> mmr_lib::MMRStore<NodeOf<T, I, L>> for Storage<RuntimeStorage, T, I, L> where
 	T: Config<I>,
 	I: 'static,
@@ -85,16 +116,20 @@ impl<T, I, L> mmr_lib::MMRStore<NodeOf<T, I, L>> for Storage<RuntimeStorage, T,
 
 	fn append(&mut self, pos: u64, elems: Vec<NodeOf<T, I, L>>) -> mmr_lib::Result<()> {
 		let mut leaves = crate::NumberOfLeaves::<T, I>::get();
-		let mut size = crate::mmr::utils::NodesUtils::new(leaves).size();
+		let mut size = NodesUtils::new(leaves).size();
 		if pos != size {
 			return Err(mmr_lib::Error::InconsistentStore);
 		}
 
+		// Nodes are stored under a fork-specific key, so that competing forks
+		// don't overwrite each other's nodes in the Off-chain DB.
+		let parent_hash = <frame_system::Pallet<T>>::parent_hash();
+
 		for elem in elems {
 			// on-chain we only store the hash (even if it's a leaf)
 			<Nodes<T, I>>::insert(size, elem.hash());
 			// Indexing API is used to store the full leaf content.
-			let key = Pallet::<T, I>::offchain_key(size);
+			let key = Pallet::<T, I>::node_temp_offchain_key(size, parent_hash);
 			elem.using_encoded(|elem| sp_io::offchain_index::set(&key, elem));
 			size += 1;
 
@@ -17,81 +17,12 @@
 
 //! Merkle Mountain Range utilities.
 
-/// MMR nodes & size -related utilities.
-pub struct NodesUtils {
-	no_of_leaves: u64,
-}
-
-impl NodesUtils {
-	/// Create new instance of MMR nodes utilities for given number of leaves.
-	pub fn new(no_of_leaves: u64) -> Self {
-		Self { no_of_leaves }
-	}
-
-	/// Calculate number of peaks in the MMR.
-	pub fn number_of_peaks(&self) -> u64 {
-		self.number_of_leaves().count_ones() as u64
-	}
-
-	/// Return the number of leaves in the MMR.
-	pub fn number_of_leaves(&self) -> u64 {
-		self.no_of_leaves
-	}
-
-	/// Calculate the total size of MMR (number of nodes).
-	pub fn size(&self) -> u64 {
-		2 * self.no_of_leaves - self.number_of_peaks()
-	}
-
-	/// Calculate maximal depth of the MMR.
-	pub fn depth(&self) -> u32 {
-		if self.no_of_leaves == 0 {
-			return 0
-		}
-
-		64 - self.no_of_leaves
-				.next_power_of_two()
-				.leading_zeros()
-	}
-}
+pub use crate::primitives::utils::NodesUtils;
 
 #[cfg(test)]
 mod tests {
 	use super::*;
 
-	#[test]
-	fn should_calculate_number_of_leaves_correctly() {
-		assert_eq!(
-			vec![0, 1, 2, 3, 4, 9, 15, 21]
-				.into_iter()
-				.map(|n| NodesUtils::new(n).depth())
-				.collect::<Vec<_>>(),
-			vec![0, 1, 2, 3, 3, 5, 5, 6]
-		);
-	}
-
-	#[test]
-	fn should_calculate_depth_correclty() {
-		assert_eq!(
-			vec![0, 1, 2, 3, 4, 9, 15, 21]
-				.into_iter()
-				.map(|n| NodesUtils::new(n).number_of_leaves())
-				.collect::<Vec<_>>(),
-			vec![0, 1, 2, 3, 4, 9, 15, 21]
-		);
-	}
-
-	#[test]
-	fn should_calculate_number_of_peaks_correctly() {
-		assert_eq!(
-			vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 21]
-				.into_iter()
-				.map(|n| NodesUtils::new(n).number_of_peaks())
-				.collect::<Vec<_>>(),
-			vec![0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, 3]
-		);
-	}
-
 	#[test]
 	fn should_calculate_the_size_correctly() {
 		let _ = env_logger::try_init();
@@ -23,10 +23,10 @@ use sp_core::{
 	H256,
 	offchain::{
 		testing::TestOffchainExt,
-		OffchainWorkerExt, OffchainDbExt,
+		OffchainWorkerExt, OffchainDbExt, OffchainStorage,
 	},
 };
-use pallet_mmr_primitives::{Proof, Compact};
+use pallet_mmr_primitives::{Proof, BatchProof, Compact, utils::NodesUtils};
 
 pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
 	frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
@@ -135,21 +135,27 @@ fn should_append_to_mmr_when_on_initialize_is_called() {
 		));
 	});
 
-	// make sure the leaves end up in the offchain DB
+	// make sure the leaves end up in the offchain DB, under the fork-specific keys
 	ext.persist_offchain_overlay();
 	let offchain_db = ext.offchain_db();
-	assert_eq!(offchain_db.get(&MMR::offchain_key(0)).map(decode_node), Some(mmr::Node::Data((
-		(0, H256::repeat_byte(1)),
-		LeafData::new(1),
-	))));
-	assert_eq!(offchain_db.get(&MMR::offchain_key(1)).map(decode_node), Some(mmr::Node::Data((
-		(1, H256::repeat_byte(2)),
-		LeafData::new(2),
-	))));
-	assert_eq!(offchain_db.get(&MMR::offchain_key(2)).map(decode_node), Some(mmr::Node::Hash(
-		hex("672c04a9cd05a644789d769daa552d35d8de7c33129f8a7cbf49e595234c4854")
-	)));
-	assert_eq!(offchain_db.get(&MMR::offchain_key(3)), None);
+	let parent_1 = H256::repeat_byte(1);
+	let parent_2 = H256::repeat_byte(2);
+	assert_eq!(
+		offchain_db.get(&MMR::node_temp_offchain_key(0, parent_1)).map(decode_node),
+		Some(mmr::Node::Data(((0, H256::repeat_byte(1)), LeafData::new(1))))
+	);
+	assert_eq!(
+		offchain_db.get(&MMR::node_temp_offchain_key(1, parent_2)).map(decode_node),
+		Some(mmr::Node::Data(((1, H256::repeat_byte(2)), LeafData::new(2))))
+	);
+	assert_eq!(
+		offchain_db.get(&MMR::node_temp_offchain_key(2, parent_2)).map(decode_node),
+		Some(mmr::Node::Hash(hex("672c04a9cd05a644789d769daa552d35d8de7c33129f8a7cbf49e595234c4854")))
+	);
+	assert_eq!(offchain_db.get(&MMR::node_temp_offchain_key(3, parent_2)), None);
+
+	// nothing is stored under the canonical keys until the gadget canonicalizes them
+	assert_eq!(offchain_db.get(&MMR::node_canon_offchain_key(0)), None);
 }
 
 #[test]
@@ -302,3 +308,150 @@ fn should_verify_on_the_next_block_since_there_is_no_pruning_yet() {
 		assert_eq!(crate::Pallet::<Test>::verify_leaf(leaf, proof5), Ok(()));
 	});
 }
+
+#[test]
+fn should_generate_proofs_from_canonicalized_nodes() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given
+	ext.execute_with(|| init_chain(7));
+	ext.persist_offchain_overlay();
+	let expected = {
+		register_offchain_ext(&mut ext);
+		ext.execute_with(|| crate::Pallet::<Test>::generate_proof(3).unwrap())
+	};
+
+	// when the nodes of the first 5 blocks are moved to their canonical keys
+	let mut offchain_db = ext.offchain_db();
+	for leaf_index in 0..5 {
+		let parent_hash = H256::repeat_byte(leaf_index as u8 + 1);
+		for pos in NodesUtils::nodes_added_by_leaf(leaf_index) {
+			let temp_key = MMR::node_temp_offchain_key(pos, parent_hash);
+			let elem = offchain_db.get(&temp_key).unwrap();
+			offchain_db.set(b"", &MMR::node_canon_offchain_key(pos), &elem);
+			offchain_db.remove(b"", &temp_key);
+		}
+	}
+
+	// then
+	ext.execute_with(|| {
+		assert_eq!(crate::Pallet::<Test>::generate_proof(3), Ok(expected));
+	});
+}
+
+#[test]
+fn should_generate_historical_proofs_correctly() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given
+	ext.execute_with(|| init_chain(7));
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	for leaves_count in 1..=7 {
+		// the proof generated on a shorter chain
+		let mut short_ext = new_test_ext();
+		short_ext.execute_with(|| init_chain(leaves_count as usize));
+		short_ext.persist_offchain_overlay();
+		register_offchain_ext(&mut short_ext);
+
+		for leaf_index in 0..leaves_count {
+			let expected = short_ext.execute_with(|| {
+				crate::Pallet::<Test>::generate_proof(leaf_index).unwrap()
+			});
+			// when
+			let historical = ext.execute_with(|| {
+				crate::Pallet::<Test>::generate_historical_proof(leaf_index, leaves_count).unwrap()
+			});
+			// then
+			assert_eq!(historical, expected);
+		}
+	}
+
+	ext.execute_with(|| {
+		assert_eq!(
+			crate::Pallet::<Test>::generate_historical_proof(0, 8),
+			Err(primitives::Error::GenerateProof),
+		);
+		assert_eq!(
+			crate::Pallet::<Test>::generate_historical_proof(5, 5),
+			Err(primitives::Error::LeafNotFound),
+		);
+	});
+}
+
+#[test]
+fn should_generate_and_verify_batch_proofs_correctly() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given
+	ext.execute_with(|| init_chain(7));
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	let (leaves, proof) = ext.execute_with(|| {
+		// when
+		crate::Pallet::<Test>::generate_batch_proof(vec![6, 0, 4, 0]).unwrap()
+	});
+	let root = ext.execute_with(|| crate::Pallet::<Test>::mmr_root_hash());
+
+	// then
+	assert_eq!(proof.leaf_indices, vec![0, 4, 6]);
+	assert_eq!(proof.leaf_count, 7);
+	assert_eq!(leaves, vec![
+		Compact::new(((0, H256::repeat_byte(1)).into(), LeafData::new(1).into())),
+		Compact::new(((4, H256::repeat_byte(5)).into(), LeafData::new(5).into())),
+		Compact::new(((6, H256::repeat_byte(7)).into(), LeafData::new(7).into())),
+	]);
+
+	ext.execute_with(|| {
+		assert_eq!(crate::Pallet::<Test>::verify_leaves(leaves.clone(), proof.clone()), Ok(()));
+		assert_eq!(
+			crate::Pallet::<Test>::generate_batch_proof(vec![7]),
+			Err(primitives::Error::LeafNotFound),
+		);
+	});
+
+	// the proof does not hold for a different set of leaves
+	let mut wrong_leaves = leaves.clone();
+	wrong_leaves.swap(0, 1);
+	assert_eq!(
+		crate::verify_leaves_proof::<<Test as Config>::Hashing, _>(
+			root,
+			wrong_leaves.into_iter().map(crate::primitives::DataOrHash::Data).collect(),
+			proof.clone(),
+		),
+		Err(primitives::Error::Verify),
+	);
+
+	// but it's verifiable without any on-chain data
+	let leaves = leaves.into_iter().map(crate::primitives::DataOrHash::Data).collect();
+	assert_eq!(
+		crate::verify_leaves_proof::<<Test as Config>::Hashing, _>(root, leaves, proof),
+		Ok(()),
+	);
+}
+
+#[test]
+fn batch_proof_of_a_single_leaf_matches_the_proof() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given
+	ext.execute_with(|| init_chain(7));
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	ext.execute_with(|| {
+		// when
+		let (leaf, proof) = crate::Pallet::<Test>::generate_proof(5).unwrap();
+		let (leaves, batch_proof) = crate::Pallet::<Test>::generate_batch_proof(vec![5]).unwrap();
+
+		// then
+		assert_eq!(leaves, vec![leaf]);
+		assert_eq!(batch_proof, BatchProof {
+			leaf_indices: vec![5],
+			leaf_count: proof.leaf_count,
+			items: proof.items,
+		});
+	});
+}
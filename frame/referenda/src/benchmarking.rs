@@ -0,0 +1,139 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Referenda pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account, whitelist_account, impl_benchmark_test_suite};
+use frame_support::traits::{Currency, EnsureOrigin, UnfilteredDispatchable};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+use crate::Pallet as Referenda;
+
+const SEED: u32 = 0;
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let caller: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	caller
+}
+
+// Submit a referendum from a root origin, returning the index of the new referendum.
+fn create_referendum<T: Config>() -> Result<ReferendumIndex, &'static str> {
+	let caller = funded_account::<T>("caller", 0);
+	let proposal_origin: PalletsOriginOf<T> = RawOrigin::Root.into();
+	let proposal: CallOf<T> = Call::<T>::nudge_referendum(0).into();
+	Referenda::<T>::submit(
+		RawOrigin::Signed(caller).into(),
+		proposal_origin,
+		Box::new(proposal),
+		DispatchTime::After(Zero::zero()),
+	)?;
+	Ok(ReferendumCount::<T>::get() - 1)
+}
+
+fn place_deposit<T: Config>(index: ReferendumIndex) -> Result<(), &'static str> {
+	let caller = funded_account::<T>("depositor", 0);
+	Referenda::<T>::place_decision_deposit(RawOrigin::Signed(caller).into(), index)?;
+	Ok(())
+}
+
+fn status_of<T: Config>(index: ReferendumIndex) -> Result<ReferendumStatusOf<T>, &'static str> {
+	Referenda::<T>::ensure_ongoing(index).map_err(|_| "Referendum is not ongoing")
+}
+
+benchmarks! {
+	submit {
+		let caller = funded_account::<T>("caller", 0);
+		let proposal_origin: PalletsOriginOf<T> = RawOrigin::Root.into();
+		let proposal: CallOf<T> = Call::<T>::nudge_referendum(0).into();
+		whitelist_account!(caller);
+	}: _(
+		RawOrigin::Signed(caller),
+		proposal_origin,
+		Box::new(proposal),
+		DispatchTime::After(Zero::zero())
+	)
+	verify {
+		assert_eq!(ReferendumCount::<T>::get(), 1, "Referendum was not submitted.");
+	}
+
+	place_decision_deposit {
+		let index = create_referendum::<T>()?;
+		let caller = funded_account::<T>("depositor", 0);
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller), index)
+	verify {
+		assert!(status_of::<T>(index)?.decision_deposit.is_some(), "Deposit was not placed.");
+	}
+
+	refund_decision_deposit {
+		let index = create_referendum::<T>()?;
+		place_deposit::<T>(index)?;
+		Call::<T>::cancel(index).dispatch_bypass_filter(T::CancelOrigin::successful_origin())?;
+		let caller = funded_account::<T>("caller", 0);
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller), index)
+	verify {
+		assert!(matches!(
+			ReferendumInfoFor::<T>::get(index),
+			Some(ReferendumInfo::Cancelled(_, None))
+		), "Deposit was not refunded.");
+	}
+
+	cancel {
+		let index = create_referendum::<T>()?;
+		place_deposit::<T>(index)?;
+		let origin = T::CancelOrigin::successful_origin();
+		let call = Call::<T>::cancel(index);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(matches!(ReferendumInfoFor::<T>::get(index), Some(ReferendumInfo::Cancelled(..))));
+	}
+
+	kill {
+		let index = create_referendum::<T>()?;
+		place_deposit::<T>(index)?;
+		let origin = T::KillOrigin::successful_origin();
+		let call = Call::<T>::kill(index);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(matches!(ReferendumInfoFor::<T>::get(index), Some(ReferendumInfo::Killed(..))));
+	}
+
+	nudge_referendum {
+		let index = create_referendum::<T>()?;
+		place_deposit::<T>(index)?;
+		let status = status_of::<T>(index)?;
+		let track = T::Tracks::info(status.track).ok_or("Track does not exist")?;
+		frame_system::Pallet::<T>::set_block_number(status.submitted + track.prepare_period);
+	}: _(RawOrigin::Root, index)
+	verify {
+		let status = status_of::<T>(index)?;
+		assert!(status.deciding.is_some() || status.in_queue, "Referendum was not readied.");
+	}
+}
+
+impl_benchmark_test_suite!(
+	Referenda,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
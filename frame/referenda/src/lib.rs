@@ -0,0 +1,802 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! # Referenda Pallet
+//!
+//! - [`Config`]
+//! - [`Call`]
+//!
+//! ## Overview
+//!
+//! A pallet for executing referenda. No voting logic is present here, and the `Polling` and
+//! `PollStatus` traits are used to allow the voting logic (likely in a pallet) to be utilized.
+//!
+//! A referendum is a vote on whether a proposal should be dispatched from a particular origin.
+//! The origin is used to determine which one of several _tracks_ that a referendum happens
+//! under. Tracks each have their own configuration which governs the voting process and
+//! parameters.
+//!
+//! A referendum's lifecycle has three main stages: Preparation, deciding and conclusion.
+//! Referenda are considered "ongoing" immediately after submission until their eventual
+//! conclusion, and votes may be cast throughout.
+//!
+//! In order to progress from preparation to being decided, three things must be in place:
+//! - There must have been a *Decision Deposit* placed, an amount determined by the track. Anyone
+//!   may place this deposit.
+//! - A period must have elapsed since submission of the referendum. This period is known as the
+//!   *Preparation Period* and is determined by the track.
+//! - The track must not already be at capacity with referendum being decided. The maximum number
+//!   of referenda which may be being decided simultaneously is determined by the track.
+//!
+//! In order to become concluded, one of three things must happen:
+//! - The referendum should remain in an unbroken _Passing_ state for a period of time. This
+//!   is known as the _Confirmation Period_ and is determined by the track. A referendum is
+//!   considered _Passing_ when there is a sufficiently high support and approval, given the
+//!   amount of time it has been being decided. Generally the threshold for what counts as being
+//!   "sufficiently high" will reduce over time. The curves setting these thresholds are
+//!   determined by the track. In this case, the referendum is considered _Approved_ and the
+//!   proposal is scheduled for dispatch.
+//! - The referendum reaches the end of its deciding phase without being _Passing_. It ends in
+//!   rejection and the proposal is not dispatched.
+//! - The referendum is cancelled.
+//!
+//! A referendum which is never decided within the `UndecidingTimeout` of its submission times
+//! out, and its submission deposit is slashed.
+//!
+//! Once a referendum is concluded, the decision deposit may be refunded.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `submit` - Submit a referendum.
+//! - `place_decision_deposit` - Place the decision deposit of a referendum.
+//! - `refund_decision_deposit` - Refund the decision deposit of a concluded referendum.
+//! - `cancel` - Cancel an ongoing referendum, refunding its deposits.
+//! - `kill` - Cancel an ongoing referendum, slashing its deposits.
+//! - `nudge_referendum` - Advance a referendum onto its next logical state; only used by the
+//!   scheduler.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Encode, HasCompact};
+use frame_support::{
+	ensure, BoundedVec,
+	traits::{
+		Currency, Get, OnUnbalanced, PollStatus, Polling, ReservableCurrency, VoteTally,
+		schedule::{DispatchTime, Named as ScheduleNamed},
+	},
+	dispatch::{DispatchError, DispatchResult},
+};
+use sp_runtime::{
+	Perbill,
+	traits::{AtLeast32BitUnsigned, Dispatchable, One, Saturating, Zero},
+};
+use sp_std::prelude::*;
+
+mod types;
+pub mod weights;
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub use pallet::*;
+pub use types::{
+	BalanceOf, CallOf, Curve, DecidingStatus, Deposit, NegativeImbalanceOf, PalletsOriginOf,
+	ReferendumIndex, ReferendumInfo, ReferendumInfoOf, ReferendumStatus, ReferendumStatusOf,
+	TallyOf, TrackIdOf, TrackInfo, TrackInfoOf, TracksInfo, VotesOf,
+};
+pub use weights::WeightInfo;
+
+/// The prefix of the names of the tasks this pallet registers with the scheduler.
+const REFERENDA_ID: [u8; 8] = *b"referend";
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{pallet_prelude::*, traits::EnsureOrigin};
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + Sized {
+		/// The proposals which may be dispatched by referenda.
+		type Proposal: Parameter + Dispatchable<Origin = Self::Origin> + From<Call<Self>>;
+
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+
+		/// The Scheduler.
+		type Scheduler: ScheduleNamed<Self::BlockNumber, CallOf<Self>, PalletsOriginOf<Self>>;
+
+		/// Overarching type of all pallets origins.
+		type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>> + Parameter;
+
+		/// Currency type for this pallet.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Origin from which any vote may be cancelled.
+		type CancelOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Origin from which any vote may be killed.
+		type KillOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Handler for the unbalanced reduction when slashing a preimage deposit.
+		type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// The counting type for votes. Usually just balance.
+		type Votes: AtLeast32BitUnsigned + Copy + Parameter + HasCompact;
+
+		/// The tallying type.
+		type Tally: VoteTally<Self::Votes> + Default + Parameter;
+
+		/// The minimum amount to be used as a deposit for a public referendum proposal.
+		#[pallet::constant]
+		type SubmissionDeposit: Get<BalanceOf<Self>>;
+
+		/// Maximum size of the referendum queue for a single track.
+		#[pallet::constant]
+		type MaxQueued: Get<u32>;
+
+		/// The number of blocks after submission that a referendum must begin being decided by.
+		/// Once this passes, then anyone may cancel the referendum.
+		#[pallet::constant]
+		type UndecidingTimeout: Get<Self::BlockNumber>;
+
+		/// Information concerning the different referendum tracks.
+		type Tracks: TracksInfo<BalanceOf<Self>, Self::BlockNumber, Origin = Self::PalletsOrigin>;
+	}
+
+	/// The next free referendum index, aka the number of referenda started so far.
+	#[pallet::storage]
+	pub type ReferendumCount<T> = StorageValue<_, ReferendumIndex, ValueQuery>;
+
+	/// Information concerning any given referendum.
+	#[pallet::storage]
+	pub type ReferendumInfoFor<T: Config> =
+		StorageMap<_, Blake2_128Concat, ReferendumIndex, ReferendumInfoOf<T>>;
+
+	/// The sorted list of referenda ready to be decided but not yet being decided, ordered by
+	/// the aye votes they had when queued. The last item has the most votes.
+	///
+	/// This should be empty if `DecidingCount` is less than `TrackInfo::max_deciding`.
+	#[pallet::storage]
+	pub type TrackQueue<T: Config> = StorageMap<
+		_,
+		Twox64Concat, TrackIdOf<T>,
+		BoundedVec<(ReferendumIndex, T::Votes), T::MaxQueued>,
+		ValueQuery,
+	>;
+
+	/// The number of referenda being decided currently.
+	#[pallet::storage]
+	pub type DecidingCount<T: Config> = StorageMap<_, Twox64Concat, TrackIdOf<T>, u32, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(
+		T::AccountId = "AccountId", BalanceOf<T> = "Balance", TrackIdOf<T> = "TrackId", T::Tally = "Tally"
+	)]
+	pub enum Event<T: Config> {
+		/// A referendum has been submitted. \[index, track\]
+		Submitted(ReferendumIndex, TrackIdOf<T>),
+		/// The decision deposit has been placed. \[index, who, amount\]
+		DecisionDepositPlaced(ReferendumIndex, T::AccountId, BalanceOf<T>),
+		/// The decision deposit has been refunded. \[index, who, amount\]
+		DecisionDepositRefunded(ReferendumIndex, T::AccountId, BalanceOf<T>),
+		/// A referendum has moved into the deciding phase. \[index, track, tally\]
+		DecisionStarted(ReferendumIndex, TrackIdOf<T>, T::Tally),
+		/// A referendum has entered its confirmation period. \[index\]
+		ConfirmStarted(ReferendumIndex),
+		/// A referendum has left its confirmation period. \[index\]
+		ConfirmAborted(ReferendumIndex),
+		/// A referendum has ended its confirmation phase and is ready for approval. \[index\]
+		Approved(ReferendumIndex),
+		/// A referendum has been rejected. \[index, tally\]
+		Rejected(ReferendumIndex, T::Tally),
+		/// A referendum has been timed out without being decided. \[index, tally\]
+		TimedOut(ReferendumIndex, T::Tally),
+		/// A referendum has been cancelled. \[index, tally\]
+		Cancelled(ReferendumIndex, T::Tally),
+		/// A referendum has been killed. \[index, tally\]
+		Killed(ReferendumIndex, T::Tally),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Referendum is not ongoing.
+		NotOngoing,
+		/// Referendum's decision deposit is already paid.
+		HasDeposit,
+		/// The referendum index provided is invalid in this context.
+		BadReferendum,
+		/// No track exists for the proposal origin.
+		NoTrack,
+		/// Any deposit cannot be refunded until after the decision is over.
+		Unfinished,
+		/// The deposit cannot be refunded since none was made.
+		NoDeposit,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Propose a referendum on a privileged action.
+		///
+		/// - `origin`: must be `Signed` and the account must have `SubmissionDeposit` funds
+		///   available.
+		/// - `proposal_origin`: The origin from which the proposal should be executed.
+		/// - `proposal`: The proposal.
+		/// - `enactment_moment`: The moment that the proposal should be enacted.
+		///
+		/// Emits `Submitted`.
+		#[pallet::weight(T::WeightInfo::submit())]
+		pub fn submit(
+			origin: OriginFor<T>,
+			proposal_origin: PalletsOriginOf<T>,
+			proposal: Box<CallOf<T>>,
+			enactment_moment: DispatchTime<T::BlockNumber>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let track = T::Tracks::track_for(&proposal_origin).map_err(|_| Error::<T>::NoTrack)?;
+			let submission_deposit = Self::take_deposit(who, T::SubmissionDeposit::get())?;
+			let index = ReferendumCount::<T>::mutate(|x| {
+				let r = *x;
+				*x += 1;
+				r
+			});
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut status = ReferendumStatus {
+				track,
+				origin: proposal_origin,
+				proposal: *proposal,
+				enactment: enactment_moment,
+				submitted: now,
+				submission_deposit,
+				decision_deposit: None,
+				deciding: None,
+				tally: Default::default(),
+				in_queue: false,
+				alarm: None,
+			};
+			Self::set_alarm(index, &mut status, Some(now.saturating_add(T::UndecidingTimeout::get())));
+			ReferendumInfoFor::<T>::insert(index, ReferendumInfo::Ongoing(status));
+
+			Self::deposit_event(Event::<T>::Submitted(index, track));
+			Ok(())
+		}
+
+		/// Post the Decision Deposit for a referendum.
+		///
+		/// - `origin`: must be `Signed` and the account must have funds available for the
+		///   referendum's track's Decision Deposit.
+		/// - `index`: The index of the submitted referendum whose Decision Deposit is yet to be
+		///   posted.
+		///
+		/// Emits `DecisionDepositPlaced`.
+		#[pallet::weight(T::WeightInfo::place_decision_deposit())]
+		pub fn place_decision_deposit(
+			origin: OriginFor<T>,
+			index: ReferendumIndex,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut status = Self::ensure_ongoing(index)?;
+			ensure!(status.decision_deposit.is_none(), Error::<T>::HasDeposit);
+			let track = Self::track(status.track).ok_or(Error::<T>::NoTrack)?;
+			status.decision_deposit = Some(Self::take_deposit(who.clone(), track.decision_deposit)?);
+			Self::deposit_event(Event::<T>::DecisionDepositPlaced(index, who, track.decision_deposit));
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let (info, _) = Self::service_referendum(now, index, status);
+			ReferendumInfoFor::<T>::insert(index, info);
+			Ok(())
+		}
+
+		/// Refund the Decision Deposit for a closed referendum back to the depositor.
+		///
+		/// - `origin`: must be `Signed`.
+		/// - `index`: The index of a closed referendum whose Decision Deposit has not yet been
+		///   refunded.
+		///
+		/// Emits `DecisionDepositRefunded`.
+		#[pallet::weight(T::WeightInfo::refund_decision_deposit())]
+		pub fn refund_decision_deposit(
+			origin: OriginFor<T>,
+			index: ReferendumIndex,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let mut info = ReferendumInfoFor::<T>::get(index).ok_or(Error::<T>::BadReferendum)?;
+			let deposit = info
+				.take_decision_deposit()
+				.map_err(|_| Error::<T>::Unfinished)?
+				.ok_or(Error::<T>::NoDeposit)?;
+			T::Currency::unreserve(&deposit.who, deposit.amount);
+			ReferendumInfoFor::<T>::insert(index, info);
+
+			Self::deposit_event(Event::<T>::DecisionDepositRefunded(index, deposit.who, deposit.amount));
+			Ok(())
+		}
+
+		/// Cancel an ongoing referendum, refunding its deposits.
+		///
+		/// - `origin`: must be the `CancelOrigin`.
+		/// - `index`: The index of the referendum to be cancelled.
+		///
+		/// Emits `Cancelled`.
+		#[pallet::weight(T::WeightInfo::cancel())]
+		pub fn cancel(origin: OriginFor<T>, index: ReferendumIndex) -> DispatchResult {
+			T::CancelOrigin::ensure_origin(origin)?;
+			let status = Self::ensure_ongoing(index)?;
+			let status = Self::end_ongoing(index, status);
+			let now = frame_system::Pallet::<T>::block_number();
+
+			Self::deposit_event(Event::<T>::Cancelled(index, status.tally));
+			T::Currency::unreserve(&status.submission_deposit.who, status.submission_deposit.amount);
+			ReferendumInfoFor::<T>::insert(index, ReferendumInfo::Cancelled(now, status.decision_deposit));
+			Ok(())
+		}
+
+		/// Cancel an ongoing referendum and slash the deposits.
+		///
+		/// - `origin`: must be the `KillOrigin`.
+		/// - `index`: The index of the referendum to be cancelled.
+		///
+		/// Emits `Killed`.
+		#[pallet::weight(T::WeightInfo::kill())]
+		pub fn kill(origin: OriginFor<T>, index: ReferendumIndex) -> DispatchResult {
+			T::KillOrigin::ensure_origin(origin)?;
+			let status = Self::ensure_ongoing(index)?;
+			let status = Self::end_ongoing(index, status);
+			let now = frame_system::Pallet::<T>::block_number();
+
+			Self::deposit_event(Event::<T>::Killed(index, status.tally));
+			Self::slash_deposit(Some(status.submission_deposit));
+			Self::slash_deposit(status.decision_deposit);
+			ReferendumInfoFor::<T>::insert(index, ReferendumInfo::Killed(now));
+			Ok(())
+		}
+
+		/// Advance a referendum onto its next logical state. Only used internally.
+		///
+		/// - `origin`: must be `Root`.
+		/// - `index`: the referendum to be advanced.
+		#[pallet::weight(T::WeightInfo::nudge_referendum())]
+		pub fn nudge_referendum(origin: OriginFor<T>, index: ReferendumIndex) -> DispatchResult {
+			ensure_root(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut status = Self::ensure_ongoing(index)?;
+			// This is our wake-up, so we can disregard the alarm.
+			status.alarm = None;
+			let (info, dirty) = Self::service_referendum(now, index, status);
+			if dirty {
+				ReferendumInfoFor::<T>::insert(index, info);
+			}
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Polling<T::Tally> for Pallet<T> {
+	type Index = ReferendumIndex;
+	type Votes = VotesOf<T>;
+	type Class = TrackIdOf<T>;
+	type Moment = T::BlockNumber;
+
+	fn classes() -> Vec<Self::Class> {
+		T::Tracks::tracks().iter().map(|x| x.0).collect()
+	}
+
+	fn access_poll<R>(
+		index: Self::Index,
+		f: impl FnOnce(PollStatus<&mut T::Tally, T::BlockNumber, TrackIdOf<T>>) -> R,
+	) -> R {
+		match ReferendumInfoFor::<T>::get(index) {
+			Some(ReferendumInfo::Ongoing(mut status)) => {
+				let result = f(PollStatus::Ongoing(&mut status.tally, status.track));
+				Self::note_tally_changed(index, &mut status);
+				ReferendumInfoFor::<T>::insert(index, ReferendumInfo::Ongoing(status));
+				result
+			},
+			Some(ReferendumInfo::Approved(end, ..)) => f(PollStatus::Completed(end, true)),
+			Some(ReferendumInfo::Rejected(end, ..)) => f(PollStatus::Completed(end, false)),
+			_ => f(PollStatus::None),
+		}
+	}
+
+	fn try_access_poll<R>(
+		index: Self::Index,
+		f: impl FnOnce(
+			PollStatus<&mut T::Tally, T::BlockNumber, TrackIdOf<T>>,
+		) -> Result<R, DispatchError>,
+	) -> Result<R, DispatchError> {
+		match ReferendumInfoFor::<T>::get(index) {
+			Some(ReferendumInfo::Ongoing(mut status)) => {
+				let result = f(PollStatus::Ongoing(&mut status.tally, status.track))?;
+				Self::note_tally_changed(index, &mut status);
+				ReferendumInfoFor::<T>::insert(index, ReferendumInfo::Ongoing(status));
+				Ok(result)
+			},
+			Some(ReferendumInfo::Approved(end, ..)) => f(PollStatus::Completed(end, true)),
+			Some(ReferendumInfo::Rejected(end, ..)) => f(PollStatus::Completed(end, false)),
+			_ => f(PollStatus::None),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_ongoing(class: Self::Class) -> Result<Self::Index, ()> {
+		let index = ReferendumCount::<T>::mutate(|x| {
+			let r = *x;
+			*x += 1;
+			r
+		});
+		let now = frame_system::Pallet::<T>::block_number();
+		let status = ReferendumStatusOf::<T> {
+			track: class,
+			origin: frame_system::RawOrigin::Root.into(),
+			proposal: Call::<T>::nudge_referendum(index).into(),
+			enactment: DispatchTime::After(Zero::zero()),
+			submitted: now,
+			submission_deposit: Deposit { who: Default::default(), amount: Zero::zero() },
+			decision_deposit: None,
+			deciding: None,
+			tally: Default::default(),
+			in_queue: false,
+			alarm: None,
+		};
+		ReferendumInfoFor::<T>::insert(index, ReferendumInfo::Ongoing(status));
+		Ok(index)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn end_ongoing(index: Self::Index, approved: bool) -> Result<(), ()> {
+		let status = Self::ensure_ongoing(index).map_err(|_| ())?;
+		let status = Self::end_ongoing(index, status);
+		let now = frame_system::Pallet::<T>::block_number();
+		let info = if approved {
+			ReferendumInfo::Approved(now, status.decision_deposit)
+		} else {
+			ReferendumInfo::Rejected(now, status.decision_deposit)
+		};
+		ReferendumInfoFor::<T>::insert(index, info);
+		Ok(())
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Check that referendum `index` is in the `Ongoing` state and return the `ReferendumStatus`
+	/// value, or `Err` otherwise.
+	pub fn ensure_ongoing(index: ReferendumIndex) -> Result<ReferendumStatusOf<T>, DispatchError> {
+		match ReferendumInfoFor::<T>::get(index) {
+			Some(ReferendumInfo::Ongoing(status)) => Ok(status),
+			_ => Err(Error::<T>::NotOngoing.into()),
+		}
+	}
+
+	/// Return the information of track `id`, if it exists.
+	fn track(id: TrackIdOf<T>) -> Option<&'static TrackInfoOf<T>> {
+		T::Tracks::info(id)
+	}
+
+	/// Schedule the enactment of an approved referendum, no sooner than the minimum enactment
+	/// period of its track.
+	fn schedule_enactment(
+		index: ReferendumIndex,
+		track: &TrackInfoOf<T>,
+		desired: DispatchTime<T::BlockNumber>,
+		origin: PalletsOriginOf<T>,
+		call: CallOf<T>,
+	) {
+		let now = frame_system::Pallet::<T>::block_number();
+		let earliest_allowed = now.saturating_add(track.min_enactment_period.max(One::one()));
+		let desired = match desired {
+			DispatchTime::At(x) => x,
+			DispatchTime::After(x) => now.saturating_add(x),
+		};
+		let when = desired.max(earliest_allowed);
+		// The task name is unique to the referendum and the dispatch time is in the future, so
+		// this cannot fail.
+		let ok = T::Scheduler::schedule_named(
+			(REFERENDA_ID, "enactment", index).encode(),
+			DispatchTime::At(when),
+			None,
+			63,
+			origin,
+			call,
+		).is_ok();
+		debug_assert!(ok, "LOGIC ERROR: schedule_enactment/schedule_named failed");
+	}
+
+	/// Set the alarm of referendum `index` to `when`, cancelling the previous alarm if there is
+	/// one. Returns `true` if the alarm changed.
+	///
+	/// The alarm dispatches `nudge_referendum` no sooner than the next block.
+	fn set_alarm(
+		index: ReferendumIndex,
+		status: &mut ReferendumStatusOf<T>,
+		when: Option<T::BlockNumber>,
+	) -> bool {
+		let next_block = frame_system::Pallet::<T>::block_number().saturating_add(One::one());
+		let when = when.map(|x| x.max(next_block));
+		if status.alarm == when {
+			return false
+		}
+		// Alarms are named after their block, so that an alarm may be set again while the
+		// previous one is being dispatched.
+		if let Some(old) = status.alarm.take() {
+			let _ = T::Scheduler::cancel_named((REFERENDA_ID, "alarm", index, old).encode());
+		}
+		status.alarm = when.filter(|&when| {
+			let call = Call::<T>::nudge_referendum(index).into();
+			T::Scheduler::schedule_named(
+				(REFERENDA_ID, "alarm", index, when).encode(),
+				DispatchTime::At(when),
+				None,
+				128u8,
+				frame_system::RawOrigin::Root.into(),
+				call,
+			).is_ok()
+		});
+		true
+	}
+
+	/// Make sure a deciding referendum is serviced in the next block after its tally changed.
+	fn note_tally_changed(index: ReferendumIndex, status: &mut ReferendumStatusOf<T>) {
+		if status.deciding.is_some() {
+			let next_block = frame_system::Pallet::<T>::block_number().saturating_add(One::one());
+			if status.alarm.map_or(true, |alarm| alarm > next_block) {
+				Self::set_alarm(index, status, Some(next_block));
+			}
+		}
+	}
+
+	/// Begin deciding referendum `index`.
+	fn begin_deciding(index: ReferendumIndex, status: &mut ReferendumStatusOf<T>, now: T::BlockNumber) {
+		status.in_queue = false;
+		status.deciding = Some(DecidingStatus { since: now, confirming: None });
+		Self::deposit_event(Event::<T>::DecisionStarted(index, status.track, status.tally.clone()));
+	}
+
+	/// Begin deciding referendum `index` if its track has room for it, otherwise put it in the
+	/// queue of its track.
+	///
+	/// If the queue is full, the referendum with the fewest votes is left out of it.
+	fn ready_for_deciding(
+		now: T::BlockNumber,
+		track: &TrackInfoOf<T>,
+		index: ReferendumIndex,
+		status: &mut ReferendumStatusOf<T>,
+	) {
+		let deciding_count = DecidingCount::<T>::get(status.track);
+		if deciding_count < track.max_deciding {
+			DecidingCount::<T>::insert(status.track, deciding_count.saturating_add(1));
+			Self::begin_deciding(index, status, now);
+			return
+		}
+
+		let item = (index, status.tally.ayes());
+		let left_out = TrackQueue::<T>::mutate(status.track, |q| {
+			let pos = q.binary_search_by_key(&item.1, |x| x.1).unwrap_or_else(|x| x);
+			if q.len() < T::MaxQueued::get() as usize {
+				let _ = q.try_insert(pos, item);
+				None
+			} else if pos == 0 {
+				Some(index)
+			} else {
+				let lowest = q.remove(0).0;
+				let _ = q.try_insert(pos - 1, item);
+				Some(lowest)
+			}
+		});
+		status.in_queue = left_out != Some(index);
+		if let Some(lowest) = left_out.filter(|&x| x != index) {
+			ReferendumInfoFor::<T>::mutate(lowest, |info| {
+				if let Some(ReferendumInfo::Ongoing(s)) = info {
+					s.in_queue = false;
+					let timeout = s.submitted.saturating_add(T::UndecidingTimeout::get());
+					Self::set_alarm(lowest, s, Some(timeout));
+				}
+			});
+		}
+	}
+
+	/// Note that a referendum of track `id` is no longer being decided, and let the best queued
+	/// referendum of the track, if any, take its place.
+	fn note_one_fewer_deciding(id: TrackIdOf<T>) {
+		let mut queue = TrackQueue::<T>::get(id);
+		if queue.is_empty() {
+			DecidingCount::<T>::mutate(id, |x| *x = x.saturating_sub(1));
+			return
+		}
+		let (index, _) = queue.remove(queue.len() - 1);
+		TrackQueue::<T>::insert(id, queue);
+		let now = frame_system::Pallet::<T>::block_number();
+		ReferendumInfoFor::<T>::mutate(index, |info| {
+			if let Some(ReferendumInfo::Ongoing(status)) = info {
+				Self::begin_deciding(index, status, now);
+				// Check whether it passes in the next block.
+				Self::set_alarm(index, status, Some(now.saturating_add(One::one())));
+			}
+		});
+	}
+
+	/// Take a referendum out of the ongoing state: cancel its alarm and free its place in the
+	/// deciding set or in the queue of its track.
+	fn end_ongoing(index: ReferendumIndex, mut status: ReferendumStatusOf<T>) -> ReferendumStatusOf<T> {
+		Self::set_alarm(index, &mut status, None);
+		if status.deciding.is_some() {
+			Self::note_one_fewer_deciding(status.track);
+		} else if status.in_queue {
+			TrackQueue::<T>::mutate(status.track, |q| q.retain(|x| x.0 != index));
+		}
+		status
+	}
+
+	/// Advance the state of a referendum, which comes down to:
+	/// - If it's ready to be decided, start deciding;
+	/// - If it's not ready to be decided and the undeciding timeout has passed, time it out;
+	/// - If it's deciding and passing, ensure confirming; if at end of confirmation period, pass.
+	/// - If it's deciding and not passing, stop confirming; if it has reached end time, fail.
+	///
+	/// Returns the new information of the referendum, and whether it changed.
+	fn service_referendum(
+		now: T::BlockNumber,
+		index: ReferendumIndex,
+		mut status: ReferendumStatusOf<T>,
+	) -> (ReferendumInfoOf<T>, bool) {
+		let mut dirty = false;
+		// Should it begin being decided?
+		let track = match Self::track(status.track) {
+			Some(x) => x,
+			None => return (ReferendumInfo::Ongoing(status), false),
+		};
+		let timeout = status.submitted.saturating_add(T::UndecidingTimeout::get());
+		let mut alarm = Some(timeout);
+		if status.deciding.is_none() && !status.in_queue {
+			if status.decision_deposit.is_some() {
+				let prepare_end = status.submitted.saturating_add(track.prepare_period);
+				if now >= prepare_end {
+					Self::ready_for_deciding(now, track, index, &mut status);
+					dirty = true;
+				} else {
+					alarm = Some(prepare_end.min(timeout));
+				}
+			}
+			if status.deciding.is_none() && !status.in_queue && now >= timeout {
+				// Too long without being decided - end it.
+				let status = Self::end_ongoing(index, status);
+				Self::deposit_event(Event::<T>::TimedOut(index, status.tally));
+				Self::slash_deposit(Some(status.submission_deposit));
+				return (ReferendumInfo::TimedOut(now, status.decision_deposit), true)
+			}
+		}
+		if status.in_queue {
+			// Waiting for a deciding slot of the track.
+			alarm = None;
+		}
+
+		if let Some(deciding) = &mut status.deciding {
+			let is_passing = Self::is_passing(
+				&status.tally,
+				now.saturating_sub(deciding.since),
+				track.decision_period,
+				&track.min_support,
+				&track.min_approval,
+			);
+			if is_passing {
+				let confirming = deciding.confirming;
+				match confirming {
+					Some(confirm_end) if now >= confirm_end => {
+						// Passed!
+						let status = Self::end_ongoing(index, status);
+						Self::deposit_event(Event::<T>::Approved(index));
+						T::Currency::unreserve(
+							&status.submission_deposit.who,
+							status.submission_deposit.amount,
+						);
+						Self::schedule_enactment(
+							index,
+							track,
+							status.enactment,
+							status.origin,
+							status.proposal,
+						);
+						return (ReferendumInfo::Approved(now, status.decision_deposit), true)
+					},
+					Some(confirm_end) => alarm = Some(confirm_end),
+					None => {
+						// Start confirming.
+						let confirm_end = now.saturating_add(track.confirm_period);
+						deciding.confirming = Some(confirm_end);
+						alarm = Some(confirm_end);
+						dirty = true;
+						Self::deposit_event(Event::<T>::ConfirmStarted(index));
+					},
+				}
+			} else {
+				let decision_end = deciding.since.saturating_add(track.decision_period);
+				if now >= decision_end {
+					// Failed!
+					let status = Self::end_ongoing(index, status);
+					Self::deposit_event(Event::<T>::Rejected(index, status.tally));
+					T::Currency::unreserve(
+						&status.submission_deposit.who,
+						status.submission_deposit.amount,
+					);
+					return (ReferendumInfo::Rejected(now, status.decision_deposit), true)
+				}
+				if deciding.confirming.take().is_some() {
+					// Stop confirming.
+					dirty = true;
+					Self::deposit_event(Event::<T>::ConfirmAborted(index));
+				}
+				// Wake up once the thresholds have come down to the current tally.
+				let delay = track.min_support.delay(status.tally.support())
+					.max(track.min_approval.delay(status.tally.approval()));
+				let passing_from = deciding.since.saturating_add(delay.mul_ceil(track.decision_period));
+				alarm = Some(passing_from.min(decision_end));
+			}
+		}
+
+		dirty = Self::set_alarm(index, &mut status, alarm) || dirty;
+		(ReferendumInfo::Ongoing(status), dirty)
+	}
+
+	/// Determine whether the given `tally` passes the support and approval curves of a track,
+	/// `elapsed` into a decision `period`.
+	fn is_passing(
+		tally: &T::Tally,
+		elapsed: T::BlockNumber,
+		period: T::BlockNumber,
+		support_needed: &Curve,
+		approval_needed: &Curve,
+	) -> bool {
+		let x = if period.is_zero() {
+			Perbill::one()
+		} else {
+			Perbill::from_rational(elapsed.min(period), period)
+		};
+		support_needed.passing(x, tally.support()) && approval_needed.passing(x, tally.approval())
+	}
+
+	/// Reserve a deposit of `amount` from `who`.
+	fn take_deposit(
+		who: T::AccountId,
+		amount: BalanceOf<T>,
+	) -> Result<Deposit<T::AccountId, BalanceOf<T>>, DispatchError> {
+		T::Currency::reserve(&who, amount)?;
+		Ok(Deposit { who, amount })
+	}
+
+	/// Slash a deposit, if `Some`.
+	fn slash_deposit(deposit: Option<Deposit<T::AccountId, BalanceOf<T>>>) {
+		if let Some(Deposit { who, amount }) = deposit {
+			T::Slash::on_unbalanced(T::Currency::slash_reserved(&who, amount).0);
+		}
+	}
+}
@@ -0,0 +1,289 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Test environment for the referenda pallet.
+
+use super::*;
+use crate as pallet_referenda;
+use codec::{Encode, Decode};
+use frame_support::{
+	assert_ok, ord_parameter_types, parameter_types,
+	traits::{OnInitialize, VoteTally},
+	weights::Weight,
+};
+use frame_system::{EnsureRoot, EnsureSignedBy};
+use sp_core::H256;
+use sp_runtime::{
+	Perbill, RuntimeDebug,
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+use sp_std::convert::TryFrom;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Config, Event<T>},
+		Referenda: pallet_referenda::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub BlockWeights: frame_system::limits::BlockWeights =
+		frame_system::limits::BlockWeights::simple_max(2_000_000_000_000);
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::AllowAll;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+}
+impl pallet_scheduler::Config for Test {
+	type Event = Event;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<u64>;
+	type MaxScheduledPerBlock = ();
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxLocks: u32 = 10;
+}
+impl pallet_balances::Config for Test {
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type MaxLocks = MaxLocks;
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const SubmissionDeposit: u64 = 2;
+	pub const MaxQueued: u32 = 2;
+	pub const UndecidingTimeout: u64 = 20;
+}
+ord_parameter_types! {
+	pub const Four: u64 = 4;
+}
+
+/// A tally where the support is the number of aye votes as a percentage.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Tally {
+	pub ayes: u32,
+	pub nays: u32,
+}
+
+impl VoteTally<u32> for Tally {
+	fn ayes(&self) -> u32 {
+		self.ayes
+	}
+
+	fn support(&self) -> Perbill {
+		Perbill::from_percent(self.ayes)
+	}
+
+	fn approval(&self) -> Perbill {
+		if self.ayes + self.nays == 0 {
+			return Perbill::zero()
+		}
+		Perbill::from_rational(self.ayes, self.ayes + self.nays)
+	}
+}
+
+pub struct TestTracksInfo;
+impl TracksInfo<u64, u64> for TestTracksInfo {
+	type Id = u8;
+	type Origin = OriginCaller;
+
+	fn tracks() -> &'static [(Self::Id, TrackInfo<u64, u64>)] {
+		static DATA: [(u8, TrackInfo<u64, u64>); 2] = [
+			(
+				0u8,
+				TrackInfo {
+					name: "root",
+					max_deciding: 1,
+					decision_deposit: 10,
+					prepare_period: 4,
+					decision_period: 4,
+					confirm_period: 2,
+					min_enactment_period: 4,
+					min_approval: Curve::LinearDecreasing {
+						begin: Perbill::from_percent(100),
+						delta: Perbill::from_percent(50),
+					},
+					min_support: Curve::LinearDecreasing {
+						begin: Perbill::from_percent(100),
+						delta: Perbill::from_percent(100),
+					},
+				},
+			),
+			(
+				1u8,
+				TrackInfo {
+					name: "none",
+					max_deciding: 3,
+					decision_deposit: 1,
+					prepare_period: 2,
+					decision_period: 2,
+					confirm_period: 1,
+					min_enactment_period: 2,
+					min_approval: Curve::LinearDecreasing {
+						begin: Perbill::from_percent(95),
+						delta: Perbill::from_percent(90),
+					},
+					min_support: Curve::LinearDecreasing {
+						begin: Perbill::from_percent(90),
+						delta: Perbill::from_percent(89),
+					},
+				},
+			),
+		];
+		&DATA[..]
+	}
+
+	fn track_for(id: &Self::Origin) -> Result<Self::Id, ()> {
+		match frame_system::RawOrigin::try_from(id.clone()) {
+			Ok(frame_system::RawOrigin::Root) => Ok(0),
+			Ok(frame_system::RawOrigin::None) => Ok(1),
+			_ => Err(()),
+		}
+	}
+}
+
+impl Config for Test {
+	type Proposal = Call;
+	type Event = Event;
+	type WeightInfo = ();
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type Currency = pallet_balances::Pallet<Self>;
+	type CancelOrigin = EnsureRoot<u64>;
+	type KillOrigin = EnsureSignedBy<Four, u64>;
+	type Slash = ();
+	type Votes = u32;
+	type Tally = Tally;
+	type SubmissionDeposit = SubmissionDeposit;
+	type MaxQueued = MaxQueued;
+	type UndecidingTimeout = UndecidingTimeout;
+	type Tracks = TestTracksInfo;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 10), (2, 20), (3, 30), (4, 40), (5, 50), (6, 60)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+pub fn next_block() {
+	System::set_block_number(System::block_number() + 1);
+	Scheduler::on_initialize(System::block_number());
+}
+
+pub fn run_to(n: u64) {
+	while System::block_number() < n {
+		next_block();
+	}
+}
+
+/// A proposal setting the balance of account 42.
+pub fn set_balance_proposal(value: u64) -> Box<Call> {
+	Box::new(Call::Balances(pallet_balances::Call::set_balance(42, value, 0)))
+}
+
+/// Submit a referendum on the root track from account 1, returning its index.
+pub fn submit_root_referendum(value: u64) -> ReferendumIndex {
+	let index = ReferendumCount::<Test>::get();
+	assert_ok!(Referenda::submit(
+		Origin::signed(1),
+		frame_system::RawOrigin::Root.into(),
+		set_balance_proposal(value),
+		DispatchTime::After(0),
+	));
+	index
+}
+
+pub fn set_tally(index: ReferendumIndex, ayes: u32, nays: u32) {
+	<Referenda as Polling<Tally>>::access_poll(index, |status| {
+		let tally = status.ensure_ongoing().unwrap().0;
+		tally.ayes = ayes;
+		tally.nays = nays;
+	});
+}
+
+pub fn deciding_and_queued(track: u8) -> (u32, Vec<ReferendumIndex>) {
+	let queue = TrackQueue::<Test>::get(track).iter().map(|x| x.0).collect();
+	(DecidingCount::<Test>::get(track), queue)
+}
+
+pub fn is_deciding(index: ReferendumIndex) -> bool {
+	matches!(
+		ReferendumInfoFor::<Test>::get(index),
+		Some(ReferendumInfo::Ongoing(ReferendumStatus { deciding: Some(_), .. }))
+	)
+}
+
+pub fn is_confirming(index: ReferendumIndex) -> bool {
+	matches!(
+		ReferendumInfoFor::<Test>::get(index),
+		Some(ReferendumInfo::Ongoing(ReferendumStatus {
+			deciding: Some(DecidingStatus { confirming: Some(_), .. }),
+			..
+		}))
+	)
+}
@@ -0,0 +1,340 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! The crate's tests.
+
+use super::*;
+use crate::mock::{*, Event};
+use frame_support::{assert_noop, assert_ok};
+use pallet_balances::Error as BalancesError;
+use sp_runtime::traits::BadOrigin;
+
+fn last_event() -> crate::Event<Test> {
+	System::events().into_iter().map(|r| r.event)
+		.filter_map(|e| if let Event::Referenda(inner) = e { Some(inner) } else { None })
+		.last()
+		.unwrap()
+}
+
+#[test]
+fn params_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ReferendumCount::<Test>::get(), 0);
+		assert_eq!(Balances::free_balance(42), 0);
+		assert_eq!(Balances::total_issuance(), 210);
+		assert_eq!(<Referenda as Polling<Tally>>::classes(), vec![0, 1]);
+	});
+}
+
+#[test]
+fn basic_happy_path_works() {
+	new_test_ext().execute_with(|| {
+		// #1: submit
+		let i = submit_root_referendum(1);
+		assert_eq!(last_event(), crate::Event::Submitted(i, 0));
+		assert_eq!(Balances::reserved_balance(&1), 2);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), i));
+		assert_eq!(Balances::reserved_balance(&2), 10);
+		run_to(4);
+		assert!(!is_deciding(i));
+		run_to(5);
+		// #5: Preparation period over, deciding begins.
+		assert!(is_deciding(i));
+		assert_eq!(deciding_and_queued(0), (1, vec![]));
+		set_tally(i, 100, 0);
+		run_to(6);
+		// #6: Passing, confirmation begins.
+		assert!(is_confirming(i));
+		run_to(7);
+		assert!(is_confirming(i));
+		run_to(8);
+		// #8: Confirmation period over, approved and scheduled for enactment.
+		assert_eq!(
+			ReferendumInfoFor::<Test>::get(i),
+			Some(ReferendumInfo::Approved(8, Some(Deposit { who: 2, amount: 10 }))),
+		);
+		assert_eq!(last_event(), crate::Event::Approved(i));
+		assert_eq!(deciding_and_queued(0), (0, vec![]));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		// The minimum enactment period of the track is 4 blocks.
+		run_to(11);
+		assert_eq!(Balances::free_balance(42), 0);
+		run_to(12);
+		assert_eq!(Balances::free_balance(42), 1);
+
+		assert_ok!(Referenda::refund_decision_deposit(Origin::signed(3), i));
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		assert_eq!(last_event(), crate::Event::DecisionDepositRefunded(i, 2, 10));
+		assert_noop!(
+			Referenda::refund_decision_deposit(Origin::signed(3), i),
+			Error::<Test>::NoDeposit,
+		);
+	});
+}
+
+#[test]
+fn insufficient_decision_is_rejected() {
+	new_test_ext().execute_with(|| {
+		let i = submit_root_referendum(1);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), i));
+		run_to(5);
+		assert!(is_deciding(i));
+		set_tally(i, 10, 90);
+		run_to(8);
+		assert!(is_deciding(i));
+		run_to(9);
+		// #9: Decision period over without passing.
+		assert_eq!(
+			ReferendumInfoFor::<Test>::get(i),
+			Some(ReferendumInfo::Rejected(9, Some(Deposit { who: 2, amount: 10 }))),
+		);
+		assert_eq!(last_event(), crate::Event::Rejected(i, Tally { ayes: 10, nays: 90 }));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		run_to(20);
+		assert_eq!(Balances::free_balance(42), 0);
+	});
+}
+
+#[test]
+fn confirmation_is_aborted_when_no_longer_passing() {
+	new_test_ext().execute_with(|| {
+		let i = submit_root_referendum(1);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), i));
+		run_to(5);
+		set_tally(i, 100, 0);
+		run_to(6);
+		assert!(is_confirming(i));
+		set_tally(i, 0, 100);
+		run_to(7);
+		assert!(!is_confirming(i));
+		assert_eq!(last_event(), crate::Event::ConfirmAborted(i));
+		run_to(9);
+		assert!(matches!(
+			ReferendumInfoFor::<Test>::get(i),
+			Some(ReferendumInfo::Rejected(9, _))
+		));
+	});
+}
+
+#[test]
+fn thresholds_come_down_over_the_decision_period() {
+	new_test_ext().execute_with(|| {
+		let i = submit_root_referendum(1);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), i));
+		run_to(5);
+		// Half of the support: the support curve reaches it half way through the decision period.
+		set_tally(i, 50, 0);
+		run_to(6);
+		assert!(!is_confirming(i));
+		run_to(7);
+		assert!(is_confirming(i));
+		run_to(9);
+		assert!(matches!(
+			ReferendumInfoFor::<Test>::get(i),
+			Some(ReferendumInfo::Approved(9, _))
+		));
+	});
+}
+
+#[test]
+fn tracks_are_selected_by_origin() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Referenda::submit(
+			Origin::signed(1),
+			frame_system::RawOrigin::None.into(),
+			set_balance_proposal(1),
+			DispatchTime::At(10),
+		));
+		assert_eq!(last_event(), crate::Event::Submitted(0, 1));
+		assert_noop!(
+			Referenda::submit(
+				Origin::signed(1),
+				frame_system::RawOrigin::Signed(1).into(),
+				set_balance_proposal(1),
+				DispatchTime::At(10),
+			),
+			Error::<Test>::NoTrack,
+		);
+
+		// The "none" track has a smaller decision deposit and shorter periods.
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), 0));
+		assert_eq!(Balances::reserved_balance(&2), 1);
+		run_to(3);
+		assert!(is_deciding(0));
+		set_tally(0, 100, 0);
+		run_to(5);
+		assert!(matches!(
+			ReferendumInfoFor::<Test>::get(0),
+			Some(ReferendumInfo::Approved(5, _))
+		));
+		// Enacted at the desired moment, which is after the minimum enactment period.
+		run_to(9);
+		assert_eq!(Balances::free_balance(42), 0);
+		run_to(10);
+		assert_eq!(Balances::free_balance(42), 1);
+	});
+}
+
+#[test]
+fn queueing_works() {
+	new_test_ext().execute_with(|| {
+		// The root track decides a single referendum at a time.
+		for i in 0 .. 3 {
+			assert_eq!(submit_root_referendum(i as u64 + 1), i);
+			assert_ok!(Referenda::place_decision_deposit(Origin::signed(i as u64 + 2), i));
+		}
+		run_to(5);
+		assert!(is_deciding(0));
+		// Equal votes: first come, first served.
+		assert_eq!(deciding_and_queued(0), (1, vec![2, 1]));
+
+		assert_ok!(Referenda::cancel(Origin::root(), 0));
+		assert_eq!(
+			ReferendumInfoFor::<Test>::get(0),
+			Some(ReferendumInfo::Cancelled(5, Some(Deposit { who: 2, amount: 10 }))),
+		);
+		assert!(is_deciding(1));
+		assert_eq!(deciding_and_queued(0), (1, vec![2]));
+
+		assert_ok!(Referenda::cancel(Origin::root(), 2));
+		assert_eq!(deciding_and_queued(0), (1, vec![]));
+		assert_ok!(Referenda::cancel(Origin::root(), 1));
+		assert_eq!(deciding_and_queued(0), (0, vec![]));
+
+		// All the deposits can be refunded.
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		for i in 0 .. 3 {
+			assert_ok!(Referenda::refund_decision_deposit(Origin::signed(1), i));
+		}
+		for who in 2 .. 5 {
+			assert_eq!(Balances::reserved_balance(&who), 0);
+		}
+	});
+}
+
+#[test]
+fn full_queue_leaves_out_the_fewest_votes() {
+	new_test_ext().execute_with(|| {
+		for i in 0 .. 4 {
+			assert_eq!(submit_root_referendum(i as u64 + 1), i);
+			assert_ok!(Referenda::place_decision_deposit(Origin::signed(i as u64 + 2), i));
+		}
+		set_tally(3, 10, 0);
+		run_to(5);
+		// Referendum 2 has the fewest votes of the queued referenda and is left out.
+		assert!(is_deciding(0));
+		assert_eq!(deciding_and_queued(0), (1, vec![1, 3]));
+
+		// The best queued referendum is decided next.
+		run_to(9);
+		assert!(matches!(ReferendumInfoFor::<Test>::get(0), Some(ReferendumInfo::Rejected(9, _))));
+		assert!(is_deciding(3));
+		assert_eq!(deciding_and_queued(0), (1, vec![1]));
+
+		// Referendum 3 passes once the thresholds have come down to its support.
+		run_to(15);
+		assert!(matches!(ReferendumInfoFor::<Test>::get(3), Some(ReferendumInfo::Approved(15, _))));
+		assert!(is_deciding(1));
+		run_to(19);
+		assert!(matches!(ReferendumInfoFor::<Test>::get(1), Some(ReferendumInfo::Rejected(19, _))));
+		assert_eq!(Balances::free_balance(42), 4);
+		assert_eq!(deciding_and_queued(0), (0, vec![]));
+
+		// Referendum 2 is reconsidered at its undeciding timeout, when there is room for it.
+		run_to(21);
+		assert!(is_deciding(2));
+		assert_eq!(deciding_and_queued(0), (1, vec![]));
+		assert_eq!(Balances::reserved_balance(&1), 2);
+	});
+}
+
+#[test]
+fn undecided_referendum_times_out() {
+	new_test_ext().execute_with(|| {
+		let i = submit_root_referendum(1);
+		run_to(20);
+		assert!(matches!(ReferendumInfoFor::<Test>::get(i), Some(ReferendumInfo::Ongoing(_))));
+		run_to(21);
+		assert_eq!(ReferendumInfoFor::<Test>::get(i), Some(ReferendumInfo::TimedOut(21, None)));
+		assert_eq!(last_event(), crate::Event::TimedOut(i, Tally::default()));
+		assert_eq!(Balances::free_balance(&1), 8);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_noop!(
+			Referenda::place_decision_deposit(Origin::signed(2), i),
+			Error::<Test>::NotOngoing,
+		);
+	});
+}
+
+#[test]
+fn kill_slashes_deposits() {
+	new_test_ext().execute_with(|| {
+		let i = submit_root_referendum(1);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), i));
+		run_to(5);
+		assert_noop!(Referenda::kill(Origin::root(), i), BadOrigin);
+		assert_ok!(Referenda::kill(Origin::signed(4), i));
+		assert_eq!(ReferendumInfoFor::<Test>::get(i), Some(ReferendumInfo::Killed(5)));
+		assert_eq!(deciding_and_queued(0), (0, vec![]));
+		assert_eq!(Balances::total_balance(&1), 8);
+		assert_eq!(Balances::total_balance(&2), 10);
+		assert_noop!(
+			Referenda::refund_decision_deposit(Origin::signed(2), i),
+			Error::<Test>::NoDeposit,
+		);
+	});
+}
+
+#[test]
+fn decision_deposit_errors_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Referenda::place_decision_deposit(Origin::signed(2), 0),
+			Error::<Test>::NotOngoing,
+		);
+		assert_noop!(
+			Referenda::refund_decision_deposit(Origin::signed(2), 0),
+			Error::<Test>::BadReferendum,
+		);
+		let i = submit_root_referendum(1);
+		assert_noop!(
+			Referenda::place_decision_deposit(Origin::signed(1), i),
+			BalancesError::<Test>::InsufficientBalance,
+		);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), i));
+		assert_noop!(
+			Referenda::place_decision_deposit(Origin::signed(3), i),
+			Error::<Test>::HasDeposit,
+		);
+		assert_noop!(
+			Referenda::refund_decision_deposit(Origin::signed(2), i),
+			Error::<Test>::Unfinished,
+		);
+	});
+}
+
+#[test]
+fn privileged_calls_check_origin() {
+	new_test_ext().execute_with(|| {
+		let i = submit_root_referendum(1);
+		assert_noop!(Referenda::cancel(Origin::signed(1), i), BadOrigin);
+		assert_noop!(Referenda::nudge_referendum(Origin::signed(1), i), BadOrigin);
+		assert_ok!(Referenda::cancel(Origin::root(), i));
+		assert_noop!(Referenda::cancel(Origin::root(), i), Error::<Test>::NotOngoing);
+	});
+}
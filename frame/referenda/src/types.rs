@@ -0,0 +1,249 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Miscellaneous additional datatypes.
+
+use super::*;
+use codec::{Encode, Decode};
+use frame_support::{Parameter, traits::schedule::DispatchTime};
+use sp_runtime::{Perbill, RuntimeDebug, traits::Saturating};
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+pub type CallOf<T> = <T as Config>::Proposal;
+pub type VotesOf<T> = <T as Config>::Votes;
+pub type TallyOf<T> = <T as Config>::Tally;
+pub type PalletsOriginOf<T> = <T as Config>::PalletsOrigin;
+pub type ReferendumInfoOf<T> = ReferendumInfo<
+	TrackIdOf<T>,
+	PalletsOriginOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	CallOf<T>,
+	BalanceOf<T>,
+	TallyOf<T>,
+	<T as frame_system::Config>::AccountId,
+>;
+pub type ReferendumStatusOf<T> = ReferendumStatus<
+	TrackIdOf<T>,
+	PalletsOriginOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	CallOf<T>,
+	BalanceOf<T>,
+	TallyOf<T>,
+	<T as frame_system::Config>::AccountId,
+>;
+pub type TrackInfoOf<T> = TrackInfo<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+pub type TrackIdOf<T> = <<T as Config>::Tracks as TracksInfo<
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+>>::Id;
+
+/// A referendum index.
+pub type ReferendumIndex = u32;
+
+/// Details of a track of referenda: all referenda submitted by origins of the track share its
+/// deposits, timings and passing curves.
+#[derive(Clone, RuntimeDebug)]
+pub struct TrackInfo<Balance, Moment> {
+	/// Name of this track.
+	pub name: &'static str,
+	/// A limit for the number of referenda on this track that can be being decided at once.
+	/// For Root origin this should generally be just one.
+	pub max_deciding: u32,
+	/// Amount that must be placed on deposit before a decision can be made.
+	pub decision_deposit: Balance,
+	/// Amount of time this must be submitted for before a decision can be made.
+	pub prepare_period: Moment,
+	/// Amount of time that a decision may take to be approved prior to cancellation.
+	pub decision_period: Moment,
+	/// Amount of time that the approval criteria must hold before it can be approved.
+	pub confirm_period: Moment,
+	/// Minimum amount of time that an approved proposal must be in the dispatch queue.
+	pub min_enactment_period: Moment,
+	/// Minimum aye votes as percentage of overall conviction-weighted votes needed for
+	/// approval as a function of time into decision period.
+	pub min_approval: Curve,
+	/// Minimum pre-conviction aye-votes ("support") as percentage of overall population that is
+	/// needed for approval as a function of time into decision period.
+	pub min_support: Curve,
+}
+
+/// Information on the voting tracks.
+pub trait TracksInfo<Balance, Moment> {
+	/// The identifier for a track.
+	type Id: Parameter + Ord + PartialOrd + Copy + Send + Sync + 'static;
+
+	/// The origin type from which a track is implied.
+	type Origin;
+
+	/// Return the array of known tracks and their information.
+	fn tracks() -> &'static [(Self::Id, TrackInfo<Balance, Moment>)];
+
+	/// Determine the voting track for the given `origin`.
+	fn track_for(origin: &Self::Origin) -> Result<Self::Id, ()>;
+
+	/// Return the track info for track `id`, by default this just looks it up in `Self::tracks()`.
+	fn info(id: Self::Id) -> Option<&'static TrackInfo<Balance, Moment>> {
+		Self::tracks().iter().find(|x| x.0 == id).map(|x| &x.1)
+	}
+}
+
+/// A curve describing a threshold which varies over the decision period of a referendum.
+///
+/// The x axis is the proportion of the decision period elapsed, the y axis the threshold.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Curve {
+	/// Linear curve starting at `(0, begin)`, ending at `(period, begin - delta)`.
+	LinearDecreasing { begin: Perbill, delta: Perbill },
+}
+
+impl Curve {
+	/// Determine the `y` value for the given `x` value.
+	pub fn threshold(&self, x: Perbill) -> Perbill {
+		match self {
+			Self::LinearDecreasing { begin, delta } => begin.saturating_sub(*delta * x),
+		}
+	}
+
+	/// Determine whether `y` passes the threshold at `x`.
+	pub fn passing(&self, x: Perbill, y: Perbill) -> bool {
+		y >= self.threshold(x)
+	}
+
+	/// Determine the smallest `x` value such that `passing` returns `true` when passed along with
+	/// the given `y` value. If `passing` never returns `true` for the `y` value, then
+	/// `Perbill::one()` is returned.
+	pub fn delay(&self, y: Perbill) -> Perbill {
+		match self {
+			Self::LinearDecreasing { begin, delta } => {
+				if y >= *begin {
+					Perbill::zero()
+				} else if *delta == Perbill::zero() {
+					Perbill::one()
+				} else {
+					Perbill::from_rational(begin.deconstruct() - y.deconstruct(), delta.deconstruct())
+				}
+			},
+		}
+	}
+}
+
+/// A deposit placed by an account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Deposit<AccountId, Balance> {
+	/// The depositor.
+	pub who: AccountId,
+	/// The amount reserved from the depositor.
+	pub amount: Balance,
+}
+
+/// The state of a referendum which is being decided.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct DecidingStatus<BlockNumber> {
+	/// When this referendum began being "decided". If confirming, then the end will actually be
+	/// delayed until the end of the confirmation period.
+	pub since: BlockNumber,
+	/// If `Some`, then the referendum has entered confirmation stage and will end at
+	/// the block number as long as it doesn't lose its approval in the meantime.
+	pub confirming: Option<BlockNumber>,
+}
+
+/// Info regarding an ongoing referendum.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ReferendumStatus<TrackId, Origin, Moment, Call, Balance, Tally, AccountId> {
+	/// The track of this referendum.
+	pub track: TrackId,
+	/// The origin for this referendum.
+	pub origin: Origin,
+	/// The proposal which is being voted on.
+	pub proposal: Call,
+	/// The time the proposal should be scheduled for enactment.
+	pub enactment: DispatchTime<Moment>,
+	/// The time of submission. Once `UndecidingTimeout` passes, it may be closed by anyone if
+	/// `deciding` is `None`.
+	pub submitted: Moment,
+	/// The deposit reserved for the submission of this referendum.
+	pub submission_deposit: Deposit<AccountId, Balance>,
+	/// The deposit reserved for this referendum to be decided.
+	pub decision_deposit: Option<Deposit<AccountId, Balance>>,
+	/// The status of a decision being made. If `None`, it has not entered the deciding period.
+	pub deciding: Option<DecidingStatus<Moment>>,
+	/// The current tally of votes in this referendum.
+	pub tally: Tally,
+	/// Whether we have been placed in the queue for being decided or not.
+	pub in_queue: bool,
+	/// The next scheduled wake-up, if `Some`.
+	pub alarm: Option<Moment>,
+}
+
+/// Info regarding a referendum, present or past.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ReferendumInfo<TrackId, Origin, Moment, Call, Balance, Tally, AccountId> {
+	/// Referendum has been submitted and is being voted on.
+	Ongoing(ReferendumStatus<TrackId, Origin, Moment, Call, Balance, Tally, AccountId>),
+	/// Referendum finished at `Moment` with approval. Decision deposit is held.
+	Approved(Moment, Option<Deposit<AccountId, Balance>>),
+	/// Referendum finished at `Moment` with rejection. Decision deposit is held.
+	Rejected(Moment, Option<Deposit<AccountId, Balance>>),
+	/// Referendum finished at `Moment` with cancellation. Decision deposit is held.
+	Cancelled(Moment, Option<Deposit<AccountId, Balance>>),
+	/// Referendum finished at `Moment` and was never decided. Decision deposit is held.
+	TimedOut(Moment, Option<Deposit<AccountId, Balance>>),
+	/// Referendum finished at `Moment` with a kill.
+	Killed(Moment),
+}
+
+impl<TrackId, Origin, Moment, Call, Balance, Tally, AccountId>
+	ReferendumInfo<TrackId, Origin, Moment, Call, Balance, Tally, AccountId>
+{
+	/// Take the decision deposit, if there is one.
+	pub fn take_decision_deposit(&mut self) -> Result<Option<Deposit<AccountId, Balance>>, ()> {
+		use ReferendumInfo::*;
+		match self {
+			Ongoing(x) if x.decision_deposit.is_none() => Ok(None),
+			// Cannot refund deposit if Ongoing as this breaks assumptions.
+			Ongoing(_) => Err(()),
+			Approved(_, d) | Rejected(_, d) | Cancelled(_, d) | TimedOut(_, d) => Ok(d.take()),
+			Killed(_) => Ok(None),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn linear_decreasing_curve_works() {
+		let c = Curve::LinearDecreasing {
+			begin: Perbill::from_percent(100),
+			delta: Perbill::from_percent(50),
+		};
+		assert_eq!(c.threshold(Perbill::zero()), Perbill::from_percent(100));
+		assert_eq!(c.threshold(Perbill::from_percent(50)), Perbill::from_percent(75));
+		assert_eq!(c.threshold(Perbill::one()), Perbill::from_percent(50));
+		assert!(c.passing(Perbill::from_percent(50), Perbill::from_percent(80)));
+		assert!(!c.passing(Perbill::zero(), Perbill::from_percent(80)));
+		assert_eq!(c.delay(Perbill::from_percent(100)), Perbill::zero());
+		assert_eq!(c.delay(Perbill::from_percent(75)), Perbill::from_percent(50));
+		assert_eq!(c.delay(Perbill::from_percent(10)), Perbill::one());
+	}
+}
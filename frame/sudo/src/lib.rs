@@ -279,7 +279,6 @@ pub mod pallet {
 		pub key: T::AccountId,
 	}
 
-	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
 			Self {
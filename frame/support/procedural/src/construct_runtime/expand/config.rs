@@ -18,7 +18,7 @@
 use crate::construct_runtime::Pallet;
 use inflector::Inflector;
 use proc_macro2::TokenStream;
-use quote::{ToTokens, format_ident, quote};
+use quote::{format_ident, quote};
 use syn::Ident;
 
 pub fn expand_outer_config(
@@ -29,13 +29,13 @@ pub fn expand_outer_config(
 	let mut types = TokenStream::new();
 	let mut fields = TokenStream::new();
 	let mut build_storage_calls = TokenStream::new();
+	let mut build_calls = TokenStream::new();
 	let mut query_genesis_config_part_macros = Vec::new();
 
 	for decl in pallet_decls {
 		if let Some(pallet_entry) = decl.find_part("Config") {
 			let path = &decl.path;
 			let pallet_name = &decl.name;
-			let path_str = path.into_token_stream().to_string();
 			let config = format_ident!("{}Config", pallet_name);
 			let field_name = &Ident::new(
 				&pallet_name.to_string().to_snake_case(),
@@ -46,10 +46,9 @@ pub fn expand_outer_config(
 			types.extend(expand_config_types(runtime, decl, &config, part_is_generic));
 			fields.extend(quote!(pub #field_name: #config,));
 			build_storage_calls.extend(expand_config_build_storage_call(scrate, runtime, decl, &field_name));
+			build_calls.extend(expand_config_build_call(scrate, runtime, decl, &field_name));
 			query_genesis_config_part_macros.push(quote! {
 				#path::__substrate_genesis_config_check::is_genesis_config_defined!(#pallet_name);
-				#[cfg(feature = "std")]
-				#path::__substrate_genesis_config_check::is_std_enabled_for_genesis!(#pallet_name, #path_str);
 			});
 		}
 	}
@@ -59,9 +58,7 @@ pub fn expand_outer_config(
 
 		#types
 
-		#[cfg(any(feature = "std", test))]
 		use #scrate::serde as __genesis_config_serde_import__;
-		#[cfg(any(feature = "std", test))]
 		#[derive(#scrate::serde::Serialize, #scrate::serde::Deserialize, Default)]
 		#[serde(rename_all = "camelCase")]
 		#[serde(deny_unknown_fields)]
@@ -85,6 +82,14 @@ pub fn expand_outer_config(
 				Ok(())
 			}
 		}
+
+		impl #scrate::traits::BuildGenesisConfig for GenesisConfig {
+			fn build(&self) {
+				#build_calls
+
+				<AllPalletsWithSystem as #scrate::traits::OnGenesis>::on_genesis();
+			}
+		}
 	}
 }
 
@@ -98,15 +103,12 @@ fn expand_config_types(
 
 	match (decl.instance.as_ref(), part_is_generic) {
 		(Some(inst), true) => quote!{
-			#[cfg(any(feature = "std", test))]
 			pub type #config = #path::GenesisConfig<#runtime, #path::#inst>;
 		},
 		(None, true) => quote!{
-			#[cfg(any(feature = "std", test))]
 			pub type #config = #path::GenesisConfig<#runtime>;
 		},
 		(_, false) => quote!{
-			#[cfg(any(feature = "std", test))]
 			pub type #config = #path::GenesisConfig;
 		},
 	}
@@ -130,3 +132,21 @@ fn expand_config_build_storage_call(
 			<#runtime, #instance>::build_module_genesis_storage(&self.#field_name, storage)?;
 	}
 }
+
+fn expand_config_build_call(
+	scrate: &TokenStream,
+	runtime: &Ident,
+	decl: &Pallet,
+	field_name: &Ident,
+) -> TokenStream {
+	let path = &decl.path;
+	let instance = if let Some(inst) = decl.instance.as_ref() {
+		quote!(#path::#inst)
+	} else {
+		quote!(#path::__InherentHiddenInstance)
+	};
+
+	quote!{
+		#scrate::traits::GenesisBuild::<#runtime, #instance>::build(&self.#field_name);
+	}
+}
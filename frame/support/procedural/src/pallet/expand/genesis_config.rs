@@ -23,30 +23,20 @@ use syn::{Ident, spanned::Spanned};
 pub fn expand_genesis_config(def: &mut Def) -> proc_macro2::TokenStream {
 	let count = COUNTER.with(|counter| counter.borrow_mut().inc());
 
-	let (genesis_config, def_macro_ident, std_macro_ident) =
+	let (genesis_config, def_macro_ident) =
 		if let Some(genesis_config) = &def.genesis_config {
 			let def_macro_ident = Ident::new(
 				&format!("__is_genesis_config_defined_{}", count),
 				genesis_config.genesis_config.span(),
 			);
 
-			let std_macro_ident = Ident::new(
-				&format!("__is_std_macro_defined_for_genesis_{}", count),
-				genesis_config.genesis_config.span(),
-			);
-
-			(genesis_config, def_macro_ident, std_macro_ident)
+			(genesis_config, def_macro_ident)
 		} else {
 			let def_macro_ident = Ident::new(
 				&format!("__is_genesis_config_defined_{}", count),
 				def.item.span(),
 			);
 
-			let std_macro_ident = Ident::new(
-				&format!("__is_std_enabled_for_genesis_{}", count),
-				def.item.span(),
-			);
-
 			return quote::quote! {
 				#[doc(hidden)]
 				pub mod __substrate_genesis_config_check {
@@ -63,16 +53,8 @@ pub fn expand_genesis_config(def: &mut Def) -> proc_macro2::TokenStream {
 						}
 					}
 
-					#[macro_export]
-					#[doc(hidden)]
-					macro_rules! #std_macro_ident {
-						($pallet_name:ident, $pallet_path:expr) => {};
-					}
-
 					#[doc(hidden)]
 					pub use #def_macro_ident as is_genesis_config_defined;
-					#[doc(hidden)]
-					pub use #std_macro_ident as is_std_enabled_for_genesis;
 				}
 			};
 		};
@@ -84,6 +66,24 @@ pub fn expand_genesis_config(def: &mut Def) -> proc_macro2::TokenStream {
 
 	let serde_crate = format!("{}::serde", frame_support);
 
+	// Without `std` the types of the fields are not bounded by `MaybeSerializeDeserialize`, so
+	// bound each of them like `decl_storage` does.
+	let field_types = match &*genesis_config_item {
+		syn::Item::Enum(item) => item.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+		syn::Item::Struct(item) => item.fields.iter().collect(),
+		_ => Vec::new(),
+	};
+	let mut no_std_ser_bound = String::new();
+	let mut no_std_de_bound = String::new();
+	for field in field_types {
+		let typ = &field.ty;
+		let typ = quote::quote!( #typ );
+		no_std_ser_bound.push_str(&format!("{} : {}::serde::Serialize, ", typ, frame_support));
+		no_std_de_bound.push_str(
+			&format!("{} : {}::serde::de::DeserializeOwned, ", typ, frame_support),
+		);
+	}
+
 	match genesis_config_item {
 		syn::Item::Enum(syn::ItemEnum { attrs, ..}) |
 		syn::Item::Struct(syn::ItemStruct { attrs, .. }) |
@@ -97,14 +97,23 @@ pub fn expand_genesis_config(def: &mut Def) -> proc_macro2::TokenStream {
 					"]
 				));
 			}
-			attrs.push(syn::parse_quote!( #[cfg(feature = "std")] ));
 			attrs.push(syn::parse_quote!(
 				#[derive(#frame_support::Serialize, #frame_support::Deserialize)]
 			));
 			attrs.push(syn::parse_quote!( #[serde(rename_all = "camelCase")] ));
 			attrs.push(syn::parse_quote!( #[serde(deny_unknown_fields)] ));
-			attrs.push(syn::parse_quote!( #[serde(bound(serialize = ""))] ));
-			attrs.push(syn::parse_quote!( #[serde(bound(deserialize = ""))] ));
+			attrs.push(syn::parse_quote!(
+				#[cfg_attr(feature = "std", serde(bound(serialize = "")))]
+			));
+			attrs.push(syn::parse_quote!(
+				#[cfg_attr(feature = "std", serde(bound(deserialize = "")))]
+			));
+			attrs.push(syn::parse_quote!(
+				#[cfg_attr(not(feature = "std"), serde(bound(serialize = #no_std_ser_bound)))]
+			));
+			attrs.push(syn::parse_quote!(
+				#[cfg_attr(not(feature = "std"), serde(bound(deserialize = #no_std_de_bound)))]
+			));
 			attrs.push(syn::parse_quote!( #[serde(crate = #serde_crate)] ));
 		},
 		_ => unreachable!("Checked by genesis_config parser"),
@@ -119,32 +128,8 @@ pub fn expand_genesis_config(def: &mut Def) -> proc_macro2::TokenStream {
 				($pallet_name:ident) => {};
 			}
 
-			#[cfg(not(feature = "std"))]
-			#[macro_export]
-			#[doc(hidden)]
-			macro_rules! #std_macro_ident {
-				($pallet_name:ident, $pallet_path:expr) => {
-					compile_error!(concat!(
-						"`",
-						stringify!($pallet_name),
-						"` does not have the std feature enabled, this will cause the `",
-						$pallet_path,
-						"::GenesisConfig` type to be undefined."
-					));
-				};
-			}
-
-			#[cfg(feature = "std")]
-			#[macro_export]
-			#[doc(hidden)]
-			macro_rules! #std_macro_ident {
-				($pallet_name:ident, $pallet_path:expr) => {};
-			}
-
 			#[doc(hidden)]
 			pub use #def_macro_ident as is_genesis_config_defined;
-			#[doc(hidden)]
-			pub use #std_macro_ident as is_std_enabled_for_genesis;
 		}
 	}
 }
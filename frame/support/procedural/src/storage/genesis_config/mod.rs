@@ -70,7 +70,6 @@ fn decl_genesis_config_and_impl_default(
 	quote!(
 		/// Genesis config for the module, allow to build genesis storage.
 		#[derive(#scrate::Serialize, #scrate::Deserialize)]
-		#[cfg(feature = "std")]
 		#[serde(rename_all = "camelCase")]
 		#[serde(deny_unknown_fields)]
 		#[serde(crate = #serde_crate)]
@@ -79,7 +78,6 @@ fn decl_genesis_config_and_impl_default(
 			#( #config_fields )*
 		}
 
-		#[cfg(feature = "std")]
 		impl#genesis_impl Default for GenesisConfig#genesis_struct #genesis_where_clause {
 			fn default() -> Self {
 				GenesisConfig {
@@ -137,6 +135,15 @@ fn impl_build_storage(
 	let build_storage_impl_trait = quote!(
 		#scrate::sp_runtime::BuildModuleGenesisStorage<#runtime_generic, #inherent_instance>
 	);
+	let genesis_build_trait = quote!(
+		#scrate::traits::GenesisBuild<#runtime_generic, #inherent_instance>
+	);
+	// The serde bounds of the genesis config depend on its fields, so require them explicitly.
+	let mut genesis_build_where_clause = where_clause.clone()
+		.unwrap_or_else(|| syn::parse_quote!(where));
+	genesis_build_where_clause.predicates.push(syn::parse_quote!(
+		GenesisConfig#genesis_struct: #scrate::sp_runtime::traits::MaybeSerializeDeserialize
+	));
 
 	quote!{
 		#[cfg(feature = "std")]
@@ -174,6 +181,14 @@ fn impl_build_storage(
 				self.assimilate_storage::<#fn_traitinstance> (storage)
 			}
 		}
+
+		impl#build_storage_impl #genesis_build_trait for GenesisConfig#genesis_struct
+			#genesis_build_where_clause
+		{
+			fn build(&self) {
+				#( #builder_blocks )*
+			}
+		}
 	}
 }
 
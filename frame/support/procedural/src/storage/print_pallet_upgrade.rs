@@ -131,7 +131,6 @@ pub fn maybe_print_pallet_upgrade(def: &super::DeclStorageDefExt) {
 	{{{genesis_config_decl_fields}
 	}}
 
-	#[cfg(feature = \"std\")]
 	impl{genesis_config_impl_gen} Default for GenesisConfig{genesis_config_use_gen}
 		// TODO_MAYBE_WHERE_CLAUSE
 	{{
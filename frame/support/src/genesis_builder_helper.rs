@@ -0,0 +1,70 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers to implement the `GenesisBuilder` runtime api of `sp-genesis-builder`.
+//!
+//! They are available without `std`, so the wasm runtime serves the api as well.
+
+use crate::traits::BuildGenesisConfig;
+use sp_runtime::{format_runtime_string, RuntimeString};
+use sp_std::vec::Vec;
+
+/// Returns the default `GC` serialized as JSON.
+pub fn create_default_config<GC: BuildGenesisConfig>() -> Vec<u8> {
+	serde_json::to_vec(&GC::default())
+		.expect("Serializing a `GenesisConfig` to JSON can not fail; qed")
+}
+
+/// Build the genesis state of the `GC` serialized as JSON in `json`, writing it to the current
+/// externalities.
+pub fn build_config<GC: BuildGenesisConfig>(json: Vec<u8>) -> Result<(), RuntimeString> {
+	let config = serde_json::from_slice::<GC>(&json)
+		.map_err(|e| format_runtime_string!("Invalid genesis config JSON: {}", e))?;
+	config.build();
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Default, serde::Serialize, serde::Deserialize)]
+	#[serde(deny_unknown_fields)]
+	struct Config {
+		value: u32,
+	}
+
+	impl BuildGenesisConfig for Config {
+		fn build(&self) {
+			sp_io::storage::set(b"value", &self.value.to_le_bytes());
+		}
+	}
+
+	#[test]
+	fn default_config_round_trips() {
+		let default = create_default_config::<Config>();
+		assert_eq!(default, br#"{"value":0}"#.to_vec());
+
+		let mut ext = sp_io::TestExternalities::default();
+		ext.execute_with(|| {
+			assert_eq!(build_config::<Config>(br#"{"value":42}"#.to_vec()), Ok(()));
+			assert_eq!(sp_io::storage::get(b"value"), Some(42u32.to_le_bytes().to_vec()));
+			assert!(build_config::<Config>(br#"{"other":42}"#.to_vec()).is_err());
+		});
+	}
+}
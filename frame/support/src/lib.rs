@@ -25,7 +25,6 @@ extern crate self as frame_support;
 #[doc(hidden)]
 pub use sp_tracing;
 
-#[cfg(feature = "std")]
 pub use serde;
 pub use sp_core::Void;
 #[doc(hidden)]
@@ -44,6 +43,7 @@ pub use sp_state_machine::BasicExternalities;
 pub use sp_io::{storage::root as storage_root, self};
 #[doc(hidden)]
 pub use sp_runtime::RuntimeDebug;
+pub use sp_runtime::StateVersion;
 #[doc(hidden)]
 pub use log;
 #[doc(hidden)]
@@ -61,6 +61,7 @@ pub mod error;
 pub mod traits;
 pub mod weights;
 pub mod instances;
+pub mod genesis_builder_helper;
 
 #[doc(hidden)]
 pub mod unsigned {
@@ -710,9 +711,9 @@ macro_rules! assert_noop {
 		$x:expr,
 		$y:expr $(,)?
 	) => {
-		let h = $crate::storage_root();
+		let h = $crate::storage_root($crate::StateVersion::V1);
 		$crate::assert_err!($x, $y);
-		assert_eq!(h, $crate::storage_root());
+		assert_eq!(h, $crate::storage_root($crate::StateVersion::V1));
 	}
 }
 
@@ -725,9 +726,9 @@ macro_rules! assert_storage_noop {
 	(
 		$x:expr
 	) => {
-		let h = $crate::storage_root();
+		let h = $crate::storage_root($crate::StateVersion::V1);
 		$x;
-		assert_eq!(h, $crate::storage_root());
+		assert_eq!(h, $crate::storage_root($crate::StateVersion::V1));
 	}
 }
 
@@ -783,7 +784,6 @@ macro_rules! assert_ok {
 	}
 }
 
-#[cfg(feature = "std")]
 #[doc(hidden)]
 pub use serde::{Serialize, Deserialize};
 
@@ -1270,7 +1270,6 @@ pub mod tests {
 /// Prelude to be used alongside pallet macro, for ease of use.
 pub mod pallet_prelude {
 	pub use sp_std::marker::PhantomData;
-	#[cfg(feature = "std")]
 	pub use crate::traits::GenesisBuild;
 	pub use crate::{
 		EqNoBound, PartialEqNoBound, RuntimeDebugNoBound, DebugNoBound, CloneNoBound, Twox256,
@@ -1770,12 +1769,14 @@ pub mod pallet_prelude {
 /// ### Macro expansion
 ///
 /// Macro will add the following attribute on it:
-/// * `#[cfg(feature = "std")]`
 /// * `#[derive(Serialize, Deserialize)]`
 /// * `#[serde(rename_all = "camelCase")]`
 /// * `#[serde(deny_unknown_fields)]`
-/// * `#[serde(bound(serialize = ""))]`
-/// * `#[serde(bound(deserialize = ""))]`
+/// * `#[serde(bound(serialize = ""))]` and `#[serde(bound(deserialize = ""))]` with `std`,
+/// 	otherwise a bound on the type of each field.
+///
+/// The genesis config is also available without `std`, so its `Default` implementation must be
+/// too.
 ///
 /// # Genesis build: `#[pallet::genesis_build]` optional
 ///
@@ -1801,9 +1802,6 @@ pub mod pallet_prelude {
 ///
 /// ### Macro expansion
 ///
-/// Macro will add the following attribute on it:
-/// * `#[cfg(feature = "std")]`
-///
 /// Macro will implement `sp_runtime::BuildModuleGenesisStorage` using `()` as second generic for
 /// non-instantiable pallets.
 ///
@@ -23,7 +23,7 @@
 
 use crate::sp_std::prelude::*;
 use codec::{Codec, Encode, Decode};
-pub use sp_core::storage::{ChildInfo, ChildType};
+pub use sp_core::storage::{ChildInfo, ChildType, StateVersion};
 pub use crate::sp_io::KillStorageResult;
 
 /// Return the value of the item in storage under `key`, or `None` if there is no explicit entry.
@@ -226,13 +226,15 @@ pub fn put_raw(
 	}
 }
 
-/// Calculate current child root value.
+/// Calculate current child root value, using the given state version for the trie layout.
 pub fn root(
 	child_info: &ChildInfo,
+	version: StateVersion,
 ) -> Vec<u8> {
 	match child_info.child_type() {
 		ChildType::ParentKeyId => sp_io::default_child_storage::root(
 			child_info.storage_key(),
+			version,
 		),
 	}
 }
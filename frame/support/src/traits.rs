@@ -70,8 +70,7 @@ mod hooks;
 pub use hooks::{Hooks, OnGenesis, OnInitialize, OnFinalize, OnIdle, OnRuntimeUpgrade, OnTimestampSet};
 #[cfg(feature = "try-runtime")]
 pub use hooks::{OnRuntimeUpgradeHelpersExt, ON_RUNTIME_UPGRADE_PREFIX};
-#[cfg(feature = "std")]
-pub use hooks::GenesisBuild;
+pub use hooks::{GenesisBuild, BuildGenesisConfig};
 
 pub mod schedule;
 mod storage;
@@ -81,4 +80,6 @@ mod dispatch;
 pub use dispatch::{EnsureOrigin, OriginTrait, UnfilteredDispatchable};
 
 mod voting;
-pub use voting::{CurrencyToVote, SaturatingCurrencyToVote, U128CurrencyToVote};
+pub use voting::{
+	CurrencyToVote, SaturatingCurrencyToVote, U128CurrencyToVote, VoteTally, PollStatus, Polling,
+};
@@ -19,6 +19,7 @@
 
 use sp_arithmetic::traits::Saturating;
 use sp_runtime::traits::MaybeSerializeDeserialize;
+use serde::{Serialize, de::DeserializeOwned};
 use impl_trait_for_tuples::impl_for_tuples;
 
 /// The block initialization trait.
@@ -281,13 +282,13 @@ pub trait Hooks<BlockNumber> {
 
 /// A trait to define the build function of a genesis config, T and I are placeholder for pallet
 /// trait and pallet instance.
-#[cfg(feature = "std")]
 pub trait GenesisBuild<T, I=()>: Default + MaybeSerializeDeserialize {
 	/// The build function is called within an externalities allowing storage APIs.
 	/// Thus one can write to storage using regular pallet storages.
 	fn build(&self);
 
 	/// Build the storage using `build` inside default storage.
+	#[cfg(feature = "std")]
 	fn build_storage(&self) -> Result<sp_runtime::Storage, String> {
 		let mut storage = Default::default();
 		self.assimilate_storage(&mut storage)?;
@@ -295,6 +296,7 @@ pub trait GenesisBuild<T, I=()>: Default + MaybeSerializeDeserialize {
 	}
 
 	/// Assimilate the storage for this module into pre-existing overlays.
+	#[cfg(feature = "std")]
 	fn assimilate_storage(&self, storage: &mut sp_runtime::Storage) -> Result<(), String> {
 		sp_state_machine::BasicExternalities::execute_with_storage(storage, || {
 			self.build();
@@ -303,6 +305,15 @@ pub trait GenesisBuild<T, I=()>: Default + MaybeSerializeDeserialize {
 	}
 }
 
+/// The genesis config of a whole runtime, implemented by `construct_runtime!`.
+///
+/// Unlike `sp_runtime::BuildStorage` it is also available without `std`, which allows the
+/// runtime itself to build its genesis state.
+pub trait BuildGenesisConfig: Default + Serialize + DeserializeOwned {
+	/// Build the genesis state of all pallets, writing it to the current externalities.
+	fn build(&self);
+}
+
 /// A trait which is called when the timestamp is set in the runtime.
 #[impl_for_tuples(30)]
 pub trait OnTimestampSet<Moment> {
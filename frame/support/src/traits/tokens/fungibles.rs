@@ -140,6 +140,20 @@ pub trait Transfer<AccountId>: Inspect<AccountId> {
 	) -> Result<Self::Balance, DispatchError>;
 }
 
+/// Trait for providing the ability to create new fungible assets.
+pub trait Create<AccountId>: Inspect<AccountId> {
+	/// Create a new fungible asset `id`, administered by `admin`.
+	///
+	/// Accounts holding a sufficient asset need no other provider reference to exist. No balance
+	/// of the asset below `min_balance` may be held.
+	fn create(
+		id: Self::AssetId,
+		admin: AccountId,
+		is_sufficient: bool,
+		min_balance: Self::Balance,
+	) -> DispatchResult;
+}
+
 /// Trait for inspecting a set of named fungible assets which can be placed on hold.
 pub trait InspectHold<AccountId>: Inspect<AccountId> {
 	/// Amount of funds held in hold.
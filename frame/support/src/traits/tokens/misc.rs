@@ -167,3 +167,11 @@ impl<T: FullCodec + Copy + Eq + PartialEq + Debug> AssetId for T {}
 /// Simple amalgamation trait to collect together properties for a Balance under one roof.
 pub trait Balance: AtLeast32BitUnsigned + FullCodec + Copy + Default + Debug {}
 impl<T: AtLeast32BitUnsigned + FullCodec + Copy + Default + Debug> Balance for T {}
+
+/// Converts a balance value into an asset balance.
+pub trait BalanceConversion<InBalance, AssetId, AssetBalance> {
+	/// The error returned when the conversion is not possible.
+	type Error;
+	/// Convert `balance` into a balance of the asset `asset_id`.
+	fn to_asset_balance(balance: InBalance, asset_id: AssetId) -> Result<AssetBalance, Self::Error>;
+}
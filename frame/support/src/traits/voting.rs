@@ -18,7 +18,11 @@
 //! Traits and associated data structures concerned with voting, and moving between tokens and
 //! votes.
 
-use sp_arithmetic::traits::{UniqueSaturatedInto, UniqueSaturatedFrom, SaturatedConversion};
+use sp_std::prelude::*;
+use sp_arithmetic::{Perbill, traits::{UniqueSaturatedInto, UniqueSaturatedFrom, SaturatedConversion}};
+use sp_runtime::DispatchError;
+use codec::HasCompact;
+use crate::dispatch::Parameter;
 
 /// A trait similar to `Convert` to convert values from `B` an abstract balance type
 /// into u64 and back from u128. (This conversion is used in election and other places where complex
@@ -86,3 +90,75 @@ impl<B: UniqueSaturatedInto<u64> + UniqueSaturatedFrom<u128>> CurrencyToVote<B>
 		B::unique_saturated_from(value)
 	}
 }
+
+/// The tally of votes for a poll, as seen by something which needs to decide on its outcome.
+pub trait VoteTally<Votes> {
+	/// The number of aye votes, expressed in terms of post-conviction lock-vote.
+	fn ayes(&self) -> Votes;
+
+	/// The proportion of the total voting capital that has voted in favour.
+	fn support(&self) -> Perbill;
+
+	/// The proportion of post-conviction votes that are aye votes.
+	fn approval(&self) -> Perbill;
+}
+
+/// The status of a poll, as provided to a `Polling` accessor.
+pub enum PollStatus<Tally, Moment, Class> {
+	/// No poll exists under the index.
+	None,
+	/// The poll is ongoing; its tally may be altered and it belongs to the given class.
+	Ongoing(Tally, Class),
+	/// The poll ended at the given moment, either approved (`true`) or rejected (`false`).
+	Completed(Moment, bool),
+}
+
+impl<Tally, Moment, Class> PollStatus<Tally, Moment, Class> {
+	/// Return the tally and class if the poll is ongoing.
+	pub fn ensure_ongoing(self) -> Option<(Tally, Class)> {
+		match self {
+			Self::Ongoing(t, c) => Some((t, c)),
+			_ => None,
+		}
+	}
+}
+
+/// Something which holds a set of polls, each with a tally which may be altered by voters.
+pub trait Polling<Tally> {
+	/// The index by which polls are identified.
+	type Index: Parameter + Ord + Copy + HasCompact;
+	/// The type of votes in the tally.
+	type Votes: Parameter + Ord + Copy + HasCompact;
+	/// The class of a poll; voting and delegation happen independently for each class.
+	type Class: Parameter + Ord;
+	/// The moment at which a poll may be completed.
+	type Moment;
+
+	/// Provides a vector of all classes that polls may be in.
+	fn classes() -> Vec<Self::Class>;
+
+	/// Provide mutable access to the status of the poll `index`.
+	fn access_poll<R>(
+		index: Self::Index,
+		f: impl FnOnce(PollStatus<&mut Tally, Self::Moment, Self::Class>) -> R,
+	) -> R;
+
+	/// Provide mutable access to the status of the poll `index`; the status is only altered if
+	/// `f` returns `Ok`.
+	fn try_access_poll<R>(
+		index: Self::Index,
+		f: impl FnOnce(PollStatus<&mut Tally, Self::Moment, Self::Class>) -> Result<R, DispatchError>,
+	) -> Result<R, DispatchError>;
+
+	/// Create an ongoing poll of the given `class` with an empty tally.
+	///
+	/// This is only intended for benchmarking.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_ongoing(class: Self::Class) -> Result<Self::Index, ()>;
+
+	/// End the ongoing poll `index`, either approving or rejecting it.
+	///
+	/// This is only intended for benchmarking.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn end_ongoing(index: Self::Index, approved: bool) -> Result<(), ()>;
+}
@@ -1,24 +0,0 @@
-use frame_support::construct_runtime;
-use sp_runtime::{generic, traits::BlakeTwo256};
-use sp_core::sr25519;
-
-pub type Signature = sr25519::Signature;
-pub type BlockNumber = u64;
-pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
-pub type Block = generic::Block<Header, UncheckedExtrinsic>;
-pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<u32, Call, Signature, ()>;
-
-impl test_pallet::Config for Runtime {}
-
-construct_runtime! {
-	pub enum Runtime where
-		Block = Block,
-		NodeBlock = Block,
-		UncheckedExtrinsic = UncheckedExtrinsic
-	{
-		System: system::{Pallet, Call, Storage, Config, Event<T>},
-		Pallet: test_pallet::{Pallet, Config},
-	}
-}
-
-fn main() {}
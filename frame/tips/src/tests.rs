@@ -146,6 +146,10 @@ impl pallet_treasury::Config for Test {
 	type WeightInfo = ();
 	type SpendFunds = ();
 	type MaxApprovals = MaxApprovals;
+	type SpendAssetOrigin = frame_system::EnsureRoot<u128>;
+	type AssetId = u32;
+	type AssetBalance = u64;
+	type Assets = pallet_treasury::NoAssets<u32, u64>;
 }
 parameter_types! {
 	pub const TipCountdown: u64 = 1;
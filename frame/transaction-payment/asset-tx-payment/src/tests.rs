@@ -0,0 +1,382 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use super::*;
+use crate as pallet_asset_tx_payment;
+
+use std::cell::RefCell;
+
+use frame_support::{
+	assert_ok, parameter_types,
+	traits::{Get, fungibles::Mutate},
+	weights::{DispatchClass, DispatchInfo, PostDispatchInfo, Weight, IdentityFee, Pays},
+};
+use frame_system as system;
+use pallet_balances::Call as BalancesCall;
+use pallet_transaction_payment::CurrencyAdapter;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+type Balance = u64;
+type AccountId = u64;
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		AssetTxPayment: pallet_asset_tx_payment::{Pallet},
+	}
+);
+
+const CALL: &<Runtime as frame_system::Config>::Call =
+	&Call::Balances(BalancesCall::transfer(2, 69));
+
+const EXTRINSIC_BASE_WEIGHT: Weight = 5;
+
+pub struct BlockWeights;
+impl Get<frame_system::limits::BlockWeights> for BlockWeights {
+	fn get() -> frame_system::limits::BlockWeights {
+		frame_system::limits::BlockWeights::builder()
+			.base_block(0)
+			.for_class(DispatchClass::all(), |weights| {
+				weights.base_extrinsic = EXTRINSIC_BASE_WEIGHT;
+			})
+			.for_class(DispatchClass::non_mandatory(), |weights| {
+				weights.max_total = 1024.into();
+			})
+			.build_or_panic()
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const TransactionByteFee: Balance = 1;
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = frame_support::traits::AllowAll;
+	type BlockWeights = BlockWeights;
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 10;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = Balance;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type WeightInfo = ();
+}
+
+impl pallet_transaction_payment::Config for Runtime {
+	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = IdentityFee<Balance>;
+	type FeeMultiplierUpdate = ();
+}
+
+parameter_types! {
+	pub const AssetDeposit: u64 = 2;
+	pub const MetadataDeposit: u64 = 0;
+	pub const StringLimit: u32 = 20;
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDeposit;
+	type MetadataDepositPerByte = MetadataDeposit;
+	type ApprovalDeposit = MetadataDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
+thread_local! {
+	static CREDITS: RefCell<Vec<Balance>> = RefCell::new(vec![]);
+}
+
+/// Records the credits it is handed, then burns them.
+pub struct RecordCredits;
+impl HandleCredit<AccountId, Assets> for RecordCredits {
+	fn handle_credit(credit: CreditOf<AccountId, Assets>) {
+		CREDITS.with(|c| c.borrow_mut().push(credit.peek()));
+	}
+}
+
+fn credits() -> Vec<Balance> {
+	CREDITS.with(|c| c.borrow().clone())
+}
+
+impl Config for Runtime {
+	type Fungibles = Assets;
+	type OnChargeAssetTransaction = FungiblesAdapter<
+		pallet_assets::BalanceToAssetBalance<Balances, Runtime, ConvertInto>,
+		RecordCredits,
+	>;
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	CREDITS.with(|c| c.borrow_mut().clear());
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(1, 1_000), (2, 1_000)],
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
+}
+
+/// Create the sufficient asset `id`, with a minimum balance of `min_balance`, and give `who`
+/// `amount` of it.
+fn create_asset(id: u32, min_balance: Balance, who: AccountId, amount: Balance) {
+	assert_ok!(Assets::force_create(Origin::root(), id, 42, true, min_balance));
+	assert_ok!(Assets::mint_into(id, &who, amount));
+}
+
+fn info_from_weight(w: Weight) -> DispatchInfo {
+	// pays_fee: Pays::Yes -- class: DispatchClass::Normal
+	DispatchInfo { weight: w, ..Default::default() }
+}
+
+fn post_info_from_weight(w: Weight) -> PostDispatchInfo {
+	PostDispatchInfo {
+		actual_weight: Some(w),
+		pays_fee: Default::default(),
+	}
+}
+
+fn default_post_info() -> PostDispatchInfo {
+	PostDispatchInfo {
+		actual_weight: None,
+		pays_fee: Default::default(),
+	}
+}
+
+#[test]
+fn transaction_payment_in_native_possible() {
+	new_test_ext().execute_with(|| {
+		let len = 10;
+		// 5 base + 10 length + 5 weight.
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, None)
+			.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+			.unwrap();
+		assert_eq!(Balances::free_balance(1), 1_000 - 20);
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(5),
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Balances::free_balance(1), 1_000 - 20);
+
+		// Unused weight is refunded in the native currency.
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, None)
+			.pre_dispatch(&2, CALL, &info_from_weight(100), len)
+			.unwrap();
+		assert_eq!(Balances::free_balance(2), 1_000 - 115);
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(100),
+			&post_info_from_weight(50),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Balances::free_balance(2), 1_000 - 65);
+		assert!(credits().is_empty());
+	});
+}
+
+#[test]
+fn transaction_payment_in_asset_possible() {
+	new_test_ext().execute_with(|| {
+		// The native minimum balance is 10, so the asset is worth five times the native currency.
+		create_asset(1, 2, 3, 1_000);
+		let len = 10;
+		// 5 base + 10 length + 5 weight, converted at a rate of 2 / 10.
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(1))
+			.pre_dispatch(&3, CALL, &info_from_weight(5), len)
+			.unwrap();
+		assert_eq!(Assets::balance(1, 3), 1_000 - 4);
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(5),
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(1, 3), 1_000 - 4);
+		// The whole fee is handed over, and no tip.
+		assert_eq!(credits(), vec![4, 0]);
+		assert_eq!(Assets::total_supply(1), 1_000 - 4);
+	});
+}
+
+#[test]
+fn unused_weight_is_refunded_in_asset() {
+	new_test_ext().execute_with(|| {
+		create_asset(1, 2, 3, 1_000);
+		let len = 10;
+		// 5 base + 10 length + 100 weight.
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(1))
+			.pre_dispatch(&3, CALL, &info_from_weight(100), len)
+			.unwrap();
+		assert_eq!(Assets::balance(1, 3), 1_000 - 23);
+		// 5 base + 10 length + 50 weight.
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(100),
+			&post_info_from_weight(50),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(1, 3), 1_000 - 13);
+		assert_eq!(credits(), vec![13, 0]);
+	});
+}
+
+#[test]
+fn tip_is_handed_over_separately() {
+	new_test_ext().execute_with(|| {
+		create_asset(1, 2, 3, 1_000);
+		let len = 10;
+		// 5 base + 10 length + 5 weight + 10 tip.
+		let pre = ChargeAssetTxPayment::<Runtime>::from(10, Some(1))
+			.pre_dispatch(&3, CALL, &info_from_weight(100), len)
+			.unwrap();
+		assert_eq!(Assets::balance(1, 3), 1_000 - 25);
+		// The tip is never refunded.
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(100),
+			&post_info_from_weight(5),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(1, 3), 1_000 - 6);
+		assert_eq!(credits(), vec![4, 2]);
+	});
+}
+
+#[test]
+fn converted_fee_is_never_zero_if_fee_is_not_zero() {
+	new_test_ext().execute_with(|| {
+		// The asset is worth ten times the native currency.
+		create_asset(1, 1, 3, 1_000);
+		// 5 base, which is worth less than one unit of the asset.
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(1))
+			.pre_dispatch(&3, CALL, &info_from_weight(0), 0)
+			.unwrap();
+		assert_eq!(Assets::balance(1, 3), 1_000 - 1);
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(0),
+			&default_post_info(),
+			0,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(1, 3), 1_000 - 1);
+	});
+}
+
+#[test]
+fn payment_not_possible_without_enough_asset() {
+	new_test_ext().execute_with(|| {
+		create_asset(1, 2, 3, 10);
+		// The fee of 100 converts to 20, which is more than the balance.
+		assert!(ChargeAssetTxPayment::<Runtime>::from(0, Some(1))
+			.pre_dispatch(&3, CALL, &info_from_weight(85), 10)
+			.is_err());
+		assert_eq!(Assets::balance(1, 3), 10);
+
+		// The asset must exist.
+		assert!(ChargeAssetTxPayment::<Runtime>::from(0, Some(2))
+			.pre_dispatch(&3, CALL, &info_from_weight(5), 10)
+			.is_err());
+
+		// The asset must be sufficient for its minimum balance to be a reliable price.
+		assert_ok!(Assets::force_create(Origin::root(), 3, 42, false, 2));
+		assert_ok!(Assets::mint_into(3, &1, 1_000));
+		assert!(ChargeAssetTxPayment::<Runtime>::from(0, Some(3))
+			.pre_dispatch(&1, CALL, &info_from_weight(5), 10)
+			.is_err());
+		assert_eq!(Assets::balance(3, 1), 1_000);
+	});
+}
+
+#[test]
+fn no_fee_no_payment() {
+	new_test_ext().execute_with(|| {
+		create_asset(1, 2, 3, 1_000);
+		let info = DispatchInfo { weight: 5, pays_fee: Pays::No, ..Default::default() };
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(1))
+			.pre_dispatch(&3, CALL, &info, 10)
+			.unwrap();
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info,
+			&default_post_info(),
+			10,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(1, 3), 1_000);
+		assert!(credits().is_empty());
+	});
+}
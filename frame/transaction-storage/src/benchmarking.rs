@@ -104,7 +104,8 @@ benchmarks! {
 	}: _(RawOrigin::Signed(caller.clone()), vec![0u8; l as usize])
 	verify {
 		assert!(!BlockTransactions::<T>::get().is_empty());
-		assert_last_event::<T>(Event::Stored(0).into());
+		let cid = unixfs::build_dag(&vec![0u8; l as usize], sp_io::hashing::sha2_256).pop().unwrap().cid();
+		assert_last_event::<T>(Event::Stored(0, cid).into());
 	}
 
 	renew {
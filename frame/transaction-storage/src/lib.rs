@@ -38,7 +38,7 @@ use codec::{Encode, Decode};
 use sp_runtime::traits::{Saturating, BlakeTwo256, Hash, Zero, One};
 use sp_transaction_storage_proof::{
 	TransactionStorageProof, InherentError,
-	random_chunk, encode_index,
+	random_chunk, encode_index, unixfs,
 	CHUNK_SIZE, INHERENT_IDENTIFIER, DEFAULT_STORAGE_PERIOD,
 };
 
@@ -61,7 +61,8 @@ pub const DEFAULT_MAX_BLOCK_TRANSACTIONS: u32 = 512;
 pub struct TransactionInfo {
 	/// Chunk trie root.
 	chunk_root: <BlakeTwo256 as Hash>::Output,
-	/// Plain hash of indexed data.
+	/// Sha2-256 digest of the root block of the UnixFS DAG of indexed data. Indexed data is
+	/// stored by the client under this hash.
 	content_hash: <BlakeTwo256 as Hash>::Output,
 	/// Size of indexed data in bytes.
 	size: u32,
@@ -188,7 +189,10 @@ pub mod pallet {
 			let chunks = data.chunks(CHUNK_SIZE).map(|c| c.to_vec()).collect();
 			let root = sp_io::trie::blake2_256_ordered_root(chunks);
 
-			let content_hash = sp_io::hashing::blake2_256(&data);
+			// Index data under the digest of its IPFS root block, so that it can be retrieved by CID.
+			let dag_root = unixfs::build_dag(&data, sp_io::hashing::sha2_256).pop()
+				.expect("data is not empty, so its DAG has a root; qed");
+			let content_hash = dag_root.digest;
 			let extrinsic_index = <frame_system::Pallet<T>>::extrinsic_index().ok_or_else(
 				|| Error::<T>::BadContext)?;
 			sp_io::transaction_index::index(extrinsic_index, data.len() as u32, content_hash);
@@ -208,7 +212,7 @@ pub mod pallet {
 				});
 				Ok(())
 			})?;
-			Self::deposit_event(Event::Stored(index));
+			Self::deposit_event(Event::Stored(index, dag_root.cid()));
 			Ok(())
 		}
 
@@ -306,8 +310,9 @@ pub mod pallet {
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Stored data under specified index.
-		Stored(u32),
+		/// Stored data under specified index, retrievable over bitswap by the given CID.
+		/// \[index, cid\]
+		Stored(u32, Vec<u8>),
 		/// Renewed data under specified index.
 		Renewed(u32),
 		/// Storage proof was successfully checked.
@@ -378,7 +383,6 @@ pub mod pallet {
 		pub max_transaction_size: u32,
 	}
 
-	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
 			Self {
@@ -155,3 +155,15 @@ fn renews_data() {
 	});
 }
 
+#[test]
+fn indexes_data_by_root_cid() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let data = vec![7u8; 2 * unixfs::LEAF_SIZE + 1];
+		assert_ok!(TransactionStorage::<Test>::store(RawOrigin::Signed(1).into(), data.clone()));
+		let root = unixfs::build_dag(&data, sp_io::hashing::sha2_256).pop().unwrap();
+		assert_eq!(root.codec, unixfs::DAG_PB_CODEC);
+		assert_eq!(BlockTransactions::<Test>::get()[0].content_hash, root.digest.into());
+		System::assert_last_event(mock::Event::TransactionStorage(crate::Event::Stored(0, root.cid())));
+	});
+}
@@ -22,8 +22,9 @@
 use super::{*, Pallet as Treasury};
 
 use frame_benchmarking::{benchmarks_instance_pallet, account, impl_benchmark_test_suite};
-use frame_support::{traits::OnInitialize, ensure};
+use frame_support::{traits::{EnsureOrigin, OnInitialize, UnfilteredDispatchable}, ensure};
 use frame_system::RawOrigin;
+use sp_runtime::traits::TrailingZeroInput;
 
 const SEED: u32 = 0;
 
@@ -57,6 +58,27 @@ fn create_approved_proposals<T: Config<I>, I: 'static>(n: u32) -> Result<(), &'s
 	Ok(())
 }
 
+// Some asset id. The treasury account does not need to hold any of it.
+fn asset_id<T: Config<I>, I: 'static>() -> T::AssetId {
+	T::AssetId::decode(&mut TrailingZeroInput::new(&[][..]))
+		.expect("infinite length input; no invalid inputs for type; qed")
+}
+
+// Create `n` approved asset spends.
+fn create_asset_spends<T: Config<I>, I: 'static>(n: u32) -> Result<(), &'static str> {
+	for i in 0 .. n {
+		let beneficiary = account("beneficiary", i, SEED);
+		Treasury::<T, I>::spend_asset(
+			T::SpendAssetOrigin::successful_origin(),
+			asset_id::<T, I>(),
+			100u32.into(),
+			T::Lookup::unlookup(beneficiary),
+		)?;
+	}
+	ensure!(<AssetApprovals<T, I>>::get().len() == n as usize, "Not all approved");
+	Ok(())
+}
+
 fn setup_pot_account<T: Config<I>, I: 'static>() {
 	let pot_account = Treasury::<T, I>::account_id();
 	let value = T::Currency::minimum_balance().saturating_mul(1_000_000_000u32.into());
@@ -100,6 +122,31 @@ benchmarks_instance_pallet! {
 	}: {
 		Treasury::<T, _>::on_initialize(T::BlockNumber::zero());
 	}
+
+	spend_asset {
+		let beneficiary_lookup = T::Lookup::unlookup(account("beneficiary", SEED, SEED));
+		let origin = T::SpendAssetOrigin::successful_origin();
+		let call = Call::<T, I>::spend_asset(asset_id::<T, _>(), 100u32.into(), beneficiary_lookup);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		ensure!(<AssetSpends<T, I>>::contains_key(0), "Asset spend was not approved");
+	}
+
+	void_asset_spend {
+		let p in 1 .. T::MaxApprovals::get();
+		create_asset_spends::<T, _>(p)?;
+	}: _(RawOrigin::Root, 0)
+	verify {
+		ensure!(<AssetApprovals<T, I>>::get().len() == (p - 1) as usize, "Asset spend was not voided");
+	}
+
+	on_initialize_asset_spends {
+		let p in 0 .. T::MaxApprovals::get();
+		// The treasury holds none of the asset, so every spend is checked and kept.
+		create_asset_spends::<T, _>(p)?;
+	}: {
+		Treasury::<T, _>::spend_assets();
+	}
 }
 
 impl_benchmark_test_suite!(
@@ -41,6 +41,8 @@
 //! - **Deposit:** Funds that a proposer must lock when making a proposal. The deposit will be
 //!   returned or slashed if the proposal is approved or rejected respectively.
 //! - **Pot:** Unspent funds accumulated by the treasury pallet.
+//! - **Asset Spend:** An approved payment of a non-native asset held by the treasury account.
+//!   Asset spends are kept in their own ledger and are never subject to the burn.
 //!
 //! ## Interface
 //!
@@ -51,6 +53,10 @@
 //! - `reject_proposal` - Reject a proposal, slashing the deposit.
 //! - `approve_proposal` - Accept the proposal, returning the deposit.
 //!
+//! Spending of non-native assets:
+//! - `spend_asset` - Approve a spend of a non-native asset, paid out at the next spend period.
+//! - `void_asset_spend` - Remove an approved spend of a non-native asset before it is paid out.
+//!
 //! ## GenesisConfig
 //!
 //! The Treasury pallet depends on the [`GenesisConfig`].
@@ -66,16 +72,18 @@ use codec::{Encode, Decode, MaxEncodedLen};
 
 use sp_std::prelude::*;
 use sp_runtime::{
-	Permill, RuntimeDebug,
+	DispatchError, Permill, RuntimeDebug, TokenError,
 	traits::{
-		Zero, StaticLookup, AccountIdConversion, Saturating
+		Zero, StaticLookup, AccountIdConversion, Saturating, AtLeast32BitUnsigned
 	}
 };
 
-use frame_support::{print, PalletId};
+use frame_support::{print, PalletId, Parameter};
 use frame_support::traits::{
 	Currency, Get, Imbalance, OnUnbalanced, ExistenceRequirement::KeepAlive,
-	ReservableCurrency, WithdrawReasons
+	ReservableCurrency, WithdrawReasons,
+	tokens::{DepositConsequence, WithdrawConsequence},
+	tokens::fungibles::{self, Inspect as _, Transfer as _},
 };
 use frame_support::weights::Weight;
 
@@ -114,6 +122,76 @@ pub trait SpendFunds<T: Config<I>, I: 'static = ()> {
 /// An index of a proposal. Just a `u32`.
 pub type ProposalIndex = u32;
 
+/// An index of an approved spend of a non-native asset. Just a `u32`.
+pub type AssetSpendIndex = u32;
+
+/// An approved spend of a non-native asset.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, MaxEncodedLen, RuntimeDebug)]
+pub struct AssetSpend<AssetId, AccountId, Balance> {
+	/// The asset to be paid out.
+	asset_id: AssetId,
+	/// The amount of the asset to be paid out.
+	amount: Balance,
+	/// The account to whom the asset should be paid.
+	beneficiary: AccountId,
+}
+
+/// Non-native assets of which the treasury never holds anything.
+///
+/// Use it as `Config::Assets` in runtimes where the treasury only spends the native currency.
+/// Asset spends can still be approved, but are never paid out.
+pub struct NoAssets<AssetId, Balance>(sp_std::marker::PhantomData<(AssetId, Balance)>);
+
+impl<AccountId, AssetId, Balance> fungibles::Inspect<AccountId> for NoAssets<AssetId, Balance>
+where
+	AssetId: Parameter + Copy,
+	Balance: Parameter + AtLeast32BitUnsigned + Default + Copy,
+{
+	type AssetId = AssetId;
+	type Balance = Balance;
+
+	fn total_issuance(_: AssetId) -> Balance {
+		Zero::zero()
+	}
+
+	fn minimum_balance(_: AssetId) -> Balance {
+		Zero::zero()
+	}
+
+	fn balance(_: AssetId, _: &AccountId) -> Balance {
+		Zero::zero()
+	}
+
+	fn reducible_balance(_: AssetId, _: &AccountId, _: bool) -> Balance {
+		Zero::zero()
+	}
+
+	fn can_deposit(_: AssetId, _: &AccountId, _: Balance) -> DepositConsequence {
+		DepositConsequence::UnknownAsset
+	}
+
+	fn can_withdraw(_: AssetId, _: &AccountId, _: Balance) -> WithdrawConsequence<Balance> {
+		WithdrawConsequence::UnknownAsset
+	}
+}
+
+impl<AccountId, AssetId, Balance> fungibles::Transfer<AccountId> for NoAssets<AssetId, Balance>
+where
+	AssetId: Parameter + Copy,
+	Balance: Parameter + AtLeast32BitUnsigned + Default + Copy,
+{
+	fn transfer(
+		_: AssetId,
+		_: &AccountId,
+		_: &AccountId,
+		_: Balance,
+		_: bool,
+	) -> Result<Balance, DispatchError> {
+		Err(TokenError::UnknownAsset.into())
+	}
+}
+
 /// A spending proposal.
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Encode, Decode, Clone, PartialEq, Eq, MaxEncodedLen, RuntimeDebug)]
@@ -187,8 +265,26 @@ pub mod pallet {
 		type SpendFunds: SpendFunds<Self, I>;
 
 		/// The maximum number of approvals that can wait in the spending queue.
+		///
+		/// This also bounds the queue of approved asset spends.
 		#[pallet::constant]
 		type MaxApprovals: Get<u32>;
+
+		/// Origin from which spends of non-native assets must be approved.
+		type SpendAssetOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Identifier of the non-native assets which the treasury may spend.
+		type AssetId: Member + Parameter + Copy + MaxEncodedLen;
+
+		/// The balance type of the non-native assets.
+		type AssetBalance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy + MaxEncodedLen;
+
+		/// The non-native assets held by the treasury account.
+		type Assets: fungibles::Transfer<
+			Self::AccountId,
+			AssetId = Self::AssetId,
+			Balance = Self::AssetBalance,
+		>;
 	}
 
 	/// Number of proposals that have been made.
@@ -216,10 +312,34 @@ pub mod pallet {
 		ValueQuery
 	>;
 
+	/// Number of asset spends that have been approved.
+	#[pallet::storage]
+	#[pallet::getter(fn asset_spend_count)]
+	pub(crate) type AssetSpendCount<T, I = ()> = StorageValue<_, AssetSpendIndex, ValueQuery>;
+
+	/// Asset spends that have been approved but not yet paid out.
+	#[pallet::storage]
+	#[pallet::getter(fn asset_spends)]
+	pub type AssetSpends<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Twox64Concat,
+		AssetSpendIndex,
+		AssetSpend<T::AssetId, T::AccountId, T::AssetBalance>,
+		OptionQuery
+	>;
+
+	/// Asset spend indices in the order in which they are to be paid out.
+	#[pallet::storage]
+	#[pallet::getter(fn asset_approvals)]
+	pub type AssetApprovals<T: Config<I>, I: 'static = ()> = StorageValue<
+		_,
+		BoundedVec<AssetSpendIndex, T::MaxApprovals>,
+		ValueQuery
+	>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig;
 
-	#[cfg(feature = "std")]
 	impl Default for GenesisConfig {
 		fn default() -> Self {
 			Self
@@ -252,7 +372,12 @@ pub mod pallet {
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T, I> = "Balance")]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		BalanceOf<T, I> = "Balance",
+		T::AssetId = "AssetId",
+		T::AssetBalance = "AssetBalance"
+	)]
 	pub enum Event<T: Config<I>, I: 'static = ()> {
 		/// New proposal. \[proposal_index\]
 		Proposed(ProposalIndex),
@@ -269,6 +394,17 @@ pub mod pallet {
 		Rollover(BalanceOf<T, I>),
 		/// Some funds have been deposited. \[deposit\]
 		Deposit(BalanceOf<T, I>),
+		/// A spend of a non-native asset was approved.
+		/// \[index, asset_id, amount, beneficiary\]
+		AssetSpendApproved(AssetSpendIndex, T::AssetId, T::AssetBalance, T::AccountId),
+		/// A spend of a non-native asset was paid out.
+		/// \[index, asset_id, amount, beneficiary\]
+		AssetSpendPaid(AssetSpendIndex, T::AssetId, T::AssetBalance, T::AccountId),
+		/// The payout of an affordable asset spend failed; it will be retried at the next spend
+		/// period. \[index\]
+		AssetSpendFailed(AssetSpendIndex),
+		/// An asset spend was removed before being paid out. \[index\]
+		AssetSpendVoided(AssetSpendIndex),
 	}
 
 	/// Old name generated by `decl_event`.
@@ -284,6 +420,10 @@ pub mod pallet {
 		InvalidIndex,
 		/// Too many approvals in the queue.
 		TooManyApprovals,
+		/// An asset spend of a zero amount.
+		ZeroAmount,
+		/// No more asset spends can be approved.
+		AssetSpendIndexOverflow,
 	}
 
 	#[pallet::hooks]
@@ -294,11 +434,13 @@ pub mod pallet {
 		/// - Db reads and writes per approval:
 		///   `Proposals`, `proposer account data`, `beneficiary account data`
 		/// - The weight is overestimated if some approvals got missed.
+		/// - Db reads and writes per asset spend:
+		///   `AssetSpends`, `treasury asset account`, `beneficiary asset account`
 		/// # </weight>
 		fn on_initialize(n: T::BlockNumber) -> Weight {
 			// Check to see if we should spend some funds!
 			if (n % T::SpendPeriod::get()).is_zero() {
-				Self::spend_funds()
+				Self::spend_funds().saturating_add(Self::spend_assets())
 			} else {
 				0
 			}
@@ -383,6 +525,64 @@ pub mod pallet {
 			Approvals::<T, I>::try_append(proposal_id).map_err(|_| Error::<T, I>::TooManyApprovals)?;
 			Ok(())
 		}
+
+		/// Approve a spend of `amount` of the non-native asset `asset_id` to `beneficiary`. The
+		/// asset is paid out of the treasury account at the next spend period which it can be
+		/// afforded in.
+		///
+		/// May only be called from `T::SpendAssetOrigin`.
+		///
+		/// # <weight>
+		/// - Complexity: O(1).
+		/// - DbReads: `AssetSpendCount`, `AssetApprovals`
+		/// - DbWrites: `AssetSpendCount`, `AssetSpends`, `AssetApprovals`
+		/// # </weight>
+		#[pallet::weight((T::WeightInfo::spend_asset(), DispatchClass::Operational))]
+		pub fn spend_asset(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			#[pallet::compact] amount: T::AssetBalance,
+			beneficiary: <T::Lookup as StaticLookup>::Source
+		) -> DispatchResult {
+			T::SpendAssetOrigin::ensure_origin(origin)?;
+			ensure!(!amount.is_zero(), Error::<T, I>::ZeroAmount);
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			let index = Self::asset_spend_count();
+			let next_index = index.checked_add(1).ok_or(Error::<T, I>::AssetSpendIndexOverflow)?;
+			AssetApprovals::<T, I>::try_append(index).map_err(|_| Error::<T, I>::TooManyApprovals)?;
+			<AssetSpendCount<T, I>>::put(next_index);
+			<AssetSpends<T, I>>::insert(
+				index,
+				AssetSpend { asset_id, amount, beneficiary: beneficiary.clone() },
+			);
+
+			Self::deposit_event(Event::AssetSpendApproved(index, asset_id, amount, beneficiary));
+			Ok(())
+		}
+
+		/// Remove an approved asset spend which has not yet been paid out.
+		///
+		/// May only be called from `T::RejectOrigin`.
+		///
+		/// # <weight>
+		/// - Complexity: O(A) where `A` is the number of pending asset spends.
+		/// - DbReads: `AssetSpends`, `AssetApprovals`
+		/// - DbWrites: `AssetSpends`, `AssetApprovals`
+		/// # </weight>
+		#[pallet::weight((T::WeightInfo::void_asset_spend(T::MaxApprovals::get()), DispatchClass::Operational))]
+		pub fn void_asset_spend(
+			origin: OriginFor<T>,
+			#[pallet::compact] index: AssetSpendIndex
+		) -> DispatchResult {
+			T::RejectOrigin::ensure_origin(origin)?;
+
+			<AssetSpends<T, I>>::take(index).ok_or(Error::<T, I>::InvalidIndex)?;
+			AssetApprovals::<T, I>::mutate(|v| v.retain(|&i| i != index));
+
+			Self::deposit_event(Event::AssetSpendVoided(index));
+			Ok(())
+		}
 	}
 }
 
@@ -477,6 +677,53 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		total_weight
 	}
 
+	/// Pay out the approved asset spends, in order of approval. Returns the weight used.
+	///
+	/// A spend which the treasury account cannot afford stays in the queue until a later spend
+	/// period. Unlike the native pot, no part of the non-native assets is ever burnt.
+	pub fn spend_assets() -> Weight {
+		let account_id = Self::account_id();
+		let spends_len = AssetApprovals::<T, I>::mutate(|v| {
+			let spends_approvals_len = v.len() as u32;
+			v.retain(|&index| {
+				let spend = match Self::asset_spends(index) {
+					Some(spend) => spend,
+					// Should never happen, but there is nothing to pay out.
+					None => return false,
+				};
+				let available = T::Assets::reducible_balance(spend.asset_id, &account_id, false);
+				if spend.amount > available {
+					return true
+				}
+				match T::Assets::transfer(
+					spend.asset_id,
+					&account_id,
+					&spend.beneficiary,
+					spend.amount,
+					false,
+				) {
+					Ok(_) => {
+						<AssetSpends<T, I>>::remove(index);
+						Self::deposit_event(Event::AssetSpendPaid(
+							index,
+							spend.asset_id,
+							spend.amount,
+							spend.beneficiary,
+						));
+						false
+					},
+					Err(_) => {
+						Self::deposit_event(Event::AssetSpendFailed(index));
+						true
+					},
+				}
+			});
+			spends_approvals_len
+		});
+
+		T::WeightInfo::on_initialize_asset_spends(spends_len)
+	}
+
 	/// Return the amount of money in the pot.
 	// The existential deposit is not part of the pot so treasury account never gets deleted.
 	pub fn pot() -> BalanceOf<T, I> {
@@ -24,11 +24,11 @@ use std::cell::RefCell;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BadOrigin, BlakeTwo256, IdentityLookup},
 };
 
 use frame_support::{
-	assert_noop, assert_ok, parameter_types,
+	assert_noop, assert_ok, parameter_types, traits::tokens::fungibles::Inspect,
 	traits::OnInitialize, PalletId, pallet_prelude::GenesisBuild,
 };
 
@@ -47,6 +47,7 @@ frame_support::construct_runtime!(
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Treasury: treasury::{Pallet, Call, Storage, Config, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -94,6 +95,28 @@ impl pallet_balances::Config for Test {
 	type AccountStore = System;
 	type WeightInfo = ();
 }
+parameter_types! {
+	pub const AssetDeposit: u64 = 1;
+	pub const ApprovalDeposit: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+}
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u128>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type WeightInfo = ();
+	type Extra = ();
+}
 thread_local! {
 	static TEN_TO_FOURTEEN: RefCell<Vec<u128>> = RefCell::new(vec![10,11,12,13,14]);
 }
@@ -123,6 +146,10 @@ impl Config for Test {
 	type WeightInfo = ();
 	type SpendFunds = ();
 	type MaxApprovals = MaxApprovals;
+	type SpendAssetOrigin = frame_system::EnsureRoot<u128>;
+	type AssetId = u32;
+	type AssetBalance = u64;
+	type Assets = Assets;
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -382,3 +409,131 @@ fn max_approvals_limited() {
 		assert_noop!(Treasury::approve_proposal(Origin::root(), 0), Error::<Test, _>::TooManyApprovals);
 	});
 }
+
+// Create asset `0`, issued by account `0`.
+fn create_asset() {
+	assert_ok!(Assets::force_create(Origin::root(), 0, 0, true, 1));
+}
+
+// Give `amount` of asset `0` to the treasury account.
+fn fund_treasury_asset(amount: u64) {
+	assert_ok!(Assets::mint(Origin::signed(0), 0, Treasury::account_id(), amount));
+}
+
+#[test]
+fn spend_asset_requires_spend_asset_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Treasury::spend_asset(Origin::signed(0), 0, 10, 3), BadOrigin);
+		assert_eq!(Treasury::asset_spend_count(), 0);
+	});
+}
+
+#[test]
+fn spend_asset_rejects_zero_amount_and_index_overflow() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Treasury::spend_asset(Origin::root(), 0, 0, 3),
+			Error::<Test, _>::ZeroAmount,
+		);
+
+		AssetSpendCount::<Test>::put(AssetSpendIndex::max_value());
+		assert_noop!(
+			Treasury::spend_asset(Origin::root(), 0, 10, 3),
+			Error::<Test, _>::AssetSpendIndexOverflow,
+		);
+	});
+}
+
+#[test]
+fn asset_spend_paid_out_on_spend_period() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		create_asset();
+		fund_treasury_asset(100);
+
+		assert_ok!(Treasury::spend_asset(Origin::root(), 0, 30, 3));
+		assert_eq!(Treasury::asset_approvals().into_inner(), vec![0]);
+
+		<Treasury as OnInitialize<u64>>::on_initialize(1);
+		assert_eq!(Assets::balance(0, 3), 0);
+
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+		assert_eq!(Assets::balance(0, 3), 30);
+		assert_eq!(Assets::balance(0, Treasury::account_id()), 70);
+		assert_eq!(Treasury::asset_spends(0), None);
+		assert!(Treasury::asset_approvals().is_empty());
+		// The native pot is burnt as usual, the asset is not.
+		assert_eq!(Treasury::pot(), 50);
+		assert_eq!(Assets::total_issuance(0), 100);
+	});
+}
+
+#[test]
+fn unaffordable_asset_spend_waits_for_funds() {
+	new_test_ext().execute_with(|| {
+		create_asset();
+		fund_treasury_asset(10);
+		assert_ok!(Treasury::spend_asset(Origin::root(), 0, 30, 3));
+		assert_ok!(Treasury::spend_asset(Origin::root(), 0, 5, 4));
+
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+		// The second spend does not have to wait for the first one.
+		assert_eq!(Assets::balance(0, 3), 0);
+		assert_eq!(Assets::balance(0, 4), 5);
+		assert_eq!(Treasury::asset_approvals().into_inner(), vec![0]);
+
+		fund_treasury_asset(30);
+		<Treasury as OnInitialize<u64>>::on_initialize(4);
+		assert_eq!(Assets::balance(0, 3), 30);
+		assert_eq!(Assets::balance(0, Treasury::account_id()), 5);
+		assert!(Treasury::asset_approvals().is_empty());
+	});
+}
+
+#[test]
+fn failed_asset_spend_is_retried() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 0, true, 10));
+		fund_treasury_asset(100);
+		// The payout is below the minimum balance of the asset, so it fails.
+		assert_ok!(Treasury::spend_asset(Origin::root(), 0, 5, 3));
+
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+		assert!(Treasury::asset_spends(0).is_some());
+		assert_eq!(Treasury::asset_approvals().into_inner(), vec![0]);
+		System::assert_last_event(treasury::Event::AssetSpendFailed(0).into());
+	});
+}
+
+#[test]
+fn void_asset_spend_works() {
+	new_test_ext().execute_with(|| {
+		create_asset();
+		fund_treasury_asset(100);
+		assert_ok!(Treasury::spend_asset(Origin::root(), 0, 30, 3));
+
+		assert_noop!(Treasury::void_asset_spend(Origin::signed(0), 0), BadOrigin);
+		assert_ok!(Treasury::void_asset_spend(Origin::root(), 0));
+		assert_noop!(Treasury::void_asset_spend(Origin::root(), 0), Error::<Test, _>::InvalidIndex);
+
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+		assert_eq!(Assets::balance(0, 3), 0);
+		assert_eq!(Assets::balance(0, Treasury::account_id()), 100);
+	});
+}
+
+#[test]
+fn asset_approvals_limited() {
+	new_test_ext().execute_with(|| {
+		for _ in 0 .. MaxApprovals::get() {
+			assert_ok!(Treasury::spend_asset(Origin::root(), 0, 100, 3));
+		}
+
+		// One too many will fail
+		assert_noop!(
+			Treasury::spend_asset(Origin::root(), 0, 100, 3),
+			Error::<Test, _>::TooManyApprovals,
+		);
+	});
+}
@@ -48,6 +48,9 @@ pub trait WeightInfo {
 	fn reject_proposal() -> Weight;
 	fn approve_proposal(p: u32, ) -> Weight;
 	fn on_initialize_proposals(p: u32, ) -> Weight;
+	fn spend_asset() -> Weight;
+	fn void_asset_spend(p: u32, ) -> Weight;
+	fn on_initialize_asset_spends(p: u32, ) -> Weight;
 }
 
 /// Weights for pallet_treasury using the Substrate node and recommended hardware.
@@ -79,6 +82,27 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(p as Weight)))
 	}
+	fn spend_asset() -> Weight {
+		(17_862_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn void_asset_spend(p: u32, ) -> Weight {
+		(16_104_000 as Weight)
+			// Standard Error: 2_000
+			.saturating_add((121_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn on_initialize_asset_spends(p: u32, ) -> Weight {
+		(6_921_000 as Weight)
+			// Standard Error: 21_000
+			.saturating_add((64_118_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(p as Weight)))
+	}
 }
 
 // For backwards compatibility and tests
@@ -109,4 +133,25 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(p as Weight)))
 	}
+	fn spend_asset() -> Weight {
+		(17_862_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn void_asset_spend(p: u32, ) -> Weight {
+		(16_104_000 as Weight)
+			// Standard Error: 2_000
+			.saturating_add((121_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn on_initialize_asset_spends(p: u32, ) -> Weight {
+		(6_921_000 as Weight)
+			// Standard Error: 21_000
+			.saturating_add((64_118_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(p as Weight)))
+	}
 }
@@ -26,6 +26,10 @@
 //! pallet ensures that there is a lock in place preventing the balance to drop below the *unvested*
 //! amount for any reason other than transaction fee payment.
 //!
+//! An account can have up to `MaxVestingSchedules` vesting schedules at the same time, for example
+//! one per grant, each with its own start and rate. The locked amount is the sum of the amounts
+//! still locked by each schedule.
+//!
 //! As the amount vested increases over time, the amount unvested reduces. However, locks remain in
 //! place and explicit action is needed on behalf of the user to ensure that the amount locked is
 //! equivalent to the amount remaining to be vested. This is done through a dispatchable function,
@@ -41,10 +45,14 @@
 //! - `vest` - Update the lock, reducing it in line with the amount "vested" so far.
 //! - `vest_other` - Update the lock of another account, reducing it in line with the amount
 //!   "vested" so far.
+//! - `vested_transfer` - Transfer funds to an account and add a vesting schedule over them.
+//! - `force_vested_transfer` - Same as `vested_transfer`, from any account, with root origin.
+//! - `merge_schedules` - Merge two vesting schedules of the sender into a single one.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod benchmarking;
+mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -64,10 +72,13 @@ use frame_support::{
 use frame_system::{ensure_root, ensure_signed, pallet_prelude::*};
 pub use pallet::*;
 use sp_runtime::{
-	traits::{AtLeast32BitUnsigned, Convert, MaybeSerializeDeserialize, StaticLookup, Zero},
+	traits::{
+		AtLeast32BitUnsigned, Convert, MaybeSerializeDeserialize, One, Saturating, StaticLookup,
+		Zero,
+	},
 	RuntimeDebug,
 };
-use sp_std::{fmt::Debug, prelude::*};
+use sp_std::{convert::TryInto, fmt::Debug, prelude::*};
 pub use weights::WeightInfo;
 
 type BalanceOf<T> =
@@ -77,6 +88,23 @@ type MaxLocksOf<T> =
 
 const VESTING_ID: LockIdentifier = *b"vesting ";
 
+// A value placed in storage that represents the current version of the Vesting storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	/// A single vesting schedule per account.
+	V0,
+	/// Up to `MaxVestingSchedules` vesting schedules per account.
+	V1,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V0
+	}
+}
+
 /// Struct to encode the vesting schedule of an individual account.
 #[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
 pub struct VestingInfo<Balance, BlockNumber> {
@@ -108,6 +136,28 @@ impl<
 			Zero::zero()
 		}
 	}
+
+	/// Whether the schedule can be added to an account: it must lock something and unlock
+	/// something every block.
+	pub fn is_valid(&self) -> bool {
+		!self.locked.is_zero() && !self.per_block.is_zero()
+	}
+
+	/// Block number, converted to a balance, at which the schedule is fully vested.
+	pub fn ending_block_as_balance<
+		BlockNumberToBalance: Convert<BlockNumber, Balance>
+	>(&self) -> Balance {
+		let starting_block = BlockNumberToBalance::convert(self.starting_block);
+		// Schedules stored before validation was enforced may have a zero `per_block`.
+		let per_block = self.per_block.max(One::one());
+		let duration = if per_block >= self.locked {
+			One::one()
+		} else {
+			let remainder = if (self.locked % per_block).is_zero() { Zero::zero() } else { One::one() };
+			(self.locked / per_block).saturating_add(remainder)
+		};
+		starting_block.saturating_add(duration)
+	}
 }
 
 #[frame_support::pallet]
@@ -129,6 +179,10 @@ pub mod pallet {
 		#[pallet::constant]
 		type MinVestedTransfer: Get<BalanceOf<Self>>;
 
+		/// Maximum number of vesting schedules an account may have at a given moment.
+		#[pallet::constant]
+		type MaxVestingSchedules: Get<u32>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -140,9 +194,15 @@ pub mod pallet {
 		_,
 		Blake2_128Concat,
 		T::AccountId,
-		VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		BoundedVec<VestingInfo<BalanceOf<T>, T::BlockNumber>, T::MaxVestingSchedules>,
 	>;
 
+	/// Storage version of the pallet.
+	///
+	/// New networks start with latest version, as determined by the genesis build.
+	#[pallet::storage]
+	pub(crate) type StorageVersion<T> = StorageValue<_, Releases, ValueQuery>;
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	pub struct Pallet<T>(_);
@@ -152,7 +212,6 @@ pub mod pallet {
 		pub vesting: Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T>)>,
 	}
 
-	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
 			GenesisConfig {
@@ -164,13 +223,15 @@ pub mod pallet {
 	#[pallet::genesis_build]
 	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
-			use sp_runtime::traits::Saturating;
+			// Genesis uses the latest storage version.
+			StorageVersion::<T>::put(Releases::V1);
 
 			// Generate initial vesting configuration
 			// * who - Account which we are generating vesting configuration for
 			// * begin - Block when the account will start to vest
 			// * length - Number of blocks from `begin` until fully vested
 			// * liquid - Number of units which can be spent before vesting begins
+			// An account may appear several times, once per schedule.
 			for &(ref who, begin, length, liquid) in self.vesting.iter() {
 				let balance = T::Currency::free_balance(who);
 				assert!(!balance.is_zero(), "Currencies must be init'd before vesting");
@@ -179,13 +240,23 @@ pub mod pallet {
 				let length_as_balance = T::BlockNumberToBalance::convert(length);
 				let per_block = locked / length_as_balance.max(sp_runtime::traits::One::one());
 
-				Vesting::<T>::insert(who, VestingInfo {
+				let vesting_info = VestingInfo {
 					locked: locked,
 					per_block: per_block,
 					starting_block: begin
+				};
+				assert!(vesting_info.is_valid(), "Invalid VestingInfo params at genesis");
+
+				Vesting::<T>::try_append(who, vesting_info)
+					.expect("Too many vesting schedules at genesis.");
+				// The lock covers every schedule of the account so far.
+				let total_locked = Vesting::<T>::get(who).map_or(Zero::zero(), |schedules| {
+					schedules.iter().fold(Zero::zero(), |total: BalanceOf<T>, schedule| {
+						total.saturating_add(schedule.locked)
+					})
 				});
 				let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
-				T::Currency::set_lock(VESTING_ID, who, locked, reasons);
+				T::Currency::set_lock(VESTING_ID, who, total_locked, reasons);
 			}
 		}
 	}
@@ -207,10 +278,41 @@ pub mod pallet {
 	pub enum Error<T> {
 		/// The account given is not vesting.
 		NotVesting,
-		/// An existing vesting schedule already exists for this account that cannot be clobbered.
-		ExistingVestingSchedule,
+		/// The account already has `MaxVestingSchedules` vesting schedules and this call would
+		/// add another.
+		AtMaxVestingSchedules,
 		/// Amount being transferred is too low to create a vesting schedule.
 		AmountLow,
+		/// An index was out of bounds of the vesting schedules.
+		ScheduleIndexOutOfBounds,
+		/// Failed to create a new schedule because some parameter was invalid.
+		InvalidScheduleParams,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::<T>::get() == Releases::V0 {
+				StorageVersion::<T>::put(Releases::V1);
+				migrations::v1::migrate::<T>().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			migrations::v1::pre_migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			migrations::v1::post_migrate::<T>()
+		}
+
+		fn integrity_test() {
+			assert!(T::MaxVestingSchedules::get() > 0, "`MaxVestingSchedules` must be greater than 0");
+		}
 	}
 
 	#[pallet::call]
@@ -223,13 +325,14 @@ pub mod pallet {
 		/// Emits either `VestingCompleted` or `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of the account, bounded by
+		///   `MaxVestingSchedules`.
 		/// - DbWeight: 2 Reads, 2 Writes
 		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
 		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
 		/// # </weight>
-		#[pallet::weight(T::WeightInfo::vest_locked(MaxLocksOf::<T>::get())
-			.max(T::WeightInfo::vest_unlocked(MaxLocksOf::<T>::get()))
+		#[pallet::weight(T::WeightInfo::vest_locked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())
+			.max(T::WeightInfo::vest_unlocked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get()))
 		)]
 		pub fn vest(origin: OriginFor<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
@@ -246,13 +349,14 @@ pub mod pallet {
 		/// Emits either `VestingCompleted` or `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of the account, bounded by
+		///   `MaxVestingSchedules`.
 		/// - DbWeight: 3 Reads, 3 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account
 		///     - Writes: Vesting Storage, Balances Locks, Target Account
 		/// # </weight>
-		#[pallet::weight(T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get())
-			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get()))
+		#[pallet::weight(T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())
+			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get()))
 		)]
 		pub fn vest_other(origin: OriginFor<T>, target: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
 			ensure_signed(origin)?;
@@ -264,35 +368,28 @@ pub mod pallet {
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
 		///
-		/// Emits `VestingCreated`.
+		/// Emits `VestingUpdated`.
+		///
+		/// NOTE: This will unlock all schedules of the target that have fully vested.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of the target, bounded by
+		///   `MaxVestingSchedules`.
 		/// - DbWeight: 3 Reads, 3 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account, [Sender Account]
 		///     - Writes: Vesting Storage, Balances Locks, Target Account, [Sender Account]
 		/// # </weight>
-		#[pallet::weight(T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get()))]
+		#[pallet::weight(T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get()))]
 		pub fn vested_transfer(
 			origin: OriginFor<T>,
 			target: <T::Lookup as StaticLookup>::Source,
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			let transactor = ensure_signed(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
-			let who = T::Lookup::lookup(target)?;
-			ensure!(!Vesting::<T>::contains_key(&who), Error::<T>::ExistingVestingSchedule);
-
-			T::Currency::transfer(&transactor, &who, schedule.locked, ExistenceRequirement::AllowDeath)?;
-
-			Self::add_vesting_schedule(&who, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
-
-			Ok(())
+			let target = T::Lookup::lookup(target)?;
+			Self::do_vested_transfer(&transactor, &target, schedule)
 		}
 
 		/// Force a vested transfer.
@@ -301,18 +398,20 @@ pub mod pallet {
 		///
 		/// - `source`: The account whose funds should be transferred.
 		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
 		///
-		/// Emits `VestingCreated`.
+		/// Emits `VestingUpdated`.
+		///
+		/// NOTE: This will unlock all schedules of the target that have fully vested.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of the target, bounded by
+		///   `MaxVestingSchedules`.
 		/// - DbWeight: 4 Reads, 4 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account, Source Account
 		///     - Writes: Vesting Storage, Balances Locks, Target Account, Source Account
 		/// # </weight>
-		#[pallet::weight(T::WeightInfo::force_vested_transfer(MaxLocksOf::<T>::get()))]
+		#[pallet::weight(T::WeightInfo::force_vested_transfer(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get()))]
 		pub fn force_vested_transfer(
 			origin: OriginFor<T>,
 			source: <T::Lookup as StaticLookup>::Source,
@@ -320,41 +419,174 @@ pub mod pallet {
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
 			let target = T::Lookup::lookup(target)?;
 			let source = T::Lookup::lookup(source)?;
-			ensure!(!Vesting::<T>::contains_key(&target), Error::<T>::ExistingVestingSchedule);
+			Self::do_vested_transfer(&source, &target, schedule)
+		}
+
+		/// Merge two vesting schedules together, creating a new vesting schedule that unlocks over
+		/// the highest possible start and end blocks. If both schedules have already started the
+		/// current block will be used as the schedule start; with the caveat that if one schedule
+		/// is finished by the current block, the other will be treated as the new merged schedule,
+		/// unmodified.
+		///
+		/// NOTE: If `schedule1_index == schedule2_index` this is a no-op.
+		/// NOTE: This will unlock all schedules through the current block prior to merging.
+		/// NOTE: If both schedules have ended by the current block, no new schedule will be created
+		/// and both will be removed.
+		///
+		/// Merged schedule attributes:
+		/// - `starting_block`: `MAX(schedule1.starting_block, schedule2.starting_block,
+		///   current_block)`.
+		/// - `ending_block`: `MAX(schedule1.ending_block, schedule2.ending_block)`.
+		/// - `locked`: `schedule1.locked_at(current_block) + schedule2.locked_at(current_block)`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `schedule1_index`: index of the first schedule to merge.
+		/// - `schedule2_index`: index of the second schedule to merge.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of vesting schedules of the account, bounded by
+		///   `MaxVestingSchedules`.
+		/// - DbWeight: 2 Reads, 2 Writes
+		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
+		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::merge_schedules(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get()))]
+		pub fn merge_schedules(
+			origin: OriginFor<T>,
+			schedule1_index: u32,
+			schedule2_index: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			if schedule1_index == schedule2_index {
+				return Ok(());
+			}
 
-			T::Currency::transfer(&source, &target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+			let mut schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?.into_inner();
+			let (first, second) = (schedule1_index as usize, schedule2_index as usize);
+			ensure!(
+				first < schedules.len() && second < schedules.len(),
+				Error::<T>::ScheduleIndexOutOfBounds,
+			);
 
-			Self::add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
+			// Remove the highest index first so that the other one stays valid.
+			let schedule1 = schedules[first];
+			let schedule2 = schedules[second];
+			schedules.remove(first.max(second));
+			schedules.remove(first.min(second));
 
-			Ok(())
+			let now = <frame_system::Pallet<T>>::block_number();
+			if let Some(merged) = Self::merge_vesting_info(now, schedule1, schedule2) {
+				schedules.push(merged);
+			}
+
+			Self::write_schedules(&who, schedules)
 		}
 	}
 }
 
 impl<T: Config> Pallet<T> {
 	/// (Re)set or remove the pallet's currency lock on `who`'s account in accordance with their
-	/// current unvested amount.
+	/// current unvested amount, removing the schedules that are fully vested.
 	fn update_lock(who: T::AccountId) -> DispatchResult {
-		let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
-		let now = <frame_system::Pallet<T>>::block_number();
-		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+		let schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+		Self::write_schedules(&who, schedules.into_inner())
+	}
 
-		if locked_now.is_zero() {
-			T::Currency::remove_lock(VESTING_ID, &who);
-			Vesting::<T>::remove(&who);
-			Self::deposit_event(Event::<T>::VestingCompleted(who));
+	/// Writes the given schedules of `who` to storage, dropping the ones that are fully vested,
+	/// and updates the lock accordingly.
+	fn write_schedules(
+		who: &T::AccountId,
+		schedules: Vec<VestingInfo<BalanceOf<T>, T::BlockNumber>>,
+	) -> DispatchResult {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let mut locked_now = BalanceOf::<T>::zero();
+		let schedules: Vec<_> = schedules.into_iter()
+			.filter(|schedule| {
+				let locked = schedule.locked_at::<T::BlockNumberToBalance>(now);
+				locked_now = locked_now.saturating_add(locked);
+				!locked.is_zero()
+			})
+			.collect();
+
+		if schedules.is_empty() {
+			T::Currency::remove_lock(VESTING_ID, who);
+			Vesting::<T>::remove(who);
+			Self::deposit_event(Event::<T>::VestingCompleted(who.clone()));
 		} else {
+			let schedules: BoundedVec<_, T::MaxVestingSchedules> = schedules.try_into()
+				.map_err(|_| Error::<T>::AtMaxVestingSchedules)?;
+			Vesting::<T>::insert(who, schedules);
 			let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
-			T::Currency::set_lock(VESTING_ID, &who, locked_now, reasons);
-			Self::deposit_event(Event::<T>::VestingUpdated(who, locked_now));
+			T::Currency::set_lock(VESTING_ID, who, locked_now, reasons);
+			Self::deposit_event(Event::<T>::VestingUpdated(who.clone(), locked_now));
 		}
 		Ok(())
 	}
+
+	/// Transfers `schedule.locked` from `source` to `target` and adds the schedule to `target`.
+	fn do_vested_transfer(
+		source: &T::AccountId,
+		target: &T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+		Self::can_add_vesting_schedule(
+			target,
+			schedule.locked,
+			schedule.per_block,
+			schedule.starting_block,
+		)?;
+
+		T::Currency::transfer(source, target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+
+		Self::add_vesting_schedule(target, schedule.locked, schedule.per_block, schedule.starting_block)
+			.expect("schedule was checked by `can_add_vesting_schedule`; qed");
+
+		Ok(())
+	}
+
+	/// Merges two schedules at block `now`. Returns `None` if both are fully vested.
+	fn merge_vesting_info(
+		now: T::BlockNumber,
+		schedule1: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		schedule2: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>> {
+		let schedule1_ending_block = schedule1.ending_block_as_balance::<T::BlockNumberToBalance>();
+		let schedule2_ending_block = schedule2.ending_block_as_balance::<T::BlockNumberToBalance>();
+		let now_as_balance = T::BlockNumberToBalance::convert(now);
+
+		match (schedule1_ending_block <= now_as_balance, schedule2_ending_block <= now_as_balance) {
+			(true, true) => return None,
+			(true, false) => return Some(schedule2),
+			(false, true) => return Some(schedule1),
+			(false, false) => {},
+		}
+
+		let locked = schedule1.locked_at::<T::BlockNumberToBalance>(now)
+			.saturating_add(schedule2.locked_at::<T::BlockNumberToBalance>(now));
+		let ending_block = schedule1_ending_block.max(schedule2_ending_block);
+		let starting_block = now.max(schedule1.starting_block).max(schedule2.starting_block);
+
+		let duration = ending_block
+			.saturating_sub(T::BlockNumberToBalance::convert(starting_block));
+		let per_block = if duration > One::one() {
+			// The rounding down is compensated by the schedule running slightly longer.
+			locked / duration
+		} else {
+			locked
+		};
+
+		Some(VestingInfo {
+			locked,
+			per_block: per_block.max(One::one()),
+			starting_block,
+		})
+	}
 }
 
 impl<T: Config> VestingSchedule<T::AccountId> for Pallet<T> where
@@ -365,9 +597,11 @@ impl<T: Config> VestingSchedule<T::AccountId> for Pallet<T> where
 
 	/// Get the amount that is currently being vested and cannot be transferred out of this account.
 	fn vesting_balance(who: &T::AccountId) -> Option<BalanceOf<T>> {
-		if let Some(v) = Self::vesting(who) {
+		if let Some(schedules) = Self::vesting(who) {
 			let now = <frame_system::Pallet<T>>::block_number();
-			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
+			let locked_now = schedules.iter().fold(Zero::zero(), |total: BalanceOf<T>, schedule| {
+				total.saturating_add(schedule.locked_at::<T::BlockNumberToBalance>(now))
+			});
 			Some(T::Currency::free_balance(who).min(locked_now))
 		} else {
 			None
@@ -376,7 +610,7 @@ impl<T: Config> VestingSchedule<T::AccountId> for Pallet<T> where
 
 	/// Adds a vesting schedule to a given account.
 	///
-	/// If there already exists a vesting schedule for the given account, an `Err` is returned
+	/// If the account already has `MaxVestingSchedules` vesting schedules, an `Err` is returned
 	/// and nothing is updated.
 	///
 	/// On success, a linearly reducing amount of funds will be locked. In order to realise any
@@ -391,26 +625,40 @@ impl<T: Config> VestingSchedule<T::AccountId> for Pallet<T> where
 		starting_block: T::BlockNumber
 	) -> DispatchResult {
 		if locked.is_zero() { return Ok(()) }
-		if Vesting::<T>::contains_key(who) {
-			Err(Error::<T>::ExistingVestingSchedule)?
-		}
+		Self::can_add_vesting_schedule(who, locked, per_block, starting_block)?;
+
 		let vesting_schedule = VestingInfo {
 			locked,
 			per_block,
 			starting_block
 		};
-		Vesting::<T>::insert(who, vesting_schedule);
-		// it can't fail, but even if somehow it did, we don't really care.
-		let res = Self::update_lock(who.clone());
-		debug_assert!(res.is_ok());
+		let mut schedules = Self::vesting(who).map(|s| s.into_inner()).unwrap_or_default();
+		schedules.push(vesting_schedule);
+		Self::write_schedules(who, schedules)
+	}
+
+	/// Checks that a vesting schedule can be added to a given account, without changing anything.
+	fn can_add_vesting_schedule(
+		who: &T::AccountId,
+		locked: BalanceOf<T>,
+		per_block: BalanceOf<T>,
+		starting_block: T::BlockNumber
+	) -> DispatchResult {
+		let schedule = VestingInfo { locked, per_block, starting_block };
+		ensure!(schedule.is_valid(), Error::<T>::InvalidScheduleParams);
+		let count = Self::vesting(who).map_or(0, |schedules| schedules.len());
+		ensure!(count < T::MaxVestingSchedules::get() as usize, Error::<T>::AtMaxVestingSchedules);
 		Ok(())
 	}
 
-	/// Remove a vesting schedule for a given account.
-	fn remove_vesting_schedule(who: &T::AccountId) {
-		Vesting::<T>::remove(who);
-		// it can't fail, but even if somehow it did, we don't really care.
-		let res = Self::update_lock(who.clone());
-		debug_assert!(res.is_ok());
+	/// Remove the vesting schedule at `schedule_index` of a given account.
+	fn remove_vesting_schedule(who: &T::AccountId, schedule_index: u32) -> DispatchResult {
+		let mut schedules = Self::vesting(who).ok_or(Error::<T>::NotVesting)?.into_inner();
+		ensure!(
+			(schedule_index as usize) < schedules.len(),
+			Error::<T>::ScheduleIndexOutOfBounds,
+		);
+		schedules.remove(schedule_index as usize);
+		Self::write_schedules(who, schedules)
 	}
 }
@@ -86,12 +86,14 @@ impl pallet_balances::Config for Test {
 }
 parameter_types! {
 	pub const MinVestedTransfer: u64 = 256 * 2;
+	pub const MaxVestingSchedules: u32 = 3;
 	pub static ExistentialDeposit: u64 = 0;
 }
 impl Config for Test {
 	type BlockNumberToBalance = Identity;
 	type Currency = Balances;
 	type Event = Event;
+	type MaxVestingSchedules = MaxVestingSchedules;
 	type MinVestedTransfer = MinVestedTransfer;
 	type WeightInfo = ();
 }
@@ -15,12 +15,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
 use frame_system::RawOrigin;
 use sp_runtime::traits::BadOrigin;
 
 use super::*;
-use crate::mock::{Balances, ExtBuilder, System, Test, Vesting};
+use crate::mock::{Balances, ExtBuilder, MaxVestingSchedules, System, Test, Vesting};
 
 #[test]
 fn check_vesting_status() {
@@ -49,9 +49,9 @@ fn check_vesting_status() {
 				per_block: 64, // Vesting over 20 blocks
 				starting_block: 10,
 			};
-			assert_eq!(Vesting::vesting(&1), Some(user1_vesting_schedule)); // Account 1 has a vesting schedule
-			assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule)); // Account 2 has a vesting schedule
-			assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule)); // Account 12 has a vesting schedule
+			assert_eq!(Vesting::vesting(&1).unwrap(), vec![user1_vesting_schedule]); // Account 1 has a vesting schedule
+			assert_eq!(Vesting::vesting(&2).unwrap(), vec![user2_vesting_schedule]); // Account 2 has a vesting schedule
+			assert_eq!(Vesting::vesting(&12).unwrap(), vec![user12_vesting_schedule]); // Account 12 has a vesting schedule
 
 			// Account 1 has only 128 units vested from their illiquid 256 * 5 units at block 1
 			assert_eq!(Vesting::vesting_balance(&1), Some(128 * 9));
@@ -172,7 +172,7 @@ fn liquid_funds_should_transfer_with_delayed_vesting() {
 				per_block: 64, // Vesting over 20 blocks
 				starting_block: 10,
 			};
-			assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule));
+			assert_eq!(Vesting::vesting(&12).unwrap(), vec![user12_vesting_schedule]);
 
 			// Account 12 can still send liquid funds
 			assert_ok!(Balances::transfer(Some(12).into(), 3, 256 * 5));
@@ -199,7 +199,7 @@ fn vested_transfer_works() {
 			};
 			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule));
 			// Now account 4 should have vesting.
-			assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+			assert_eq!(Vesting::vesting(&4).unwrap(), vec![new_vesting_schedule]);
 			// Ensure the transfer happened correctly.
 			let user3_free_balance_updated = Balances::free_balance(&3);
 			assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -238,17 +238,35 @@ fn vested_transfer_correctly_fails() {
 				per_block: 256, // Vesting over 20 blocks
 				starting_block: 10,
 			};
-			assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+			assert_eq!(Vesting::vesting(&2).unwrap(), vec![user2_vesting_schedule]);
 
-			// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+			// Fill up the vesting schedules of account 2.
 			let new_vesting_schedule = VestingInfo {
 				locked: 256 * 5,
 				per_block: 64, // Vesting over 20 blocks
 				starting_block: 10,
 			};
+			for _ in 1 .. MaxVestingSchedules::get() {
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+			}
+			let user2_free_balance = Balances::free_balance(&2);
+
+			// The vesting schedule we will try to create, fails due to account 2 having the
+			// maximum number of schedules.
 			assert_noop!(
 				Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule),
-				Error::<Test>::ExistingVestingSchedule,
+				Error::<Test>::AtMaxVestingSchedules,
+			);
+
+			// Fails due to a schedule that never unlocks anything.
+			let new_vesting_schedule_zero_per_block = VestingInfo {
+				locked: 256 * 5,
+				per_block: 0,
+				starting_block: 10,
+			};
+			assert_noop!(
+				Vesting::vested_transfer(Some(4).into(), 4, new_vesting_schedule_zero_per_block),
+				Error::<Test>::InvalidScheduleParams,
 			);
 
 			// Fails due to too low transfer amount.
@@ -263,8 +281,8 @@ fn vested_transfer_correctly_fails() {
 			);
 
 			// Verify no currency transfer happened.
-			assert_eq!(user2_free_balance, 256 * 20);
-			assert_eq!(user4_free_balance, 256 * 40);
+			assert_eq!(Balances::free_balance(&2), user2_free_balance);
+			assert_eq!(Balances::free_balance(&4), user4_free_balance);
 		});
 }
 
@@ -289,7 +307,7 @@ fn force_vested_transfer_works() {
 			assert_noop!(Vesting::force_vested_transfer(Some(4).into(), 3, 4, new_vesting_schedule), BadOrigin);
 			assert_ok!(Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 4, new_vesting_schedule));
 			// Now account 4 should have vesting.
-			assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+			assert_eq!(Vesting::vesting(&4).unwrap(), vec![new_vesting_schedule]);
 			// Ensure the transfer happened correctly.
 			let user3_free_balance_updated = Balances::free_balance(&3);
 			assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -328,17 +346,35 @@ fn force_vested_transfer_correctly_fails() {
 				per_block: 256, // Vesting over 20 blocks
 				starting_block: 10,
 			};
-			assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+			assert_eq!(Vesting::vesting(&2).unwrap(), vec![user2_vesting_schedule]);
 
-			// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+			// Fill up the vesting schedules of account 2.
 			let new_vesting_schedule = VestingInfo {
 				locked: 256 * 5,
 				per_block: 64, // Vesting over 20 blocks
 				starting_block: 10,
 			};
+			for _ in 1 .. MaxVestingSchedules::get() {
+				assert_ok!(Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 2, new_vesting_schedule));
+			}
+			let user2_free_balance = Balances::free_balance(&2);
+
+			// The vesting schedule we will try to create, fails due to account 2 having the
+			// maximum number of schedules.
 			assert_noop!(
 				Vesting::force_vested_transfer(RawOrigin::Root.into(), 4, 2, new_vesting_schedule),
-				Error::<Test>::ExistingVestingSchedule,
+				Error::<Test>::AtMaxVestingSchedules,
+			);
+
+			// Fails due to a schedule that never unlocks anything.
+			let new_vesting_schedule_zero_per_block = VestingInfo {
+				locked: 256 * 5,
+				per_block: 0,
+				starting_block: 10,
+			};
+			assert_noop!(
+				Vesting::force_vested_transfer(RawOrigin::Root.into(), 4, 4, new_vesting_schedule_zero_per_block),
+				Error::<Test>::InvalidScheduleParams,
 			);
 
 			// Fails due to too low transfer amount.
@@ -353,7 +389,175 @@ fn force_vested_transfer_correctly_fails() {
 			);
 
 			// Verify no currency transfer happened.
-			assert_eq!(user2_free_balance, 256 * 20);
-			assert_eq!(user4_free_balance, 256 * 40);
+			assert_eq!(Balances::free_balance(&2), user2_free_balance);
+			assert_eq!(Balances::free_balance(&4), user4_free_balance);
+		});
+}
+
+#[test]
+fn multiple_schedules_vest_independently() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			let short_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 128, // Vesting over 10 blocks
+				starting_block: 10,
+			};
+			let long_schedule = VestingInfo {
+				locked: 256 * 4,
+				per_block: 32, // Vesting over 32 blocks
+				starting_block: 20,
+			};
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, short_schedule));
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, long_schedule));
+			assert_eq!(Vesting::vesting(&4).unwrap(), vec![short_schedule, long_schedule]);
+			// Both schedules are fully locked.
+			assert_eq!(Vesting::vesting_balance(&4), Some(256 * 9));
+
+			System::set_block_number(15);
+			// Half of the first schedule has vested.
+			assert_eq!(Vesting::vesting_balance(&4), Some(128 * 5 + 256 * 4));
+
+			System::set_block_number(20);
+			assert_ok!(Vesting::vest(Some(4).into()));
+			// The first schedule has fully vested and is removed.
+			assert_eq!(Vesting::vesting(&4).unwrap(), vec![long_schedule]);
+			assert_eq!(Vesting::vesting_balance(&4), Some(256 * 4));
+			// Only the funds of the second schedule are kept locked.
+			assert_noop!(
+				Balances::transfer(Some(4).into(), 3, 256 * 46),
+				pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+			);
+			assert_ok!(Balances::transfer(Some(4).into(), 3, 256 * 45));
+
+			System::set_block_number(52);
+			assert_ok!(Vesting::vest(Some(4).into()));
+			// All schedules have vested and the lock is removed.
+			assert_eq!(Vesting::vesting(&4), None);
+			assert_eq!(Vesting::vesting_balance(&4), None);
+		});
+}
+
+#[test]
+fn merge_schedules_works() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			let user2_vesting_schedule = VestingInfo {
+				locked: 256 * 20,
+				per_block: 256, // Vesting over 20 blocks
+				starting_block: 10,
+			};
+			let new_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 128, // Vesting over 10 blocks
+				starting_block: 15,
+			};
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+			assert_eq!(
+				Vesting::vesting(&2).unwrap(),
+				vec![user2_vesting_schedule, new_vesting_schedule],
+			);
+			assert_eq!(Vesting::vesting_balance(&2), Some(256 * 25));
+
+			// Merging a schedule with itself does nothing.
+			assert_ok!(Vesting::merge_schedules(Some(2).into(), 1, 1));
+			assert_eq!(Vesting::vesting(&2).unwrap().len(), 2);
+
+			assert_ok!(Vesting::merge_schedules(Some(2).into(), 0, 1));
+			// Neither schedule has started, so the merged schedule starts at the latest start and
+			// ends at the latest end.
+			let merged_schedule = VestingInfo {
+				locked: 256 * 25,
+				per_block: 256 * 25 / 15,
+				starting_block: 15,
+			};
+			assert_eq!(Vesting::vesting(&2).unwrap(), vec![merged_schedule]);
+			// Nothing got unlocked by the merge.
+			assert_eq!(Vesting::vesting_balance(&2), Some(256 * 25));
+
+			System::set_block_number(30);
+			assert_ok!(Vesting::vest(Some(2).into()));
+			assert_eq!(Vesting::vesting(&2).unwrap(), vec![merged_schedule]);
+			System::set_block_number(31);
+			assert_ok!(Vesting::vest(Some(2).into()));
+			assert_eq!(Vesting::vesting(&2), None);
+		});
+}
+
+#[test]
+fn merge_schedules_drops_ended_schedules() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			let user1_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 128, // Vesting over 10 blocks
+				starting_block: 0,
+			};
+			let new_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 64, // Vesting over 20 blocks
+				starting_block: 10,
+			};
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 1, new_vesting_schedule));
+			assert_eq!(
+				Vesting::vesting(&1).unwrap(),
+				vec![user1_vesting_schedule, new_vesting_schedule],
+			);
+
+			// The first schedule has ended, so the second is kept unmodified.
+			System::set_block_number(10);
+			assert_ok!(Vesting::merge_schedules(Some(1).into(), 1, 0));
+			assert_eq!(Vesting::vesting(&1).unwrap(), vec![new_vesting_schedule]);
+			assert_eq!(Vesting::vesting_balance(&1), Some(256 * 5));
+
+			assert_noop!(
+				Vesting::merge_schedules(Some(1).into(), 0, 1),
+				Error::<Test>::ScheduleIndexOutOfBounds,
+			);
+			assert_noop!(
+				Vesting::merge_schedules(Some(4).into(), 0, 1),
+				Error::<Test>::NotVesting,
+			);
+		});
+}
+
+#[test]
+fn migration_to_v1_wraps_existing_schedules() {
+	ExtBuilder::default()
+		.existential_deposit(256)
+		.build()
+		.execute_with(|| {
+			let user1_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 128,
+				starting_block: 0,
+			};
+			let user12_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 64,
+				starting_block: 10,
+			};
+			// Write the schedules in the format used before multiple schedules were supported.
+			crate::Vesting::<Test>::remove_all(None);
+			for (who, schedule) in [(1u64, user1_vesting_schedule), (12, user12_vesting_schedule)].iter() {
+				frame_support::storage::unhashed::put(
+					&crate::Vesting::<Test>::hashed_key_for(who),
+					schedule,
+				);
+			}
+			StorageVersion::<Test>::put(Releases::V0);
+
+			<Vesting as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+			assert_eq!(StorageVersion::<Test>::get(), Releases::V1);
+			assert_eq!(Vesting::vesting(&1).unwrap(), vec![user1_vesting_schedule]);
+			assert_eq!(Vesting::vesting(&12).unwrap(), vec![user12_vesting_schedule]);
+			assert_eq!(Vesting::vesting(&2), None);
 		});
 }
@@ -44,103 +44,146 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_vesting.
 pub trait WeightInfo {
-	fn vest_locked(l: u32, ) -> Weight;
-	fn vest_unlocked(l: u32, ) -> Weight;
-	fn vest_other_locked(l: u32, ) -> Weight;
-	fn vest_other_unlocked(l: u32, ) -> Weight;
-	fn vested_transfer(l: u32, ) -> Weight;
-	fn force_vested_transfer(l: u32, ) -> Weight;
+	fn vest_locked(l: u32, s: u32, ) -> Weight;
+	fn vest_unlocked(l: u32, s: u32, ) -> Weight;
+	fn vest_other_locked(l: u32, s: u32, ) -> Weight;
+	fn vest_other_unlocked(l: u32, s: u32, ) -> Weight;
+	fn vested_transfer(l: u32, s: u32, ) -> Weight;
+	fn force_vested_transfer(l: u32, s: u32, ) -> Weight;
+	fn merge_schedules(l: u32, s: u32, ) -> Weight;
 }
 
 /// Weights for pallet_vesting using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	fn vest_locked(l: u32, ) -> Weight {
+	fn vest_locked(l: u32, s: u32, ) -> Weight {
 		(42_905_000 as Weight)
 			// Standard Error: 13_000
 			.saturating_add((232_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	fn vest_unlocked(l: u32, ) -> Weight {
+	fn vest_unlocked(l: u32, s: u32, ) -> Weight {
 		(45_650_000 as Weight)
 			// Standard Error: 12_000
 			.saturating_add((215_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
-	fn vest_other_locked(l: u32, ) -> Weight {
+	fn vest_other_locked(l: u32, s: u32, ) -> Weight {
 		(42_273_000 as Weight)
 			// Standard Error: 15_000
 			.saturating_add((246_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
-	fn vest_other_unlocked(l: u32, ) -> Weight {
+	fn vest_other_unlocked(l: u32, s: u32, ) -> Weight {
 		(45_324_000 as Weight)
 			// Standard Error: 12_000
 			.saturating_add((214_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
-	fn vested_transfer(l: u32, ) -> Weight {
+	fn vested_transfer(l: u32, s: u32, ) -> Weight {
 		(96_661_000 as Weight)
 			// Standard Error: 10_000
 			.saturating_add((211_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
-	fn force_vested_transfer(l: u32, ) -> Weight {
+	fn force_vested_transfer(l: u32, s: u32, ) -> Weight {
 		(98_812_000 as Weight)
 			// Standard Error: 13_000
 			.saturating_add((139_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(4 as Weight))
 			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 	}
+	fn merge_schedules(l: u32, s: u32, ) -> Weight {
+		(55_173_000 as Weight)
+			// Standard Error: 14_000
+			.saturating_add((225_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 25_000
+			.saturating_add((201_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	fn vest_locked(l: u32, ) -> Weight {
+	fn vest_locked(l: u32, s: u32, ) -> Weight {
 		(42_905_000 as Weight)
 			// Standard Error: 13_000
 			.saturating_add((232_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	fn vest_unlocked(l: u32, ) -> Weight {
+	fn vest_unlocked(l: u32, s: u32, ) -> Weight {
 		(45_650_000 as Weight)
 			// Standard Error: 12_000
 			.saturating_add((215_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
-	fn vest_other_locked(l: u32, ) -> Weight {
+	fn vest_other_locked(l: u32, s: u32, ) -> Weight {
 		(42_273_000 as Weight)
 			// Standard Error: 15_000
 			.saturating_add((246_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
-	fn vest_other_unlocked(l: u32, ) -> Weight {
+	fn vest_other_unlocked(l: u32, s: u32, ) -> Weight {
 		(45_324_000 as Weight)
 			// Standard Error: 12_000
 			.saturating_add((214_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
-	fn vested_transfer(l: u32, ) -> Weight {
+	fn vested_transfer(l: u32, s: u32, ) -> Weight {
 		(96_661_000 as Weight)
 			// Standard Error: 10_000
 			.saturating_add((211_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
-	fn force_vested_transfer(l: u32, ) -> Weight {
+	fn force_vested_transfer(l: u32, s: u32, ) -> Weight {
 		(98_812_000 as Weight)
 			// Standard Error: 13_000
 			.saturating_add((139_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 21_000
+			.saturating_add((178_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 	}
+	fn merge_schedules(l: u32, s: u32, ) -> Weight {
+		(55_173_000 as Weight)
+			// Standard Error: 14_000
+			.saturating_add((225_000 as Weight).saturating_mul(l as Weight))
+			// Standard Error: 25_000
+			.saturating_add((201_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
 }
@@ -299,11 +299,18 @@ fn generate_runtime_api_base_structures() -> Result<TokenStream> {
 				#crate_::StorageChanges<C::StateBackend, Block>,
 				String
 			> where Self: Sized {
+				let state_version = #crate_::CallApiAt::<Block>::runtime_version_at(
+					self.call,
+					&#crate_::BlockId::Hash(parent_hash.clone()),
+				).map(|v| v.state_version())
+					.map_err(|e| format!("Failed to get state version: {}", e))?;
+
 				self.changes.replace(Default::default()).into_storage_changes(
 					backend,
 					changes_trie_state,
 					parent_hash,
 					self.storage_transaction_cache.replace(Default::default()),
+					state_version,
 				)
 			}
 		}
@@ -265,6 +265,7 @@ pub use sp_api_proc_macro::decl_runtime_apis;
 ///     // Here we are exposing the runtime api versions.
 ///     apis: RUNTIME_API_VERSIONS,
 ///     transaction_version: 1,
+///     state_version: 1,
 /// };
 ///
 /// # fn main() {}
@@ -676,6 +677,7 @@ impl From<OldRuntimeVersion> for RuntimeVersion {
 			impl_version: x.impl_version,
 			apis: x.apis,
 			transaction_version: 1,
+			state_version: 0,
 		}
 	}
 }
@@ -696,7 +698,7 @@ impl From<RuntimeVersion> for OldRuntimeVersion {
 decl_runtime_apis! {
 	/// The `Core` runtime api that every Substrate runtime needs to implement.
 	#[core_trait]
-	#[api_version(3)]
+	#[api_version(4)]
 	pub trait Core {
 		/// Returns the version of the runtime.
 		fn version() -> RuntimeVersion;
@@ -25,15 +25,22 @@
 pub use sp_core::{self, crypto::{CryptoType, CryptoTypePublicPair, Public, Derive, IsWrappedBy, Wraps}, RuntimeDebug};
 #[doc(hidden)]
 #[cfg(feature = "full_crypto")]
-pub use sp_core::crypto::{SecretStringError, DeriveJunction, Ss58Codec, Pair};
+pub use sp_core::crypto::{SecretStringError, DeriveJunction, Pair};
+#[doc(hidden)]
+pub use sp_core::crypto::Ss58Codec;
 pub use sp_core::crypto::{KeyTypeId, CryptoTypeId, key_types};
 
 #[doc(hidden)]
 pub use codec;
 #[doc(hidden)]
-#[cfg(feature = "std")]
 pub use serde;
 #[doc(hidden)]
+#[cfg(feature = "std")]
+pub use std::{format, string::String};
+#[doc(hidden)]
+#[cfg(not(feature = "std"))]
+pub use sp_std::alloc::{format, string::String};
+#[doc(hidden)]
 pub use sp_std::{
 	convert::TryFrom,
 	ops::Deref,
@@ -259,6 +266,26 @@ macro_rules! app_crypto_public_common {
 	($public:ty, $sig:ty, $key_type:expr, $crypto_type:expr) => {
 		$crate::app_crypto_public_common_if_std!();
 
+		impl $crate::serde::Serialize for Public {
+			fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where
+				S: $crate::serde::Serializer
+			{
+				use $crate::Ss58Codec;
+				serializer.serialize_str(&self.to_ss58check())
+			}
+		}
+
+		impl<'de> $crate::serde::Deserialize<'de> for Public {
+			fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where
+				D: $crate::serde::Deserializer<'de>
+			{
+				use $crate::Ss58Codec;
+				let s = <$crate::String as $crate::serde::Deserialize>::deserialize(deserializer)?;
+				Public::from_ss58check(&s)
+					.map_err(|e| $crate::serde::de::Error::custom($crate::format!("{:?}", e)))
+			}
+		}
+
 		impl AsRef<[u8]> for Public {
 			fn as_ref(&self) -> &[u8] { self.0.as_ref() }
 		}
@@ -355,25 +382,6 @@ macro_rules! app_crypto_public_common_if_std {
 				write!(f, "{}", self.0.to_ss58check())
 			}
 		}
-
-		impl $crate::serde::Serialize for Public {
-			fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> where
-				S: $crate::serde::Serializer
-			{
-				use $crate::Ss58Codec;
-				serializer.serialize_str(&self.to_ss58check())
-			}
-		}
-
-		impl<'de> $crate::serde::Deserialize<'de> for Public {
-			fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error> where
-				D: $crate::serde::Deserializer<'de>
-			{
-				use $crate::Ss58Codec;
-				Public::from_ss58check(&String::deserialize(deserializer)?)
-					.map_err(|e| $crate::serde::de::Error::custom(format!("{:?}", e)))
-			}
-		}
 	}
 }
 
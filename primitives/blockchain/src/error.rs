@@ -40,6 +40,11 @@ pub enum ApplyExtrinsicFailed {
 
 	#[error("Application specific error")]
 	Application(#[source] Box<dyn 'static + std::error::Error + Send + Sync>),
+
+	/// Including the extrinsic would make the storage proof of the block exceed the given
+	/// limit in bytes.
+	#[error("Storage proof size limit of {0} bytes exceeded")]
+	ProofSizeLimitExceeded(usize),
 }
 
 /// Substrate Client error
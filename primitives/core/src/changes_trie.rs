@@ -17,14 +17,13 @@
 
 //! Substrate changes trie configuration.
 
-#[cfg(any(feature = "std", test))]
 use serde::{Serialize, Deserialize};
 use codec::{Encode, Decode};
 use num_traits::Zero;
 
 /// Substrate changes trie configuration.
-#[cfg_attr(any(feature = "std", test), derive(Serialize, Deserialize, parity_util_mem::MallocSizeOf))]
-#[derive(Debug, Clone, PartialEq, Eq, Default, Encode, Decode)]
+#[cfg_attr(any(feature = "std", test), derive(parity_util_mem::MallocSizeOf))]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Encode, Decode, Serialize, Deserialize)]
 pub struct ChangesTrieConfiguration {
 	/// Interval (in blocks) at which level1-digests are created. Digests are not
 	/// created when this is less or equal to 1.
@@ -23,9 +23,9 @@ use crate::{sr25519, ed25519};
 use sp_std::hash::Hash;
 use sp_std::vec::Vec;
 use sp_std::str;
-#[cfg(feature = "std")]
-use sp_std::convert::TryInto;
-use sp_std::convert::TryFrom;
+use sp_std::convert::{TryFrom, TryInto};
+#[cfg(not(feature = "std"))]
+use sp_std::alloc::{format, string::String, vec};
 #[cfg(feature = "std")]
 use parking_lot::Mutex;
 #[cfg(feature = "std")]
@@ -34,8 +34,6 @@ use codec::{Encode, Decode, MaxEncodedLen};
 #[cfg(feature = "std")]
 use regex::Regex;
 #[cfg(feature = "std")]
-use base58::{FromBase58, ToBase58};
-#[cfg(feature = "std")]
 use crate::hexdisplay::HexDisplay;
 #[doc(hidden)]
 pub use sp_std::ops::Deref;
@@ -197,7 +195,6 @@ impl<T: AsRef<str>> From<T> for DeriveJunction {
 }
 
 /// An error type for SS58 decoding.
-#[cfg(feature = "full_crypto")]
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum PublicError {
 	/// Bad alphabet.
@@ -220,7 +217,6 @@ pub enum PublicError {
 ///
 /// See <https://github.com/paritytech/substrate/wiki/External-Address-Format-(SS58)#address-type>
 /// for information on the codec.
-#[cfg(feature = "full_crypto")]
 pub trait Ss58Codec: Sized + AsMut<[u8]> + AsRef<[u8]> + Default {
 	/// A format filterer, can be used to ensure that `from_ss58check` family only decode for
 	/// allowed identifiers. By default just refuses the two reserved identifiers.
@@ -229,18 +225,16 @@ pub trait Ss58Codec: Sized + AsMut<[u8]> + AsRef<[u8]> + Default {
 	}
 
 	/// Some if the string is a properly encoded SS58Check address.
-	#[cfg(feature = "std")]
 	fn from_ss58check(s: &str) -> Result<Self, PublicError> {
 		Self::from_ss58check_with_version(s)
 			.and_then(|(r, v)| match v {
 				v if !v.is_custom() => Ok(r),
-				v if v == *DEFAULT_VERSION.lock() => Ok(r),
+				v if v == default_ss58_version() => Ok(r),
 				_ => Err(PublicError::UnknownVersion),
 			})
 	}
 
 	/// Some if the string is a properly encoded SS58Check address.
-	#[cfg(feature = "std")]
 	fn from_ss58check_with_version(s: &str) -> Result<(Self, Ss58AddressFormat), PublicError> {
 		const CHECKSUM_LEN: usize = 2;
 		let mut res = Self::default();
@@ -248,7 +242,7 @@ pub trait Ss58Codec: Sized + AsMut<[u8]> + AsRef<[u8]> + Default {
 		// Must decode to our type.
 		let body_len = res.as_mut().len();
 
-		let data = s.from_base58().map_err(|_| PublicError::BadBase58)?;
+		let data = bs58::decode(s).into_vec().map_err(|_| PublicError::BadBase58)?;
 		if data.len() < 2 { return Err(PublicError::BadLength); }
 		let (prefix_len, ident) = match data[0] {
 			0..=63 => (1, data[0] as u16),
@@ -285,13 +279,12 @@ pub trait Ss58Codec: Sized + AsMut<[u8]> + AsRef<[u8]> + Default {
 		Self::from_string_with_version(s)
 			.and_then(|(r, v)| match v {
 				v if !v.is_custom() => Ok(r),
-				v if v == *DEFAULT_VERSION.lock() => Ok(r),
+				v if v == default_ss58_version() => Ok(r),
 				_ => Err(PublicError::UnknownVersion),
 			})
 	}
 
 	/// Return the ss58-check string for this key.
-	#[cfg(feature = "std")]
 	fn to_ss58check_with_version(&self, version: Ss58AddressFormat) -> String {
 		// We mask out the upper two bits of the ident - SS58 Prefix currently only supports 14-bits
 		let ident: u16 = u16::from(version) & 0b0011_1111_1111_1111;
@@ -310,12 +303,11 @@ pub trait Ss58Codec: Sized + AsMut<[u8]> + AsRef<[u8]> + Default {
 		v.extend(self.as_ref());
 		let r = ss58hash(&v);
 		v.extend(&r.as_bytes()[0..2]);
-		v.to_base58()
+		bs58::encode(v).into_string()
 	}
 
 	/// Return the ss58-check string for this key.
-	#[cfg(feature = "std")]
-	fn to_ss58check(&self) -> String { self.to_ss58check_with_version(*DEFAULT_VERSION.lock()) }
+	fn to_ss58check(&self) -> String { self.to_ss58check_with_version(default_ss58_version()) }
 
 	/// Some if the string is a properly encoded SS58Check address, optionally with
 	/// a derivation path following.
@@ -336,10 +328,8 @@ pub trait Derive: Sized {
 	}
 }
 
-#[cfg(feature = "std")]
 const PREFIX: &[u8] = b"SS58PRE";
 
-#[cfg(feature = "std")]
 fn ss58hash(data: &[u8]) -> blake2_rfc::blake2b::Blake2bResult {
 	let mut context = blake2_rfc::blake2b::Blake2b::new(64);
 	context.update(PREFIX);
@@ -353,7 +343,19 @@ lazy_static::lazy_static! {
 		= Mutex::new(Ss58AddressFormat::SubstrateAccount);
 }
 
-#[cfg(feature = "full_crypto")]
+/// The SS58 version used when none is given. Only `std` can change it, see
+/// `set_default_ss58_version`.
+fn default_ss58_version() -> Ss58AddressFormat {
+	#[cfg(feature = "std")]
+	{
+		*DEFAULT_VERSION.lock()
+	}
+	#[cfg(not(feature = "std"))]
+	{
+		Ss58AddressFormat::SubstrateAccount
+	}
+}
+
 macro_rules! ss58_address_format {
 	( $( $identifier:tt => ($number:expr, $name:expr, $desc:tt) )* ) => (
 		/// A known address (sub)format/network ID for SS58.
@@ -459,10 +461,9 @@ macro_rules! ss58_address_format {
 			}
 		}
 
-		#[cfg(feature = "std")]
 		impl Default for Ss58AddressFormat {
 			fn default() -> Self {
-				*DEFAULT_VERSION.lock()
+				default_ss58_version()
 			}
 		}
 
@@ -475,7 +476,6 @@ macro_rules! ss58_address_format {
 	)
 }
 
-#[cfg(feature = "full_crypto")]
 ss58_address_format!(
 	PolkadotAccount =>
 		(0, "polkadot", "Polkadot Relay-chain, standard account (*25519).")
@@ -625,8 +625,8 @@ lazy_static::lazy_static! {
 		.expect("constructed from known-good static value; qed");
 }
 
-#[cfg(feature = "std")]
 impl<T: Sized + AsMut<[u8]> + AsRef<[u8]> + Default + Derive> Ss58Codec for T {
+	#[cfg(feature = "std")]
 	fn from_string(s: &str) -> Result<Self, PublicError> {
 		let cap = SS58_REGEX.captures(s).ok_or(PublicError::InvalidFormat)?;
 		let s = cap.name("ss58")
@@ -654,6 +654,7 @@ impl<T: Sized + AsMut<[u8]> + AsRef<[u8]> + Default + Derive> Ss58Codec for T {
 		}
 	}
 
+	#[cfg(feature = "std")]
 	fn from_string_with_version(s: &str) -> Result<(Self, Ss58AddressFormat), PublicError> {
 		let cap = SS58_REGEX.captures(s).ok_or(PublicError::InvalidFormat)?;
 		let (addr, v) = Self::from_ss58check_with_version(
@@ -723,7 +724,6 @@ impl UncheckedFrom<crate::hash::H256> for AccountId32 {
 	}
 }
 
-#[cfg(feature = "std")]
 impl Ss58Codec for AccountId32 {}
 
 impl AsRef<[u8]> for AccountId32 {
@@ -807,14 +807,12 @@ impl sp_std::fmt::Debug for AccountId32 {
 	}
 }
 
-#[cfg(feature = "std")]
 impl serde::Serialize for AccountId32 {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
 		serializer.serialize_str(&self.to_ss58check())
 	}
 }
 
-#[cfg(feature = "std")]
 impl<'de> serde::Deserialize<'de> for AccountId32 {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
 		Ss58Codec::from_ss58check(&String::deserialize(deserializer)?)
@@ -35,10 +35,10 @@ use substrate_bip39::seed_from_entropy;
 use bip39::{Mnemonic, Language, MnemonicType};
 #[cfg(feature = "full_crypto")]
 use crate::crypto::{Pair as TraitPair, DeriveJunction, SecretStringError};
-#[cfg(feature = "std")]
 use crate::crypto::Ss58Codec;
-#[cfg(feature = "std")]
 use serde::{de, Serializer, Serialize, Deserializer, Deserialize};
+#[cfg(not(feature = "std"))]
+use sp_std::alloc::{format, string::String};
 use crate::crypto::{Public as TraitPublic, CryptoTypePublicPair, UncheckedFrom, CryptoType, Derive, CryptoTypeId};
 use sp_runtime_interface::pass_by::PassByInner;
 use sp_std::ops::Deref;
@@ -175,14 +175,12 @@ impl sp_std::fmt::Debug for Public {
 	}
 }
 
-#[cfg(feature = "std")]
 impl Serialize for Public {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
 		serializer.serialize_str(&self.to_ss58check())
 	}
 }
 
-#[cfg(feature = "std")]
 impl<'de> Deserialize<'de> for Public {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
 		Public::from_ss58check(&String::deserialize(deserializer)?)
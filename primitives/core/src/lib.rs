@@ -37,7 +37,6 @@ use sp_std::prelude::*;
 use sp_std::ops::Deref;
 #[cfg(feature = "std")]
 use std::borrow::Cow;
-#[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
 #[cfg(feature = "std")]
 pub use serde;
@@ -46,7 +45,6 @@ pub use codec::{Encode, Decode};
 
 pub use sp_debug_derive::RuntimeDebug;
 
-#[cfg(feature = "std")]
 pub use impl_serde::serialize as bytes;
 
 #[cfg(feature = "full_crypto")]
@@ -184,7 +182,7 @@ impl sp_std::ops::Deref for OpaqueMetadata {
 
 /// Simple blob to hold a `PeerId` without committing to its format.
 #[derive(Default, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug, PassByInner)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Serialize, Deserialize)]
 pub struct OpaquePeerId(pub Vec<u8>);
 
 impl OpaquePeerId {
@@ -768,12 +768,100 @@ impl TransactionPoolExt {
 }
 
 /// Change to be applied to the offchain worker db in regards to a key.
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Encode, Decode)]
 pub enum OffchainOverlayedChange {
 	/// Remove the data associated with the key
 	Remove,
 	/// Overwrite the value of an associated key
 	SetValue(Vec<u8>),
+	/// Append the data to the value of an associated key, starting from an empty value
+	/// if there is none.
+	Append(Vec<u8>),
+	/// Overwrite the value of an associated key if its current value is `old_value`.
+	///
+	/// A `None` `old_value` only matches a key without a value.
+	CompareAndSet {
+		/// Value the key must have for the change to be applied.
+		old_value: Option<Vec<u8>>,
+		/// Value to set.
+		new_value: Vec<u8>,
+	},
+	/// Changes that depend on the current value of the key, applied in order.
+	Sequence(Vec<OffchainOverlayedChange>),
+}
+
+impl OffchainOverlayedChange {
+	/// Combine this change with a `next` change applied after it.
+	///
+	/// Changes following a change that sets or removes the value are resolved right away.
+	/// Otherwise the result is only known once the current value of the key is, and the
+	/// changes are kept in a [`OffchainOverlayedChange::Sequence`].
+	pub fn merge(self, next: OffchainOverlayedChange) -> OffchainOverlayedChange {
+		use OffchainOverlayedChange::*;
+		match (self, next) {
+			(_, next @ Remove) | (_, next @ SetValue(_)) => next,
+			(Remove, next) => next.apply(None).into(),
+			(SetValue(value), next) => next.apply(Some(value)).into(),
+			(Append(mut data), Append(more)) => {
+				data.extend(more);
+				Append(data)
+			},
+			(Sequence(mut changes), next) => {
+				let last = changes.pop().expect("sequences are never empty; qed");
+				match last.merge(next) {
+					Sequence(merged) => changes.extend(merged),
+					merged => changes.push(merged),
+				}
+				Sequence(changes)
+			},
+			(current, Sequence(mut changes)) => {
+				changes.insert(0, current);
+				Sequence(changes)
+			},
+			(current, next) => Sequence(sp_std::vec![current, next]),
+		}
+	}
+
+	/// Apply this change to the `current` value of the key, returning the new value.
+	pub fn apply(self, current: Option<Vec<u8>>) -> Option<Vec<u8>> {
+		use OffchainOverlayedChange::*;
+		match self {
+			Remove => None,
+			SetValue(value) => Some(value),
+			Append(data) => {
+				let mut value = current.unwrap_or_default();
+				value.extend(data);
+				Some(value)
+			},
+			CompareAndSet { old_value, new_value } => if current == old_value {
+				Some(new_value)
+			} else {
+				current
+			},
+			Sequence(changes) => changes.into_iter().fold(current, |value, change| change.apply(value)),
+		}
+	}
+
+	/// The value of the key after this change, if it doesn't depend on the current one.
+	///
+	/// The outer `Option` is `None` if the value is unknown, the inner one is `None` if the
+	/// key is removed.
+	pub fn known_value(&self) -> Option<Option<&[u8]>> {
+		match self {
+			OffchainOverlayedChange::Remove => Some(None),
+			OffchainOverlayedChange::SetValue(value) => Some(Some(value)),
+			_ => None,
+		}
+	}
+}
+
+impl From<Option<Vec<u8>>> for OffchainOverlayedChange {
+	fn from(value: Option<Vec<u8>>) -> Self {
+		match value {
+			Some(value) => OffchainOverlayedChange::SetValue(value),
+			None => OffchainOverlayedChange::Remove,
+		}
+	}
 }
 
 #[cfg(test)]
@@ -801,4 +889,30 @@ mod tests {
 		assert!(all.has(Capability::TransactionPool));
 		assert!(!some.has(Capability::TransactionPool));
 	}
+
+	#[test]
+	fn overlayed_changes_merge_and_apply() {
+		use OffchainOverlayedChange::*;
+		let cas = |old: Option<&[u8]>, new: &[u8]| CompareAndSet {
+			old_value: old.map(|v| v.to_vec()),
+			new_value: new.to_vec(),
+		};
+
+		// Changes following a known value are resolved.
+		assert_eq!(SetValue(b"a".to_vec()).merge(Append(b"b".to_vec())), SetValue(b"ab".to_vec()));
+		assert_eq!(Remove.merge(Append(b"b".to_vec())), SetValue(b"b".to_vec()));
+		assert_eq!(SetValue(b"a".to_vec()).merge(cas(Some(b"a"), b"c")), SetValue(b"c".to_vec()));
+		assert_eq!(SetValue(b"a".to_vec()).merge(cas(None, b"c")), SetValue(b"a".to_vec()));
+		assert_eq!(Remove.merge(cas(Some(b"a"), b"c")), Remove);
+
+		// Appends are concatenated, other dependent changes are kept in order.
+		assert_eq!(Append(b"a".to_vec()).merge(Append(b"b".to_vec())), Append(b"ab".to_vec()));
+		let change = cas(None, b"a")
+			.merge(Append(b"b".to_vec()))
+			.merge(Append(b"c".to_vec()));
+		assert_eq!(change, Sequence(vec![cas(None, b"a"), Append(b"bc".to_vec())]));
+		assert_eq!(change.clone().apply(None), Some(b"abc".to_vec()));
+		assert_eq!(change.clone().apply(Some(b"x".to_vec())), Some(b"xbc".to_vec()));
+		assert_eq!(change.merge(SetValue(b"d".to_vec())), SetValue(b"d".to_vec()));
+	}
 }
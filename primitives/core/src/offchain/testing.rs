@@ -87,9 +87,10 @@ impl TestPersistentOffchainDB {
 	) {
 		let mut me = self.persistent.write();
 		for ((_prefix, key), value_operation) in changes {
-			match value_operation {
-				OffchainOverlayedChange::SetValue(val) => me.set(Self::PREFIX, key.as_slice(), val.as_slice()),
-				OffchainOverlayedChange::Remove => me.remove(Self::PREFIX, key.as_slice()),
+			let current = me.get(Self::PREFIX, key.as_slice());
+			match value_operation.apply(current) {
+				Some(val) => me.set(Self::PREFIX, key.as_slice(), val.as_slice()),
+				None => me.remove(Self::PREFIX, key.as_slice()),
 			}
 		}
 	}
@@ -37,7 +37,6 @@ use bip39::{Mnemonic, Language, MnemonicType};
 use crate::crypto::{
 	Pair as TraitPair, DeriveJunction, Infallible, SecretStringError
 };
-#[cfg(feature = "std")]
 use crate::crypto::Ss58Codec;
 
 use crate::crypto::{Public as TraitPublic, CryptoTypePublicPair, UncheckedFrom, CryptoType, Derive, CryptoTypeId};
@@ -45,8 +44,9 @@ use crate::hash::{H256, H512};
 use codec::{Encode, Decode, MaxEncodedLen};
 use sp_std::ops::Deref;
 
-#[cfg(feature = "std")]
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(not(feature = "std"))]
+use sp_std::alloc::{format, string::String};
 #[cfg(feature = "full_crypto")]
 use schnorrkel::keys::{MINI_SECRET_KEY_LENGTH, SECRET_KEY_LENGTH};
 use sp_runtime_interface::pass_by::PassByInner;
@@ -174,14 +174,12 @@ impl sp_std::fmt::Debug for Public {
 	}
 }
 
-#[cfg(feature = "std")]
 impl Serialize for Public {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
 		serializer.serialize_str(&self.to_ss58check())
 	}
 }
 
-#[cfg(feature = "std")]
 impl<'de> Deserialize<'de> for Public {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
 		Public::from_ss58check(&String::deserialize(deserializer)?)
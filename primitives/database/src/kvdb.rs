@@ -112,4 +112,11 @@ impl<D: KeyValueDB, H: Clone + AsRef<[u8]>> Database<H> for DbAdapter<D> {
 	fn contains(&self, col: ColumnId, key: &[u8]) -> bool {
 		handle_err(self.0.has_key(col, key))
 	}
+
+	fn iter(&self, col: ColumnId, f: &mut dyn FnMut(&[u8], &[u8])) -> bool {
+		for (key, value) in self.0.iter(col) {
+			f(&key, &value);
+		}
+		true
+	}
 }
@@ -103,6 +103,13 @@ pub trait Database<H: Clone + AsRef<[u8]>>: Send + Sync {
 	fn with_get(&self, col: ColumnId, key: &[u8], f: &mut dyn FnMut(&[u8])) {
 		self.get(col, key).map(|v| f(&v));
 	}
+
+	/// Call `f` with every key and value stored in `col`, in no particular order.
+	///
+	/// Returns `false` without calling `f` if the database can't iterate over its columns.
+	fn iter(&self, _col: ColumnId, _f: &mut dyn FnMut(&[u8], &[u8])) -> bool {
+		false
+	}
 }
 
 impl<H> std::fmt::Debug for dyn Database<H> {
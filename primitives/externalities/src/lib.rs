@@ -27,7 +27,7 @@
 
 use sp_std::{any::{Any, TypeId}, vec::Vec, boxed::Box};
 
-use sp_storage::{ChildInfo, TrackedStorageKey};
+use sp_storage::{ChildInfo, StateVersion, TrackedStorageKey};
 
 pub use scope_limited::{set_and_run_with_externalities, with_externalities};
 pub use extensions::{Extension, Extensions, ExtensionStore};
@@ -55,6 +55,18 @@ pub trait Externalities: ExtensionStore {
 	/// Write a key value pair to the offchain storage database.
 	fn set_offchain_storage(&mut self, key: &[u8], value: Option<&[u8]>);
 
+	/// Append data to the value of a key in the offchain storage database.
+	fn append_offchain_storage(&mut self, key: &[u8], data: &[u8]);
+
+	/// Set the value of a key in the offchain storage database if it is `old_value` when the
+	/// change is written to the database.
+	fn compare_and_set_offchain_storage(
+		&mut self,
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	);
+
 	/// Read runtime storage.
 	fn storage(&self, key: &[u8]) -> Option<Vec<u8>>;
 
@@ -181,7 +193,9 @@ pub trait Externalities: ExtensionStore {
 	/// This will also update all child storage keys in the top-level storage map.
 	///
 	/// The returned hash is defined by the `Block` and is SCALE encoded.
-	fn storage_root(&mut self) -> Vec<u8>;
+	///
+	/// The trie layout used for the changes is defined by the given `state_version`.
+	fn storage_root(&mut self, state_version: StateVersion) -> Vec<u8>;
 
 	/// Get the trie root of a child storage map.
 	///
@@ -192,6 +206,7 @@ pub trait Externalities: ExtensionStore {
 	fn child_storage_root(
 		&mut self,
 		child_info: &ChildInfo,
+		state_version: StateVersion,
 	) -> Vec<u8>;
 
 	/// Append storage item.
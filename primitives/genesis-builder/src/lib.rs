@@ -0,0 +1,47 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime api used to build the genesis config of a runtime from JSON.
+//!
+//! The node only needs the runtime code to build the genesis storage of a chain: it asks the
+//! runtime for its default `GenesisConfig`, applies a patch to it and hands the result back to
+//! the runtime, which writes the genesis storage.
+//!
+//! FRAME runtimes implement it with the helpers of `frame_support::genesis_builder_helper`, in
+//! the native as well as in the wasm runtime.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+use sp_std::vec::Vec;
+
+/// The result type of [`GenesisBuilder::build_config`].
+pub type Result = core::result::Result<(), sp_runtime::RuntimeString>;
+
+sp_api::decl_runtime_apis! {
+	/// Api to interact with the `GenesisConfig` of the runtime.
+	pub trait GenesisBuilder {
+		/// Returns the default `GenesisConfig` of the runtime, serialized as JSON.
+		fn create_default_config() -> Vec<u8>;
+
+		/// Build the storage of the `GenesisConfig` serialized as JSON in `json`.
+		///
+		/// The storage is written to the externalities the call is executed with. All the fields
+		/// of the `GenesisConfig` must be present in `json`.
+		fn build_config(json: Vec<u8>) -> Result;
+	}
+}
@@ -48,6 +48,7 @@ use sp_core::{
 	offchain::{
 		Timestamp, HttpRequestId, HttpRequestStatus, HttpError, StorageKind, OpaqueNetworkState,
 	},
+	storage::StateVersion,
 };
 
 #[cfg(feature = "std")]
@@ -186,7 +187,18 @@ pub trait Storage {
 	///
 	/// Returns a `Vec<u8>` that holds the SCALE encoded hash.
 	fn root(&mut self) -> Vec<u8> {
-		self.storage_root()
+		self.storage_root(StateVersion::V0)
+	}
+
+	/// "Commit" all existing operations and compute the resulting storage root.
+	///
+	/// The hashing algorithm is defined by the `Block`.
+	/// The trie layout is defined by the given state `version`.
+	///
+	/// Returns a `Vec<u8>` that holds the SCALE encoded hash.
+	#[version(2)]
+	fn root(&mut self, version: StateVersion) -> Vec<u8> {
+		self.storage_root(version)
 	}
 
 	/// "Commit" all existing operations and get the resulting storage change root.
@@ -402,7 +414,24 @@ pub trait DefaultChildStorage {
 		storage_key: &[u8],
 	) -> Vec<u8> {
 		let child_info = ChildInfo::new_default(storage_key);
-		self.child_storage_root(&child_info)
+		self.child_storage_root(&child_info, StateVersion::V0)
+	}
+
+	/// Default child root calculation.
+	///
+	/// "Commit" all existing operations and compute the resulting child storage root.
+	/// The hashing algorithm is defined by the `Block`.
+	/// The trie layout is defined by the given state `version`.
+	///
+	/// Returns a `Vec<u8>` that holds the SCALE encoded hash.
+	#[version(2)]
+	fn root(
+		&mut self,
+		storage_key: &[u8],
+		version: StateVersion,
+	) -> Vec<u8> {
+		let child_info = ChildInfo::new_default(storage_key);
+		self.child_storage_root(&child_info, version)
 	}
 
 	/// Child storage key iteration.
@@ -879,6 +908,23 @@ pub trait OffchainIndex {
 	fn clear(&mut self, key: &[u8]) {
 		self.set_offchain_storage(key, None);
 	}
+
+	/// Append data to the value of a key in the Offchain DB, in a buffered fashion.
+	///
+	/// If the key has no value, the data becomes its value.
+	fn append(&mut self, key: &[u8], data: &[u8]) {
+		self.append_offchain_storage(key, data);
+	}
+
+	/// Set the value of a key in the Offchain DB to `new_value` if its value is `old_value`,
+	/// in a buffered fashion.
+	///
+	/// The comparison is made against the value of the key when the block is imported, so
+	/// the outcome isn't visible to the runtime. A `None` `old_value` only matches a key
+	/// without a value.
+	fn compare_and_set(&mut self, key: &[u8], old_value: Option<Vec<u8>>, new_value: &[u8]) {
+		self.compare_and_set_offchain_storage(key, old_value.as_deref(), new_value);
+	}
 }
 
 #[cfg(feature = "std")]
@@ -26,7 +26,6 @@
 
 #[doc(hidden)]
 pub use codec;
-#[cfg(feature = "std")]
 #[doc(hidden)]
 pub use serde;
 #[doc(hidden)]
@@ -40,6 +39,7 @@ pub use sp_application_crypto as app_crypto;
 
 #[cfg(feature = "std")]
 pub use sp_core::storage::{Storage, StorageChild};
+pub use sp_core::storage::StateVersion;
 
 use sp_std::prelude::*;
 use sp_std::convert::TryFrom;
@@ -1316,7 +1316,6 @@ macro_rules! impl_opaque_keys_inner {
 /// }
 /// ```
 #[macro_export]
-#[cfg(feature = "std")]
 macro_rules! impl_opaque_keys {
 	{
 		$( #[ $attr:meta ] )*
@@ -1345,30 +1344,6 @@ macro_rules! impl_opaque_keys {
 	}
 }
 
-#[macro_export]
-#[cfg(not(feature = "std"))]
-macro_rules! impl_opaque_keys {
-	{
-		$( #[ $attr:meta ] )*
-		pub struct $name:ident {
-			$(
-				$( #[ $inner_attr:meta ] )*
-				pub $field:ident: $type:ty,
-			)*
-		}
-	} => {
-		$crate::impl_opaque_keys_inner! {
-			$( #[ $attr ] )*
-			pub struct $name {
-				$(
-					$( #[ $inner_attr ] )*
-					pub $field: $type,
-				)*
-			}
-		}
-	}
-}
-
 /// Trait for things which can be printed from the runtime.
 pub trait Printable {
 	/// Print the object.
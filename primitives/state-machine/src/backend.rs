@@ -20,7 +20,7 @@
 use hash_db::Hasher;
 use codec::{Decode, Encode};
 use sp_core::{
-	storage::{ChildInfo, well_known_keys, TrackedStorageKey}
+	storage::{ChildInfo, StateVersion, well_known_keys, TrackedStorageKey}
 };
 use crate::{
 	trie_backend::TrieBackend,
@@ -141,9 +141,12 @@ pub trait Backend<H: Hasher>: sp_std::fmt::Debug {
 	/// Calculate the storage root, with given delta over what is already stored in
 	/// the backend, and produce a "transaction" that can be used to commit.
 	/// Does not include child storage updates.
+	///
+	/// Modified nodes are written with the trie layout of the given `state_version`.
 	fn storage_root<'a>(
 		&self,
 		delta: impl Iterator<Item=(&'a [u8], Option<&'a [u8]>)>,
+		state_version: StateVersion,
 	) -> (H::Out, Self::Transaction) where H::Out: Ord;
 
 	/// Calculate the child storage root, with given delta over what is already stored in
@@ -153,6 +156,7 @@ pub trait Backend<H: Hasher>: sp_std::fmt::Debug {
 		&self,
 		child_info: &ChildInfo,
 		delta: impl Iterator<Item=(&'a [u8], Option<&'a [u8]>)>,
+		state_version: StateVersion,
 	) -> (H::Out, bool, Self::Transaction) where H::Out: Ord;
 
 	/// Get all key/value pairs into a Vec.
@@ -191,13 +195,14 @@ pub trait Backend<H: Hasher>: sp_std::fmt::Debug {
 			&'a ChildInfo,
 			impl Iterator<Item=(&'a [u8], Option<&'a [u8]>)>,
 		)>,
+		state_version: StateVersion,
 	) -> (H::Out, Self::Transaction) where H::Out: Ord + Encode {
 		let mut txs: Self::Transaction = Default::default();
 		let mut child_roots: Vec<_> = Default::default();
 		// child first
 		for (child_info, child_delta) in child_deltas {
 			let (child_root, empty, child_txs) =
-				self.child_storage_root(&child_info, child_delta);
+				self.child_storage_root(&child_info, child_delta, state_version);
 			let prefixed_storage_key = child_info.prefixed_storage_key();
 			txs.consolidate(child_txs);
 			if empty {
@@ -212,7 +217,8 @@ pub trait Backend<H: Hasher>: sp_std::fmt::Debug {
 				child_roots
 					.iter()
 					.map(|(k, v)| (&k[..], v.as_ref().map(|v| &v[..])))
-			)
+			),
+			state_version,
 		);
 		txs.consolidate(parent_txs);
 		(root, txs)
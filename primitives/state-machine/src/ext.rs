@@ -23,7 +23,7 @@ use crate::{
 };
 use hash_db::Hasher;
 use sp_core::{
-	storage::{well_known_keys::is_child_storage_key, ChildInfo, TrackedStorageKey},
+	storage::{well_known_keys::is_child_storage_key, ChildInfo, StateVersion, TrackedStorageKey},
 	hexdisplay::HexDisplay,
 };
 use sp_trie::{trie_types::Layout, empty_child_trie_root};
@@ -197,6 +197,19 @@ where
 		self.overlay.set_offchain_storage(key, value)
 	}
 
+	fn append_offchain_storage(&mut self, key: &[u8], data: &[u8]) {
+		self.overlay.append_offchain_storage(key, data)
+	}
+
+	fn compare_and_set_offchain_storage(
+		&mut self,
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) {
+		self.overlay.compare_and_set_offchain_storage(key, old_value, new_value)
+	}
+
 	fn storage(&self, key: &[u8]) -> Option<StorageValue> {
 		let _guard = guard();
 		let result = self.overlay.storage(key).map(|x| x.map(|x| x.to_vec())).unwrap_or_else(||
@@ -520,7 +533,7 @@ where
 		StorageAppend::new(current_value).append(value);
 	}
 
-	fn storage_root(&mut self) -> Vec<u8> {
+	fn storage_root(&mut self, state_version: StateVersion) -> Vec<u8> {
 		let _guard = guard();
 		if let Some(ref root) = self.storage_transaction_cache.transaction_storage_root {
 			trace!(target: "state", "{:04x}: Root(cached) {}",
@@ -530,7 +543,11 @@ where
 			return root.encode();
 		}
 
-		let root = self.overlay.storage_root(self.backend, self.storage_transaction_cache);
+		let root = self.overlay.storage_root(
+			self.backend,
+			self.storage_transaction_cache,
+			state_version,
+		);
 		trace!(target: "state", "{:04x}: Root {}", self.id, HexDisplay::from(&root.as_ref()));
 		root.encode()
 	}
@@ -538,6 +555,7 @@ where
 	fn child_storage_root(
 		&mut self,
 		child_info: &ChildInfo,
+		state_version: StateVersion,
 	) -> Vec<u8> {
 		let _guard = guard();
 		let storage_key = child_info.storage_key();
@@ -558,7 +576,7 @@ where
 		} else {
 			let root = if let Some((changes, info)) = self.overlay.child_changes(storage_key) {
 				let delta = changes.map(|(k, v)| (k.as_ref(), v.value().map(AsRef::as_ref)));
-				Some(self.backend.child_storage_root(info, delta))
+				Some(self.backend.child_storage_root(info, delta, state_version))
 			} else {
 				None
 			};
@@ -699,6 +717,8 @@ where
 			None,
 			Default::default(),
 			self.storage_transaction_cache,
+			// The changes are dropped, so the state version does not matter.
+			StateVersion::default(),
 		).expect(EXT_NOT_ALLOWED_TO_FAIL);
 		self.backend.wipe().expect(EXT_NOT_ALLOWED_TO_FAIL);
 		self.mark_dirty();
@@ -717,6 +737,8 @@ where
 			None,
 			Default::default(),
 			self.storage_transaction_cache,
+			// Benchmarks measure the storage root with the latest state version.
+			StateVersion::V1,
 		).expect(EXT_NOT_ALLOWED_TO_FAIL);
 		self.backend.commit(
 			changes.transaction_storage_root,
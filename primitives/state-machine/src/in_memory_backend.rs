@@ -24,7 +24,7 @@ use std::collections::{BTreeMap, HashMap};
 use hash_db::Hasher;
 use sp_trie::{MemoryDB, empty_trie_root, Layout};
 use codec::Codec;
-use sp_core::storage::{ChildInfo, Storage};
+use sp_core::storage::{ChildInfo, StateVersion, Storage};
 
 /// Create a new empty instance of in-memory backend.
 pub fn new_in_mem<H: Hasher>() -> TrieBackend<MemoryDB<H>, H>
@@ -45,9 +45,10 @@ where
 	>(
 		&self,
 		changes: T,
+		state_version: StateVersion,
 	) -> Self {
 		let mut clone = self.clone();
-		clone.insert(changes);
+		clone.insert(changes, state_version);
 		clone
 	}
 
@@ -57,6 +58,7 @@ where
 	>(
 		&mut self,
 		changes: T,
+		state_version: StateVersion,
 	) {
 		let (top, child) = changes.into_iter().partition::<Vec<_>, _>(|v| v.0.is_none());
 		let (root, transaction) = self.full_storage_root(
@@ -65,6 +67,7 @@ where
 				.filter_map(|v|
 					v.0.as_ref().map(|c| (c, v.1.iter().map(|(k, v)| (&k[..], v.as_deref()))))
 				),
+			state_version,
 		);
 
 		self.apply_transaction(root, transaction);
@@ -107,29 +110,55 @@ where
 	}
 }
 
-impl<H: Hasher> From<HashMap<Option<ChildInfo>, BTreeMap<StorageKey, StorageValue>>>
+impl<H: Hasher>
+	From<(HashMap<Option<ChildInfo>, BTreeMap<StorageKey, StorageValue>>, StateVersion)>
 	for TrieBackend<MemoryDB<H>, H>
 where
 	H::Out: Codec + Ord,
 {
-	fn from(inner: HashMap<Option<ChildInfo>, BTreeMap<StorageKey, StorageValue>>) -> Self {
+	fn from(
+		(inner, state_version): (
+			HashMap<Option<ChildInfo>, BTreeMap<StorageKey, StorageValue>>,
+			StateVersion,
+		),
+	) -> Self {
 		let mut backend = new_in_mem();
 		backend.insert(
 			inner.into_iter().map(|(k, m)| (k, m.into_iter().map(|(k, v)| (k, Some(v))).collect())),
+			state_version,
 		);
 		backend
 	}
 }
 
-impl<H: Hasher> From<Storage> for TrieBackend<MemoryDB<H>, H>
+impl<H: Hasher> From<HashMap<Option<ChildInfo>, BTreeMap<StorageKey, StorageValue>>>
+	for TrieBackend<MemoryDB<H>, H>
 where
 	H::Out: Codec + Ord,
 {
-	fn from(inners: Storage) -> Self {
+	fn from(inner: HashMap<Option<ChildInfo>, BTreeMap<StorageKey, StorageValue>>) -> Self {
+		(inner, StateVersion::default()).into()
+	}
+}
+
+impl<H: Hasher> From<(Storage, StateVersion)> for TrieBackend<MemoryDB<H>, H>
+where
+	H::Out: Codec + Ord,
+{
+	fn from((inners, state_version): (Storage, StateVersion)) -> Self {
 		let mut inner: HashMap<Option<ChildInfo>, BTreeMap<StorageKey, StorageValue>>
 			= inners.children_default.into_iter().map(|(_k, c)| (Some(c.child_info), c.data)).collect();
 		inner.insert(None, inners.top);
-		inner.into()
+		(inner, state_version).into()
+	}
+}
+
+impl<H: Hasher> From<Storage> for TrieBackend<MemoryDB<H>, H>
+where
+	H::Out: Codec + Ord,
+{
+	fn from(inners: Storage) -> Self {
+		(inners, StateVersion::default()).into()
 	}
 }
 
@@ -182,7 +211,8 @@ mod tests {
 			vec![(
 				Some(child_info.clone()),
 				vec![(b"2".to_vec(), Some(b"3".to_vec()))]
-			)]
+			)],
+			StateVersion::default(),
 		);
 		let trie_backend = storage.as_trie_backend().unwrap();
 		assert_eq!(trie_backend.child_storage(child_info, b"2").unwrap(),
@@ -196,8 +226,15 @@ mod tests {
 		let mut storage = new_in_mem::<BlakeTwo256>();
 		let child_info = ChildInfo::new_default(b"1");
 
-		storage.insert(vec![(Some(child_info.clone()), vec![(b"2".to_vec(), Some(b"3".to_vec()))])]);
-		storage.insert(vec![(Some(child_info.clone()), vec![(b"1".to_vec(), Some(b"3".to_vec()))])]);
+		let state_version = StateVersion::default();
+		storage.insert(
+			vec![(Some(child_info.clone()), vec![(b"2".to_vec(), Some(b"3".to_vec()))])],
+			state_version,
+		);
+		storage.insert(
+			vec![(Some(child_info.clone()), vec![(b"1".to_vec(), Some(b"3".to_vec()))])],
+			state_version,
+		);
 
 		assert_eq!(storage.child_storage(&child_info, &b"2"[..]), Ok(Some(b"3".to_vec())));
 		assert_eq!(storage.child_storage(&child_info, &b"1"[..]), Ok(Some(b"3".to_vec())));
@@ -1149,7 +1149,7 @@ mod tests {
 
 		// fetch execution proof from 'remote' full node
 		let remote_backend = trie_backend::tests::test_trie();
-		let remote_root = remote_backend.storage_root(std::iter::empty()).0;
+		let remote_root = remote_backend.storage_root(std::iter::empty(), Default::default()).0;
 		let (remote_result, remote_proof) = prove_execution::<_, _, u64, _, _>(
 			remote_backend,
 			&mut Default::default(),
@@ -1536,7 +1536,7 @@ mod tests {
 		let child_info = &child_info;
 		// fetch read proof from 'remote' full node
 		let remote_backend = trie_backend::tests::test_trie();
-		let remote_root = remote_backend.storage_root(std::iter::empty()).0;
+		let remote_root = remote_backend.storage_root(std::iter::empty(), Default::default()).0;
 		let remote_proof = prove_read(remote_backend, &[b"value2"]).unwrap();
 		let remote_proof = test_compact(remote_proof, &remote_root);
  		// check proof locally
@@ -1558,7 +1558,7 @@ mod tests {
 		assert_eq!(local_result2, false);
 		// on child trie
 		let remote_backend = trie_backend::tests::test_trie();
-		let remote_root = remote_backend.storage_root(std::iter::empty()).0;
+		let remote_root = remote_backend.storage_root(std::iter::empty(), Default::default()).0;
 		let remote_proof = prove_child_read(
 			remote_backend,
 			child_info,
@@ -1590,7 +1590,7 @@ mod tests {
 	#[test]
 	fn prove_read_with_size_limit_works() {
 		let remote_backend = trie_backend::tests::test_trie();
-		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let remote_root = remote_backend.storage_root(::std::iter::empty(), Default::default()).0;
 		let (proof, count) = prove_range_read_with_size(remote_backend, None, None, 0, None).unwrap();
 		// Alwasys contains at least some nodes.
 		assert_eq!(proof.into_memory_db::<BlakeTwo256>().drain().len(), 3);
@@ -1663,6 +1663,7 @@ mod tests {
 					(&b"key6"[..], Some(&b"val7"[..])),
 				].into_iter()),
 			].into_iter(),
+			Default::default(),
 		);
 		remote_backend.backend_storage_mut().consolidate(transaction);
 		remote_backend.essence.set_root(remote_root.clone());
@@ -1703,7 +1704,7 @@ mod tests {
 			);
 			ext.set_child_storage(&child_info_1, b"abc".to_vec(), b"def".to_vec());
 			ext.set_child_storage(&child_info_2, b"abc".to_vec(), b"def".to_vec());
-			ext.storage_root();
+			ext.storage_root(Default::default());
 			cache.transaction.unwrap()
 		};
 		let mut duplicate = false;
@@ -43,7 +43,7 @@ use std::collections::{HashMap as Map, hash_map::Entry as MapEntry};
 use sp_std::collections::btree_map::{BTreeMap as Map, Entry as MapEntry};
 use sp_std::collections::btree_set::BTreeSet;
 use codec::{Decode, Encode};
-use sp_core::storage::{well_known_keys::EXTRINSIC_INDEX, ChildInfo};
+use sp_core::storage::{well_known_keys::EXTRINSIC_INDEX, ChildInfo, StateVersion};
 use sp_core::offchain::OffchainOverlayedChange;
 use hash_db::Hasher;
 use crate::DefaultError;
@@ -535,9 +535,16 @@ impl OverlayedChanges {
 		changes_trie_state: Option<&ChangesTrieState<H, N>>,
 		parent_hash: H::Out,
 		mut cache: StorageTransactionCache<B::Transaction, H, N>,
+		state_version: StateVersion,
 	) -> Result<StorageChanges<B::Transaction, H, N>, DefaultError>
 		where H::Out: Ord + Encode + 'static {
-		self.drain_storage_changes(backend, changes_trie_state, parent_hash, &mut cache)
+		self.drain_storage_changes(
+			backend,
+			changes_trie_state,
+			parent_hash,
+			&mut cache,
+			state_version,
+		)
 	}
 
 	/// Drain all changes into a [`StorageChanges`] instance. Leave empty overlay in place.
@@ -548,11 +555,12 @@ impl OverlayedChanges {
 		changes_trie_state: Option<&ChangesTrieState<H, N>>,
 		parent_hash: H::Out,
 		mut cache: &mut StorageTransactionCache<B::Transaction, H, N>,
+		state_version: StateVersion,
 	) -> Result<StorageChanges<B::Transaction, H, N>, DefaultError>
 		where H::Out: Ord + Encode + 'static {
 		// If the transaction does not exist, we generate it.
 		if cache.transaction.is_none() {
-			self.storage_root(backend, &mut cache);
+			self.storage_root(backend, &mut cache, state_version);
 		}
 
 		let (transaction, transaction_storage_root) = cache.transaction.take()
@@ -623,10 +631,14 @@ impl OverlayedChanges {
 	/// as seen by the current transaction.
 	///
 	/// Returns the storage root and caches storage transaction in the given `cache`.
+	///
+	/// Modified trie nodes are written with the layout of the given `state_version`, so
+	/// values changed under a newer state version are migrated as they are written.
 	pub fn storage_root<H: Hasher, N: BlockNumber, B: Backend<H>>(
 		&self,
 		backend: &B,
 		cache: &mut StorageTransactionCache<B::Transaction, H, N>,
+		state_version: StateVersion,
 	) -> H::Out
 		where H::Out: Ord + Encode,
 	{
@@ -636,7 +648,7 @@ impl OverlayedChanges {
 				|(k, v)| (&k[..], v.value().map(|v| &v[..]))
 			)));
 
-		let (root, transaction) = backend.full_storage_root(delta, child_delta);
+		let (root, transaction) = backend.full_storage_root(delta, child_delta, state_version);
 
 		cache.transaction = Some(transaction);
 		cache.transaction_storage_root = Some(root);
@@ -708,6 +720,22 @@ impl OverlayedChanges {
 		}
 	}
 
+	/// Append data to the value of a key in the offchain storage overlay.
+	pub fn append_offchain_storage(&mut self, key: &[u8], data: &[u8]) {
+		self.offchain.append(sp_core::offchain::STORAGE_PREFIX, key, data)
+	}
+
+	/// Set the value of a key in the offchain storage overlay if it is `old_value` when the
+	/// change is applied to the offchain storage.
+	pub fn compare_and_set_offchain_storage(
+		&mut self,
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) {
+		self.offchain.compare_and_set(sp_core::offchain::STORAGE_PREFIX, key, old_value, new_value)
+	}
+
 	/// Add transaction index operation.
 	pub fn add_transaction_index(&mut self, op: IndexOperation) {
 		self.transaction_index_ops.push(op)
@@ -937,7 +965,7 @@ mod tests {
 		);
 		const ROOT: [u8; 32] = hex!("39245109cef3758c2eed2ccba8d9b370a917850af3824bc8348d505df2c298fa");
 
-		assert_eq!(&ext.storage_root()[..], &ROOT);
+		assert_eq!(&ext.storage_root(StateVersion::default())[..], &ROOT);
 	}
 
 	#[test]
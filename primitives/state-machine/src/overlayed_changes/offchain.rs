@@ -68,6 +68,35 @@ impl OffchainOverlayedChanges {
 		);
 	}
 
+	/// Append data to the value associated with a key under a prefix.
+	pub fn append(&mut self, prefix: &[u8], key: &[u8], data: &[u8]) {
+		self.merge(prefix, key, OffchainOverlayedChange::Append(data.to_vec()));
+	}
+
+	/// Set the value associated with a key under a prefix to `new_value` if its value is
+	/// `old_value` at the time the change is applied.
+	pub fn compare_and_set(
+		&mut self,
+		prefix: &[u8],
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) {
+		self.merge(prefix, key, OffchainOverlayedChange::CompareAndSet {
+			old_value: old_value.map(|v| v.to_vec()),
+			new_value: new_value.to_vec(),
+		});
+	}
+
+	fn merge(&mut self, prefix: &[u8], key: &[u8], change: OffchainOverlayedChange) {
+		let key = (prefix.to_vec(), key.to_vec());
+		let change = match self.0.get(&key) {
+			Some(entry) => entry.value_ref().clone().merge(change),
+			None => change,
+		};
+		let _ = self.0.set(key, change, None);
+	}
+
 	/// Obtain a associated value to the given key in storage with prefix.
 	pub fn get(&self, prefix: &[u8], key: &[u8]) -> Option<OffchainOverlayedChange> {
 		let key = (prefix.to_vec(), key.to_vec());
@@ -127,4 +156,23 @@ mod test {
 		);
 		assert_eq!(iter.next(), None);
 	}
+
+	#[test]
+	fn test_append_and_compare_and_set() {
+		let mut ooc = OffchainOverlayedChanges::default();
+		ooc.append(STORAGE_PREFIX, b"ppp", b"a");
+		ooc.append(STORAGE_PREFIX, b"ppp", b"b");
+		assert_eq!(
+			ooc.get(STORAGE_PREFIX, b"ppp"),
+			Some(OffchainOverlayedChange::Append(b"ab".to_vec())),
+		);
+
+		ooc.set(STORAGE_PREFIX, b"qqq", b"a");
+		ooc.compare_and_set(STORAGE_PREFIX, b"qqq", Some(b"a"), b"b");
+		ooc.compare_and_set(STORAGE_PREFIX, b"qqq", Some(b"a"), b"c");
+		assert_eq!(
+			ooc.get(STORAGE_PREFIX, b"qqq"),
+			Some(OffchainOverlayedChange::SetValue(b"b".to_vec())),
+		);
+	}
 }
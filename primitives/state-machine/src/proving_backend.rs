@@ -30,7 +30,7 @@ pub use sp_trie::{Recorder, trie_types::{Layout, TrieError}};
 use crate::trie_backend::TrieBackend;
 use crate::trie_backend_essence::{Ephemeral, TrieBackendEssence, TrieBackendStorage};
 use crate::{Error, ExecutionError, Backend, DBValue};
-use sp_core::storage::ChildInfo;
+use sp_core::storage::{ChildInfo, StateVersion};
 
 /// Patricia trie-based backend specialized in get value proofs.
 pub struct ProvingBackendRecorder<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> {
@@ -115,6 +115,8 @@ struct ProofRecorderInner<Hash> {
 	records: HashMap<Hash, Option<DBValue>>,
 	/// The encoded size of all recorded values.
 	encoded_size: usize,
+	/// The keys recorded in each of the open transactions, the last one being the innermost.
+	transactions: Vec<Vec<Hash>>,
 }
 
 /// Global proof recorder, act as a layer over a hash db for recording queried data.
@@ -123,14 +125,18 @@ pub struct ProofRecorder<Hash> {
 	inner: Arc<RwLock<ProofRecorderInner<Hash>>>,
 }
 
-impl<Hash: std::hash::Hash + Eq> ProofRecorder<Hash> {
+impl<Hash: std::hash::Hash + Eq + Clone> ProofRecorder<Hash> {
 	/// Record the given `key` => `val` combination.
 	pub fn record(&self, key: Hash, val: Option<DBValue>) {
 		let mut inner = self.inner.write();
-		let encoded_size = if let Entry::Vacant(entry) = inner.records.entry(key) {
+		let inner = &mut *inner;
+		let encoded_size = if let Entry::Vacant(entry) = inner.records.entry(key.clone()) {
 			let encoded_size = val.as_ref().map(Encode::encoded_size).unwrap_or(0);
 
 			entry.insert(val);
+			if let Some(transaction) = inner.transactions.last_mut() {
+				transaction.push(key);
+			}
 			encoded_size
 		} else {
 			0
@@ -139,6 +145,41 @@ impl<Hash: std::hash::Hash + Eq> ProofRecorder<Hash> {
 		inner.encoded_size += encoded_size;
 	}
 
+	/// Start a new transaction.
+	///
+	/// The values recorded from now on are forgotten if the transaction is rolled back.
+	/// Transactions can be nested.
+	pub fn start_transaction(&self) {
+		self.inner.write().transactions.push(Vec::new());
+	}
+
+	/// Forget all the values recorded since the innermost transaction was started, and close it.
+	///
+	/// Does nothing if there is no open transaction.
+	pub fn rollback_transaction(&self) {
+		let mut inner = self.inner.write();
+		if let Some(transaction) = inner.transactions.pop() {
+			for key in transaction {
+				let encoded_size = inner.records.remove(&key)
+					.and_then(|val| val.as_ref().map(Encode::encoded_size))
+					.unwrap_or(0);
+				inner.encoded_size -= encoded_size;
+			}
+		}
+	}
+
+	/// Keep all the values recorded since the innermost transaction was started, and close it.
+	///
+	/// Does nothing if there is no open transaction.
+	pub fn commit_transaction(&self) {
+		let mut inner = self.inner.write();
+		if let Some(transaction) = inner.transactions.pop() {
+			if let Some(parent) = inner.transactions.last_mut() {
+				parent.extend(transaction);
+			}
+		}
+	}
+
 	/// Returns the value at the given `key`.
 	pub fn get(&self, key: &Hash) -> Option<Option<DBValue>> {
 		self.inner.read().records.get(key).cloned()
@@ -170,6 +211,7 @@ impl<Hash: std::hash::Hash + Eq> ProofRecorder<Hash> {
 		let mut inner = self.inner.write();
 		inner.records.clear();
 		inner.encoded_size = 0;
+		inner.transactions.clear();
 	}
 }
 
@@ -336,16 +378,18 @@ impl<'a, S, H> Backend<H> for ProvingBackend<'a, S, H>
 	fn storage_root<'b>(
 		&self,
 		delta: impl Iterator<Item=(&'b [u8], Option<&'b [u8]>)>,
+		state_version: StateVersion,
 	) -> (H::Out, Self::Transaction) where H::Out: Ord {
-		self.0.storage_root(delta)
+		self.0.storage_root(delta, state_version)
 	}
 
 	fn child_storage_root<'b>(
 		&self,
 		child_info: &ChildInfo,
 		delta: impl Iterator<Item=(&'b [u8], Option<&'b [u8]>)>,
+		state_version: StateVersion,
 	) -> (H::Out, bool, Self::Transaction) where H::Out: Ord {
-		self.0.child_storage_root(child_info, delta)
+		self.0.child_storage_root(child_info, delta, state_version)
 	}
 
 	fn register_overlay_stats(&self, _stats: &crate::stats::StateMachineStats) { }
@@ -419,32 +463,47 @@ mod tests {
 		assert_eq!(trie_backend.storage(b"key").unwrap(), proving_backend.storage(b"key").unwrap());
 		assert_eq!(trie_backend.pairs(), proving_backend.pairs());
 
-		let (trie_root, mut trie_mdb) = trie_backend.storage_root(std::iter::empty());
-		let (proving_root, mut proving_mdb) = proving_backend.storage_root(std::iter::empty());
+		let (trie_root, mut trie_mdb) =
+			trie_backend.storage_root(std::iter::empty(), Default::default());
+		let (proving_root, mut proving_mdb) =
+			proving_backend.storage_root(std::iter::empty(), Default::default());
 		assert_eq!(trie_root, proving_root);
 		assert_eq!(trie_mdb.drain(), proving_mdb.drain());
 	}
 
 	#[test]
 	fn proof_recorded_and_checked() {
-		let contents = (0..64).map(|i| (vec![i], Some(vec![i]))).collect::<Vec<_>>();
+		proof_recorded_and_checked_inner(StateVersion::V0);
+		proof_recorded_and_checked_inner(StateVersion::V1);
+	}
+
+	fn proof_recorded_and_checked_inner(state_version: StateVersion) {
+		let size_content = 34; // above hashable value threshold.
+		let value_range = 0..64;
+		let contents = value_range.clone()
+			.map(|i| (vec![i], Some(vec![i; size_content])))
+			.collect::<Vec<_>>();
 		let in_memory = InMemoryBackend::<BlakeTwo256>::default();
-		let mut in_memory = in_memory.update(vec![(None, contents)]);
-		let in_memory_root = in_memory.storage_root(::std::iter::empty()).0;
-		(0..64).for_each(|i| assert_eq!(in_memory.storage(&[i]).unwrap().unwrap(), vec![i]));
+		let mut in_memory = in_memory.update(vec![(None, contents)], state_version);
+		let in_memory_root = in_memory.storage_root(::std::iter::empty(), state_version).0;
+		value_range.clone().for_each(|i|
+			assert_eq!(in_memory.storage(&[i]).unwrap().unwrap(), vec![i; size_content])
+		);
 
 		let trie = in_memory.as_trie_backend().unwrap();
-		let trie_root = trie.storage_root(::std::iter::empty()).0;
+		let trie_root = trie.storage_root(::std::iter::empty(), state_version).0;
 		assert_eq!(in_memory_root, trie_root);
-		(0..64).for_each(|i| assert_eq!(trie.storage(&[i]).unwrap().unwrap(), vec![i]));
+		value_range.for_each(|i|
+			assert_eq!(trie.storage(&[i]).unwrap().unwrap(), vec![i; size_content])
+		);
 
 		let proving = ProvingBackend::new(trie);
-		assert_eq!(proving.storage(&[42]).unwrap().unwrap(), vec![42]);
+		assert_eq!(proving.storage(&[42]).unwrap().unwrap(), vec![42; size_content]);
 
 		let proof = proving.extract_proof();
 
 		let proof_check = create_proof_check_backend::<BlakeTwo256>(in_memory_root.into(), proof).unwrap();
-		assert_eq!(proof_check.storage(&[42]).unwrap().unwrap(), vec![42]);
+		assert_eq!(proof_check.storage(&[42]).unwrap().unwrap(), vec![42; size_content]);
 	}
 
 	#[test]
@@ -461,11 +520,12 @@ mod tests {
 				(10..15).map(|i| (vec![i], Some(vec![i]))).collect()),
 		];
 		let in_memory = InMemoryBackend::<BlakeTwo256>::default();
-		let mut in_memory = in_memory.update(contents);
+		let mut in_memory = in_memory.update(contents, Default::default());
 		let child_storage_keys = vec![child_info_1.to_owned(), child_info_2.to_owned()];
 		let in_memory_root = in_memory.full_storage_root(
 			std::iter::empty(),
-			child_storage_keys.iter().map(|k|(k, std::iter::empty()))
+			child_storage_keys.iter().map(|k|(k, std::iter::empty())),
+			Default::default(),
 		).0;
 		(0..64).for_each(|i| assert_eq!(
 			in_memory.storage(&[i]).unwrap().unwrap(),
@@ -481,7 +541,7 @@ mod tests {
 		));
 
 		let trie = in_memory.as_trie_backend().unwrap();
-		let trie_root = trie.storage_root(std::iter::empty()).0;
+		let trie_root = trie.storage_root(std::iter::empty(), Default::default()).0;
 		assert_eq!(in_memory_root, trie_root);
 		(0..64).for_each(|i| assert_eq!(
 			trie.storage(&[i]).unwrap().unwrap(),
@@ -547,4 +607,50 @@ mod tests {
 		assert!(backend.storage(b"doesnotexist2").unwrap().is_none());
 		check_estimation(&backend);
 	}
+
+	#[test]
+	fn proof_recorder_transactions_work() {
+		let trie_backend = test_trie();
+		let recorder = ProofRecorder::<sp_core::H256>::default();
+
+		// Each read uses a new backend, so that nothing is cached outside of the recorder.
+		let read = |key: &[u8]| {
+			ProvingBackend::new_with_recorder(&trie_backend, recorder.clone())
+				.storage(key)
+				.unwrap()
+		};
+		let proof_nodes = || {
+			let mut nodes = recorder.to_storage_proof().into_nodes();
+			nodes.sort();
+			nodes
+		};
+
+		assert_eq!(read(b"key"), Some(b"value".to_vec()));
+		let outer_proof = proof_nodes();
+		let outer_size = recorder.estimate_encoded_size();
+
+		recorder.start_transaction();
+		assert_eq!(read(b"value1"), Some(vec![42]));
+		assert!(recorder.estimate_encoded_size() > outer_size);
+		recorder.rollback_transaction();
+		assert_eq!(proof_nodes(), outer_proof);
+		assert_eq!(recorder.estimate_encoded_size(), outer_size);
+
+		recorder.start_transaction();
+		recorder.start_transaction();
+		assert_eq!(read(b"value2"), Some(vec![24]));
+		recorder.commit_transaction();
+		let committed_size = recorder.estimate_encoded_size();
+		assert!(committed_size > outer_size);
+
+		// The values recorded in the committed inner transaction belong to the outer one.
+		recorder.rollback_transaction();
+		assert_eq!(recorder.estimate_encoded_size(), outer_size);
+
+		recorder.start_transaction();
+		assert_eq!(read(b"value2"), Some(vec![24]));
+		recorder.commit_transaction();
+		assert_eq!(recorder.estimate_encoded_size(), committed_size);
+		assert_eq!(recorder.to_storage_proof().encoded_size(), committed_size);
+	}
 }
@@ -24,7 +24,7 @@ use std::{
 use crate::{Backend, StorageKey, StorageValue};
 use hash_db::Hasher;
 use sp_core::{
-	storage::{ChildInfo, TrackedStorageKey},
+	storage::{ChildInfo, StateVersion, TrackedStorageKey},
 	traits::Externalities, Blake2Hasher,
 };
 use codec::Encode;
@@ -78,6 +78,19 @@ impl<'a, H: Hasher, B: 'a + Backend<H>> Externalities for ReadOnlyExternalities<
 		panic!("Should not be used in read-only externalities!")
 	}
 
+	fn append_offchain_storage(&mut self, _key: &[u8], _data: &[u8]) {
+		panic!("Should not be used in read-only externalities!")
+	}
+
+	fn compare_and_set_offchain_storage(
+		&mut self,
+		_key: &[u8],
+		_old_value: Option<&[u8]>,
+		_new_value: &[u8],
+	) {
+		panic!("Should not be used in read-only externalities!")
+	}
+
 	fn storage(&self, key: &[u8]) -> Option<StorageValue> {
 		self.backend.storage(key).expect("Backed failed for storage in ReadOnlyExternalities")
 	}
@@ -157,13 +170,14 @@ impl<'a, H: Hasher, B: 'a + Backend<H>> Externalities for ReadOnlyExternalities<
 		unimplemented!("storage_append is not supported in ReadOnlyExternalities")
 	}
 
-	fn storage_root(&mut self) -> Vec<u8> {
+	fn storage_root(&mut self, _state_version: StateVersion) -> Vec<u8> {
 		unimplemented!("storage_root is not supported in ReadOnlyExternalities")
 	}
 
 	fn child_storage_root(
 		&mut self,
 		_child_info: &ChildInfo,
+		_state_version: StateVersion,
 	) -> Vec<u8> {
 		unimplemented!("child_storage_root is not supported in ReadOnlyExternalities")
 	}
@@ -36,7 +36,7 @@ use sp_core::{
 	offchain::testing::TestPersistentOffchainDB,
 	storage::{
 		well_known_keys::{CHANGES_TRIE_CONFIG, CODE, is_child_storage_key},
-		Storage,
+		StateVersion, Storage,
 	},
 	traits::TaskExecutorExt,
 	testing::TaskExecutor,
@@ -59,6 +59,8 @@ where
 	changes_trie_storage: ChangesTrieInMemoryStorage<H, N>,
 	/// Extensions.
 	pub extensions: Extensions,
+	/// State version to use during tests.
+	pub state_version: StateVersion,
 }
 
 impl<H: Hasher, N: ChangesTrieBlockNumber> TestExternalities<H, N>
@@ -88,13 +90,29 @@ where
 		Self::new_with_code(&[], storage)
 	}
 
+	/// Create a new instance of `TestExternalities` with storage written using the given
+	/// state version.
+	pub fn new_with_state_version(storage: Storage, state_version: StateVersion) -> Self {
+		Self::new_with_code_and_state(&[], storage, state_version)
+	}
+
 	/// New empty test externalities.
 	pub fn new_empty() -> Self {
 		Self::new_with_code(&[], Storage::default())
 	}
 
 	/// Create a new instance of `TestExternalities` with code and storage.
-	pub fn new_with_code(code: &[u8], mut storage: Storage) -> Self {
+	pub fn new_with_code(code: &[u8], storage: Storage) -> Self {
+		Self::new_with_code_and_state(code, storage, Default::default())
+	}
+
+	/// Create a new instance of `TestExternalities` with code and storage written using the
+	/// given state version.
+	pub fn new_with_code_and_state(
+		code: &[u8],
+		mut storage: Storage,
+		state_version: StateVersion,
+	) -> Self {
 		let mut overlay = OverlayedChanges::default();
 		let changes_trie_config = storage.top.get(CHANGES_TRIE_CONFIG)
 			.and_then(|v| Decode::decode(&mut &v[..]).ok());
@@ -116,8 +134,9 @@ where
 			changes_trie_config,
 			extensions,
 			changes_trie_storage: ChangesTrieInMemoryStorage::new(),
-			backend: storage.into(),
+			backend: (storage, state_version).into(),
 			storage_transaction_cache: Default::default(),
+			state_version,
 		}
 	}
 
@@ -138,7 +157,7 @@ where
 
 	/// Insert key/value into backend
 	pub fn insert(&mut self, k: StorageKey, v: StorageValue) {
-		self.backend.insert(vec![(None, vec![(k, Some(v))])]);
+		self.backend.insert(vec![(None, vec![(k, Some(v))])], self.state_version);
 	}
 
 	/// Registers the given extension for this instance.
@@ -170,7 +189,7 @@ where
 			))
 		}
 
-		self.backend.update(transaction)
+		self.backend.update(transaction, self.state_version)
 	}
 
 	/// Commit all pending changes to the underlying backend.
@@ -184,6 +203,7 @@ where
 			None,
 			Default::default(),
 			&mut Default::default(),
+			self.state_version,
 		)?;
 
 		self.backend.apply_transaction(changes.transaction_storage_root, changes.transaction);
@@ -308,7 +328,29 @@ mod tests {
 		ext.set_storage(b"dog".to_vec(), b"puppy".to_vec());
 		ext.set_storage(b"dogglesworth".to_vec(), b"cat".to_vec());
 		let root = H256::from(hex!("ed4d8c799d996add422395a6abd7545491d40bd838d738afafa1b8a4de625489"));
-		assert_eq!(H256::from_slice(ext.storage_root().as_slice()), root);
+		assert_eq!(H256::from_slice(ext.storage_root(Default::default()).as_slice()), root);
+	}
+
+	#[test]
+	fn commit_with_state_version_v1_only_changes_large_values() {
+		let mut small = TestExternalities::<BlakeTwo256, u64>::new_with_state_version(
+			Default::default(),
+			StateVersion::V1,
+		);
+		let mut ext = small.ext();
+		ext.set_storage(b"doe".to_vec(), b"reindeer".to_vec());
+		ext.set_storage(b"dog".to_vec(), b"puppy".to_vec());
+		ext.set_storage(b"dogglesworth".to_vec(), b"cat".to_vec());
+		let root = H256::from(hex!("ed4d8c799d996add422395a6abd7545491d40bd838d738afafa1b8a4de625489"));
+		assert_eq!(H256::from_slice(ext.storage_root(StateVersion::V1).as_slice()), root);
+
+		let large_root = |state_version| {
+			let mut large = TestExternalities::<BlakeTwo256, u64>::default();
+			let mut ext = large.ext();
+			ext.set_storage(b"doe".to_vec(), vec![1; 64]);
+			ext.storage_root(state_version)
+		};
+		assert_ne!(large_root(StateVersion::V0), large_root(StateVersion::V1));
 	}
 
 	#[test]
@@ -19,9 +19,11 @@
 
 use crate::{warn, debug};
 use hash_db::Hasher;
-use sp_trie::{Trie, delta_trie_root, empty_child_trie_root, child_delta_trie_root};
+use sp_trie::{
+	Trie, delta_trie_root, empty_child_trie_root, child_delta_trie_root, LayoutV0, LayoutV1,
+};
 use sp_trie::trie_types::{TrieDB, TrieError, Layout};
-use sp_core::storage::{ChildInfo, ChildType};
+use sp_core::storage::{ChildInfo, ChildType, StateVersion};
 use codec::{Codec, Decode};
 use crate::{
 	StorageKey, StorageValue, Backend,
@@ -183,6 +185,7 @@ impl<S: TrieBackendStorage<H>, H: Hasher> Backend<H> for TrieBackend<S, H> where
 	fn storage_root<'a>(
 		&self,
 		delta: impl Iterator<Item=(&'a [u8], Option<&'a [u8]>)>,
+		state_version: StateVersion,
 	) -> (H::Out, Self::Transaction) where H::Out: Ord {
 		let mut write_overlay = S::Overlay::default();
 		let mut root = *self.essence.root();
@@ -193,7 +196,14 @@ impl<S: TrieBackendStorage<H>, H: Hasher> Backend<H> for TrieBackend<S, H> where
 				&mut write_overlay,
 			);
 
-			match delta_trie_root::<Layout<H>, _, _, _, _, _>(&mut eph, root, delta) {
+			let res = match state_version {
+				StateVersion::V0 =>
+					delta_trie_root::<LayoutV0<H>, _, _, _, _, _>(&mut eph, root, delta),
+				StateVersion::V1 =>
+					delta_trie_root::<LayoutV1<H>, _, _, _, _, _>(&mut eph, root, delta),
+			};
+
+			match res {
 				Ok(ret) => root = ret,
 				Err(e) => warn!(target: "trie", "Failed to write to trie: {}", e),
 			}
@@ -206,6 +216,7 @@ impl<S: TrieBackendStorage<H>, H: Hasher> Backend<H> for TrieBackend<S, H> where
 		&self,
 		child_info: &ChildInfo,
 		delta: impl Iterator<Item=(&'a [u8], Option<&'a [u8]>)>,
+		state_version: StateVersion,
 	) -> (H::Out, bool, Self::Transaction) where H::Out: Ord {
 		let default_root = match child_info.child_type() {
 			ChildType::ParentKeyId => empty_child_trie_root::<Layout<H>>()
@@ -228,12 +239,22 @@ impl<S: TrieBackendStorage<H>, H: Hasher> Backend<H> for TrieBackend<S, H> where
 				&mut write_overlay,
 			);
 
-			match child_delta_trie_root::<Layout<H>, _, _, _, _, _, _>(
-				child_info.keyspace(),
-				&mut eph,
-				root,
-				delta,
-			) {
+			let res = match state_version {
+				StateVersion::V0 => child_delta_trie_root::<LayoutV0<H>, _, _, _, _, _, _>(
+					child_info.keyspace(),
+					&mut eph,
+					root,
+					delta,
+				),
+				StateVersion::V1 => child_delta_trie_root::<LayoutV1<H>, _, _, _, _, _, _>(
+					child_info.keyspace(),
+					&mut eph,
+					root,
+					delta,
+				),
+			};
+
+			match res {
 				Ok(ret) => root = ret,
 				Err(e) => warn!(target: "trie", "Failed to write to trie: {}", e),
 			}
@@ -337,21 +358,102 @@ pub mod tests {
 
 	#[test]
 	fn storage_root_is_non_default() {
-		assert!(test_trie().storage_root(iter::empty()).0 != H256::repeat_byte(0));
+		assert!(
+			test_trie().storage_root(iter::empty(), Default::default()).0 != H256::repeat_byte(0)
+		);
 	}
 
 	#[test]
 	fn storage_root_transaction_is_empty() {
-		assert!(test_trie().storage_root(iter::empty()).1.drain().is_empty());
+		assert!(test_trie().storage_root(iter::empty(), Default::default()).1.drain().is_empty());
 	}
 
 	#[test]
 	fn storage_root_transaction_is_non_empty() {
 		let (new_root, mut tx) = test_trie().storage_root(
 			iter::once((&b"new-key"[..], Some(&b"new-value"[..]))),
+			Default::default(),
 		);
 		assert!(!tx.drain().is_empty());
-		assert!(new_root != test_trie().storage_root(iter::empty()).0);
+		assert!(new_root != test_trie().storage_root(iter::empty(), Default::default()).0);
+	}
+
+	#[test]
+	fn state_version_v1_only_migrates_written_values() {
+		let large = vec![1u8; 64];
+		let backend = crate::InMemoryBackend::<BlakeTwo256>::from(vec![(
+			None,
+			vec![
+				(b"large".to_vec(), Some(large.clone())),
+				(b"other".to_vec(), Some(large.clone())),
+			],
+		)]);
+
+		// Nothing written, nothing migrated.
+		let (root, _) = backend.storage_root(iter::empty(), StateVersion::V1);
+		assert_eq!(&root, backend.root());
+
+		let updated = vec![2u8; 64];
+		let delta = || iter::once((&b"large"[..], Some(&updated[..])));
+		let (root_v0, _) = backend.storage_root(delta(), StateVersion::V0);
+		let (root_v1, tx) = backend.storage_root(delta(), StateVersion::V1);
+		assert_ne!(root_v0, root_v1);
+
+		let migrated = backend.update_backend(root_v1, tx);
+		assert_eq!(migrated.storage(b"large").unwrap(), Some(updated));
+		assert_eq!(migrated.storage(b"other").unwrap(), Some(large));
+	}
+
+	#[test]
+	fn state_version_v1_upgrade_matches_mixed_layout_trie() {
+		use hash_db::{HashDB, EMPTY_PREFIX};
+		use sp_trie::{MemoryDB, trie_types::{TrieDBMutV0, TrieDBMutV1}};
+
+		let keys: Vec<Vec<u8>> = (0u8..8).map(|i| vec![i; 4]).collect();
+		let large = vec![1u8; 64];
+		let updated = vec![2u8; 64];
+
+		// A state written entirely with the V0 layout.
+		let mut db = MemoryDB::<BlakeTwo256>::default();
+		let mut root = H256::default();
+		{
+			let mut trie = TrieDBMutV0::new(&mut db, &mut root);
+			for key in &keys {
+				trie.insert(key, &large).expect("insert failed");
+			}
+		}
+		let backend = TrieBackend::new(db.clone(), root);
+
+		// The runtime switches to V1 and writes a subset of the keys.
+		let written = &keys[..3];
+		let (new_root, tx) = backend.storage_root(
+			written.iter().map(|key| (&key[..], Some(&updated[..]))),
+			StateVersion::V1,
+		);
+
+		// Same as updating the V0 trie in place with the V1 layout.
+		let mut mixed_root = root;
+		{
+			let mut trie = TrieDBMutV1::from_existing(&mut db, &mut mixed_root)
+				.expect("existing root; qed");
+			for key in written {
+				trie.insert(key, &updated).expect("insert failed");
+			}
+		}
+		assert_eq!(new_root, mixed_root);
+
+		let migrated = backend.update_backend(new_root, tx);
+		for key in written {
+			assert_eq!(migrated.storage(key).unwrap(), Some(updated.clone()));
+		}
+		for key in &keys[3..] {
+			assert_eq!(migrated.storage(key).unwrap(), Some(large.clone()));
+		}
+
+		// Written values are stored as hashed value nodes, untouched ones stay inline.
+		let storage = migrated.backend_storage();
+		assert!(storage.contains(&BlakeTwo256::hash(&updated), EMPTY_PREFIX));
+		assert!(!storage.contains(&BlakeTwo256::hash(&large), EMPTY_PREFIX));
 	}
 
 	#[test]
@@ -20,7 +20,7 @@
 
 use std::any::{TypeId, Any};
 use sp_core::{
-	storage::{ChildInfo, TrackedStorageKey},
+	storage::{ChildInfo, StateVersion, TrackedStorageKey},
 	traits::{Externalities, SpawnNamed, TaskExecutorExt, RuntimeSpawnExt, RuntimeSpawn},
 };
 use sp_externalities::{Extensions, ExternalitiesExt as _};
@@ -66,6 +66,19 @@ impl Externalities for AsyncExternalities {
 		panic!("`set_offchain_storage`: should not be used in async externalities!")
 	}
 
+	fn append_offchain_storage(&mut self, _key: &[u8], _data: &[u8]) {
+		panic!("`append_offchain_storage`: should not be used in async externalities!")
+	}
+
+	fn compare_and_set_offchain_storage(
+		&mut self,
+		_key: &[u8],
+		_old_value: Option<&[u8]>,
+		_new_value: &[u8],
+	) {
+		panic!("`compare_and_set_offchain_storage`: should not be used in async externalities!")
+	}
+
 	fn storage(&self, _key: &[u8]) -> Option<StorageValue> {
 		panic!("`storage`: should not be used in async externalities!")
 	}
@@ -144,13 +157,14 @@ impl Externalities for AsyncExternalities {
 		panic!("`storage_append`: should not be used in async externalities!")
 	}
 
-	fn storage_root(&mut self) -> Vec<u8> {
+	fn storage_root(&mut self, _state_version: StateVersion) -> Vec<u8> {
 		panic!("`storage_root`: should not be used in async externalities!")
 	}
 
 	fn child_storage_root(
 		&mut self,
 		_child_info: &ChildInfo,
+		_state_version: StateVersion,
 	) -> Vec<u8> {
 		panic!("`child_storage_root`: should not be used in async externalities!")
 	}
@@ -20,6 +20,8 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod unixfs;
+
 use sp_std::{result::Result, prelude::*};
 
 use codec::{Encode, Decode};
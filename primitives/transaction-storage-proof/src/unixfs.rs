@@ -0,0 +1,224 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! IPFS-compatible UnixFS/DAG-PB layout of indexed data.
+//!
+//! Indexed data is split into raw leaves of `LEAF_SIZE` bytes, which are linked together by a
+//! balanced tree of DAG-PB nodes with at most `MAX_LINKS` links each. All blocks are addressed by
+//! CIDv1 with a sha2-256 multihash. These are the settings used by `ipfs add --cid-version=1`, so
+//! the root CID of some data matches the one computed by a regular IPFS node for the same file.
+//!
+//! Data that fits in a single leaf is represented by that raw leaf alone.
+
+use sp_std::prelude::*;
+
+/// Size of a leaf block, in bytes.
+pub const LEAF_SIZE: usize = 256 * 1024;
+/// Maximum number of links of a DAG-PB node.
+pub const MAX_LINKS: usize = 174;
+/// Multicodec of raw binary blocks.
+pub const RAW_CODEC: u64 = 0x55;
+/// Multicodec of DAG-PB blocks.
+pub const DAG_PB_CODEC: u64 = 0x70;
+/// Multicodec of the sha2-256 multihash.
+pub const SHA2_256_CODE: u64 = 0x12;
+
+/// UnixFS data type of a file node.
+const UNIXFS_FILE: u64 = 2;
+
+/// Content of a block of the DAG.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BlockContent {
+	/// A leaf, which is the slice of the data at `offset` of `len` bytes.
+	Leaf {
+		/// Offset of the leaf in the data.
+		offset: u32,
+		/// Length of the leaf.
+		len: u32,
+	},
+	/// An encoded DAG-PB node.
+	Node(Vec<u8>),
+}
+
+/// A block of the DAG of some data.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Block {
+	/// Multicodec of the block, either `RAW_CODEC` or `DAG_PB_CODEC`.
+	pub codec: u64,
+	/// Sha2-256 digest of the block.
+	pub digest: [u8; 32],
+	/// Content of the block.
+	pub content: BlockContent,
+}
+
+impl Block {
+	/// The CIDv1 of this block.
+	pub fn cid(&self) -> Vec<u8> {
+		cid(self.codec, &self.digest)
+	}
+}
+
+/// Encode a CIDv1 for a block with the given codec and sha2-256 digest.
+pub fn cid(codec: u64, digest: &[u8; 32]) -> Vec<u8> {
+	let mut cid = Vec::with_capacity(36);
+	encode_varint(1, &mut cid);
+	encode_varint(codec, &mut cid);
+	encode_varint(SHA2_256_CODE, &mut cid);
+	encode_varint(digest.len() as u64, &mut cid);
+	cid.extend_from_slice(digest);
+	cid
+}
+
+/// Build the DAG of `data`, hashing blocks with `sha2_256`.
+///
+/// Returns all blocks of the DAG, leaves first. The last block is the root. `data` must not be
+/// empty.
+pub fn build_dag(data: &[u8], sha2_256: impl Fn(&[u8]) -> [u8; 32]) -> Vec<Block> {
+	let mut blocks = Vec::new();
+	// Blocks of the level being built, with their file size and cumulative encoded size.
+	let mut level = Vec::new();
+	for (i, leaf) in data.chunks(LEAF_SIZE).enumerate() {
+		blocks.push(Block {
+			codec: RAW_CODEC,
+			digest: sha2_256(leaf),
+			content: BlockContent::Leaf { offset: (i * LEAF_SIZE) as u32, len: leaf.len() as u32 },
+		});
+		level.push((blocks.len() - 1, leaf.len() as u64, leaf.len() as u64));
+	}
+
+	while level.len() > 1 {
+		let mut next_level = Vec::with_capacity((level.len() + MAX_LINKS - 1) / MAX_LINKS);
+		for children in level.chunks(MAX_LINKS) {
+			let links = children.iter()
+				.map(|&(index, file_size, tree_size)| (blocks[index].cid(), file_size, tree_size));
+			let node = encode_node(links);
+			let file_size: u64 = children.iter().map(|c| c.1).sum();
+			let tree_size = node.len() as u64 + children.iter().map(|c| c.2).sum::<u64>();
+			blocks.push(Block {
+				codec: DAG_PB_CODEC,
+				digest: sha2_256(&node),
+				content: BlockContent::Node(node),
+			});
+			next_level.push((blocks.len() - 1, file_size, tree_size));
+		}
+		level = next_level;
+	}
+	blocks
+}
+
+/// Encode a DAG-PB node of a UnixFS file linking to the given `(cid, file_size, tree_size)`.
+fn encode_node(links: impl Iterator<Item = (Vec<u8>, u64, u64)>) -> Vec<u8> {
+	let mut node = Vec::new();
+	let mut unixfs = Vec::new();
+	let mut total_size = 0;
+	let mut block_sizes = Vec::new();
+	for (cid, file_size, tree_size) in links {
+		let mut link = Vec::new();
+		encode_bytes(1, &cid, &mut link);
+		// Links of files have an empty name.
+		encode_bytes(2, &[], &mut link);
+		encode_uint(3, tree_size, &mut link);
+		encode_bytes(2, &link, &mut node);
+		total_size += file_size;
+		block_sizes.push(file_size);
+	}
+	encode_uint(1, UNIXFS_FILE, &mut unixfs);
+	encode_uint(3, total_size, &mut unixfs);
+	for size in block_sizes {
+		encode_uint(4, size, &mut unixfs);
+	}
+	encode_bytes(1, &unixfs, &mut node);
+	node
+}
+
+/// Encode a protobuf length-delimited field.
+fn encode_bytes(field: u64, bytes: &[u8], out: &mut Vec<u8>) {
+	encode_varint(field << 3 | 2, out);
+	encode_varint(bytes.len() as u64, out);
+	out.extend_from_slice(bytes);
+}
+
+/// Encode a protobuf varint field.
+fn encode_uint(field: u64, value: u64, out: &mut Vec<u8>) {
+	encode_varint(field << 3, out);
+	encode_varint(value, out);
+}
+
+fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+	while value >= 0x80 {
+		out.push(value as u8 | 0x80);
+		value >>= 7;
+	}
+	out.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::hashing::sha2_256;
+
+	#[test]
+	fn single_leaf_is_raw_root() {
+		let data = vec![1u8; LEAF_SIZE];
+		let blocks = build_dag(&data, sha2_256);
+		assert_eq!(blocks, vec![Block {
+			codec: RAW_CODEC,
+			digest: sha2_256(&data),
+			content: BlockContent::Leaf { offset: 0, len: LEAF_SIZE as u32 },
+		}]);
+		let mut expected_cid = vec![0x01, 0x55, 0x12, 0x20];
+		expected_cid.extend_from_slice(&sha2_256(&data));
+		assert_eq!(blocks[0].cid(), expected_cid);
+	}
+
+	#[test]
+	fn leaves_are_linked_by_a_node() {
+		let data = vec![2u8; 2 * LEAF_SIZE + 10];
+		let blocks = build_dag(&data, sha2_256);
+		assert_eq!(blocks.len(), 4);
+		assert_eq!(blocks[2].content, BlockContent::Leaf { offset: 2 * LEAF_SIZE as u32, len: 10 });
+		let root = &blocks[3];
+		assert_eq!(root.codec, DAG_PB_CODEC);
+		match &root.content {
+			BlockContent::Node(node) => {
+				assert_eq!(root.digest, sha2_256(node));
+				// The node starts with a link to the first leaf.
+				assert_eq!(&node[..2], &[0x12, 0x2c]);
+				assert_eq!(&node[2..4], &[0x0a, 0x24]);
+				assert_eq!(&node[4..40], &blocks[0].cid()[..]);
+				// And ends with the UnixFS data: file type, total size and the sizes of leaves.
+				let mut unixfs = vec![0x08, 0x02];
+				encode_uint(3, data.len() as u64, &mut unixfs);
+				for size in &[LEAF_SIZE as u64, LEAF_SIZE as u64, 10] {
+					encode_uint(4, *size, &mut unixfs);
+				}
+				assert!(node.ends_with(&unixfs));
+			},
+			_ => panic!("Root must be a node"),
+		}
+	}
+
+	#[test]
+	fn nodes_are_balanced() {
+		let data = vec![3u8; (MAX_LINKS + 1) * LEAF_SIZE];
+		let blocks = build_dag(&data, sha2_256);
+		// Leaves, two nodes linking them, and the root.
+		assert_eq!(blocks.len(), MAX_LINKS + 1 + 3);
+		assert!(blocks[..MAX_LINKS + 1].iter().all(|b| b.codec == RAW_CODEC));
+		assert!(blocks[MAX_LINKS + 1..].iter().all(|b| b.codec == DAG_PB_CODEC));
+	}
+}
@@ -52,22 +52,67 @@ pub use trie_codec::{decode_compact, encode_compact, Error as CompactProofError}
 
 #[derive(Default)]
 /// substrate trie layout
-pub struct Layout<H>(sp_std::marker::PhantomData<H>);
+pub struct LayoutV0<H>(sp_std::marker::PhantomData<H>);
 
-impl<H: Hasher> TrieLayout for Layout<H> {
+#[derive(Default)]
+/// substrate trie layout, with external value nodes.
+pub struct LayoutV1<H>(sp_std::marker::PhantomData<H>);
+
+/// Trie layout used when reading a trie.
+///
+/// Both layouts share the same node codec, so a trie written with either
+/// `LayoutV0` or `LayoutV1` can be read (and proven) with this layout.
+/// Writes must use the layout matching the runtime `StateVersion`.
+pub type Layout<H> = LayoutV0<H>;
+
+impl<H: Hasher> TrieLayout for LayoutV0<H> {
+	const USE_EXTENSION: bool = false;
+	const ALLOW_EMPTY: bool = true;
+	const MAX_INLINE_VALUE: Option<u32> = None;
+	type Hash = H;
+	type Codec = NodeCodec<Self::Hash>;
+}
+
+impl<H: Hasher> TrieConfiguration for LayoutV0<H> {
+	fn trie_root<I, A, B>(input: I) -> <Self::Hash as Hasher>::Out where
+		I: IntoIterator<Item = (A, B)>,
+		A: AsRef<[u8]> + Ord,
+		B: AsRef<[u8]>,
+	{
+		trie_root::trie_root_no_extension::<H, TrieStream, _, _, _>(input, Self::MAX_INLINE_VALUE)
+	}
+
+	fn trie_root_unhashed<I, A, B>(input: I) -> Vec<u8> where
+		I: IntoIterator<Item = (A, B)>,
+		A: AsRef<[u8]> + Ord,
+		B: AsRef<[u8]>,
+	{
+		trie_root::unhashed_trie_no_extension::<H, TrieStream, _, _, _>(
+			input,
+			Self::MAX_INLINE_VALUE,
+		)
+	}
+
+	fn encode_index(input: u32) -> Vec<u8> {
+		codec::Encode::encode(&codec::Compact(input))
+	}
+}
+
+impl<H: Hasher> TrieLayout for LayoutV1<H> {
 	const USE_EXTENSION: bool = false;
 	const ALLOW_EMPTY: bool = true;
+	const MAX_INLINE_VALUE: Option<u32> = Some(sp_core::storage::TRIE_VALUE_NODE_THRESHOLD);
 	type Hash = H;
 	type Codec = NodeCodec<Self::Hash>;
 }
 
-impl<H: Hasher> TrieConfiguration for Layout<H> {
+impl<H: Hasher> TrieConfiguration for LayoutV1<H> {
 	fn trie_root<I, A, B>(input: I) -> <Self::Hash as Hasher>::Out where
 		I: IntoIterator<Item = (A, B)>,
 		A: AsRef<[u8]> + Ord,
 		B: AsRef<[u8]>,
 	{
-		trie_root::trie_root_no_extension::<H, TrieStream, _, _, _>(input)
+		trie_root::trie_root_no_extension::<H, TrieStream, _, _, _>(input, Self::MAX_INLINE_VALUE)
 	}
 
 	fn trie_root_unhashed<I, A, B>(input: I) -> Vec<u8> where
@@ -75,7 +120,10 @@ impl<H: Hasher> TrieConfiguration for Layout<H> {
 		A: AsRef<[u8]> + Ord,
 		B: AsRef<[u8]>,
 	{
-		trie_root::unhashed_trie_no_extension::<H, TrieStream, _, _, _>(input)
+		trie_root::unhashed_trie_no_extension::<H, TrieStream, _, _, _>(
+			input,
+			Self::MAX_INLINE_VALUE,
+		)
 	}
 
 	fn encode_index(input: u32) -> Vec<u8> {
@@ -129,6 +177,12 @@ pub mod trie_types {
 	pub type TrieDB<'a, H> = super::TrieDB<'a, Layout<H>>;
 	/// Persistent trie database write-access interface for the a given hasher.
 	pub type TrieDBMut<'a, H> = super::TrieDBMut<'a, Layout<H>>;
+	/// Persistent trie database write-access interface for the a given hasher,
+	/// old layout.
+	pub type TrieDBMutV0<'a, H> = super::TrieDBMut<'a, super::LayoutV0<H>>;
+	/// Persistent trie database write-access interface for the a given hasher,
+	/// with external value nodes.
+	pub type TrieDBMutV1<'a, H> = super::TrieDBMut<'a, super::LayoutV1<H>>;
 	/// Querying interface, as in `trie_db` but less generic.
 	pub type Lookup<'a, H, Q> = trie_db::Lookup<'a, Layout<H>, Q>;
 	/// As in `trie_db`, but less generic, error type for the crate.
@@ -168,12 +222,12 @@ pub fn verify_trie_proof<'a, L: TrieConfiguration, I, K, V>(
 	root: &TrieHash<L>,
 	proof: &[Vec<u8>],
 	items: I,
-) -> Result<(), VerifyError<TrieHash<L>, error::Error>> where
+) -> Result<(), VerifyError<TrieHash<L>, CError<L>>> where
 	I: IntoIterator<Item=&'a (K, Option<V>)>,
 	K: 'a + AsRef<[u8]>,
 	V: 'a + AsRef<[u8]>,
 {
-	verify_proof::<Layout<L::Hash>, _, _, _>(root, proof, items)
+	verify_proof::<L, _, _, _>(root, proof, items)
 }
 
 /// Determine a trie root given a hash DB and delta values.
@@ -442,6 +496,9 @@ mod trie_constants {
 	pub const LEAF_PREFIX_MASK: u8 = 0b_01 << 6;
 	pub const BRANCH_WITHOUT_MASK: u8 = 0b_10 << 6;
 	pub const BRANCH_WITH_MASK: u8 = 0b_11 << 6;
+	pub const ALT_HASHING_LEAF_PREFIX_MASK: u8 = 0b_001 << 5;
+	pub const ALT_HASHING_BRANCH_WITH_MASK: u8 = 0b_0001 << 4;
+	pub const ESCAPE_COMPACT_HEADER: u8 = EMPTY_TRIE | 0b_00_01;
 }
 
 #[cfg(test)]
@@ -607,6 +664,77 @@ mod tests {
 		check_iteration::<Layout>(&input);
 	}
 
+	#[test]
+	fn long_values_are_equivalent_with_value_nodes() {
+		let long = &b"ABCABCABCABCABCABCABCABCABCABCABCABCABCABCABCABCABCABCABCABCABCABCABCABC"[..];
+		let input: Vec<(&[u8], &[u8])> = vec![
+			(&[0xaa][..], long),
+			(&[0xaa, 0xaa][..], long),
+			(&[0xba][..], &[0x11][..]),
+		];
+		check_equivalent::<LayoutV1<Blake2Hasher>>(&input);
+		check_iteration::<LayoutV1<Blake2Hasher>>(&input);
+	}
+
+	#[test]
+	fn layouts_only_differ_above_value_threshold() {
+		let small: Vec<(&[u8], &[u8])> = vec![
+			(&[0xaa][..], &[0x11; 32][..]),
+			(&[0xba][..], &[0x22; 2][..]),
+		];
+		assert_eq!(
+			LayoutV0::<Blake2Hasher>::trie_root(small.clone()),
+			LayoutV1::<Blake2Hasher>::trie_root(small),
+		);
+
+		let large: Vec<(&[u8], &[u8])> = vec![
+			(&[0xaa][..], &[0x11; 33][..]),
+			(&[0xba][..], &[0x22; 2][..]),
+		];
+		assert_ne!(
+			LayoutV0::<Blake2Hasher>::trie_root(large.clone()),
+			LayoutV1::<Blake2Hasher>::trie_root(large),
+		);
+	}
+
+	#[test]
+	fn trie_written_with_v1_is_readable_with_default_layout() {
+		let long = vec![0x42; 64];
+		let mut memdb = MemoryDB::default();
+		let mut root = Default::default();
+		{
+			let mut t = TrieDBMut::<LayoutV1<Blake2Hasher>>::new(&mut memdb, &mut root);
+			t.insert(b"key", &long).unwrap();
+			t.insert(b"other", b"short").unwrap();
+		}
+		assert_eq!(
+			read_trie_value::<Layout, _>(&memdb, &root, b"key").unwrap(),
+			Some(long),
+		);
+		assert_eq!(
+			read_trie_value::<Layout, _>(&memdb, &root, b"other").unwrap(),
+			Some(b"short".to_vec()),
+		);
+	}
+
+	#[test]
+	fn hashed_value_headers_round_trip() {
+		use crate::node_header::NodeHeader;
+
+		for nibble_count in [0usize, 1, 14, 15, 16, 30, 31, 62, 63, 64, 300].iter() {
+			for header in [
+				NodeHeader::Leaf(*nibble_count),
+				NodeHeader::Branch(true, *nibble_count),
+				NodeHeader::Branch(false, *nibble_count),
+				NodeHeader::HashedValueLeaf(*nibble_count),
+				NodeHeader::HashedValueBranch(*nibble_count),
+			].iter() {
+				let encoded = header.encode();
+				assert_eq!(NodeHeader::decode(&mut &encoded[..]).unwrap(), *header);
+			}
+		}
+	}
+
 	fn populate_trie<'db, T: TrieConfiguration>(
 		db: &'db mut dyn HashDB<T::Hash, DBValue>,
 		root: &'db mut TrieHash<T>,
@@ -23,8 +23,8 @@ use sp_std::vec::Vec;
 use sp_std::borrow::Borrow;
 use codec::{Encode, Decode, Input, Compact};
 use hash_db::Hasher;
-use trie_db::{self, node::{NibbleSlicePlan, NodePlan, NodeHandlePlan}, ChildReference,
-	nibble_ops, Partial, NodeCodec as NodeCodecT};
+use trie_db::{self, node::{NibbleSlicePlan, NodePlan, NodeHandlePlan, Value, ValuePlan},
+	ChildReference, nibble_ops, Partial, NodeCodec as NodeCodecT};
 use crate::error::Error;
 use crate::trie_constants;
 use super::{node_header::{NodeHeader, NodeKind}};
@@ -87,7 +87,24 @@ impl<'a> Input for ByteSliceInput<'a> {
 #[derive(Default, Clone)]
 pub struct NodeCodec<H>(PhantomData<H>);
 
+impl<H: Hasher> NodeCodec<H> {
+	/// Decode a value stored in a node, either inline (length prefixed) or as
+	/// the hash of a separate value node.
+	fn decode_value_plan(
+		input: &mut ByteSliceInput,
+		contains_hash: bool,
+	) -> Result<ValuePlan, Error> {
+		Ok(if contains_hash {
+			ValuePlan::Node(input.take(H::LENGTH)?)
+		} else {
+			let count = <Compact<u32>>::decode(input)?.0 as usize;
+			ValuePlan::Inline(input.take(count)?)
+		})
+	}
+}
+
 impl<H: Hasher> NodeCodecT for NodeCodec<H> {
+	const ESCAPE_HEADER: Option<u8> = Some(trie_constants::ESCAPE_COMPACT_HEADER);
 	type Error = Error;
 	type HashOut = H::Out;
 
@@ -97,9 +114,16 @@ impl<H: Hasher> NodeCodecT for NodeCodec<H> {
 
 	fn decode_plan(data: &[u8]) -> sp_std::result::Result<NodePlan, Self::Error> {
 		let mut input = ByteSliceInput::new(data);
-		match NodeHeader::decode(&mut input)? {
+		let header = NodeHeader::decode(&mut input)?;
+		let contains_hash = header.contains_hash_of_value();
+		match header {
 			NodeHeader::Null => Ok(NodePlan::Empty),
-			NodeHeader::Branch(has_value, nibble_count) => {
+			NodeHeader::HashedValueBranch(nibble_count) |
+			NodeHeader::Branch(_, nibble_count) => {
+				let has_value = match header {
+					NodeHeader::Branch(has_value, _) => has_value,
+					_ => true,
+				};
 				let padding = nibble_count % nibble_ops::NIBBLE_PER_BYTE != 0;
 				// check that the padding is valid (if any)
 				if padding && nibble_ops::pad_left(data[input.offset]) != 0 {
@@ -112,8 +136,7 @@ impl<H: Hasher> NodeCodecT for NodeCodec<H> {
 				let bitmap_range = input.take(BITMAP_LENGTH)?;
 				let bitmap = Bitmap::decode(&data[bitmap_range])?;
 				let value = if has_value {
-					let count = <Compact<u32>>::decode(&mut input)?.0 as usize;
-					Some(input.take(count)?)
+					Some(Self::decode_value_plan(&mut input, contains_hash)?)
 				} else {
 					None
 				};
@@ -138,6 +161,7 @@ impl<H: Hasher> NodeCodecT for NodeCodec<H> {
 					children,
 				})
 			}
+			NodeHeader::HashedValueLeaf(nibble_count) |
 			NodeHeader::Leaf(nibble_count) => {
 				let padding = nibble_count % nibble_ops::NIBBLE_PER_BYTE != 0;
 				// check that the padding is valid (if any)
@@ -148,10 +172,9 @@ impl<H: Hasher> NodeCodecT for NodeCodec<H> {
 					(nibble_count + (nibble_ops::NIBBLE_PER_BYTE - 1)) / nibble_ops::NIBBLE_PER_BYTE,
 				)?;
 				let partial_padding = nibble_ops::number_padding(nibble_count);
-				let count = <Compact<u32>>::decode(&mut input)?.0 as usize;
 				Ok(NodePlan::Leaf {
 					partial: NibbleSlicePlan::new(partial, partial_padding),
-					value: input.take(count)?,
+					value: Self::decode_value_plan(&mut input, contains_hash)?,
 				})
 			}
 		}
@@ -165,9 +188,12 @@ impl<H: Hasher> NodeCodecT for NodeCodec<H> {
 		&[trie_constants::EMPTY_TRIE]
 	}
 
-	fn leaf_node(partial: Partial, value: &[u8]) -> Vec<u8> {
-		let mut output = partial_encode(partial, NodeKind::Leaf);
-		value.encode_to(&mut output);
+	fn leaf_node(partial: Partial, value: Value) -> Vec<u8> {
+		let mut output = match &value {
+			Value::Inline(..) => partial_encode(partial, NodeKind::Leaf),
+			Value::Node(..) => partial_encode(partial, NodeKind::HashedValueLeaf),
+		};
+		encode_value(value, &mut output);
 		output
 	}
 
@@ -181,7 +207,7 @@ impl<H: Hasher> NodeCodecT for NodeCodec<H> {
 
 	fn branch_node(
 		_children: impl Iterator<Item = impl Borrow<Option<ChildReference<<H as Hasher>::Out>>>>,
-		_maybe_value: Option<&[u8]>,
+		_maybe_value: Option<Value>,
 	) -> Vec<u8> {
 		unreachable!()
 	}
@@ -190,18 +216,19 @@ impl<H: Hasher> NodeCodecT for NodeCodec<H> {
 		partial: impl Iterator<Item = u8>,
 		number_nibble: usize,
 		children: impl Iterator<Item = impl Borrow<Option<ChildReference<<H as Hasher>::Out>>>>,
-		maybe_value: Option<&[u8]>,
+		maybe_value: Option<Value>,
 	) -> Vec<u8> {
-		let mut output = if maybe_value.is_some() {
-			partial_from_iterator_encode(partial, number_nibble, NodeKind::BranchWithValue)
-		} else {
-			partial_from_iterator_encode(partial, number_nibble, NodeKind::BranchNoValue)
+		let kind = match &maybe_value {
+			None => NodeKind::BranchNoValue,
+			Some(Value::Inline(..)) => NodeKind::BranchWithValue,
+			Some(Value::Node(..)) => NodeKind::HashedValueBranch,
 		};
+		let mut output = partial_from_iterator_encode(partial, number_nibble, kind);
 		let bitmap_index = output.len();
 		let mut bitmap: [u8; BITMAP_LENGTH] = [0; BITMAP_LENGTH];
 		(0..BITMAP_LENGTH).for_each(|_|output.push(0));
 		if let Some(value) = maybe_value {
-			value.encode_to(&mut output);
+			encode_value(value, &mut output);
 		};
 		Bitmap::encode(children.map(|maybe_child| match maybe_child.borrow() {
 			Some(ChildReference::Hash(h)) => {
@@ -223,6 +250,15 @@ impl<H: Hasher> NodeCodecT for NodeCodec<H> {
 
 // utils
 
+/// Encode a node value: inline values are length prefixed, value nodes are
+/// referenced by their hash.
+fn encode_value(value: Value, output: &mut Vec<u8>) {
+	match value {
+		Value::Inline(value) => value.encode_to(output),
+		Value::Node(hash, _) => output.extend_from_slice(hash),
+	}
+}
+
 /// Encode and allocate node type header (type and size), and partial value.
 /// It uses an iterator over encoded partial bytes as input.
 fn partial_from_iterator_encode<I: Iterator<Item = u8>>(
@@ -237,6 +273,10 @@ fn partial_from_iterator_encode<I: Iterator<Item = u8>>(
 		NodeKind::Leaf => NodeHeader::Leaf(nibble_count).encode_to(&mut output),
 		NodeKind::BranchWithValue => NodeHeader::Branch(true, nibble_count).encode_to(&mut output),
 		NodeKind::BranchNoValue => NodeHeader::Branch(false, nibble_count).encode_to(&mut output),
+		NodeKind::HashedValueLeaf =>
+			NodeHeader::HashedValueLeaf(nibble_count).encode_to(&mut output),
+		NodeKind::HashedValueBranch =>
+			NodeHeader::HashedValueBranch(nibble_count).encode_to(&mut output),
 	};
 	output.extend(partial);
 	output
@@ -255,6 +295,10 @@ fn partial_encode(partial: Partial, node_kind: NodeKind) -> Vec<u8> {
 		NodeKind::Leaf => NodeHeader::Leaf(nibble_count).encode_to(&mut output),
 		NodeKind::BranchWithValue => NodeHeader::Branch(true, nibble_count).encode_to(&mut output),
 		NodeKind::BranchNoValue => NodeHeader::Branch(false, nibble_count).encode_to(&mut output),
+		NodeKind::HashedValueLeaf =>
+			NodeHeader::HashedValueLeaf(nibble_count).encode_to(&mut output),
+		NodeKind::HashedValueBranch =>
+			NodeHeader::HashedValueBranch(nibble_count).encode_to(&mut output),
 	};
 	if number_nibble_encoded > 0 {
 		output.push(nibble_ops::pad_right((partial.0).1));
@@ -26,8 +26,24 @@ use sp_std::iter::once;
 #[derive(sp_core::RuntimeDebug)]
 pub(crate) enum NodeHeader {
 	Null,
+	// contains wether there is a value and nibble count
 	Branch(bool, usize),
+	// contains nibble count
 	Leaf(usize),
+	// contains nibble count.
+	HashedValueBranch(usize),
+	// contains nibble count.
+	HashedValueLeaf(usize),
+}
+
+impl NodeHeader {
+	/// Does the node store the hash of its value instead of the value itself.
+	pub(crate) fn contains_hash_of_value(&self) -> bool {
+		match self {
+			NodeHeader::HashedValueBranch(_) | NodeHeader::HashedValueLeaf(_) => true,
+			_ => false,
+		}
+	}
 }
 
 /// NodeHeader without content
@@ -35,6 +51,8 @@ pub(crate) enum NodeKind {
 	Leaf,
 	BranchNoValue,
 	BranchWithValue,
+	HashedValueLeaf,
+	HashedValueBranch,
 }
 
 impl Encode for NodeHeader {
@@ -42,11 +60,23 @@ impl Encode for NodeHeader {
 		match self {
 			NodeHeader::Null => output.push_byte(trie_constants::EMPTY_TRIE),
 			NodeHeader::Branch(true, nibble_count)	=>
-				encode_size_and_prefix(*nibble_count, trie_constants::BRANCH_WITH_MASK, output),
+				encode_size_and_prefix(*nibble_count, trie_constants::BRANCH_WITH_MASK, 2, output),
 			NodeHeader::Branch(false, nibble_count) =>
-				encode_size_and_prefix(*nibble_count, trie_constants::BRANCH_WITHOUT_MASK, output),
+				encode_size_and_prefix(*nibble_count, trie_constants::BRANCH_WITHOUT_MASK, 2, output),
 			NodeHeader::Leaf(nibble_count) =>
-				encode_size_and_prefix(*nibble_count, trie_constants::LEAF_PREFIX_MASK, output),
+				encode_size_and_prefix(*nibble_count, trie_constants::LEAF_PREFIX_MASK, 2, output),
+			NodeHeader::HashedValueBranch(nibble_count) => encode_size_and_prefix(
+				*nibble_count,
+				trie_constants::ALT_HASHING_BRANCH_WITH_MASK,
+				4,
+				output,
+			),
+			NodeHeader::HashedValueLeaf(nibble_count) => encode_size_and_prefix(
+				*nibble_count,
+				trie_constants::ALT_HASHING_LEAF_PREFIX_MASK,
+				3,
+				output,
+			),
 		}
 	}
 }
@@ -60,11 +90,20 @@ impl Decode for NodeHeader {
 			return Ok(NodeHeader::Null);
 		}
 		match i & (0b11 << 6) {
-			trie_constants::LEAF_PREFIX_MASK => Ok(NodeHeader::Leaf(decode_size(i, input)?)),
-			trie_constants::BRANCH_WITHOUT_MASK => Ok(NodeHeader::Branch(false, decode_size(i, input)?)),
-			trie_constants::BRANCH_WITH_MASK => Ok(NodeHeader::Branch(true, decode_size(i, input)?)),
-			// do not allow any special encoding
-			_ => Err("Unallowed encoding".into()),
+			trie_constants::LEAF_PREFIX_MASK => Ok(NodeHeader::Leaf(decode_size(i, input, 2)?)),
+			trie_constants::BRANCH_WITHOUT_MASK => Ok(NodeHeader::Branch(false, decode_size(i, input, 2)?)),
+			trie_constants::BRANCH_WITH_MASK => Ok(NodeHeader::Branch(true, decode_size(i, input, 2)?)),
+			trie_constants::EMPTY_TRIE => {
+				if i & (0b111 << 5) == trie_constants::ALT_HASHING_LEAF_PREFIX_MASK {
+					Ok(NodeHeader::HashedValueLeaf(decode_size(i, input, 3)?))
+				} else if i & (0b1111 << 4) == trie_constants::ALT_HASHING_BRANCH_WITH_MASK {
+					Ok(NodeHeader::HashedValueBranch(decode_size(i, input, 4)?))
+				} else {
+					// do not allow any special encoding
+					Err("Unallowed encoding".into())
+				}
+			},
+			_ => unreachable!("All four two bits prefixes are covered above; qed"),
 		}
 	}
 }
@@ -72,14 +111,21 @@ impl Decode for NodeHeader {
 /// Returns an iterator over encoded bytes for node header and size.
 /// Size encoding allows unlimited, length inefficient, representation, but
 /// is bounded to 16 bit maximum value to avoid possible DOS.
-pub(crate) fn size_and_prefix_iterator(size: usize, prefix: u8) -> impl Iterator<Item = u8> {
+///
+/// `prefix_mask` is the number of bits used by the node kind prefix.
+pub(crate) fn size_and_prefix_iterator(
+	size: usize,
+	prefix: u8,
+	prefix_mask: usize,
+) -> impl Iterator<Item = u8> {
 	let size = sp_std::cmp::min(trie_constants::NIBBLE_SIZE_BOUND, size);
 
-	let l1 = sp_std::cmp::min(62, size);
+	let max_value = 255u8 >> prefix_mask;
+	let l1 = sp_std::cmp::min(max_value as usize - 1, size);
 	let (first_byte, mut rem) = if size == l1 {
 		(once(prefix + l1 as u8), 0)
 	} else {
-		(once(prefix + 63), size - l1)
+		(once(prefix + max_value), size - l1)
 	};
 	let next_bytes = move || {
 		if rem > 0 {
@@ -99,16 +145,26 @@ pub(crate) fn size_and_prefix_iterator(size: usize, prefix: u8) -> impl Iterator
 }
 
 /// Encodes size and prefix to a stream output.
-fn encode_size_and_prefix<W: Output + ?Sized>(size: usize, prefix: u8, out: &mut W) {
-	for b in size_and_prefix_iterator(size, prefix) {
+fn encode_size_and_prefix<W: Output + ?Sized>(
+	size: usize,
+	prefix: u8,
+	prefix_mask: usize,
+	out: &mut W,
+) {
+	for b in size_and_prefix_iterator(size, prefix, prefix_mask) {
 		out.push_byte(b)
 	}
 }
 
 /// Decode size only from stream input and header byte.
-fn decode_size(first: u8, input: &mut impl Input) -> Result<usize, codec::Error> {
-	let mut result = (first & 255u8 >> 2) as usize;
-	if result < 63 {
+fn decode_size(
+	first: u8,
+	input: &mut impl Input,
+	prefix_mask: usize,
+) -> Result<usize, codec::Error> {
+	let max_value = 255u8 >> prefix_mask;
+	let mut result = (first & max_value) as usize;
+	if result < max_value as usize {
 		return Ok(result);
 	}
 	result -= 1;
@@ -88,11 +88,14 @@ impl StorageProof {
 
 	/// Encode as a compact proof with default
 	/// trie layout.
+	///
+	/// The value node aware layout is used, it can encode proofs of tries
+	/// written with any state version.
 	pub fn into_compact_proof<H: Hasher>(
 		self,
 		root: H::Out,
-	) -> Result<CompactProof, crate::CompactProofError<crate::Layout<H>>> {
-		crate::encode_compact::<crate::Layout<H>>(self, root)
+	) -> Result<CompactProof, crate::CompactProofError<crate::LayoutV1<H>>> {
+		crate::encode_compact::<crate::LayoutV1<H>>(self, root)
 	}
 	
 	/// Returns the estimated encoded size of the compact proof.
@@ -120,9 +123,9 @@ impl CompactProof {
 	pub fn to_storage_proof<H: Hasher>(
 		&self,
 		expected_root: Option<&H::Out>,
-	) -> Result<(StorageProof, H::Out), crate::CompactProofError<crate::Layout<H>>> {
+	) -> Result<(StorageProof, H::Out), crate::CompactProofError<crate::LayoutV1<H>>> {
 		let mut db = crate::MemoryDB::<H>::new(&[]);
-		let root = crate::decode_compact::<crate::Layout<H>, _, _>(
+		let root = crate::decode_compact::<crate::LayoutV1<H>, _, _>(
 			&mut db,
 			self.iter_compact_encoded_nodes(),
 			expected_root,
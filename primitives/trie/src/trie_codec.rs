@@ -113,7 +113,7 @@ pub fn decode_compact<'a, L, DB, I>(
 		I: IntoIterator<Item = &'a [u8]>,
 {
 	let mut nodes_iter = encoded.into_iter();
-	let (top_root, _nb_used) = trie_db::decode_compact_from_iter::<L, _, _, _>(
+	let (top_root, _nb_used) = trie_db::decode_compact_from_iter::<L, _, _>(
 		db,
 		&mut nodes_iter,
 	)?;
@@ -166,7 +166,7 @@ pub fn decode_compact<'a, L, DB, I>(
 	let mut previous_extracted_child_trie = None;
 	for child_root in child_tries.into_iter() {
 		if previous_extracted_child_trie.is_none() {
-			let (top_root, _) = trie_db::decode_compact_from_iter::<L, _, _, _>(
+			let (top_root, _) = trie_db::decode_compact_from_iter::<L, _, _>(
 				db,
 				&mut nodes_iter,
 			)?;
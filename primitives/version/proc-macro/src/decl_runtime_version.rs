@@ -62,6 +62,7 @@ struct RuntimeVersion {
 	impl_version: u32,
 	apis: u8,
 	transaction_version: u32,
+	state_version: u8,
 }
 
 #[derive(Default, Debug)]
@@ -72,6 +73,7 @@ struct ParseRuntimeVersion {
 	spec_version: Option<u32>,
 	impl_version: Option<u32>,
 	transaction_version: Option<u32>,
+	state_version: Option<u8>,
 }
 
 impl ParseRuntimeVersion {
@@ -140,6 +142,8 @@ impl ParseRuntimeVersion {
 				field_value,
 				Self::parse_num_literal,
 			)?;
+		} else if field_name == "state_version" {
+			parse_once(&mut self.state_version, field_value, Self::parse_num_literal_u8)?;
 		} else if field_name == "apis" {
 			// Intentionally ignored
 			//
@@ -169,6 +173,22 @@ impl ParseRuntimeVersion {
 		lit.base10_parse::<u32>()
 	}
 
+	fn parse_num_literal_u8(expr: &Expr) -> Result<u8> {
+		let lit = match *expr {
+			Expr::Lit(ExprLit {
+				lit: Lit::Int(ref lit),
+				..
+			}) => lit,
+			_ => {
+				return Err(Error::new(
+					expr.span(),
+					"only numeric literals (e.g. `10`) are supported here",
+				));
+			}
+		};
+		lit.base10_parse::<u8>()
+	}
+
 	fn parse_str_literal(expr: &Expr) -> Result<String> {
 		let mac = match *expr {
 			Expr::Macro(syn::ExprMacro { ref mac, .. }) => mac,
@@ -220,6 +240,7 @@ impl ParseRuntimeVersion {
 			spec_version,
 			impl_version,
 			transaction_version,
+			state_version,
 		} = self;
 
 		Ok(RuntimeVersion {
@@ -229,6 +250,7 @@ impl ParseRuntimeVersion {
 			spec_version: required!(spec_version),
 			impl_version: required!(impl_version),
 			transaction_version: required!(transaction_version),
+			state_version: required!(state_version),
 			apis: 0,
 		})
 	}
@@ -261,6 +283,7 @@ mod tests {
 			impl_version: 1,
 			apis: 0,
 			transaction_version: 2,
+			state_version: 1,
 		}
 		.encode();
 
@@ -274,6 +297,7 @@ mod tests {
 				impl_version: 1,
 				apis: Cow::Owned(vec![]),
 				transaction_version: 2,
+				state_version: 1,
 			},
 		);
 	}
@@ -26,8 +26,8 @@ use std::fmt;
 #[cfg(feature = "std")]
 use std::collections::HashSet;
 
-use codec::{Encode, Decode};
-use sp_runtime::RuntimeString;
+use codec::{Encode, Decode, Input};
+use sp_runtime::{RuntimeString, StateVersion};
 pub use sp_runtime::create_runtime_str;
 #[doc(hidden)]
 pub use sp_std;
@@ -64,6 +64,7 @@ pub mod embed;
 /// 	impl_version: 1,
 /// 	apis: RUNTIME_API_VERSIONS,
 /// 	transaction_version: 2,
+/// 	state_version: 1,
 /// };
 ///
 /// # const RUNTIME_API_VERSIONS: sp_version::ApisVec = sp_version::create_apis_vec!([]);
@@ -76,9 +77,9 @@ pub mod embed;
 /// - The `spec_name` and `impl_name` must be set by a macro-like expression. The name of the macro
 ///   doesn't matter though.
 ///
-/// - `authoring_version`, `spec_version`, `impl_version` and `transaction_version` must be set
-///   by a literal. Literal must be an integer. No other expressions are allowed there. In particular,
-///   you can't supply a constant variable.
+/// - `authoring_version`, `spec_version`, `impl_version`, `transaction_version` and `state_version`
+///   must be set by a literal. Literal must be an integer. No other expressions are allowed there.
+///   In particular, you can't supply a constant variable.
 ///
 /// - `apis` doesn't have any specific constraints. This is because this information doesn't get into
 ///   the custom section and is not parsed.
@@ -100,6 +101,11 @@ pub use sp_version_proc_macro::runtime_version;
 /// The identity of a particular API interface that the runtime might provide.
 pub type ApiId = [u8; 8];
 
+/// The identity of the `Core` runtime api, `blake2_64(b"Core")`.
+///
+/// Its version is used to know which fields of an encoded `RuntimeVersion` are present.
+pub const CORE_API_ID: ApiId = [223, 106, 203, 104, 153, 7, 96, 155];
+
 /// A vector of pairs of `ApiId` and a `u32` for version.
 pub type ApisVec = sp_std::borrow::Cow<'static, [(ApiId, u32)]>;
 
@@ -114,7 +120,7 @@ macro_rules! create_apis_vec {
 /// This triplet have different semantics and mis-interpretation could cause problems.
 /// In particular: bug fixes should result in an increment of `spec_version` and possibly `authoring_version`,
 /// absolutely not `impl_version` since they change the semantics of the runtime.
-#[derive(Clone, PartialEq, Eq, Encode, Decode, Default, sp_runtime::RuntimeDebug)]
+#[derive(Clone, PartialEq, Eq, Encode, Default, sp_runtime::RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub struct RuntimeVersion {
@@ -167,6 +173,77 @@ pub struct RuntimeVersion {
 	///
 	/// It need *not* change when a new module is added or when a dispatchable is added.
 	pub transaction_version: u32,
+
+	/// Version of the state implementation used by this runtime.
+	///
+	/// Use of an incorrect version is consensus breaking. `0` stores every value inline in
+	/// the trie nodes, `1` stores values of at least `TRIE_VALUE_NODE_THRESHOLD` bytes in
+	/// separate hashed nodes.
+	#[cfg_attr(feature = "std", serde(default))]
+	pub state_version: u8,
+}
+
+impl RuntimeVersion {
+	/// `Decode` while giving a "version hint"
+	///
+	/// There exists multiple versions of `RuntimeVersion` and they are versioned using the `Core`
+	/// runtime api:
+	/// - `Core` version < 3 is a runtime version without a transaction version and state version.
+	/// - `Core` version 3 is a runtime version without a state version.
+	/// - `Core` version 4 is the latest runtime version.
+	///
+	/// When no hint is given, the version of `Core` found in the decoded `apis` is used, and an
+	/// encoding without `Core` in its `apis` is treated as the latest one.
+	pub fn decode_with_version_hint<I: Input>(
+		input: &mut I,
+		core_version: Option<u32>,
+	) -> Result<RuntimeVersion, codec::Error> {
+		let spec_name = Decode::decode(input)?;
+		let impl_name = Decode::decode(input)?;
+		let authoring_version = Decode::decode(input)?;
+		let spec_version = Decode::decode(input)?;
+		let impl_version = Decode::decode(input)?;
+		let apis: ApisVec = Decode::decode(input)?;
+		let core_version = core_version.or_else(|| {
+			apis.iter().find_map(|(id, version)| (*id == CORE_API_ID).then(|| *version))
+		});
+		let transaction_version = if core_version.map(|v| v >= 3).unwrap_or(true) {
+			Decode::decode(input)?
+		} else {
+			1
+		};
+		let state_version = if core_version.map(|v| v >= 4).unwrap_or(true) {
+			Decode::decode(input)?
+		} else {
+			0
+		};
+		Ok(RuntimeVersion {
+			spec_name,
+			impl_name,
+			authoring_version,
+			spec_version,
+			impl_version,
+			apis,
+			transaction_version,
+			state_version,
+		})
+	}
+
+	/// Returns the state version to use when writing to the state.
+	///
+	/// Runtimes with a `Core` api older than 4 are decoded with a `state_version` of `0` and
+	/// therefore keep using `StateVersion::V0`.
+	pub fn state_version(&self) -> StateVersion {
+		use sp_std::convert::TryFrom;
+		// Unknown versions are assumed to be newer than this node, use the latest one we know.
+		StateVersion::try_from(self.state_version).unwrap_or(StateVersion::V1)
+	}
+}
+
+impl Decode for RuntimeVersion {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		Self::decode_with_version_hint(input, None)
+	}
 }
 
 #[cfg(feature = "std")]
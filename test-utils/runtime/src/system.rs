@@ -34,7 +34,8 @@ use sp_runtime::{
 use codec::{KeyedVec, Encode, Decode};
 use frame_system::Config;
 use crate::{
-	AccountId, BlockNumber, Extrinsic, Transfer, H256 as Hash, Block, Header, Digest, AuthorityId
+	AccountId, BlockNumber, Extrinsic, Transfer, H256 as Hash, Block, Header, Digest, AuthorityId,
+	VERSION,
 };
 use sp_core::{storage::well_known_keys, ChangesTrieConfiguration};
 
@@ -215,7 +216,7 @@ pub fn finalize_block() -> Header {
 
 	// This MUST come after all changes to storage are done. Otherwise we will fail the
 	// “Storage root does not match that calculated” assertion.
-	let storage_root = Hash::decode(&mut &storage_root()[..])
+	let storage_root = Hash::decode(&mut &storage_root(VERSION.state_version())[..])
 		.expect("`storage_root` is a valid hash");
 	let storage_changes_root = storage_changes_root(&parent_hash.encode())
 		.map(|r| Hash::decode(&mut &r[..]).expect("`storage_changes_root` is a valid hash"));
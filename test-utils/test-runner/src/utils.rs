@@ -59,7 +59,7 @@ pub fn default_config(task_executor: TaskExecutor, mut chain_spec: Box<dyn Chain
 		Default::default(),
 		None,
 	);
-	let informant_output_format = OutputFormat { enable_color: false };
+	let informant_output_format = OutputFormat { enable_color: false, json: None };
 	network_config.allow_non_globals_in_dht = true;
 
 	network_config
@@ -102,13 +102,19 @@ pub fn default_config(task_executor: TaskExecutor, mut chain_spec: Box<dyn Chain
 		rpc_cors: None,
 		rpc_methods: Default::default(),
 		rpc_max_payload: None,
+		rpc_max_request_size: None,
+		rpc_max_response_size: None,
+		rpc_limits: Default::default(),
 		prometheus_config: None,
+		health_config: None,
 		telemetry_endpoints: None,
 		telemetry_external_transport: None,
 		default_heap_pages: None,
 		offchain_worker: Default::default(),
 		force_authoring: false,
+		block_proof_size_limit: None,
 		disable_grandpa: false,
+		grandpa_justification_archive_period: None,
 		dev_key_seed: Some(key_seed),
 		tracing_targets: None,
 		tracing_receiver: Default::default(),
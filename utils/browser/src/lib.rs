@@ -87,12 +87,15 @@ where
 		default_heap_pages: Default::default(),
 		dev_key_seed: Default::default(),
 		disable_grandpa: Default::default(),
+		grandpa_justification_archive_period: None,
 		execution_strategies: Default::default(),
 		force_authoring: Default::default(),
+		block_proof_size_limit: None,
 		impl_name: String::from("parity-substrate"),
 		impl_version: String::from("0.0.0"),
 		offchain_worker: Default::default(),
 		prometheus_config: Default::default(),
+		health_config: None,
 		state_pruning: Default::default(),
 		keep_blocks: KeepBlocks::All,
 		transaction_storage: TransactionStorageMode::BlockBody,
@@ -104,6 +107,9 @@ where
 		rpc_http_threads: Default::default(),
 		rpc_methods: Default::default(),
 		rpc_max_payload: Default::default(),
+		rpc_max_request_size: Default::default(),
+		rpc_max_response_size: Default::default(),
+		rpc_limits: Default::default(),
 		state_cache_child_ratio: Default::default(),
 		state_cache_size: Default::default(),
 		tracing_receiver: Default::default(),
@@ -116,6 +122,7 @@ where
 		base_path: None,
 		informant_output_format: sc_informant::OutputFormat {
 			enable_color: false,
+			json: None,
 		},
 		disable_log_reloading: false,
 	};
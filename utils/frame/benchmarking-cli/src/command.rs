@@ -29,7 +29,7 @@ use sp_core::offchain::{
 };
 use sp_externalities::Extensions;
 use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStorePtr};
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+use sp_runtime::{StateVersion, traits::{Block as BlockT, Header as HeaderT, NumberFor}};
 use sp_state_machine::StateMachine;
 use std::{fmt::Debug, sync::Arc};
 
@@ -63,7 +63,14 @@ impl BenchmarkCmd {
 		let genesis_storage = spec.build_storage()?;
 		let mut changes = Default::default();
 		let cache_size = Some(self.database_cache_size as usize);
-		let state = BenchmarkingState::<BB>::new(genesis_storage, cache_size, self.record_proof)?;
+		// Benchmarks measure the storage root with the latest state version, so the genesis state
+		// is built with it as well.
+		let state = BenchmarkingState::<BB>::new(
+			genesis_storage,
+			cache_size,
+			self.record_proof,
+			StateVersion::V1,
+		)?;
 		let executor = NativeExecutor::<ExecDispatch>::new(
 			wasm_method,
 			self.heap_pages,
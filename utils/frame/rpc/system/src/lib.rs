@@ -17,7 +17,7 @@
 
 //! System FRAME specific RPC methods.
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use codec::{self, Codec, Decode, Encode};
 use sc_client_api::light::{future_header, RemoteBlockchain, Fetcher, RemoteCallRequest};
@@ -36,7 +36,11 @@ use sp_runtime::{
 	traits,
 };
 use sp_core::{hexdisplay::HexDisplay, Bytes};
-use sc_transaction_pool_api::{TransactionPool, InPoolTransaction};
+use sc_transaction_pool_api::{
+	TransactionPool, InPoolTransaction, InspectedTransaction, PoolInspection, PoolQueue,
+	TransactionTag,
+};
+use serde::{Deserialize, Serialize};
 use sp_block_builder::BlockBuilder;
 use sc_rpc_api::DenyUnsafe;
 
@@ -57,6 +61,17 @@ pub trait SystemApi<BlockHash, AccountId, Index> {
 	#[rpc(name = "system_accountNextIndex", alias("account_nextIndex"))]
 	fn nonce(&self, account: AccountId) -> FutureResult<Index>;
 
+	/// Returns the nonce of given account in the state of the best block, along with the nonces
+	/// used by its transactions in the pool, ready or future, and the next nonce that doesn't
+	/// conflict with any of them.
+	#[rpc(name = "system_accountNonceInfo")]
+	fn nonce_info(&self, account: AccountId) -> FutureResult<NonceInfo<Index>>;
+
+	/// Returns the content of the transaction pool, grouped by sender, and the hashes of the
+	/// transactions that are banned from it.
+	#[rpc(name = "system_inspectPool")]
+	fn inspect_pool(&self) -> FutureResult<PoolContent<AccountId, Index, BlockHash>>;
+
 	/// Dry run an extrinsic at a given block. Return SCALE encoded ApplyExtrinsicResult.
 	#[rpc(name = "system_dryRun", alias("system_dryRunAt"))]
 	fn dry_run(&self, extrinsic: Bytes, at: Option<BlockHash>) -> FutureResult<Bytes>;
@@ -79,6 +94,65 @@ impl From<Error> for i64 {
 	}
 }
 
+/// Nonces of an account, as returned by `system_accountNonceInfo`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NonceInfo<Index> {
+	/// Nonce of the account in the state of the best block.
+	pub state_nonce: Index,
+	/// Nonces of the transactions of the account in the ready queue of the pool, in order.
+	pub ready: Vec<Index>,
+	/// Nonces of the transactions of the account in the future queue of the pool, in order.
+	pub future: Vec<Index>,
+	/// Lowest nonce, not below the state nonce, that isn't used by any transaction of the pool.
+	///
+	/// If the account has future transactions, this fills the first gap in front of them.
+	pub next_nonce: Index,
+}
+
+/// A transaction of the pool, as returned by `system_inspectPool`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolTransaction<Index, Hash> {
+	/// Hash of the transaction.
+	pub hash: Hash,
+	/// Nonce of the transaction, if it could be inferred from its tags.
+	pub nonce: Option<Index>,
+	/// Queue in which the transaction is waiting.
+	pub queue: PoolQueue,
+	/// Priority of the transaction.
+	pub priority: u64,
+	/// Number of the block after which the transaction is no longer valid.
+	pub valid_till: u64,
+	/// Tags required by the transaction.
+	pub requires: Vec<Bytes>,
+	/// Tags provided by the transaction.
+	pub provides: Vec<Bytes>,
+	/// Number of seconds the transaction has spent in the pool.
+	pub in_pool_secs: u64,
+}
+
+/// Transactions of the pool sent by the same account, as returned by `system_inspectPool`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SenderTransactions<AccountId, Index, Hash> {
+	/// Sender of the transactions, or `None` for the transactions whose sender couldn't be
+	/// inferred, such as unsigned transactions.
+	pub sender: Option<AccountId>,
+	/// Transactions of the sender, ordered by nonce.
+	pub transactions: Vec<PoolTransaction<Index, Hash>>,
+}
+
+/// Content of the pool, as returned by `system_inspectPool`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolContent<AccountId, Index, Hash> {
+	/// Transactions of the pool, grouped by sender.
+	pub senders: Vec<SenderTransactions<AccountId, Index, Hash>>,
+	/// Hashes of the transactions that are temporarily banned from entering the pool.
+	pub banned: Vec<Hash>,
+}
+
 /// An implementation of System-specific RPC methods on full client.
 pub struct FullSystem<P: TransactionPool, C, B> {
 	client: Arc<C>,
@@ -97,6 +171,24 @@ impl<P: TransactionPool, C, B> FullSystem<P, C, B> {
 			_marker: Default::default(),
 		}
 	}
+
+	/// Queries the nonce of given account in the state of the best block.
+	fn state_nonce<AccountId, Index>(&self, account: AccountId) -> Result<Index, RpcError> where
+		C: sp_api::ProvideRuntimeApi<B> + HeaderBackend<B>,
+		C::Api: AccountNonceApi<B, AccountId, Index>,
+		B: traits::Block,
+		AccountId: Codec,
+		Index: Codec,
+	{
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+
+		api.account_nonce(&at, account).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to query nonce.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
 }
 
 impl<P, C, Block, AccountId, Index> SystemApi<<Block as traits::Block>::Hash, AccountId, Index>
@@ -107,29 +199,39 @@ where
 	C: Send + Sync + 'static,
 	C::Api: AccountNonceApi<Block, AccountId, Index>,
 	C::Api: BlockBuilder<Block>,
-	P: TransactionPool + 'static,
+	P: TransactionPool<Hash = <Block as traits::Block>::Hash> + 'static,
 	Block: traits::Block,
-	AccountId: Clone + std::fmt::Display + Codec,
+	AccountId: Clone + std::fmt::Display + Codec + Send + 'static,
 	Index: Clone + std::fmt::Display + Codec + Send + traits::AtLeast32Bit + 'static,
 {
 	fn nonce(&self, account: AccountId) -> FutureResult<Index> {
 		let get_nonce = || {
-			let api = self.client.runtime_api();
-			let best = self.client.info().best_hash;
-			let at = BlockId::hash(best);
-
-			let nonce = api.account_nonce(&at, account.clone()).map_err(|e| RpcError {
-				code: ErrorCode::ServerError(Error::RuntimeError.into()),
-				message: "Unable to query nonce.".into(),
-				data: Some(format!("{:?}", e).into()),
-			})?;
-
+			let nonce = self.state_nonce(account.clone())?;
 			Ok(adjust_nonce(&*self.pool, account, nonce))
 		};
 
 		Box::new(result(get_nonce()))
 	}
 
+	fn nonce_info(&self, account: AccountId) -> FutureResult<NonceInfo<Index>> {
+		let get_nonce_info = || {
+			let nonce = self.state_nonce(account.clone())?;
+			Ok(nonce_info(&self.pool.inspect().transactions, &account, nonce))
+		};
+
+		Box::new(result(get_nonce_info()))
+	}
+
+	fn inspect_pool(
+		&self,
+	) -> FutureResult<PoolContent<AccountId, Index, <Block as traits::Block>::Hash>> {
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return Box::new(rpc_future::err(err.into()));
+		}
+
+		Box::new(result(Ok(pool_content(self.pool.inspect()))))
+	}
+
 	fn dry_run(&self, extrinsic: Bytes, at: Option<<Block as traits::Block>::Hash>) -> FutureResult<Bytes> {
 		if let Err(err) = self.deny_unsafe.check_if_safe() {
 			return Box::new(rpc_future::err(err.into()));
@@ -188,18 +290,17 @@ impl<P: TransactionPool, C, F, Block> LightSystem<P, C, F, Block> {
 	}
 }
 
-impl<P, C, F, Block, AccountId, Index> SystemApi<<Block as traits::Block>::Hash, AccountId, Index>
-	for LightSystem<P, C, F, Block>
-where
-	P: TransactionPool + 'static,
+impl<P, C, F, Block> LightSystem<P, C, F, Block> where
+	P: TransactionPool,
 	C: HeaderBackend<Block>,
-	C: Send + Sync + 'static,
 	F: Fetcher<Block> + 'static,
 	Block: traits::Block,
-	AccountId: Clone + std::fmt::Display + Codec + Send + 'static,
-	Index: Clone + std::fmt::Display + Codec + Send + traits::AtLeast32Bit + 'static,
 {
-	fn nonce(&self, account: AccountId) -> FutureResult<Index> {
+	/// Fetches the nonce of given account in the state of the best block.
+	fn state_nonce<AccountId, Index>(&self, account: AccountId) -> FutureResult<Index> where
+		AccountId: Encode,
+		Index: Decode + Send + 'static,
+	{
 		let best_hash = self.client.info().best_hash;
 		let best_id = BlockId::hash(best_hash);
 		let future_best_header = future_header(&*self.remote_blockchain, &*self.fetcher, best_id);
@@ -226,10 +327,45 @@ where
 			data: Some(format!("{:?}", e).into()),
 		});
 
+		Box::new(future_nonce)
+	}
+}
+
+impl<P, C, F, Block, AccountId, Index> SystemApi<<Block as traits::Block>::Hash, AccountId, Index>
+	for LightSystem<P, C, F, Block>
+where
+	P: TransactionPool<Hash = <Block as traits::Block>::Hash> + 'static,
+	C: HeaderBackend<Block>,
+	C: Send + Sync + 'static,
+	F: Fetcher<Block> + 'static,
+	Block: traits::Block,
+	AccountId: Clone + std::fmt::Display + Codec + Send + 'static,
+	Index: Clone + std::fmt::Display + Codec + Send + traits::AtLeast32Bit + 'static,
+{
+	fn nonce(&self, account: AccountId) -> FutureResult<Index> {
 		let pool = self.pool.clone();
-		let future_nonce = future_nonce.map(move |nonce| adjust_nonce(&*pool, account, nonce));
+		let future_nonce = self.state_nonce(account.clone());
 
-		Box::new(future_nonce)
+		Box::new(future_nonce.map(move |nonce| adjust_nonce(&*pool, account, nonce)))
+	}
+
+	fn nonce_info(&self, account: AccountId) -> FutureResult<NonceInfo<Index>> {
+		let pool = self.pool.clone();
+		let future_nonce = self.state_nonce(account.clone());
+
+		Box::new(future_nonce.map(move |nonce| {
+			nonce_info(&pool.inspect().transactions, &account, nonce)
+		}))
+	}
+
+	fn inspect_pool(
+		&self,
+	) -> FutureResult<PoolContent<AccountId, Index, <Block as traits::Block>::Hash>> {
+		Box::new(result(Err(RpcError {
+			code: ErrorCode::MethodNotFound,
+			message: "Unable to inspect the pool.".into(),
+			data: None,
+		})))
 	}
 
 	fn dry_run(&self, _extrinsic: Bytes, _at: Option<<Block as traits::Block>::Hash>) -> FutureResult<Bytes> {
@@ -280,12 +416,105 @@ fn adjust_nonce<P, AccountId, Index>(
 	current_nonce
 }
 
+/// Infers the sender and the nonce of a transaction from the tags it provides.
+///
+/// FRAME's `CheckNonce` signed extension makes every signed transaction provide the
+/// `(sender, nonce)` pair as a tag, hence the first tag that decodes exactly as such a pair is
+/// used. Transactions that don't provide one, such as unsigned transactions, have no sender.
+fn sender_of<AccountId: Decode, Index: Decode>(
+	provides: &[TransactionTag],
+) -> Option<(AccountId, Index)> {
+	provides.iter().find_map(|tag| {
+		let mut input = &tag[..];
+		let decoded = <(AccountId, Index)>::decode(&mut input).ok()?;
+		if input.is_empty() {
+			Some(decoded)
+		} else {
+			None
+		}
+	})
+}
+
+/// Computes the nonces of an account given the content of the pool and its state nonce.
+fn nonce_info<Hash, AccountId, Index>(
+	transactions: &[InspectedTransaction<Hash>],
+	account: &AccountId,
+	state_nonce: Index,
+) -> NonceInfo<Index> where
+	AccountId: Codec,
+	Index: Decode + traits::AtLeast32Bit + Clone,
+{
+	let account = account.encode();
+	let mut ready = Vec::new();
+	let mut future = Vec::new();
+	for tx in transactions {
+		match sender_of::<AccountId, Index>(&tx.provides) {
+			Some((sender, nonce)) if sender.encode() == account && nonce >= state_nonce => match tx.queue {
+				PoolQueue::Ready => ready.push(nonce),
+				PoolQueue::Future => future.push(nonce),
+			},
+			_ => {},
+		}
+	}
+	ready.sort();
+	future.sort();
+
+	let mut next_nonce = state_nonce.clone();
+	while ready.contains(&next_nonce) || future.contains(&next_nonce) {
+		next_nonce += traits::One::one();
+	}
+
+	NonceInfo { state_nonce, ready, future, next_nonce }
+}
+
+/// Groups the transactions of the pool by sender, transactions without a known sender coming last.
+fn pool_content<Hash, AccountId, Index>(
+	inspection: PoolInspection<Hash>,
+) -> PoolContent<AccountId, Index, Hash> where
+	AccountId: Codec,
+	Index: Decode + Ord + Clone,
+{
+	let mut senders = BTreeMap::<Vec<u8>, (AccountId, Vec<PoolTransaction<Index, Hash>>)>::new();
+	let mut unknown = Vec::new();
+	for tx in inspection.transactions {
+		let sender = sender_of::<AccountId, Index>(&tx.provides);
+		let transaction = PoolTransaction {
+			hash: tx.hash,
+			nonce: sender.as_ref().map(|(_, nonce)| nonce.clone()),
+			queue: tx.queue,
+			priority: tx.priority,
+			valid_till: tx.valid_till,
+			requires: tx.requires.into_iter().map(Into::into).collect(),
+			provides: tx.provides.into_iter().map(Into::into).collect(),
+			in_pool_secs: tx.in_pool_for.as_secs(),
+		};
+		match sender {
+			Some((sender, _)) => senders.entry(sender.encode())
+				.or_insert_with(|| (sender, Vec::new()))
+				.1
+				.push(transaction),
+			None => unknown.push(transaction),
+		}
+	}
+
+	let mut grouped: Vec<_> = senders.into_iter()
+		.map(|(_, (sender, mut transactions))| {
+			transactions.sort_by(|a, b| a.nonce.cmp(&b.nonce));
+			SenderTransactions { sender: Some(sender), transactions }
+		})
+		.collect();
+	if !unknown.is_empty() {
+		grouped.push(SenderTransactions { sender: None, transactions: unknown });
+	}
+	PoolContent { senders: grouped, banned: inspection.banned }
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 
 	use futures::executor::block_on;
-	use substrate_test_runtime_client::{runtime::Transfer, AccountKeyring};
+	use substrate_test_runtime_client::{runtime::{AccountId, Index, Transfer}, AccountKeyring};
 	use sc_transaction_pool::BasicPool;
 	use sp_runtime::{ApplyExtrinsicResult, transaction_validity::{TransactionValidityError, InvalidTransaction}};
 
@@ -329,6 +558,133 @@ mod tests {
 		assert_eq!(nonce.wait().unwrap(), 2);
 	}
 
+	#[test]
+	fn should_return_nonce_info_with_future_transactions() {
+		sp_tracing::try_init_simple();
+
+		// given
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool = BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner,
+			client.clone(),
+		);
+
+		let source = sp_runtime::transaction_validity::TransactionSource::External;
+		for nonce in &[0, 1, 3] {
+			let t = Transfer {
+				from: AccountKeyring::Alice.into(),
+				to: AccountKeyring::Bob.into(),
+				amount: 5,
+				nonce: *nonce,
+			};
+			block_on(pool.submit_one(&BlockId::number(0), source, t.into_signed_tx())).unwrap();
+		}
+
+		let accounts = FullSystem::new(client, pool, DenyUnsafe::Yes);
+
+		// when
+		let alice = accounts.nonce_info(AccountKeyring::Alice.into());
+		let bob = accounts.nonce_info(AccountKeyring::Bob.into());
+
+		// then
+		assert_eq!(alice.wait().unwrap(), NonceInfo {
+			state_nonce: 0,
+			ready: vec![0, 1],
+			future: vec![3],
+			next_nonce: 2,
+		});
+		assert_eq!(bob.wait().unwrap(), NonceInfo {
+			state_nonce: 0,
+			ready: vec![],
+			future: vec![],
+			next_nonce: 0,
+		});
+	}
+
+	#[test]
+	fn should_inspect_pool_grouped_by_sender() {
+		sp_tracing::try_init_simple();
+
+		// given
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool = BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner,
+			client.clone(),
+		);
+
+		let source = sp_runtime::transaction_validity::TransactionSource::External;
+		let transfers = [
+			(AccountKeyring::Alice, 2),
+			(AccountKeyring::Bob, 0),
+			(AccountKeyring::Alice, 0),
+		];
+		for (from, nonce) in &transfers {
+			let t = Transfer {
+				from: (*from).into(),
+				to: AccountKeyring::Charlie.into(),
+				amount: 5,
+				nonce: *nonce,
+			};
+			block_on(pool.submit_one(&BlockId::number(0), source, t.into_signed_tx())).unwrap();
+		}
+
+		let accounts = FullSystem::new(client, pool, DenyUnsafe::No);
+
+		// when
+		let content = SystemApi::<_, AccountId, Index>::inspect_pool(&accounts).wait().unwrap();
+
+		// then
+		assert!(content.banned.is_empty());
+		let grouped = content.senders;
+		assert_eq!(grouped.len(), 2);
+		let alice = grouped.iter()
+			.find(|group| group.sender == Some(AccountKeyring::Alice.into()))
+			.unwrap();
+		let nonces: Vec<_> = alice.transactions.iter().map(|tx| (tx.nonce, tx.queue)).collect();
+		assert_eq!(nonces, vec![(Some(0), PoolQueue::Ready), (Some(2), PoolQueue::Future)]);
+		assert_eq!(alice.transactions[0].valid_till, 64);
+		assert_eq!(
+			alice.transactions[1].requires,
+			vec![Bytes::from((AccountId::from(AccountKeyring::Alice), 1u64).encode())],
+		);
+		let bob = grouped.iter()
+			.find(|group| group.sender == Some(AccountKeyring::Bob.into()))
+			.unwrap();
+		assert_eq!(bob.transactions.len(), 1);
+	}
+
+	#[test]
+	fn inspect_pool_should_deny_unsafe() {
+		sp_tracing::try_init_simple();
+
+		// given
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool = BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner,
+			client.clone(),
+		);
+
+		let accounts = FullSystem::new(client, pool, DenyUnsafe::Yes);
+
+		// when
+		let res = SystemApi::<_, AccountId, Index>::inspect_pool(&accounts);
+
+		// then
+		assert_eq!(res.wait(), Err(RpcError::method_not_found()));
+	}
+
 	#[test]
 	fn dry_run_should_deny_unsafe() {
 		sp_tracing::try_init_simple();
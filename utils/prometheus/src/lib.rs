@@ -39,7 +39,9 @@ pub use sourced::{SourcedCounter, SourcedGauge, MetricSource, SourcedMetric};
 #[cfg(target_os = "unknown")]
 pub use unknown_os::init_prometheus;
 #[cfg(not(target_os = "unknown"))]
-pub use known_os::init_prometheus;
+pub use known_os::{init_prometheus, Executor};
+#[cfg(not(target_os = "unknown"))]
+pub use networking::{Incoming, TcpStream};
 
 pub fn register<T: Clone + Collector + 'static>(metric: T, registry: &Registry) -> Result<T, PrometheusError> {
 	registry.register(Box::new(metric.clone()))?;
@@ -106,6 +108,7 @@ mod known_os {
 
 	}
 
+	/// Executor spawning the tasks of a `hyper` server on `async-std`.
 	#[derive(Clone)]
 	pub struct Executor;
 
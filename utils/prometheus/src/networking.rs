@@ -19,6 +19,7 @@ use async_std::pin::Pin;
 use std::task::{Poll, Context};
 use futures_util::{stream::Stream, io::{AsyncRead, AsyncWrite}};
 
+/// Connections accepted by an `async-std` listener, to be served by a `hyper` server.
 pub struct Incoming<'a>(pub async_std::net::Incoming<'a>);
 
 impl hyper::server::accept::Accept for Incoming<'_> {
@@ -32,6 +33,7 @@ impl hyper::server::accept::Accept for Incoming<'_> {
 	}
 }
 
+/// An `async-std` TCP stream usable by `hyper`.
 pub struct TcpStream(pub async_std::net::TcpStream);
 
 impl tokio::io::AsyncRead for TcpStream {